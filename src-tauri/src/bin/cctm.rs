@@ -0,0 +1,4 @@
+fn main() {
+    let args = std::env::args().skip(1).collect();
+    std::process::exit(claude_code_tool_manager::cli::run(args))
+}
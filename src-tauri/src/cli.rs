@@ -0,0 +1,366 @@
+//! Headless command line entry point (`cctm`)
+//!
+//! A thin dispatcher over the same database and service functions the app
+//! uses, so MCPs can be listed, tested and synced from scripts and CI without
+//! starting the GUI.
+
+use crate::commands::mcp_test::test_mcp_with_db;
+use crate::commands::projects::{get_project_by_path, sync_project_config_from_db};
+use crate::db::Database;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Bundle identifier from tauri.conf.json; the app keeps its data under it
+const APP_IDENTIFIER: &str = "com.claude-code-tool-manager.app";
+
+const USAGE: &str = "Usage: cctm [--format table|json|ndjson] [--db <path>] <command>
+
+Commands:
+  list mcps             List the MCPs in the library
+  test-mcp <name>       Connect to an MCP and list its tools (exit 1 on failure)
+  sync-project <path>   Write a project's MCP config for every enabled editor";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Table,
+    Json,
+    Ndjson,
+}
+
+#[derive(Debug, PartialEq)]
+struct CliArgs {
+    command: Vec<String>,
+    format: OutputFormat,
+    db_path: Option<PathBuf>,
+}
+
+/// Rows printed by a command, with the column order used for tables
+struct Output {
+    columns: &'static [&'static str],
+    rows: Vec<Value>,
+}
+
+/// Run the CLI with `args` (without the program name), returning the exit code
+pub fn run(args: Vec<String>) -> i32 {
+    let args = match parse_args(args) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return 0;
+        }
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return 2;
+        }
+    };
+
+    let db = match open_database(args.db_path.clone()) {
+        Ok(db) => Mutex::new(db),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return 1;
+        }
+    };
+
+    match dispatch(&db, &args.command) {
+        Ok((output, ok)) => {
+            print!("{}", render(&output, args.format));
+            if ok {
+                0
+            } else {
+                1
+            }
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            1
+        }
+    }
+}
+
+/// Parse flags and the command words. `Ok(None)` means help was requested.
+fn parse_args(args: Vec<String>) -> Result<Option<CliArgs>, String> {
+    let mut command = Vec::new();
+    let mut format = OutputFormat::Table;
+    let mut db_path = None;
+
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                (flag.to_string(), Some(value.to_string()))
+            }
+            _ => (arg.clone(), None),
+        };
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--format" => {
+                let value = inline
+                    .or_else(|| iter.next())
+                    .ok_or("--format needs a value")?;
+                format = match value.as_str() {
+                    "table" => OutputFormat::Table,
+                    "json" => OutputFormat::Json,
+                    "ndjson" => OutputFormat::Ndjson,
+                    other => return Err(format!("Unknown format '{}'", other)),
+                };
+            }
+            "--db" => {
+                let value = inline.or_else(|| iter.next()).ok_or("--db needs a path")?;
+                db_path = Some(PathBuf::from(value));
+            }
+            other if other.starts_with('-') => return Err(format!("Unknown option '{}'", other)),
+            _ => command.push(arg),
+        }
+    }
+
+    if command.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(CliArgs {
+        command,
+        format,
+        db_path,
+    }))
+}
+
+/// Open the app's library database (or `path`), refusing to create a new one
+fn open_database(path: Option<PathBuf>) -> Result<Database, String> {
+    let path = match path {
+        Some(path) => path,
+        None => dirs::data_dir()
+            .ok_or("Could not determine the data directory")?
+            .join(APP_IDENTIFIER)
+            .join("mcp_library.db"),
+    };
+    if !path.exists() {
+        return Err(format!(
+            "No database at {}. Start the app once or pass --db <path>",
+            path.display()
+        ));
+    }
+    let db = Database::new(&path).map_err(|e| e.to_string())?;
+    db.run_migrations().map_err(|e| e.to_string())?;
+    Ok(db)
+}
+
+/// Run a command, returning its output and whether it succeeded
+fn dispatch(db: &Mutex<Database>, command: &[String]) -> Result<(Output, bool), String> {
+    let words: Vec<&str> = command.iter().map(String::as_str).collect();
+    match words.as_slice() {
+        ["list", "mcps"] => {
+            let db = db.lock().map_err(|e| e.to_string())?;
+            list_mcps(&db).map(|output| (output, true))
+        }
+        ["test-mcp", name] => test_mcp_by_name(db, name),
+        ["sync-project", path] => {
+            let db = db.lock().map_err(|e| e.to_string())?;
+            sync_project(&db, path).map(|output| (output, true))
+        }
+        _ => Err(format!("Unknown command '{}'", command.join(" "))),
+    }
+}
+
+fn list_mcps(db: &Database) -> Result<Output, String> {
+    let rows = db
+        .get_all_mcps()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|mcp| {
+            let target = match mcp.mcp_type.as_str() {
+                "stdio" => mcp.command.map(|cmd| {
+                    std::iter::once(cmd)
+                        .chain(mcp.args.into_iter().flatten())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                }),
+                _ => mcp.url,
+            };
+            json!({
+                "id": mcp.id,
+                "name": mcp.name,
+                "type": mcp.mcp_type,
+                "source": mcp.source,
+                "target": target,
+            })
+        })
+        .collect();
+    Ok(Output {
+        columns: &["id", "name", "type", "source", "target"],
+        rows,
+    })
+}
+
+fn test_mcp_by_name(db: &Mutex<Database>, name: &str) -> Result<(Output, bool), String> {
+    let mcp_id: i64 = db
+        .lock()
+        .map_err(|e| e.to_string())?
+        .conn()
+        .query_row("SELECT id FROM mcps WHERE name = ?", [name], |row| {
+            row.get(0)
+        })
+        .map_err(|_| format!("No MCP named '{}'", name))?;
+
+    let result = test_mcp_with_db(db, mcp_id)?;
+    let row = json!({
+        "name": name,
+        "success": result.success,
+        "skipped": result.skipped,
+        "tools": result.tools.iter().map(|t| t.name.clone()).collect::<Vec<_>>(),
+        "responseTimeMs": result.response_time_ms,
+        "error": result.error,
+    });
+    Ok((
+        Output {
+            columns: &[
+                "name",
+                "success",
+                "skipped",
+                "tools",
+                "responseTimeMs",
+                "error",
+            ],
+            rows: vec![row],
+        },
+        result.success,
+    ))
+}
+
+fn sync_project(db: &Database, path: &str) -> Result<Output, String> {
+    let canonical = std::fs::canonicalize(path)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string());
+    let project = get_project_by_path(db, path)
+        .or_else(|_| get_project_by_path(db, &canonical))
+        .map_err(|_| format!("No project registered at '{}'", path))?;
+
+    let pending = sync_project_config_from_db(db, project.id)?;
+    Ok(Output {
+        columns: &["id", "name", "path", "synced"],
+        rows: vec![json!({
+            "id": project.id,
+            "name": project.name,
+            "path": project.path,
+            "synced": pending.is_empty(),
+        })],
+    })
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => "-".to_string(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(cell).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+fn render(output: &Output, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Json => {
+            let mut out = serde_json::to_string_pretty(&output.rows).unwrap_or_default();
+            out.push('\n');
+            out
+        }
+        OutputFormat::Ndjson => output.rows.iter().map(|row| format!("{}\n", row)).collect(),
+        OutputFormat::Table => {
+            let cells: Vec<Vec<String>> = output
+                .rows
+                .iter()
+                .map(|row| output.columns.iter().map(|c| cell(&row[*c])).collect())
+                .collect();
+            let widths: Vec<usize> = output
+                .columns
+                .iter()
+                .enumerate()
+                .map(|(i, c)| {
+                    cells
+                        .iter()
+                        .map(|r| r[i].chars().count())
+                        .chain(std::iter::once(c.len()))
+                        .max()
+                        .unwrap_or(0)
+                })
+                .collect();
+
+            let line = |values: Vec<String>| -> String {
+                let padded: Vec<String> = values
+                    .iter()
+                    .zip(&widths)
+                    .map(|(v, w)| format!("{:<width$}", v, width = *w))
+                    .collect();
+                format!("{}\n", padded.join("  ").trim_end())
+            };
+            let mut out = line(output.columns.iter().map(|c| c.to_uppercase()).collect());
+            for row in cells {
+                out.push_str(&line(row));
+            }
+            out
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args_flags_and_command() {
+        let parsed = parse_args(args(&[
+            "--format=ndjson",
+            "test-mcp",
+            "fs",
+            "--db",
+            "/tmp/x.db",
+        ]))
+        .unwrap()
+        .unwrap();
+        assert_eq!(parsed.command, vec!["test-mcp", "fs"]);
+        assert_eq!(parsed.format, OutputFormat::Ndjson);
+        assert_eq!(parsed.db_path, Some(PathBuf::from("/tmp/x.db")));
+
+        assert_eq!(parse_args(args(&["--help"])).unwrap(), None);
+        assert_eq!(parse_args(args(&[])).unwrap(), None);
+        assert!(parse_args(args(&["--format", "xml", "list", "mcps"])).is_err());
+        assert!(parse_args(args(&["--verbose"])).is_err());
+    }
+
+    #[test]
+    fn test_list_mcps_renders_each_format() {
+        let db = Database::in_memory().unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO mcps (name, type, command, args, source) VALUES ('fs', 'stdio', 'npx', '[\"-y\",\"fs-server\"]', 'manual')",
+                [],
+            )
+            .unwrap();
+
+        let (output, ok) = dispatch(&Mutex::new(db), &args(&["list", "mcps"])).unwrap();
+        assert!(ok);
+
+        let table = render(&output, OutputFormat::Table);
+        let mut lines = table.lines();
+        assert!(lines.next().unwrap().starts_with("ID  NAME  TYPE"));
+        assert!(lines.next().unwrap().ends_with("npx -y fs-server"));
+
+        let ndjson = render(&output, OutputFormat::Ndjson);
+        let row: Value = serde_json::from_str(ndjson.lines().next().unwrap()).unwrap();
+        assert_eq!(row["name"], "fs");
+
+        let json: Value = serde_json::from_str(&render(&output, OutputFormat::Json)).unwrap();
+        assert_eq!(json[0]["type"], "stdio");
+    }
+
+    #[test]
+    fn test_dispatch_rejects_unknown_targets() {
+        let db = Mutex::new(Database::in_memory().unwrap());
+        assert!(dispatch(&db, &args(&["list", "widgets"])).is_err());
+        assert!(dispatch(&db, &args(&["test-mcp", "missing"])).is_err());
+        assert!(dispatch(&db, &args(&["sync-project", "/nowhere"])).is_err());
+    }
+}
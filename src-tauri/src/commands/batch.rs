@@ -0,0 +1,343 @@
+//! Batched library operations
+//!
+//! `run_batch` applies a list of operations inside one database transaction
+//! and rolls all of them back if any step fails. Config syncs touch the
+//! filesystem, so they're collected and only run once the transaction commits.
+
+use crate::commands::config::{
+    add_global_mcp_in_db, remove_global_mcp_from_db, sync_global_config_from_db,
+};
+use crate::commands::mcp::create_mcp_impl;
+use crate::commands::projects::{
+    assign_mcp_to_project_in_db, get_project_by_id, remove_mcp_from_project_in_db,
+    sync_project_config_from_db,
+};
+use crate::db::{CreateMcpRequest, Database};
+use crate::services::pending_changes::PendingChange;
+use log::{info, warn};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// An MCP named by id or by name, so a batch can refer to an MCP it creates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum McpRef {
+    Id(i64),
+    Name(String),
+}
+
+/// One step of a batch, mirroring an existing command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum BatchOperation {
+    CreateMcp {
+        mcp: Box<CreateMcpRequest>,
+    },
+    AssignMcpToProject {
+        project_id: i64,
+        mcp: McpRef,
+    },
+    RemoveMcpFromProject {
+        project_id: i64,
+        mcp: McpRef,
+    },
+    ToggleProjectMcp {
+        project_id: i64,
+        mcp: McpRef,
+        enabled: bool,
+    },
+    AddGlobalMcp {
+        mcp: McpRef,
+    },
+    RemoveGlobalMcp {
+        mcp: McpRef,
+    },
+    ToggleGlobalMcp {
+        mcp: McpRef,
+        enabled: bool,
+    },
+    SyncProject {
+        project_id: i64,
+    },
+    SyncGlobal,
+}
+
+impl BatchOperation {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::CreateMcp { .. } => "createMcp",
+            Self::AssignMcpToProject { .. } => "assignMcpToProject",
+            Self::RemoveMcpFromProject { .. } => "removeMcpFromProject",
+            Self::ToggleProjectMcp { .. } => "toggleProjectMcp",
+            Self::AddGlobalMcp { .. } => "addGlobalMcp",
+            Self::RemoveGlobalMcp { .. } => "removeGlobalMcp",
+            Self::ToggleGlobalMcp { .. } => "toggleGlobalMcp",
+            Self::SyncProject { .. } => "syncProject",
+            Self::SyncGlobal => "syncGlobal",
+        }
+    }
+}
+
+/// Outcome of one batch operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchOperationResult {
+    pub index: usize,
+    pub op: String,
+    pub success: bool,
+    pub error: Option<String>,
+    /// The MCP the operation created or acted on, when there is one
+    pub mcp_id: Option<i64>,
+    /// Config writes a sync queued for confirmation
+    pub pending_changes: Vec<PendingChange>,
+}
+
+/// Outcome of a whole batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchResult {
+    /// Whether the database changes were kept. False means every operation
+    /// was rolled back and no config was synced.
+    pub committed: bool,
+    pub results: Vec<BatchOperationResult>,
+}
+
+/// Run `operations` in order as one transaction. Syncs run after commit; a
+/// failed sync is reported but doesn't undo the committed changes.
+#[tauri::command]
+pub fn run_batch(
+    db: State<'_, Arc<Mutex<Database>>>,
+    operations: Vec<BatchOperation>,
+) -> Result<BatchResult, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    run_batch_in_db(&db, &operations)
+}
+
+pub(crate) fn run_batch_in_db(
+    db: &Database,
+    operations: &[BatchOperation],
+) -> Result<BatchResult, String> {
+    info!("[Batch] Running {} operations", operations.len());
+    let mut results: Vec<BatchOperationResult> = operations
+        .iter()
+        .enumerate()
+        .map(|(index, op)| BatchOperationResult {
+            index,
+            op: op.name().to_string(),
+            success: false,
+            error: None,
+            mcp_id: None,
+            pending_changes: Vec::new(),
+        })
+        .collect();
+
+    let tx = db
+        .conn()
+        .unchecked_transaction()
+        .map_err(|e| e.to_string())?;
+    for (index, op) in operations.iter().enumerate() {
+        match apply_operation(db, op) {
+            Ok(mcp_id) => {
+                results[index].success = true;
+                results[index].mcp_id = mcp_id;
+            }
+            Err(e) => {
+                warn!("[Batch] Operation {} ({}) failed: {}", index, op.name(), e);
+                results[index].error = Some(e);
+                for skipped in &mut results[index + 1..] {
+                    skipped.error = Some("Skipped after an earlier failure".to_string());
+                }
+                // Dropping the transaction rolls back every earlier step
+                return Ok(BatchResult {
+                    committed: false,
+                    results,
+                });
+            }
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    for (index, op) in operations.iter().enumerate() {
+        let synced = match op {
+            BatchOperation::SyncProject { project_id } => {
+                sync_project_config_from_db(db, *project_id)
+            }
+            BatchOperation::SyncGlobal => sync_global_config_from_db(db),
+            _ => continue,
+        };
+        match synced {
+            Ok(pending) => results[index].pending_changes = pending,
+            Err(e) => {
+                warn!("[Batch] Sync {} ({}) failed: {}", index, op.name(), e);
+                results[index].success = false;
+                results[index].error = Some(e);
+            }
+        }
+    }
+
+    Ok(BatchResult {
+        committed: true,
+        results,
+    })
+}
+
+fn resolve_mcp(db: &Database, mcp: &McpRef) -> Result<i64, String> {
+    let found = match mcp {
+        McpRef::Id(id) => db.get_mcp_by_id(*id),
+        McpRef::Name(name) => db.get_mcp_by_name(name),
+    }
+    .map_err(|e| e.to_string())?;
+    found.map(|m| m.id).ok_or_else(|| match mcp {
+        McpRef::Id(id) => format!("MCP {} not found", id),
+        McpRef::Name(name) => format!("MCP '{}' not found", name),
+    })
+}
+
+/// Apply the database side of one operation. Syncs only validate here.
+fn apply_operation(db: &Database, op: &BatchOperation) -> Result<Option<i64>, String> {
+    match op {
+        BatchOperation::CreateMcp { mcp } => Ok(Some(create_mcp_impl(db, mcp)?.id)),
+        BatchOperation::AssignMcpToProject { project_id, mcp } => {
+            get_project_by_id(db, *project_id)?;
+            let mcp_id = resolve_mcp(db, mcp)?;
+            assign_mcp_to_project_in_db(db, *project_id, mcp_id)?;
+            Ok(Some(mcp_id))
+        }
+        BatchOperation::RemoveMcpFromProject { project_id, mcp } => {
+            let mcp_id = resolve_mcp(db, mcp)?;
+            remove_mcp_from_project_in_db(db, *project_id, mcp_id)?;
+            Ok(Some(mcp_id))
+        }
+        BatchOperation::ToggleProjectMcp {
+            project_id,
+            mcp,
+            enabled,
+        } => {
+            let mcp_id = resolve_mcp(db, mcp)?;
+            let updated = db
+                .conn()
+                .execute(
+                    "UPDATE project_mcps SET is_enabled = ? WHERE project_id = ? AND mcp_id = ?",
+                    params![*enabled as i32, project_id, mcp_id],
+                )
+                .map_err(|e| e.to_string())?;
+            if updated == 0 {
+                return Err(format!(
+                    "MCP {} is not assigned to project {}",
+                    mcp_id, project_id
+                ));
+            }
+            Ok(Some(mcp_id))
+        }
+        BatchOperation::AddGlobalMcp { mcp } => {
+            let mcp_id = resolve_mcp(db, mcp)?;
+            add_global_mcp_in_db(db, mcp_id)?;
+            Ok(Some(mcp_id))
+        }
+        BatchOperation::RemoveGlobalMcp { mcp } => {
+            let mcp_id = resolve_mcp(db, mcp)?;
+            remove_global_mcp_from_db(db, mcp_id)?;
+            Ok(Some(mcp_id))
+        }
+        BatchOperation::ToggleGlobalMcp { mcp, enabled } => {
+            let mcp_id = resolve_mcp(db, mcp)?;
+            let updated = db
+                .conn()
+                .execute(
+                    "UPDATE global_mcps SET is_enabled = ? WHERE mcp_id = ?",
+                    params![*enabled as i32, mcp_id],
+                )
+                .map_err(|e| e.to_string())?;
+            if updated == 0 {
+                return Err(format!("MCP {} is not a global MCP", mcp_id));
+            }
+            Ok(Some(mcp_id))
+        }
+        BatchOperation::SyncProject { project_id } => {
+            get_project_by_id(db, *project_id)?;
+            Ok(None)
+        }
+        BatchOperation::SyncGlobal => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::projects::{create_project_in_db, get_project_mcps_from_db};
+    use crate::db::CreateProjectRequest;
+
+    fn parse(ops: serde_json::Value) -> Vec<BatchOperation> {
+        serde_json::from_value(ops).unwrap()
+    }
+
+    fn create_project(db: &Database) -> i64 {
+        create_project_in_db(
+            db,
+            &CreateProjectRequest {
+                name: "app".to_string(),
+                path: "/tmp/batch-app".to_string(),
+            },
+        )
+        .unwrap()
+        .id
+    }
+
+    #[test]
+    fn test_run_batch_creates_and_assigns_by_name() {
+        let db = Database::in_memory().unwrap();
+        let project_id = create_project(&db);
+
+        let ops = parse(serde_json::json!([
+            { "op": "createMcp", "mcp": { "name": "github", "type": "stdio", "command": "gh-mcp" } },
+            { "op": "assignMcpToProject", "projectId": project_id, "mcp": "github" },
+            { "op": "toggleProjectMcp", "projectId": project_id, "mcp": "github", "enabled": false },
+            { "op": "addGlobalMcp", "mcp": "github" }
+        ]));
+        let result = run_batch_in_db(&db, &ops).unwrap();
+
+        assert!(result.committed);
+        assert!(result.results.iter().all(|r| r.success));
+        let mcp_id = result.results[0].mcp_id.unwrap();
+        assert_eq!(result.results[1].mcp_id, Some(mcp_id));
+
+        let assigned = get_project_mcps_from_db(&db, project_id).unwrap();
+        assert_eq!(assigned.len(), 1);
+        assert!(!assigned[0].is_enabled);
+    }
+
+    #[test]
+    fn test_run_batch_rolls_back_on_failure() {
+        let db = Database::in_memory().unwrap();
+        let project_id = create_project(&db);
+
+        let ops = parse(serde_json::json!([
+            { "op": "createMcp", "mcp": { "name": "github", "type": "stdio", "command": "gh-mcp" } },
+            { "op": "assignMcpToProject", "projectId": project_id, "mcp": "github" },
+            { "op": "assignMcpToProject", "projectId": project_id, "mcp": "missing" },
+            { "op": "syncProject", "projectId": project_id }
+        ]));
+        let result = run_batch_in_db(&db, &ops).unwrap();
+
+        assert!(!result.committed);
+        assert!(result.results[0].success);
+        assert!(!result.results[2].success);
+        assert!(result.results[2]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("not found"));
+        assert!(result.results[3]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("Skipped"));
+
+        assert!(db.get_mcp_by_name("github").unwrap().is_none());
+        assert!(get_project_mcps_from_db(&db, project_id)
+            .unwrap()
+            .is_empty());
+    }
+}
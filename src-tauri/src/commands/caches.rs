@@ -0,0 +1,237 @@
+//! Runtime caches
+//!
+//! Everything here can be rebuilt by re-testing MCPs or re-syncing repos, so
+//! clearing a cache is a safe way out of stale or corrupt data. Library
+//! entities (MCPs, skills, projects, ...) and settings are never touched.
+
+use crate::db::schema::Database;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// Size of one cache
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheSummary {
+    pub name: String,
+    pub description: String,
+    pub rows: i64,
+    /// Approximate bytes of cached text
+    pub bytes: i64,
+}
+
+struct CacheSpec {
+    name: &'static str,
+    description: &'static str,
+    count_sql: &'static str,
+    bytes_sql: &'static str,
+    /// Run in order inside one transaction
+    clear_sql: &'static [&'static str],
+}
+
+const CACHES: &[CacheSpec] = &[
+    CacheSpec {
+        name: "tool_schemas",
+        description: "Tools and input schemas each MCP last reported",
+        count_sql: "SELECT COUNT(*) FROM mcp_tools",
+        bytes_sql: "SELECT COALESCE(SUM(length(CAST(name AS BLOB)) + COALESCE(length(CAST(description AS BLOB)), 0) + COALESCE(length(CAST(input_schema AS BLOB)), 0)), 0) FROM mcp_tools",
+        clear_sql: &["DELETE FROM mcp_tools"],
+    },
+    CacheSpec {
+        name: "test_history",
+        description: "Past MCP test results used for latency stats",
+        count_sql: "SELECT COUNT(*) FROM mcp_test_history",
+        bytes_sql: "SELECT COALESCE(SUM(COALESCE(length(CAST(error AS BLOB)), 0) + 32), 0) FROM mcp_test_history",
+        clear_sql: &["DELETE FROM mcp_test_history"],
+    },
+    CacheSpec {
+        name: "repo_items",
+        description: "Marketplace items and fetch state from synced repos",
+        count_sql: "SELECT COUNT(*) FROM repo_items",
+        bytes_sql: "SELECT COALESCE(SUM(COALESCE(length(CAST(raw_content AS BLOB)), 0) + COALESCE(length(CAST(metadata AS BLOB)), 0)), 0) FROM repo_items",
+        // Import links live in repo_imports, so the next sync re-marks imported items
+        clear_sql: &[
+            "DELETE FROM repo_items",
+            "UPDATE repos SET etag = NULL, last_fetched_at = NULL",
+        ],
+    },
+    CacheSpec {
+        name: "statusline_gallery",
+        description: "Status line gallery entries",
+        count_sql: "SELECT COUNT(*) FROM app_settings WHERE key = 'statusline_gallery_cache'",
+        bytes_sql: "SELECT COALESCE(SUM(length(CAST(value AS BLOB))), 0) FROM app_settings WHERE key = 'statusline_gallery_cache'",
+        clear_sql: &["DELETE FROM app_settings WHERE key = 'statusline_gallery_cache'"],
+    },
+];
+
+#[tauri::command]
+pub fn get_cache_summary(db: State<'_, Arc<Mutex<Database>>>) -> Result<Vec<CacheSummary>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    get_cache_summary_from_db(&db)
+}
+
+/// Clear one cache by name and return how many rows it held
+#[tauri::command]
+pub fn clear_cache(db: State<'_, Arc<Mutex<Database>>>, cache_name: String) -> Result<i64, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    clear_cache_in_db(&db, &cache_name)
+}
+
+/// Clear every cache and return the total rows removed
+#[tauri::command]
+pub fn clear_all_caches(db: State<'_, Arc<Mutex<Database>>>) -> Result<i64, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    clear_all_caches_in_db(&db)
+}
+
+fn summarize(db: &Database, spec: &CacheSpec) -> Result<CacheSummary, String> {
+    let query = |sql: &str| {
+        db.conn()
+            .query_row(sql, [], |row| row.get::<_, i64>(0))
+            .map_err(|e| e.to_string())
+    };
+    Ok(CacheSummary {
+        name: spec.name.to_string(),
+        description: spec.description.to_string(),
+        rows: query(spec.count_sql)?,
+        bytes: query(spec.bytes_sql)?,
+    })
+}
+
+fn clear(db: &Database, spec: &CacheSpec) -> Result<i64, String> {
+    let rows = summarize(db, spec)?.rows;
+    let tx = db
+        .conn()
+        .unchecked_transaction()
+        .map_err(|e| e.to_string())?;
+    for sql in spec.clear_sql {
+        tx.execute(sql, []).map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    log::info!("[Caches] Cleared {} ({} rows)", spec.name, rows);
+    Ok(rows)
+}
+
+pub(crate) fn get_cache_summary_from_db(db: &Database) -> Result<Vec<CacheSummary>, String> {
+    CACHES.iter().map(|spec| summarize(db, spec)).collect()
+}
+
+pub(crate) fn clear_cache_in_db(db: &Database, cache_name: &str) -> Result<i64, String> {
+    let spec = CACHES
+        .iter()
+        .find(|spec| spec.name == cache_name)
+        .ok_or_else(|| {
+            let names: Vec<&str> = CACHES.iter().map(|spec| spec.name).collect();
+            format!(
+                "Unknown cache '{}'; expected one of {}",
+                cache_name,
+                names.join(", ")
+            )
+        })?;
+    clear(db, spec)
+}
+
+pub(crate) fn clear_all_caches_in_db(db: &Database) -> Result<i64, String> {
+    CACHES.iter().map(|spec| clear(db, spec)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mcp::create_mcp_in_db;
+    use crate::db::models::CreateMcpRequest;
+    use rusqlite::params;
+
+    fn seed(db: &Database) -> i64 {
+        let mcp_id = create_mcp_in_db(
+            db,
+            &CreateMcpRequest {
+                name: "github".to_string(),
+                description: None,
+                mcp_type: "stdio".to_string(),
+                command: Some("npx".to_string()),
+                args: None,
+                url: None,
+                headers: None,
+                env: None,
+                icon: None,
+                tags: None,
+                testable: None,
+                env_file: None,
+                display_name: None,
+                inherit_env: None,
+                shell: None,
+                split_command: None,
+            },
+        )
+        .unwrap()
+        .id;
+        db.conn()
+            .execute(
+                "INSERT INTO mcp_tools (mcp_id, name, description, input_schema) VALUES (?, 'search', 'Search', '{}')",
+                params![mcp_id],
+            )
+            .unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO mcp_test_history (mcp_id, success, response_time_ms) VALUES (?, 1, 120)",
+                params![mcp_id],
+            )
+            .unwrap();
+        db.set_setting("statusline_gallery_cache", "[]").unwrap();
+        mcp_id
+    }
+
+    fn rows(summary: &[CacheSummary], name: &str) -> i64 {
+        summary.iter().find(|c| c.name == name).unwrap().rows
+    }
+
+    #[test]
+    fn test_cache_summary_counts_rows() {
+        let db = Database::in_memory().unwrap();
+        seed(&db);
+
+        let summary = get_cache_summary_from_db(&db).unwrap();
+        assert_eq!(rows(&summary, "tool_schemas"), 1);
+        assert_eq!(rows(&summary, "test_history"), 1);
+        assert_eq!(rows(&summary, "statusline_gallery"), 1);
+        assert!(
+            summary
+                .iter()
+                .find(|c| c.name == "tool_schemas")
+                .unwrap()
+                .bytes
+                > 0
+        );
+    }
+
+    #[test]
+    fn test_clear_cache_by_name() {
+        let db = Database::in_memory().unwrap();
+        seed(&db);
+
+        assert_eq!(clear_cache_in_db(&db, "tool_schemas").unwrap(), 1);
+        let summary = get_cache_summary_from_db(&db).unwrap();
+        assert_eq!(rows(&summary, "tool_schemas"), 0);
+        assert_eq!(rows(&summary, "test_history"), 1);
+
+        let err = clear_cache_in_db(&db, "mcps").unwrap_err();
+        assert!(err.contains("Unknown cache 'mcps'"));
+    }
+
+    #[test]
+    fn test_clear_all_caches_keeps_entities() {
+        let db = Database::in_memory().unwrap();
+        let mcp_id = seed(&db);
+
+        assert_eq!(clear_all_caches_in_db(&db).unwrap(), 3);
+        let summary = get_cache_summary_from_db(&db).unwrap();
+        assert!(summary.iter().all(|c| c.rows == 0));
+
+        assert!(db.get_mcp_by_id(mcp_id).unwrap().is_some());
+        assert_eq!(
+            db.get_setting("default_editor").as_deref(),
+            Some("claude_code")
+        );
+    }
+}
@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use tauri::State;
 
-use crate::services::claude_json::{self, ClaudeJsonMcpServer, DetectedMcp};
+use crate::db::Database;
+use crate::services::claude_json::{self, ClaudeJsonMcpServer, ClaudeJsonPreview, DetectedMcp};
+use crate::services::pending_changes::PendingChange;
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -104,25 +108,115 @@ pub struct AddMcpToClaudeJsonRequest {
     pub env: Option<HashMap<String, String>>,
 }
 
-/// Add an MCP to a project in claude.json
+impl AddMcpToClaudeJsonRequest {
+    fn server(&self) -> ClaudeJsonMcpServer {
+        ClaudeJsonMcpServer {
+            mcp_type: self.mcp_type.clone(),
+            command: self.command.clone(),
+            args: self.args.clone(),
+            url: self.url.clone(),
+            headers: self.headers.clone(),
+            env: self.env.clone(),
+            disabled: false,
+        }
+    }
+}
+
+/// One of the claude.json mutation commands, described for a preview
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum ClaudeJsonMutation {
+    Add(AddMcpToClaudeJsonRequest),
+    #[serde(rename_all = "camelCase")]
+    Remove {
+        project_path: String,
+        mcp_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Toggle {
+        project_path: String,
+        mcp_name: String,
+        enabled: bool,
+    },
+    AddGlobal(AddMcpToClaudeJsonRequest),
+    #[serde(rename_all = "camelCase")]
+    RemoveGlobal {
+        mcp_name: String,
+    },
+}
+
+impl ClaudeJsonMutation {
+    /// Apply the mutation through the same code the real command uses
+    pub(crate) fn apply(&self, json: &mut serde_json::Value) -> anyhow::Result<()> {
+        match self {
+            Self::Add(request) => claude_json::apply_add_mcp_to_project(
+                json,
+                &request.project_path,
+                &request.mcp_name,
+                request.server(),
+            ),
+            Self::Remove {
+                project_path,
+                mcp_name,
+            } => {
+                claude_json::apply_remove_mcp_from_project(json, project_path, mcp_name);
+                Ok(())
+            }
+            Self::Toggle {
+                project_path,
+                mcp_name,
+                enabled,
+            } => {
+                claude_json::apply_toggle_mcp_in_project(json, project_path, mcp_name, *enabled);
+                Ok(())
+            }
+            Self::AddGlobal(request) => {
+                claude_json::apply_add_global_mcp(json, &request.mcp_name, request.server())
+            }
+            Self::RemoveGlobal { mcp_name } => {
+                claude_json::apply_remove_global_mcp(json, mcp_name);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Show what a claude.json mutation would change without writing the file
 #[tauri::command]
-pub fn add_mcp_to_claude_json(request: AddMcpToClaudeJsonRequest) -> Result<(), String> {
-    let server = ClaudeJsonMcpServer {
-        mcp_type: request.mcp_type,
-        command: request.command,
-        args: request.args,
-        url: request.url,
-        headers: request.headers,
-        env: request.env,
-    };
-
-    claude_json::add_mcp_to_project_in_claude_json(&request.project_path, &request.mcp_name, server)
+pub fn preview_claude_json_change(
+    mutation: ClaudeJsonMutation,
+) -> Result<ClaudeJsonPreview, String> {
+    let json = claude_json::read_claude_json().map_err(|e| e.to_string())?;
+    preview_claude_json_mutation(json, &mutation)
+}
+
+pub(crate) fn preview_claude_json_mutation(
+    json: serde_json::Value,
+    mutation: &ClaudeJsonMutation,
+) -> Result<ClaudeJsonPreview, String> {
+    claude_json::preview_claude_json_change(json, |after| mutation.apply(after))
         .map_err(|e| e.to_string())
 }
 
+/// Add an MCP to a project in claude.json
+#[tauri::command]
+pub fn add_mcp_to_claude_json(
+    request: AddMcpToClaudeJsonRequest,
+) -> Result<Option<PendingChange>, String> {
+    claude_json::add_mcp_to_project_in_claude_json(
+        &request.project_path,
+        &request.mcp_name,
+        request.server(),
+    )
+    .map_err(|e| e.to_string())
+}
+
 /// Remove an MCP from a project in claude.json
 #[tauri::command]
-pub fn remove_mcp_from_claude_json(project_path: String, mcp_name: String) -> Result<(), String> {
+pub fn remove_mcp_from_claude_json(
+    project_path: String,
+    mcp_name: String,
+) -> Result<Option<PendingChange>, String> {
     claude_json::remove_mcp_from_project_in_claude_json(&project_path, &mcp_name)
         .map_err(|e| e.to_string())
 }
@@ -133,32 +227,125 @@ pub fn toggle_mcp_in_claude_json(
     project_path: String,
     mcp_name: String,
     enabled: bool,
-) -> Result<(), String> {
+) -> Result<Option<PendingChange>, String> {
     claude_json::toggle_mcp_in_project_claude_json(&project_path, &mcp_name, enabled)
         .map_err(|e| e.to_string())
 }
 
 /// Add a global MCP to claude.json
 #[tauri::command]
-pub fn add_global_mcp_to_claude_json(request: AddMcpToClaudeJsonRequest) -> Result<(), String> {
-    let server = ClaudeJsonMcpServer {
-        mcp_type: request.mcp_type,
-        command: request.command,
-        args: request.args,
-        url: request.url,
-        headers: request.headers,
-        env: request.env,
-    };
-
-    claude_json::add_global_mcp_to_claude_json(&request.mcp_name, server).map_err(|e| e.to_string())
+pub fn add_global_mcp_to_claude_json(
+    request: AddMcpToClaudeJsonRequest,
+) -> Result<Option<PendingChange>, String> {
+    claude_json::add_global_mcp_to_claude_json(&request.mcp_name, request.server())
+        .map_err(|e| e.to_string())
 }
 
 /// Remove a global MCP from claude.json
 #[tauri::command]
-pub fn remove_global_mcp_from_claude_json(mcp_name: String) -> Result<(), String> {
+pub fn remove_global_mcp_from_claude_json(
+    mcp_name: String,
+) -> Result<Option<PendingChange>, String> {
     claude_json::remove_global_mcp_from_claude_json(&mcp_name).map_err(|e| e.to_string())
 }
 
+/// An MCP defined in claude.json with no library row of the same name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnlinkedClaudeJsonMcp {
+    pub name: String,
+    /// None for root `mcpServers` entries
+    pub project_path: Option<String>,
+}
+
+/// A library MCP that belongs in the root `mcpServers` of claude.json but is
+/// not there: it is assigned globally or was imported from the file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingClaudeJsonMcp {
+    pub mcp_id: i64,
+    pub name: String,
+}
+
+/// Divergence between `~/.claude.json` and the MCP library
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeJsonLinkReport {
+    pub not_in_library: Vec<UnlinkedClaudeJsonMcp>,
+    pub not_in_claude_json: Vec<MissingClaudeJsonMcp>,
+}
+
+/// Compare the MCPs in `~/.claude.json` against the library and report the
+/// entries each side has that the other lacks
+#[tauri::command]
+pub fn find_unlinked_claude_json_mcps(
+    db: State<'_, Arc<Mutex<Database>>>,
+) -> Result<ClaudeJsonLinkReport, String> {
+    let detected = claude_json::get_all_mcps_from_claude_json().map_err(|e| e.to_string())?;
+    let db = db.lock().map_err(|e| e.to_string())?;
+    find_unlinked_claude_json_mcps_in_db(&db, &detected)
+}
+
+pub(crate) fn find_unlinked_claude_json_mcps_in_db(
+    db: &Database,
+    detected: &[DetectedMcp],
+) -> Result<ClaudeJsonLinkReport, String> {
+    let mut stmt = db
+        .conn()
+        .prepare("SELECT name FROM mcps")
+        .map_err(|e| e.to_string())?;
+    let library: HashSet<String> = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut not_in_library: Vec<UnlinkedClaudeJsonMcp> = detected
+        .iter()
+        .filter(|m| !library.contains(&m.name))
+        .map(|m| UnlinkedClaudeJsonMcp {
+            name: m.name.clone(),
+            project_path: m.project_path.clone(),
+        })
+        .collect();
+    not_in_library.sort_by(|a, b| {
+        a.project_path
+            .cmp(&b.project_path)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    let in_file: HashSet<&str> = detected
+        .iter()
+        .filter(|m| m.project_path.is_none())
+        .map(|m| m.name.as_str())
+        .collect();
+    let mut stmt = db
+        .conn()
+        .prepare(
+            "SELECT m.id, m.name FROM mcps m
+             WHERE m.id IN (SELECT mcp_id FROM global_mcps WHERE is_enabled = 1)
+                OR m.source_path = '~/.claude.json'
+             ORDER BY m.name",
+        )
+        .map_err(|e| e.to_string())?;
+    let not_in_claude_json = stmt
+        .query_map([], |row| {
+            Ok(MissingClaudeJsonMcp {
+                mcp_id: row.get(0)?,
+                name: row.get(1)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .filter(|m| !in_file.contains(m.name.as_str()))
+        .collect();
+
+    Ok(ClaudeJsonLinkReport {
+        not_in_library,
+        not_in_claude_json,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -524,6 +711,7 @@ mod tests {
                 url: None,
                 headers: None,
                 env: None,
+                disabled: false,
             },
         );
 
@@ -549,6 +737,7 @@ mod tests {
                 url: None,
                 headers: None,
                 env: None,
+                disabled: false,
             },
         );
         servers.insert(
@@ -560,6 +749,7 @@ mod tests {
                 url: Some("https://example.com".to_string()),
                 headers: None,
                 env: None,
+                disabled: false,
             },
         );
 
@@ -603,6 +793,7 @@ mod tests {
                     "Bearer tok".to_string(),
                 )])),
                 env: Some(HashMap::from([("KEY".to_string(), "val".to_string())])),
+                disabled: false,
             },
         );
 
@@ -620,4 +811,134 @@ mod tests {
             Some(&"val".to_string())
         );
     }
+
+    // =========================================================================
+    // find_unlinked_claude_json_mcps tests
+    // =========================================================================
+
+    fn detected(name: &str, project_path: Option<&str>) -> DetectedMcp {
+        DetectedMcp {
+            name: name.to_string(),
+            mcp_type: "stdio".to_string(),
+            command: Some("npx".to_string()),
+            args: None,
+            url: None,
+            headers: None,
+            env: None,
+            project_path: project_path.map(str::to_string),
+            is_enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_find_unlinked_claude_json_mcps_reports_both_directions() {
+        let db = Database::in_memory().unwrap();
+        let conn = db.conn();
+        conn.execute(
+            "INSERT INTO mcps (name, type, command, source, source_path)
+             VALUES ('linked', 'stdio', 'npx', 'auto-detected', '~/.claude.json')",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO mcps (name, type, command, source, source_path)
+             VALUES ('removed-from-file', 'stdio', 'npx', 'auto-detected', '~/.claude.json')",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO mcps (name, type, command, source) VALUES ('global-only', 'stdio', 'npx', 'manual')",
+            [],
+        )
+        .unwrap();
+        let global_id = conn.last_insert_rowid();
+        conn.execute("INSERT INTO global_mcps (mcp_id) VALUES (?)", [global_id])
+            .unwrap();
+        conn.execute(
+            "INSERT INTO mcps (name, type, command, source) VALUES ('library-only', 'stdio', 'npx', 'manual')",
+            [],
+        )
+        .unwrap();
+
+        let report = find_unlinked_claude_json_mcps_in_db(
+            &db,
+            &[
+                detected("linked", None),
+                detected("deleted-in-app", None),
+                detected("project-mcp", Some("/work/app")),
+            ],
+        )
+        .unwrap();
+
+        let missing: Vec<_> = report
+            .not_in_library
+            .iter()
+            .map(|m| (m.name.as_str(), m.project_path.as_deref()))
+            .collect();
+        assert_eq!(
+            missing,
+            vec![("deleted-in-app", None), ("project-mcp", Some("/work/app"))]
+        );
+
+        let absent: Vec<_> = report
+            .not_in_claude_json
+            .iter()
+            .map(|m| m.name.as_str())
+            .collect();
+        assert_eq!(absent, vec!["global-only", "removed-from-file"]);
+    }
+
+    #[test]
+    fn test_preview_claude_json_mutation_matches_write_path() {
+        let json = serde_json::json!({
+            "mcpServers": { "memory": { "type": "stdio", "command": "npx" } },
+            "projects": {
+                "/work/app": {
+                    "mcpServers": { "github": { "type": "stdio", "command": "gh-mcp" } },
+                    "disabledMcpServers": []
+                }
+            }
+        });
+
+        let mutation: ClaudeJsonMutation = serde_json::from_value(serde_json::json!({
+            "action": "add",
+            "projectPath": "/work/app",
+            "mcpName": "remote",
+            "type": "http",
+            "url": "https://mcp.example.com"
+        }))
+        .unwrap();
+        let preview = preview_claude_json_mutation(json.clone(), &mutation).unwrap();
+        assert!(preview.changed);
+        assert_eq!(preview.before, json);
+        assert_eq!(
+            preview.after["projects"]["/work/app"]["mcpServers"]["remote"]["url"],
+            "https://mcp.example.com"
+        );
+
+        let mutation: ClaudeJsonMutation = serde_json::from_value(serde_json::json!({
+            "action": "toggle",
+            "projectPath": "/work/app",
+            "mcpName": "github",
+            "enabled": false
+        }))
+        .unwrap();
+        let preview = preview_claude_json_mutation(json.clone(), &mutation).unwrap();
+        assert_eq!(
+            preview.after["projects"]["/work/app"]["disabledMcpServers"],
+            serde_json::json!(["github"])
+        );
+
+        let mutation = ClaudeJsonMutation::RemoveGlobal {
+            mcp_name: "missing".to_string(),
+        };
+        let preview = preview_claude_json_mutation(json.clone(), &mutation).unwrap();
+        assert!(!preview.changed);
+
+        let mutation = ClaudeJsonMutation::RemoveGlobal {
+            mcp_name: "memory".to_string(),
+        };
+        let preview = preview_claude_json_mutation(json, &mutation).unwrap();
+        assert!(preview.after["mcpServers"].get("memory").is_none());
+    }
 }
@@ -1,4 +1,5 @@
 use crate::services::claude_settings::{self, AllClaudeSettings, ClaudeSettings};
+use crate::services::pending_changes::PendingChange;
 use crate::services::permission_writer::PermissionScope;
 use log::info;
 use std::path::Path;
@@ -46,7 +47,7 @@ pub fn save_claude_settings(
     scope: String,
     project_path: Option<String>,
     settings: ClaudeSettings,
-) -> Result<ClaudeSettings, String> {
+) -> Result<Option<PendingChange>, String> {
     info!(
         "[ClaudeSettings] Saving settings for scope={} (project={:?})",
         scope, project_path
@@ -54,12 +55,7 @@ pub fn save_claude_settings(
     let ps = parse_scope(&scope)?;
     let pp = project_path.as_deref().map(Path::new);
 
-    claude_settings::write_claude_settings(&ps, pp, &settings).map_err(|e| e.to_string())?;
-
-    // Re-read to return updated state
-    let path = crate::services::permission_writer::resolve_settings_path(&ps, pp)
-        .map_err(|e| e.to_string())?;
-    claude_settings::read_claude_settings_from_file(&path, &scope).map_err(|e| e.to_string())
+    claude_settings::write_claude_settings(&ps, pp, &settings).map_err(|e| e.to_string())
 }
 
 #[cfg(test)]
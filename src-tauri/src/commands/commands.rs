@@ -5,6 +5,8 @@ use crate::services::command_writer;
 use log::warn;
 use regex::Regex;
 use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 use tauri::State;
@@ -60,7 +62,7 @@ impl ValidationResult {
 }
 
 /// Validate a command name according to official Claude Code documentation
-fn validate_command_name(name: &str) -> ValidationResult {
+pub(crate) fn validate_command_name(name: &str) -> ValidationResult {
     let name = name.trim();
 
     // Check if empty
@@ -203,6 +205,18 @@ fn row_to_command_with_offset(
     })
 }
 
+/// Get a command by ID from the database
+pub(crate) fn get_command_by_id(db: &Database, id: i64) -> Result<Command, String> {
+    let query = format!(
+        "SELECT {} FROM commands WHERE id = ?",
+        COMMAND_SELECT_FIELDS
+    );
+    let mut stmt = db.conn().prepare(&query).map_err(|e| e.to_string())?;
+
+    stmt.query_row([id], row_to_command)
+        .map_err(|e| e.to_string())
+}
+
 // ============================================================================
 // Tauri Commands - CRUD Operations
 // ============================================================================
@@ -210,8 +224,13 @@ fn row_to_command_with_offset(
 #[tauri::command]
 pub fn get_all_commands(db: State<'_, Arc<Mutex<Database>>>) -> Result<Vec<Command>, String> {
     let db = db.lock().map_err(|e| e.to_string())?;
+    get_all_commands_from_db(&db)
+}
+
+/// Get all commands from the database
+pub(crate) fn get_all_commands_from_db(db: &Database) -> Result<Vec<Command>, String> {
     let query = format!(
-        "SELECT {} FROM commands ORDER BY name",
+        "SELECT {} FROM commands WHERE removed_at IS NULL ORDER BY name",
         COMMAND_SELECT_FIELDS
     );
     let mut stmt = db.conn().prepare(&query).map_err(|e| e.to_string())?;
@@ -230,10 +249,17 @@ pub fn create_command(
     db: State<'_, Arc<Mutex<Database>>>,
     command: CreateCommandRequest,
 ) -> Result<Command, String> {
-    // Validate the command request
-    let _warning = validate_command_request(&command)?;
-
     let db_guard = db.lock().map_err(|e| e.to_string())?;
+    create_command_in_db(&db_guard, &command)
+}
+
+/// Create a command in the database with validation (no file sync)
+pub(crate) fn create_command_in_db(
+    db: &Database,
+    command: &CreateCommandRequest,
+) -> Result<Command, String> {
+    // Validate the command request
+    let _warning = validate_command_request(command)?;
 
     let allowed_tools_json = command
         .allowed_tools
@@ -244,7 +270,7 @@ pub fn create_command(
         .as_ref()
         .map(|t| serde_json::to_string(t).unwrap());
 
-    db_guard.conn()
+    db.conn()
         .execute(
             "INSERT INTO commands (name, description, content, allowed_tools, argument_hint, model, tags, source)
              VALUES (?, ?, ?, ?, ?, ?, ?, 'manual')",
@@ -252,16 +278,8 @@ pub fn create_command(
         )
         .map_err(|e| e.to_string())?;
 
-    let id = db_guard.conn().last_insert_rowid();
-
-    let query = format!(
-        "SELECT {} FROM commands WHERE id = ?",
-        COMMAND_SELECT_FIELDS
-    );
-    let mut stmt = db_guard.conn().prepare(&query).map_err(|e| e.to_string())?;
-
-    stmt.query_row([id], row_to_command)
-        .map_err(|e| e.to_string())
+    let id = db.conn().last_insert_rowid();
+    get_command_by_id(db, id)
 }
 
 #[tauri::command]
@@ -387,6 +405,8 @@ pub fn add_global_command(
             }
             "opencode" => command_writer::write_global_command_opencode(&command)
                 .map_err(|e| e.to_string())?,
+            "windsurf" => command_writer::write_global_command_windsurf(&command)
+                .map_err(|e| e.to_string())?,
             unknown => warn!(
                 "[Commands] Unknown editor type '{}' for command '{}'. Skipping.",
                 unknown, command.name
@@ -432,6 +452,8 @@ pub fn remove_global_command(
             }
             "opencode" => command_writer::delete_global_command_opencode(&command)
                 .map_err(|e| e.to_string())?,
+            "windsurf" => command_writer::delete_global_command_windsurf(&command)
+                .map_err(|e| e.to_string())?,
             unknown => warn!(
                 "[Commands] Unknown editor type '{}' for command '{}'. Skipping.",
                 unknown, command.name
@@ -482,6 +504,8 @@ pub fn toggle_global_command(
                 }
                 "opencode" => command_writer::write_global_command_opencode(&command)
                     .map_err(|e| e.to_string())?,
+                "windsurf" => command_writer::write_global_command_windsurf(&command)
+                    .map_err(|e| e.to_string())?,
                 unknown => warn!(
                     "[Commands] Unknown editor type '{}' for command '{}'. Skipping.",
                     unknown, command.name
@@ -494,6 +518,8 @@ pub fn toggle_global_command(
                 }
                 "opencode" => command_writer::delete_global_command_opencode(&command)
                     .map_err(|e| e.to_string())?,
+                "windsurf" => command_writer::delete_global_command_windsurf(&command)
+                    .map_err(|e| e.to_string())?,
                 unknown => warn!(
                     "[Commands] Unknown editor type '{}' for command '{}'. Skipping.",
                     unknown, command.name
@@ -557,6 +583,10 @@ pub fn assign_command_to_project(
                 command_writer::write_project_command_opencode(Path::new(&project_path), &command)
                     .map_err(|e| e.to_string())?
             }
+            "windsurf" => {
+                command_writer::write_project_command_windsurf(Path::new(&project_path), &command)
+                    .map_err(|e| e.to_string())?
+            }
             unknown => warn!(
                 "[Commands] Unknown editor type '{}' for command '{}'. Skipping.",
                 unknown, command.name
@@ -615,6 +645,10 @@ pub fn remove_command_from_project(
                 command_writer::delete_project_command_opencode(Path::new(&project_path), &command)
                     .map_err(|e| e.to_string())?
             }
+            "windsurf" => {
+                command_writer::delete_project_command_windsurf(Path::new(&project_path), &command)
+                    .map_err(|e| e.to_string())?
+            }
             unknown => warn!(
                 "[Commands] Unknown editor type '{}' for command '{}'. Skipping.",
                 unknown, command.name
@@ -669,6 +703,11 @@ pub fn toggle_project_command(
                     &command,
                 )
                 .map_err(|e| e.to_string())?,
+                "windsurf" => command_writer::write_project_command_windsurf(
+                    Path::new(&project_path),
+                    &command,
+                )
+                .map_err(|e| e.to_string())?,
                 unknown => warn!(
                     "[Commands] Unknown editor type '{}' for command '{}'. Skipping.",
                     unknown, command.name
@@ -685,6 +724,11 @@ pub fn toggle_project_command(
                     &command,
                 )
                 .map_err(|e| e.to_string())?,
+                "windsurf" => command_writer::delete_project_command_windsurf(
+                    Path::new(&project_path),
+                    &command,
+                )
+                .map_err(|e| e.to_string())?,
                 unknown => warn!(
                     "[Commands] Unknown editor type '{}' for command '{}'. Skipping.",
                     unknown, command.name
@@ -726,6 +770,185 @@ pub fn get_project_commands(
     Ok(commands)
 }
 
+// ============================================================================
+// Slash-command namespace
+// ============================================================================
+
+/// Commands built into Claude Code; a custom command with one of these names
+/// is never reachable
+const BUILTIN_SLASH_COMMANDS: &[&str] = &[
+    "add-dir",
+    "agents",
+    "bug",
+    "clear",
+    "compact",
+    "config",
+    "context",
+    "cost",
+    "doctor",
+    "exit",
+    "export",
+    "help",
+    "hooks",
+    "init",
+    "login",
+    "logout",
+    "mcp",
+    "memory",
+    "model",
+    "permissions",
+    "pr-comments",
+    "review",
+    "resume",
+    "rewind",
+    "status",
+    "statusline",
+    "terminal-setup",
+    "upgrade",
+    "vim",
+];
+
+/// Something answering to `/name` in a project
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlashCommandEntry {
+    pub name: String,
+    /// "builtin", "skill" or "command"
+    pub kind: String,
+    /// "builtin", "global" or "project"
+    pub scope: String,
+    /// Library id of the command or skill; None for built-ins
+    pub entity_id: Option<i64>,
+}
+
+/// A name claimed by more than one entry; `entries[0]` is the one Claude runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlashCommandConflict {
+    pub name: String,
+    pub entries: Vec<SlashCommandEntry>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlashCommandNamespace {
+    /// Every enabled command and skill for the project, by name
+    pub entries: Vec<SlashCommandEntry>,
+    pub conflicts: Vec<SlashCommandConflict>,
+}
+
+/// Lower wins. Built-ins beat everything and skills beat commands of the same
+/// name; between scopes, user skills beat project skills while project
+/// commands beat user commands.
+fn slash_command_precedence(entry: &SlashCommandEntry) -> u8 {
+    match (entry.kind.as_str(), entry.scope.as_str()) {
+        ("builtin", _) => 0,
+        ("skill", "global") => 1,
+        ("skill", _) => 2,
+        ("command", "project") => 3,
+        _ => 4,
+    }
+}
+
+/// List the slash-command names active in a project (global and project
+/// commands and skills) and flag names claimed more than once
+#[tauri::command(rename_all = "camelCase")]
+pub fn detect_command_conflicts(
+    db: State<'_, Arc<Mutex<Database>>>,
+    project_id: i64,
+) -> Result<SlashCommandNamespace, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    detect_command_conflicts_in_db(&db, project_id)
+}
+
+pub(crate) fn detect_command_conflicts_in_db(
+    db: &Database,
+    project_id: i64,
+) -> Result<SlashCommandNamespace, String> {
+    let queries = [
+        (
+            "command",
+            "global",
+            "SELECT c.id, c.name FROM global_commands g JOIN commands c ON c.id = g.command_id
+             WHERE g.is_enabled = 1",
+        ),
+        (
+            "command",
+            "project",
+            "SELECT c.id, c.name FROM project_commands p JOIN commands c ON c.id = p.command_id
+             WHERE p.is_enabled = 1 AND p.project_id = ?",
+        ),
+        (
+            "skill",
+            "global",
+            "SELECT s.id, s.name FROM global_skills g JOIN skills s ON s.id = g.skill_id
+             WHERE g.is_enabled = 1",
+        ),
+        (
+            "skill",
+            "project",
+            "SELECT s.id, s.name FROM project_skills p JOIN skills s ON s.id = p.skill_id
+             WHERE p.is_enabled = 1 AND p.project_id = ?",
+        ),
+    ];
+
+    let mut entries = Vec::new();
+    for (kind, scope, sql) in queries {
+        let mut stmt = db.conn().prepare(sql).map_err(|e| e.to_string())?;
+        let id_and_name = |row: &rusqlite::Row| Ok((row.get::<_, i64>(0)?, row.get(1)?));
+        let rows = if scope == "project" {
+            stmt.query_map([project_id], id_and_name)
+        } else {
+            stmt.query_map([], id_and_name)
+        }
+        .map_err(|e| e.to_string())?
+        .collect::<rusqlite::Result<Vec<(i64, String)>>>()
+        .map_err(|e| e.to_string())?;
+        entries.extend(rows.into_iter().map(|(id, name)| SlashCommandEntry {
+            name,
+            kind: kind.to_string(),
+            scope: scope.to_string(),
+            entity_id: Some(id),
+        }));
+    }
+
+    let mut by_name: BTreeMap<String, Vec<SlashCommandEntry>> = BTreeMap::new();
+    for entry in &entries {
+        by_name
+            .entry(entry.name.to_lowercase())
+            .or_default()
+            .push(entry.clone());
+    }
+    let conflicts = by_name
+        .into_iter()
+        .filter_map(|(name, mut claimants)| {
+            if BUILTIN_SLASH_COMMANDS.contains(&name.as_str()) {
+                claimants.push(SlashCommandEntry {
+                    name: name.clone(),
+                    kind: "builtin".to_string(),
+                    scope: "builtin".to_string(),
+                    entity_id: None,
+                });
+            }
+            if claimants.len() < 2 {
+                return None;
+            }
+            claimants.sort_by_key(slash_command_precedence);
+            Some(SlashCommandConflict {
+                name,
+                entries: claimants,
+            })
+        })
+        .collect();
+
+    entries.sort_by(|a, b| {
+        a.name
+            .cmp(&b.name)
+            .then_with(|| slash_command_precedence(a).cmp(&slash_command_precedence(b)))
+    });
+    Ok(SlashCommandNamespace { entries, conflicts })
+}
+
 // ============================================================================
 // Favorites
 // ============================================================================
@@ -961,6 +1184,90 @@ mod tests {
         assert!(result.error.unwrap().contains("anthropic"));
     }
 
+    #[test]
+    fn test_detect_command_conflicts() {
+        let db = Database::in_memory().unwrap();
+        let conn = db.conn();
+        conn.execute(
+            "INSERT INTO projects (name, path) VALUES ('p', '/tmp/p')",
+            [],
+        )
+        .unwrap();
+        let project = conn.last_insert_rowid();
+        let insert = |sql: &str, name: &str| {
+            conn.execute(sql, [name]).unwrap();
+            conn.last_insert_rowid()
+        };
+        let deploy = insert(
+            "INSERT INTO commands (name, content) VALUES (?, 'x')",
+            "deploy",
+        );
+        let review = insert(
+            "INSERT INTO commands (name, content) VALUES (?, 'x')",
+            "review",
+        );
+        let lint = insert(
+            "INSERT INTO commands (name, content) VALUES (?, 'x')",
+            "lint",
+        );
+        let skill = insert(
+            "INSERT INTO skills (name, content) VALUES (?, 'x')",
+            "deploy",
+        );
+        for (sql, id) in [
+            (
+                "INSERT INTO global_commands (command_id) VALUES (?)",
+                deploy,
+            ),
+            (
+                "INSERT INTO global_commands (command_id) VALUES (?)",
+                review,
+            ),
+            ("INSERT INTO global_commands (command_id) VALUES (?)", lint),
+            ("INSERT INTO global_skills (skill_id) VALUES (?)", skill),
+        ] {
+            conn.execute(sql, [id]).unwrap();
+        }
+        conn.execute(
+            "INSERT INTO project_commands (project_id, command_id) VALUES (?, ?)",
+            params![project, deploy],
+        )
+        .unwrap();
+
+        let namespace = detect_command_conflicts_in_db(&db, project).unwrap();
+        assert_eq!(namespace.entries.len(), 5);
+
+        let names: Vec<&str> = namespace
+            .conflicts
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["deploy", "review"]);
+
+        let deploy_conflict = &namespace.conflicts[0];
+        let order: Vec<(&str, &str)> = deploy_conflict
+            .entries
+            .iter()
+            .map(|e| (e.kind.as_str(), e.scope.as_str()))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("skill", "global"),
+                ("command", "project"),
+                ("command", "global")
+            ]
+        );
+
+        // A custom /review is hidden by the built-in one
+        assert_eq!(namespace.conflicts[1].entries[0].kind, "builtin");
+
+        // Another project only sees the global entries
+        let other = detect_command_conflicts_in_db(&db, project + 1).unwrap();
+        assert_eq!(other.entries.len(), 4);
+        assert_eq!(other.conflicts.len(), 2);
+    }
+
     // =========================================================================
     // Command serde tests
     // =========================================================================
@@ -1,9 +1,22 @@
+use crate::commands::settings::get_available_editors_from_db;
+use crate::db::schema::SCHEMA_MIGRATIONS;
 use crate::db::{ClaudePaths, Database, GlobalMcp, Mcp};
-use crate::services::config_writer;
+use crate::services::config_validation::{self, ConfigWarning};
+use crate::services::mcp_json_config::McpTuple;
+use crate::services::pending_changes::{line_diff, read_config_file, PendingChange};
+use crate::services::scanner::{self, StaleGlobalMcp};
+use crate::services::{claude_json, config_writer, debug_logger, diagnostics, setup_script};
+use crate::utils::claude_version::{self, ClaudeCodeVersion};
 use crate::utils::paths;
 use rusqlite::params;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use tauri::State;
+use tauri::{Manager, State};
+
+/// Number of log lines included in a diagnostics bundle
+const DIAGNOSTICS_LOG_LINES: usize = 500;
 
 fn parse_json_array(s: Option<String>) -> Option<Vec<String>> {
     s.and_then(|v| serde_json::from_str(&v).ok())
@@ -32,6 +45,12 @@ fn row_to_mcp(row: &rusqlite::Row, offset: usize) -> rusqlite::Result<Mcp> {
         is_favorite: row.get::<_, i32>(offset + 14)? != 0,
         created_at: row.get(offset + 15)?,
         updated_at: row.get(offset + 16)?,
+        testable: row.get::<_, i32>(offset + 17)? != 0,
+        env_file: row.get(offset + 18)?,
+        display_name: row.get(offset + 19)?,
+        homepage: row.get(offset + 20)?,
+        inherit_env: row.get::<_, i32>(offset + 21)? != 0,
+        shell: row.get(offset + 22)?,
     })
 }
 
@@ -41,6 +60,26 @@ pub fn get_global_mcps(db: State<'_, Arc<Mutex<Database>>>) -> Result<Vec<Global
     get_global_mcps_from_db(&db)
 }
 
+/// Enabled global MCPs that ~/.claude.json no longer lists, e.g. after a
+/// `claude mcp remove`. Report only; the rows are left for the user to clean up.
+#[tauri::command]
+pub fn find_stale_global_mcps(
+    db: State<'_, Arc<Mutex<Database>>>,
+) -> Result<Vec<StaleGlobalMcp>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    find_stale_global_mcps_from_db(&db)
+}
+
+pub(crate) fn find_stale_global_mcps_from_db(db: &Database) -> Result<Vec<StaleGlobalMcp>, String> {
+    let present: HashSet<String> = claude_json::get_all_mcps_from_claude_json()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|m| m.project_path.is_none())
+        .map(|m| m.name)
+        .collect();
+    scanner::find_stale_global_mcps(db, &present).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn add_global_mcp(db: State<'_, Arc<Mutex<Database>>>, mcp_id: i64) -> Result<(), String> {
     let db = db.lock().map_err(|e| e.to_string())?;
@@ -64,26 +103,109 @@ pub fn toggle_global_mcp_assignment(
 }
 
 #[tauri::command]
-pub fn sync_global_config(db: State<'_, Arc<Mutex<Database>>>) -> Result<(), String> {
+pub fn sync_global_config(
+    db: State<'_, Arc<Mutex<Database>>>,
+) -> Result<Vec<PendingChange>, String> {
     let db = db.lock().map_err(|e| e.to_string())?;
     sync_global_config_from_db(&db)
 }
 
 /// Sync global config from database to disk (reusable helper without Tauri State)
-#[allow(clippy::type_complexity)]
-pub(crate) fn sync_global_config_from_db(db: &Database) -> Result<(), String> {
+pub(crate) fn sync_global_config_from_db(db: &Database) -> Result<Vec<PendingChange>, String> {
     use crate::commands::settings::get_enabled_editors_from_db;
+
+    let mcps = load_global_mcps_from_db(db)?;
+
+    // Write to all enabled editors
+    let enabled_editors = get_enabled_editors_from_db(db);
+    let mut pending = Vec::new();
+    for editor in &enabled_editors {
+        pending.extend(write_global_mcp_config(editor, &mcps)?);
+    }
+
+    Ok(pending)
+}
+
+/// Write `mcps` to one editor's global MCP config. Returns the pending change
+/// when the Claude Code write was queued for confirmation.
+pub(crate) fn write_global_mcp_config(
+    editor: &str,
+    mcps: &[McpTuple],
+) -> Result<Option<PendingChange>, String> {
     use crate::services::{
         codex_config, copilot_config, cursor_config, gemini_config, opencode_config,
+        windsurf_config,
+    };
+    use crate::utils::{
+        codex_paths, copilot_paths, cursor_paths, gemini_paths, opencode_paths, windsurf_paths,
     };
-    use crate::utils::{codex_paths, copilot_paths, cursor_paths, gemini_paths, opencode_paths};
     use log::{info, warn};
 
-    // Get enabled global MCPs
+    match editor {
+        "claude_code" => {
+            let claude_paths = paths::get_claude_paths().map_err(|e| e.to_string())?;
+            let pending = config_writer::write_global_config(&claude_paths, mcps)
+                .map_err(|e| e.to_string())?;
+            info!("[Config] Wrote global config to Claude Code");
+            return Ok(pending);
+        }
+        "opencode" => {
+            if let Ok(paths) = opencode_paths::get_opencode_paths() {
+                opencode_config::write_opencode_global_config(&paths.config_file, mcps)
+                    .map_err(|e| e.to_string())?;
+                info!("[Config] Wrote global config to OpenCode");
+            }
+        }
+        "codex" => {
+            if let Ok(paths) = codex_paths::get_codex_paths() {
+                codex_config::write_codex_config(&paths.config_file, mcps)
+                    .map_err(|e| e.to_string())?;
+                info!("[Config] Wrote global config to Codex CLI");
+            }
+        }
+        "copilot" => {
+            if let Ok(paths) = copilot_paths::get_copilot_paths() {
+                copilot_config::write_copilot_config(&paths.mcp_config_file, mcps)
+                    .map_err(|e| e.to_string())?;
+                info!("[Config] Wrote global config to Copilot CLI");
+            }
+        }
+        "cursor" => {
+            if let Ok(paths) = cursor_paths::get_cursor_paths() {
+                cursor_config::write_cursor_config(&paths.mcp_config_file, mcps)
+                    .map_err(|e| e.to_string())?;
+                info!("[Config] Wrote global config to Cursor");
+            }
+        }
+        "gemini" => {
+            if let Ok(paths) = gemini_paths::get_gemini_paths() {
+                gemini_config::write_gemini_config(&paths.settings_file, mcps)
+                    .map_err(|e| e.to_string())?;
+                info!("[Config] Wrote global config to Gemini CLI");
+            }
+        }
+        "windsurf" => {
+            if let Ok(paths) = windsurf_paths::get_windsurf_paths() {
+                windsurf_config::write_windsurf_config(&paths.mcp_config_file, mcps)
+                    .map_err(|e| e.to_string())?;
+                info!("[Config] Wrote global config to Windsurf");
+            }
+        }
+        unknown => warn!("[Config] Unknown editor type '{}'. Skipping.", unknown),
+    }
+
+    Ok(None)
+}
+
+/// Enabled global MCPs in display order, as the config writers take them
+pub(crate) fn load_global_mcps_from_db(db: &Database) -> Result<Vec<McpTuple>, String> {
+    use crate::commands::settings::get_inline_env_files_from_db;
+    use crate::services::mcp_client::resolve_env_file;
+
     let mut stmt = db
         .conn()
         .prepare(
-            "SELECT m.name, m.type, m.command, m.args, m.url, m.headers, m.env
+            "SELECT m.name, m.type, m.command, m.args, m.url, m.headers, m.env, m.env_file, m.source_path
              FROM global_mcps gm
              JOIN mcps m ON gm.mcp_id = m.id
              WHERE gm.is_enabled = 1
@@ -91,16 +213,13 @@ pub(crate) fn sync_global_config_from_db(db: &Database) -> Result<(), String> {
         )
         .map_err(|e| e.to_string())?;
 
-    let mcps: Vec<(
-        String,
-        String,
-        Option<String>,
-        Option<String>,
-        Option<String>,
-        Option<String>,
-        Option<String>,
-    )> = stmt
+    let inline_env_files = get_inline_env_files_from_db(db);
+    let mcps = stmt
         .query_map([], |row| {
+            let env: Option<String> = row.get(6)?;
+            let env_file: Option<String> = row.get(7)?;
+            let source_path: Option<String> = row.get(8)?;
+            let env_file = env_file.map(|f| resolve_env_file(&f, source_path.as_deref(), None));
             Ok((
                 row.get(0)?,
                 row.get(1)?,
@@ -108,63 +227,133 @@ pub(crate) fn sync_global_config_from_db(db: &Database) -> Result<(), String> {
                 row.get(3)?,
                 row.get(4)?,
                 row.get(5)?,
-                row.get(6)?,
+                config_writer::merge_env_file(env, env_file.as_deref(), inline_env_files),
             ))
         })
         .map_err(|e| e.to_string())?
         .filter_map(|r| r.ok())
         .collect();
 
-    // Write to all enabled editors
-    let enabled_editors = get_enabled_editors_from_db(db);
-    for editor in &enabled_editors {
-        match editor.as_str() {
-            "claude_code" => {
-                let claude_paths = paths::get_claude_paths().map_err(|e| e.to_string())?;
-                config_writer::write_global_config(&claude_paths, &mcps)
-                    .map_err(|e| e.to_string())?;
-                info!("[Config] Wrote global config to Claude Code");
-            }
-            "opencode" => {
-                if let Ok(paths) = opencode_paths::get_opencode_paths() {
-                    opencode_config::write_opencode_global_config(&paths.config_file, &mcps)
-                        .map_err(|e| e.to_string())?;
-                    info!("[Config] Wrote global config to OpenCode");
-                }
-            }
-            "codex" => {
-                if let Ok(paths) = codex_paths::get_codex_paths() {
-                    codex_config::write_codex_config(&paths.config_file, &mcps)
-                        .map_err(|e| e.to_string())?;
-                    info!("[Config] Wrote global config to Codex CLI");
-                }
-            }
-            "copilot" => {
-                if let Ok(paths) = copilot_paths::get_copilot_paths() {
-                    copilot_config::write_copilot_config(&paths.mcp_config_file, &mcps)
-                        .map_err(|e| e.to_string())?;
-                    info!("[Config] Wrote global config to Copilot CLI");
-                }
-            }
-            "cursor" => {
-                if let Ok(paths) = cursor_paths::get_cursor_paths() {
-                    cursor_config::write_cursor_config(&paths.mcp_config_file, &mcps)
-                        .map_err(|e| e.to_string())?;
-                    info!("[Config] Wrote global config to Cursor");
-                }
-            }
-            "gemini" => {
-                if let Ok(paths) = gemini_paths::get_gemini_paths() {
-                    gemini_config::write_gemini_config(&paths.settings_file, &mcps)
-                        .map_err(|e| e.to_string())?;
-                    info!("[Config] Wrote global config to Gemini CLI");
-                }
-            }
-            unknown => warn!("[Config] Unknown editor type '{}'. Skipping.", unknown),
+    Ok(mcps)
+}
+
+/// An editor's MCP config file as it is and as a sync would write it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpConfigPreview {
+    pub editor: String,
+    pub path: String,
+    /// Current content, `None` if the file doesn't exist
+    pub before: Option<String>,
+    /// Content after a sync; same as `before` when the file would be left alone
+    pub after: Option<String>,
+    pub changed: bool,
+    pub diff: String,
+}
+
+/// Compare `path`'s current content with what a writer rendered for it
+pub(crate) fn build_mcp_config_preview(
+    editor: &str,
+    path: &Path,
+    rendered: Option<String>,
+) -> Result<McpConfigPreview, String> {
+    let before = read_config_file(path).map_err(|e| e.to_string())?;
+    let after = rendered.or_else(|| before.clone());
+    Ok(McpConfigPreview {
+        editor: editor.to_string(),
+        path: path.to_string_lossy().to_string(),
+        changed: after != before,
+        diff: line_diff(
+            before.as_deref().unwrap_or(""),
+            after.as_deref().unwrap_or(""),
+        ),
+        before,
+        after,
+    })
+}
+
+/// Preview what syncing the global config would write for `editor`, without
+/// touching disk
+#[tauri::command]
+pub fn preview_global_mcp_config(
+    db: State<'_, Arc<Mutex<Database>>>,
+    editor: String,
+) -> Result<McpConfigPreview, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    preview_global_mcp_config_from_db(&db, &editor)
+}
+
+pub(crate) fn preview_global_mcp_config_from_db(
+    db: &Database,
+    editor: &str,
+) -> Result<McpConfigPreview, String> {
+    use crate::services::{
+        codex_config, copilot_config, cursor_config, gemini_config, opencode_config,
+        windsurf_config,
+    };
+    use crate::utils::{
+        codex_paths, copilot_paths, cursor_paths, gemini_paths, opencode_paths, windsurf_paths,
+    };
+
+    let mcps = load_global_mcps_from_db(db)?;
+    let (path, rendered) = match editor {
+        "claude_code" => {
+            let paths = paths::get_claude_paths().map_err(|e| e.to_string())?;
+            let content = config_writer::render_global_config(&paths, &mcps);
+            (paths.claude_json, content.map(Some))
         }
-    }
+        "opencode" => {
+            let path = opencode_paths::get_opencode_paths()
+                .map_err(|e| e.to_string())?
+                .config_file;
+            let content = opencode_config::render_opencode_config(&path, &mcps);
+            (path, content.map(Some))
+        }
+        "codex" => {
+            let path = codex_paths::get_codex_paths()
+                .map_err(|e| e.to_string())?
+                .config_file;
+            let content = codex_config::render_codex_config(&path, &mcps);
+            (path, content)
+        }
+        "copilot" => {
+            let path = copilot_paths::get_copilot_paths()
+                .map_err(|e| e.to_string())?
+                .mcp_config_file;
+            let content = copilot_config::render_copilot_config(&path, &mcps);
+            (path, content)
+        }
+        "cursor" => {
+            let path = cursor_paths::get_cursor_paths()
+                .map_err(|e| e.to_string())?
+                .mcp_config_file;
+            let content = cursor_config::render_cursor_config(&path, &mcps);
+            (path, content)
+        }
+        "gemini" => {
+            let path = gemini_paths::get_gemini_paths()
+                .map_err(|e| e.to_string())?
+                .settings_file;
+            let content = gemini_config::render_gemini_config(&path, &mcps);
+            (path, content)
+        }
+        "windsurf" => {
+            let path = windsurf_paths::get_windsurf_paths()
+                .map_err(|e| e.to_string())?
+                .mcp_config_file;
+            let content = windsurf_config::render_windsurf_config(&path, &mcps);
+            (path, content)
+        }
+        unknown => return Err(format!("Unknown editor type '{}'", unknown)),
+    };
 
-    Ok(())
+    build_mcp_config_preview(editor, &path, rendered.map_err(|e| e.to_string())?)
+}
+
+/// Check an editor's MCP config file for entries a sync would drop
+#[tauri::command]
+pub fn validate_editor_config(editor: String, path: String) -> Result<Vec<ConfigWarning>, String> {
+    config_validation::validate_config(&editor, Path::new(&path)).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -180,6 +369,9 @@ pub fn get_claude_paths() -> Result<ClaudePaths, String> {
 
 #[tauri::command]
 pub fn open_config_file(path: String) -> Result<(), String> {
+    // Paths recorded from WSL (or from Windows, when running inside WSL)
+    // must be translated before the platform opener can find them
+    let path = paths::to_host_path(&path);
     #[cfg(target_os = "windows")]
     {
         std::process::Command::new("explorer")
@@ -231,6 +423,292 @@ pub fn backup_configs() -> Result<(), String> {
     Ok(())
 }
 
+/// Collect app/OS/schema info, the tail of the debug log, detected editors and
+/// secret-redacted copies of the config files into a zip in the temp dir, then
+/// reveal it in the file manager.
+#[tauri::command]
+pub fn generate_diagnostics_bundle(
+    app: tauri::AppHandle,
+    db: State<'_, Arc<Mutex<Database>>>,
+) -> Result<PathBuf, String> {
+    use tauri_plugin_opener::OpenerExt;
+
+    let log_path = debug_logger::get_log_file_path().or_else(|| {
+        app.path()
+            .app_data_dir()
+            .ok()
+            .and_then(|dir| latest_log_file(&debug_logger::get_logs_dir(&dir)))
+    });
+
+    let entries = {
+        let db = db.lock().map_err(|e| e.to_string())?;
+        build_diagnostics_entries(&db, log_path.as_deref())
+    };
+
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let bundle_path = std::env::temp_dir().join(format!(
+        "claude-code-tool-manager-diagnostics-{}.zip",
+        timestamp
+    ));
+    diagnostics::write_bundle(&bundle_path, &entries).map_err(|e| e.to_string())?;
+    log::info!(
+        "[Config] Wrote diagnostics bundle to {}",
+        bundle_path.display()
+    );
+
+    app.opener()
+        .reveal_item_in_dir(&bundle_path)
+        .map_err(|e| e.to_string())?;
+    Ok(bundle_path)
+}
+
+/// Setting holding the Claude Code version found by the last detection
+const CLAUDE_CODE_VERSION_SETTING: &str = "claude_code_version";
+
+/// Run `claude --version` and remember the result. `None` means Claude Code
+/// isn't installed (or didn't answer), which also clears the stored version.
+/// Also runs once at startup.
+#[tauri::command]
+pub async fn detect_claude_code_version(
+    db: State<'_, Arc<Mutex<Database>>>,
+) -> Result<Option<ClaudeCodeVersion>, String> {
+    detect_and_store_claude_code_version(db.inner()).await
+}
+
+pub(crate) async fn detect_and_store_claude_code_version(
+    db: &Arc<Mutex<Database>>,
+) -> Result<Option<ClaudeCodeVersion>, String> {
+    let detected = tokio::task::spawn_blocking(claude_version::detect_claude_code_version)
+        .await
+        .map_err(|e| e.to_string())?;
+    match &detected {
+        Some(v) => log::info!("[Config] Detected Claude Code {}", v.version),
+        None => log::warn!("[Config] Claude Code not found or did not report a version"),
+    }
+    let db = db.lock().map_err(|e| e.to_string())?;
+    store_claude_code_version_in_db(&db, detected.as_ref())?;
+    Ok(detected)
+}
+
+/// The Claude Code version stored by the last detection
+#[tauri::command]
+pub fn get_claude_code_version(
+    db: State<'_, Arc<Mutex<Database>>>,
+) -> Result<Option<ClaudeCodeVersion>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    Ok(get_claude_code_version_from_db(&db))
+}
+
+pub(crate) fn store_claude_code_version_in_db(
+    db: &Database,
+    version: Option<&ClaudeCodeVersion>,
+) -> Result<(), String> {
+    db.set_setting(
+        CLAUDE_CODE_VERSION_SETTING,
+        version.map(|v| v.version.as_str()).unwrap_or_default(),
+    )
+    .map_err(|e| e.to_string())
+}
+
+pub(crate) fn get_claude_code_version_from_db(db: &Database) -> Option<ClaudeCodeVersion> {
+    db.get_setting(CLAUDE_CODE_VERSION_SETTING)
+        .and_then(|v| claude_version::parse_claude_version(&v))
+}
+
+/// Most recently modified `.log` file in `logs_dir`
+fn latest_log_file(logs_dir: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(logs_dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "log"))
+        .max_by_key(|p| std::fs::metadata(p).and_then(|m| m.modified()).ok())
+}
+
+/// Build the (archive name, contents) entries of a diagnostics bundle.
+/// Config files and the log tail are redacted before they are added.
+pub(crate) fn build_diagnostics_entries(
+    db: &Database,
+    log_path: Option<&Path>,
+) -> Vec<(String, String)> {
+    let editors = get_available_editors_from_db(db);
+    let schema_version: i64 = db
+        .conn()
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .unwrap_or(0);
+    let integrity = match db.integrity_check() {
+        Ok(None) => "ok".to_string(),
+        Ok(Some(problem)) => problem,
+        Err(e) => format!("failed to run: {}", e),
+    };
+
+    let info = serde_json::json!({
+        "appVersion": env!("CARGO_PKG_VERSION"),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "generatedAt": chrono::Utc::now().to_rfc3339(),
+        "schemaVersion": schema_version,
+        "integrityCheck": integrity,
+        "editors": editors,
+        "claudeCodeVersion": get_claude_code_version_from_db(db).map(|v| v.version),
+        "logFile": log_path.map(|p| p.to_string_lossy().to_string()),
+    });
+    let mut entries = vec![(
+        "diagnostics.json".to_string(),
+        serde_json::to_string_pretty(&info).unwrap_or_default(),
+    )];
+
+    if let Some(log) = log_path.and_then(|p| std::fs::read_to_string(p).ok()) {
+        entries.push((
+            "log-tail.txt".to_string(),
+            diagnostics::redact_text(&diagnostics::tail_lines(&log, DIAGNOSTICS_LOG_LINES)),
+        ));
+    }
+
+    let mut config_files: Vec<(String, PathBuf)> = Vec::new();
+    if let Ok(claude_paths) = paths::get_claude_paths() {
+        config_files.push(("claude_code".to_string(), claude_paths.global_settings));
+    }
+    for editor in editors.iter().filter(|e| e.is_installed) {
+        config_files.push((editor.id.clone(), PathBuf::from(&editor.config_path)));
+    }
+    for (editor, path) in config_files {
+        let Ok(contents) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        entries.push((
+            format!("configs/{}/{}", editor, file_name),
+            diagnostics::redact_config(&contents),
+        ));
+    }
+
+    entries
+}
+
+/// One known schema migration and whether this database has applied it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationInfo {
+    pub version: i64,
+    pub name: String,
+    pub applied: bool,
+    pub applied_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationStatus {
+    /// `PRAGMA user_version` as recorded in the database file
+    pub schema_version: i64,
+    /// Highest migration this build knows about
+    pub latest_version: i64,
+    pub migrations: Vec<MigrationInfo>,
+    pub pending: Vec<i64>,
+}
+
+/// List the known schema migrations, which have been applied, and the schema
+/// version recorded in the database
+#[tauri::command]
+pub fn get_migration_status(
+    db: State<'_, Arc<Mutex<Database>>>,
+) -> Result<MigrationStatus, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    get_migration_status_from_db(&db)
+}
+
+pub(crate) fn get_migration_status_from_db(db: &Database) -> Result<MigrationStatus, String> {
+    let mut stmt = db
+        .conn()
+        .prepare("SELECT version, applied_at FROM schema_migrations")
+        .map_err(|e| e.to_string())?;
+    let applied: std::collections::HashMap<i64, Option<String>> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let migrations: Vec<MigrationInfo> = SCHEMA_MIGRATIONS
+        .iter()
+        .map(|(version, name)| MigrationInfo {
+            version: *version,
+            name: name.to_string(),
+            applied: applied.contains_key(version),
+            applied_at: applied.get(version).cloned().flatten(),
+        })
+        .collect();
+    let pending = migrations
+        .iter()
+        .filter(|m| !m.applied)
+        .map(|m| m.version)
+        .collect();
+
+    let schema_version = db
+        .conn()
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+
+    Ok(MigrationStatus {
+        schema_version,
+        latest_version: SCHEMA_MIGRATIONS.last().map(|(v, _)| *v).unwrap_or(0),
+        migrations,
+        pending,
+    })
+}
+
+/// Render the enabled global MCPs as a shell script of `editor_id` CLI calls
+/// (`claude mcp add ...`) for setting up another machine. Global skills,
+/// sub-agents and commands are listed as comments since they must be copied
+/// as files.
+#[tauri::command]
+pub fn generate_setup_script(
+    db: State<'_, Arc<Mutex<Database>>>,
+    editor_id: String,
+) -> Result<String, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    generate_setup_script_from_db(&db, &editor_id)
+}
+
+pub(crate) fn generate_setup_script_from_db(
+    db: &Database,
+    editor_id: &str,
+) -> Result<String, String> {
+    let mcps: Vec<Mcp> = get_global_mcps_from_db(db)?
+        .into_iter()
+        .filter(|g| g.is_enabled)
+        .map(|g| g.mcp)
+        .collect();
+    let files = setup_script::FileCopyItems {
+        skills: enabled_global_names(db, "global_skills", "skills", "skill_id")?,
+        subagents: enabled_global_names(db, "global_subagents", "subagents", "subagent_id")?,
+        commands: enabled_global_names(db, "global_commands", "commands", "command_id")?,
+    };
+    setup_script::render_setup_script(editor_id, &mcps, &files).map_err(|e| e.to_string())
+}
+
+/// Names of the globally enabled rows of `table`, sorted
+fn enabled_global_names(
+    db: &Database,
+    global_table: &str,
+    table: &str,
+    column: &str,
+) -> Result<Vec<String>, String> {
+    let sql = format!(
+        "SELECT t.name FROM {global_table} g JOIN {table} t ON g.{column} = t.id
+         WHERE g.is_enabled = 1 ORDER BY t.name"
+    );
+    let mut stmt = db.conn().prepare(&sql).map_err(|e| e.to_string())?;
+    let names = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(names)
+}
+
 // ============================================================================
 // Testable helper functions (no Tauri State dependency)
 // ============================================================================
@@ -282,7 +760,7 @@ pub(crate) fn get_global_mcps_from_db(db: &Database) -> Result<Vec<GlobalMcp>, S
         .prepare(
             "SELECT gm.id, gm.mcp_id, gm.is_enabled, gm.env_overrides,
                     m.id, m.name, m.description, m.type, m.command, m.args, m.url, m.headers, m.env,
-                    m.icon, m.tags, m.source, m.source_path, m.is_enabled_global, m.is_favorite, m.created_at, m.updated_at
+                    m.icon, m.tags, m.source, m.source_path, m.is_enabled_global, m.is_favorite, m.created_at, m.updated_at, m.testable, m.env_file, m.display_name, m.homepage, m.inherit_env, m.shell
              FROM global_mcps gm
              JOIN mcps m ON gm.mcp_id = m.id
              ORDER BY gm.display_order",
@@ -324,6 +802,12 @@ mod tests {
             env: None,
             icon: None,
             tags: None,
+            testable: None,
+            env_file: None,
+            display_name: None,
+            inherit_env: None,
+            shell: None,
+            split_command: None,
         };
         create_mcp_in_db(db, &mcp).unwrap().id
     }
@@ -332,6 +816,32 @@ mod tests {
     // Global MCP CRUD tests
     // =========================================================================
 
+    #[test]
+    fn test_find_stale_global_mcps() {
+        let db = Database::in_memory().unwrap();
+        let kept = create_test_mcp(&db, "kept");
+        let removed = create_test_mcp(&db, "removed-by-cli");
+        let disabled = create_test_mcp(&db, "disabled");
+        for id in [kept, removed, disabled] {
+            add_global_mcp_in_db(&db, id).unwrap();
+        }
+        let disabled_row = get_global_mcps_from_db(&db)
+            .unwrap()
+            .into_iter()
+            .find(|g| g.mcp_id == disabled)
+            .unwrap();
+        toggle_global_mcp_in_db(&db, disabled_row.id, false).unwrap();
+
+        let present = HashSet::from(["kept".to_string()]);
+        let stale = scanner::find_stale_global_mcps(&db, &present).unwrap();
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].mcp_id, removed);
+        assert_eq!(stale[0].name, "removed-by-cli");
+
+        // Reporting never removes the row
+        assert_eq!(get_global_mcps_from_db(&db).unwrap().len(), 3);
+    }
+
     #[test]
     fn test_add_global_mcp() {
         let db = Database::in_memory().unwrap();
@@ -433,6 +943,12 @@ mod tests {
             env: None,
             icon: None,
             tags: None,
+            testable: None,
+            env_file: None,
+            display_name: None,
+            inherit_env: None,
+            shell: None,
+            split_command: None,
         };
         let mcp_id = create_mcp_in_db(&db, &mcp).unwrap().id;
 
@@ -564,4 +1080,89 @@ mod tests {
             .unwrap();
         assert_eq!(count, 1);
     }
+
+    #[test]
+    fn test_build_diagnostics_entries_redacts_log_tail() {
+        let db = Database::in_memory().unwrap();
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let log_path = temp_dir.path().join("debug.log");
+        let mut log: Vec<String> = (0..600).map(|i| format!("line {}", i)).collect();
+        log.push("sending Authorization: Bearer supersecret".to_string());
+        std::fs::write(&log_path, log.join("\n")).unwrap();
+
+        let entries = build_diagnostics_entries(&db, Some(&log_path));
+
+        let (_, info) = entries
+            .iter()
+            .find(|(name, _)| name == "diagnostics.json")
+            .unwrap();
+        let info: serde_json::Value = serde_json::from_str(info).unwrap();
+        assert_eq!(info["appVersion"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(info["integrityCheck"], "ok");
+        assert!(info["claudeCodeVersion"].is_null());
+
+        let (_, tail) = entries
+            .iter()
+            .find(|(name, _)| name == "log-tail.txt")
+            .unwrap();
+        assert_eq!(tail.lines().count(), DIAGNOSTICS_LOG_LINES);
+        assert!(!tail.contains("line 0\n"));
+        assert!(!tail.contains("supersecret"));
+    }
+
+    #[test]
+    fn test_claude_code_version_round_trip() {
+        let db = Database::in_memory().unwrap();
+        assert_eq!(get_claude_code_version_from_db(&db), None);
+
+        let version = claude_version::parse_claude_version("1.0.35 (Claude Code)").unwrap();
+        store_claude_code_version_in_db(&db, Some(&version)).unwrap();
+        assert_eq!(get_claude_code_version_from_db(&db), Some(version));
+
+        let entries = build_diagnostics_entries(&db, None);
+        let info: serde_json::Value = serde_json::from_str(&entries[0].1).unwrap();
+        assert_eq!(info["claudeCodeVersion"], "1.0.35");
+
+        store_claude_code_version_in_db(&db, None).unwrap();
+        assert_eq!(get_claude_code_version_from_db(&db), None);
+    }
+
+    #[test]
+    fn test_get_migration_status_reports_all_applied() {
+        let db = Database::in_memory().unwrap();
+        let status = get_migration_status_from_db(&db).unwrap();
+        assert_eq!(status.migrations.len(), SCHEMA_MIGRATIONS.len());
+        assert!(status.pending.is_empty());
+        assert!(status.migrations.iter().all(|m| m.applied_at.is_some()));
+        assert_eq!(status.schema_version, status.latest_version);
+
+        db.conn()
+            .execute(
+                "DELETE FROM schema_migrations WHERE version = ?",
+                [status.latest_version],
+            )
+            .unwrap();
+        let status = get_migration_status_from_db(&db).unwrap();
+        assert_eq!(status.pending, vec![status.latest_version]);
+    }
+
+    #[test]
+    fn test_generate_setup_script_skips_disabled_global_mcps() {
+        let db = Database::in_memory().unwrap();
+        let on = create_test_mcp(&db, "enabled-mcp");
+        let off = create_test_mcp(&db, "disabled-mcp");
+        add_global_mcp_in_db(&db, on).unwrap();
+        add_global_mcp_in_db(&db, off).unwrap();
+        let off_global = get_global_mcps_from_db(&db)
+            .unwrap()
+            .into_iter()
+            .find(|g| g.mcp_id == off)
+            .unwrap();
+        toggle_global_mcp_in_db(&db, off_global.id, false).unwrap();
+
+        let script = generate_setup_script_from_db(&db, "claude_code").unwrap();
+        assert!(script.contains("claude mcp add --scope user enabled-mcp -- npx"));
+        assert!(!script.contains("disabled-mcp"));
+        assert!(generate_setup_script_from_db(&db, "cursor").is_err());
+    }
 }
@@ -6,6 +6,7 @@ use crate::db::models::{
 use crate::db::Database;
 use crate::services::docker::client::DockerClientManager;
 use crate::services::docker::templates::get_builtin_templates;
+use crate::utils::command_line::shell_escape;
 use log::{error, info};
 use rusqlite::params;
 use std::collections::HashMap;
@@ -71,11 +72,6 @@ fn is_valid_git_url(url: &str) -> bool {
     valid_patterns.iter().any(|&v| v)
 }
 
-/// Shell-escape a single argument for use in sh -c commands.
-fn shell_escape(s: &str) -> String {
-    format!("'{}'", s.replace('\'', "'\\''"))
-}
-
 /// Validate that a volume host_path does not traverse to sensitive locations.
 pub fn validate_volume_host_path(host_path: &str) -> Result<(), String> {
     let normalized = host_path.replace('\\', "/");
@@ -0,0 +1,289 @@
+use crate::db::schema::Database;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// Setting holding the soft size limit, in bytes, for skill and sub-agent content
+pub const CONTENT_SIZE_LIMIT_SETTING: &str = "content_size_soft_limit";
+
+/// Limit used when the setting is unset or invalid
+pub const DEFAULT_CONTENT_SIZE_LIMIT: usize = 20 * 1024;
+
+/// A skill or sub-agent whose content is over the soft size limit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OversizedEntity {
+    /// "skill" or "subagent"
+    pub entity_type: String,
+    pub id: i64,
+    pub name: String,
+    pub bytes: usize,
+}
+
+/// A created or updated skill or sub-agent, plus a warning when its content is
+/// over the soft size limit
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedContent<T> {
+    pub item: T,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_warning: Option<String>,
+}
+
+/// Everything over the soft size limit, largest first. Advisory only.
+#[tauri::command]
+pub fn get_oversized_entities(
+    db: State<'_, Arc<Mutex<Database>>>,
+) -> Result<Vec<OversizedEntity>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    get_oversized_entities_from_db(&db)
+}
+
+#[tauri::command]
+pub fn get_content_size_limit(db: State<'_, Arc<Mutex<Database>>>) -> Result<usize, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    Ok(content_size_limit(&db))
+}
+
+#[tauri::command]
+pub fn set_content_size_limit(
+    db: State<'_, Arc<Mutex<Database>>>,
+    limit: usize,
+) -> Result<(), String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    if limit == 0 {
+        return Err("Size limit must be greater than zero".to_string());
+    }
+    db.set_setting(CONTENT_SIZE_LIMIT_SETTING, &limit.to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// The configured soft limit in bytes
+pub(crate) fn content_size_limit(db: &Database) -> usize {
+    db.get_setting(CONTENT_SIZE_LIMIT_SETTING)
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|limit| *limit > 0)
+        .unwrap_or(DEFAULT_CONTENT_SIZE_LIMIT)
+}
+
+/// A warning for content over the soft limit, or `None` when it fits
+pub(crate) fn content_size_warning(db: &Database, label: &str, content: &str) -> Option<String> {
+    let limit = content_size_limit(db);
+    let bytes = content.len();
+    (bytes > limit).then(|| {
+        format!(
+            "{} is {} KB, over the {} KB soft limit; large content is loaded into every context that uses it",
+            label,
+            bytes.div_ceil(1024),
+            limit.div_ceil(1024)
+        )
+    })
+}
+
+pub(crate) fn get_oversized_entities_from_db(
+    db: &Database,
+) -> Result<Vec<OversizedEntity>, String> {
+    let limit = content_size_limit(db) as i64;
+    let mut oversized = Vec::new();
+    for (entity_type, table) in [("skill", "skills"), ("subagent", "subagents")] {
+        let mut stmt = db
+            .conn()
+            .prepare(&format!(
+                "SELECT id, name, length(CAST(content AS BLOB)) FROM {} WHERE length(CAST(content AS BLOB)) > ?",
+                table
+            ))
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([limit], |row| {
+                Ok(OversizedEntity {
+                    entity_type: entity_type.to_string(),
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    bytes: row.get::<_, i64>(2)? as usize,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(|e| e.to_string())?;
+        oversized.extend(rows);
+    }
+    oversized.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+    Ok(oversized)
+}
+
+/// Rough characters-per-token ratio for English prose and markdown
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Approximate token count of `text`
+pub(crate) fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// Estimated context an enabled skill, sub-agent or command takes up
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextCostEntry {
+    /// "skill", "subagent" or "command"
+    pub entity_type: String,
+    pub id: i64,
+    pub name: String,
+    pub tokens: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextCostEstimate {
+    /// Most expensive first
+    pub entries: Vec<ContextCostEntry>,
+    pub total_tokens: usize,
+}
+
+/// Approximate tokens the skills, sub-agents and commands enabled for a
+/// project (globally or in the project) add to every session's context
+#[tauri::command(rename_all = "camelCase")]
+pub fn estimate_project_context_cost(
+    db: State<'_, Arc<Mutex<Database>>>,
+    project_id: i64,
+) -> Result<ContextCostEstimate, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    estimate_project_context_cost_from_db(&db, project_id)
+}
+
+/// Claude Code preloads only the name and description of each entity; the
+/// body is read when the entity is invoked. Skills with model invocation
+/// disabled are not preloaded at all.
+pub(crate) fn estimate_project_context_cost_from_db(
+    db: &Database,
+    project_id: i64,
+) -> Result<ContextCostEstimate, String> {
+    db.conn()
+        .query_row("SELECT 1 FROM projects WHERE id = ?", [project_id], |_| {
+            Ok(())
+        })
+        .map_err(|_| format!("No project with id {}", project_id))?;
+
+    let mut entries = Vec::new();
+    for (kind, filter) in [
+        ("skill", "AND COALESCE(e.disable_model_invocation, 0) = 0"),
+        ("subagent", ""),
+        ("command", ""),
+    ] {
+        let mut stmt = db
+            .conn()
+            .prepare(&format!(
+                "SELECT e.id, e.name, COALESCE(e.description, '') FROM {kind}s e
+                 WHERE (e.id IN (SELECT {kind}_id FROM global_{kind}s WHERE is_enabled = 1)
+                     OR e.id IN (SELECT {kind}_id FROM project_{kind}s WHERE project_id = ?1 AND is_enabled = 1))
+                 {filter}"
+            ))
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([project_id], |row| {
+                let name: String = row.get(1)?;
+                let description: String = row.get(2)?;
+                Ok(ContextCostEntry {
+                    entity_type: kind.to_string(),
+                    id: row.get(0)?,
+                    tokens: estimate_tokens(&name) + estimate_tokens(&description),
+                    name,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(|e| e.to_string())?;
+        entries.extend(rows);
+    }
+    entries.sort_by(|a, b| b.tokens.cmp(&a.tokens).then_with(|| a.name.cmp(&b.name)));
+    let total_tokens = entries.iter().map(|e| e.tokens).sum();
+    Ok(ContextCostEstimate {
+        entries,
+        total_tokens,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::skills::create_skill_in_db;
+    use crate::db::models::CreateSkillRequest;
+
+    fn skill(name: &str, content: String) -> CreateSkillRequest {
+        CreateSkillRequest {
+            name: name.to_string(),
+            description: None,
+            content,
+            allowed_tools: None,
+            model: None,
+            disable_model_invocation: None,
+            tags: None,
+            context: None,
+            agent: None,
+            hooks: None,
+            paths: None,
+            shell: None,
+            once: None,
+            effort: None,
+        }
+    }
+
+    #[test]
+    fn test_content_size_warning_respects_setting() {
+        let db = Database::in_memory().unwrap();
+        let content = "x".repeat(DEFAULT_CONTENT_SIZE_LIMIT + 1);
+        let warning = content_size_warning(&db, "Skill 'big'", &content).unwrap();
+        assert!(warning.contains("21 KB"));
+        assert!(content_size_warning(&db, "Skill 'small'", "tiny").is_none());
+
+        db.set_setting(CONTENT_SIZE_LIMIT_SETTING, "100000")
+            .unwrap();
+        assert!(content_size_warning(&db, "Skill 'big'", &content).is_none());
+        assert_eq!(content_size_limit(&db), 100000);
+    }
+
+    #[test]
+    fn test_get_oversized_entities_lists_largest_first() {
+        let db = Database::in_memory().unwrap();
+        db.set_setting(CONTENT_SIZE_LIMIT_SETTING, "10").unwrap();
+        create_skill_in_db(&db, &skill("small", "short".to_string())).unwrap();
+        create_skill_in_db(&db, &skill("medium", "m".repeat(20))).unwrap();
+        // Multi-byte characters count as bytes, not chars
+        create_skill_in_db(&db, &skill("large", "é".repeat(20))).unwrap();
+
+        let oversized = get_oversized_entities_from_db(&db).unwrap();
+        let names: Vec<_> = oversized.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["large", "medium"]);
+        assert_eq!(oversized[0].bytes, 40);
+        assert_eq!(oversized[0].entity_type, "skill");
+    }
+
+    #[test]
+    fn test_estimate_project_context_cost() {
+        let db = Database::in_memory().unwrap();
+        db.conn()
+            .execute_batch(&format!(
+                "INSERT INTO projects (name, path) VALUES ('proj', '/tmp/proj'), ('other', '/tmp/other');
+                 INSERT INTO skills (name, description, content) VALUES
+                     ('lint', '{}', 'body is not preloaded'),
+                     ('manual', 'only when asked', 'x');
+                 UPDATE skills SET disable_model_invocation = 1 WHERE name = 'manual';
+                 INSERT INTO subagents (name, description, content) VALUES ('reviewer', 'Reviews code', 'x');
+                 INSERT INTO commands (name, description, content) VALUES ('deploy', NULL, 'x'), ('elsewhere', 'd', 'x');
+                 INSERT INTO global_skills (skill_id) VALUES (1), (2);
+                 INSERT INTO project_subagents (project_id, subagent_id) VALUES (1, 1);
+                 INSERT INTO project_commands (project_id, command_id) VALUES (1, 1), (2, 2);",
+                "d".repeat(40)
+            ))
+            .unwrap();
+
+        let estimate = estimate_project_context_cost_from_db(&db, 1).unwrap();
+        let names: Vec<_> = estimate.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["lint", "reviewer", "deploy"]);
+        // "lint" is 1 token, 40 description chars are 10
+        assert_eq!(estimate.entries[0].tokens, 11);
+        assert_eq!(estimate.entries[1].tokens, 2 + 3);
+        assert_eq!(estimate.entries[2].tokens, 2);
+        assert_eq!(estimate.total_tokens, 18);
+
+        assert!(estimate_project_context_cost_from_db(&db, 99).is_err());
+    }
+}
@@ -1,5 +1,22 @@
-use crate::services::debug_logger;
-use tauri::Manager;
+use crate::db::schema::{Database, DatabaseRecovery};
+use crate::services::{debug_logger, diagnostics};
+use std::sync::{Arc, Mutex};
+use tauri::{Manager, State};
+
+/// Setting that overrides the key pattern masked in MCP debug logs
+pub const LOG_SECRET_PATTERN_KEY: &str = "log_secret_pattern";
+
+/// Set during startup when a corrupt database had to be replaced
+pub struct StartupDatabaseRecovery(pub Option<DatabaseRecovery>);
+
+/// Details of a database recovery performed at startup, if any. Lets the UI
+/// show the notice even if it mounted after the `database-recovered` event.
+#[tauri::command]
+pub fn get_database_recovery(
+    recovery: tauri::State<'_, StartupDatabaseRecovery>,
+) -> Option<DatabaseRecovery> {
+    recovery.0.clone()
+}
 
 /// Enable debug mode and return the log file path
 #[tauri::command]
@@ -91,6 +108,35 @@ pub fn open_logs_folder(app: tauri::AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// The secret key pattern used to mask MCP env vars and headers in the log
+#[tauri::command]
+pub fn get_log_secret_pattern(db: State<'_, Arc<Mutex<Database>>>) -> Result<String, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    Ok(get_log_secret_pattern_from_db(&db))
+}
+
+/// An empty pattern restores the default
+#[tauri::command]
+pub fn set_log_secret_pattern(
+    db: State<'_, Arc<Mutex<Database>>>,
+    pattern: String,
+) -> Result<(), String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    set_log_secret_pattern_in_db(&db, &pattern)
+}
+
+pub(crate) fn get_log_secret_pattern_from_db(db: &Database) -> String {
+    db.get_setting(LOG_SECRET_PATTERN_KEY)
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| diagnostics::DEFAULT_SECRET_KEY_PATTERN.to_string())
+}
+
+pub(crate) fn set_log_secret_pattern_in_db(db: &Database, pattern: &str) -> Result<(), String> {
+    diagnostics::set_secret_key_pattern(Some(pattern)).map_err(|e| e.to_string())?;
+    db.set_setting(LOG_SECRET_PATTERN_KEY, pattern.trim())
+        .map_err(|e| e.to_string())
+}
+
 /// Write a log entry from the frontend
 #[tauri::command]
 pub fn write_frontend_log(
@@ -261,4 +307,36 @@ mod tests {
         // Just verify it doesn't panic
         let _ = debug_logger::get_log_file_path();
     }
+
+    // =========================================================================
+    // log secret pattern tests
+    // =========================================================================
+
+    #[test]
+    #[serial_test::serial]
+    fn test_log_secret_pattern_round_trip() {
+        let db = Database::in_memory().unwrap();
+        assert_eq!(
+            get_log_secret_pattern_from_db(&db),
+            diagnostics::DEFAULT_SECRET_KEY_PATTERN
+        );
+
+        // Extend rather than replace the default so tests running alongside
+        // still see every usual secret masked
+        let pattern = format!("{}|^x-tenant$", diagnostics::DEFAULT_SECRET_KEY_PATTERN);
+        set_log_secret_pattern_in_db(&db, &pattern).unwrap();
+        assert_eq!(get_log_secret_pattern_from_db(&db), pattern);
+        assert!(diagnostics::is_secret_key("X-Tenant"));
+
+        // An invalid pattern is rejected and the stored one kept
+        assert!(set_log_secret_pattern_in_db(&db, "(unclosed").is_err());
+        assert_eq!(get_log_secret_pattern_from_db(&db), pattern);
+
+        set_log_secret_pattern_in_db(&db, "").unwrap();
+        assert_eq!(
+            get_log_secret_pattern_from_db(&db),
+            diagnostics::DEFAULT_SECRET_KEY_PATTERN
+        );
+        assert!(!diagnostics::is_secret_key("X-Tenant"));
+    }
 }
@@ -0,0 +1,697 @@
+//! Export and import of everything enabled globally, for moving a setup
+//! from one editor to another
+//!
+//! A bundle reuses the create-request shapes as its schema, so anything the
+//! app can create can round-trip. Importing creates whatever the library is
+//! missing, enables it globally, and writes it for the target editor only.
+
+use crate::commands::commands::{
+    create_command_in_db, get_all_commands_from_db, get_command_by_id,
+};
+use crate::commands::config::{load_global_mcps_from_db, write_global_mcp_config};
+use crate::commands::hooks::{
+    create_hook_in_db, get_global_hooks_from_db, get_hook_by_id, sync_global_hooks,
+};
+use crate::commands::mcp::{create_mcp_in_db, get_all_mcps_from_db, get_mcp_by_id};
+use crate::commands::skills::{create_skill_in_db, get_all_skills_from_db, get_skill_by_id};
+use crate::commands::subagents::{
+    create_subagent_in_db, get_all_subagents_from_db, get_subagent_by_id,
+};
+use crate::db::models::{
+    Command, CreateCommandRequest, CreateHookRequest, CreateMcpRequest, CreateSkillRequest,
+    CreateSubAgentRequest, Skill, SubAgent,
+};
+use crate::db::schema::Database;
+use crate::services::pending_changes::PendingChange;
+use crate::services::{command_writer, skill_writer, subagent_writer};
+use log::info;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// Bumped when a bundle field changes meaning
+pub const BUNDLE_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditorBundle {
+    pub schema_version: u32,
+    pub source_editor: String,
+    pub exported_at: String,
+    #[serde(default)]
+    pub mcps: Vec<CreateMcpRequest>,
+    #[serde(default)]
+    pub skills: Vec<CreateSkillRequest>,
+    #[serde(default)]
+    pub subagents: Vec<CreateSubAgentRequest>,
+    #[serde(default)]
+    pub commands: Vec<CreateCommandRequest>,
+    #[serde(default)]
+    pub hooks: Vec<CreateHookRequest>,
+    /// Items left out or changed on export
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleImportResult {
+    /// Names of items added to the library
+    pub created: Vec<String>,
+    /// Names of items the library already had, enabled as they are
+    pub reused: Vec<String>,
+    /// Items skipped or fields the target editor can't represent
+    pub warnings: Vec<String>,
+    /// Claude config writes queued for confirmation
+    #[serde(default)]
+    pub pending_changes: Vec<PendingChange>,
+}
+
+/// Items enabled by an import, for writing to the target editor
+#[derive(Debug, Default)]
+pub(crate) struct ImportedItems {
+    pub mcps: bool,
+    pub skills: Vec<Skill>,
+    pub subagents: Vec<SubAgent>,
+    pub commands: Vec<Command>,
+    pub hooks: bool,
+}
+
+#[tauri::command]
+pub fn export_editor_bundle(
+    db: State<'_, Arc<Mutex<Database>>>,
+    editor_id: String,
+) -> Result<EditorBundle, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    export_editor_bundle_from_db(&db, &editor_id)
+}
+
+#[tauri::command]
+pub fn import_editor_bundle(
+    db: State<'_, Arc<Mutex<Database>>>,
+    editor_id: String,
+    bundle: EditorBundle,
+) -> Result<BundleImportResult, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    let (mut result, items) = import_editor_bundle_in_db(&db, &editor_id, &bundle)?;
+    result.pending_changes = write_imported_items(&db, &editor_id, &items)?;
+    info!(
+        "[Bundle] Imported {} new and {} existing items for {}",
+        result.created.len(),
+        result.reused.len(),
+        editor_id
+    );
+    Ok(result)
+}
+
+/// Everything enabled globally. Skills, subagents and hooks are exported
+/// even if `editor` doesn't write them so the bundle can move elsewhere.
+pub(crate) fn export_editor_bundle_from_db(
+    db: &Database,
+    editor: &str,
+) -> Result<EditorBundle, String> {
+    let mut warnings = Vec::new();
+
+    let mcp_ids = enabled_global_ids(db, "global_mcps", "mcp_id")?;
+    let mut mcps = Vec::new();
+    for mcp in get_all_mcps_from_db(db)?
+        .into_iter()
+        .filter(|m| mcp_ids.contains(&m.id))
+    {
+        if mcp.source == "system" {
+            warnings.push(format!("MCP '{}' is built into the app; skipped", mcp.name));
+            continue;
+        }
+        mcps.push(CreateMcpRequest {
+            name: mcp.name,
+            description: mcp.description,
+            mcp_type: mcp.mcp_type,
+            command: mcp.command,
+            args: mcp.args,
+            url: mcp.url,
+            headers: mcp.headers,
+            env: mcp.env,
+            icon: mcp.icon,
+            tags: mcp.tags,
+            testable: Some(mcp.testable),
+            env_file: mcp.env_file,
+            display_name: mcp.display_name,
+            inherit_env: Some(mcp.inherit_env),
+            shell: mcp.shell,
+            split_command: None,
+        });
+    }
+
+    let skill_ids = enabled_global_ids(db, "global_skills", "skill_id")?;
+    let skills = get_all_skills_from_db(db)?
+        .into_iter()
+        .filter(|s| skill_ids.contains(&s.id))
+        .map(|s| CreateSkillRequest {
+            name: s.name,
+            description: s.description,
+            content: s.content,
+            allowed_tools: s.allowed_tools,
+            model: s.model,
+            disable_model_invocation: Some(s.disable_model_invocation),
+            tags: s.tags,
+            context: s.context,
+            agent: s.agent,
+            hooks: s.hooks,
+            paths: s.paths,
+            shell: s.shell,
+            once: s.once,
+            effort: s.effort,
+        })
+        .collect();
+
+    let subagent_ids = enabled_global_ids(db, "global_subagents", "subagent_id")?;
+    let subagents = get_all_subagents_from_db(db)?
+        .into_iter()
+        .filter(|s| subagent_ids.contains(&s.id))
+        .map(|s| CreateSubAgentRequest {
+            name: s.name,
+            description: s.description,
+            content: s.content,
+            tools: s.tools,
+            model: s.model,
+            permission_mode: s.permission_mode,
+            skills: s.skills,
+            tags: s.tags,
+            disallowed_tools: s.disallowed_tools,
+            max_turns: s.max_turns,
+            memory: s.memory,
+            background: s.background,
+            effort: s.effort,
+            isolation: s.isolation,
+            hooks: s.hooks,
+            mcp_servers: s.mcp_servers,
+            initial_prompt: s.initial_prompt,
+        })
+        .collect();
+
+    let command_ids = enabled_global_ids(db, "global_commands", "command_id")?;
+    let commands = get_all_commands_from_db(db)?
+        .into_iter()
+        .filter(|c| command_ids.contains(&c.id))
+        .map(|c| CreateCommandRequest {
+            name: c.name,
+            description: c.description,
+            content: c.content,
+            allowed_tools: c.allowed_tools,
+            argument_hint: c.argument_hint,
+            model: c.model,
+            tags: c.tags,
+        })
+        .collect();
+
+    let hooks = get_global_hooks_from_db(db)?
+        .into_iter()
+        .filter(|gh| gh.is_enabled)
+        .map(|gh| {
+            let h = gh.hook;
+            CreateHookRequest {
+                name: h.name,
+                description: h.description,
+                event_type: h.event_type,
+                matcher: h.matcher,
+                hook_type: h.hook_type,
+                command: h.command,
+                prompt: h.prompt,
+                timeout: h.timeout,
+                tags: h.tags,
+                url: h.url,
+                headers: h.headers,
+                allowed_env_vars: h.allowed_env_vars,
+                if_condition: h.if_condition,
+                status_message: h.status_message,
+                once: Some(h.once),
+                async_mode: Some(h.async_mode),
+                shell: h.shell,
+                path_pattern: h.path_pattern,
+            }
+        })
+        .collect();
+
+    Ok(EditorBundle {
+        schema_version: BUNDLE_SCHEMA_VERSION,
+        source_editor: editor.to_string(),
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        mcps,
+        skills,
+        subagents,
+        commands,
+        hooks,
+        warnings,
+    })
+}
+
+fn enabled_global_ids(db: &Database, table: &str, column: &str) -> Result<HashSet<i64>, String> {
+    let mut stmt = db
+        .conn()
+        .prepare(&format!(
+            "SELECT {} FROM {} WHERE is_enabled = 1",
+            column, table
+        ))
+        .map_err(|e| e.to_string())?;
+    let ids = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(ids)
+}
+
+fn id_by_name(db: &Database, table: &str, name: &str) -> Option<i64> {
+    db.conn()
+        .query_row(
+            &format!("SELECT id FROM {} WHERE name = ?", table),
+            [name],
+            |row| row.get(0),
+        )
+        .ok()
+}
+
+fn enable_global(db: &Database, table: &str, column: &str, id: i64) -> Result<(), String> {
+    db.conn()
+        .execute(
+            &format!(
+                "INSERT INTO {} ({}) VALUES (?) ON CONFLICT({}) DO UPDATE SET is_enabled = 1",
+                table, column, column
+            ),
+            params![id],
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Find `name` in `table` or create it, then enable it globally. `differs`
+/// says whether an existing item's definition doesn't match the bundle's.
+fn find_or_create(
+    db: &Database,
+    kind: &str,
+    (table, global_table, column): (&str, &str, &str),
+    name: &str,
+    differs: impl FnOnce(i64) -> bool,
+    create: impl FnOnce() -> Result<i64, String>,
+    result: &mut BundleImportResult,
+) -> Option<i64> {
+    let id = match id_by_name(db, table, name) {
+        Some(id) => {
+            if differs(id) {
+                result.warnings.push(format!(
+                    "{} '{}' already exists with a different definition; kept the library version",
+                    kind, name
+                ));
+            }
+            result.reused.push(name.to_string());
+            id
+        }
+        None => match create() {
+            Ok(id) => {
+                result.created.push(name.to_string());
+                id
+            }
+            Err(e) => {
+                result
+                    .warnings
+                    .push(format!("{} '{}' could not be created: {}", kind, name, e));
+                return None;
+            }
+        },
+    };
+
+    if let Err(e) = enable_global(db, global_table, column, id) {
+        result
+            .warnings
+            .push(format!("{} '{}' could not be enabled: {}", kind, name, e));
+        return None;
+    }
+    Some(id)
+}
+
+/// Whether `editor` has a global home for `kind`
+fn editor_supports(editor: &str, kind: &str) -> bool {
+    match kind {
+        "mcp" => true,
+        "skill" | "subagent" => matches!(editor, "claude_code" | "opencode"),
+        "command" => matches!(editor, "claude_code" | "opencode" | "windsurf"),
+        "hook" => editor == "claude_code",
+        _ => false,
+    }
+}
+
+/// Fields of a bundle item that `editor` has nowhere to put
+pub(crate) fn field_warnings(editor: &str, bundle: &EditorBundle) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    if editor == "opencode" {
+        for s in &bundle.subagents {
+            if let Some(mode) = s.permission_mode.as_deref() {
+                if !mode.is_empty() && subagent_writer::opencode_permission(mode).is_none() {
+                    warnings.push(format!(
+                        "Subagent '{}': permission mode '{}' has no OpenCode equivalent",
+                        s.name, mode
+                    ));
+                }
+            }
+            if s.skills.as_ref().is_some_and(|v| !v.is_empty()) {
+                warnings.push(format!(
+                    "Subagent '{}': OpenCode agents can't preload skills",
+                    s.name
+                ));
+            }
+        }
+    }
+
+    for c in &bundle.commands {
+        let dropped: Vec<&str> = match editor {
+            "opencode" => [
+                c.allowed_tools.is_some().then_some("allowed tools"),
+                c.argument_hint.is_some().then_some("argument hint"),
+            ]
+            .into_iter()
+            .flatten()
+            .collect(),
+            "windsurf" => [
+                c.allowed_tools.is_some().then_some("allowed tools"),
+                c.argument_hint.is_some().then_some("argument hint"),
+                c.model.is_some().then_some("model"),
+            ]
+            .into_iter()
+            .flatten()
+            .collect(),
+            _ => Vec::new(),
+        };
+        if !dropped.is_empty() {
+            warnings.push(format!(
+                "Command '{}': {} not supported by {}",
+                c.name,
+                dropped.join(", "),
+                editor
+            ));
+        }
+    }
+
+    warnings
+}
+
+/// Add a bundle's items to the library and enable them globally, skipping
+/// kinds `editor` can't use. Nothing is written to disk.
+pub(crate) fn import_editor_bundle_in_db(
+    db: &Database,
+    editor: &str,
+    bundle: &EditorBundle,
+) -> Result<(BundleImportResult, ImportedItems), String> {
+    if bundle.schema_version > BUNDLE_SCHEMA_VERSION {
+        return Err(format!(
+            "Bundle schema version {} is newer than this app supports ({})",
+            bundle.schema_version, BUNDLE_SCHEMA_VERSION
+        ));
+    }
+
+    // Roll back everything if a step fails partway
+    let tx = db
+        .conn()
+        .unchecked_transaction()
+        .map_err(|e| e.to_string())?;
+
+    let mut result = BundleImportResult {
+        warnings: field_warnings(editor, bundle),
+        ..Default::default()
+    };
+    let mut items = ImportedItems::default();
+
+    let skip = |kind: &str, count: usize, result: &mut BundleImportResult| {
+        let supported = editor_supports(editor, kind);
+        if !supported && count > 0 {
+            result.warnings.push(format!(
+                "{} {}(s) skipped: not supported by {}",
+                count, kind, editor
+            ));
+        }
+        !supported
+    };
+
+    if !skip("mcp", bundle.mcps.len(), &mut result) {
+        for mcp in &bundle.mcps {
+            let id = find_or_create(
+                db,
+                "MCP",
+                ("mcps", "global_mcps", "mcp_id"),
+                &mcp.name,
+                |id| {
+                    get_mcp_by_id(db, id).is_ok_and(|m| {
+                        m.mcp_type != mcp.mcp_type
+                            || m.command != mcp.command
+                            || m.args != mcp.args
+                            || m.url != mcp.url
+                            || m.headers != mcp.headers
+                            || m.env != mcp.env
+                    })
+                },
+                || create_mcp_in_db(db, mcp).map(|m| m.id),
+                &mut result,
+            );
+            items.mcps |= id.is_some();
+        }
+    }
+
+    if !skip("skill", bundle.skills.len(), &mut result) {
+        for skill in &bundle.skills {
+            let id = find_or_create(
+                db,
+                "Skill",
+                ("skills", "global_skills", "skill_id"),
+                &skill.name,
+                |id| get_skill_by_id(db, id).is_ok_and(|s| s.content != skill.content),
+                || create_skill_in_db(db, skill).map(|s| s.id),
+                &mut result,
+            );
+            if let Some(id) = id {
+                items.skills.push(get_skill_by_id(db, id)?);
+            }
+        }
+    }
+
+    if !skip("subagent", bundle.subagents.len(), &mut result) {
+        for subagent in &bundle.subagents {
+            let id = find_or_create(
+                db,
+                "Subagent",
+                ("subagents", "global_subagents", "subagent_id"),
+                &subagent.name,
+                |id| {
+                    get_subagent_by_id(db, id).is_ok_and(|s| {
+                        s.content != subagent.content
+                            || s.tools != subagent.tools
+                            || s.model != subagent.model
+                    })
+                },
+                || create_subagent_in_db(db, subagent).map(|s| s.id),
+                &mut result,
+            );
+            if let Some(id) = id {
+                items.subagents.push(get_subagent_by_id(db, id)?);
+            }
+        }
+    }
+
+    if !skip("command", bundle.commands.len(), &mut result) {
+        for command in &bundle.commands {
+            let id = find_or_create(
+                db,
+                "Command",
+                ("commands", "global_commands", "command_id"),
+                &command.name,
+                |id| get_command_by_id(db, id).is_ok_and(|c| c.content != command.content),
+                || create_command_in_db(db, command).map(|c| c.id),
+                &mut result,
+            );
+            if let Some(id) = id {
+                items.commands.push(get_command_by_id(db, id)?);
+            }
+        }
+    }
+
+    if !skip("hook", bundle.hooks.len(), &mut result) {
+        for hook in &bundle.hooks {
+            let id = find_or_create(
+                db,
+                "Hook",
+                ("hooks", "global_hooks", "hook_id"),
+                &hook.name,
+                |id| {
+                    get_hook_by_id(db, id).is_ok_and(|h| {
+                        h.event_type != hook.event_type
+                            || h.matcher != hook.matcher
+                            || h.hook_type != hook.hook_type
+                            || h.command != hook.command
+                            || h.prompt != hook.prompt
+                            || h.url != hook.url
+                    })
+                },
+                || create_hook_in_db(db, hook).map(|h| h.id),
+                &mut result,
+            );
+            items.hooks |= id.is_some();
+        }
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok((result, items))
+}
+
+/// Write imported items with `editor`'s writers, returning the Claude config
+/// writes queued for confirmation
+fn write_imported_items(
+    db: &Database,
+    editor: &str,
+    items: &ImportedItems,
+) -> Result<Vec<PendingChange>, String> {
+    let mut pending = Vec::new();
+    if items.mcps {
+        pending.extend(write_global_mcp_config(
+            editor,
+            &load_global_mcps_from_db(db)?,
+        )?);
+    }
+
+    for skill in &items.skills {
+        match editor {
+            "opencode" => skill_writer::write_global_skill_opencode(skill),
+            _ => skill_writer::write_global_skill(skill),
+        }
+        .map_err(|e| e.to_string())?;
+    }
+
+    for subagent in &items.subagents {
+        match editor {
+            "opencode" => subagent_writer::write_global_subagent_opencode(subagent),
+            _ => subagent_writer::write_global_subagent(subagent),
+        }
+        .map_err(|e| e.to_string())?;
+    }
+
+    for command in &items.commands {
+        match editor {
+            "opencode" => command_writer::write_global_command_opencode(command),
+            "windsurf" => command_writer::write_global_command_windsurf(command),
+            _ => command_writer::write_global_command(command),
+        }
+        .map_err(|e| e.to_string())?;
+    }
+
+    if items.hooks {
+        pending.extend(sync_global_hooks(db)?);
+    }
+
+    Ok(pending)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_bundle() -> EditorBundle {
+        let json = serde_json::json!({
+            "schemaVersion": 1,
+            "sourceEditor": "claude_code",
+            "exportedAt": "2026-01-01T00:00:00Z",
+            "mcps": [{"name": "fs", "type": "stdio", "command": "npx", "args": ["-y", "fs"]}],
+            "skills": [{"name": "review", "description": "Review", "content": "Review code"}],
+            "subagents": [{
+                "name": "planner",
+                "description": "Plans",
+                "content": "Plan things",
+                "permissionMode": "plan",
+                "skills": ["review"]
+            }],
+            "commands": [{
+                "name": "deploy",
+                "content": "Deploy $ARGUMENTS",
+                "argumentHint": "[env]",
+                "model": "sonnet"
+            }],
+            "hooks": [{"name": "lint", "eventType": "PostToolUse", "hookType": "command", "command": "npm run lint"}]
+        });
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_import_then_export_round_trips() {
+        let db = Database::in_memory().unwrap();
+        let bundle = sample_bundle();
+
+        let (result, items) = import_editor_bundle_in_db(&db, "claude_code", &bundle).unwrap();
+        assert_eq!(result.created.len(), 5);
+        assert!(result.warnings.is_empty());
+        assert!(items.mcps && items.hooks);
+        assert_eq!(items.subagents[0].permission_mode.as_deref(), Some("plan"));
+
+        let exported = export_editor_bundle_from_db(&db, "claude_code").unwrap();
+        assert_eq!(exported.schema_version, BUNDLE_SCHEMA_VERSION);
+        assert_eq!(exported.mcps[0].args, bundle.mcps[0].args);
+        assert_eq!(exported.skills[0].name, "review");
+        assert_eq!(exported.subagents[0].skills, bundle.subagents[0].skills);
+        assert_eq!(exported.commands[0].argument_hint.as_deref(), Some("[env]"));
+        assert_eq!(exported.hooks[0].command.as_deref(), Some("npm run lint"));
+
+        // Importing again reuses what's already in the library
+        let (again, _) = import_editor_bundle_in_db(&db, "claude_code", &exported).unwrap();
+        assert!(again.created.is_empty());
+        assert_eq!(again.reused.len(), 5);
+        assert!(again.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_import_warns_when_reused_item_differs() {
+        let db = Database::in_memory().unwrap();
+        let mut bundle = sample_bundle();
+        import_editor_bundle_in_db(&db, "claude_code", &bundle).unwrap();
+
+        bundle.skills[0].content = "Review code carefully".to_string();
+        bundle.mcps[0].args = Some(vec!["-y".to_string(), "fs@2".to_string()]);
+        let (result, _) = import_editor_bundle_in_db(&db, "claude_code", &bundle).unwrap();
+        assert_eq!(result.reused.len(), 5);
+        assert_eq!(
+            result.warnings,
+            vec![
+                "MCP 'fs' already exists with a different definition; kept the library version",
+                "Skill 'review' already exists with a different definition; kept the library version",
+            ]
+        );
+        let skill_id = id_by_name(&db, "skills", "review").unwrap();
+        assert_eq!(
+            get_skill_by_id(&db, skill_id).unwrap().content,
+            "Review code"
+        );
+    }
+
+    #[test]
+    fn test_import_skips_and_maps_for_target_editor() {
+        let db = Database::in_memory().unwrap();
+        let mut bundle = sample_bundle();
+
+        let (result, items) = import_editor_bundle_in_db(&db, "windsurf", &bundle).unwrap();
+        assert_eq!(result.created, vec!["fs", "deploy"]);
+        assert!(items.skills.is_empty() && items.subagents.is_empty() && !items.hooks);
+        assert!(result
+            .warnings
+            .iter()
+            .any(|w| w == "1 hook(s) skipped: not supported by windsurf"));
+        assert!(result
+            .warnings
+            .iter()
+            .any(|w| w.contains("argument hint, model")));
+
+        bundle.subagents[0].permission_mode = Some("dontAsk".to_string());
+        let warnings = field_warnings("opencode", &bundle);
+        assert!(warnings.iter().any(|w| w.contains("'dontAsk'")));
+        assert!(warnings.iter().any(|w| w.contains("preload skills")));
+
+        bundle.schema_version = BUNDLE_SCHEMA_VERSION + 1;
+        assert!(import_editor_bundle_in_db(&db, "claude_code", &bundle).is_err());
+    }
+}
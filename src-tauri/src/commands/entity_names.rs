@@ -0,0 +1,556 @@
+use crate::commands::commands::{get_command_by_id, validate_command_name};
+use crate::commands::models::{enabled_project_paths, is_enabled_globally};
+use crate::commands::settings::get_enabled_editors_from_db;
+use crate::commands::skills::{get_skill_by_id, validate_skill_name, RESERVED_WORDS};
+use crate::commands::subagents::get_subagent_by_id;
+use crate::db::schema::Database;
+use crate::services::{command_writer, skill_writer, subagent_writer};
+use directories::BaseDirs;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// Maximum length of a generated slug (matches the name validators)
+const MAX_SLUG_LENGTH: usize = 64;
+
+/// Entity kinds whose name becomes a file or directory name on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NamedEntityType {
+    Skill,
+    Subagent,
+    Command,
+}
+
+impl NamedEntityType {
+    const ALL: [NamedEntityType; 3] = [
+        NamedEntityType::Skill,
+        NamedEntityType::Subagent,
+        NamedEntityType::Command,
+    ];
+
+    fn table(self) -> &'static str {
+        match self {
+            NamedEntityType::Skill => "skills",
+            NamedEntityType::Subagent => "subagents",
+            NamedEntityType::Command => "commands",
+        }
+    }
+}
+
+/// A skill, sub-agent or command whose name Claude Code may refuse to load
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NameIssue {
+    pub entity_type: NamedEntityType,
+    pub id: i64,
+    pub name: String,
+    pub problem: String,
+    /// Slug [`normalize_entity_name`] would rename to, if one can be derived
+    pub suggested_name: Option<String>,
+}
+
+/// Why `name` is not a valid slug, or None when it is
+fn name_problem(entity_type: NamedEntityType, name: &str) -> Option<String> {
+    if name != name.trim() {
+        return Some("Name has leading or trailing whitespace".to_string());
+    }
+    // Sub-agents follow the same lowercase-hyphen rules as skills
+    match entity_type {
+        NamedEntityType::Skill | NamedEntityType::Subagent => validate_skill_name(name).error,
+        NamedEntityType::Command => validate_command_name(name).error,
+    }
+}
+
+/// Turn an arbitrary name into a valid slug: lowercase, runs of other
+/// characters collapsed to single hyphens, reserved words dropped.
+pub(crate) fn slugify_name(name: &str) -> Option<String> {
+    let mut lowered = name.to_lowercase();
+    for reserved in RESERVED_WORDS {
+        lowered = lowered.replace(reserved, " ");
+    }
+
+    let mut slug = lowered
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    slug.truncate(MAX_SLUG_LENGTH);
+    let slug = slug.trim_end_matches('-').to_string();
+
+    (!slug.is_empty()).then_some(slug)
+}
+
+/// Flag every skill, sub-agent and command whose name is not a valid slug
+#[tauri::command]
+pub fn validate_entity_names(
+    db: State<'_, Arc<Mutex<Database>>>,
+) -> Result<Vec<NameIssue>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    validate_entity_names_in_db(&db)
+}
+
+pub(crate) fn validate_entity_names_in_db(db: &Database) -> Result<Vec<NameIssue>, String> {
+    let mut issues = Vec::new();
+    for entity_type in NamedEntityType::ALL {
+        let sql = format!("SELECT id, name FROM {} ORDER BY name", entity_type.table());
+        let mut stmt = db.conn().prepare(&sql).map_err(|e| e.to_string())?;
+        let rows: Vec<(i64, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        for (id, name) in rows {
+            if let Some(problem) = name_problem(entity_type, &name) {
+                issues.push(NameIssue {
+                    entity_type,
+                    id,
+                    suggested_name: slugify_name(&name),
+                    name,
+                    problem,
+                });
+            }
+        }
+    }
+    Ok(issues)
+}
+
+/// Rename an entity to a valid slug and move its files in every scope it is
+/// enabled in. Returns the new name.
+#[tauri::command]
+pub fn normalize_entity_name(
+    db: State<'_, Arc<Mutex<Database>>>,
+    entity_type: NamedEntityType,
+    id: i64,
+) -> Result<String, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    normalize_entity_name_in_db(&db, entity_type, id)
+}
+
+pub(crate) fn normalize_entity_name_in_db(
+    db: &Database,
+    entity_type: NamedEntityType,
+    id: i64,
+) -> Result<String, String> {
+    let table = entity_type.table();
+    let name: String = db
+        .conn()
+        .query_row(
+            &format!("SELECT name FROM {table} WHERE id = ?"),
+            [id],
+            |row| row.get(0),
+        )
+        .map_err(|_| format!("No {} with id {}", table, id))?;
+
+    if name_problem(entity_type, &name).is_none() {
+        return Ok(name);
+    }
+    let slug =
+        slugify_name(&name).ok_or_else(|| format!("Cannot derive a valid name from '{}'", name))?;
+
+    let taken: bool = db
+        .conn()
+        .query_row(
+            &format!("SELECT COUNT(*) > 0 FROM {table} WHERE name = ? AND id != ?"),
+            rusqlite::params![slug, id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if taken {
+        return Err(format!(
+            "Cannot rename '{}' to '{}': the name is already taken",
+            name, slug
+        ));
+    }
+
+    rename_entity(db, entity_type, id, &slug)?;
+    Ok(slug)
+}
+
+/// Update the name column, then move the written files from the old name to
+/// the new one in each enabled scope and editor. The rename is rolled back
+/// if any file can't be moved.
+fn rename_entity(
+    db: &Database,
+    entity_type: NamedEntityType,
+    id: i64,
+    new_name: &str,
+) -> Result<(), String> {
+    let tx = db
+        .conn()
+        .unchecked_transaction()
+        .map_err(|e| e.to_string())?;
+    rename_entity_files(db, entity_type, id, new_name)?;
+    tx.commit().map_err(|e| e.to_string())
+}
+
+fn rename_entity_files(
+    db: &Database,
+    entity_type: NamedEntityType,
+    id: i64,
+    new_name: &str,
+) -> Result<(), String> {
+    let editors = get_enabled_editors_from_db(db);
+    let update = |db: &Database| {
+        db.conn()
+            .execute(
+                &format!(
+                    "UPDATE {} SET name = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+                    entity_type.table()
+                ),
+                rusqlite::params![new_name, id],
+            )
+            .map_err(|e| e.to_string())
+    };
+
+    match entity_type {
+        NamedEntityType::Skill => {
+            let old = get_skill_by_id(db, id)?;
+            update(db)?;
+            let new = get_skill_by_id(db, id)?;
+            let (global, projects) = enabled_scopes(db, "skill", id)?;
+            for editor in &editors {
+                match editor.as_str() {
+                    "claude_code" => {
+                        // Move the whole directory so supporting files come along
+                        for base in claude_bases(global, &projects)? {
+                            move_skill_dir(&base, &old.name, &new.name)?;
+                            skill_writer::write_skill_file(&base, &new)
+                                .map_err(|e| e.to_string())?;
+                        }
+                    }
+                    "opencode" => {
+                        if global {
+                            skill_writer::delete_global_skill_opencode(&old)
+                                .and_then(|_| skill_writer::write_global_skill_opencode(&new))
+                                .map_err(|e| e.to_string())?;
+                        }
+                        for path in &projects {
+                            skill_writer::delete_project_skill_opencode(path, &old)
+                                .and_then(|_| {
+                                    skill_writer::write_project_skill_opencode(path, &new)
+                                })
+                                .map_err(|e| e.to_string())?;
+                        }
+                    }
+                    unknown => warn_unknown_editor(unknown, &new.name),
+                }
+            }
+        }
+        NamedEntityType::Subagent => {
+            let old = get_subagent_by_id(db, id)?;
+            update(db)?;
+            let new = get_subagent_by_id(db, id)?;
+            let (global, projects) = enabled_scopes(db, "subagent", id)?;
+            for editor in &editors {
+                let result = match editor.as_str() {
+                    "claude_code" => migrate_files(
+                        global,
+                        &projects,
+                        || {
+                            subagent_writer::delete_global_subagent(
+                                subagent_writer::subagent_file_stem(&old),
+                            )
+                        },
+                        || subagent_writer::write_global_subagent(&new),
+                        |p| {
+                            subagent_writer::delete_project_subagent(
+                                p,
+                                subagent_writer::subagent_file_stem(&old),
+                            )
+                        },
+                        |p| subagent_writer::write_project_subagent(p, &new),
+                    ),
+                    "opencode" => migrate_files(
+                        global,
+                        &projects,
+                        || {
+                            subagent_writer::delete_global_subagent_opencode(
+                                subagent_writer::subagent_file_stem(&old),
+                            )
+                        },
+                        || subagent_writer::write_global_subagent_opencode(&new),
+                        |p| {
+                            subagent_writer::delete_project_subagent_opencode(
+                                p,
+                                subagent_writer::subagent_file_stem(&old),
+                            )
+                        },
+                        |p| subagent_writer::write_project_subagent_opencode(p, &new),
+                    ),
+                    unknown => {
+                        warn_unknown_editor(unknown, &new.name);
+                        Ok(())
+                    }
+                };
+                result.map_err(|e| e.to_string())?;
+            }
+        }
+        NamedEntityType::Command => {
+            let old = get_command_by_id(db, id)?;
+            update(db)?;
+            let new = get_command_by_id(db, id)?;
+            let (global, projects) = enabled_scopes(db, "command", id)?;
+            for editor in &editors {
+                let result = match editor.as_str() {
+                    "claude_code" => migrate_files(
+                        global,
+                        &projects,
+                        || command_writer::delete_global_command(&old),
+                        || command_writer::write_global_command(&new),
+                        |p| command_writer::delete_project_command(p, &old),
+                        |p| command_writer::write_project_command(p, &new),
+                    ),
+                    "opencode" => migrate_files(
+                        global,
+                        &projects,
+                        || command_writer::delete_global_command_opencode(&old),
+                        || command_writer::write_global_command_opencode(&new),
+                        |p| command_writer::delete_project_command_opencode(p, &old),
+                        |p| command_writer::write_project_command_opencode(p, &new),
+                    ),
+                    "windsurf" => migrate_files(
+                        global,
+                        &projects,
+                        || command_writer::delete_global_command_windsurf(&old),
+                        || command_writer::write_global_command_windsurf(&new),
+                        |p| command_writer::delete_project_command_windsurf(p, &old),
+                        |p| command_writer::write_project_command_windsurf(p, &new),
+                    ),
+                    unknown => {
+                        warn_unknown_editor(unknown, &new.name);
+                        Ok(())
+                    }
+                };
+                result.map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether an entity is enabled globally, and the projects it is enabled in
+fn enabled_scopes(db: &Database, kind: &str, id: i64) -> Result<(bool, Vec<PathBuf>), String> {
+    let column = format!("{kind}_id");
+    let global = is_enabled_globally(db, &format!("global_{kind}s"), &column, id);
+    let projects = enabled_project_paths(db, &format!("project_{kind}s"), &column, id)?
+        .into_iter()
+        .map(PathBuf::from)
+        .collect();
+    Ok((global, projects))
+}
+
+/// Base directories Claude Code files are written under for the given scopes
+fn claude_bases(global: bool, projects: &[PathBuf]) -> Result<Vec<PathBuf>, String> {
+    let mut bases = projects.to_vec();
+    if global {
+        let base_dirs = BaseDirs::new().ok_or("Could not find home directory")?;
+        bases.push(base_dirs.home_dir().to_path_buf());
+    }
+    Ok(bases)
+}
+
+/// Rename a skill directory. A missing one is logged and left for the caller
+/// to write afresh; an existing target is an error rather than merged into.
+fn move_skill_dir(base: &Path, old_name: &str, new_name: &str) -> Result<(), String> {
+    let from = skill_writer::skill_dir(base, old_name);
+    let to = skill_writer::skill_dir(base, new_name);
+    if !from.exists() {
+        warn!(
+            "[EntityNames] Skill directory {} is missing; writing '{}' fresh",
+            from.display(),
+            new_name
+        );
+        return Ok(());
+    }
+    if to.exists() {
+        return Err(format!(
+            "Cannot move {} to {}: the target already exists",
+            from.display(),
+            to.display()
+        ));
+    }
+    std::fs::rename(&from, &to).map_err(|e| {
+        format!(
+            "Failed to move {} to {}: {}",
+            from.display(),
+            to.display(),
+            e
+        )
+    })
+}
+
+/// Delete the old file and write the new one in each enabled scope
+fn migrate_files(
+    global: bool,
+    projects: &[PathBuf],
+    delete_global: impl Fn() -> anyhow::Result<()>,
+    write_global: impl Fn() -> anyhow::Result<()>,
+    delete_project: impl Fn(&Path) -> anyhow::Result<()>,
+    write_project: impl Fn(&Path) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    if global {
+        delete_global()?;
+        write_global()?;
+    }
+    for path in projects {
+        delete_project(path)?;
+        write_project(path)?;
+    }
+    Ok(())
+}
+
+fn warn_unknown_editor(editor: &str, name: &str) {
+    warn!(
+        "[EntityNames] Unknown editor type '{}' for '{}'. Skipping.",
+        editor, name
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn insert_project(db: &Database, path: &Path) -> i64 {
+        db.conn()
+            .execute(
+                "INSERT INTO projects (name, path) VALUES ('proj', ?)",
+                [path.to_string_lossy().to_string()],
+            )
+            .unwrap();
+        db.conn().last_insert_rowid()
+    }
+
+    #[test]
+    fn test_slugify_name() {
+        assert_eq!(
+            slugify_name("My Cool Skill").as_deref(),
+            Some("my-cool-skill")
+        );
+        assert_eq!(
+            slugify_name("  code_review!! ").as_deref(),
+            Some("code-review")
+        );
+        assert_eq!(slugify_name("Claude Helper").as_deref(), Some("helper"));
+        assert_eq!(slugify_name("***"), None);
+        assert_eq!(
+            slugify_name(&"a".repeat(80)).unwrap().len(),
+            MAX_SLUG_LENGTH
+        );
+    }
+
+    #[test]
+    fn test_validate_entity_names_flags_invalid_names() {
+        let db = Database::in_memory().unwrap();
+        db.conn()
+            .execute_batch(
+                "INSERT INTO skills (name, content) VALUES ('My Cool Skill', 'x'), ('fine-skill', 'x');
+                 INSERT INTO subagents (name, description, content) VALUES ('Reviewer', 'd', 'x');
+                 INSERT INTO commands (name, content) VALUES ('deploy', 'x');",
+            )
+            .unwrap();
+
+        let issues = validate_entity_names_in_db(&db).unwrap();
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].entity_type, NamedEntityType::Skill);
+        assert_eq!(issues[0].suggested_name.as_deref(), Some("my-cool-skill"));
+        assert_eq!(issues[1].entity_type, NamedEntityType::Subagent);
+        assert_eq!(issues[1].suggested_name.as_deref(), Some("reviewer"));
+    }
+
+    #[test]
+    fn test_normalize_skill_moves_directory_with_support_files() {
+        let db = Database::in_memory().unwrap();
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO skills (name, content) VALUES ('My Cool Skill', 'Do it.')",
+                [],
+            )
+            .unwrap();
+        let skill_id = db.conn().last_insert_rowid();
+        let project_id = insert_project(&db, temp_dir.path());
+        db.conn()
+            .execute(
+                "INSERT INTO project_skills (project_id, skill_id) VALUES (?, ?)",
+                [project_id, skill_id],
+            )
+            .unwrap();
+
+        let skill = get_skill_by_id(&db, skill_id).unwrap();
+        skill_writer::write_project_skill(temp_dir.path(), &skill).unwrap();
+        let old_dir = skill_writer::skill_dir(temp_dir.path(), "My Cool Skill");
+        std::fs::create_dir_all(old_dir.join("scripts")).unwrap();
+        std::fs::write(old_dir.join("scripts/run.sh"), "echo hi").unwrap();
+
+        let new_name = normalize_entity_name_in_db(&db, NamedEntityType::Skill, skill_id).unwrap();
+        assert_eq!(new_name, "my-cool-skill");
+        assert_eq!(
+            get_skill_by_id(&db, skill_id).unwrap().name,
+            "my-cool-skill"
+        );
+
+        let new_dir = skill_writer::skill_dir(temp_dir.path(), "my-cool-skill");
+        assert!(!old_dir.exists());
+        assert!(new_dir.join("scripts/run.sh").exists());
+        let written = std::fs::read_to_string(new_dir.join("SKILL.md")).unwrap();
+        assert!(written.contains("name: my-cool-skill"));
+    }
+
+    #[test]
+    fn test_normalize_skill_rolls_back_when_the_move_fails() {
+        let db = Database::in_memory().unwrap();
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO skills (name, content) VALUES ('My Cool Skill', 'Do it.')",
+                [],
+            )
+            .unwrap();
+        let skill_id = db.conn().last_insert_rowid();
+        let project_id = insert_project(&db, temp_dir.path());
+        db.conn()
+            .execute(
+                "INSERT INTO project_skills (project_id, skill_id) VALUES (?, ?)",
+                [project_id, skill_id],
+            )
+            .unwrap();
+
+        let skill = get_skill_by_id(&db, skill_id).unwrap();
+        skill_writer::write_project_skill(temp_dir.path(), &skill).unwrap();
+        // A directory already sits where the skill would move to
+        std::fs::create_dir_all(skill_writer::skill_dir(temp_dir.path(), "my-cool-skill")).unwrap();
+
+        let err = normalize_entity_name_in_db(&db, NamedEntityType::Skill, skill_id).unwrap_err();
+        assert!(err.contains("already exists"));
+        assert_eq!(
+            get_skill_by_id(&db, skill_id).unwrap().name,
+            "My Cool Skill"
+        );
+    }
+
+    #[test]
+    fn test_normalize_command_rejects_taken_slug() {
+        let db = Database::in_memory().unwrap();
+        db.conn()
+            .execute_batch(
+                "INSERT INTO commands (name, content) VALUES ('Deploy App', 'x'), ('deploy-app', 'x');",
+            )
+            .unwrap();
+        let id: i64 = db
+            .conn()
+            .query_row(
+                "SELECT id FROM commands WHERE name = 'Deploy App'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+
+        let err = normalize_entity_name_in_db(&db, NamedEntityType::Command, id).unwrap_err();
+        assert!(err.contains("already taken"));
+        assert_eq!(get_command_by_id(&db, id).unwrap().name, "Deploy App");
+    }
+}
@@ -0,0 +1,265 @@
+use crate::commands::commands::get_command_by_id;
+use crate::commands::entity_names::NamedEntityType;
+use crate::commands::settings::get_enabled_editors_from_db;
+use crate::commands::skills::get_skill_by_id;
+use crate::commands::subagents::get_subagent_by_id;
+use crate::db::schema::Database;
+use crate::services::{command_writer, skill_writer, subagent_writer};
+use crate::utils::opencode_paths::{opencode_config_dir, project_opencode_dir};
+use directories::BaseDirs;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// Whether one editor's copy of an entity, in one scope, matches the library
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntitySyncStatus {
+    pub editor_id: String,
+    pub scope: String, // "global" or "project"
+    pub project_id: Option<i64>,
+    pub path: String,
+    pub exists: bool,
+    /// The file exists and its content is exactly what would be written now
+    pub in_sync: bool,
+}
+
+/// Where an editor keeps an entity under `base`, and what the file should
+/// contain. `base` is the home or project directory for Claude Code and the
+/// OpenCode config directory (`~/.config/opencode` or `{project}/.opencode`)
+/// for OpenCode.
+type ExpectedFile = fn(&Database, i64, &str, &Path) -> Result<Option<(PathBuf, String)>, String>;
+
+fn expected_skill_file(
+    db: &Database,
+    id: i64,
+    editor: &str,
+    base: &Path,
+) -> Result<Option<(PathBuf, String)>, String> {
+    let skill = get_skill_by_id(db, id)?;
+    let path = match editor {
+        "claude_code" => skill_writer::skill_file_path(base, &skill.name),
+        "opencode" => skill_writer::skill_file_path_opencode(base, &skill.name),
+        _ => return Ok(None),
+    };
+    Ok(Some((path, skill_writer::generate_skill_markdown(&skill))))
+}
+
+fn expected_subagent_file(
+    db: &Database,
+    id: i64,
+    editor: &str,
+    base: &Path,
+) -> Result<Option<(PathBuf, String)>, String> {
+    let subagent = get_subagent_by_id(db, id)?;
+    Ok(match editor {
+        "claude_code" => Some((
+            subagent_writer::subagent_file_path(
+                base,
+                subagent_writer::subagent_file_stem(&subagent),
+            ),
+            subagent_writer::generate_subagent_markdown(&subagent),
+        )),
+        "opencode" => Some((
+            subagent_writer::subagent_file_path_opencode(
+                base,
+                subagent_writer::subagent_file_stem(&subagent),
+            ),
+            subagent_writer::generate_subagent_markdown_opencode(&subagent),
+        )),
+        _ => None,
+    })
+}
+
+fn expected_command_file(
+    db: &Database,
+    id: i64,
+    editor: &str,
+    base: &Path,
+) -> Result<Option<(PathBuf, String)>, String> {
+    let command = get_command_by_id(db, id)?;
+    Ok(match editor {
+        "claude_code" => Some((
+            command_writer::command_file_path(base, &command.name),
+            command_writer::generate_command_markdown(&command),
+        )),
+        "opencode" => Some((
+            command_writer::command_file_path_opencode(base, &command.name),
+            command_writer::generate_command_markdown_opencode(&command),
+        )),
+        _ => None,
+    })
+}
+
+/// For every enabled editor and every scope an entity is enabled in, whether
+/// its file exists and matches the library
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_entity_sync_status(
+    db: State<'_, Arc<Mutex<Database>>>,
+    entity_type: NamedEntityType,
+    id: i64,
+) -> Result<Vec<EntitySyncStatus>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    let base_dirs = BaseDirs::new().ok_or("Could not find home directory")?;
+    get_entity_sync_status_from_db(&db, entity_type, id, base_dirs.home_dir())
+}
+
+/// `home` is the base for the global scope: `~/.claude/` for Claude Code and
+/// `~/.config/opencode/` for OpenCode.
+pub(crate) fn get_entity_sync_status_from_db(
+    db: &Database,
+    entity_type: NamedEntityType,
+    id: i64,
+    home: &Path,
+) -> Result<Vec<EntitySyncStatus>, String> {
+    let (kind, expected): (&str, ExpectedFile) = match entity_type {
+        NamedEntityType::Skill => ("skill", expected_skill_file),
+        NamedEntityType::Subagent => ("subagent", expected_subagent_file),
+        NamedEntityType::Command => ("command", expected_command_file),
+    };
+
+    // Every scope the entity is enabled in, as (project id, project path)
+    let mut scopes: Vec<(Option<i64>, Option<PathBuf>)> = Vec::new();
+    let global: bool = db
+        .conn()
+        .query_row(
+            &format!(
+                "SELECT COUNT(*) > 0 FROM global_{kind}s WHERE {kind}_id = ? AND is_enabled = 1"
+            ),
+            [id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if global {
+        scopes.push((None, None));
+    }
+    let mut stmt = db
+        .conn()
+        .prepare(&format!(
+            "SELECT p.id, p.path FROM project_{kind}s x JOIN projects p ON x.project_id = p.id
+             WHERE x.{kind}_id = ? AND x.is_enabled = 1
+             ORDER BY p.name"
+        ))
+        .map_err(|e| e.to_string())?;
+    let projects: Vec<(i64, String)> = stmt
+        .query_map([id], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    scopes.extend(
+        projects
+            .into_iter()
+            .map(|(project_id, path)| (Some(project_id), Some(PathBuf::from(path)))),
+    );
+
+    let mut statuses = Vec::new();
+    for editor in get_enabled_editors_from_db(db) {
+        for (project_id, project_path) in &scopes {
+            let base = match (editor.as_str(), project_path) {
+                ("opencode", Some(path)) => project_opencode_dir(path),
+                ("opencode", None) => opencode_config_dir(home),
+                (_, Some(path)) => path.clone(),
+                (_, None) => home.to_path_buf(),
+            };
+            let Some((path, content)) = expected(db, id, &editor, &base)? else {
+                continue;
+            };
+            let on_disk = std::fs::read_to_string(&path).ok();
+            statuses.push(EntitySyncStatus {
+                editor_id: editor.clone(),
+                scope: if project_id.is_some() {
+                    "project"
+                } else {
+                    "global"
+                }
+                .to_string(),
+                project_id: *project_id,
+                path: path.to_string_lossy().to_string(),
+                exists: path.exists(),
+                in_sync: on_disk.as_deref() == Some(content.as_str()),
+            });
+        }
+    }
+    Ok(statuses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn enable_editors(db: &Database, editors: &[&str]) {
+        db.set_setting("enabled_editors", &serde_json::to_string(editors).unwrap())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_entity_sync_status_per_editor_and_scope() {
+        let db = Database::in_memory().unwrap();
+        let home = TempDir::new().unwrap();
+        let project = TempDir::new().unwrap();
+        enable_editors(&db, &["claude_code", "opencode"]);
+        db.conn()
+            .execute_batch(&format!(
+                "INSERT INTO commands (name, content) VALUES ('deploy', 'Ship it.');
+                 INSERT INTO projects (name, path) VALUES ('proj', '{}');
+                 INSERT INTO global_commands (command_id) VALUES (1);
+                 INSERT INTO project_commands (project_id, command_id) VALUES (1, 1);",
+                project.path().display()
+            ))
+            .unwrap();
+
+        let command = get_command_by_id(&db, 1).unwrap();
+        command_writer::write_command_file(home.path(), &command).unwrap();
+        command_writer::write_project_command_opencode(project.path(), &command).unwrap();
+        // Hand-edited project copy for Claude Code
+        let edited = project.path().join(".claude/commands/deploy.md");
+        std::fs::create_dir_all(edited.parent().unwrap()).unwrap();
+        std::fs::write(&edited, "Ship it later.").unwrap();
+
+        let statuses =
+            get_entity_sync_status_from_db(&db, NamedEntityType::Command, 1, home.path()).unwrap();
+        let find = |editor: &str, scope: &str| {
+            statuses
+                .iter()
+                .find(|s| s.editor_id == editor && s.scope == scope)
+                .unwrap()
+        };
+        assert_eq!(statuses.len(), 4);
+        assert!(find("claude_code", "global").in_sync);
+        let project_claude = find("claude_code", "project");
+        assert!(project_claude.exists && !project_claude.in_sync);
+        assert_eq!(project_claude.project_id, Some(1));
+        let global_opencode = find("opencode", "global");
+        assert!(!global_opencode.exists && !global_opencode.in_sync);
+        assert!(find("opencode", "project").in_sync);
+    }
+
+    #[test]
+    fn test_entity_sync_status_skips_disabled_scopes() {
+        let db = Database::in_memory().unwrap();
+        let home = TempDir::new().unwrap();
+        db.conn()
+            .execute_batch(
+                "INSERT INTO skills (name, content) VALUES ('review', 'Review it.');
+                 INSERT INTO global_skills (skill_id, is_enabled) VALUES (1, 0);",
+            )
+            .unwrap();
+
+        let statuses =
+            get_entity_sync_status_from_db(&db, NamedEntityType::Skill, 1, home.path()).unwrap();
+        assert!(statuses.is_empty());
+
+        db.conn()
+            .execute("UPDATE global_skills SET is_enabled = 1", [])
+            .unwrap();
+        let skill = get_skill_by_id(&db, 1).unwrap();
+        skill_writer::write_skill_file(home.path(), &skill).unwrap();
+        let statuses =
+            get_entity_sync_status_from_db(&db, NamedEntityType::Skill, 1, home.path()).unwrap();
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].editor_id, "claude_code");
+        assert!(statuses[0].in_sync);
+    }
+}
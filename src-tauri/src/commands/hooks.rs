@@ -1,6 +1,7 @@
 use crate::db::models::{CreateHookRequest, GlobalHook, Hook, ProjectHook};
 use crate::db::schema::Database;
-use crate::services::hook_writer;
+use crate::services::pending_changes::{PendingChange, WithPendingChanges};
+use crate::services::{hook_writer, plugin_hooks};
 use log::{error, info};
 use rusqlite::params;
 use std::path::Path;
@@ -34,6 +35,7 @@ fn row_to_hook(row: &rusqlite::Row) -> rusqlite::Result<Hook> {
         once: row.get::<_, i32>(14).unwrap_or(0) != 0,
         async_mode: row.get::<_, i32>(15).unwrap_or(0) != 0,
         shell: row.get(16)?,
+        path_pattern: row.get(22)?,
         tags: parse_json_array(row.get(17)?),
         source: row.get(18)?,
         is_template: row.get::<_, i32>(19)? != 0,
@@ -61,6 +63,7 @@ fn row_to_hook_with_offset(row: &rusqlite::Row, offset: usize) -> rusqlite::Resu
         once: row.get::<_, i32>(offset + 14).unwrap_or(0) != 0,
         async_mode: row.get::<_, i32>(offset + 15).unwrap_or(0) != 0,
         shell: row.get(offset + 16)?,
+        path_pattern: row.get(offset + 22)?,
         tags: parse_json_array(row.get(offset + 17)?),
         source: row.get(offset + 18)?,
         is_template: row.get::<_, i32>(offset + 19)? != 0,
@@ -69,13 +72,13 @@ fn row_to_hook_with_offset(row: &rusqlite::Row, offset: usize) -> rusqlite::Resu
     })
 }
 
-const HOOK_SELECT_FIELDS: &str = "id, name, description, event_type, matcher, hook_type, command, prompt, timeout, url, headers, allowed_env_vars, if_condition, status_message, once, async_mode, shell, tags, source, is_template, created_at, updated_at";
+const HOOK_SELECT_FIELDS: &str = "id, name, description, event_type, matcher, hook_type, command, prompt, timeout, url, headers, allowed_env_vars, if_condition, status_message, once, async_mode, shell, tags, source, is_template, created_at, updated_at, path_pattern";
 
 // Table-prefixed version for JOIN queries to avoid ambiguous column names
-const HOOK_SELECT_FIELDS_H: &str = "h.id, h.name, h.description, h.event_type, h.matcher, h.hook_type, h.command, h.prompt, h.timeout, h.url, h.headers, h.allowed_env_vars, h.if_condition, h.status_message, h.once, h.async_mode, h.shell, h.tags, h.source, h.is_template, h.created_at, h.updated_at";
+const HOOK_SELECT_FIELDS_H: &str = "h.id, h.name, h.description, h.event_type, h.matcher, h.hook_type, h.command, h.prompt, h.timeout, h.url, h.headers, h.allowed_env_vars, h.if_condition, h.status_message, h.once, h.async_mode, h.shell, h.tags, h.source, h.is_template, h.created_at, h.updated_at, h.path_pattern";
 
 // Helper to get all enabled global hooks and write to settings.json
-fn sync_global_hooks(db: &Database) -> Result<(), String> {
+pub(crate) fn sync_global_hooks(db: &Database) -> Result<Option<PendingChange>, String> {
     let mut stmt = db
         .conn()
         .prepare(&format!(
@@ -94,7 +97,10 @@ fn sync_global_hooks(db: &Database) -> Result<(), String> {
 }
 
 // Helper to get all enabled project hooks and write to settings.local.json
-fn sync_project_hooks(db: &Database, project_path: &str) -> Result<(), String> {
+pub(crate) fn sync_project_hooks(
+    db: &Database,
+    project_path: &str,
+) -> Result<Option<PendingChange>, String> {
     let mut stmt = db
         .conn()
         .prepare(&format!(
@@ -156,6 +162,7 @@ pub fn create_hook(
     db: State<'_, Arc<Mutex<Database>>>,
     hook: CreateHookRequest,
 ) -> Result<Hook, String> {
+    hook_writer::validate_hook(&hook)?;
     let db_guard = db.lock().map_err(|e| e.to_string())?;
 
     let tags_json = hook
@@ -174,8 +181,8 @@ pub fn create_hook(
     db_guard
         .conn()
         .execute(
-            "INSERT INTO hooks (name, description, event_type, matcher, hook_type, command, prompt, timeout, url, headers, allowed_env_vars, if_condition, status_message, once, async_mode, shell, tags, source)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'manual')",
+            "INSERT INTO hooks (name, description, event_type, matcher, hook_type, command, prompt, timeout, url, headers, allowed_env_vars, if_condition, status_message, once, async_mode, shell, path_pattern, tags, source)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'manual')",
             params![
                 hook.name,
                 hook.description,
@@ -193,6 +200,7 @@ pub fn create_hook(
                 hook.once.unwrap_or(false) as i32,
                 hook.async_mode.unwrap_or(false) as i32,
                 hook.shell,
+                hook.path_pattern,
                 tags_json
             ],
         )
@@ -236,8 +244,8 @@ pub fn create_hook_from_template(
     db_guard
         .conn()
         .execute(
-            "INSERT INTO hooks (name, description, event_type, matcher, hook_type, command, prompt, timeout, url, headers, allowed_env_vars, if_condition, status_message, once, async_mode, shell, tags, source, is_template)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'template', 0)",
+            "INSERT INTO hooks (name, description, event_type, matcher, hook_type, command, prompt, timeout, url, headers, allowed_env_vars, if_condition, status_message, once, async_mode, shell, path_pattern, tags, source, is_template)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'template', 0)",
             params![
                 name,
                 template.description,
@@ -255,6 +263,7 @@ pub fn create_hook_from_template(
                 template.once as i32,
                 template.async_mode as i32,
                 template.shell,
+                template.path_pattern,
                 template.tags.as_ref().map(|t| serde_json::to_string(t).unwrap())
             ],
         )
@@ -278,7 +287,8 @@ pub fn update_hook(
     db: State<'_, Arc<Mutex<Database>>>,
     id: i64,
     hook: CreateHookRequest,
-) -> Result<Hook, String> {
+) -> Result<WithPendingChanges<Hook>, String> {
+    hook_writer::validate_hook(&hook)?;
     let db_guard = db.lock().map_err(|e| e.to_string())?;
 
     let tags_json = hook
@@ -297,7 +307,7 @@ pub fn update_hook(
     db_guard
         .conn()
         .execute(
-            "UPDATE hooks SET name = ?, description = ?, event_type = ?, matcher = ?, hook_type = ?, command = ?, prompt = ?, timeout = ?, url = ?, headers = ?, allowed_env_vars = ?, if_condition = ?, status_message = ?, once = ?, async_mode = ?, shell = ?, tags = ?, updated_at = CURRENT_TIMESTAMP
+            "UPDATE hooks SET name = ?, description = ?, event_type = ?, matcher = ?, hook_type = ?, command = ?, prompt = ?, timeout = ?, url = ?, headers = ?, allowed_env_vars = ?, if_condition = ?, status_message = ?, once = ?, async_mode = ?, shell = ?, path_pattern = ?, tags = ?, updated_at = CURRENT_TIMESTAMP
              WHERE id = ?",
             params![
                 hook.name,
@@ -316,6 +326,7 @@ pub fn update_hook(
                 hook.once.unwrap_or(false) as i32,
                 hook.async_mode.unwrap_or(false) as i32,
                 hook.shell,
+                hook.path_pattern,
                 tags_json,
                 id
             ],
@@ -332,8 +343,9 @@ pub fn update_hook(
         )
         .unwrap_or(false);
 
+    let mut pending_changes = Vec::new();
     if is_global {
-        sync_global_hooks(&db_guard)?;
+        pending_changes.extend(sync_global_hooks(&db_guard)?);
     }
 
     // Sync project hooks for all projects that have this hook
@@ -349,7 +361,7 @@ pub fn update_hook(
         .collect();
 
     for path in project_paths {
-        sync_project_hooks(&db_guard, &path)?;
+        pending_changes.extend(sync_project_hooks(&db_guard, &path)?);
     }
 
     let mut stmt = db_guard
@@ -360,11 +372,20 @@ pub fn update_hook(
         ))
         .map_err(|e| e.to_string())?;
 
-    stmt.query_row([id], row_to_hook).map_err(|e| e.to_string())
+    let hook = stmt
+        .query_row([id], row_to_hook)
+        .map_err(|e| e.to_string())?;
+    Ok(WithPendingChanges {
+        item: hook,
+        pending_changes,
+    })
 }
 
 #[tauri::command]
-pub fn delete_hook(db: State<'_, Arc<Mutex<Database>>>, id: i64) -> Result<(), String> {
+pub fn delete_hook(
+    db: State<'_, Arc<Mutex<Database>>>,
+    id: i64,
+) -> Result<Vec<PendingChange>, String> {
     let db_guard = db.lock().map_err(|e| e.to_string())?;
 
     // Check if hook is assigned globally
@@ -396,16 +417,17 @@ pub fn delete_hook(db: State<'_, Arc<Mutex<Database>>>, id: i64) -> Result<(), S
         .map_err(|e| e.to_string())?;
 
     // Sync global hooks if it was assigned globally
+    let mut pending_changes = Vec::new();
     if is_global {
-        sync_global_hooks(&db_guard)?;
+        pending_changes.extend(sync_global_hooks(&db_guard)?);
     }
 
     // Sync project hooks for affected projects
     for path in project_paths {
-        sync_project_hooks(&db_guard, &path)?;
+        pending_changes.extend(sync_project_hooks(&db_guard, &path)?);
     }
 
-    Ok(())
+    Ok(pending_changes)
 }
 
 // Global Hooks
@@ -449,7 +471,10 @@ pub fn get_global_hooks(db: State<'_, Arc<Mutex<Database>>>) -> Result<Vec<Globa
 }
 
 #[tauri::command]
-pub fn add_global_hook(db: State<'_, Arc<Mutex<Database>>>, hook_id: i64) -> Result<(), String> {
+pub fn add_global_hook(
+    db: State<'_, Arc<Mutex<Database>>>,
+    hook_id: i64,
+) -> Result<Option<PendingChange>, String> {
     info!("[Hooks] Adding global hook id={}", hook_id);
     let db_guard = db.lock().map_err(|e| e.to_string())?;
 
@@ -465,7 +490,10 @@ pub fn add_global_hook(db: State<'_, Arc<Mutex<Database>>>, hook_id: i64) -> Res
 }
 
 #[tauri::command]
-pub fn remove_global_hook(db: State<'_, Arc<Mutex<Database>>>, hook_id: i64) -> Result<(), String> {
+pub fn remove_global_hook(
+    db: State<'_, Arc<Mutex<Database>>>,
+    hook_id: i64,
+) -> Result<Option<PendingChange>, String> {
     let db_guard = db.lock().map_err(|e| e.to_string())?;
 
     db_guard
@@ -481,7 +509,7 @@ pub fn toggle_global_hook(
     db: State<'_, Arc<Mutex<Database>>>,
     id: i64,
     enabled: bool,
-) -> Result<(), String> {
+) -> Result<Option<PendingChange>, String> {
     let db_guard = db.lock().map_err(|e| e.to_string())?;
 
     db_guard
@@ -546,7 +574,7 @@ pub fn assign_hook_to_project(
     db: State<'_, Arc<Mutex<Database>>>,
     project_id: i64,
     hook_id: i64,
-) -> Result<(), String> {
+) -> Result<Option<PendingChange>, String> {
     let db_guard = db.lock().map_err(|e| e.to_string())?;
 
     // Get project path
@@ -575,7 +603,7 @@ pub fn remove_hook_from_project(
     db: State<'_, Arc<Mutex<Database>>>,
     project_id: i64,
     hook_id: i64,
-) -> Result<(), String> {
+) -> Result<Option<PendingChange>, String> {
     let db_guard = db.lock().map_err(|e| e.to_string())?;
 
     // Get project path
@@ -604,7 +632,7 @@ pub fn toggle_project_hook(
     db: State<'_, Arc<Mutex<Database>>>,
     assignment_id: i64,
     enabled: bool,
-) -> Result<(), String> {
+) -> Result<Option<PendingChange>, String> {
     let db_guard = db.lock().map_err(|e| e.to_string())?;
 
     db_guard
@@ -756,6 +784,35 @@ pub fn export_hooks_to_json(
     serde_json::to_string_pretty(&export).map_err(|e| e.to_string())
 }
 
+/// Export the selected hooks as a `{"hooks": {...}}` fragment that can be
+/// pasted straight into a settings.json
+#[tauri::command]
+pub fn export_hooks_as_settings(
+    db: State<'_, Arc<Mutex<Database>>>,
+    hook_ids: Vec<i64>,
+) -> Result<String, String> {
+    info!("[Hooks] Exporting {} hooks as settings", hook_ids.len());
+    let db = db.lock().map_err(|e| e.to_string())?;
+    export_hooks_as_settings_from_db(&db, &hook_ids)
+}
+
+/// Export the selected hooks as a Claude Code plugin (`.claude-plugin/plugin.json`
+/// plus `hooks/hooks.json`) in `output_dir`
+#[tauri::command]
+pub fn export_hooks_as_plugin(
+    db: State<'_, Arc<Mutex<Database>>>,
+    hook_ids: Vec<i64>,
+    output_dir: String,
+) -> Result<String, String> {
+    info!(
+        "[Hooks] Exporting {} hooks as plugin to {}",
+        hook_ids.len(),
+        output_dir
+    );
+    let db = db.lock().map_err(|e| e.to_string())?;
+    export_hooks_as_plugin_from_db(&db, &hook_ids, Path::new(&output_dir))
+}
+
 /// Create sound notification hooks for common events
 #[tauri::command]
 pub fn create_sound_notification_hooks(
@@ -763,7 +820,7 @@ pub fn create_sound_notification_hooks(
     events: Vec<String>,
     sound_path: String,
     method: String,
-) -> Result<Vec<Hook>, String> {
+) -> Result<WithPendingChanges<Vec<Hook>>, String> {
     use crate::services::sound_player;
 
     info!(
@@ -834,15 +891,19 @@ pub fn create_sound_notification_hooks(
     }
 
     // Sync global hooks to settings.json
+    let mut pending_changes = Vec::new();
     if !created_hooks.is_empty() {
-        sync_global_hooks(&db_guard)?;
+        pending_changes.extend(sync_global_hooks(&db_guard)?);
     }
 
     info!(
         "[Hooks] Created {} sound notification hooks",
         created_hooks.len()
     );
-    Ok(created_hooks)
+    Ok(WithPendingChanges {
+        item: created_hooks,
+        pending_changes,
+    })
 }
 
 /// Duplicate a hook with a new name
@@ -875,8 +936,8 @@ pub fn duplicate_hook(
     db_guard
         .conn()
         .execute(
-            "INSERT INTO hooks (name, description, event_type, matcher, hook_type, command, prompt, timeout, tags, source, is_template)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, 'manual', 0)",
+            "INSERT INTO hooks (name, description, event_type, matcher, hook_type, command, prompt, timeout, tags, path_pattern, source, is_template)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'manual', 0)",
             params![
                 new_name,
                 original.description,
@@ -886,7 +947,8 @@ pub fn duplicate_hook(
                 original.command,
                 original.prompt,
                 original.timeout,
-                original.tags.as_ref().map(|t| serde_json::to_string(t).unwrap())
+                original.tags.as_ref().map(|t| serde_json::to_string(t).unwrap()),
+                original.path_pattern
             ],
         )
         .map_err(|e| e.to_string())?;
@@ -912,6 +974,7 @@ pub fn duplicate_hook(
 /// Create a hook in the database (no file sync)
 #[cfg_attr(not(test), allow(dead_code))]
 pub(crate) fn create_hook_in_db(db: &Database, hook: &CreateHookRequest) -> Result<Hook, String> {
+    hook_writer::validate_hook(hook)?;
     let tags_json = hook
         .tags
         .as_ref()
@@ -919,8 +982,8 @@ pub(crate) fn create_hook_in_db(db: &Database, hook: &CreateHookRequest) -> Resu
 
     db.conn()
         .execute(
-            "INSERT INTO hooks (name, description, event_type, matcher, hook_type, command, prompt, timeout, tags, source)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, 'manual')",
+            "INSERT INTO hooks (name, description, event_type, matcher, hook_type, command, prompt, timeout, tags, path_pattern, source)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'manual')",
             params![
                 hook.name,
                 hook.description,
@@ -930,7 +993,8 @@ pub(crate) fn create_hook_in_db(db: &Database, hook: &CreateHookRequest) -> Resu
                 hook.command,
                 hook.prompt,
                 hook.timeout,
-                tags_json
+                tags_json,
+                hook.path_pattern
             ],
         )
         .map_err(|e| e.to_string())?;
@@ -940,7 +1004,6 @@ pub(crate) fn create_hook_in_db(db: &Database, hook: &CreateHookRequest) -> Resu
 }
 
 /// Get a hook by ID from the database
-#[cfg_attr(not(test), allow(dead_code))]
 pub(crate) fn get_hook_by_id(db: &Database, id: i64) -> Result<Hook, String> {
     let mut stmt = db
         .conn()
@@ -953,6 +1016,58 @@ pub(crate) fn get_hook_by_id(db: &Database, id: i64) -> Result<Hook, String> {
     stmt.query_row([id], row_to_hook).map_err(|e| e.to_string())
 }
 
+/// Assemble hooks into a settings.json fragment, failing on the first hook
+/// that is missing or would not be accepted by Claude Code
+pub(crate) fn export_hooks_as_settings_from_db(
+    db: &Database,
+    hook_ids: &[i64],
+) -> Result<String, String> {
+    let hooks = hook_ids
+        .iter()
+        .map(|&id| {
+            let hook = get_hook_by_id(db, id).map_err(|_| format!("Hook {} not found", id))?;
+            hook_writer::validate_hook_for_export(&hook)?;
+            Ok(hook)
+        })
+        .collect::<Result<Vec<Hook>, String>>()?;
+
+    let export = hook_writer::hooks_to_settings_format(&hooks);
+    serde_json::to_string_pretty(&export).map_err(|e| e.to_string())
+}
+
+/// Write the selected hooks as a Claude Code plugin under `output_dir`,
+/// named after the directory. Returns the plugin root.
+pub(crate) fn export_hooks_as_plugin_from_db(
+    db: &Database,
+    hook_ids: &[i64],
+    output_dir: &Path,
+) -> Result<String, String> {
+    if hook_ids.is_empty() {
+        return Err("Select at least one hook to export".to_string());
+    }
+    let hooks = hook_ids
+        .iter()
+        .map(|&id| {
+            let hook = get_hook_by_id(db, id).map_err(|_| format!("Hook {} not found", id))?;
+            hook_writer::validate_hook_for_export(&hook)?;
+            Ok(hook)
+        })
+        .collect::<Result<Vec<Hook>, String>>()?;
+
+    let plugin_name = output_dir
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| format!("Invalid output directory: {}", output_dir.display()))?;
+    let description = format!(
+        "{} hooks exported from Claude Code Tool Manager",
+        hooks.len()
+    );
+
+    plugin_hooks::write_hooks_plugin(output_dir, plugin_name, Some(&description), &hooks)
+        .map(|root| root.to_string_lossy().to_string())
+        .map_err(|e| e.to_string())
+}
+
 /// Get all hooks from the database (excludes templates)
 pub(crate) fn get_all_hooks_from_db(db: &Database) -> Result<Vec<Hook>, String> {
     let mut stmt = db
@@ -979,6 +1094,7 @@ pub(crate) fn update_hook_in_db(
     id: i64,
     hook: &CreateHookRequest,
 ) -> Result<Hook, String> {
+    hook_writer::validate_hook(hook)?;
     let tags_json = hook
         .tags
         .as_ref()
@@ -994,7 +1110,7 @@ pub(crate) fn update_hook_in_db(
 
     db.conn()
         .execute(
-            "UPDATE hooks SET name = ?, description = ?, event_type = ?, matcher = ?, hook_type = ?, command = ?, prompt = ?, timeout = ?, url = ?, headers = ?, allowed_env_vars = ?, if_condition = ?, status_message = ?, once = ?, async_mode = ?, shell = ?, tags = ?, updated_at = CURRENT_TIMESTAMP
+            "UPDATE hooks SET name = ?, description = ?, event_type = ?, matcher = ?, hook_type = ?, command = ?, prompt = ?, timeout = ?, url = ?, headers = ?, allowed_env_vars = ?, if_condition = ?, status_message = ?, once = ?, async_mode = ?, shell = ?, path_pattern = ?, tags = ?, updated_at = CURRENT_TIMESTAMP
              WHERE id = ?",
             params![
                 hook.name,
@@ -1013,6 +1129,7 @@ pub(crate) fn update_hook_in_db(
                 hook.once.unwrap_or(false) as i32,
                 hook.async_mode.unwrap_or(false) as i32,
                 hook.shell,
+                hook.path_pattern,
                 tags_json,
                 id
             ],
@@ -1128,6 +1245,7 @@ mod tests {
             once: None,
             async_mode: None,
             shell: None,
+            path_pattern: None,
         };
 
         let created = create_hook_in_db(&db, &hook).unwrap();
@@ -1170,6 +1288,7 @@ mod tests {
             once: None,
             async_mode: None,
             shell: None,
+            path_pattern: None,
         };
 
         let created = create_hook_in_db(&db, &hook).unwrap();
@@ -1205,6 +1324,7 @@ mod tests {
             once: None,
             async_mode: None,
             shell: None,
+            path_pattern: None,
         };
 
         let created = create_hook_in_db(&db, &hook).unwrap();
@@ -1247,6 +1367,7 @@ mod tests {
                 once: None,
                 async_mode: None,
                 shell: None,
+                path_pattern: None,
             };
             create_hook_in_db(&db, &hook).unwrap();
         }
@@ -1283,6 +1404,7 @@ mod tests {
             once: None,
             async_mode: None,
             shell: None,
+            path_pattern: None,
         };
         create_hook_in_db(&db, &hook).unwrap();
 
@@ -1323,6 +1445,7 @@ mod tests {
             once: None,
             async_mode: None,
             shell: None,
+            path_pattern: None,
         };
 
         let created = create_hook_in_db(&db, &hook).unwrap();
@@ -1345,6 +1468,7 @@ mod tests {
             once: None,
             async_mode: None,
             shell: None,
+            path_pattern: None,
         };
 
         let updated = update_hook_in_db(&db, created.id, &update).unwrap();
@@ -1384,6 +1508,7 @@ mod tests {
             once: None,
             async_mode: None,
             shell: None,
+            path_pattern: None,
         };
 
         let created = create_hook_in_db(&db, &hook).unwrap();
@@ -1419,6 +1544,7 @@ mod tests {
             once: None,
             async_mode: None,
             shell: None,
+            path_pattern: None,
         };
 
         let created = create_hook_in_db(&db, &hook).unwrap();
@@ -1453,6 +1579,7 @@ mod tests {
             once: None,
             async_mode: None,
             shell: None,
+            path_pattern: None,
         };
 
         let created = create_hook_in_db(&db, &hook).unwrap();
@@ -1494,6 +1621,7 @@ mod tests {
             once: None,
             async_mode: None,
             shell: None,
+            path_pattern: None,
         };
 
         let created = create_hook_in_db(&db, &hook).unwrap();
@@ -1537,6 +1665,7 @@ mod tests {
             once: None,
             async_mode: None,
             shell: None,
+            path_pattern: None,
         };
 
         let created = create_hook_in_db(&db, &hook).unwrap();
@@ -1579,6 +1708,7 @@ mod tests {
                 once: None,
                 async_mode: None,
                 shell: None,
+                path_pattern: None,
             };
 
             let created = create_hook_in_db(&db, &hook).unwrap();
@@ -1608,6 +1738,7 @@ mod tests {
             once: None,
             async_mode: None,
             shell: None,
+            path_pattern: None,
         };
 
         let created = create_hook_in_db(&db, &hook).unwrap();
@@ -1636,10 +1767,98 @@ mod tests {
             once: None,
             async_mode: None,
             shell: None,
+            path_pattern: None,
         };
 
         let created = create_hook_in_db(&db, &hook).unwrap();
         // Empty vec serializes to "[]" which deserializes back to Some(vec![])
         assert_eq!(created.tags, Some(vec![]));
     }
+
+    // =========================================================================
+    // export_hooks_as_settings tests
+    // =========================================================================
+
+    fn export_test_hook(name: &str, hook_type: &str, matcher: Option<&str>) -> CreateHookRequest {
+        CreateHookRequest {
+            name: name.to_string(),
+            description: None,
+            event_type: "PreToolUse".to_string(),
+            matcher: matcher.map(|m| m.to_string()),
+            hook_type: hook_type.to_string(),
+            command: (hook_type == "command").then(|| format!("echo {}", name)),
+            prompt: (hook_type == "prompt").then(|| format!("Check {}", name)),
+            timeout: Some(45),
+            tags: None,
+            url: None,
+            headers: None,
+            allowed_env_vars: None,
+            if_condition: None,
+            status_message: None,
+            once: None,
+            async_mode: None,
+            shell: None,
+            path_pattern: None,
+        }
+    }
+
+    #[test]
+    fn test_export_hooks_as_settings_round_trips_through_parser() {
+        let db = Database::in_memory().unwrap();
+        let a = create_hook_in_db(&db, &export_test_hook("a", "command", Some("Bash"))).unwrap();
+        let b = create_hook_in_db(&db, &export_test_hook("b", "prompt", Some("Bash"))).unwrap();
+        let c = create_hook_in_db(&db, &export_test_hook("c", "command", None)).unwrap();
+
+        let exported = export_hooks_as_settings_from_db(&db, &[a.id, b.id, c.id]).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&exported).unwrap();
+        let entries = value["hooks"]["PreToolUse"].as_array().unwrap();
+        // Hooks sharing the "Bash" matcher are nested in one entry
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["hooks"].as_array().unwrap().len(), 2);
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let settings = dir.path().join("settings.json");
+        std::fs::write(&settings, &exported).unwrap();
+        let parsed = crate::services::scanner::parse_hooks_from_settings(&settings);
+
+        assert_eq!(parsed.len(), 3);
+        for (parsed, original) in parsed.iter().zip([&a, &b, &c]) {
+            assert_eq!(parsed.event_type, original.event_type);
+            assert_eq!(parsed.matcher, original.matcher);
+            assert_eq!(parsed.hook_type, original.hook_type);
+            assert_eq!(parsed.command, original.command);
+            assert_eq!(parsed.prompt, original.prompt);
+            assert_eq!(parsed.timeout, Some(45));
+        }
+    }
+
+    #[test]
+    fn test_export_hooks_as_settings_rejects_invalid_hook() {
+        let db = Database::in_memory().unwrap();
+        let mut req = export_test_hook("empty", "command", None);
+        req.command = Some("  ".to_string());
+        assert!(create_hook_in_db(&db, &req)
+            .unwrap_err()
+            .contains("has no command"));
+
+        // Hooks scanned from existing settings skip create-time validation
+        db.conn()
+            .execute(
+                "INSERT INTO hooks (name, event_type, hook_type, command, source)
+                 VALUES ('empty', 'PreToolUse', 'command', '  ', 'auto-detected')",
+                [],
+            )
+            .unwrap();
+        let id = db.conn().last_insert_rowid();
+
+        let err = export_hooks_as_settings_from_db(&db, &[id]).unwrap_err();
+        assert!(err.contains("has no command"));
+    }
+
+    #[test]
+    fn test_export_hooks_as_settings_missing_id() {
+        let db = Database::in_memory().unwrap();
+        let err = export_hooks_as_settings_from_db(&db, &[999]).unwrap_err();
+        assert_eq!(err, "Hook 999 not found");
+    }
 }
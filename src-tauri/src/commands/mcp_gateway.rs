@@ -2,12 +2,14 @@
 //!
 //! These commands allow the frontend to start/stop and configure the MCP Gateway.
 
-use crate::db::models::GatewayMcp;
+use crate::db::models::{GatewayMcp, GatewayToolFilter};
 use crate::db::Database;
 use crate::mcp_gateway::backend::BackendInfo;
-use crate::mcp_gateway::server::{GatewayServerConfig, GatewayServerStatus};
+use crate::mcp_gateway::server::{GatewayServerConfig, GatewayServerStatus, GATEWAY_MCP_NAME};
 use crate::mcp_gateway::GatewayServerState;
+use crate::services::tool_cache;
 use log::info;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::sync::{Arc, Mutex};
 use tauri::State;
@@ -41,6 +43,21 @@ pub(crate) fn persist_gateway_config_to_db(
         .map_err(|e| e.to_string())?;
     db.set_setting("gateway_auto_start", &config.auto_start.to_string())
         .map_err(|e| e.to_string())?;
+    db.set_setting(
+        "gateway_keep_alive_interval_secs",
+        &config.keep_alive_interval_secs.to_string(),
+    )
+    .map_err(|e| e.to_string())?;
+    db.set_setting(
+        "gateway_max_concurrent_connects",
+        &config.max_concurrent_connects.to_string(),
+    )
+    .map_err(|e| e.to_string())?;
+    db.set_setting(
+        "gateway_backend_timeout_secs",
+        &config.backend_timeout_secs.to_string(),
+    )
+    .map_err(|e| e.to_string())?;
     Ok(())
 }
 
@@ -184,6 +201,41 @@ pub fn set_gateway_mcp_auto_restart(
         .map_err(|e| e.to_string())
 }
 
+/// Get the tool allow/deny filter for a gateway MCP
+#[tauri::command]
+pub fn get_gateway_mcp_tool_filter(
+    db: State<'_, Arc<Mutex<Database>>>,
+    mcp_id: i64,
+) -> Result<Option<GatewayToolFilter>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    db.get_gateway_mcp_tool_filter(mcp_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Set (or clear) the tool allow/deny filter for a gateway MCP. Applies to
+/// a running backend immediately, without reconnecting.
+#[tauri::command]
+pub async fn set_gateway_mcp_tool_filter(
+    gateway_state: State<'_, Arc<GatewayServerState>>,
+    db: State<'_, Arc<Mutex<Database>>>,
+    mcp_id: i64,
+    filter: Option<GatewayToolFilter>,
+) -> Result<(), String> {
+    info!(
+        "[GatewayCmd] Setting tool filter for gateway MCP {}: {:?}",
+        mcp_id, filter
+    );
+    {
+        let db = db.lock().map_err(|e| e.to_string())?;
+        db.set_gateway_mcp_tool_filter(mcp_id, filter.as_ref())
+            .map_err(|e| e.to_string())?;
+    } // db lock is released here before the await
+
+    let mut backend_manager = gateway_state.backend_manager.lock().await;
+    backend_manager.set_tool_filter(mcp_id, filter);
+    Ok(())
+}
+
 /// Check if an MCP is in the gateway
 #[tauri::command]
 pub fn is_mcp_in_gateway(db: State<'_, Arc<Mutex<Database>>>, mcp_id: i64) -> Result<bool, String> {
@@ -191,6 +243,191 @@ pub fn is_mcp_in_gateway(db: State<'_, Arc<Mutex<Database>>>, mcp_id: i64) -> Re
     db.is_mcp_in_gateway(mcp_id).map_err(|e| e.to_string())
 }
 
+/// A gateway backend's place in the aggregated listing (0 comes first)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayBackendPriority {
+    pub mcp_id: i64,
+    pub mcp_name: String,
+    pub priority: i32,
+    pub is_enabled: bool,
+}
+
+/// Get gateway backends in priority order
+#[tauri::command]
+pub fn get_gateway_backend_priority(
+    db: State<'_, Arc<Mutex<Database>>>,
+) -> Result<Vec<GatewayBackendPriority>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    get_gateway_backend_priority_from_db(&db)
+}
+
+/// Reorder gateway backends so `mcp_ids` are listed first, in that order.
+/// A running gateway picks up the new order immediately.
+#[tauri::command]
+pub async fn set_gateway_backend_priority(
+    gateway_state: State<'_, Arc<GatewayServerState>>,
+    db: State<'_, Arc<Mutex<Database>>>,
+    mcp_ids: Vec<i64>,
+) -> Result<Vec<GatewayBackendPriority>, String> {
+    info!("[GatewayCmd] Setting backend priority: {:?}", mcp_ids);
+    let priority = {
+        let db = db.lock().map_err(|e| e.to_string())?;
+        set_gateway_backend_priority_in_db(&db, &mcp_ids)?
+    }; // db lock is released here before the await
+
+    let order: Vec<i64> = priority.iter().map(|p| p.mcp_id).collect();
+    let mut backend_manager = gateway_state.backend_manager.lock().await;
+    backend_manager.set_backend_priority(&order);
+    Ok(priority)
+}
+
+pub(crate) fn get_gateway_backend_priority_from_db(
+    db: &Database,
+) -> Result<Vec<GatewayBackendPriority>, String> {
+    Ok(db
+        .get_gateway_mcps()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|gm| GatewayBackendPriority {
+            mcp_id: gm.mcp_id,
+            mcp_name: gm.mcp.name,
+            priority: gm.display_order,
+            is_enabled: gm.is_enabled,
+        })
+        .collect())
+}
+
+pub(crate) fn set_gateway_backend_priority_in_db(
+    db: &Database,
+    mcp_ids: &[i64],
+) -> Result<Vec<GatewayBackendPriority>, String> {
+    let current = get_gateway_backend_priority_from_db(db)?;
+    for (i, mcp_id) in mcp_ids.iter().enumerate() {
+        if !current.iter().any(|p| p.mcp_id == *mcp_id) {
+            return Err(format!("MCP {} is not in the gateway", mcp_id));
+        }
+        if mcp_ids[..i].contains(mcp_id) {
+            return Err(format!("MCP {} is listed more than once", mcp_id));
+        }
+    }
+    db.set_gateway_backend_priority(mcp_ids)
+        .map_err(|e| e.to_string())?;
+    get_gateway_backend_priority_from_db(db)
+}
+
+/// A gateway backend that is also enabled directly, so its tools are
+/// registered twice
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayOverlap {
+    pub mcp_id: i64,
+    pub mcp_name: String,
+    /// "global" or "project"
+    pub direct_scope: String,
+    /// Tools exposed through both paths (after the gateway tool filter)
+    pub tools: Vec<String>,
+    /// False when the MCP's tools have never been cached, so `tools` is empty
+    /// even though everything it exposes is duplicated
+    pub tools_known: bool,
+}
+
+/// Report MCPs whose tools reach Claude both directly and through the
+/// gateway, for the given project (plus global MCPs) or globally only
+#[tauri::command]
+pub fn detect_gateway_overlaps(
+    db: State<'_, Arc<Mutex<Database>>>,
+    project_id: Option<i64>,
+) -> Result<Vec<GatewayOverlap>, String> {
+    info!(
+        "[GatewayCmd] Detecting gateway overlaps for project {:?}",
+        project_id
+    );
+    let db = db.lock().map_err(|e| e.to_string())?;
+    detect_gateway_overlaps_in_db(&db, project_id)
+}
+
+/// Directly enabled MCP ids in scope, paired with the scope they come from
+fn directly_enabled_mcps(
+    db: &Database,
+    project_id: Option<i64>,
+) -> Result<Vec<(i64, &'static str)>, String> {
+    let mut enabled: Vec<(i64, &'static str)> = Vec::new();
+    let mut stmt = db
+        .conn()
+        .prepare("SELECT mcp_id FROM global_mcps WHERE is_enabled = 1")
+        .map_err(|e| e.to_string())?;
+    let global = stmt
+        .query_map([], |row| row.get::<_, i64>(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok());
+    enabled.extend(global.map(|id| (id, "global")));
+
+    if let Some(project_id) = project_id {
+        let mut stmt = db
+            .conn()
+            .prepare("SELECT mcp_id FROM project_mcps WHERE project_id = ? AND is_enabled = 1")
+            .map_err(|e| e.to_string())?;
+        let project = stmt
+            .query_map([project_id], |row| row.get::<_, i64>(0))
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok());
+        for id in project {
+            if !enabled.iter().any(|(existing, _)| *existing == id) {
+                enabled.push((id, "project"));
+            }
+        }
+    }
+    Ok(enabled)
+}
+
+pub(crate) fn detect_gateway_overlaps_in_db(
+    db: &Database,
+    project_id: Option<i64>,
+) -> Result<Vec<GatewayOverlap>, String> {
+    let gateway_mcp_id = match db
+        .get_mcp_by_name(GATEWAY_MCP_NAME)
+        .map_err(|e| e.to_string())?
+    {
+        Some(mcp) if mcp.source == "system" => mcp.id,
+        _ => return Ok(Vec::new()),
+    };
+
+    let direct = directly_enabled_mcps(db, project_id)?;
+    if !direct.iter().any(|(id, _)| *id == gateway_mcp_id) {
+        // The gateway isn't reachable in this scope, so nothing is duplicated
+        return Ok(Vec::new());
+    }
+
+    let mut overlaps = Vec::new();
+    for backend in db.get_enabled_gateway_mcps().map_err(|e| e.to_string())? {
+        let Some((_, scope)) = direct.iter().find(|(id, _)| *id == backend.mcp_id) else {
+            continue;
+        };
+        let cached =
+            tool_cache::get_cached_mcp_tools(db, backend.mcp_id).map_err(|e| e.to_string())?;
+        let tools_known = !cached.is_empty();
+        let tools = cached
+            .into_iter()
+            .map(|t| t.name)
+            .filter(|name| {
+                backend
+                    .tool_filter
+                    .as_ref()
+                    .is_none_or(|filter| filter.allows(name))
+            })
+            .collect();
+        overlaps.push(GatewayOverlap {
+            mcp_id: backend.mcp_id,
+            mcp_name: backend.mcp.name,
+            direct_scope: scope.to_string(),
+            tools,
+            tools_known,
+        });
+    }
+    Ok(overlaps)
+}
+
 /// Get backend connection info
 #[tauri::command]
 pub async fn get_gateway_backends(
@@ -221,6 +458,7 @@ mod tests {
             enabled: true,
             port: 8080,
             auto_start: false,
+            ..Default::default()
         };
         let json = serde_json::to_string(&config).unwrap();
         let deser: GatewayServerConfig = serde_json::from_str(&json).unwrap();
@@ -243,6 +481,7 @@ mod tests {
             enabled: true,
             port: 9999,
             auto_start: true,
+            ..Default::default()
         };
         let json = serde_json::to_string(&config).unwrap();
         let deser: GatewayServerConfig = serde_json::from_str(&json).unwrap();
@@ -260,6 +499,7 @@ mod tests {
             enabled: true,
             port: 8080,
             auto_start: true,
+            ..Default::default()
         };
         let json = serde_json::to_string(&config).unwrap();
         assert!(json.contains("autoStart"));
@@ -287,6 +527,7 @@ mod tests {
             enabled: true,
             port: 8080,
             auto_start: false,
+            ..Default::default()
         };
 
         persist_gateway_config_to_db(&db, &config).unwrap();
@@ -307,6 +548,7 @@ mod tests {
             enabled: false,
             port: 3000,
             auto_start: true,
+            ..Default::default()
         };
         persist_gateway_config_to_db(&db, &config1).unwrap();
 
@@ -314,6 +556,7 @@ mod tests {
             enabled: true,
             port: 9090,
             auto_start: false,
+            ..Default::default()
         };
         persist_gateway_config_to_db(&db, &config2).unwrap();
 
@@ -344,11 +587,18 @@ mod tests {
                 is_favorite: false,
                 created_at: "2024-01-01".to_string(),
                 updated_at: "2024-01-01".to_string(),
+                testable: true,
+                env_file: None,
+                display_name: None,
+                homepage: None,
+                inherit_env: true,
+                shell: None,
             },
             is_enabled: true,
             auto_restart: false,
             display_order: 0,
             created_at: "2024-01-01".to_string(),
+            tool_filter: None,
         };
         let json = serde_json::to_string(&gw_mcp).unwrap();
         assert!(json.contains("mcpId"));
@@ -359,4 +609,115 @@ mod tests {
         assert!(deser.is_enabled);
         assert!(!deser.auto_restart);
     }
+
+    #[test]
+    fn test_detect_gateway_overlaps_in_db() {
+        use crate::db::models::{CreateMcpRequest, GatewayToolFilterMode};
+        use crate::services::mcp_client::McpTool;
+
+        let db = Database::in_memory().unwrap();
+        let create = |name: &str| {
+            db.create_mcp(&CreateMcpRequest {
+                name: name.to_string(),
+                description: None,
+                mcp_type: "stdio".to_string(),
+                command: Some("npx".to_string()),
+                args: None,
+                url: None,
+                headers: None,
+                env: None,
+                icon: None,
+                tags: None,
+                testable: None,
+                env_file: None,
+                display_name: None,
+                inherit_env: None,
+                shell: None,
+                split_command: None,
+            })
+            .unwrap()
+            .id
+        };
+        let fs = create("fs");
+        let gh = create("github");
+        let gateway = db
+            .create_system_mcp(&crate::mcp_gateway::server::generate_gateway_mcp_entry(
+                23848,
+            ))
+            .unwrap()
+            .id;
+        db.add_gateway_mcp(fs).unwrap();
+        db.add_gateway_mcp(gh).unwrap();
+        db.set_gateway_mcp_tool_filter(
+            fs,
+            Some(&GatewayToolFilter {
+                mode: GatewayToolFilterMode::Deny,
+                tools: vec!["delete_file".to_string()],
+            }),
+        )
+        .unwrap();
+        let tool = |name: &str| McpTool {
+            name: name.to_string(),
+            description: None,
+            input_schema: None,
+        };
+        tool_cache::cache_mcp_tools(&db, fs, &[tool("read_file"), tool("delete_file")]).unwrap();
+
+        // fs is enabled directly, but the gateway isn't enabled yet
+        crate::commands::config::add_global_mcp_in_db(&db, fs).unwrap();
+        assert!(detect_gateway_overlaps_in_db(&db, None).unwrap().is_empty());
+
+        crate::commands::config::add_global_mcp_in_db(&db, gateway).unwrap();
+        let overlaps = detect_gateway_overlaps_in_db(&db, None).unwrap();
+        assert_eq!(overlaps.len(), 1);
+        assert_eq!(overlaps[0].mcp_name, "fs");
+        assert_eq!(overlaps[0].direct_scope, "global");
+        assert_eq!(overlaps[0].tools, vec!["read_file".to_string()]);
+        assert!(overlaps[0].tools_known);
+    }
+
+    #[test]
+    fn test_set_gateway_backend_priority_in_db_validates_ids() {
+        use crate::db::models::CreateMcpRequest;
+
+        let db = Database::in_memory().unwrap();
+        let mut ids = Vec::new();
+        for name in ["primary", "secondary"] {
+            let mcp = db
+                .create_mcp(&CreateMcpRequest {
+                    name: name.to_string(),
+                    description: None,
+                    mcp_type: "stdio".to_string(),
+                    command: Some("npx".to_string()),
+                    args: None,
+                    url: None,
+                    headers: None,
+                    env: None,
+                    icon: None,
+                    tags: None,
+                    testable: None,
+                    env_file: None,
+                    display_name: None,
+                    inherit_env: None,
+                    shell: None,
+                    split_command: None,
+                })
+                .unwrap();
+            db.add_gateway_mcp(mcp.id).unwrap();
+            ids.push(mcp.id);
+        }
+
+        let priority = set_gateway_backend_priority_in_db(&db, &[ids[1]]).unwrap();
+        let names: Vec<&str> = priority.iter().map(|p| p.mcp_name.as_str()).collect();
+        assert_eq!(names, vec!["secondary", "primary"]);
+        assert_eq!(priority[0].priority, 0);
+        assert_eq!(priority[1].priority, 1);
+
+        assert!(set_gateway_backend_priority_in_db(&db, &[999])
+            .unwrap_err()
+            .contains("not in the gateway"));
+        assert!(set_gateway_backend_priority_in_db(&db, &[ids[0], ids[0]])
+            .unwrap_err()
+            .contains("more than once"));
+    }
 }
@@ -0,0 +1,42 @@
+use crate::db::Database;
+use crate::services::mcp_oauth::{self, McpOAuthConfig, McpOAuthStatus};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// An MCP's OAuth settings, without the tokens themselves
+#[tauri::command]
+pub fn get_mcp_oauth(
+    db: State<'_, Arc<Mutex<Database>>>,
+    mcp_id: i64,
+) -> Result<Option<McpOAuthStatus>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    let config = mcp_oauth::get_oauth_config(&db, mcp_id).map_err(|e| e.to_string())?;
+    Ok(config.as_ref().map(McpOAuthStatus::from))
+}
+
+/// Store the token endpoint and refresh token for a remote MCP. An access
+/// token is fetched with them the next time the MCP is connected to.
+#[tauri::command]
+pub fn set_mcp_oauth(
+    db: State<'_, Arc<Mutex<Database>>>,
+    mcp_id: i64,
+    token_endpoint: String,
+    client_id: Option<String>,
+    refresh_token: String,
+) -> Result<(), String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    let config = McpOAuthConfig {
+        token_endpoint: token_endpoint.trim().to_string(),
+        client_id: client_id.filter(|id| !id.trim().is_empty()),
+        refresh_token,
+        access_token: None,
+        expires_at: None,
+    };
+    mcp_oauth::set_oauth_config(&db, mcp_id, &config).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn clear_mcp_oauth(db: State<'_, Arc<Mutex<Database>>>, mcp_id: i64) -> Result<(), String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    mcp_oauth::clear_oauth_config(&db, mcp_id).map_err(|e| e.to_string())
+}
@@ -106,8 +106,8 @@ pub fn import_mcp_from_registry_in_db(
 
     db.conn()
         .execute(
-            r#"INSERT INTO mcps (name, description, type, command, args, url, headers, env, source, source_path)
-               VALUES (?, ?, ?, ?, ?, ?, ?, ?, 'registry', ?)"#,
+            r#"INSERT INTO mcps (name, description, type, command, args, url, headers, env, source, source_path, homepage)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?, 'registry', ?, ?)"#,
             params![
                 entry.name,
                 entry.description,
@@ -117,6 +117,7 @@ pub fn import_mcp_from_registry_in_db(
                 entry.url,
                 headers_json,
                 env_json,
+                entry.source_url,
                 entry.source_url
             ],
         )
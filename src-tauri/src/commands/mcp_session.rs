@@ -3,12 +3,13 @@
 //! Tauri commands for managing MCP execution sessions.
 
 use crate::db::Database;
-use crate::services::mcp_client::{McpTool, ToolCallResult};
+use crate::services::mcp_client::{McpTool, SpawnOptions, ToolCallResult};
 use crate::services::mcp_session::{McpSessionManager, SessionInfo, StartSessionResult};
 use log::{error, info};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tauri::State;
 
 /// MCP session data extracted from the database
@@ -19,6 +20,9 @@ pub struct McpSessionData {
     pub command: Option<String>,
     pub args: Vec<String>,
     pub env: Option<HashMap<String, String>>,
+    pub env_file: Option<String>,
+    pub inherit_env: bool,
+    pub shell: Option<String>,
     pub url: Option<String>,
     pub headers: Option<HashMap<String, String>>,
     pub source: String,
@@ -33,7 +37,8 @@ pub(crate) fn get_mcp_session_data_from_db(
     let mut stmt = db
         .conn()
         .prepare(
-            "SELECT name, type, command, args, env, url, headers, source FROM mcps WHERE id = ?",
+            "SELECT name, type, command, args, env, url, headers, source, COALESCE(inherit_env, 1), shell
+             FROM mcps WHERE id = ?",
         )
         .map_err(|e| e.to_string())?;
 
@@ -46,6 +51,8 @@ pub(crate) fn get_mcp_session_data_from_db(
         Option<String>,
         Option<String>,
         String,
+        bool,
+        Option<String>,
     ) = stmt
         .query_row([mcp_id], |row| {
             Ok((
@@ -57,11 +64,24 @@ pub(crate) fn get_mcp_session_data_from_db(
                 row.get(5)?,
                 row.get(6)?,
                 row.get(7)?,
+                row.get(8)?,
+                row.get(9)?,
             ))
         })
         .map_err(|e| format!("MCP not found: {}", e))?;
 
-    let (name, mcp_type, command, args_json, env_json, url, headers_json, source) = mcp_data;
+    let (
+        name,
+        mcp_type,
+        command,
+        args_json,
+        env_json,
+        url,
+        headers_json,
+        source,
+        inherit_env,
+        shell,
+    ) = mcp_data;
 
     let args: Vec<String> = args_json
         .and_then(|s| serde_json::from_str(&s).ok())
@@ -78,6 +98,9 @@ pub(crate) fn get_mcp_session_data_from_db(
         command,
         args,
         env,
+        env_file: crate::commands::mcp_test::get_mcp_env_file_from_db(db, mcp_id)?,
+        inherit_env,
+        shell,
         url,
         headers,
         source,
@@ -140,10 +163,15 @@ pub fn start_mcp_session(
         command,
         args,
         env,
+        env_file,
+        inherit_env,
+        shell,
         url,
         headers,
         source,
     } = data;
+    let headers =
+        crate::commands::mcp_test::remote_headers(&db, mcp_id, &mcp_type, &source, headers)?;
 
     // Start session based on MCP type
     let manager = session_manager.lock().map_err(|e| {
@@ -170,7 +198,19 @@ pub fn start_mcp_session(
         "stdio" => {
             let cmd = command.ok_or_else(|| "STDIO MCP requires a command".to_string())?;
             manager
-                .start_stdio_session(mcp_id, &name, &cmd, &args, env.as_ref(), 60)
+                .start_stdio_session(
+                    mcp_id,
+                    &name,
+                    &SpawnOptions {
+                        command: &cmd,
+                        args: &args,
+                        env: env.as_ref(),
+                        env_file: env_file.as_deref(),
+                        inherit_env,
+                        shell: shell.as_deref(),
+                    },
+                    60,
+                )
                 .map_err(|e| e.to_string())
         }
         "http" => {
@@ -185,17 +225,27 @@ pub fn start_mcp_session(
                 .start_sse_session(mcp_id, &name, &mcp_url, headers.as_ref(), 60)
                 .map_err(|e| e.to_string())
         }
+        "ws" => {
+            let mcp_url = url.ok_or_else(|| "WebSocket MCP requires a URL".to_string())?;
+            manager
+                .start_ws_session(mcp_id, &name, &mcp_url, headers.as_ref(), 60)
+                .map_err(|e| e.to_string())
+        }
         _ => Err(format!("Unknown MCP type: {}", mcp_type)),
     }
 }
 
-/// Execute a tool in an active session
+/// Execute a tool in an active session. Passing `cache_ttl_secs` opts this
+/// call into the result cache: an identical earlier call on the same MCP that
+/// succeeded within the TTL is returned instead of re-running the tool. Only
+/// use it for read-only tools.
 #[tauri::command]
 pub fn execute_tool(
     session_manager: State<'_, Mutex<McpSessionManager>>,
     session_id: String,
     tool_name: String,
     arguments: Value,
+    cache_ttl_secs: Option<u64>,
 ) -> Result<ToolCallResult, String> {
     info!(
         "[MCP Session] Executing tool '{}' in session {}",
@@ -210,9 +260,13 @@ pub fn execute_tool(
         e.to_string()
     })?;
 
-    manager
-        .call_tool(&session_id, &tool_name, arguments)
-        .map_err(|e| e.to_string())
+    match cache_ttl_secs.filter(|ttl| *ttl > 0) {
+        Some(ttl) => {
+            manager.call_tool_cached(&session_id, &tool_name, arguments, Duration::from_secs(ttl))
+        }
+        None => manager.call_tool(&session_id, &tool_name, arguments),
+    }
+    .map_err(|e| e.to_string())
 }
 
 /// End an MCP session
@@ -304,6 +358,23 @@ pub fn cleanup_idle_sessions(
     Ok(manager.cleanup_idle_sessions(idle_threshold))
 }
 
+/// Drop every tool result cached by `execute_tool` calls that opted in
+#[tauri::command]
+pub fn clear_tool_result_cache(
+    session_manager: State<'_, Mutex<McpSessionManager>>,
+) -> Result<(), String> {
+    let manager = session_manager.lock().map_err(|e| {
+        error!(
+            "[MCP Session] Failed to acquire session manager lock: {}",
+            e
+        );
+        e.to_string()
+    })?;
+
+    manager.clear_tool_result_cache();
+    Ok(())
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -338,6 +409,7 @@ mod tests {
             server_info: Some(McpServerInfo {
                 name: "test-server".to_string(),
                 version: Some("1.0.0".to_string()),
+                protocol_version: None,
             }),
             tool_count: 5,
             created_at: "10s".to_string(),
@@ -429,7 +501,10 @@ mod tests {
             content: vec![],
             is_error: false,
             error: None,
+            error_code: None,
+            error_data: None,
             execution_time_ms: 150,
+            cached: false,
         };
         let json = serde_json::to_string(&result).unwrap();
         assert!(json.contains("executionTimeMs"));
@@ -447,13 +522,22 @@ mod tests {
             content: vec![],
             is_error: true,
             error: Some("Command failed".to_string()),
+            error_code: Some(-32602),
+            error_data: Some(serde_json::json!({"field": "path"})),
             execution_time_ms: 50,
+            cached: false,
         };
         let json = serde_json::to_string(&result).unwrap();
+        assert!(json.contains("\"errorCode\":-32602"));
         let deserialized: ToolCallResult = serde_json::from_str(&json).unwrap();
         assert!(!deserialized.success);
         assert!(deserialized.is_error);
         assert_eq!(deserialized.error, Some("Command failed".to_string()));
+        assert_eq!(deserialized.error_code, Some(-32602));
+        assert_eq!(
+            deserialized.error_data,
+            Some(serde_json::json!({"field": "path"}))
+        );
     }
 
     #[test]
@@ -602,9 +686,12 @@ mod tests {
             command: Some("npx".to_string()),
             args: vec![],
             env: None,
+            env_file: None,
             url: None,
             headers: None,
             source: "manual".to_string(),
+            inherit_env: true,
+            shell: None,
         };
         assert!(validate_mcp_session_data(&data).is_ok());
     }
@@ -617,9 +704,12 @@ mod tests {
             command: None,
             args: vec![],
             env: None,
+            env_file: None,
             url: None,
             headers: None,
             source: "manual".to_string(),
+            inherit_env: true,
+            shell: None,
         };
         let result = validate_mcp_session_data(&data);
         assert!(result.is_err());
@@ -634,9 +724,12 @@ mod tests {
             command: None,
             args: vec![],
             env: None,
+            env_file: None,
             url: Some("https://example.com".to_string()),
             headers: None,
             source: "manual".to_string(),
+            inherit_env: true,
+            shell: None,
         };
         assert!(validate_mcp_session_data(&data).is_ok());
     }
@@ -649,9 +742,12 @@ mod tests {
             command: None,
             args: vec![],
             env: None,
+            env_file: None,
             url: None,
             headers: None,
             source: "manual".to_string(),
+            inherit_env: true,
+            shell: None,
         };
         assert!(validate_mcp_session_data(&data).is_err());
     }
@@ -664,9 +760,12 @@ mod tests {
             command: None,
             args: vec![],
             env: None,
+            env_file: None,
             url: None,
             headers: None,
             source: "manual".to_string(),
+            inherit_env: true,
+            shell: None,
         };
         assert!(validate_mcp_session_data(&data).is_err());
     }
@@ -679,9 +778,12 @@ mod tests {
             command: None,
             args: vec![],
             env: None,
+            env_file: None,
             url: Some("http://localhost:8080".to_string()),
             headers: None,
             source: "system".to_string(),
+            inherit_env: true,
+            shell: None,
         };
         assert!(validate_mcp_session_data(&data).is_ok());
     }
@@ -694,9 +796,12 @@ mod tests {
             command: None,
             args: vec![],
             env: None,
+            env_file: None,
             url: None,
             headers: None,
             source: "system".to_string(),
+            inherit_env: true,
+            shell: None,
         };
         assert!(validate_mcp_session_data(&data).is_err());
     }
@@ -709,9 +814,12 @@ mod tests {
             command: None,
             args: vec![],
             env: None,
+            env_file: None,
             url: None,
             headers: None,
             source: "manual".to_string(),
+            inherit_env: true,
+            shell: None,
         };
         let result = validate_mcp_session_data(&data);
         assert!(result.is_err());
@@ -3,11 +3,54 @@
 //! Tauri commands for testing MCP server connections.
 
 use crate::db::Database;
-use crate::services::mcp_client::{self, McpTestResult};
+use crate::services::mcp_client::{
+    self, HttpMcpClient, McpPrompt, McpPromptMessage, McpResource, McpTestResult, McpTool,
+    RetryPolicy, SpawnOptions, SseMcpClient, StdioFramingReport, StdioMcpClient,
+    StreamableHttpMcpClient, ToolContent, WebSocketMcpClient,
+};
+use crate::services::mcp_oauth;
+use crate::services::test_history::{self, McpLatencyStats};
+use crate::services::tool_cache::{self, CachedToolMatch};
 use log::{error, info};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use tauri::State;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// MCPs tested at once during a health check
+const HEALTH_CHECK_CONCURRENCY: usize = 4;
+
+/// MCPs tested at once by `test_all_mcps`
+const TEST_ALL_CONCURRENCY: usize = 8;
+
+/// How long a sweep waits for one MCP before reporting it as timed out. The
+/// transports give up after 30s on their own; this is the backstop for a
+/// server that hangs anyway.
+const SWEEP_TEST_DEADLINE: Duration = Duration::from_secs(45);
+
+/// Event emitted as each MCP finishes in `test_all_mcps`
+pub const MCP_TEST_ALL_PROGRESS_EVENT: &str = "mcp-test-all-progress";
+
+/// Outcome of one MCP in a health check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpHealthResult {
+    pub mcp_id: i64,
+    pub mcp_name: String,
+    pub result: McpTestResult,
+}
+
+/// Payload of [`MCP_TEST_ALL_PROGRESS_EVENT`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpTestProgress {
+    pub completed: usize,
+    pub total: usize,
+    pub result: McpHealthResult,
+}
 
 /// Extract MCP test data including source field from the database (no Tauri State dependency)
 #[allow(clippy::type_complexity)]
@@ -67,62 +110,751 @@ pub fn get_mcp_test_data_with_source_from_db(
     Ok((mcp_type, command, args, headers, env, url, source))
 }
 
+/// Headers for connecting to a remote MCP, with a bearer token from its
+/// OAuth settings (refreshed first if expired). Stdio MCPs are left alone.
+pub(crate) fn remote_headers(
+    db: &Mutex<Database>,
+    mcp_id: i64,
+    mcp_type: &str,
+    source: &str,
+    headers: Option<HashMap<String, String>>,
+) -> Result<Option<HashMap<String, String>>, String> {
+    if mcp_type == "stdio" && source != "system" {
+        return Ok(headers);
+    }
+    mcp_oauth::authorize_headers(db, mcp_id, headers).map_err(|e| e.to_string())
+}
+
+/// Whether an MCP may be spawned/connected to for testing
+pub(crate) fn is_mcp_testable_from_db(db: &Database, mcp_id: i64) -> Result<bool, String> {
+    db.conn()
+        .query_row(
+            "SELECT COALESCE(testable, 1) FROM mcps WHERE id = ?",
+            [mcp_id],
+            |row| row.get::<_, i32>(0),
+        )
+        .map(|v| v != 0)
+        .map_err(|e| format!("MCP not found: {}", e))
+}
+
+/// The `.env` file configured for an MCP, if any, resolved against the
+/// config the MCP came from
+pub(crate) fn get_mcp_env_file_from_db(
+    db: &Database,
+    mcp_id: i64,
+) -> Result<Option<String>, String> {
+    let (env_file, source_path): (Option<String>, Option<String>) = db
+        .conn()
+        .query_row(
+            "SELECT env_file, source_path FROM mcps WHERE id = ?",
+            [mcp_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| format!("MCP not found: {}", e))?;
+    Ok(env_file.map(|f| {
+        mcp_client::resolve_env_file(&f, source_path.as_deref(), None)
+            .to_string_lossy()
+            .into_owned()
+    }))
+}
+
+/// Whether an MCP's stdio process inherits the app's environment
+pub(crate) fn get_mcp_inherit_env_from_db(db: &Database, mcp_id: i64) -> Result<bool, String> {
+    db.conn()
+        .query_row(
+            "SELECT COALESCE(inherit_env, 1) FROM mcps WHERE id = ?",
+            [mcp_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("MCP not found: {}", e))
+}
+
+/// The shell an MCP's stdio process is launched through (`sh` when unset)
+pub(crate) fn get_mcp_shell_from_db(db: &Database, mcp_id: i64) -> Result<Option<String>, String> {
+    db.conn()
+        .query_row("SELECT shell FROM mcps WHERE id = ?", [mcp_id], |row| {
+            row.get(0)
+        })
+        .map_err(|e| format!("MCP not found: {}", e))
+}
+
 /// Test an MCP by its database ID
 #[tauri::command]
 pub fn test_mcp(db: State<'_, Arc<Mutex<Database>>>, mcp_id: i64) -> Result<McpTestResult, String> {
+    test_mcp_with_db(&db, mcp_id)
+}
+
+/// Test an MCP, holding the database lock only while reading its config and
+/// recording the result (history and the tools it reports)
+pub(crate) fn test_mcp_with_db(db: &Mutex<Database>, mcp_id: i64) -> Result<McpTestResult, String> {
     info!("[MCP Test] Testing MCP id={}", mcp_id);
 
     // Extract MCP data from database in a separate scope to release the lock
-    let (mcp_type, command, args, headers, env, url, source) = {
+    let (mcp_type, command, args, headers, env, url, source, env_file, inherit_env, shell) = {
         let db = db.lock().map_err(|e| {
             error!("[MCP Test] Failed to acquire database lock: {}", e);
             e.to_string()
         })?;
-        get_mcp_test_data_with_source_from_db(&db, mcp_id)?
+        if !is_mcp_testable_from_db(&db, mcp_id)? {
+            info!(
+                "[MCP Test] MCP id={} is marked as not testable, skipping",
+                mcp_id
+            );
+            return Ok(McpTestResult::not_testable());
+        }
+        let (mcp_type, command, args, headers, env, url, source) =
+            get_mcp_test_data_with_source_from_db(&db, mcp_id)?;
+        let env_file = get_mcp_env_file_from_db(&db, mcp_id)?;
+        let inherit_env = get_mcp_inherit_env_from_db(&db, mcp_id)?;
+        let shell = get_mcp_shell_from_db(&db, mcp_id)?;
+        (
+            mcp_type,
+            command,
+            args,
+            headers,
+            env,
+            url,
+            source,
+            env_file,
+            inherit_env,
+            shell,
+        )
     };
 
+    let headers = remote_headers(db, mcp_id, &mcp_type, &source, headers)?;
+
     // System MCPs (Tool Manager and Gateway) use Streamable HTTP which requires
     // async SSE handling for full protocol test
-    if source == "system" {
-        let mcp_url = url.ok_or_else(|| "System MCP requires a URL".to_string())?;
-        info!(
-            "[MCP Test] Testing system MCP with Streamable HTTP: {}",
-            mcp_url
-        );
-        return Ok(mcp_client::test_streamable_http_mcp(
-            &mcp_url,
-            headers.as_ref(),
-            30,
-        ));
-    }
-
     // Now the database lock is released, perform the test
     let result = match mcp_type.as_str() {
+        _ if source == "system" => {
+            let mcp_url = url.ok_or_else(|| "System MCP requires a URL".to_string())?;
+            info!(
+                "[MCP Test] Testing system MCP with Streamable HTTP: {}",
+                mcp_url
+            );
+            mcp_client::test_streamable_http_mcp(&mcp_url, headers.as_ref(), 30, None)
+        }
         "stdio" => {
             let cmd = command.ok_or_else(|| "STDIO MCP requires a command".to_string())?;
             info!("[MCP Test] Testing STDIO MCP: {} {:?}", cmd, args);
-            mcp_client::test_stdio_mcp(&cmd, &args, env.as_ref(), 30)
+            mcp_client::test_stdio_mcp(
+                &SpawnOptions {
+                    command: &cmd,
+                    args: &args,
+                    env: env.as_ref(),
+                    env_file: env_file.as_deref(),
+                    inherit_env,
+                    shell: shell.as_deref(),
+                },
+                30,
+            )
         }
         "http" => {
             let mcp_url = url.ok_or_else(|| "HTTP MCP requires a URL".to_string())?;
             info!("[MCP Test] Testing HTTP MCP: {}", mcp_url);
-            mcp_client::test_http_mcp(&mcp_url, headers.as_ref(), 30)
+            mcp_client::test_http_mcp(&mcp_url, headers.as_ref(), 30, None)
         }
         "sse" => {
             let mcp_url = url.ok_or_else(|| "SSE MCP requires a URL".to_string())?;
             info!("[MCP Test] Testing SSE MCP: {}", mcp_url);
-            mcp_client::test_sse_mcp(&mcp_url, headers.as_ref(), 30)
+            mcp_client::test_sse_mcp(&mcp_url, headers.as_ref(), 30, None)
+        }
+        "ws" => {
+            let mcp_url = url.ok_or_else(|| "WebSocket MCP requires a URL".to_string())?;
+            info!("[MCP Test] Testing WebSocket MCP: {}", mcp_url);
+            mcp_client::test_websocket_mcp(&mcp_url, headers.as_ref(), 30)
         }
         _ => {
             return Err(format!("Unknown MCP type: {}", mcp_type));
         }
     };
 
+    let db = db.lock().map_err(|e| e.to_string())?;
+    if let Err(e) = test_history::record_test_result(&db, mcp_id, &result) {
+        error!(
+            "[MCP Test] Failed to record history for MCP {}: {}",
+            mcp_id, e
+        );
+    }
+    if result.success {
+        if let Err(e) = tool_cache::cache_mcp_tools(&db, mcp_id, &result.tools) {
+            error!("[MCP Test] Failed to cache tools for MCP {}: {}", mcp_id, e);
+        }
+    }
+
     Ok(result)
 }
 
-/// Test an MCP configuration directly (for testing before saving)
+/// Re-discover one MCP's tools with a fresh handshake and return them.
+/// The cached tools are only replaced once the handshake succeeds, so a
+/// transient failure keeps the last good list. Server info and capabilities
+/// are not cached; every test reports them fresh.
+#[tauri::command]
+pub fn refresh_mcp_metadata(
+    db: State<'_, Arc<Mutex<Database>>>,
+    mcp_id: i64,
+) -> Result<Vec<McpTool>, String> {
+    refresh_mcp_metadata_with_db(&db, mcp_id)
+}
+
+pub(crate) fn refresh_mcp_metadata_with_db(
+    db: &Mutex<Database>,
+    mcp_id: i64,
+) -> Result<Vec<McpTool>, String> {
+    info!("[MCP Test] Refreshing metadata of MCP id={}", mcp_id);
+    let result = test_mcp_with_db(db, mcp_id)?;
+    if result.skipped {
+        return Err(format!("MCP {} is marked as not testable", mcp_id));
+    }
+    if !result.success {
+        return Err(format!(
+            "{}; the cached tools were kept",
+            result.error.as_deref().unwrap_or("Handshake failed")
+        ));
+    }
+
+    // Read back what was cached rather than trusting the write succeeded
+    let db = db.lock().map_err(|e| e.to_string())?;
+    let mut tools = tool_cache::get_cached_mcp_tools(&db, mcp_id).map_err(|e| e.to_string())?;
+    tool_cache::summarize_large_schemas(&mut tools, tool_cache::schema_summary_threshold(&db));
+    Ok(tools)
+}
+
+/// Test `mcp_ids` concurrently (at most `HEALTH_CHECK_CONCURRENCY` at a time),
+/// returning one result per id in the order given. Duplicate ids are tested
+/// once; unknown ids come back as failed results rather than aborting the rest.
+pub(crate) async fn run_health_checks(
+    db: Arc<Mutex<Database>>,
+    mcp_ids: Vec<i64>,
+) -> Vec<McpHealthResult> {
+    run_mcp_tests(
+        db,
+        mcp_ids,
+        HEALTH_CHECK_CONCURRENCY,
+        SWEEP_TEST_DEADLINE,
+        |_| {},
+    )
+    .await
+}
+
+/// Test `mcp_ids` on the blocking pool, at most `concurrency` at a time.
+/// Results come back in the order given; `on_progress` sees them in
+/// completion order. An MCP still running after `deadline` is reported as
+/// timed out so it can't hold up the sweep, but it keeps its slot until its
+/// own transport timeout ends it, so no more than `concurrency` servers (and
+/// their processes and sockets) are ever live at once.
+pub(crate) async fn run_mcp_tests(
+    db: Arc<Mutex<Database>>,
+    mcp_ids: Vec<i64>,
+    concurrency: usize,
+    deadline: Duration,
+    on_progress: impl Fn(&McpTestProgress),
+) -> Vec<McpHealthResult> {
+    let mut ids = Vec::with_capacity(mcp_ids.len());
+    for id in mcp_ids {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+
+    let total = ids.len();
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    let mut pending = JoinSet::new();
+    for (index, mcp_id) in ids.iter().copied().enumerate() {
+        let db = Arc::clone(&db);
+        let semaphore = Arc::clone(&semaphore);
+        pending.spawn(async move {
+            let permit = semaphore.acquire_owned().await;
+            let mcp_name = get_mcp_name_from_db(&db, mcp_id);
+            let test = tokio::task::spawn_blocking(move || {
+                let _permit = permit;
+                test_mcp_with_db(&db, mcp_id).unwrap_or_else(|e| McpTestResult::error(e, 0))
+            });
+            let result = match tokio::time::timeout(deadline, test).await {
+                Ok(Ok(result)) => result,
+                Ok(Err(e)) => McpTestResult::error(format!("Test panicked: {}", e), 0),
+                Err(_) => {
+                    error!(
+                        "[MCP Test] MCP id={} did not finish within {}s",
+                        mcp_id,
+                        deadline.as_secs()
+                    );
+                    McpTestResult::error(
+                        format!("Timed out after {}s", deadline.as_secs()),
+                        deadline.as_millis() as u64,
+                    )
+                }
+            };
+            (
+                index,
+                McpHealthResult {
+                    mcp_id,
+                    mcp_name,
+                    result,
+                },
+            )
+        });
+    }
+
+    let mut results: Vec<Option<McpHealthResult>> = vec![None; total];
+    let mut completed = 0;
+    while let Some(joined) = pending.join_next().await {
+        if let Ok((index, checked)) = joined {
+            completed += 1;
+            on_progress(&McpTestProgress {
+                completed,
+                total,
+                result: checked.clone(),
+            });
+            results[index] = Some(checked);
+        }
+    }
+    results.into_iter().flatten().collect()
+}
+
+fn get_mcp_name_from_db(db: &Mutex<Database>, mcp_id: i64) -> String {
+    db.lock()
+        .ok()
+        .and_then(|db| {
+            db.conn()
+                .query_row("SELECT name FROM mcps WHERE id = ?", [mcp_id], |row| {
+                    row.get(0)
+                })
+                .ok()
+        })
+        .unwrap_or_default()
+}
+
+/// Ids of every stored MCP
+pub(crate) fn get_all_mcp_ids_from_db(db: &Database) -> Result<Vec<i64>, String> {
+    let mut stmt = db
+        .conn()
+        .prepare("SELECT id FROM mcps ORDER BY name")
+        .map_err(|e| e.to_string())?;
+    let ids = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .collect::<rusqlite::Result<Vec<i64>>>()
+        .map_err(|e| e.to_string())?;
+    Ok(ids)
+}
+
+/// Test every stored MCP over its own transport, a few at a time, emitting
+/// [`MCP_TEST_ALL_PROGRESS_EVENT`] as each one finishes
+#[tauri::command]
+pub async fn test_all_mcps(
+    app: AppHandle,
+    db: State<'_, Arc<Mutex<Database>>>,
+) -> Result<Vec<(i64, McpTestResult)>, String> {
+    let ids = {
+        let db = db.lock().map_err(|e| e.to_string())?;
+        get_all_mcp_ids_from_db(&db)?
+    };
+    info!("[MCP Test] Testing all {} MCPs", ids.len());
+
+    let results = run_mcp_tests(
+        Arc::clone(&db),
+        ids,
+        TEST_ALL_CONCURRENCY,
+        SWEEP_TEST_DEADLINE,
+        |progress| {
+            let _ = app.emit(MCP_TEST_ALL_PROGRESS_EVENT, progress);
+        },
+    )
+    .await;
+    Ok(results
+        .into_iter()
+        .map(|checked| (checked.mcp_id, checked.result))
+        .collect())
+}
+
+/// Ids of the enabled global MCPs
+pub(crate) fn get_enabled_global_mcp_ids_from_db(db: &Database) -> Result<Vec<i64>, String> {
+    let mut stmt = db
+        .conn()
+        .prepare(
+            "SELECT gm.mcp_id FROM global_mcps gm JOIN mcps m ON m.id = gm.mcp_id
+             WHERE gm.is_enabled = 1 ORDER BY m.name",
+        )
+        .map_err(|e| e.to_string())?;
+    let ids = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .collect::<rusqlite::Result<Vec<i64>>>()
+        .map_err(|e| e.to_string())?;
+    Ok(ids)
+}
+
+/// Test every enabled global MCP
+#[tauri::command]
+pub async fn health_check_all_global(
+    db: State<'_, Arc<Mutex<Database>>>,
+) -> Result<Vec<McpHealthResult>, String> {
+    let ids = {
+        let db = db.lock().map_err(|e| e.to_string())?;
+        get_enabled_global_mcp_ids_from_db(&db)?
+    };
+    info!("[MCP Test] Health check of {} global MCPs", ids.len());
+    Ok(run_health_checks(Arc::clone(&db), ids).await)
+}
+
+/// Test just the given MCPs, e.g. to retry the ones that failed a sweep
+#[tauri::command]
+pub async fn health_check_mcps(
+    db: State<'_, Arc<Mutex<Database>>>,
+    mcp_ids: Vec<i64>,
+) -> Result<Vec<McpHealthResult>, String> {
+    info!("[MCP Test] Health check of {} MCPs", mcp_ids.len());
+    Ok(run_health_checks(Arc::clone(&db), mcp_ids).await)
+}
+
+/// Spawn a stdio MCP and report which of its stdout lines were JSON-RPC and
+/// which were noise that can break clients
+#[tauri::command]
+pub fn diagnose_stdio_framing(
+    db: State<'_, Arc<Mutex<Database>>>,
+    mcp_id: i64,
+) -> Result<StdioFramingReport, String> {
+    diagnose_stdio_framing_with_db(&db, mcp_id)
+}
+
+pub(crate) fn diagnose_stdio_framing_with_db(
+    db: &Mutex<Database>,
+    mcp_id: i64,
+) -> Result<StdioFramingReport, String> {
+    let (mcp_type, command, args, env, env_file, inherit_env, shell) = {
+        let db = db.lock().map_err(|e| e.to_string())?;
+        if !is_mcp_testable_from_db(&db, mcp_id)? {
+            return Err(format!("MCP {} is marked as not testable", mcp_id));
+        }
+        let (mcp_type, command, args, _, env, _, _) =
+            get_mcp_test_data_with_source_from_db(&db, mcp_id)?;
+        let env_file = get_mcp_env_file_from_db(&db, mcp_id)?;
+        let inherit_env = get_mcp_inherit_env_from_db(&db, mcp_id)?;
+        let shell = get_mcp_shell_from_db(&db, mcp_id)?;
+        (mcp_type, command, args, env, env_file, inherit_env, shell)
+    };
+    if mcp_type != "stdio" {
+        return Err(format!(
+            "Framing diagnostics only apply to stdio MCPs, not {}",
+            mcp_type
+        ));
+    }
+    let cmd = command.ok_or_else(|| "STDIO MCP requires a command".to_string())?;
+    info!("[MCP Test] Diagnosing stdout framing of MCP id={}", mcp_id);
+    Ok(mcp_client::diagnose_stdio_framing(
+        &SpawnOptions {
+            command: &cmd,
+            args: &args,
+            env: env.as_ref(),
+            env_file: env_file.as_deref(),
+            inherit_env,
+            shell: shell.as_deref(),
+        },
+        30,
+    ))
+}
+
+/// A one-shot connection to a stored MCP, used to browse its resources and
+/// prompts
+enum McpConnection {
+    Stdio(StdioMcpClient),
+    Http(HttpMcpClient),
+    Sse(SseMcpClient),
+    StreamableHttp(StreamableHttpMcpClient),
+    WebSocket(Box<WebSocketMcpClient>),
+}
+
+impl McpConnection {
+    /// Connect to an MCP using its stored config, holding the database lock
+    /// only while reading it
+    fn open(db: &Mutex<Database>, mcp_id: i64) -> Result<Self, String> {
+        let (mcp_type, command, args, headers, env, url, source, env_file, inherit_env, shell) = {
+            let db = db.lock().map_err(|e| e.to_string())?;
+            if !is_mcp_testable_from_db(&db, mcp_id)? {
+                return Err(format!("MCP {} is marked as not testable", mcp_id));
+            }
+            let (mcp_type, command, args, headers, env, url, source) =
+                get_mcp_test_data_with_source_from_db(&db, mcp_id)?;
+            let env_file = get_mcp_env_file_from_db(&db, mcp_id)?;
+            let inherit_env = get_mcp_inherit_env_from_db(&db, mcp_id)?;
+            let shell = get_mcp_shell_from_db(&db, mcp_id)?;
+            (
+                mcp_type,
+                command,
+                args,
+                headers,
+                env,
+                url,
+                source,
+                env_file,
+                inherit_env,
+                shell,
+            )
+        };
+
+        let headers = remote_headers(db, mcp_id, &mcp_type, &source, headers)?;
+        let connection = match mcp_type.as_str() {
+            _ if source == "system" => {
+                let mcp_url = url.ok_or_else(|| "System MCP requires a URL".to_string())?;
+                StreamableHttpMcpClient::connect(&mcp_url, headers.as_ref(), 30)
+                    .map(Self::StreamableHttp)
+            }
+            "stdio" => {
+                let cmd = command.ok_or_else(|| "STDIO MCP requires a command".to_string())?;
+                StdioMcpClient::spawn(
+                    &SpawnOptions {
+                        command: &cmd,
+                        args: &args,
+                        env: env.as_ref(),
+                        env_file: env_file.as_deref(),
+                        inherit_env,
+                        shell: shell.as_deref(),
+                    },
+                    30,
+                )
+                .map(Self::Stdio)
+            }
+            "http" => {
+                let mcp_url = url.ok_or_else(|| "HTTP MCP requires a URL".to_string())?;
+                HttpMcpClient::connect(&mcp_url, headers.as_ref(), 30).map(Self::Http)
+            }
+            "sse" => {
+                let mcp_url = url.ok_or_else(|| "SSE MCP requires a URL".to_string())?;
+                SseMcpClient::connect(&mcp_url, headers.as_ref(), 30).map(Self::Sse)
+            }
+            "ws" => {
+                let mcp_url = url.ok_or_else(|| "WebSocket MCP requires a URL".to_string())?;
+                WebSocketMcpClient::connect(&mcp_url, headers.as_ref(), 30)
+                    .map(|client| Self::WebSocket(Box::new(client)))
+            }
+            _ => return Err(format!("Unknown MCP type: {}", mcp_type)),
+        };
+        connection.map_err(|e| e.to_string())
+    }
+
+    fn list_resources(&mut self) -> anyhow::Result<Vec<McpResource>> {
+        match self {
+            Self::Stdio(client) => client.list_resources(),
+            Self::Http(client) => client.list_resources(),
+            Self::Sse(client) => client.list_resources(),
+            Self::StreamableHttp(client) => client.list_resources(),
+            Self::WebSocket(client) => client.list_resources(),
+        }
+    }
+
+    fn read_resource(&mut self, uri: &str) -> anyhow::Result<Vec<ToolContent>> {
+        match self {
+            Self::Stdio(client) => client.read_resource(uri),
+            Self::Http(client) => client.read_resource(uri),
+            Self::Sse(client) => client.read_resource(uri),
+            Self::StreamableHttp(client) => client.read_resource(uri),
+            Self::WebSocket(client) => client.read_resource(uri),
+        }
+    }
+
+    fn list_prompts(&mut self) -> anyhow::Result<Vec<McpPrompt>> {
+        match self {
+            Self::Stdio(client) => client.list_prompts(),
+            Self::Http(client) => client.list_prompts(),
+            Self::Sse(client) => client.list_prompts(),
+            Self::StreamableHttp(client) => client.list_prompts(),
+            Self::WebSocket(client) => client.list_prompts(),
+        }
+    }
+
+    fn get_prompt(
+        &mut self,
+        name: &str,
+        arguments: serde_json::Value,
+    ) -> anyhow::Result<Vec<McpPromptMessage>> {
+        match self {
+            Self::Stdio(client) => client.get_prompt(name, arguments),
+            Self::Http(client) => client.get_prompt(name, arguments),
+            Self::Sse(client) => client.get_prompt(name, arguments),
+            Self::StreamableHttp(client) => client.get_prompt(name, arguments),
+            Self::WebSocket(client) => client.get_prompt(name, arguments),
+        }
+    }
+
+    fn close(self) {
+        match self {
+            Self::Stdio(client) => client.close(),
+            Self::Http(client) => client.close(),
+            Self::Sse(client) => client.close(),
+            Self::StreamableHttp(client) => client.close(),
+            Self::WebSocket(client) => client.close(),
+        }
+    }
+}
+
+/// Connect to an MCP and list the resources it exposes. Servers that don't
+/// advertise resources return an empty list.
+#[tauri::command]
+pub fn get_mcp_resources(
+    db: State<'_, Arc<Mutex<Database>>>,
+    mcp_id: i64,
+) -> Result<Vec<McpResource>, String> {
+    get_mcp_resources_with_db(&db, mcp_id)
+}
+
+pub(crate) fn get_mcp_resources_with_db(
+    db: &Mutex<Database>,
+    mcp_id: i64,
+) -> Result<Vec<McpResource>, String> {
+    info!("[MCP Test] Listing resources of MCP id={}", mcp_id);
+    let mut connection = McpConnection::open(db, mcp_id)?;
+    let resources = connection.list_resources();
+    connection.close();
+    resources.map_err(|e| e.to_string())
+}
+
+/// Connect to an MCP and read the contents of one of its resources
 #[tauri::command]
+pub fn read_mcp_resource(
+    db: State<'_, Arc<Mutex<Database>>>,
+    mcp_id: i64,
+    uri: String,
+) -> Result<Vec<ToolContent>, String> {
+    read_mcp_resource_with_db(&db, mcp_id, &uri)
+}
+
+pub(crate) fn read_mcp_resource_with_db(
+    db: &Mutex<Database>,
+    mcp_id: i64,
+    uri: &str,
+) -> Result<Vec<ToolContent>, String> {
+    info!("[MCP Test] Reading resource {} of MCP id={}", uri, mcp_id);
+    let mut connection = McpConnection::open(db, mcp_id)?;
+    let contents = connection.read_resource(uri);
+    connection.close();
+    contents.map_err(|e| e.to_string())
+}
+
+/// Connect to an MCP and list the prompt templates it provides. Servers that
+/// don't advertise prompts return an empty list.
+#[tauri::command]
+pub fn get_mcp_prompts(
+    db: State<'_, Arc<Mutex<Database>>>,
+    mcp_id: i64,
+) -> Result<Vec<McpPrompt>, String> {
+    get_mcp_prompts_with_db(&db, mcp_id)
+}
+
+pub(crate) fn get_mcp_prompts_with_db(
+    db: &Mutex<Database>,
+    mcp_id: i64,
+) -> Result<Vec<McpPrompt>, String> {
+    info!("[MCP Test] Listing prompts of MCP id={}", mcp_id);
+    let mut connection = McpConnection::open(db, mcp_id)?;
+    let prompts = connection.list_prompts();
+    connection.close();
+    prompts.map_err(|e| e.to_string())
+}
+
+/// Connect to an MCP and render one of its prompts with `arguments`
+#[tauri::command]
+pub fn get_mcp_prompt(
+    db: State<'_, Arc<Mutex<Database>>>,
+    mcp_id: i64,
+    name: String,
+    arguments: Option<HashMap<String, String>>,
+) -> Result<Vec<McpPromptMessage>, String> {
+    get_mcp_prompt_with_db(&db, mcp_id, &name, arguments.unwrap_or_default())
+}
+
+pub(crate) fn get_mcp_prompt_with_db(
+    db: &Mutex<Database>,
+    mcp_id: i64,
+    name: &str,
+    arguments: HashMap<String, String>,
+) -> Result<Vec<McpPromptMessage>, String> {
+    info!("[MCP Test] Rendering prompt {} of MCP id={}", name, mcp_id);
+    let arguments = serde_json::to_value(arguments).map_err(|e| e.to_string())?;
+    let mut connection = McpConnection::open(db, mcp_id)?;
+    let messages = connection.get_prompt(name, arguments);
+    connection.close();
+    messages.map_err(|e| e.to_string())
+}
+
+/// Handshake latency percentiles from an MCP's recorded test history
+#[tauri::command]
+pub fn get_mcp_latency_stats(
+    db: State<'_, Arc<Mutex<Database>>>,
+    mcp_id: i64,
+) -> Result<McpLatencyStats, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    test_history::get_latency_stats(&db, mcp_id).map_err(|e| e.to_string())
+}
+
+/// Search the cached tools of every installed MCP by name and description
+#[tauri::command]
+pub fn search_cached_tools(
+    db: State<'_, Arc<Mutex<Database>>>,
+    query: String,
+) -> Result<Vec<CachedToolMatch>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    let mut matches = tool_cache::search_cached_tools(&db, &query).map_err(|e| e.to_string())?;
+    let threshold = tool_cache::schema_summary_threshold(&db);
+    for m in &mut matches {
+        tool_cache::summarize_large_schemas(std::slice::from_mut(&mut m.tool), threshold);
+    }
+    Ok(matches)
+}
+
+/// The tools an MCP reported the last time it was tested or connected.
+/// Schemas above the summary threshold come back summarized.
+#[tauri::command]
+pub fn get_cached_mcp_tools(
+    db: State<'_, Arc<Mutex<Database>>>,
+    mcp_id: i64,
+) -> Result<Vec<McpTool>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    let mut tools = tool_cache::get_cached_mcp_tools(&db, mcp_id).map_err(|e| e.to_string())?;
+    tool_cache::summarize_large_schemas(&mut tools, tool_cache::schema_summary_threshold(&db));
+    Ok(tools)
+}
+
+/// The complete cached input schema of one tool, for tools listed with a
+/// summarized schema
+#[tauri::command]
+pub fn get_full_tool_schema(
+    db: State<'_, Arc<Mutex<Database>>>,
+    mcp_id: i64,
+    tool_name: String,
+) -> Result<Option<serde_json::Value>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    tool_cache::get_full_tool_schema(&db, mcp_id, &tool_name).map_err(|e| e.to_string())
+}
+
+/// Schema size in bytes above which tool listings summarize schemas
+/// (`0` means never)
+#[tauri::command]
+pub fn get_tool_schema_summary_threshold(
+    db: State<'_, Arc<Mutex<Database>>>,
+) -> Result<usize, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    Ok(tool_cache::schema_summary_threshold(&db).unwrap_or(0))
+}
+
+#[tauri::command]
+pub fn set_tool_schema_summary_threshold(
+    db: State<'_, Arc<Mutex<Database>>>,
+    threshold: usize,
+) -> Result<(), String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    db.set_setting(
+        tool_cache::SCHEMA_SUMMARY_THRESHOLD_SETTING,
+        &threshold.to_string(),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Test an MCP configuration directly (for testing before saving). `retry`
+/// retries transient connection failures of HTTP and SSE servers.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn test_mcp_config(
     mcp_type: String,
     command: Option<String>,
@@ -130,26 +862,50 @@ pub fn test_mcp_config(
     url: Option<String>,
     headers: Option<HashMap<String, String>>,
     env: Option<HashMap<String, String>>,
+    env_file: Option<String>,
+    inherit_env: Option<bool>,
+    shell: Option<String>,
+    retry: Option<RetryPolicy>,
 ) -> Result<McpTestResult, String> {
     info!("[MCP Test] Testing MCP config: type={}", mcp_type);
 
     let args_vec = args.unwrap_or_default();
+    let env_file = env_file.map(|f| {
+        mcp_client::resolve_env_file(&f, None, None)
+            .to_string_lossy()
+            .into_owned()
+    });
 
     let result = match mcp_type.as_str() {
         "stdio" => {
             let cmd = command.ok_or_else(|| "STDIO MCP requires a command".to_string())?;
             info!("[MCP Test] Testing STDIO config: {} {:?}", cmd, args_vec);
-            mcp_client::test_stdio_mcp(&cmd, &args_vec, env.as_ref(), 30)
+            mcp_client::test_stdio_mcp(
+                &SpawnOptions {
+                    command: &cmd,
+                    args: &args_vec,
+                    env: env.as_ref(),
+                    env_file: env_file.as_deref(),
+                    inherit_env: inherit_env.unwrap_or(true),
+                    shell: shell.as_deref(),
+                },
+                30,
+            )
         }
         "http" => {
             let mcp_url = url.ok_or_else(|| "HTTP MCP requires a URL".to_string())?;
             info!("[MCP Test] Testing HTTP config: {}", mcp_url);
-            mcp_client::test_http_mcp(&mcp_url, headers.as_ref(), 30)
+            mcp_client::test_http_mcp(&mcp_url, headers.as_ref(), 30, retry)
         }
         "sse" => {
             let mcp_url = url.ok_or_else(|| "SSE MCP requires a URL".to_string())?;
             info!("[MCP Test] Testing SSE config: {}", mcp_url);
-            mcp_client::test_sse_mcp(&mcp_url, headers.as_ref(), 30)
+            mcp_client::test_sse_mcp(&mcp_url, headers.as_ref(), 30, retry)
+        }
+        "ws" => {
+            let mcp_url = url.ok_or_else(|| "WebSocket MCP requires a URL".to_string())?;
+            info!("[MCP Test] Testing WebSocket config: {}", mcp_url);
+            mcp_client::test_websocket_mcp(&mcp_url, headers.as_ref(), 30)
         }
         _ => {
             return Err(format!("Unknown MCP type: {}", mcp_type));
@@ -232,7 +988,7 @@ pub fn validate_mcp_config(
                 return Err("STDIO MCP requires a command".to_string());
             }
         }
-        "http" | "sse" => {
+        "http" | "sse" | "ws" => {
             if url.is_none() {
                 return Err(format!("{} MCP requires a URL", mcp_type.to_uppercase()));
             }
@@ -440,4 +1196,268 @@ mod tests {
         assert!(env.is_some());
         assert!(env.unwrap().is_empty());
     }
+
+    #[test]
+    fn test_is_mcp_testable_from_db() {
+        let db = Database::in_memory().unwrap();
+
+        db.conn()
+            .execute(
+                "INSERT INTO mcps (name, type, command) VALUES ('default', 'stdio', 'cmd')",
+                [],
+            )
+            .unwrap();
+        let default_id = db.conn().last_insert_rowid();
+        db.conn()
+            .execute(
+                "INSERT INTO mcps (name, type, command, testable) VALUES ('external', 'stdio', 'cmd', 0)",
+                [],
+            )
+            .unwrap();
+        let external_id = db.conn().last_insert_rowid();
+
+        assert!(is_mcp_testable_from_db(&db, default_id).unwrap());
+        assert!(!is_mcp_testable_from_db(&db, external_id).unwrap());
+        assert!(is_mcp_testable_from_db(&db, 9999).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_health_checks_only_tests_requested_ids() {
+        let db = Database::in_memory().unwrap();
+        let mut ids = Vec::new();
+        for (name, testable) in [("alpha", 0), ("broken", 1), ("gamma", 0)] {
+            db.conn()
+                .execute(
+                    "INSERT INTO mcps (name, type, command, testable) VALUES (?, 'stdio', '/nonexistent/mcp-server', ?)",
+                    params![name, testable],
+                )
+                .unwrap();
+            ids.push(db.conn().last_insert_rowid());
+        }
+        let db = Arc::new(Mutex::new(db));
+
+        let results = run_health_checks(Arc::clone(&db), vec![ids[1], 9999, ids[1]]).await;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].mcp_id, ids[1]);
+        assert_eq!(results[0].mcp_name, "broken");
+        assert!(!results[0].result.success);
+        assert!(!results[0].result.skipped);
+        assert_eq!(results[1].mcp_id, 9999);
+        assert!(results[1]
+            .result
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("not found"));
+
+        // Only the requested MCP gained history; the others were never probed
+        let db = db.lock().unwrap();
+        let tested: Vec<i64> = db
+            .conn()
+            .prepare("SELECT DISTINCT mcp_id FROM mcp_test_history")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(tested, vec![ids[1]]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_mcp_tests_reports_hanging_server_as_timed_out() {
+        let db = Database::in_memory().unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO mcps (name, type, command, args) VALUES ('hangs', 'stdio', 'sleep', '[\"2\"]')",
+                [],
+            )
+            .unwrap();
+        let hanging = db.conn().last_insert_rowid();
+        db.conn()
+            .execute(
+                "INSERT INTO mcps (name, type, command) VALUES ('missing', 'stdio', '/nonexistent/mcp-server')",
+                [],
+            )
+            .unwrap();
+        let missing = db.conn().last_insert_rowid();
+        assert_eq!(
+            get_all_mcp_ids_from_db(&db).unwrap(),
+            vec![hanging, missing]
+        );
+        let db = Arc::new(Mutex::new(db));
+
+        let seen = Mutex::new(Vec::new());
+        let results = run_mcp_tests(
+            Arc::clone(&db),
+            vec![hanging, missing],
+            2,
+            Duration::from_millis(500),
+            |progress| seen.lock().unwrap().push(progress.result.mcp_id),
+        )
+        .await;
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].mcp_name, "hangs");
+        assert!(results[0]
+            .result
+            .error
+            .as_deref()
+            .unwrap()
+            .starts_with("Timed out"));
+        assert_eq!(results[1].mcp_name, "missing");
+        assert!(!results[1].result.success);
+        // The quick failure was reported before the hanging server timed out
+        assert_eq!(*seen.lock().unwrap(), vec![missing, hanging]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_get_mcp_resources_from_stored_config() {
+        let server = r#"while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  case "$line" in
+    *'"initialize"'*) printf '{"jsonrpc":"2.0","id":%s,"result":{"capabilities":{"resources":{}}}}\n' "$id" ;;
+    *'"resources/list"'*) printf '{"jsonrpc":"2.0","id":%s,"result":{"resources":[{"uri":"mem://notes","name":"notes"}]}}\n' "$id" ;;
+    *'"resources/read"'*) printf '{"jsonrpc":"2.0","id":%s,"result":{"contents":[{"uri":"mem://notes","text":"hi"}]}}\n' "$id" ;;
+    *) [ -n "$id" ] && printf '{"jsonrpc":"2.0","id":%s,"result":{"tools":[]}}\n' "$id" ;;
+  esac
+done"#;
+        let db = Database::in_memory().unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO mcps (name, type, command) VALUES ('notes', 'stdio', ?)",
+                [server],
+            )
+            .unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO mcps (name, type, command, testable) VALUES ('external', 'stdio', 'cmd', 0)",
+                [],
+            )
+            .unwrap();
+        let db = Mutex::new(db);
+
+        let resources = get_mcp_resources_with_db(&db, 1).unwrap();
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].uri, "mem://notes");
+        let contents = read_mcp_resource_with_db(&db, 1, "mem://notes").unwrap();
+        assert!(matches!(
+            contents.as_slice(),
+            [ToolContent::Resource { text: Some(text), .. }] if text == "hi"
+        ));
+
+        assert!(get_mcp_resources_with_db(&db, 2)
+            .unwrap_err()
+            .contains("not testable"));
+        assert!(get_mcp_resources_with_db(&db, 9999).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_get_mcp_prompts_from_stored_config() {
+        let server = r#"while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  case "$line" in
+    *'"initialize"'*) printf '{"jsonrpc":"2.0","id":%s,"result":{"capabilities":{"prompts":{}}}}\n' "$id" ;;
+    *'"prompts/list"'*) printf '{"jsonrpc":"2.0","id":%s,"result":{"prompts":[]}}\n' "$id" ;;
+    *'"prompts/get"'*) printf '{"jsonrpc":"2.0","id":%s,"result":{"messages":[{"role":"user","content":{"type":"text","text":"hi"}}]}}\n' "$id" ;;
+    *) [ -n "$id" ] && printf '{"jsonrpc":"2.0","id":%s,"result":{"tools":[]}}\n' "$id" ;;
+  esac
+done"#;
+        let db = Database::in_memory().unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO mcps (name, type, command) VALUES ('prompts', 'stdio', ?)",
+                [server],
+            )
+            .unwrap();
+        let db = Mutex::new(db);
+
+        // Advertising prompts but listing none is not an error
+        assert!(get_mcp_prompts_with_db(&db, 1).unwrap().is_empty());
+        let arguments = HashMap::from([("topic".to_string(), "rust".to_string())]);
+        let messages = get_mcp_prompt_with_db(&db, 1, "greet", arguments).unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].content["text"], "hi");
+    }
+
+    #[test]
+    fn test_refresh_mcp_metadata_keeps_cache_on_failure() {
+        let db = Database::in_memory().unwrap();
+        db.conn()
+            .execute_batch(
+                "INSERT INTO mcps (name, type, command) VALUES ('broken', 'stdio', '/nonexistent/mcp-server');
+                 INSERT INTO mcps (name, type, command, testable) VALUES ('external', 'stdio', 'cmd', 0);",
+            )
+            .unwrap();
+        let tool = McpTool {
+            name: "read_file".to_string(),
+            description: None,
+            input_schema: None,
+        };
+        tool_cache::cache_mcp_tools(&db, 1, std::slice::from_ref(&tool)).unwrap();
+        let db = Mutex::new(db);
+
+        let err = refresh_mcp_metadata_with_db(&db, 1).unwrap_err();
+        assert!(err.contains("cached tools were kept"), "{}", err);
+        let cached = tool_cache::get_cached_mcp_tools(&db.lock().unwrap(), 1).unwrap();
+        assert_eq!(cached.len(), 1);
+        assert_eq!(cached[0].name, "read_file");
+
+        let err = refresh_mcp_metadata_with_db(&db, 2).unwrap_err();
+        assert!(err.contains("not testable"), "{}", err);
+    }
+
+    #[test]
+    fn test_diagnose_stdio_framing_rejects_remote_mcps() {
+        let db = Database::in_memory().unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO mcps (name, type, url) VALUES ('remote', 'http', 'https://example.com/mcp')",
+                [],
+            )
+            .unwrap();
+        let id = db.conn().last_insert_rowid();
+        let db = Mutex::new(db);
+        let err = diagnose_stdio_framing_with_db(&db, id).unwrap_err();
+        assert!(err.contains("only apply to stdio"), "{}", err);
+        assert!(diagnose_stdio_framing_with_db(&db, 9999).is_err());
+    }
+
+    #[test]
+    fn test_diagnose_stdio_framing_skips_untestable_mcps() {
+        let db = Database::in_memory().unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO mcps (name, type, command, testable) VALUES ('external', 'stdio', 'cmd', 0)",
+                [],
+            )
+            .unwrap();
+        let id = db.conn().last_insert_rowid();
+        let db = Mutex::new(db);
+        let err = diagnose_stdio_framing_with_db(&db, id).unwrap_err();
+        assert!(err.contains("not testable"), "{}", err);
+    }
+
+    #[test]
+    fn test_get_enabled_global_mcp_ids_from_db() {
+        let db = Database::in_memory().unwrap();
+        for name in ["zeta", "alpha", "off"] {
+            db.conn()
+                .execute(
+                    "INSERT INTO mcps (name, type, command) VALUES (?, 'stdio', 'cmd')",
+                    [name],
+                )
+                .unwrap();
+            let id = db.conn().last_insert_rowid();
+            db.conn()
+                .execute(
+                    "INSERT INTO global_mcps (mcp_id, is_enabled) VALUES (?, ?)",
+                    params![id, name != "off"],
+                )
+                .unwrap();
+        }
+        assert_eq!(get_enabled_global_mcp_ids_from_db(&db).unwrap(), vec![2, 1]);
+    }
 }
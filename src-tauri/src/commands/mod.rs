@@ -1,5 +1,7 @@
 pub mod agent_memory;
 pub mod analytics;
+pub mod batch;
+pub mod caches;
 pub mod claude_json;
 pub mod claude_settings;
 pub mod cloud_sync;
@@ -7,19 +9,26 @@ pub mod cloud_sync;
 pub mod commands;
 pub mod config;
 pub mod containers;
+pub mod content_limits;
 pub mod debug;
 pub mod docker_hosts;
+pub mod editor_bundle;
+pub mod entity_names;
+pub mod entity_sync;
 pub mod hooks;
 pub mod insights;
 pub mod keybindings;
 pub mod managed_settings;
 pub mod mcp;
 pub mod mcp_gateway;
+pub mod mcp_oauth;
 pub mod mcp_registry;
 pub mod mcp_server;
 pub mod mcp_session;
 pub mod mcp_test;
 pub mod memory;
+pub mod models;
+pub mod pending_changes;
 pub mod permissions;
 pub mod profiles;
 pub mod projects;
@@ -33,3 +42,4 @@ pub mod sounds;
 pub mod spinner_verbs;
 pub mod statusline;
 pub mod subagents;
+pub mod tags;
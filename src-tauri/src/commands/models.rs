@@ -0,0 +1,360 @@
+use crate::commands::settings::get_enabled_editors_from_db;
+use crate::commands::skills::get_skill_by_id;
+use crate::commands::subagents::get_subagent_by_id;
+use crate::db::models::{Skill, SubAgent};
+use crate::db::schema::Database;
+use crate::services::{skill_writer, subagent_writer};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// Model aliases Claude Code resolves itself. Full model ids (`claude-...`)
+/// are accepted as well so pinned versions keep working.
+const MODEL_ALIASES: &[&str] = &[
+    "default",
+    "sonnet",
+    "sonnet[1m]",
+    "opus",
+    "opusplan",
+    "haiku",
+    "inherit",
+];
+
+type SkillProjectWriter = fn(&Path, &Skill) -> anyhow::Result<()>;
+type SubAgentProjectWriter = fn(&Path, &SubAgent) -> anyhow::Result<()>;
+
+/// Entity kinds that carry a `model` field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelEntityType {
+    Skill,
+    Subagent,
+}
+
+impl ModelEntityType {
+    fn table(self) -> &'static str {
+        match self {
+            ModelEntityType::Skill => "skills",
+            ModelEntityType::Subagent => "subagents",
+        }
+    }
+}
+
+/// Outcome of a bulk model update for a single entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkModelResult {
+    pub id: i64,
+    pub updated: bool,
+    pub synced: bool,
+    pub error: Option<String>,
+}
+
+/// Check a model value against the known aliases / full model id format
+pub(crate) fn validate_model(model: &str) -> Result<(), String> {
+    if MODEL_ALIASES.contains(&model) || model.starts_with("claude-") {
+        Ok(())
+    } else {
+        Err(format!(
+            "Unknown model '{}'. Expected one of {} or a full 'claude-...' model id",
+            model,
+            MODEL_ALIASES.join(", ")
+        ))
+    }
+}
+
+/// Set `model` on many skills or sub-agents at once. An empty or missing
+/// model clears the field so the entity falls back to the session model.
+#[tauri::command]
+pub fn bulk_set_model(
+    db: State<'_, Arc<Mutex<Database>>>,
+    entity_type: ModelEntityType,
+    ids: Vec<i64>,
+    model: Option<String>,
+) -> Result<Vec<BulkModelResult>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    bulk_set_model_in_db(&db, entity_type, &ids, model.as_deref())
+}
+
+/// Update the model column for every id in one transaction, then rewrite the
+/// files of each updated entity in the scopes it is assigned to.
+pub(crate) fn bulk_set_model_in_db(
+    db: &Database,
+    entity_type: ModelEntityType,
+    ids: &[i64],
+    model: Option<&str>,
+) -> Result<Vec<BulkModelResult>, String> {
+    let model = model.map(str::trim).filter(|m| !m.is_empty());
+    if let Some(model) = model {
+        validate_model(model)?;
+    }
+
+    let sql = format!(
+        "UPDATE {} SET model = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+        entity_type.table()
+    );
+
+    let tx = db
+        .conn()
+        .unchecked_transaction()
+        .map_err(|e| e.to_string())?;
+    let mut results = Vec::with_capacity(ids.len());
+    for &id in ids {
+        let changed = tx
+            .execute(&sql, rusqlite::params![model, id])
+            .map_err(|e| e.to_string())?;
+        results.push(BulkModelResult {
+            id,
+            updated: changed > 0,
+            synced: false,
+            error: (changed == 0).then(|| format!("No {} with id {}", entity_type.table(), id)),
+        });
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    let editors = get_enabled_editors_from_db(db);
+    for result in results.iter_mut().filter(|r| r.updated) {
+        let synced = match entity_type {
+            ModelEntityType::Skill => resync_skill(db, result.id, &editors),
+            ModelEntityType::Subagent => resync_subagent(db, result.id, &editors),
+        };
+        match synced {
+            Ok(()) => result.synced = true,
+            Err(e) => result.error = Some(format!("Updated, but failed to resync files: {}", e)),
+        }
+    }
+
+    Ok(results)
+}
+
+/// Paths of the projects an entity is enabled in
+pub(crate) fn enabled_project_paths(
+    db: &Database,
+    table: &str,
+    column: &str,
+    id: i64,
+) -> Result<Vec<String>, String> {
+    let sql = format!(
+        "SELECT p.path FROM {table} x JOIN projects p ON x.project_id = p.id
+         WHERE x.{column} = ? AND x.is_enabled = 1"
+    );
+    let mut stmt = db.conn().prepare(&sql).map_err(|e| e.to_string())?;
+    let paths = stmt
+        .query_map([id], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(paths)
+}
+
+pub(crate) fn is_enabled_globally(db: &Database, table: &str, column: &str, id: i64) -> bool {
+    db.conn()
+        .query_row(
+            &format!("SELECT COUNT(*) > 0 FROM {table} WHERE {column} = ? AND is_enabled = 1"),
+            [id],
+            |row| row.get(0),
+        )
+        .unwrap_or(false)
+}
+
+pub(crate) fn resync_skill(db: &Database, id: i64, editors: &[String]) -> Result<(), String> {
+    let skill = get_skill_by_id(db, id)?;
+    let global = is_enabled_globally(db, "global_skills", "skill_id", id);
+    let projects = enabled_project_paths(db, "project_skills", "skill_id", id)?;
+
+    for editor in editors {
+        let (write_global, write_project): (fn(&Skill) -> anyhow::Result<()>, SkillProjectWriter) =
+            match editor.as_str() {
+                "claude_code" => (
+                    skill_writer::write_global_skill,
+                    skill_writer::write_project_skill,
+                ),
+                "opencode" => (
+                    skill_writer::write_global_skill_opencode,
+                    skill_writer::write_project_skill_opencode,
+                ),
+                unknown => {
+                    warn!(
+                        "[Models] Unknown editor type '{}' for skill '{}'. Skipping.",
+                        unknown, skill.name
+                    );
+                    continue;
+                }
+            };
+        if global {
+            write_global(&skill).map_err(|e| e.to_string())?;
+        }
+        for path in &projects {
+            write_project(Path::new(path), &skill).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn resync_subagent(db: &Database, id: i64, editors: &[String]) -> Result<(), String> {
+    let subagent = get_subagent_by_id(db, id)?;
+    let global = is_enabled_globally(db, "global_subagents", "subagent_id", id);
+    let projects = enabled_project_paths(db, "project_subagents", "subagent_id", id)?;
+
+    for editor in editors {
+        let (write_global, write_project): (
+            fn(&SubAgent) -> anyhow::Result<()>,
+            SubAgentProjectWriter,
+        ) = match editor.as_str() {
+            "claude_code" => (
+                subagent_writer::write_global_subagent,
+                subagent_writer::write_project_subagent,
+            ),
+            "opencode" => (
+                subagent_writer::write_global_subagent_opencode,
+                subagent_writer::write_project_subagent_opencode,
+            ),
+            unknown => {
+                warn!(
+                    "[Models] Unknown editor type '{}' for sub-agent '{}'. Skipping.",
+                    unknown, subagent.name
+                );
+                continue;
+            }
+        };
+        if global {
+            write_global(&subagent).map_err(|e| e.to_string())?;
+        }
+        for path in &projects {
+            write_project(Path::new(path), &subagent).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::skills::create_skill_in_db;
+    use crate::commands::subagents::create_subagent_in_db;
+    use crate::db::models::{CreateSkillRequest, CreateSubAgentRequest};
+
+    fn sample_skill(name: &str) -> CreateSkillRequest {
+        CreateSkillRequest {
+            name: name.to_string(),
+            description: Some("A skill".to_string()),
+            content: "Do the thing.".to_string(),
+            allowed_tools: None,
+            model: Some("sonnet".to_string()),
+            disable_model_invocation: None,
+            tags: None,
+            context: None,
+            agent: None,
+            hooks: None,
+            paths: None,
+            shell: None,
+            once: None,
+            effort: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_model() {
+        assert!(validate_model("opus").is_ok());
+        for alias in ["default", "opusplan", "sonnet[1m]"] {
+            assert!(validate_model(alias).is_ok(), "{}", alias);
+        }
+        assert!(validate_model("claude-sonnet-4-5-20250929").is_ok());
+        assert!(validate_model("gpt-4").is_err());
+    }
+
+    #[test]
+    fn test_bulk_set_model_updates_skills_and_reports_missing() {
+        let db = Database::in_memory().unwrap();
+        let a = create_skill_in_db(&db, &sample_skill("skill-a")).unwrap();
+        let b = create_skill_in_db(&db, &sample_skill("skill-b")).unwrap();
+
+        let results = bulk_set_model_in_db(
+            &db,
+            ModelEntityType::Skill,
+            &[a.id, b.id, 9999],
+            Some("opus"),
+        )
+        .unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert!(results[0].updated && results[0].synced);
+        assert!(results[1].updated && results[1].synced);
+        assert!(!results[2].updated);
+        assert!(results[2].error.is_some());
+        assert_eq!(
+            get_skill_by_id(&db, a.id).unwrap().model.as_deref(),
+            Some("opus")
+        );
+        assert_eq!(
+            get_skill_by_id(&db, b.id).unwrap().model.as_deref(),
+            Some("opus")
+        );
+    }
+
+    #[test]
+    fn test_bulk_set_model_rejects_unknown_model() {
+        let db = Database::in_memory().unwrap();
+        let a = create_skill_in_db(&db, &sample_skill("skill-a")).unwrap();
+
+        assert!(bulk_set_model_in_db(&db, ModelEntityType::Skill, &[a.id], Some("gpt-4")).is_err());
+        assert_eq!(
+            get_skill_by_id(&db, a.id).unwrap().model.as_deref(),
+            Some("sonnet")
+        );
+    }
+
+    #[test]
+    fn test_bulk_set_model_clears_and_resyncs_project_subagent() {
+        let db = Database::in_memory().unwrap();
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let subagent = create_subagent_in_db(
+            &db,
+            &CreateSubAgentRequest {
+                name: "reviewer".to_string(),
+                description: "Reviews code".to_string(),
+                content: "Review the diff.".to_string(),
+                tools: None,
+                model: Some("opus".to_string()),
+                permission_mode: None,
+                skills: None,
+                tags: None,
+                disallowed_tools: None,
+                max_turns: None,
+                memory: None,
+                background: None,
+                effort: None,
+                isolation: None,
+                hooks: None,
+                mcp_servers: None,
+                initial_prompt: None,
+            },
+        )
+        .unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO projects (name, path) VALUES ('proj', ?)",
+                [temp_dir.path().to_string_lossy().to_string()],
+            )
+            .unwrap();
+        let project_id = db.conn().last_insert_rowid();
+        db.conn()
+            .execute(
+                "INSERT INTO project_subagents (project_id, subagent_id) VALUES (?, ?)",
+                [project_id, subagent.id],
+            )
+            .unwrap();
+
+        let results =
+            bulk_set_model_in_db(&db, ModelEntityType::Subagent, &[subagent.id], Some(" "))
+                .unwrap();
+        assert!(results[0].synced, "{:?}", results[0].error);
+        assert_eq!(get_subagent_by_id(&db, subagent.id).unwrap().model, None);
+
+        let written =
+            std::fs::read_to_string(temp_dir.path().join(".claude/agents/reviewer.md")).unwrap();
+        assert!(!written.contains("model:"));
+    }
+}
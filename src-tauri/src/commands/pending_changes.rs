@@ -0,0 +1,116 @@
+use crate::db::schema::Database;
+use crate::services::pending_changes::{self, PendingChange};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// Setting that holds the "confirm before writing config files" mode
+pub const CONFIRM_CONFIG_WRITES_KEY: &str = "confirm_config_writes";
+
+#[tauri::command]
+pub fn get_confirm_config_writes(db: State<'_, Arc<Mutex<Database>>>) -> Result<bool, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    Ok(get_confirm_config_writes_from_db(&db))
+}
+
+/// Turning the mode off leaves already-queued changes pending
+#[tauri::command]
+pub fn set_confirm_config_writes(
+    db: State<'_, Arc<Mutex<Database>>>,
+    enabled: bool,
+) -> Result<(), String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    db.set_setting(
+        CONFIRM_CONFIG_WRITES_KEY,
+        if enabled { "true" } else { "false" },
+    )
+    .map_err(|e| e.to_string())?;
+    pending_changes::store().set_confirm_writes(enabled);
+    Ok(())
+}
+
+pub(crate) fn get_confirm_config_writes_from_db(db: &Database) -> bool {
+    db.get_setting(CONFIRM_CONFIG_WRITES_KEY).as_deref() == Some("true")
+}
+
+/// Config writes queued while confirmation is on, each with its diff
+#[tauri::command]
+pub fn list_pending_changes() -> Result<Vec<PendingChange>, String> {
+    pending_changes::store().list().map_err(|e| e.to_string())
+}
+
+/// Write a queued change to disk
+#[tauri::command]
+pub fn confirm_pending_change(
+    db: State<'_, Arc<Mutex<Database>>>,
+    token: String,
+) -> Result<PendingChange, String> {
+    let change = pending_changes::store()
+        .confirm(&token)
+        .map_err(|e| e.to_string())?;
+    let db = db.lock().map_err(|e| e.to_string())?;
+    mark_confirmed_change_synced(&db, &change)?;
+    Ok(change)
+}
+
+/// A project sync that queued its `.mcp.json` isn't marked synced until that
+/// change is confirmed
+pub(crate) fn mark_confirmed_change_synced(
+    db: &Database,
+    change: &PendingChange,
+) -> Result<(), String> {
+    let path = Path::new(&change.path);
+    if path.file_name().and_then(|n| n.to_str()) != Some(".mcp.json") {
+        return Ok(());
+    }
+    let Some(project_dir) = path.parent() else {
+        return Ok(());
+    };
+    db.conn()
+        .execute(
+            "UPDATE projects SET has_mcp_file = 1, updated_at = CURRENT_TIMESTAMP WHERE path = ?",
+            [project_dir.to_string_lossy()],
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn discard_pending_change(token: String) -> Result<(), String> {
+    pending_changes::store()
+        .discard(&token)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::projects::{create_project_in_db, get_project_by_id};
+    use crate::db::CreateProjectRequest;
+
+    #[test]
+    fn test_confirming_mcp_json_marks_project_synced() {
+        let db = Database::in_memory().unwrap();
+        let project = create_project_in_db(
+            &db,
+            &CreateProjectRequest {
+                name: "demo".to_string(),
+                path: "/work/demo".to_string(),
+            },
+        )
+        .unwrap();
+        let change = |path: &str| PendingChange {
+            token: "t".to_string(),
+            path: path.to_string(),
+            before: None,
+            after: "{}".to_string(),
+            diff: String::new(),
+        };
+
+        mark_confirmed_change_synced(&db, &change("/work/demo/.claude/settings.json")).unwrap();
+        assert!(!get_project_by_id(&db, project.id).unwrap().has_mcp_file);
+
+        mark_confirmed_change_synced(&db, &change("/work/demo/.mcp.json")).unwrap();
+        assert!(get_project_by_id(&db, project.id).unwrap().has_mcp_file);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::db::models::PermissionTemplate;
 use crate::db::schema::Database;
+use crate::services::pending_changes::PendingChange;
 use crate::services::permission_writer::{self, AllPermissions, PermissionScope};
 use log::info;
 use rusqlite::params;
@@ -38,7 +39,7 @@ pub fn set_permission_rules(
     project_path: Option<String>,
     category: String,
     rules: Vec<String>,
-) -> Result<(), String> {
+) -> Result<Option<PendingChange>, String> {
     info!(
         "[Permissions] Setting {} rules for scope={} ({} rules)",
         category,
@@ -57,7 +58,7 @@ pub fn add_permission_rule(
     project_path: Option<String>,
     category: String,
     rule: String,
-) -> Result<(), String> {
+) -> Result<Option<PendingChange>, String> {
     info!(
         "[Permissions] Adding rule '{}' to {} in scope={}",
         rule, category, scope
@@ -92,7 +93,7 @@ pub fn remove_permission_rule(
     project_path: Option<String>,
     category: String,
     index: usize,
-) -> Result<(), String> {
+) -> Result<Option<PendingChange>, String> {
     info!(
         "[Permissions] Removing rule at index {} from {} in scope={}",
         index, category, scope
@@ -131,7 +132,7 @@ pub fn reorder_permission_rules(
     project_path: Option<String>,
     category: String,
     rules: Vec<String>,
-) -> Result<(), String> {
+) -> Result<Option<PendingChange>, String> {
     info!(
         "[Permissions] Reordering {} rules in {} scope={}",
         rules.len(),
@@ -149,7 +150,7 @@ pub fn set_default_mode(
     scope: String,
     project_path: Option<String>,
     mode: Option<String>,
-) -> Result<(), String> {
+) -> Result<Option<PendingChange>, String> {
     info!(
         "[Permissions] Setting defaultMode={:?} for scope={}",
         mode, scope
@@ -165,7 +166,7 @@ pub fn set_additional_directories(
     scope: String,
     project_path: Option<String>,
     directories: Vec<String>,
-) -> Result<(), String> {
+) -> Result<Option<PendingChange>, String> {
     info!(
         "[Permissions] Setting {} additionalDirectories for scope={}",
         directories.len(),
@@ -1,5 +1,6 @@
 use crate::commands::config::sync_global_config_from_db;
 use crate::db::{CreateProfileRequest, Database, Profile, ProfileWithItems};
+use crate::services::pending_changes::PendingChange;
 use log::info;
 use rusqlite::params;
 use std::sync::{Arc, Mutex};
@@ -71,15 +72,16 @@ pub fn capture_profile_from_current(
 
 /// Activate a profile (replace global config with profile items)
 #[tauri::command]
-pub fn activate_profile(db: State<'_, Arc<Mutex<Database>>>, id: i64) -> Result<(), String> {
+pub fn activate_profile(
+    db: State<'_, Arc<Mutex<Database>>>,
+    id: i64,
+) -> Result<Vec<PendingChange>, String> {
     info!("[Profiles] Activating profile id={}", id);
     let db = db.lock().map_err(|e| e.to_string())?;
     activate_profile_in_db(&db, id)?;
 
     // Sync config to disk
-    sync_global_config_from_db(&db)?;
-
-    Ok(())
+    sync_global_config_from_db(&db)
 }
 
 /// Deactivate the current active profile (just marks none as active)
@@ -1,11 +1,20 @@
+use crate::commands::config::{build_mcp_config_preview, McpConfigPreview};
 use crate::db::{CreateProjectRequest, Database, Mcp, Project, ProjectMcp};
 use crate::services::config_writer;
+use crate::services::pending_changes::{read_config_file, PendingChange, WithPendingChanges};
+use crate::services::project_manifest::{self, ManifestSyncResult};
+use crate::services::tool_references::{self, ToolReferenceReport};
+use crate::utils::git::{self, GitInfo};
+use crate::utils::paths::{get_claude_paths, ClaudePathsInternal};
 use log::{error, info, warn};
 use rusqlite::params;
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 use tauri_plugin_dialog::DialogExt;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 
 fn parse_json_array(s: Option<String>) -> Option<Vec<String>> {
     s.and_then(|v| serde_json::from_str(&v).ok())
@@ -34,6 +43,12 @@ fn row_to_mcp(row: &rusqlite::Row, offset: usize) -> rusqlite::Result<Mcp> {
         is_favorite: row.get::<_, i32>(offset + 14)? != 0,
         created_at: row.get(offset + 15)?,
         updated_at: row.get(offset + 16)?,
+        testable: row.get::<_, i32>(offset + 17)? != 0,
+        env_file: row.get(offset + 18)?,
+        display_name: row.get(offset + 19)?,
+        homepage: row.get(offset + 20)?,
+        inherit_env: row.get::<_, i32>(offset + 21)? != 0,
+        shell: row.get(offset + 22)?,
     })
 }
 
@@ -84,7 +99,7 @@ pub fn get_all_projects(db: State<'_, Arc<Mutex<Database>>>) -> Result<Vec<Proje
             .prepare(
                 "SELECT pm.id, pm.mcp_id, pm.is_enabled, pm.env_overrides, pm.display_order,
                         m.id, m.name, m.description, m.type, m.command, m.args, m.url, m.headers, m.env,
-                        m.icon, m.tags, m.source, m.source_path, m.is_enabled_global, m.is_favorite, m.created_at, m.updated_at
+                        m.icon, m.tags, m.source, m.source_path, m.is_enabled_global, m.is_favorite, m.created_at, m.updated_at, m.testable, m.env_file, m.display_name, m.homepage, m.inherit_env, m.shell
                  FROM project_mcps pm
                  JOIN mcps m ON pm.mcp_id = m.id
                  WHERE pm.project_id = ?
@@ -118,9 +133,8 @@ pub fn get_all_projects(db: State<'_, Arc<Mutex<Database>>>) -> Result<Vec<Proje
 pub fn add_project(
     db: State<'_, Arc<Mutex<Database>>>,
     project: CreateProjectRequest,
-) -> Result<Project, String> {
+) -> Result<WithPendingChanges<Project>, String> {
     use crate::services::scanner;
-    use crate::utils::paths::get_claude_paths;
 
     info!(
         "[Projects] Adding project: {} at {}",
@@ -164,26 +178,36 @@ pub fn add_project(
     );
 
     // Register project in claude.json (even with no MCPs)
+    let mut pending_changes = Vec::new();
     if let Ok(paths) = get_claude_paths() {
         let empty_mcps: Vec<config_writer::McpWithEnabledTuple> = vec![];
-        let _ = config_writer::write_project_to_claude_json(&paths, &project.path, &empty_mcps);
+        match config_writer::write_project_to_claude_json(&paths, &project.path, &empty_mcps) {
+            Ok(change) => pending_changes.extend(change),
+            Err(e) => warn!(
+                "[Projects] Failed to register {} in claude.json: {}",
+                project.path, e
+            ),
+        }
     }
 
     // Fetch assigned MCPs to return in the response
     let assigned_mcps = get_project_assigned_mcps(&db, id);
 
-    Ok(Project {
-        id,
-        name: project.name,
-        path: project.path,
-        has_mcp_file,
-        has_settings_file,
-        last_scanned_at: None,
-        editor_type: "claude_code".to_string(),
-        is_favorite: false,
-        created_at: chrono::Utc::now().to_rfc3339(),
-        updated_at: chrono::Utc::now().to_rfc3339(),
-        assigned_mcps,
+    Ok(WithPendingChanges {
+        item: Project {
+            id,
+            name: project.name,
+            path: project.path,
+            has_mcp_file,
+            has_settings_file,
+            last_scanned_at: None,
+            editor_type: "claude_code".to_string(),
+            is_favorite: false,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+            assigned_mcps,
+        },
+        pending_changes,
     })
 }
 
@@ -246,19 +270,380 @@ pub fn toggle_project_mcp(
 }
 
 #[tauri::command]
-#[allow(clippy::type_complexity)]
 pub fn sync_project_config(
     db: State<'_, Arc<Mutex<Database>>>,
     project_id: i64,
+) -> Result<Vec<PendingChange>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    sync_project_config_from_db(&db, project_id)
+}
+
+/// Hash of the `.mcp.json` the project would get from a sync, for CI to
+/// compare against the committed file
+#[tauri::command]
+pub fn get_project_config_checksum(
+    db: State<'_, Arc<Mutex<Database>>>,
+    project_id: i64,
+) -> Result<String, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    get_project_config_checksum_from_db(&db, project_id)
+}
+
+/// Most project configs written at once by `sync_all_projects`
+const SYNC_ALL_CONCURRENCY: usize = 4;
+
+/// Event emitted as each project finishes syncing in `sync_all_projects`
+pub const PROJECT_SYNC_PROGRESS_EVENT: &str = "project-sync-progress";
+
+/// Outcome of syncing one project's config
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectSyncReport {
+    pub project_id: i64,
+    pub project_name: String,
+    pub success: bool,
+    pub error: Option<String>,
+    /// Claude config writes queued for confirmation instead of written
+    pub pending_changes: Vec<PendingChange>,
+}
+
+/// Payload of [`PROJECT_SYNC_PROGRESS_EVENT`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectSyncProgress {
+    pub completed: usize,
+    pub total: usize,
+    pub report: ProjectSyncReport,
+}
+
+/// Regenerate every project's config files, a few projects at a time,
+/// emitting progress as each one finishes
+#[tauri::command]
+pub async fn sync_all_projects(
+    app: AppHandle,
+    db: State<'_, Arc<Mutex<Database>>>,
+) -> Result<Vec<ProjectSyncReport>, String> {
+    let plans = {
+        let db = db.lock().map_err(|e| e.to_string())?;
+        load_all_project_sync_plans(&db)?
+    };
+    info!("[Projects] Syncing config for {} projects", plans.len());
+
+    let reports = run_project_syncs(plans, |progress| {
+        let _ = app.emit(PROJECT_SYNC_PROGRESS_EVENT, progress);
+    })
+    .await;
+
+    let db = db.lock().map_err(|e| e.to_string())?;
+    for report in reports
+        .iter()
+        .filter(|r| r.success && r.pending_changes.is_empty())
+    {
+        mark_project_synced(&db, report.project_id)?;
+    }
+    Ok(reports)
+}
+
+/// A project with its sync plan, or why the plan couldn't be built
+pub(crate) type PlannedProjectSync = (Project, Result<ProjectSyncPlan, String>);
+
+/// Sync plans for every project, read in one pass under the database lock.
+/// A project whose plan can't be built is reported as failed.
+pub(crate) fn load_all_project_sync_plans(
+    db: &Database,
+) -> Result<Vec<PlannedProjectSync>, String> {
+    let projects = db.get_all_projects().map_err(|e| e.to_string())?;
+    Ok(projects
+        .into_iter()
+        .map(|project| {
+            let plan = load_project_sync_plan(db, project.id);
+            (project, plan)
+        })
+        .collect())
+}
+
+/// Write each plan on the blocking pool, at most `SYNC_ALL_CONCURRENCY` at a
+/// time. Reports come back in the order of `plans`; `on_progress` sees them
+/// in completion order.
+pub(crate) async fn run_project_syncs(
+    plans: Vec<PlannedProjectSync>,
+    on_progress: impl Fn(&ProjectSyncProgress),
+) -> Vec<ProjectSyncReport> {
+    let total = plans.len();
+    let semaphore = Arc::new(Semaphore::new(SYNC_ALL_CONCURRENCY));
+    let mut pending = JoinSet::new();
+    for (index, (project, plan)) in plans.into_iter().enumerate() {
+        let semaphore = Arc::clone(&semaphore);
+        pending.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let result = match plan {
+                Ok(plan) => tokio::task::spawn_blocking(move || write_project_sync_plan(&plan))
+                    .await
+                    .unwrap_or_else(|e| Err(format!("Sync panicked: {}", e))),
+                Err(e) => Err(e),
+            };
+            (
+                index,
+                match result {
+                    Ok(pending_changes) => ProjectSyncReport {
+                        project_id: project.id,
+                        project_name: project.name,
+                        success: true,
+                        error: None,
+                        pending_changes,
+                    },
+                    Err(e) => ProjectSyncReport {
+                        project_id: project.id,
+                        project_name: project.name,
+                        success: false,
+                        error: Some(e),
+                        pending_changes: Vec::new(),
+                    },
+                },
+            )
+        });
+    }
+
+    let mut reports: Vec<Option<ProjectSyncReport>> = vec![None; total];
+    let mut completed = 0;
+    while let Some(joined) = pending.join_next().await {
+        if let Ok((index, report)) = joined {
+            completed += 1;
+            if let Some(error) = &report.error {
+                warn!(
+                    "[Projects] Failed to sync project '{}': {}",
+                    report.project_name, error
+                );
+            }
+            on_progress(&ProjectSyncProgress {
+                completed,
+                total,
+                report: report.clone(),
+            });
+            reports[index] = Some(report);
+        }
+    }
+    reports.into_iter().flatten().collect()
+}
+
+/// Report skill and sub-agent tool references that no enabled MCP provides
+#[tauri::command]
+pub fn validate_tool_references(
+    db: State<'_, Arc<Mutex<Database>>>,
+    project_id: i64,
+) -> Result<ToolReferenceReport, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    get_project_by_id(&db, project_id)?;
+    tool_references::validate_tool_references(&db, project_id).map_err(|e| e.to_string())
+}
+
+/// Generate the project's `.cctm.json` from its current enabled assignments
+#[tauri::command]
+pub fn write_project_manifest(
+    db: State<'_, Arc<Mutex<Database>>>,
+    project_id: i64,
+) -> Result<String, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    write_project_manifest_from_db(&db, project_id)
+}
+
+/// Make the project's assignments and config files match its `.cctm.json`
+#[tauri::command]
+pub fn sync_project_from_manifest(
+    db: State<'_, Arc<Mutex<Database>>>,
+    project_id: i64,
+) -> Result<ManifestSyncResult, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    sync_project_from_manifest_in_db(&db, project_id)
+}
+
+pub(crate) fn write_project_manifest_from_db(
+    db: &Database,
+    project_id: i64,
+) -> Result<String, String> {
+    let project = get_project_by_id(db, project_id)?;
+    let manifest = project_manifest::manifest_from_db(db, project_id).map_err(|e| e.to_string())?;
+    let path = project_manifest::write_manifest(Path::new(&project.path), &manifest)
+        .map_err(|e| e.to_string())?;
+    info!(
+        "[Projects] Wrote manifest for project {} to {}",
+        project_id,
+        path.display()
+    );
+    Ok(path.to_string_lossy().to_string())
+}
+
+pub(crate) fn sync_project_from_manifest_in_db(
+    db: &Database,
+    project_id: i64,
+) -> Result<ManifestSyncResult, String> {
+    let project = get_project_by_id(db, project_id)?;
+    let project_path = Path::new(&project.path);
+    let manifest = project_manifest::read_manifest(project_path).map_err(|e| e.to_string())?;
+    let mut result =
+        project_manifest::apply_manifest(db, project_id, &manifest).map_err(|e| e.to_string())?;
+    info!(
+        "[Projects] Applied manifest to project {}: {} assigned, {} removed, {} missing",
+        project_id,
+        result.assigned.len(),
+        result.removed.len(),
+        result.missing.len()
+    );
+
+    result
+        .pending_changes
+        .extend(sync_project_config_from_db(db, project_id)?);
+    result
+        .pending_changes
+        .extend(crate::commands::hooks::sync_project_hooks(
+            db,
+            &project.path,
+        )?);
+    write_manifest_entity_files(db, project_path, &result)?;
+    Ok(result)
+}
+
+/// Write the files of newly assigned skills, sub-agents and commands and
+/// delete those of removed ones, for every enabled editor
+fn write_manifest_entity_files(
+    db: &Database,
+    project_path: &Path,
+    result: &ManifestSyncResult,
 ) -> Result<(), String> {
     use crate::commands::settings::get_enabled_editors_from_db;
-    use crate::services::{
-        codex_config, copilot_config, cursor_config, gemini_config, opencode_config,
+    use crate::commands::{
+        commands::get_command_by_id, skills::get_skill_by_id, subagents::get_subagent_by_id,
     };
-    use crate::utils::paths::get_claude_paths;
+    use crate::services::{command_writer, skill_writer, subagent_writer};
+
+    let editors = get_enabled_editors_from_db(db);
+    let changes = result
+        .assigned
+        .iter()
+        .map(|e| (e, true))
+        .chain(result.removed.iter().map(|e| (e, false)));
+    for (entity, write) in changes {
+        let Some(id) = entity.entity_id else {
+            continue;
+        };
+        for editor in editors.iter().map(String::as_str) {
+            let written = match (entity.entity_type.as_str(), editor) {
+                ("skill", "claude_code" | "opencode") => {
+                    let skill = get_skill_by_id(db, id)?;
+                    match (editor, write) {
+                        ("claude_code", true) => {
+                            skill_writer::write_project_skill(project_path, &skill)
+                        }
+                        ("claude_code", false) => {
+                            skill_writer::delete_project_skill(project_path, &skill)
+                        }
+                        (_, true) => {
+                            skill_writer::write_project_skill_opencode(project_path, &skill)
+                        }
+                        (_, false) => {
+                            skill_writer::delete_project_skill_opencode(project_path, &skill)
+                        }
+                    }
+                }
+                ("subagent", "claude_code" | "opencode") => {
+                    let subagent = get_subagent_by_id(db, id)?;
+                    match (editor, write) {
+                        ("claude_code", true) => {
+                            subagent_writer::write_project_subagent(project_path, &subagent)
+                        }
+                        ("claude_code", false) => subagent_writer::delete_project_subagent(
+                            project_path,
+                            subagent_writer::subagent_file_stem(&subagent),
+                        ),
+                        (_, true) => subagent_writer::write_project_subagent_opencode(
+                            project_path,
+                            &subagent,
+                        ),
+                        (_, false) => subagent_writer::delete_project_subagent_opencode(
+                            project_path,
+                            subagent_writer::subagent_file_stem(&subagent),
+                        ),
+                    }
+                }
+                ("command", "claude_code" | "opencode" | "windsurf") => {
+                    let command = get_command_by_id(db, id)?;
+                    match (editor, write) {
+                        ("claude_code", true) => {
+                            command_writer::write_project_command(project_path, &command)
+                        }
+                        ("claude_code", false) => {
+                            command_writer::delete_project_command(project_path, &command)
+                        }
+                        ("windsurf", true) => {
+                            command_writer::write_project_command_windsurf(project_path, &command)
+                        }
+                        ("windsurf", false) => {
+                            command_writer::delete_project_command_windsurf(project_path, &command)
+                        }
+                        (_, true) => {
+                            command_writer::write_project_command_opencode(project_path, &command)
+                        }
+                        (_, false) => {
+                            command_writer::delete_project_command_opencode(project_path, &command)
+                        }
+                    }
+                }
+                // MCPs and hooks are rewritten as a whole by their own sync
+                _ => Ok(()),
+            };
+            written.map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
 
+/// One project MCP as written to editor configs: name, type, command, args,
+/// url, headers, env, enabled
+pub(crate) type ProjectMcpRow = (
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    bool,
+);
+
+/// Everything needed to write a project's editor configs, read from the
+/// database up front so the writes can happen without holding it
+#[derive(Debug, Clone)]
+pub(crate) struct ProjectSyncPlan {
+    pub project_id: i64,
+    pub path: String,
+    pub mcps_with_enabled: Vec<ProjectMcpRow>,
+    pub enabled_editors: Vec<String>,
+}
+
+/// Write a project's MCPs to every enabled editor's project config
+/// Write a project's config files. The project is only marked synced once
+/// nothing is left waiting for confirmation.
+pub(crate) fn sync_project_config_from_db(
+    db: &Database,
+    project_id: i64,
+) -> Result<Vec<PendingChange>, String> {
     info!("[Projects] Syncing config for project id={}", project_id);
-    let db = db.lock().map_err(|e| e.to_string())?;
+
+    let plan = load_project_sync_plan(db, project_id)?;
+    let pending = write_project_sync_plan(&plan)?;
+    if pending.is_empty() {
+        mark_project_synced(db, project_id)?;
+    }
+    Ok(pending)
+}
+
+/// Read a project's path, MCP rows and the enabled editors
+pub(crate) fn load_project_sync_plan(
+    db: &Database,
+    project_id: i64,
+) -> Result<ProjectSyncPlan, String> {
+    use crate::commands::settings::{get_enabled_editors_from_db, get_inline_env_files_from_db};
+    use crate::services::mcp_client::resolve_env_file;
 
     // Get project path
     let path: String = db
@@ -271,10 +656,12 @@ pub fn sync_project_config(
         .map_err(|e| e.to_string())?;
 
     // Get ALL MCPs for this project (including disabled ones)
+    let inline_env_files = get_inline_env_files_from_db(db);
     let mut stmt = db
         .conn()
         .prepare(
-            "SELECT m.name, m.type, m.command, m.args, m.url, m.headers, m.env, pm.is_enabled
+            "SELECT m.name, m.type, m.command, m.args, m.url, m.headers, m.env, pm.is_enabled, m.env_file,
+                    m.source_path
              FROM project_mcps pm
              JOIN mcps m ON pm.mcp_id = m.id
              WHERE pm.project_id = ?
@@ -282,17 +669,13 @@ pub fn sync_project_config(
         )
         .map_err(|e| e.to_string())?;
 
-    let mcps_with_enabled: Vec<(
-        String,
-        String,
-        Option<String>,
-        Option<String>,
-        Option<String>,
-        Option<String>,
-        Option<String>,
-        bool,
-    )> = stmt
+    let mcps_with_enabled: Vec<ProjectMcpRow> = stmt
         .query_map([project_id], |row| {
+            let env: Option<String> = row.get(6)?;
+            let env_file: Option<String> = row.get(8)?;
+            let source_path: Option<String> = row.get(9)?;
+            let env_file = env_file
+                .map(|f| resolve_env_file(&f, source_path.as_deref(), Some(Path::new(&path))));
             Ok((
                 row.get(0)?,
                 row.get(1)?,
@@ -300,7 +683,7 @@ pub fn sync_project_config(
                 row.get(3)?,
                 row.get(4)?,
                 row.get(5)?,
-                row.get(6)?,
+                config_writer::merge_env_file(env, env_file.as_deref(), inline_env_files),
                 row.get::<_, i32>(7)? != 0,
             ))
         })
@@ -308,37 +691,206 @@ pub fn sync_project_config(
         .filter_map(|r| r.ok())
         .collect();
 
-    let project_path = PathBuf::from(&path);
+    Ok(ProjectSyncPlan {
+        project_id,
+        path,
+        mcps_with_enabled,
+        enabled_editors: get_enabled_editors_from_db(db),
+    })
+}
+
+/// A project MCP row without its enabled flag, as the config writers take it
+type ProjectMcpTuple = (
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+/// Split project MCP rows into (enabled, disabled), dropping the flag
+fn split_by_enabled(rows: &[ProjectMcpRow]) -> (Vec<ProjectMcpTuple>, Vec<ProjectMcpTuple>) {
+    let mut enabled_mcps = Vec::new();
+    let mut disabled_mcps = Vec::new();
+    for (n, t, cmd, args, url, headers, env, enabled) in rows {
+        let mcp = (
+            n.clone(),
+            t.clone(),
+            cmd.clone(),
+            args.clone(),
+            url.clone(),
+            headers.clone(),
+            env.clone(),
+        );
+        if *enabled {
+            enabled_mcps.push(mcp);
+        } else {
+            disabled_mcps.push(mcp);
+        }
+    }
+    (enabled_mcps, disabled_mcps)
+}
+
+/// SHA-256 (lowercase hex) of the `.mcp.json` a sync would write for the
+/// project right now, merged into its current file. Matches `sha256sum
+/// .mcp.json` once synced, so CI can detect a committed file that's drifted.
+pub(crate) fn get_project_config_checksum_from_db(
+    db: &Database,
+    project_id: i64,
+) -> Result<String, String> {
+    use sha2::{Digest, Sha256};
+
+    let plan = load_project_sync_plan(db, project_id)?;
+    let (enabled_mcps, disabled_mcps) = split_by_enabled(&plan.mcps_with_enabled);
+    let project_path = Path::new(&plan.path);
+    let content = match config_writer::render_project_config_with_disabled(
+        project_path,
+        &enabled_mcps,
+        &disabled_mcps,
+    )
+    .map_err(|e| e.to_string())?
+    {
+        Some(content) => content,
+        // Nothing to sync leaves the file as it is
+        None => read_config_file(&project_path.join(".mcp.json"))
+            .map_err(|e| e.to_string())?
+            .unwrap_or_default(),
+    };
+
+    Ok(Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// Preview what syncing a project would write to each of `editor`'s project
+/// MCP config files, without touching disk. Claude Code gets two: `.mcp.json`
+/// and the project's entry in ~/.claude.json.
+#[tauri::command]
+pub fn preview_project_mcp_config(
+    db: State<'_, Arc<Mutex<Database>>>,
+    project_id: i64,
+    editor: String,
+) -> Result<Vec<McpConfigPreview>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    let claude_paths = get_claude_paths().map_err(|e| e.to_string())?;
+    preview_project_mcp_config_from_db(&db, &claude_paths, project_id, &editor)
+}
+
+pub(crate) fn preview_project_mcp_config_from_db(
+    db: &Database,
+    claude_paths: &ClaudePathsInternal,
+    project_id: i64,
+    editor: &str,
+) -> Result<Vec<McpConfigPreview>, String> {
+    use crate::services::{
+        codex_config, copilot_config, cursor_config, gemini_config, opencode_config,
+    };
+    use crate::utils::{codex_paths, copilot_paths, cursor_paths, gemini_paths};
+
+    let plan = load_project_sync_plan(db, project_id)?;
+    let project_path = PathBuf::from(&plan.path);
+    let (enabled_mcps, disabled_mcps) = split_by_enabled(&plan.mcps_with_enabled);
+
+    let preview = |path: &Path, rendered: anyhow::Result<Option<String>>| {
+        build_mcp_config_preview(editor, path, rendered.map_err(|e| e.to_string())?)
+    };
+
+    match editor {
+        "claude_code" => Ok(vec![
+            preview(
+                &project_path.join(".mcp.json"),
+                config_writer::render_project_config_with_disabled(
+                    &project_path,
+                    &enabled_mcps,
+                    &disabled_mcps,
+                ),
+            )?,
+            preview(
+                &claude_paths.claude_json,
+                config_writer::render_project_to_claude_json(
+                    claude_paths,
+                    &plan.path,
+                    &plan.mcps_with_enabled,
+                )
+                .map(Some),
+            )?,
+        ]),
+        "opencode" => {
+            let path = opencode_config::opencode_project_config_path(&project_path);
+            let content = opencode_config::render_opencode_config(&path, &enabled_mcps);
+            Ok(vec![preview(&path, content.map(Some))?])
+        }
+        "codex" => {
+            let path = codex_paths::project_codex_config(&project_path);
+            let content = codex_config::render_codex_config(&path, &enabled_mcps);
+            Ok(vec![preview(&path, content)?])
+        }
+        "copilot" => {
+            let path = copilot_paths::project_copilot_mcp_config(&project_path);
+            let content = copilot_config::render_copilot_config(&path, &enabled_mcps);
+            Ok(vec![preview(&path, content)?])
+        }
+        "cursor" => {
+            let path = cursor_paths::project_cursor_mcp_config(&project_path);
+            let content = cursor_config::render_cursor_config(&path, &enabled_mcps);
+            Ok(vec![preview(&path, content)?])
+        }
+        "gemini" => {
+            let path = gemini_paths::project_gemini_settings(&project_path);
+            let content = gemini_config::render_gemini_config(&path, &enabled_mcps);
+            Ok(vec![preview(&path, content)?])
+        }
+        "windsurf" => Err("Windsurf has no project MCP config".to_string()),
+        unknown => Err(format!("Unknown editor type '{}'", unknown)),
+    }
+}
+
+/// Write a project's config for every editor in the plan. Touches only the
+/// filesystem, so plans for different projects can be written in parallel.
+/// Returns the Claude config writes queued for confirmation.
+pub(crate) fn write_project_sync_plan(
+    plan: &ProjectSyncPlan,
+) -> Result<Vec<PendingChange>, String> {
+    use crate::services::{
+        codex_config, copilot_config, cursor_config, gemini_config, opencode_config,
+    };
+    use crate::utils::{codex_paths, copilot_paths, cursor_paths, gemini_paths};
+
+    let ProjectSyncPlan {
+        project_id,
+        path,
+        mcps_with_enabled,
+        enabled_editors,
+    } = plan;
+    let project_path = PathBuf::from(path);
+    let mut pending = Vec::new();
 
     // Write to all enabled editors
-    let enabled_editors = get_enabled_editors_from_db(&db);
-    for editor in &enabled_editors {
+    for editor in enabled_editors {
         match editor.as_str() {
             "claude_code" => {
                 // Claude Code: Write to claude.json (includes disabled state)
                 let paths = get_claude_paths().map_err(|e| e.to_string())?;
-                config_writer::write_project_to_claude_json(&paths, &path, &mcps_with_enabled)
-                    .map_err(|e| e.to_string())?;
-
-                // Also write .mcp.json for enabled MCPs only (legacy support)
-                let enabled_mcps: Vec<_> = mcps_with_enabled
-                    .iter()
-                    .filter(|(_, _, _, _, _, _, _, enabled)| *enabled)
-                    .map(|(n, t, cmd, args, url, headers, env, _)| {
-                        (
-                            n.clone(),
-                            t.clone(),
-                            cmd.clone(),
-                            args.clone(),
-                            url.clone(),
-                            headers.clone(),
-                            env.clone(),
-                        )
-                    })
-                    .collect();
+                pending.extend(
+                    config_writer::write_project_to_claude_json(&paths, path, mcps_with_enabled)
+                        .map_err(|e| e.to_string())?,
+                );
 
-                config_writer::write_project_config(&project_path, &enabled_mcps)
-                    .map_err(|e| e.to_string())?;
+                // Also write .mcp.json for enabled MCPs (legacy support). Disabled
+                // MCPs are only kept where the file marks the entry `disabled`.
+                let (enabled_mcps, disabled_mcps) = split_by_enabled(mcps_with_enabled);
+
+                pending.extend(
+                    config_writer::write_project_config_with_disabled(
+                        &project_path,
+                        &enabled_mcps,
+                        &disabled_mcps,
+                    )
+                    .map_err(|e| e.to_string())?,
+                );
 
                 info!(
                     "[Projects] Wrote Claude Code config for project {}",
@@ -389,7 +941,7 @@ pub fn sync_project_config(
                     })
                     .collect();
 
-                let codex_config_path = project_path.join(".codex").join("config.toml");
+                let codex_config_path = codex_paths::project_codex_config(&project_path);
                 codex_config::write_codex_config(&codex_config_path, &enabled_mcps)
                     .map_err(|e| e.to_string())?;
 
@@ -416,7 +968,7 @@ pub fn sync_project_config(
                     })
                     .collect();
 
-                let copilot_config_path = project_path.join(".copilot").join("mcp-config.json");
+                let copilot_config_path = copilot_paths::project_copilot_mcp_config(&project_path);
                 copilot_config::write_copilot_config(&copilot_config_path, &enabled_mcps)
                     .map_err(|e| e.to_string())?;
 
@@ -443,7 +995,7 @@ pub fn sync_project_config(
                     })
                     .collect();
 
-                let cursor_config_path = project_path.join(".cursor").join("mcp.json");
+                let cursor_config_path = cursor_paths::project_cursor_mcp_config(&project_path);
                 cursor_config::write_cursor_config(&cursor_config_path, &enabled_mcps)
                     .map_err(|e| e.to_string())?;
 
@@ -467,7 +1019,7 @@ pub fn sync_project_config(
                     })
                     .collect();
 
-                let gemini_config_path = project_path.join(".gemini").join("settings.json");
+                let gemini_config_path = gemini_paths::project_gemini_settings(&project_path);
                 gemini_config::write_gemini_config(&gemini_config_path, &enabled_mcps)
                     .map_err(|e| e.to_string())?;
 
@@ -476,6 +1028,14 @@ pub fn sync_project_config(
                     project_id
                 );
             }
+            "windsurf" => {
+                // Windsurf only reads the global mcp_config.json; there is no
+                // per-project MCP file to write
+                info!(
+                    "[Projects] Windsurf has no project MCP config; skipping project {}",
+                    project_id
+                );
+            }
             unknown => warn!(
                 "[Projects] Unknown editor type '{}' for project '{}'. Skipping.",
                 unknown, path
@@ -483,14 +1043,17 @@ pub fn sync_project_config(
         }
     }
 
-    // Update has_mcp_file flag
+    Ok(pending)
+}
+
+/// Record that a project's config file has been written
+pub(crate) fn mark_project_synced(db: &Database, project_id: i64) -> Result<(), String> {
     db.conn()
         .execute(
             "UPDATE projects SET has_mcp_file = 1, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
             [project_id],
         )
         .map_err(|e| e.to_string())?;
-
     Ok(())
 }
 
@@ -544,7 +1107,6 @@ pub(crate) fn get_project_by_id(db: &Database, id: i64) -> Result<Project, Strin
 }
 
 /// Get a project by path from the database
-#[cfg_attr(not(test), allow(dead_code))]
 pub(crate) fn get_project_by_path(db: &Database, path: &str) -> Result<Project, String> {
     db.conn()
         .query_row(
@@ -650,6 +1212,13 @@ pub fn open_folder(path: String) -> Result<(), String> {
     Ok(())
 }
 
+/// Git root, branch and origin remote for a project path; `None` outside a
+/// git repo
+#[tauri::command]
+pub fn get_git_info(path: String) -> Result<Option<GitInfo>, String> {
+    Ok(git::get_git_info(Path::new(&path)))
+}
+
 #[tauri::command]
 pub fn update_project_editor_type(
     db: State<'_, Arc<Mutex<Database>>>,
@@ -687,7 +1256,7 @@ fn get_project_assigned_mcps(db: &Database, project_id: i64) -> Vec<ProjectMcp>
         let mut stmt = db.conn().prepare(
             "SELECT pm.id, pm.mcp_id, pm.is_enabled, pm.env_overrides, pm.display_order,
                     m.id, m.name, m.description, m.type, m.command, m.args, m.url, m.headers, m.env,
-                    m.icon, m.tags, m.source, m.source_path, m.is_enabled_global, m.is_favorite, m.created_at, m.updated_at
+                    m.icon, m.tags, m.source, m.source_path, m.is_enabled_global, m.is_favorite, m.created_at, m.updated_at, m.testable, m.env_file, m.display_name, m.homepage, m.inherit_env, m.shell
              FROM project_mcps pm
              JOIN mcps m ON pm.mcp_id = m.id
              WHERE pm.project_id = ?
@@ -780,7 +1349,7 @@ pub(crate) fn get_project_mcps_from_db(
         .prepare(
             "SELECT pm.id, pm.mcp_id, pm.is_enabled, pm.env_overrides, pm.display_order,
                     m.id, m.name, m.description, m.type, m.command, m.args, m.url, m.headers, m.env,
-                    m.icon, m.tags, m.source, m.source_path, m.is_enabled_global, m.is_favorite, m.created_at, m.updated_at
+                    m.icon, m.tags, m.source, m.source_path, m.is_enabled_global, m.is_favorite, m.created_at, m.updated_at, m.testable, m.env_file, m.display_name, m.homepage, m.inherit_env, m.shell
              FROM project_mcps pm
              JOIN mcps m ON pm.mcp_id = m.id
              WHERE pm.project_id = ?
@@ -928,6 +1497,12 @@ mod tests {
             env: None,
             icon: None,
             tags: None,
+            testable: None,
+            env_file: None,
+            display_name: None,
+            inherit_env: None,
+            shell: None,
+            split_command: None,
         };
         create_mcp_in_db(db, &mcp).unwrap().id
     }
@@ -1079,4 +1654,286 @@ mod tests {
             .unwrap();
         assert_eq!(count, 0);
     }
+
+    #[test]
+    fn test_sync_project_from_manifest_updates_assignments_and_files() {
+        let db = Database::in_memory().unwrap();
+        db.set_setting("enabled_editors", r#"["opencode"]"#)
+            .unwrap();
+        let dir = tempfile::TempDir::new().unwrap();
+        let project = create_project_in_db(
+            &db,
+            &CreateProjectRequest {
+                name: "app".to_string(),
+                path: dir.path().to_string_lossy().to_string(),
+            },
+        )
+        .unwrap();
+        for name in ["github", "slack"] {
+            db.conn()
+                .execute(
+                    "INSERT INTO mcps (name, type, command) VALUES (?, 'stdio', 'npx')",
+                    [name],
+                )
+                .unwrap();
+        }
+        db.conn()
+            .execute(
+                "INSERT INTO skills (name, description, content) VALUES ('review', 'd', 'Review the diff')",
+                [],
+            )
+            .unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO project_mcps (project_id, mcp_id) VALUES (?, 1)",
+                [project.id],
+            )
+            .unwrap();
+
+        let path = write_project_manifest_from_db(&db, project.id).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.contains("\"github\""));
+
+        std::fs::write(
+            &path,
+            r#"{"version": 1, "mcps": ["slack"], "skills": ["review"]}"#,
+        )
+        .unwrap();
+        let result = sync_project_from_manifest_in_db(&db, project.id).unwrap();
+        assert_eq!(result.assigned.len(), 2);
+        assert_eq!(result.removed.len(), 1);
+        assert!(result.missing.is_empty());
+
+        let mcps: Vec<String> = get_project_mcps_from_db(&db, project.id)
+            .unwrap()
+            .into_iter()
+            .map(|pm| pm.mcp.name)
+            .collect();
+        assert_eq!(mcps, vec!["slack"]);
+        let opencode = std::fs::read_to_string(dir.path().join("opencode.json")).unwrap();
+        assert!(opencode.contains("slack"));
+        assert!(!opencode.contains("github"));
+        assert!(dir.path().join(".opencode/skill/review/SKILL.md").exists());
+    }
+
+    #[tokio::test]
+    async fn test_run_project_syncs_reports_each_project() {
+        let db = Database::in_memory().unwrap();
+        db.set_setting("enabled_editors", r#"["opencode"]"#)
+            .unwrap();
+        let dir = tempfile::TempDir::new().unwrap();
+        let good = dir.path().join("good");
+        std::fs::create_dir(&good).unwrap();
+        // A file where the project directory should be, so writing fails
+        let broken = dir.path().join("broken");
+        std::fs::write(&broken, "not a dir").unwrap();
+
+        db.conn()
+            .execute(
+                "INSERT INTO mcps (name, type, command) VALUES ('github', 'stdio', 'npx')",
+                [],
+            )
+            .unwrap();
+        for (name, path) in [("good", &good), ("broken", &broken)] {
+            let project = create_project_in_db(
+                &db,
+                &CreateProjectRequest {
+                    name: name.to_string(),
+                    path: path.to_string_lossy().to_string(),
+                },
+            )
+            .unwrap();
+            assign_mcp_to_project_in_db(&db, project.id, 1).unwrap();
+        }
+
+        let plans = load_all_project_sync_plans(&db).unwrap();
+        let progress = Mutex::new(Vec::new());
+        let reports = run_project_syncs(plans, |p| {
+            progress.lock().unwrap().push((p.completed, p.total));
+        })
+        .await;
+
+        let by_name = |name: &str| reports.iter().find(|r| r.project_name == name).unwrap();
+        assert!(by_name("good").success);
+        assert!(!by_name("broken").success);
+        assert!(by_name("broken").error.is_some());
+        let opencode = std::fs::read_to_string(good.join("opencode.json")).unwrap();
+        assert!(opencode.contains("github"));
+
+        let mut progress = progress.into_inner().unwrap();
+        progress.sort();
+        assert_eq!(progress, vec![(1, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn test_project_config_checksum_matches_written_file() {
+        use sha2::{Digest, Sha256};
+
+        let db = Database::in_memory().unwrap();
+        let dir = tempfile::TempDir::new().unwrap();
+        let project = create_project_in_db(
+            &db,
+            &CreateProjectRequest {
+                name: "app".to_string(),
+                path: dir.path().to_string_lossy().to_string(),
+            },
+        )
+        .unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO mcps (name, type, command) VALUES ('github', 'stdio', 'npx')",
+                [],
+            )
+            .unwrap();
+        assign_mcp_to_project_in_db(&db, project.id, 1).unwrap();
+
+        let checksum = get_project_config_checksum_from_db(&db, project.id).unwrap();
+        assert_eq!(checksum.len(), 64);
+        // Computing it doesn't write anything
+        assert!(!dir.path().join(".mcp.json").exists());
+        assert_eq!(
+            get_project_config_checksum_from_db(&db, project.id).unwrap(),
+            checksum
+        );
+
+        let plan = load_project_sync_plan(&db, project.id).unwrap();
+        let (enabled_mcps, disabled_mcps) = split_by_enabled(&plan.mcps_with_enabled);
+        config_writer::write_project_config_with_disabled(
+            dir.path(),
+            &enabled_mcps,
+            &disabled_mcps,
+        )
+        .unwrap();
+        let written = std::fs::read(dir.path().join(".mcp.json")).unwrap();
+        let written_hash: String = Sha256::digest(&written)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        assert_eq!(written_hash, checksum);
+
+        db.conn()
+            .execute("UPDATE mcps SET command = 'uvx' WHERE id = 1", [])
+            .unwrap();
+        assert_ne!(
+            get_project_config_checksum_from_db(&db, project.id).unwrap(),
+            checksum
+        );
+    }
+
+    fn temp_claude_paths(dir: &Path) -> ClaudePathsInternal {
+        ClaudePathsInternal {
+            home: dir.to_path_buf(),
+            claude_json: dir.join(".claude.json"),
+            claude_dir: dir.join(".claude"),
+            global_settings: dir.join(".claude").join("settings.json"),
+            plugins_dir: dir.join(".claude").join("plugins"),
+            marketplaces_dir: dir.join(".claude").join("plugins").join("marketplaces"),
+            commands_dir: dir.join(".claude").join("commands"),
+            skills_dir: dir.join(".claude").join("skills"),
+            agents_dir: dir.join(".claude").join("agents"),
+            rules_dir: dir.join(".claude").join("rules"),
+        }
+    }
+
+    #[test]
+    fn test_preview_project_mcp_config_matches_write_without_writing() {
+        use crate::services::cursor_config;
+
+        let db = Database::in_memory().unwrap();
+        let dir = tempfile::TempDir::new().unwrap();
+        let home = tempfile::TempDir::new().unwrap();
+        let claude_paths = temp_claude_paths(home.path());
+        let project = create_project_in_db(
+            &db,
+            &CreateProjectRequest {
+                name: "app".to_string(),
+                path: dir.path().to_string_lossy().to_string(),
+            },
+        )
+        .unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO mcps (name, type, command) VALUES ('github', 'stdio', 'npx')",
+                [],
+            )
+            .unwrap();
+        assign_mcp_to_project_in_db(&db, project.id, 1).unwrap();
+
+        let previews =
+            preview_project_mcp_config_from_db(&db, &claude_paths, project.id, "cursor").unwrap();
+        assert_eq!(previews.len(), 1);
+        let preview = &previews[0];
+        let cursor_path = dir.path().join(".cursor").join("mcp.json");
+        assert!(!cursor_path.exists());
+        assert!(preview.changed);
+        assert!(preview.before.is_none());
+        assert!(preview.diff.contains("+"));
+
+        let plan = load_project_sync_plan(&db, project.id).unwrap();
+        let (enabled_mcps, _) = split_by_enabled(&plan.mcps_with_enabled);
+        cursor_config::write_cursor_config(&cursor_path, &enabled_mcps).unwrap();
+        assert_eq!(
+            preview.after.as_deref(),
+            Some(std::fs::read_to_string(&cursor_path).unwrap().as_str())
+        );
+
+        let previews =
+            preview_project_mcp_config_from_db(&db, &claude_paths, project.id, "cursor").unwrap();
+        assert!(!previews[0].changed);
+        assert!(
+            preview_project_mcp_config_from_db(&db, &claude_paths, project.id, "windsurf").is_err()
+        );
+    }
+
+    #[test]
+    fn test_preview_project_mcp_config_claude_code_includes_claude_json() {
+        let db = Database::in_memory().unwrap();
+        let dir = tempfile::TempDir::new().unwrap();
+        let home = tempfile::TempDir::new().unwrap();
+        let claude_paths = temp_claude_paths(home.path());
+        let project = create_project_in_db(
+            &db,
+            &CreateProjectRequest {
+                name: "app".to_string(),
+                path: dir.path().to_string_lossy().to_string(),
+            },
+        )
+        .unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO mcps (name, type, command) VALUES ('github', 'stdio', 'npx')",
+                [],
+            )
+            .unwrap();
+        assign_mcp_to_project_in_db(&db, project.id, 1).unwrap();
+
+        let previews =
+            preview_project_mcp_config_from_db(&db, &claude_paths, project.id, "claude_code")
+                .unwrap();
+        let paths: Vec<&str> = previews.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                dir.path().join(".mcp.json").to_string_lossy().as_ref(),
+                claude_paths.claude_json.to_string_lossy().as_ref(),
+            ]
+        );
+        assert!(!claude_paths.claude_json.exists());
+
+        let plan = load_project_sync_plan(&db, project.id).unwrap();
+        config_writer::write_project_to_claude_json(
+            &claude_paths,
+            &plan.path,
+            &plan.mcps_with_enabled,
+        )
+        .unwrap();
+        assert_eq!(
+            previews[1].after.as_deref(),
+            Some(
+                std::fs::read_to_string(&claude_paths.claude_json)
+                    .unwrap()
+                    .as_str()
+            )
+        );
+    }
 }
@@ -1,12 +1,25 @@
-use crate::commands::settings::get_github_token_from_db;
+use crate::commands::commands::get_command_by_id;
+use crate::commands::config::sync_global_config_from_db;
+use crate::commands::hooks::{sync_global_hooks, sync_project_hooks};
+use crate::commands::models;
+use crate::commands::projects::sync_project_config_from_db;
+use crate::commands::settings::{get_enabled_editors_from_db, get_github_token_from_db};
 use crate::db::{
-    CreateRepoRequest, Database, ImportResult, RateLimitInfo, Repo, RepoItem, SyncResult,
+    BulkImportItem, Command, CreateRepoRequest, Database, ImportConflictPolicy, ImportResult,
+    RateLimitInfo, Repo, RepoItem, RepoPreview, RepoResetEntry, RepoResetPlan, SyncResult,
 };
+use crate::services::command_writer;
+use crate::services::config_parser::{self, ParsedMcp};
 use crate::services::github_client::{parse_github_url, GitHubClient};
+use crate::services::mcp_conflicts::McpDefinition;
+use crate::services::pending_changes::{PendingChange, WithPendingChanges};
+use crate::services::plugin_hooks;
 use crate::services::repo_parser::parse_frontmatter;
-use crate::services::repo_sync;
+use crate::services::repo_sync::{self, RepoImport};
+use crate::services::scanner::parse_agent_tools;
 use chrono::Utc;
-use rusqlite::params;
+use rusqlite::{params, OptionalExtension};
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use tauri::State;
 
@@ -163,6 +176,80 @@ pub async fn sync_all_repos(db: State<'_, Arc<Mutex<Database>>>) -> Result<SyncR
     })
 }
 
+/// Fetch a repo's items into memory without adding or syncing it, so a
+/// community repo can be vetted first. Uses the stored GitHub token; the
+/// repo defaults to file-based with mixed content when types are omitted.
+#[tauri::command]
+pub async fn preview_repo(
+    db: State<'_, Arc<Mutex<Database>>>,
+    url: String,
+    git_ref: Option<String>,
+    repo_type: Option<String>,
+    content_type: Option<String>,
+) -> Result<RepoPreview, String> {
+    let (repo, token, already_added) = {
+        let db = db.lock().map_err(|e| e.to_string())?;
+        let repo = preview_repo_target(&url, repo_type, content_type)?;
+        let already_added = repo_sync::get_all_repos(&db)
+            .map_err(|e| e.to_string())?
+            .iter()
+            .any(|r| {
+                r.owner.eq_ignore_ascii_case(&repo.owner) && r.repo.eq_ignore_ascii_case(&repo.repo)
+            });
+        (repo, get_github_token_from_db(&db), already_added)
+    };
+
+    let git_ref = git_ref
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty());
+    let items = repo_sync::fetch_repo_items_at(&repo, token, git_ref.clone())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(repo_sync::build_repo_preview(
+        &repo,
+        git_ref.as_deref(),
+        items,
+        already_added,
+    ))
+}
+
+/// An unsaved repo to preview
+pub(crate) fn preview_repo_target(
+    url: &str,
+    repo_type: Option<String>,
+    content_type: Option<String>,
+) -> Result<Repo, String> {
+    let (owner, repo) = parse_github_url(url).ok_or_else(|| "Invalid GitHub URL".to_string())?;
+    let repo_type = repo_type.unwrap_or_else(|| "file_based".to_string());
+    if !matches!(repo_type.as_str(), "file_based" | "readme_based") {
+        return Err(format!("Unknown repo type '{}'", repo_type));
+    }
+    let content_type = content_type.unwrap_or_else(|| "mixed".to_string());
+    if !matches!(
+        content_type.as_str(),
+        "skill" | "subagent" | "mcp" | "mixed"
+    ) {
+        return Err(format!("Unknown content type '{}'", content_type));
+    }
+    let now = Utc::now().to_rfc3339();
+    Ok(Repo {
+        id: 0,
+        name: format!("{}/{}", owner, repo),
+        owner,
+        repo,
+        repo_type,
+        content_type,
+        github_url: url.trim().to_string(),
+        description: None,
+        is_default: false,
+        is_enabled: true,
+        last_fetched_at: None,
+        etag: None,
+        created_at: now.clone(),
+        updated_at: now,
+    })
+}
+
 /// Fetch raw content from a GitHub URL (converts blob URLs to raw URLs)
 async fn fetch_content_from_url(url: &str) -> Result<String, String> {
     let raw_url = convert_to_raw_url(url);
@@ -188,6 +275,17 @@ async fn fetch_content_from_url(url: &str) -> Result<String, String> {
         .map_err(|e| format!("Failed to read content: {}", e))
 }
 
+/// Content to import for an item, fetching it when the repo only listed a URL
+async fn item_content(item: &RepoItem) -> Result<String, String> {
+    match item.raw_content.as_deref() {
+        Some(content) if !content.trim().is_empty() => Ok(content.to_string()),
+        _ => match item.source_url.as_deref() {
+            Some(url) => fetch_content_from_url(url).await,
+            None => Err("No content available for this item".to_string()),
+        },
+    }
+}
+
 /// Import a repository item to the local library
 #[tauri::command]
 pub async fn import_repo_item(
@@ -195,65 +293,397 @@ pub async fn import_repo_item(
     item_id: i64,
 ) -> Result<ImportResult, String> {
     // Get the repo item (scope the lock)
-    let item: RepoItem = {
+    let (item, import) = {
         let db = db.lock().map_err(|e| e.to_string())?;
-        db.conn()
-            .query_row(
-                r#"SELECT id, repo_id, item_type, name, description, source_url, raw_content,
-                          file_path, metadata, stars, is_imported, imported_item_id, created_at, updated_at
-                   FROM repo_items WHERE id = ?"#,
-                params![item_id],
-                |row| {
-                    Ok(RepoItem {
-                        id: row.get(0)?,
-                        repo_id: row.get(1)?,
-                        item_type: row.get(2)?,
-                        name: row.get(3)?,
-                        description: row.get(4)?,
-                        source_url: row.get(5)?,
-                        raw_content: row.get(6)?,
-                        file_path: row.get(7)?,
-                        metadata: row.get(8)?,
-                        stars: row.get(9)?,
-                        is_imported: row.get::<_, i32>(10)? != 0,
-                        imported_item_id: row.get(11)?,
-                        created_at: row.get(12)?,
-                        updated_at: row.get(13)?,
-                    })
-                },
-            )
-            .map_err(|e| e.to_string())?
+        let item = get_repo_item_by_id(&db, item_id)?;
+        let import = repo_sync::get_import(&db, &item).map_err(|e| e.to_string())?;
+        (item, import)
     };
 
-    if item.is_imported {
+    if item.is_imported || import.is_some() {
         return Ok(ImportResult {
             success: false,
             item_type: item.item_type,
-            item_id: item.imported_item_id.unwrap_or(0),
+            item_id: import
+                .map(|i| i.entity_id)
+                .or(item.imported_item_id)
+                .unwrap_or(0),
             message: Some("Item already imported".to_string()),
         });
     }
 
-    // If raw_content is empty but we have a source_url, fetch the content
-    let raw_content = match item.raw_content {
-        Some(content) if !content.trim().is_empty() => content,
-        _ => {
-            if let Some(ref url) = item.source_url {
-                fetch_content_from_url(url).await?
+    let raw_content = item_content(&item).await?;
+
+    // Re-acquire lock for database operations
+    let db = db.lock().map_err(|e| e.to_string())?;
+    import_item_content_in_db(&db, &item, &raw_content, None)
+}
+
+/// Re-import the latest version of a marketplace item over the entity it
+/// was previously imported as
+#[tauri::command]
+pub async fn update_imported_item(
+    db: State<'_, Arc<Mutex<Database>>>,
+    repo_item_id: i64,
+) -> Result<WithPendingChanges<ImportResult>, String> {
+    let (item, import) = {
+        let db = db.lock().map_err(|e| e.to_string())?;
+        let item = get_repo_item_by_id(&db, repo_item_id)?;
+        let import = repo_sync::get_import(&db, &item)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("'{}' has not been imported", item.name))?;
+        (item, import)
+    };
+
+    let raw_content = item_content(&item).await?;
+
+    let db = db.lock().map_err(|e| e.to_string())?;
+    update_imported_item_in_db(&db, &item, &raw_content, &import)
+}
+
+/// Overwrite the entity `item` was imported as with `raw_content` and
+/// rewrite its files wherever it is enabled
+pub(crate) fn update_imported_item_in_db(
+    db: &Database,
+    item: &RepoItem,
+    raw_content: &str,
+    import: &RepoImport,
+) -> Result<WithPendingChanges<ImportResult>, String> {
+    let result = import_item_content_in_db(db, item, raw_content, Some(import))?;
+    let pending_changes = resync_imported_entity(db, &import.entity_type, result.item_id)?;
+    Ok(WithPendingChanges {
+        item: result,
+        pending_changes,
+    })
+}
+
+/// Import every item of a synced repo (optionally only `item_types`) in one
+/// transaction, resolving name clashes with `on_conflict`
+#[tauri::command]
+pub async fn import_all_repo_items(
+    db: State<'_, Arc<Mutex<Database>>>,
+    repo_id: i64,
+    item_types: Option<Vec<String>>,
+    on_conflict: ImportConflictPolicy,
+) -> Result<Vec<BulkImportItem>, String> {
+    let items: Vec<RepoItem> = {
+        let db = db.lock().map_err(|e| e.to_string())?;
+        get_repo_impl(&db, repo_id)?;
+        repo_sync::get_repo_items(&db, repo_id)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .filter(|item| {
+                item_types
+                    .as_ref()
+                    .is_none_or(|types| types.contains(&item.item_type))
+            })
+            .collect()
+    };
+
+    // Fetch content up front so the lock isn't held across network calls
+    let mut prepared = Vec::with_capacity(items.len());
+    for item in items {
+        let content = if item.installed || item.is_imported {
+            Ok(String::new())
+        } else {
+            item_content(&item).await
+        };
+        prepared.push((item, content));
+    }
+
+    let db = db.lock().map_err(|e| e.to_string())?;
+    import_all_repo_items_in_db(&db, &prepared, on_conflict)
+}
+
+/// Import `items` (each with its fetched content or the fetch error) in one
+/// transaction. Per-item failures are reported rather than aborting the rest.
+pub(crate) fn import_all_repo_items_in_db(
+    db: &Database,
+    items: &[(RepoItem, Result<String, String>)],
+    on_conflict: ImportConflictPolicy,
+) -> Result<Vec<BulkImportItem>, String> {
+    let tx = db
+        .conn()
+        .unchecked_transaction()
+        .map_err(|e| e.to_string())?;
+    let results = items
+        .iter()
+        .map(|(item, content)| {
+            bulk_import_item(db, item, content, on_conflict).unwrap_or_else(|e| BulkImportItem {
+                repo_item_id: item.id,
+                name: item.name.clone(),
+                item_type: item.item_type.clone(),
+                status: "failed".to_string(),
+                entity_id: None,
+                imported_as: None,
+                message: Some(e),
+            })
+        })
+        .collect();
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(results)
+}
+
+fn bulk_import_item(
+    db: &Database,
+    item: &RepoItem,
+    content: &Result<String, String>,
+    on_conflict: ImportConflictPolicy,
+) -> Result<BulkImportItem, String> {
+    let outcome = |status: &str, entity_id: Option<i64>| BulkImportItem {
+        repo_item_id: item.id,
+        name: item.name.clone(),
+        item_type: item.item_type.clone(),
+        status: status.to_string(),
+        entity_id,
+        imported_as: None,
+        message: None,
+    };
+
+    if let Some(import) = repo_sync::get_import(db, item).map_err(|e| e.to_string())? {
+        return Ok(outcome("already_imported", Some(import.entity_id)));
+    }
+    if item.is_imported {
+        return Ok(outcome("already_imported", item.imported_item_id));
+    }
+
+    let raw_content = content.as_deref().map_err(|e| e.clone())?;
+    let entity_type = target_entity_type(item, raw_content)?;
+    let table = repo_sync::entity_table(entity_type).ok_or("Unknown item type")?;
+    let Some(existing_id) = entity_id_by_name(db, table, &item.name)? else {
+        let (_, id) = write_item_entity(db, item, &item.name, raw_content, None)?;
+        link_import(db, item, entity_type, id, raw_content)?;
+        return Ok(outcome("imported", Some(id)));
+    };
+
+    match on_conflict {
+        ImportConflictPolicy::Skip => Ok(BulkImportItem {
+            message: Some(format!(
+                "A {} named '{}' already exists",
+                entity_type, item.name
+            )),
+            ..outcome("skipped", Some(existing_id))
+        }),
+        ImportConflictPolicy::Link => {
+            link_import(db, item, entity_type, existing_id, raw_content)?;
+            Ok(outcome("linked", Some(existing_id)))
+        }
+        ImportConflictPolicy::Rename => {
+            let mut suffix = 2;
+            let name = loop {
+                let candidate = format!("{}-{}", item.name, suffix);
+                if entity_id_by_name(db, table, &candidate)?.is_none() {
+                    break candidate;
+                }
+                suffix += 1;
+            };
+            let (_, id) = write_item_entity(db, item, &name, raw_content, None)?;
+            link_import(db, item, entity_type, id, raw_content)?;
+            Ok(BulkImportItem {
+                imported_as: Some(name),
+                ..outcome("renamed", Some(id))
+            })
+        }
+    }
+}
+
+/// Id of the entity named `name` in `table`, if any
+fn entity_id_by_name(db: &Database, table: &str, name: &str) -> Result<Option<i64>, String> {
+    db.conn()
+        .query_row(
+            &format!("SELECT id FROM {} WHERE name = ?", table),
+            [name],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())
+}
+
+/// The entity to overwrite when updating, or `None` for a fresh import
+fn existing_entity_id(
+    existing: Option<&RepoImport>,
+    entity_type: &str,
+) -> Result<Option<i64>, String> {
+    match existing {
+        None => Ok(None),
+        Some(import) if import.entity_type == entity_type => Ok(Some(import.entity_id)),
+        Some(import) => Err(format!(
+            "The upstream item is now a {} but was imported as a {}; delete the imported {} and import it again",
+            entity_type, import.entity_type, import.entity_type
+        )),
+    }
+}
+
+/// Write `raw_content` for `item` into the library. With `existing`, the
+/// previously imported entity is updated in place instead of inserting a new
+/// one. Either way the import is recorded so the marketplace can show it as
+/// installed.
+pub(crate) fn import_item_content_in_db(
+    db: &Database,
+    item: &RepoItem,
+    raw_content: &str,
+    existing: Option<&RepoImport>,
+) -> Result<ImportResult, String> {
+    let (entity_type, entity_id) = write_item_entity(db, item, &item.name, raw_content, existing)?;
+    link_import(db, item, entity_type, entity_id, raw_content)?;
+
+    let message = if existing.is_some() {
+        "Updated to the latest version"
+    } else {
+        "Successfully imported"
+    };
+    Ok(ImportResult {
+        success: true,
+        item_type: item.item_type.clone(),
+        item_id: entity_id,
+        message: Some(message.to_string()),
+    })
+}
+
+/// Link an entity back to its marketplace item and mark the item imported
+fn link_import(
+    db: &Database,
+    item: &RepoItem,
+    entity_type: &str,
+    entity_id: i64,
+    raw_content: &str,
+) -> Result<(), String> {
+    repo_sync::record_import(db, item, entity_type, entity_id, raw_content)
+        .map_err(|e| e.to_string())?;
+    mark_item_imported_in_db(db, item.id, entity_id)
+}
+
+/// The kind of entity `raw_content` imports as. Marketplace "skills" with
+/// `allowed-tools` become skills; the rest are slash commands.
+fn target_entity_type(item: &RepoItem, raw_content: &str) -> Result<&'static str, String> {
+    match item.item_type.as_str() {
+        "skill" => {
+            let (frontmatter, _) = parse_frontmatter(raw_content);
+            if frontmatter.contains_key("allowed-tools") || frontmatter.contains_key("allowedtools")
+            {
+                Ok("skill")
             } else {
-                return Err("No content available for this item".to_string());
+                Ok("command")
             }
         }
-    };
+        "subagent" => Ok("subagent"),
+        "mcp" => Ok("mcp"),
+        "hook" => Ok("hook"),
+        _ => Err("Unknown item type".to_string()),
+    }
+}
 
-    // Re-acquire lock for database operations
-    let db = db.lock().map_err(|e| e.to_string())?;
+/// The server definition in an MCP item's content: an `.mcp.json`-style
+/// document, either the whole content or a fenced code block in it. Picks the
+/// server named like the item, or the only one.
+fn parse_item_mcp(name: &str, raw_content: &str) -> Option<ParsedMcp> {
+    let fenced = raw_content
+        .split("```")
+        .skip(1)
+        .step_by(2)
+        .map(|block| block.trim_start_matches("jsonc").trim_start_matches("json"));
+    std::iter::once(raw_content).chain(fenced).find_map(|doc| {
+        let mut servers = config_parser::parse_mcp_json(doc.trim()).ok()?;
+        match servers.iter().position(|s| s.name == name) {
+            Some(i) => Some(servers.swap_remove(i)),
+            None if servers.len() == 1 => servers.pop(),
+            None => None,
+        }
+    })
+}
+
+type CommandProjectWriter = fn(&Path, &Command) -> anyhow::Result<()>;
 
+/// Rewrite the files of an updated entity wherever it is enabled, returning
+/// the Claude config writes queued for confirmation
+fn resync_imported_entity(
+    db: &Database,
+    entity_type: &str,
+    id: i64,
+) -> Result<Vec<PendingChange>, String> {
+    let editors = get_enabled_editors_from_db(db);
+    match entity_type {
+        "skill" => models::resync_skill(db, id, &editors).map(|_| Vec::new()),
+        "subagent" => models::resync_subagent(db, id, &editors).map(|_| Vec::new()),
+        "command" => {
+            let command = get_command_by_id(db, id)?;
+            let global = models::is_enabled_globally(db, "global_commands", "command_id", id);
+            let projects = models::enabled_project_paths(db, "project_commands", "command_id", id)?;
+            for editor in &editors {
+                let (write_global, write_project): (
+                    fn(&Command) -> anyhow::Result<()>,
+                    CommandProjectWriter,
+                ) = match editor.as_str() {
+                    "claude_code" => (
+                        command_writer::write_global_command,
+                        command_writer::write_project_command,
+                    ),
+                    "opencode" => (
+                        command_writer::write_global_command_opencode,
+                        command_writer::write_project_command_opencode,
+                    ),
+                    "windsurf" => (
+                        command_writer::write_global_command_windsurf,
+                        command_writer::write_project_command_windsurf,
+                    ),
+                    _ => continue,
+                };
+                if global {
+                    write_global(&command).map_err(|e| e.to_string())?;
+                }
+                for path in &projects {
+                    write_project(Path::new(path), &command).map_err(|e| e.to_string())?;
+                }
+            }
+            Ok(Vec::new())
+        }
+        "hook" => {
+            let mut pending = Vec::new();
+            if models::is_enabled_globally(db, "global_hooks", "hook_id", id) {
+                pending.extend(sync_global_hooks(db)?);
+            }
+            for path in models::enabled_project_paths(db, "project_hooks", "hook_id", id)? {
+                pending.extend(sync_project_hooks(db, &path)?);
+            }
+            Ok(pending)
+        }
+        "mcp" => {
+            let mut pending = Vec::new();
+            if models::is_enabled_globally(db, "global_mcps", "mcp_id", id) {
+                pending.extend(sync_global_config_from_db(db)?);
+            }
+            let mut stmt = db
+                .conn()
+                .prepare("SELECT project_id FROM project_mcps WHERE mcp_id = ?")
+                .map_err(|e| e.to_string())?;
+            let project_ids: Vec<i64> = stmt
+                .query_map([id], |row| row.get(0))
+                .map_err(|e| e.to_string())?
+                .filter_map(|r| r.ok())
+                .collect();
+            for project_id in project_ids {
+                pending.extend(sync_project_config_from_db(db, project_id)?);
+            }
+            Ok(pending)
+        }
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Insert (or with `existing`, update) the library entity for `item`,
+/// named `name`. Returns the entity type and id.
+fn write_item_entity(
+    db: &Database,
+    item: &RepoItem,
+    name: &str,
+    raw_content: &str,
+    existing: Option<&RepoImport>,
+) -> Result<(&'static str, i64), String> {
     // Import based on item type
-    let imported_id = match item.item_type.as_str() {
+    let written = match item.item_type.as_str() {
         "skill" => {
             // Parse frontmatter to extract body and allowed_tools
-            let (frontmatter, body) = parse_frontmatter(&raw_content);
+            let (frontmatter, body) = parse_frontmatter(raw_content);
             let content = body.trim().to_string();
 
             let allowed_tools = frontmatter
@@ -265,32 +695,63 @@ pub async fn import_repo_item(
             // Route to commands or skills table based on whether allowed_tools is present
             if allowed_tools.is_some() {
                 // Has allowed_tools -> goes into skills table
-                db.conn()
-                    .execute(
-                        r#"INSERT INTO skills (name, description, content, allowed_tools, model, source)
-                           VALUES (?, ?, ?, ?, ?, 'imported')"#,
-                        params![item.name, item.description, content, allowed_tools, model],
-                    )
-                    .map_err(|e| e.to_string())?;
+                let id = match existing_entity_id(existing, "skill")? {
+                    Some(id) => {
+                        db.conn()
+                            .execute(
+                                r#"UPDATE skills SET description = ?, content = ?, allowed_tools = ?, model = ?,
+                                   updated_at = CURRENT_TIMESTAMP WHERE id = ?"#,
+                                params![item.description, content, allowed_tools, model, id],
+                            )
+                            .map_err(|e| e.to_string())?;
+                        id
+                    }
+                    None => {
+                        db.conn()
+                            .execute(
+                                r#"INSERT INTO skills (name, description, content, allowed_tools, model, source)
+                                   VALUES (?, ?, ?, ?, ?, 'imported')"#,
+                                params![name, item.description, content, allowed_tools, model],
+                            )
+                            .map_err(|e| e.to_string())?;
+                        db.conn().last_insert_rowid()
+                    }
+                };
+                ("skill", id)
             } else {
                 // No allowed_tools -> it's a slash command, goes into commands table
                 let argument_hint = frontmatter
                     .get("argument-hint")
                     .or_else(|| frontmatter.get("argumenthint"))
                     .cloned();
-                db.conn()
-                    .execute(
-                        r#"INSERT INTO commands (name, description, content, allowed_tools, argument_hint, model, source)
-                           VALUES (?, ?, ?, ?, ?, ?, 'imported')"#,
-                        params![item.name, item.description, content, allowed_tools, argument_hint, model],
-                    )
-                    .map_err(|e| e.to_string())?;
+                let id = match existing_entity_id(existing, "command")? {
+                    Some(id) => {
+                        db.conn()
+                            .execute(
+                                r#"UPDATE commands SET description = ?, content = ?, allowed_tools = ?, argument_hint = ?,
+                                   model = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?"#,
+                                params![item.description, content, allowed_tools, argument_hint, model, id],
+                            )
+                            .map_err(|e| e.to_string())?;
+                        id
+                    }
+                    None => {
+                        db.conn()
+                            .execute(
+                                r#"INSERT INTO commands (name, description, content, allowed_tools, argument_hint, model, source)
+                                   VALUES (?, ?, ?, ?, ?, ?, 'imported')"#,
+                                params![name, item.description, content, allowed_tools, argument_hint, model],
+                            )
+                            .map_err(|e| e.to_string())?;
+                        db.conn().last_insert_rowid()
+                    }
+                };
+                ("command", id)
             }
-            db.conn().last_insert_rowid()
         }
         "subagent" => {
             // Parse frontmatter to extract body content
-            let (frontmatter, body) = parse_frontmatter(&raw_content);
+            let (frontmatter, body) = parse_frontmatter(raw_content);
             let content = body.trim().to_string();
 
             // Extract all fields from frontmatter
@@ -299,12 +760,10 @@ pub async fn import_repo_item(
                 .get("permissionmode")
                 .or_else(|| frontmatter.get("permission-mode"))
                 .cloned();
-            let tools = frontmatter.get("tools").map(|t| {
-                t.split(',')
-                    .map(|s| s.trim().to_string())
-                    .filter(|s| !s.is_empty())
-                    .collect::<Vec<_>>()
-            });
+            let tools = frontmatter
+                .get("tools")
+                .map(|t| parse_agent_tools(t))
+                .filter(|t| !t.is_empty());
             let skills = frontmatter.get("skills").map(|t| {
                 t.split(',')
                     .map(|s| s.trim().to_string())
@@ -317,45 +776,183 @@ pub async fn import_repo_item(
 
             let description = item
                 .description
+                .clone()
                 .unwrap_or_else(|| "Imported from marketplace".to_string());
-            db.conn()
-                .execute(
-                    r#"INSERT INTO subagents (name, description, content, tools, model, permission_mode, skills, tags, source)
-                       VALUES (?, ?, ?, ?, ?, ?, ?, ?, 'imported')"#,
-                    params![item.name, description, content, tools_json, model, permission_mode, skills_json, Option::<String>::None],
-                )
-                .map_err(|e| e.to_string())?;
-            db.conn().last_insert_rowid()
+            let id = match existing_entity_id(existing, "subagent")? {
+                Some(id) => {
+                    db.conn()
+                        .execute(
+                            r#"UPDATE subagents SET description = ?, content = ?, tools = ?, model = ?, permission_mode = ?,
+                               skills = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?"#,
+                            params![description, content, tools_json, model, permission_mode, skills_json, id],
+                        )
+                        .map_err(|e| e.to_string())?;
+                    id
+                }
+                None => {
+                    db.conn()
+                        .execute(
+                            r#"INSERT INTO subagents (name, description, content, tools, model, permission_mode, skills, tags, source)
+                               VALUES (?, ?, ?, ?, ?, ?, ?, ?, 'imported')"#,
+                            params![name, description, content, tools_json, model, permission_mode, skills_json, Option::<String>::None],
+                        )
+                        .map_err(|e| e.to_string())?;
+                    db.conn().last_insert_rowid()
+                }
+            };
+            ("subagent", id)
         }
         "mcp" => {
-            // For MCPs from README, we just store the reference
-            // User would need to configure the MCP manually
-            db.conn()
-                .execute(
-                    r#"INSERT INTO mcps (name, description, type, source, source_path)
-                       VALUES (?, ?, 'stdio', 'imported', ?)"#,
-                    params![item.name, item.description, item.source_url],
+            // Items that carry a server definition are imported with it; ones
+            // linked from a README only store the reference, to be configured
+            // by hand
+            let definition = parse_item_mcp(name, raw_content).map(|mcp| {
+                McpDefinition::new(
+                    &mcp.mcp_type,
+                    mcp.command.as_deref(),
+                    mcp.args.as_ref(),
+                    mcp.url.as_deref(),
+                    mcp.headers.as_ref(),
+                    mcp.env.as_ref(),
                 )
+            });
+            let id = match (existing_entity_id(existing, "mcp")?, definition) {
+                (Some(id), Some(def)) => {
+                    db.conn()
+                        .execute(
+                            r#"UPDATE mcps SET description = ?, source_path = ?, homepage = ?, type = ?, command = ?,
+                               args = ?, url = ?, headers = ?, env = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?"#,
+                            params![
+                                item.description,
+                                item.source_url,
+                                item.source_url,
+                                def.mcp_type,
+                                def.command,
+                                def.args_json(),
+                                def.url,
+                                def.headers_json(),
+                                def.env_json(),
+                                id
+                            ],
+                        )
+                        .map_err(|e| e.to_string())?;
+                    id
+                }
+                (Some(_), None) => {
+                    return Err(format!(
+                        "'{}' has no server configuration to update from",
+                        name
+                    ))
+                }
+                (None, Some(def)) => {
+                    db.conn()
+                        .execute(
+                            r#"INSERT INTO mcps (name, description, type, command, args, url, headers, env, source,
+                               source_path, homepage)
+                               VALUES (?, ?, ?, ?, ?, ?, ?, ?, 'imported', ?, ?)"#,
+                            params![
+                                name,
+                                item.description,
+                                def.mcp_type,
+                                def.command,
+                                def.args_json(),
+                                def.url,
+                                def.headers_json(),
+                                def.env_json(),
+                                item.source_url,
+                                item.source_url
+                            ],
+                        )
+                        .map_err(|e| e.to_string())?;
+                    db.conn().last_insert_rowid()
+                }
+                (None, None) => {
+                    db.conn()
+                        .execute(
+                            r#"INSERT INTO mcps (name, description, type, source, source_path, homepage)
+                               VALUES (?, ?, 'stdio', 'imported', ?, ?)"#,
+                            params![name, item.description, item.source_url, item.source_url],
+                        )
+                        .map_err(|e| e.to_string())?;
+                    db.conn().last_insert_rowid()
+                }
+            };
+            ("mcp", id)
+        }
+        "hook" => {
+            // Plugin hooks arrive as a single-hook hooks.json bundle
+            let hook = plugin_hooks::parse_hook_bundle(name, item.description.clone(), raw_content)
                 .map_err(|e| e.to_string())?;
-            db.conn().last_insert_rowid()
+            let headers_json = hook.headers.as_ref().map(|h| h.to_string());
+            let env_vars_json = hook
+                .allowed_env_vars
+                .as_ref()
+                .map(|v| serde_json::to_string(v).unwrap());
+            let id = match existing_entity_id(existing, "hook")? {
+                Some(id) => {
+                    db.conn()
+                        .execute(
+                            r#"UPDATE hooks SET description = ?1, event_type = ?2, matcher = ?3, hook_type = ?4,
+                               command = ?5, prompt = ?6, timeout = ?7, url = ?8, headers = ?9,
+                               allowed_env_vars = ?10, if_condition = ?11, status_message = ?12, once = ?13,
+                               async_mode = ?14, shell = ?15, updated_at = CURRENT_TIMESTAMP WHERE id = ?16"#,
+                            params![
+                                hook.description,
+                                hook.event_type,
+                                hook.matcher,
+                                hook.hook_type,
+                                hook.command,
+                                hook.prompt,
+                                hook.timeout,
+                                hook.url,
+                                headers_json,
+                                env_vars_json,
+                                hook.if_condition,
+                                hook.status_message,
+                                hook.once.unwrap_or(false),
+                                hook.async_mode.unwrap_or(false),
+                                hook.shell,
+                                id
+                            ],
+                        )
+                        .map_err(|e| e.to_string())?;
+                    id
+                }
+                None => {
+                    db.conn()
+                        .execute(
+                            r#"INSERT INTO hooks (name, description, event_type, matcher, hook_type, command, prompt,
+                               timeout, url, headers, allowed_env_vars, if_condition, status_message, once,
+                               async_mode, shell, source)
+                               VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'imported')"#,
+                            params![
+                                name,
+                                hook.description,
+                                hook.event_type,
+                                hook.matcher,
+                                hook.hook_type,
+                                hook.command,
+                                hook.prompt,
+                                hook.timeout,
+                                hook.url,
+                                headers_json,
+                                env_vars_json,
+                                hook.if_condition,
+                                hook.status_message,
+                                hook.once.unwrap_or(false),
+                                hook.async_mode.unwrap_or(false),
+                                hook.shell
+                            ],
+                        )
+                        .map_err(|e| e.to_string())?;
+                    db.conn().last_insert_rowid()
+                }
+            };
+            ("hook", id)
         }
         _ => return Err("Unknown item type".to_string()),
     };
-
-    // Mark as imported
-    db.conn()
-        .execute(
-            "UPDATE repo_items SET is_imported = 1, imported_item_id = ? WHERE id = ?",
-            params![imported_id, item_id],
-        )
-        .map_err(|e| e.to_string())?;
-
-    Ok(ImportResult {
-        success: true,
-        item_type: item.item_type,
-        item_id: imported_id,
-        message: Some("Successfully imported".to_string()),
-    })
+    Ok(written)
 }
 
 /// Get GitHub API rate limit information
@@ -389,10 +986,25 @@ pub fn seed_default_repos(db: State<'_, Arc<Mutex<Database>>>) -> Result<(), Str
     repo_sync::seed_default_repos(&db).map_err(|e| e.to_string())
 }
 
-/// Reset repos to defaults (removes all repos and items, then re-seeds)
+/// Reset repos to defaults (removes all repos and items, then re-seeds).
+/// With `dry_run` the plan is returned without changing anything.
 #[tauri::command]
-pub fn reset_repos_to_defaults(db: State<'_, Arc<Mutex<Database>>>) -> Result<(), String> {
+pub fn reset_repos_to_defaults(
+    db: State<'_, Arc<Mutex<Database>>>,
+    dry_run: Option<bool>,
+) -> Result<RepoResetPlan, String> {
     let db = db.lock().map_err(|e| e.to_string())?;
+    reset_repos_to_defaults_in_db(&db, dry_run.unwrap_or(false))
+}
+
+pub(crate) fn reset_repos_to_defaults_in_db(
+    db: &Database,
+    dry_run: bool,
+) -> Result<RepoResetPlan, String> {
+    let plan = plan_repo_reset(db, dry_run)?;
+    if dry_run {
+        return Ok(plan);
+    }
 
     // Delete all repo items
     db.conn()
@@ -405,7 +1017,56 @@ pub fn reset_repos_to_defaults(db: State<'_, Arc<Mutex<Database>>>) -> Result<()
         .map_err(|e| e.to_string())?;
 
     // Re-seed defaults
-    repo_sync::seed_default_repos(&db).map_err(|e| e.to_string())
+    repo_sync::seed_default_repos(db).map_err(|e| e.to_string())?;
+    Ok(plan)
+}
+
+/// Compare the current repos against the defaults by GitHub URL
+fn plan_repo_reset(db: &Database, dry_run: bool) -> Result<RepoResetPlan, String> {
+    let mut stmt = db
+        .conn()
+        .prepare("SELECT name, github_url FROM repos ORDER BY name")
+        .map_err(|e| e.to_string())?;
+    let current: Vec<RepoResetEntry> = stmt
+        .query_map([], |row| {
+            Ok(RepoResetEntry {
+                name: row.get(0)?,
+                github_url: row.get(1)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let is_default = |url: &str| {
+        repo_sync::DEFAULT_REPOS
+            .iter()
+            .any(|(_, default_url, _, _, _)| *default_url == url)
+    };
+    let (kept, removed): (Vec<_>, Vec<_>) = current
+        .into_iter()
+        .partition(|repo| is_default(&repo.github_url));
+    let added = repo_sync::DEFAULT_REPOS
+        .iter()
+        .filter(|(_, url, _, _, _)| !kept.iter().any(|repo| repo.github_url == *url))
+        .map(|(name, url, _, _, _)| RepoResetEntry {
+            name: name.to_string(),
+            github_url: url.to_string(),
+        })
+        .collect();
+
+    let cached_items_cleared = db
+        .conn()
+        .query_row("SELECT COUNT(*) FROM repo_items", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+
+    Ok(RepoResetPlan {
+        dry_run,
+        removed,
+        added,
+        kept,
+        cached_items_cleared,
+    })
 }
 
 // ============================================================================
@@ -548,7 +1209,6 @@ pub(crate) fn add_repo_item_in_db(
 }
 
 /// Get a repo item by ID directly from the database
-#[cfg_attr(not(test), allow(dead_code))]
 pub(crate) fn get_repo_item_by_id(db: &Database, id: i64) -> Result<RepoItem, String> {
     db.conn()
         .query_row(
@@ -572,6 +1232,8 @@ pub(crate) fn get_repo_item_by_id(db: &Database, id: i64) -> Result<RepoItem, St
                     imported_item_id: row.get(11)?,
                     created_at: row.get(12)?,
                     updated_at: row.get(13)?,
+                    installed: false,
+                    update_available: false,
                 })
             },
         )
@@ -579,7 +1241,6 @@ pub(crate) fn get_repo_item_by_id(db: &Database, id: i64) -> Result<RepoItem, St
 }
 
 /// Mark a repo item as imported directly in the database
-#[cfg_attr(not(test), allow(dead_code))]
 pub(crate) fn mark_item_imported_in_db(
     db: &Database,
     item_id: i64,
@@ -835,10 +1496,272 @@ mod tests {
         assert_eq!(item.imported_item_id, Some(42));
     }
 
+    #[test]
+    fn test_import_all_repo_items_applies_conflict_policy() {
+        let db = Database::in_memory().unwrap();
+        let repo_id = create_test_repo(&db);
+        let command = "Run the linter";
+        let agent = "---\ndescription: Plans\n---\nPlan things";
+        for (item_type, name, content) in [
+            ("skill", "lint", command),
+            ("skill", "format", command),
+            ("subagent", "planner", agent),
+            ("skill", "broken", command),
+        ] {
+            add_repo_item_in_db(&db, repo_id, item_type, name, None, None, Some(content)).unwrap();
+        }
+        db.conn()
+            .execute_batch(
+                "INSERT INTO commands (name, content) VALUES ('format', 'mine');
+                 INSERT INTO subagents (name, description, content) VALUES ('planner', 'mine', 'mine');",
+            )
+            .unwrap();
+
+        let prepared = |db: &Database| -> Vec<(RepoItem, Result<String, String>)> {
+            repo_sync::get_repo_items(db, repo_id)
+                .unwrap()
+                .into_iter()
+                .map(|item| {
+                    let content = if item.name == "broken" {
+                        Err("HTTP 404".to_string())
+                    } else {
+                        Ok(item.raw_content.clone().unwrap())
+                    };
+                    (item, content)
+                })
+                .collect()
+        };
+        let status = |results: &[BulkImportItem], name: &str| {
+            results.iter().find(|r| r.name == name).unwrap().clone()
+        };
+
+        let results =
+            import_all_repo_items_in_db(&db, &prepared(&db), ImportConflictPolicy::Skip).unwrap();
+        assert_eq!(status(&results, "lint").status, "imported");
+        assert_eq!(status(&results, "format").status, "skipped");
+        assert_eq!(status(&results, "planner").status, "skipped");
+        assert_eq!(status(&results, "broken").status, "failed");
+        assert_eq!(
+            status(&results, "broken").message.as_deref(),
+            Some("HTTP 404")
+        );
+
+        let (planner, others): (Vec<_>, Vec<_>) = prepared(&db)
+            .into_iter()
+            .partition(|(item, _)| item.name == "planner");
+        let results =
+            import_all_repo_items_in_db(&db, &others, ImportConflictPolicy::Rename).unwrap();
+        assert_eq!(status(&results, "lint").status, "already_imported");
+        let renamed = status(&results, "format");
+        assert_eq!(renamed.status, "renamed");
+        assert_eq!(renamed.imported_as.as_deref(), Some("format-2"));
+
+        let mine: i64 = db
+            .conn()
+            .query_row(
+                "SELECT id FROM subagents WHERE name = 'planner'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let results =
+            import_all_repo_items_in_db(&db, &planner, ImportConflictPolicy::Link).unwrap();
+        assert_eq!(results[0].status, "linked");
+        assert_eq!(results[0].entity_id, Some(mine));
+        let listed = repo_sync::get_repo_items(&db, repo_id).unwrap();
+        assert!(
+            listed
+                .iter()
+                .find(|i| i.name == "planner")
+                .unwrap()
+                .installed
+        );
+    }
+
+    #[test]
+    fn test_imported_item_survives_resync_and_updates_in_place() {
+        let db = Database::in_memory().unwrap();
+        let repo_id = create_test_repo(&db);
+        let v1 = "---\nallowed-tools: Read\n---\nVersion one";
+        let item_id =
+            add_repo_item_in_db(&db, repo_id, "skill", "reviewer", None, None, Some(v1)).unwrap();
+
+        let item = get_repo_item_by_id(&db, item_id).unwrap();
+        let imported = import_item_content_in_db(&db, &item, v1, None).unwrap();
+        let listed = &repo_sync::get_repo_items(&db, repo_id).unwrap()[0];
+        assert!(listed.installed);
+        assert!(!listed.update_available);
+
+        // A sync recreates repo_items with the upstream content
+        db.conn()
+            .execute("DELETE FROM repo_items WHERE repo_id = ?", [repo_id])
+            .unwrap();
+        let v2 = "---\nallowed-tools: Read\n---\nVersion two";
+        let new_id =
+            add_repo_item_in_db(&db, repo_id, "skill", "reviewer", None, None, Some(v2)).unwrap();
+        let listed = &repo_sync::get_all_repo_items(&db, Some("skill".to_string())).unwrap()[0];
+        assert!(listed.installed);
+        assert!(listed.update_available);
+        assert_eq!(listed.imported_item_id, Some(imported.item_id));
+
+        let item = get_repo_item_by_id(&db, new_id).unwrap();
+        let import = repo_sync::get_import(&db, &item).unwrap().unwrap();
+        let updated = update_imported_item_in_db(&db, &item, v2, &import)
+            .unwrap()
+            .item;
+        assert_eq!(updated.item_id, imported.item_id);
+        let content: String = db
+            .conn()
+            .query_row(
+                "SELECT content FROM skills WHERE id = ?",
+                [updated.item_id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(content, "Version two");
+        assert!(!repo_sync::get_repo_items(&db, repo_id).unwrap()[0].update_available);
+
+        // Deleting the imported skill makes the item installable again
+        db.conn()
+            .execute("DELETE FROM skills WHERE id = ?", [updated.item_id])
+            .unwrap();
+        assert!(!repo_sync::get_repo_items(&db, repo_id).unwrap()[0].installed);
+    }
+
+    #[test]
+    fn test_update_imported_mcp_applies_its_server_config() {
+        let db = Database::in_memory().unwrap();
+        let repo_id = create_test_repo(&db);
+        let content = |command: &str| {
+            format!(
+                "# Fetch\n\n```json\n{{\"mcpServers\": {{\"fetch\": {{\"command\": \"{}\", \"args\": [\"fetch\"]}}}}}}\n```\n",
+                command
+            )
+        };
+        let v1 = content("uvx");
+        let item_id =
+            add_repo_item_in_db(&db, repo_id, "mcp", "fetch", None, None, Some(&v1)).unwrap();
+        let item = get_repo_item_by_id(&db, item_id).unwrap();
+        let imported = import_item_content_in_db(&db, &item, &v1, None).unwrap();
+
+        let import = repo_sync::get_import(&db, &item).unwrap().unwrap();
+        update_imported_item_in_db(&db, &item, &content("npx"), &import).unwrap();
+        let (command, args): (String, String) = db
+            .conn()
+            .query_row(
+                "SELECT command, args FROM mcps WHERE id = ?",
+                [imported.item_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(command, "npx");
+        assert_eq!(args, r#"["fetch"]"#);
+
+        // Content without a server definition can't update the MCP
+        let err = update_imported_item_in_db(&db, &item, "# Fetch", &import).unwrap_err();
+        assert!(err.contains("no server configuration"));
+    }
+
+    #[test]
+    fn test_plugin_hooks_import_and_export_round_trip() {
+        let db = Database::in_memory().unwrap();
+        let repo_id = create_test_repo(&db);
+        let repo = get_repo_impl(&db, repo_id).unwrap();
+        let sample = serde_json::json!({
+            "hooks": {
+                "PostToolUse": [{
+                    "matcher": "Edit|Write",
+                    "hooks": [
+                        { "type": "command", "command": "npx prettier --write .", "timeout": 30 },
+                        { "type": "command", "command": "npx eslint --fix .", "async": true }
+                    ]
+                }],
+                "Stop": [{
+                    "hooks": [{ "type": "prompt", "prompt": "Check the tests pass", "statusMessage": "Checking" }]
+                }]
+            }
+        });
+
+        let items = repo_sync::plugin_hook_items(&repo, "lint-kit", &sample.to_string());
+        assert_eq!(items.len(), 3);
+        let mut hook_ids = Vec::new();
+        for parsed in &items {
+            assert_eq!(parsed.item_type, "hook");
+            let id = add_repo_item_in_db(
+                &db,
+                repo_id,
+                &parsed.item_type,
+                &parsed.name,
+                parsed.description.as_deref(),
+                parsed.source_url.as_deref(),
+                parsed.raw_content.as_deref(),
+            )
+            .unwrap();
+            let item = get_repo_item_by_id(&db, id).unwrap();
+            let imported =
+                import_item_content_in_db(&db, &item, parsed.raw_content.as_deref().unwrap(), None)
+                    .unwrap();
+            hook_ids.push(imported.item_id);
+        }
+        assert!(repo_sync::get_repo_items(&db, repo_id)
+            .unwrap()
+            .iter()
+            .all(|item| item.installed));
+
+        let stop = crate::commands::hooks::get_hook_by_id(&db, hook_ids[2]).unwrap();
+        assert_eq!(stop.name, "lint-kit-stop");
+        assert_eq!(stop.hook_type, "prompt");
+        assert_eq!(stop.status_message.as_deref(), Some("Checking"));
+        assert_eq!(stop.source, "imported");
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path().join("lint-kit");
+        crate::commands::hooks::export_hooks_as_plugin_from_db(&db, &hook_ids, &root).unwrap();
+        let exported: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(root.join(plugin_hooks::PLUGIN_HOOKS_PATH)).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(exported, sample);
+        let manifest =
+            std::fs::read_to_string(root.join(plugin_hooks::PLUGIN_MANIFEST_PATH)).unwrap();
+        assert_eq!(
+            plugin_hooks::plugin_name_from_manifest(&manifest).as_deref(),
+            Some("lint-kit")
+        );
+    }
+
     // =========================================================================
     // URL conversion tests
     // =========================================================================
 
+    #[test]
+    fn test_preview_repo_target() {
+        let repo = preview_repo_target("https://github.com/acme/agents", None, None).unwrap();
+        assert_eq!(repo.name, "acme/agents");
+        assert_eq!(repo.repo_type, "file_based");
+        assert_eq!(repo.content_type, "mixed");
+
+        let repo = preview_repo_target(
+            "https://github.com/acme/awesome",
+            Some("readme_based".to_string()),
+            Some("mcp".to_string()),
+        )
+        .unwrap();
+        assert_eq!(repo.repo_type, "readme_based");
+
+        assert!(preview_repo_target("not a url", None, None).is_err());
+        assert!(
+            preview_repo_target("https://github.com/acme/x", Some("git".to_string()), None)
+                .is_err()
+        );
+        assert!(preview_repo_target(
+            "https://github.com/acme/x",
+            None,
+            Some("plugin".to_string())
+        )
+        .is_err());
+    }
+
     #[test]
     fn test_convert_blob_url_to_raw() {
         let blob_url = "https://github.com/owner/repo/blob/main/path/to/file.md";
@@ -968,4 +1891,41 @@ mod tests {
             .unwrap();
         assert_eq!(count, 0);
     }
+
+    #[test]
+    fn test_reset_repos_dry_run_reports_plan_without_changes() {
+        let db = Database::in_memory().unwrap();
+        repo_sync::seed_default_repos(&db).unwrap();
+        let default_url = repo_sync::DEFAULT_REPOS[0].1;
+        db.conn()
+            .execute("DELETE FROM repos WHERE github_url = ?", [default_url])
+            .unwrap();
+        let custom = add_repo_in_db(
+            &db,
+            &CreateRepoRequest {
+                github_url: unique_github_url(),
+                repo_type: "file_based".to_string(),
+                content_type: "skill".to_string(),
+            },
+        )
+        .unwrap();
+
+        let plan = reset_repos_to_defaults_in_db(&db, true).unwrap();
+        assert!(plan.dry_run);
+        assert_eq!(plan.removed.len(), 1);
+        assert_eq!(plan.removed[0].github_url, custom.github_url);
+        assert_eq!(plan.added.len(), 1);
+        assert_eq!(plan.added[0].github_url, default_url);
+        assert_eq!(plan.kept.len(), repo_sync::DEFAULT_REPOS.len() - 1);
+        assert!(get_repo_impl(&db, custom.id).is_ok());
+
+        let applied = reset_repos_to_defaults_in_db(&db, false).unwrap();
+        assert!(!applied.dry_run);
+        assert_eq!(applied.removed.len(), 1);
+        let count: i64 = db
+            .conn()
+            .query_row("SELECT COUNT(*) FROM repos", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count as usize, repo_sync::DEFAULT_REPOS.len());
+    }
 }
@@ -1,4 +1,5 @@
 use crate::db::Database;
+use crate::services::reconcile::{self, ReconcileReport, ReconcileScope};
 use crate::services::scanner as scanner_service;
 use std::sync::{Arc, Mutex};
 use tauri::State;
@@ -16,6 +17,24 @@ pub fn scan_claude_directory(db: State<'_, Arc<Mutex<Database>>>) -> Result<usiz
     Ok(claude_json_count + plugin_count)
 }
 
+/// Compare the library with the files on disk. Only writes changes when
+/// `apply` is true; otherwise the report is a preview.
+#[tauri::command]
+pub fn reconcile_from_disk(
+    db: State<'_, Arc<Mutex<Database>>>,
+    scope: ReconcileScope,
+    apply: Option<bool>,
+) -> Result<ReconcileReport, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    reconcile::reconcile_from_disk(&db, scope, apply.unwrap_or(false)).map_err(|e| e.to_string())
+}
+
+/// Stop the startup scan before its next phase
+#[tauri::command]
+pub fn cancel_startup_scan() {
+    scanner_service::cancel_startup_scan();
+}
+
 #[cfg(test)]
 mod tests {
     // scanner commands require a full Database instance with tables created.
@@ -1,13 +1,15 @@
 use crate::db::{
     AppSettings, CodexPaths, CopilotPaths, CursorPaths, Database, EditorInfo, GeminiPaths,
-    OpenCodePaths,
+    OpenCodePaths, WindsurfPaths,
 };
-use crate::utils::codex_paths::{get_codex_paths, is_codex_installed};
-use crate::utils::copilot_paths::{get_copilot_paths, is_copilot_installed};
-use crate::utils::cursor_paths::{get_cursor_paths, is_cursor_installed};
-use crate::utils::gemini_paths::{get_gemini_paths, is_gemini_installed};
-use crate::utils::opencode_paths::{get_opencode_paths, is_opencode_installed};
-use crate::utils::paths::get_claude_paths;
+use crate::services::connectivity::{self, ConnectivityStatus};
+use crate::utils::codex_paths::{codex_install_signals, get_codex_paths};
+use crate::utils::copilot_paths::{copilot_install_signals, get_copilot_paths};
+use crate::utils::cursor_paths::{cursor_install_signals, get_cursor_paths};
+use crate::utils::gemini_paths::{gemini_install_signals, get_gemini_paths};
+use crate::utils::opencode_paths::{get_opencode_paths, opencode_install_signals};
+use crate::utils::paths::{claude_install_signals, get_claude_paths};
+use crate::utils::windsurf_paths::{get_windsurf_paths, windsurf_install_signals};
 use log::info;
 use std::sync::{Arc, Mutex};
 use tauri::State;
@@ -41,16 +43,22 @@ pub fn get_available_editors(
 ) -> Result<Vec<EditorInfo>, String> {
     info!("[Settings] Getting available editors");
     let db = db.lock().map_err(|e| e.to_string())?;
+    Ok(get_available_editors_from_db(&db))
+}
 
-    let enabled = get_enabled_editors_from_db(&db);
+/// Known editors with install/enabled status (no Tauri State dependency)
+pub(crate) fn get_available_editors_from_db(db: &Database) -> Vec<EditorInfo> {
+    let enabled = get_enabled_editors_from_db(db);
     let mut editors = Vec::new();
 
     // Claude Code
     if let Ok(paths) = get_claude_paths() {
+        let installed_via = claude_install_signals();
         editors.push(EditorInfo {
             id: "claude_code".to_string(),
             name: "Claude Code".to_string(),
-            is_installed: paths.claude_dir.exists(),
+            is_installed: !installed_via.is_empty(),
+            installed_via,
             is_enabled: enabled.contains(&"claude_code".to_string()),
             config_path: paths.claude_json.to_string_lossy().to_string(),
         });
@@ -58,10 +66,12 @@ pub fn get_available_editors(
 
     // OpenCode
     if let Ok(paths) = get_opencode_paths() {
+        let installed_via = opencode_install_signals();
         editors.push(EditorInfo {
             id: "opencode".to_string(),
             name: "OpenCode".to_string(),
-            is_installed: is_opencode_installed(),
+            is_installed: !installed_via.is_empty(),
+            installed_via,
             is_enabled: enabled.contains(&"opencode".to_string()),
             config_path: paths.config_file.to_string_lossy().to_string(),
         });
@@ -69,10 +79,12 @@ pub fn get_available_editors(
 
     // Codex CLI
     if let Ok(paths) = get_codex_paths() {
+        let installed_via = codex_install_signals();
         editors.push(EditorInfo {
             id: "codex".to_string(),
             name: "Codex CLI".to_string(),
-            is_installed: is_codex_installed(),
+            is_installed: !installed_via.is_empty(),
+            installed_via,
             is_enabled: enabled.contains(&"codex".to_string()),
             config_path: paths.config_file.to_string_lossy().to_string(),
         });
@@ -80,10 +92,12 @@ pub fn get_available_editors(
 
     // GitHub Copilot CLI
     if let Ok(paths) = get_copilot_paths() {
+        let installed_via = copilot_install_signals();
         editors.push(EditorInfo {
             id: "copilot".to_string(),
             name: "Copilot CLI".to_string(),
-            is_installed: is_copilot_installed(),
+            is_installed: !installed_via.is_empty(),
+            installed_via,
             is_enabled: enabled.contains(&"copilot".to_string()),
             config_path: paths.mcp_config_file.to_string_lossy().to_string(),
         });
@@ -91,10 +105,12 @@ pub fn get_available_editors(
 
     // Cursor IDE
     if let Ok(paths) = get_cursor_paths() {
+        let installed_via = cursor_install_signals();
         editors.push(EditorInfo {
             id: "cursor".to_string(),
             name: "Cursor".to_string(),
-            is_installed: is_cursor_installed(),
+            is_installed: !installed_via.is_empty(),
+            installed_via,
             is_enabled: enabled.contains(&"cursor".to_string()),
             config_path: paths.mcp_config_file.to_string_lossy().to_string(),
         });
@@ -102,16 +118,31 @@ pub fn get_available_editors(
 
     // Gemini CLI
     if let Ok(paths) = get_gemini_paths() {
+        let installed_via = gemini_install_signals();
         editors.push(EditorInfo {
             id: "gemini".to_string(),
             name: "Gemini CLI".to_string(),
-            is_installed: is_gemini_installed(),
+            is_installed: !installed_via.is_empty(),
+            installed_via,
             is_enabled: enabled.contains(&"gemini".to_string()),
             config_path: paths.settings_file.to_string_lossy().to_string(),
         });
     }
 
-    Ok(editors)
+    // Windsurf
+    if let Ok(paths) = get_windsurf_paths() {
+        let installed_via = windsurf_install_signals();
+        editors.push(EditorInfo {
+            id: "windsurf".to_string(),
+            name: "Windsurf".to_string(),
+            is_installed: !installed_via.is_empty(),
+            installed_via,
+            is_enabled: enabled.contains(&"windsurf".to_string()),
+            config_path: paths.mcp_config_file.to_string_lossy().to_string(),
+        });
+    }
+
+    editors
 }
 
 /// Toggle an editor's enabled status
@@ -224,6 +255,21 @@ pub fn get_gemini_paths_cmd() -> Result<GeminiPaths, String> {
     })
 }
 
+/// Get Windsurf paths
+#[tauri::command]
+pub fn get_windsurf_paths_cmd() -> Result<WindsurfPaths, String> {
+    info!("[Settings] Getting Windsurf paths");
+
+    let paths = get_windsurf_paths().map_err(|e| e.to_string())?;
+
+    Ok(WindsurfPaths {
+        config_dir: paths.config_dir.to_string_lossy().to_string(),
+        mcp_config_file: paths.mcp_config_file.to_string_lossy().to_string(),
+        global_rules_file: paths.global_rules_file.to_string_lossy().to_string(),
+        global_workflows_dir: paths.global_workflows_dir.to_string_lossy().to_string(),
+    })
+}
+
 // ============================================================================
 // Claude Code container settings
 // ============================================================================
@@ -298,6 +344,105 @@ pub fn get_container_claude_settings_from_db(db: &Database) -> ContainerClaudeSe
     }
 }
 
+// ============================================================================
+// Connectivity pre-check
+// ============================================================================
+
+/// Endpoints probed by `check_connectivity`, and whether to probe at all
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivitySettings {
+    pub enabled: bool,
+    pub github_url: String,
+    pub probe_url: String,
+}
+
+#[tauri::command]
+pub fn get_connectivity_settings(
+    db: State<'_, Arc<Mutex<Database>>>,
+) -> Result<ConnectivitySettings, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    Ok(get_connectivity_settings_from_db(&db))
+}
+
+#[tauri::command]
+pub fn set_connectivity_settings(
+    db: State<'_, Arc<Mutex<Database>>>,
+    settings: ConnectivitySettings,
+) -> Result<(), String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    set_connectivity_settings_in_db(&db, &settings)
+}
+
+/// Probe GitHub and a generic endpoint so the UI can skip remote work while
+/// offline. Returns an unchecked status without touching the network when the
+/// check is disabled.
+#[tauri::command]
+pub async fn check_connectivity(
+    db: State<'_, Arc<Mutex<Database>>>,
+) -> Result<ConnectivityStatus, String> {
+    let settings = {
+        let db = db.lock().map_err(|e| e.to_string())?;
+        get_connectivity_settings_from_db(&db)
+    };
+    if !settings.enabled {
+        return Ok(ConnectivityStatus::unchecked());
+    }
+    let status = connectivity::check_connectivity(&settings.github_url, &settings.probe_url).await;
+    if !status.online {
+        log::warn!("[Connectivity] No endpoint reachable; the network appears to be offline");
+    }
+    Ok(status)
+}
+
+pub(crate) fn get_connectivity_settings_from_db(db: &Database) -> ConnectivitySettings {
+    let url = |key: &str, default: &str| {
+        db.get_setting(key)
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| default.to_string())
+    };
+    ConnectivitySettings {
+        enabled: db
+            .get_setting("connectivity_check_enabled")
+            .map(|s| s == "true")
+            .unwrap_or(true),
+        github_url: url(
+            "connectivity_github_url",
+            connectivity::DEFAULT_GITHUB_ENDPOINT,
+        ),
+        probe_url: url(
+            "connectivity_probe_url",
+            connectivity::DEFAULT_PROBE_ENDPOINT,
+        ),
+    }
+}
+
+pub(crate) fn set_connectivity_settings_in_db(
+    db: &Database,
+    settings: &ConnectivitySettings,
+) -> Result<(), String> {
+    for url in [&settings.github_url, &settings.probe_url] {
+        let trimmed = url.trim();
+        if !(trimmed.is_empty()
+            || trimmed.starts_with("https://")
+            || trimmed.starts_with("http://"))
+        {
+            return Err(format!("'{}' is not an http(s) URL", trimmed));
+        }
+    }
+    db.set_setting(
+        "connectivity_check_enabled",
+        if settings.enabled { "true" } else { "false" },
+    )
+    .map_err(|e| e.to_string())?;
+    // An empty URL falls back to the default endpoint
+    db.set_setting("connectivity_github_url", settings.github_url.trim())
+        .map_err(|e| e.to_string())?;
+    db.set_setting("connectivity_probe_url", settings.probe_url.trim())
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
 /// Get the host's ~/.claude directory path
 pub fn get_host_claude_dir() -> Option<String> {
     dirs::home_dir().map(|h| h.join(".claude").to_string_lossy().to_string())
@@ -316,7 +461,17 @@ pub fn get_github_token_from_db(db: &Database) -> Option<String> {
 /// Get app settings directly from the database
 pub fn get_app_settings_from_db(db: &Database) -> Result<AppSettings, String> {
     let enabled_editors = get_enabled_editors_from_db(db);
-    Ok(AppSettings { enabled_editors })
+    Ok(AppSettings {
+        enabled_editors,
+        inline_env_files: get_inline_env_files_from_db(db),
+    })
+}
+
+/// Whether MCP env files are inlined into written editor configs
+pub fn get_inline_env_files_from_db(db: &Database) -> bool {
+    db.get_setting("inline_env_files")
+        .map(|v| v == "true")
+        .unwrap_or(false)
 }
 
 /// Get list of enabled editors from the database
@@ -330,7 +485,16 @@ pub fn get_enabled_editors_from_db(db: &Database) -> Vec<String> {
 pub fn update_app_settings_in_db(db: &Database, settings: &AppSettings) -> Result<(), String> {
     let json = serde_json::to_string(&settings.enabled_editors).map_err(|e| e.to_string())?;
     db.set_setting("enabled_editors", &json)
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    db.set_setting(
+        "inline_env_files",
+        if settings.inline_env_files {
+            "true"
+        } else {
+            "false"
+        },
+    )
+    .map_err(|e| e.to_string())
 }
 
 /// Toggle editor enabled status in the database
@@ -347,10 +511,68 @@ pub fn toggle_editor_in_db(db: &Database, editor_id: &str, enabled: bool) -> Res
 
     let settings = AppSettings {
         enabled_editors: editors,
+        inline_env_files: get_inline_env_files_from_db(db),
     };
     update_app_settings_in_db(db, &settings)
 }
 
+/// Editor ids the startup scan knows how to probe
+pub(crate) const SCANNABLE_EDITORS: &[&str] = &[
+    "claude_code",
+    "opencode",
+    "codex",
+    "copilot",
+    "cursor",
+    "gemini",
+    "windsurf",
+];
+
+/// Get the editors scanned at startup (`None` means all)
+#[tauri::command]
+pub fn get_scan_editors(
+    db: State<'_, Arc<Mutex<Database>>>,
+) -> Result<Option<Vec<String>>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    Ok(get_scan_editors_from_db(&db))
+}
+
+/// Limit the startup scan to `editors`, or scan every editor again with `None`
+#[tauri::command]
+pub fn set_scan_editors(
+    db: State<'_, Arc<Mutex<Database>>>,
+    editors: Option<Vec<String>>,
+) -> Result<(), String> {
+    info!("[Settings] Setting scan editors: {:?}", editors);
+    let db = db.lock().map_err(|e| e.to_string())?;
+    set_scan_editors_in_db(&db, editors)
+}
+
+/// Editors the startup scan probes. Unset defaults to all of them, so
+/// existing installs keep scanning every editor.
+pub fn get_scan_editors_from_db(db: &Database) -> Option<Vec<String>> {
+    db.get_setting("scan_editors")
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .flatten()
+}
+
+/// Persist the startup scan editor filter, rejecting unknown editor ids
+pub fn set_scan_editors_in_db(db: &Database, editors: Option<Vec<String>>) -> Result<(), String> {
+    if let Some(unknown) = editors
+        .iter()
+        .flatten()
+        .find(|e| !SCANNABLE_EDITORS.contains(&e.as_str()))
+    {
+        return Err(format!(
+            "Unknown editor '{}'. Expected one of {}",
+            unknown,
+            SCANNABLE_EDITORS.join(", ")
+        ));
+    }
+    let json = serde_json::to_string(&editors).map_err(|e| e.to_string())?;
+    db.set_setting("scan_editors", &json)
+        .map_err(|e| e.to_string())
+}
+
 /// Check if a specific editor is enabled
 #[cfg_attr(not(test), allow(dead_code))]
 pub fn is_editor_enabled(db: &Database, editor_id: &str) -> bool {
@@ -375,6 +597,53 @@ mod tests {
     // AppSettings tests
     // =========================================================================
 
+    #[test]
+    fn test_connectivity_settings_defaults_and_round_trip() {
+        let db = Database::in_memory().unwrap();
+        let defaults = get_connectivity_settings_from_db(&db);
+        assert!(defaults.enabled);
+        assert_eq!(defaults.github_url, connectivity::DEFAULT_GITHUB_ENDPOINT);
+        assert_eq!(defaults.probe_url, connectivity::DEFAULT_PROBE_ENDPOINT);
+
+        let custom = ConnectivitySettings {
+            enabled: false,
+            github_url: "https://github.example.com/api/v3".to_string(),
+            probe_url: String::new(),
+        };
+        set_connectivity_settings_in_db(&db, &custom).unwrap();
+        let stored = get_connectivity_settings_from_db(&db);
+        assert!(!stored.enabled);
+        assert_eq!(stored.github_url, "https://github.example.com/api/v3");
+        assert_eq!(stored.probe_url, connectivity::DEFAULT_PROBE_ENDPOINT);
+
+        let bad = ConnectivitySettings {
+            probe_url: "ftp://example.com".to_string(),
+            ..custom
+        };
+        assert!(set_connectivity_settings_in_db(&db, &bad).is_err());
+    }
+
+    #[test]
+    fn test_scan_editors_default_to_all_and_round_trip() {
+        let db = Database::in_memory().unwrap();
+        assert_eq!(get_scan_editors_from_db(&db), None);
+
+        set_scan_editors_in_db(&db, Some(vec!["claude_code".to_string()])).unwrap();
+        assert_eq!(
+            get_scan_editors_from_db(&db),
+            Some(vec!["claude_code".to_string()])
+        );
+
+        assert!(set_scan_editors_in_db(&db, Some(vec!["vim".to_string()])).is_err());
+        assert_eq!(
+            get_scan_editors_from_db(&db),
+            Some(vec!["claude_code".to_string()])
+        );
+
+        set_scan_editors_in_db(&db, None).unwrap();
+        assert_eq!(get_scan_editors_from_db(&db), None);
+    }
+
     #[test]
     fn test_get_app_settings_default() {
         let db = Database::in_memory().unwrap();
@@ -392,6 +661,7 @@ mod tests {
         // Enable both editors
         let settings = AppSettings {
             enabled_editors: vec!["claude_code".to_string(), "opencode".to_string()],
+            inline_env_files: false,
         };
         update_app_settings_in_db(&db, &settings).unwrap();
 
@@ -408,6 +678,7 @@ mod tests {
         // Disable all editors
         let settings = AppSettings {
             enabled_editors: vec![],
+            inline_env_files: false,
         };
         update_app_settings_in_db(&db, &settings).unwrap();
 
@@ -426,6 +697,7 @@ mod tests {
         // Enable opencode
         let settings = AppSettings {
             enabled_editors: vec!["claude_code".to_string(), "opencode".to_string()],
+            inline_env_files: false,
         };
         update_app_settings_in_db(&db, &settings).unwrap();
 
@@ -437,6 +709,7 @@ mod tests {
     fn test_app_settings_serde() {
         let settings = AppSettings {
             enabled_editors: vec!["claude_code".to_string(), "opencode".to_string()],
+            inline_env_files: false,
         };
 
         let json = serde_json::to_string(&settings).unwrap();
@@ -458,6 +731,7 @@ mod tests {
             is_installed: true,
             is_enabled: true,
             config_path: "/home/user/.claude.json".to_string(),
+            installed_via: vec!["config_dir".to_string()],
         };
 
         let json = serde_json::to_string(&info).unwrap();
@@ -480,6 +754,7 @@ mod tests {
             is_installed: true,
             is_enabled: false,
             config_path: "/home/user/.config/opencode/opencode.json".to_string(),
+            installed_via: vec!["binary".to_string()],
         };
 
         let json = serde_json::to_string(&info).unwrap();
@@ -548,11 +823,13 @@ mod tests {
                 "copilot".to_string(),
                 "cursor".to_string(),
                 "gemini".to_string(),
+                "windsurf".to_string(),
             ],
+            inline_env_files: false,
         };
         update_app_settings_in_db(&db, &settings).unwrap();
         let fetched = get_app_settings_from_db(&db).unwrap();
-        assert_eq!(fetched.enabled_editors.len(), 6);
+        assert_eq!(fetched.enabled_editors.len(), 7);
     }
 
     #[test]
@@ -560,6 +837,7 @@ mod tests {
         let db = Database::in_memory().unwrap();
         let settings = AppSettings {
             enabled_editors: vec!["opencode".to_string()],
+            inline_env_files: false,
         };
         update_app_settings_in_db(&db, &settings).unwrap();
         assert!(!is_editor_enabled(&db, "claude_code"));
@@ -654,6 +932,18 @@ mod tests {
         assert!(deserialized.config_dir.contains("opencode"));
     }
 
+    #[test]
+    fn test_windsurf_is_a_known_editor() {
+        let db = Database::in_memory().unwrap();
+        set_scan_editors_in_db(&db, Some(vec!["windsurf".to_string()])).unwrap();
+        assert!(get_available_editors_from_db(&db)
+            .iter()
+            .any(|e| e.id == "windsurf" && e.config_path.ends_with("mcp_config.json")));
+
+        toggle_editor_in_db(&db, "windsurf", true).unwrap();
+        assert!(is_editor_enabled(&db, "windsurf"));
+    }
+
     // =========================================================================
     // Toggle editor tests
     // =========================================================================
@@ -1,13 +1,18 @@
+use crate::commands::content_limits::{content_size_warning, SavedContent};
 use crate::commands::settings::get_enabled_editors_from_db;
 use crate::db::models::{
-    CreateSkillFileRequest, CreateSkillRequest, GlobalSkill, ProjectSkill, Skill, SkillFile,
+    CreateSkillFileRequest, CreateSkillRequest, GlobalSkill, ProjectSkill, Skill, SkillDiskFile,
+    SkillDiskLocation, SkillFile,
 };
 use crate::db::schema::Database;
-use crate::services::skill_writer;
+use crate::services::skill_script::{self, ScriptRunResult};
+use crate::services::{scanner, skill_writer};
+use crate::utils::opencode_paths::get_opencode_paths;
+use directories::BaseDirs;
 use log::warn;
 use regex::Regex;
 use rusqlite::params;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use tauri::State;
 
@@ -25,7 +30,7 @@ const MAX_DESCRIPTION_LENGTH: usize = 1024;
 const RECOMMENDED_MAX_CONTENT_LINES: usize = 500;
 
 /// Reserved words that cannot appear in skill names (per official docs)
-const RESERVED_WORDS: &[&str] = &["anthropic", "claude"];
+pub(crate) const RESERVED_WORDS: &[&str] = &["anthropic", "claude"];
 
 /// Validation result with optional warning
 #[derive(Debug)]
@@ -62,7 +67,7 @@ impl ValidationResult {
 }
 
 /// Validate a skill name according to official Claude Code documentation
-fn validate_skill_name(name: &str) -> ValidationResult {
+pub(crate) fn validate_skill_name(name: &str) -> ValidationResult {
     let name = name.trim();
 
     // Check if empty
@@ -253,9 +258,15 @@ pub fn get_all_skills(db: State<'_, Arc<Mutex<Database>>>) -> Result<Vec<Skill>,
 pub fn create_skill(
     db: State<'_, Arc<Mutex<Database>>>,
     skill: CreateSkillRequest,
-) -> Result<Skill, String> {
-    let db_guard = db.lock().map_err(|e| e.to_string())?;
-    create_skill_in_db(&db_guard, &skill)
+) -> Result<SavedContent<Skill>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    let created = create_skill_in_db(&db, &skill)?;
+    let size_warning =
+        content_size_warning(&db, &format!("Skill '{}'", created.name), &created.content);
+    Ok(SavedContent {
+        item: created,
+        size_warning,
+    })
 }
 
 #[tauri::command]
@@ -263,9 +274,15 @@ pub fn update_skill(
     db: State<'_, Arc<Mutex<Database>>>,
     id: i64,
     skill: CreateSkillRequest,
-) -> Result<Skill, String> {
+) -> Result<SavedContent<Skill>, String> {
     let db = db.lock().map_err(|e| e.to_string())?;
-    update_skill_in_db(&db, id, &skill)
+    let updated = update_skill_in_db(&db, id, &skill)?;
+    let size_warning =
+        content_size_warning(&db, &format!("Skill '{}'", updated.name), &updated.content);
+    Ok(SavedContent {
+        item: updated,
+        size_warning,
+    })
 }
 
 #[tauri::command]
@@ -777,6 +794,89 @@ pub fn delete_skill_file(db: State<'_, Arc<Mutex<Database>>>, id: i64) -> Result
     Ok(())
 }
 
+/// Run one of a skill's scripts in a scratch copy of the skill and return its
+/// output. This executes arbitrary code, so the UI must only call it from an
+/// explicit user action and pass `confirmed: true`.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn run_skill_script(
+    db: State<'_, Arc<Mutex<Database>>>,
+    skill_id: i64,
+    script_name: String,
+    args: Option<Vec<String>>,
+    confirmed: bool,
+) -> Result<ScriptRunResult, String> {
+    let files = {
+        let db = db.lock().map_err(|e| e.to_string())?;
+        get_skill_by_id(&db, skill_id)?;
+        get_skill_files_from_db(&db, skill_id)?
+    };
+    tokio::task::spawn_blocking(move || {
+        run_skill_script_with_files(&files, &script_name, &args.unwrap_or_default(), confirmed)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+pub(crate) fn run_skill_script_with_files(
+    files: &[SkillFile],
+    script_name: &str,
+    args: &[String],
+    confirmed: bool,
+) -> Result<ScriptRunResult, String> {
+    if !confirmed {
+        return Err(
+            "Running a skill script executes its code on this machine; confirm to continue"
+                .to_string(),
+        );
+    }
+    warn!(
+        "[Skills] Running skill script '{}' with args {:?}",
+        script_name, args
+    );
+    skill_script::run_skill_script(files, script_name, args, skill_script::SCRIPT_TIMEOUT)
+        .map_err(|e| e.to_string())
+}
+
+/// Every file a skill is written to, globally and per assigned project, for
+/// each enabled editor
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_skill_disk_locations(
+    db: State<'_, Arc<Mutex<Database>>>,
+    skill_id: i64,
+) -> Result<Vec<SkillDiskLocation>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    let base_dirs = BaseDirs::new().ok_or("Could not find home directory")?;
+    get_skill_disk_locations_from_db(&db, skill_id, base_dirs.home_dir())
+}
+
+/// Reveal a skill's directory in the OS file manager. `scope` is "global"
+/// or "project" (with `project_id`).
+#[tauri::command(rename_all = "camelCase")]
+pub fn open_skill_location(
+    db: State<'_, Arc<Mutex<Database>>>,
+    skill_id: i64,
+    scope: String,
+    project_id: Option<i64>,
+) -> Result<(), String> {
+    let locations = {
+        let db = db.lock().map_err(|e| e.to_string())?;
+        let base_dirs = BaseDirs::new().ok_or("Could not find home directory")?;
+        get_skill_disk_locations_from_db(&db, skill_id, base_dirs.home_dir())?
+    };
+
+    let location = locations
+        .iter()
+        .filter(|l| l.scope == scope && (scope == "global" || l.project_id == project_id))
+        .find(|l| l.exists)
+        .ok_or_else(|| format!("Skill {} has no files in the {} scope", skill_id, scope))?;
+
+    // Open the skill's own directory, which holds SKILL.md for every editor
+    let dir = Path::new(&location.path)
+        .parent()
+        .ok_or_else(|| format!("Invalid skill path: {}", location.path))?;
+    crate::commands::projects::open_folder(dir.to_string_lossy().to_string())
+}
+
 // ============================================================================
 // Database operations (for testing without Tauri state)
 // ============================================================================
@@ -846,7 +946,10 @@ pub(crate) fn get_skill_by_id(db: &Database, id: i64) -> Result<Skill, String> {
 
 /// Get all skills from the database
 pub(crate) fn get_all_skills_from_db(db: &Database) -> Result<Vec<Skill>, String> {
-    let query = format!("SELECT {} FROM skills ORDER BY name", SKILL_SELECT_FIELDS);
+    let query = format!(
+        "SELECT {} FROM skills WHERE removed_at IS NULL ORDER BY name",
+        SKILL_SELECT_FIELDS
+    );
     let mut stmt = db.conn().prepare(&query).map_err(|e| e.to_string())?;
 
     let skills = stmt
@@ -893,6 +996,136 @@ pub(crate) fn update_skill_in_db(
     get_skill_by_id(db, id)
 }
 
+/// A scope a skill is assigned to, used when resolving its disk locations
+struct SkillScope {
+    scope: &'static str,
+    project_id: Option<i64>,
+    project_name: Option<String>,
+    project_path: Option<String>,
+    is_enabled: bool,
+}
+
+/// Resolve the disk locations of a skill. `home` is the base for Claude
+/// Code's global `~/.claude/skills/` directory.
+pub(crate) fn get_skill_disk_locations_from_db(
+    db: &Database,
+    skill_id: i64,
+    home: &Path,
+) -> Result<Vec<SkillDiskLocation>, String> {
+    let skill = get_skill_by_id(db, skill_id)?;
+    let files = get_skill_files_from_db(db, skill_id)?;
+    let editors = get_enabled_editors_from_db(db);
+
+    let mut scopes: Vec<SkillScope> = Vec::new();
+    let global_enabled: Option<bool> = db
+        .conn()
+        .query_row(
+            "SELECT is_enabled FROM global_skills WHERE skill_id = ?",
+            [skill_id],
+            |row| Ok(row.get::<_, i32>(0)? != 0),
+        )
+        .ok();
+    if let Some(enabled) = global_enabled {
+        scopes.push(SkillScope {
+            scope: "global",
+            project_id: None,
+            project_name: None,
+            project_path: None,
+            is_enabled: enabled,
+        });
+    }
+
+    let mut stmt = db
+        .conn()
+        .prepare(
+            "SELECT p.id, p.name, p.path, ps.is_enabled
+             FROM project_skills ps
+             JOIN projects p ON ps.project_id = p.id
+             WHERE ps.skill_id = ?
+             ORDER BY p.name",
+        )
+        .map_err(|e| e.to_string())?;
+    let projects: Vec<(i64, String, String, bool)> = stmt
+        .query_map([skill_id], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get::<_, i32>(3)? != 0,
+            ))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    for (id, name, path, enabled) in projects {
+        scopes.push(SkillScope {
+            scope: "project",
+            project_id: Some(id),
+            project_name: Some(name),
+            project_path: Some(path),
+            is_enabled: enabled,
+        });
+    }
+
+    let mut locations = Vec::new();
+    for SkillScope {
+        scope,
+        project_id,
+        project_name,
+        project_path,
+        is_enabled,
+    } in scopes
+    {
+        for editor in &editors {
+            let (path, disk_files): (PathBuf, Vec<SkillDiskFile>) = match editor.as_str() {
+                "claude_code" => {
+                    let base = project_path.as_deref().map(Path::new).unwrap_or(home);
+                    let dir = skill_writer::skill_dir(base, &skill.name);
+                    let disk_files = files
+                        .iter()
+                        .map(|f| {
+                            let path =
+                                skill_writer::skill_support_file_path(&dir, &f.file_type, &f.name);
+                            SkillDiskFile {
+                                file_type: f.file_type.clone(),
+                                name: f.name.clone(),
+                                exists: path.exists(),
+                                path: path.to_string_lossy().to_string(),
+                            }
+                        })
+                        .collect();
+                    (dir.join("SKILL.md"), disk_files)
+                }
+                "opencode" => {
+                    let base = match &project_path {
+                        Some(p) => Path::new(p).join(".opencode"),
+                        None => get_opencode_paths().map_err(|e| e.to_string())?.config_dir,
+                    };
+                    // Only SKILL.md is written for OpenCode, no supporting files
+                    (
+                        skill_writer::skill_file_path_opencode(&base, &skill.name),
+                        Vec::new(),
+                    )
+                }
+                _ => continue,
+            };
+
+            locations.push(SkillDiskLocation {
+                scope: scope.to_string(),
+                project_id,
+                project_name: project_name.clone(),
+                editor: editor.clone(),
+                is_enabled,
+                exists: path.exists(),
+                path: path.to_string_lossy().to_string(),
+                files: disk_files,
+            });
+        }
+    }
+
+    Ok(locations)
+}
+
 /// Delete a skill from the database (no file sync)
 pub(crate) fn delete_skill_from_db(db: &Database, id: i64) -> Result<(), String> {
     db.conn()
@@ -930,7 +1163,6 @@ pub(crate) fn create_skill_file_in_db(
 }
 
 /// Get skill files directly from the database
-#[cfg_attr(not(test), allow(dead_code))]
 pub(crate) fn get_skill_files_from_db(
     db: &Database,
     skill_id: i64,
@@ -977,6 +1209,50 @@ pub fn toggle_skill_favorite(
     Ok(())
 }
 
+/// Import a skill directory (SKILL.md plus references/assets/scripts) from
+/// any location on disk, parsed exactly like the scanner does. Returns the new id.
+#[tauri::command]
+pub fn import_skill_from_directory(
+    db: State<'_, Arc<Mutex<Database>>>,
+    path: String,
+) -> Result<i64, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    import_skill_from_directory_in_db(&db, Path::new(&path))
+}
+
+pub(crate) fn import_skill_from_directory_in_db(db: &Database, path: &Path) -> Result<i64, String> {
+    if !path.is_dir() {
+        return Err(format!("{} is not a directory", path.display()));
+    }
+    let (skill, files) = scanner::parse_agent_skill_dir(path)
+        .ok_or_else(|| format!("No readable SKILL.md found in {}", path.display()))?;
+
+    let exists: bool = db
+        .conn()
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM skills WHERE name = ?",
+            [&skill.name],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if exists {
+        return Err(format!("A skill named '{}' already exists", skill.name));
+    }
+
+    let source_path = path.to_string_lossy().to_string();
+    let (skill_id, _) =
+        scanner::get_or_create_skill(db, &skill, &source_path).map_err(|e| e.to_string())?;
+    db.conn()
+        .execute(
+            "UPDATE skills SET source = 'imported' WHERE id = ?",
+            [skill_id],
+        )
+        .map_err(|e| e.to_string())?;
+    scanner::insert_skill_files(db, skill_id, &files).map_err(|e| e.to_string())?;
+
+    Ok(skill_id)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1412,6 +1688,30 @@ mod tests {
         assert!(result.unwrap_err().contains("UNIQUE constraint failed"));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_run_skill_script_requires_confirmation() {
+        let db = Database::in_memory().unwrap();
+        let skill = create_skill_in_db(&db, &sample_skill()).unwrap();
+        create_skill_file_in_db(
+            &db,
+            &CreateSkillFileRequest {
+                skill_id: skill.id,
+                file_type: "script".to_string(),
+                name: "hello.sh".to_string(),
+                content: "echo \"hello $1\"".to_string(),
+            },
+        )
+        .unwrap();
+        let files = get_skill_files_from_db(&db, skill.id).unwrap();
+        let args = vec!["world".to_string()];
+
+        assert!(run_skill_script_with_files(&files, "hello.sh", &args, false).is_err());
+        let result = run_skill_script_with_files(&files, "hello.sh", &args, true).unwrap();
+        assert_eq!(result.stdout, "hello world\n");
+        assert_eq!(result.exit_code, Some(0));
+    }
+
     // ========================================================================
     // parse_json_array tests
     // ========================================================================
@@ -2072,4 +2372,85 @@ mod tests {
 
         assert!(assigned.is_empty());
     }
+
+    #[test]
+    fn test_get_skill_disk_locations_global_and_project() {
+        let db = Database::in_memory().unwrap();
+        let home = tempfile::TempDir::new().unwrap();
+        let project_dir = tempfile::TempDir::new().unwrap();
+
+        let skill = create_skill_in_db(&db, &sample_skill()).unwrap();
+        create_skill_file_in_db(
+            &db,
+            &CreateSkillFileRequest {
+                skill_id: skill.id,
+                file_type: "reference".to_string(),
+                name: "guide.md".to_string(),
+                content: "Guide".to_string(),
+            },
+        )
+        .unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO global_skills (skill_id) VALUES (?)",
+                [skill.id],
+            )
+            .unwrap();
+        let project_id = insert_project(&db, "proj", &project_dir.path().to_string_lossy());
+        db.conn()
+            .execute(
+                "INSERT INTO project_skills (project_id, skill_id) VALUES (?, ?)",
+                params![project_id, skill.id],
+            )
+            .unwrap();
+
+        // Only the project copy exists on disk
+        skill_writer::write_project_skill(project_dir.path(), &skill).unwrap();
+
+        let locations = get_skill_disk_locations_from_db(&db, skill.id, home.path()).unwrap();
+        assert_eq!(locations.len(), 2);
+
+        let global = &locations[0];
+        assert_eq!(global.scope, "global");
+        assert!(!global.exists);
+        assert!(global.path.starts_with(&*home.path().to_string_lossy()));
+
+        let project = &locations[1];
+        assert_eq!(project.scope, "project");
+        assert_eq!(project.project_id, Some(project_id));
+        assert!(project.exists);
+        assert!(project.path.ends_with("SKILL.md"));
+        assert_eq!(project.files.len(), 1);
+        assert!(project.files[0].path.ends_with("guide.md"));
+        assert!(!project.files[0].exists);
+    }
+
+    #[test]
+    fn test_import_skill_from_directory_creates_skill_and_files() {
+        let db = Database::in_memory().unwrap();
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let skill_dir = temp_dir.path().join("pdf-tools");
+        std::fs::create_dir_all(skill_dir.join("references")).unwrap();
+        std::fs::create_dir_all(skill_dir.join("scripts")).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\ndescription: Work with PDFs\n---\nUse the scripts.",
+        )
+        .unwrap();
+        std::fs::write(skill_dir.join("references/forms.md"), "Forms guide").unwrap();
+        std::fs::write(skill_dir.join("scripts/fill.py"), "print('hi')").unwrap();
+
+        let id = import_skill_from_directory_in_db(&db, &skill_dir).unwrap();
+
+        let skill = get_skill_by_id(&db, id).unwrap();
+        assert_eq!(skill.name, "pdf-tools");
+        assert_eq!(skill.description.as_deref(), Some("Work with PDFs"));
+        assert_eq!(skill.source, "imported");
+        let files = get_skill_files_from_db(&db, id).unwrap();
+        assert_eq!(files.len(), 2);
+
+        let err = import_skill_from_directory_in_db(&db, &skill_dir).unwrap_err();
+        assert!(err.contains("already exists"));
+        assert!(import_skill_from_directory_in_db(&db, temp_dir.path()).is_err());
+    }
 }
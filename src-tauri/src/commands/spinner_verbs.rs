@@ -1,4 +1,5 @@
 use crate::db::{Database, SpinnerVerb};
+use crate::services::pending_changes::PendingChange;
 use crate::services::spinner_verb_writer;
 use log::info;
 use serde_json::Value;
@@ -78,7 +79,9 @@ pub fn set_spinner_verb_mode(
 
 /// Sync spinner verbs to settings.json
 #[tauri::command]
-pub fn sync_spinner_verbs(db: State<'_, Arc<Mutex<Database>>>) -> Result<(), String> {
+pub fn sync_spinner_verbs(
+    db: State<'_, Arc<Mutex<Database>>>,
+) -> Result<Option<PendingChange>, String> {
     info!("[SpinnerVerbs] Syncing spinner verbs to settings.json");
     let db = db.lock().map_err(|e| e.to_string())?;
     sync_spinner_verbs_from_db(&db)
@@ -129,7 +132,7 @@ pub fn set_spinner_verb_mode_in_db(db: &Database, mode: &str) -> Result<(), Stri
     db.set_spinner_verb_mode(mode).map_err(|e| e.to_string())
 }
 
-pub fn sync_spinner_verbs_from_db(db: &Database) -> Result<(), String> {
+pub fn sync_spinner_verbs_from_db(db: &Database) -> Result<Option<PendingChange>, String> {
     let verbs = db.get_all_spinner_verbs().map_err(|e| e.to_string())?;
     let mode = db.get_spinner_verb_mode().map_err(|e| e.to_string())?;
 
@@ -2,8 +2,10 @@ use crate::db::models::{
     CreateStatusLineRequest, SegmentsPayload, StatusLine, StatusLineGalleryEntry, StatusLineSegment,
 };
 use crate::db::schema::Database;
+use crate::services::pending_changes::{PendingChange, WithPendingChanges};
 use crate::services::{statusline_gallery, statusline_writer};
 use log::info;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use tauri::State;
 
@@ -57,18 +59,24 @@ pub fn update_statusline(
 }
 
 #[tauri::command]
-pub fn delete_statusline(db: State<'_, Arc<Mutex<Database>>>, id: i64) -> Result<(), String> {
+pub fn delete_statusline(
+    db: State<'_, Arc<Mutex<Database>>>,
+    id: i64,
+) -> Result<Option<PendingChange>, String> {
     info!("[StatusLine] Deleting statusline id={}", id);
     let db = db.lock().map_err(|e| e.to_string())?;
 
     // If this was active, remove from settings
+    let mut pending = None;
     if let Ok(Some(sl)) = db.get_statusline_by_id(id) {
         if sl.is_active {
-            statusline_writer::remove_statusline_from_settings().map_err(|e| e.to_string())?;
+            pending =
+                statusline_writer::remove_statusline_from_settings().map_err(|e| e.to_string())?;
         }
     }
 
-    db.delete_statusline(id).map_err(|e| e.to_string())
+    db.delete_statusline(id).map_err(|e| e.to_string())?;
+    Ok(pending)
 }
 
 // ============================================================================
@@ -79,7 +87,7 @@ pub fn delete_statusline(db: State<'_, Arc<Mutex<Database>>>, id: i64) -> Result
 pub fn activate_statusline(
     db: State<'_, Arc<Mutex<Database>>>,
     id: i64,
-) -> Result<StatusLine, String> {
+) -> Result<WithPendingChanges<StatusLine>, String> {
     info!("[StatusLine] Activating statusline id={}", id);
     let db = db.lock().map_err(|e| e.to_string())?;
 
@@ -89,31 +97,21 @@ pub fn activate_statusline(
         .ok_or_else(|| "StatusLine not found".to_string())?;
 
     // Determine the command to write to settings.json
+    let mut pending_changes = Vec::new();
     let command = match sl.statusline_type.as_str() {
         "custom" => {
-            // Generate script from segments and write to disk
-            let payload = sl
-                .segments_json
-                .as_ref()
-                .map(|s| SegmentsPayload::parse(s))
-                .unwrap_or_else(|| SegmentsPayload {
-                    theme: "default".to_string(),
-                    segments: vec![],
-                });
-
-            let script = statusline_writer::generate_script_from_segments_with_theme(
-                &payload.segments,
-                &payload.theme,
-            );
-
-            // Write script to ~/.claude/statusline.py
             let script_path =
-                statusline_writer::write_statusline_script(&script).map_err(|e| e.to_string())?;
-
-            // Also save the generated script in the DB
-            let mut updated = sl.clone();
-            updated.generated_script = Some(script);
-            let _ = db.update_statusline(&updated);
+                statusline_writer::get_statusline_script_path().map_err(|e| e.to_string())?;
+            if is_custom_script_mode(&db) && script_path.exists() {
+                info!(
+                    "[StatusLine] Keeping user-edited script at {}",
+                    script_path.display()
+                );
+            } else {
+                // Generate script from segments and write to ~/.claude/statusline.py
+                let (_, change) = write_generated_script(&db, &sl, &script_path)?;
+                pending_changes.extend(change);
+            }
 
             format!("{} {}", python_command(), script_path.display())
         }
@@ -123,25 +121,36 @@ pub fn activate_statusline(
     };
 
     // Write to settings.json
-    statusline_writer::write_statusline_to_settings(&command, sl.padding)
-        .map_err(|e| e.to_string())?;
+    pending_changes.extend(
+        statusline_writer::write_statusline_to_settings(&command, sl.padding)
+            .map_err(|e| e.to_string())?,
+    );
 
     // Set as active in DB
     db.set_active_statusline(id).map_err(|e| e.to_string())?;
 
-    db.get_statusline_by_id(id)
+    let activated = db
+        .get_statusline_by_id(id)
         .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Failed to retrieve activated statusline".to_string())
+        .ok_or_else(|| "Failed to retrieve activated statusline".to_string())?;
+    Ok(WithPendingChanges {
+        item: activated,
+        pending_changes,
+    })
 }
 
 #[tauri::command]
-pub fn deactivate_statusline(db: State<'_, Arc<Mutex<Database>>>) -> Result<(), String> {
+pub fn deactivate_statusline(
+    db: State<'_, Arc<Mutex<Database>>>,
+) -> Result<Option<PendingChange>, String> {
     info!("[StatusLine] Deactivating statusline");
     let db = db.lock().map_err(|e| e.to_string())?;
 
-    statusline_writer::remove_statusline_from_settings().map_err(|e| e.to_string())?;
+    let pending =
+        statusline_writer::remove_statusline_from_settings().map_err(|e| e.to_string())?;
 
-    db.deactivate_all_statuslines().map_err(|e| e.to_string())
+    db.deactivate_all_statuslines().map_err(|e| e.to_string())?;
+    Ok(pending)
 }
 
 #[tauri::command]
@@ -226,6 +235,93 @@ pub fn set_statusline_gallery_url(
     statusline_gallery::set_gallery_url(&db, &url).map_err(|e| e.to_string())
 }
 
+// ============================================================================
+// Project Overrides
+// ============================================================================
+
+/// Generate a status line script into a project's `.claude/` directory and
+/// point the project's settings.json at it, overriding the global status line
+#[tauri::command]
+pub fn write_project_statusline(
+    db: State<'_, Arc<Mutex<Database>>>,
+    project_id: i64,
+    segments: Vec<StatusLineSegment>,
+    theme: Option<String>,
+    padding: Option<i32>,
+) -> Result<WithPendingChanges<String>, String> {
+    info!(
+        "[StatusLine] Writing statusline for project id={}",
+        project_id
+    );
+    let db = db.lock().map_err(|e| e.to_string())?;
+    write_project_statusline_in_db(
+        &db,
+        project_id,
+        &segments,
+        theme.as_deref().unwrap_or("default"),
+        padding.unwrap_or(0),
+    )
+}
+
+/// Remove a project's status line override so the global one applies again
+#[tauri::command]
+pub fn remove_project_statusline(
+    db: State<'_, Arc<Mutex<Database>>>,
+    project_id: i64,
+) -> Result<Option<PendingChange>, String> {
+    info!(
+        "[StatusLine] Removing statusline for project id={}",
+        project_id
+    );
+    let db = db.lock().map_err(|e| e.to_string())?;
+    let project_path = get_project_path(&db, project_id)?;
+    statusline_writer::remove_project_statusline(&project_path).map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Custom Script
+// ============================================================================
+
+/// The current contents of ~/.claude/statusline.py, if it exists
+#[tauri::command]
+pub fn get_statusline_script_content() -> Result<Option<String>, String> {
+    let script_path = statusline_writer::get_statusline_script_path().map_err(|e| e.to_string())?;
+    statusline_writer::read_statusline_script_at(&script_path).map_err(|e| e.to_string())
+}
+
+/// Whether ~/.claude/statusline.py holds user edits that activation keeps
+#[tauri::command]
+pub fn is_statusline_script_custom(db: State<'_, Arc<Mutex<Database>>>) -> Result<bool, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    Ok(is_custom_script_mode(&db))
+}
+
+/// Save a hand-edited ~/.claude/statusline.py and switch to custom-script
+/// mode, so activating a segment-based status line no longer overwrites it
+#[tauri::command]
+pub fn set_statusline_script_content(
+    db: State<'_, Arc<Mutex<Database>>>,
+    content: String,
+) -> Result<Option<PendingChange>, String> {
+    info!("[StatusLine] Saving user-edited statusline script");
+    let db = db.lock().map_err(|e| e.to_string())?;
+    let script_path = statusline_writer::get_statusline_script_path().map_err(|e| e.to_string())?;
+    save_custom_script_in_db(&db, &script_path, &content)
+}
+
+/// Leave custom-script mode, discarding the user's edits by regenerating the
+/// script from the active status line's segments. Returns the new script, or
+/// `None` when no segment-based status line is active.
+#[tauri::command]
+pub fn regenerate_statusline_script(
+    db: State<'_, Arc<Mutex<Database>>>,
+) -> Result<WithPendingChanges<Option<String>>, String> {
+    info!("[StatusLine] Regenerating statusline script from segments");
+    let db = db.lock().map_err(|e| e.to_string())?;
+    let script_path = statusline_writer::get_statusline_script_path().map_err(|e| e.to_string())?;
+    regenerate_script_in_db(&db, &script_path)
+}
+
 // ============================================================================
 // Preview & Import
 // ============================================================================
@@ -246,10 +342,173 @@ pub fn read_current_statusline_config() -> Result<Option<serde_json::Value>, Str
     statusline_writer::read_current_statusline_config().map_err(|e| e.to_string())
 }
 
+/// Snapshot the global status line (settings.json's statusLine block and
+/// statusline.py) so it can be restored on its own later
+#[tauri::command]
+pub fn backup_statusline() -> Result<statusline_writer::StatuslineBackup, String> {
+    let (settings_path, script_path, backups_dir) = global_statusline_paths()?;
+    let backup =
+        statusline_writer::backup_statusline_at(&settings_path, &script_path, &backups_dir)
+            .map_err(|e| e.to_string())?;
+    info!("[StatusLine] Backed up status line as {}", backup.id);
+    Ok(backup)
+}
+
+/// Statusline backups, newest first
+#[tauri::command]
+pub fn list_statusline_backups() -> Result<Vec<statusline_writer::StatuslineBackup>, String> {
+    let backups_dir = statusline_writer::get_statusline_backups_dir().map_err(|e| e.to_string())?;
+    statusline_writer::list_statusline_backups_at(&backups_dir).map_err(|e| e.to_string())
+}
+
+/// Restore the status line from a backup, leaving the rest of settings.json alone
+#[tauri::command]
+pub fn restore_statusline(
+    backup_id: String,
+) -> Result<WithPendingChanges<statusline_writer::StatuslineBackup>, String> {
+    let (settings_path, script_path, backups_dir) = global_statusline_paths()?;
+    let restored = statusline_writer::restore_statusline_at(
+        &settings_path,
+        &script_path,
+        &backups_dir,
+        &backup_id,
+    )
+    .map_err(|e| e.to_string())?;
+    info!(
+        "[StatusLine] Restored status line from {}",
+        restored.item.id
+    );
+    Ok(restored)
+}
+
+/// (settings.json, statusline.py, backups dir) for the global status line
+fn global_statusline_paths() -> Result<(PathBuf, PathBuf, PathBuf), String> {
+    let claude_paths = crate::utils::paths::get_claude_paths().map_err(|e| e.to_string())?;
+    let script_path = statusline_writer::get_statusline_script_path().map_err(|e| e.to_string())?;
+    let backups_dir = statusline_writer::get_statusline_backups_dir().map_err(|e| e.to_string())?;
+    Ok((claude_paths.global_settings, script_path, backups_dir))
+}
+
 // ============================================================================
 // Extracted business logic (no Tauri State dependency)
 // ============================================================================
 
+fn get_project_path(db: &Database, project_id: i64) -> Result<PathBuf, String> {
+    db.conn()
+        .query_row(
+            "SELECT path FROM projects WHERE id = ?",
+            [project_id],
+            |row| row.get::<_, String>(0),
+        )
+        .map(PathBuf::from)
+        .map_err(|e| format!("Project not found: {}", e))
+}
+
+/// Setting that marks ~/.claude/statusline.py as edited by the user
+const CUSTOM_SCRIPT_SETTING: &str = "statusline_custom_script";
+
+pub(crate) fn is_custom_script_mode(db: &Database) -> bool {
+    db.get_setting(CUSTOM_SCRIPT_SETTING)
+        .map(|v| v == "true")
+        .unwrap_or(false)
+}
+
+fn set_custom_script_mode(db: &Database, custom: bool) -> Result<(), String> {
+    db.set_setting(CUSTOM_SCRIPT_SETTING, if custom { "true" } else { "false" })
+        .map_err(|e| e.to_string())
+}
+
+/// Generate a custom status line's script from its segments, write it to
+/// `script_path` and keep a copy in the DB
+/// Write the script generated from `sl`'s segments, returning it and the
+/// write queued for confirmation
+fn write_generated_script(
+    db: &Database,
+    sl: &StatusLine,
+    script_path: &Path,
+) -> Result<(String, Option<PendingChange>), String> {
+    let payload = sl
+        .segments_json
+        .as_ref()
+        .map(|s| SegmentsPayload::parse(s))
+        .unwrap_or_else(|| SegmentsPayload {
+            theme: "default".to_string(),
+            segments: vec![],
+        });
+
+    let script = statusline_writer::generate_script_from_segments_with_theme(
+        &payload.segments,
+        &payload.theme,
+    );
+    let pending = statusline_writer::write_statusline_script_at(script_path, &script)
+        .map_err(|e| e.to_string())?;
+
+    let mut updated = sl.clone();
+    updated.generated_script = Some(script.clone());
+    let _ = db.update_statusline(&updated);
+    Ok((script, pending))
+}
+
+/// Write a user-edited script and enter custom-script mode
+pub(crate) fn save_custom_script_in_db(
+    db: &Database,
+    script_path: &Path,
+    content: &str,
+) -> Result<Option<PendingChange>, String> {
+    let pending = statusline_writer::write_statusline_script_at(script_path, content)
+        .map_err(|e| e.to_string())?;
+    set_custom_script_mode(db, true)?;
+    Ok(pending)
+}
+
+/// Leave custom-script mode and regenerate the script from the active custom
+/// status line, if there is one
+pub(crate) fn regenerate_script_in_db(
+    db: &Database,
+    script_path: &Path,
+) -> Result<WithPendingChanges<Option<String>>, String> {
+    set_custom_script_mode(db, false)?;
+    let (script, pending) = match db.get_active_statusline().map_err(|e| e.to_string())? {
+        Some(sl) if sl.statusline_type == "custom" => {
+            let (script, pending) = write_generated_script(db, &sl, script_path)?;
+            (Some(script), pending)
+        }
+        _ => (None, None),
+    };
+    Ok(WithPendingChanges {
+        item: script,
+        pending_changes: pending.into_iter().collect(),
+    })
+}
+
+/// Write a project status line override and return the command it runs
+pub(crate) fn write_project_statusline_in_db(
+    db: &Database,
+    project_id: i64,
+    segments: &[StatusLineSegment],
+    theme: &str,
+    padding: i32,
+) -> Result<WithPendingChanges<String>, String> {
+    let project_path = get_project_path(db, project_id)?;
+    let script = statusline_writer::generate_script_from_segments_with_theme(segments, theme);
+    let mut pending_changes: Vec<PendingChange> =
+        statusline_writer::write_project_statusline_script(&project_path, &script)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .collect();
+
+    let script_path = statusline_writer::get_project_statusline_script_path(&project_path);
+    let command = format!("{} {}", python_command(), script_path.display());
+    pending_changes.extend(
+        statusline_writer::write_statusline_to_project_settings(&project_path, &command, padding)
+            .map_err(|e| e.to_string())?,
+    );
+    Ok(WithPendingChanges {
+        item: command,
+        pending_changes,
+    })
+}
+
 /// Merge an update request with an existing StatusLine, preserving immutable fields
 pub(crate) fn merge_statusline_update(
     existing: StatusLine,
@@ -1121,4 +1380,71 @@ mod tests {
         assert!(request.segments_json.is_none());
         assert_eq!(request.tags, Some(vec!["tag1".to_string()]));
     }
+
+    #[test]
+    fn test_write_project_statusline_in_db() {
+        use crate::commands::statusline::write_project_statusline_in_db;
+
+        let db = Database::in_memory().unwrap();
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO projects (name, path) VALUES ('proj', ?)",
+                [temp_dir.path().to_string_lossy().to_string()],
+            )
+            .unwrap();
+        let project_id = db.conn().last_insert_rowid();
+
+        let command = write_project_statusline_in_db(&db, project_id, &[], "default", 0)
+            .unwrap()
+            .item;
+
+        let script_path = temp_dir.path().join(".claude").join("statusline.py");
+        assert!(script_path.exists());
+        assert!(command.ends_with(&script_path.display().to_string()));
+        let settings: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(temp_dir.path().join(".claude").join("settings.json"))
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(settings["statusLine"]["command"], command);
+        assert!(write_project_statusline_in_db(&db, 9999, &[], "default", 0).is_err());
+    }
+
+    #[test]
+    fn test_custom_script_mode_survives_until_regenerated() {
+        use crate::commands::statusline::{
+            is_custom_script_mode, regenerate_script_in_db, save_custom_script_in_db,
+        };
+
+        let db = Database::in_memory().unwrap();
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let script_path = temp_dir.path().join("statusline.py");
+        let sl = db
+            .create_statusline(&make_custom_request("Segments"))
+            .unwrap();
+        db.set_active_statusline(sl.id).unwrap();
+
+        save_custom_script_in_db(&db, &script_path, "print('mine')").unwrap();
+        assert!(is_custom_script_mode(&db));
+        assert_eq!(
+            statusline_writer::read_statusline_script_at(&script_path).unwrap(),
+            Some("print('mine')".to_string())
+        );
+
+        let script = regenerate_script_in_db(&db, &script_path)
+            .unwrap()
+            .item
+            .unwrap();
+        assert!(!is_custom_script_mode(&db));
+        assert!(!script.contains("print('mine')"));
+        assert_eq!(std::fs::read_to_string(&script_path).unwrap(), script);
+        assert_eq!(
+            db.get_statusline_by_id(sl.id)
+                .unwrap()
+                .unwrap()
+                .generated_script,
+            Some(script)
+        );
+    }
 }
@@ -1,3 +1,4 @@
+use crate::commands::content_limits::{content_size_warning, SavedContent};
 use crate::commands::settings::get_enabled_editors_from_db;
 use crate::db::models::{CreateSubAgentRequest, GlobalSubAgent, ProjectSubAgent, SubAgent};
 use crate::db::schema::Database;
@@ -37,6 +38,7 @@ fn row_to_subagent(row: &rusqlite::Row) -> rusqlite::Result<SubAgent> {
         initial_prompt: row.get(20)?,
         created_at: row.get(21)?,
         updated_at: row.get(22)?,
+        file_name: row.get(23)?,
     })
 }
 
@@ -65,6 +67,7 @@ fn row_to_subagent_with_offset(row: &rusqlite::Row, offset: usize) -> rusqlite::
         initial_prompt: row.get(offset + 20)?,
         created_at: row.get(offset + 21)?,
         updated_at: row.get(offset + 22)?,
+        file_name: row.get(offset + 23)?,
     })
 }
 
@@ -78,9 +81,18 @@ pub fn get_all_subagents(db: State<'_, Arc<Mutex<Database>>>) -> Result<Vec<SubA
 pub fn create_subagent(
     db: State<'_, Arc<Mutex<Database>>>,
     subagent: CreateSubAgentRequest,
-) -> Result<SubAgent, String> {
-    let db_guard = db.lock().map_err(|e| e.to_string())?;
-    create_subagent_in_db(&db_guard, &subagent)
+) -> Result<SavedContent<SubAgent>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    let created = create_subagent_in_db(&db, &subagent)?;
+    let size_warning = content_size_warning(
+        &db,
+        &format!("Sub-agent '{}'", created.name),
+        &created.content,
+    );
+    Ok(SavedContent {
+        item: created,
+        size_warning,
+    })
 }
 
 #[tauri::command]
@@ -88,9 +100,18 @@ pub fn update_subagent(
     db: State<'_, Arc<Mutex<Database>>>,
     id: i64,
     subagent: CreateSubAgentRequest,
-) -> Result<SubAgent, String> {
+) -> Result<SavedContent<SubAgent>, String> {
     let db = db.lock().map_err(|e| e.to_string())?;
-    update_subagent_in_db(&db, id, &subagent)
+    let updated = update_subagent_in_db(&db, id, &subagent)?;
+    let size_warning = content_size_warning(
+        &db,
+        &format!("Sub-agent '{}'", updated.name),
+        &updated.content,
+    );
+    Ok(SavedContent {
+        item: updated,
+        size_warning,
+    })
 }
 
 #[tauri::command]
@@ -122,7 +143,7 @@ pub fn get_global_subagents(
         .conn()
         .prepare(
             "SELECT gs.id, gs.subagent_id, gs.is_enabled,
-                    s.id, s.name, s.description, s.content, s.tools, s.model, s.permission_mode, s.skills, s.tags, s.source, s.source_path, s.is_favorite, s.created_at, s.updated_at
+                    s.id, s.name, s.description, s.content, s.tools, s.model, s.permission_mode, s.skills, s.tags, s.source, s.source_path, s.is_favorite, s.disallowed_tools, s.max_turns, s.memory, s.background, s.effort, s.isolation, s.hooks, s.mcp_servers, s.initial_prompt, s.created_at, s.updated_at, s.file_name
              FROM global_subagents gs
              JOIN subagents s ON gs.subagent_id = s.id
              ORDER BY s.name",
@@ -154,7 +175,7 @@ pub fn add_global_subagent(
 
     // Get the subagent details for file writing
     let mut stmt = db_guard.conn()
-        .prepare("SELECT id, name, description, content, tools, model, permission_mode, skills, tags, source, source_path, is_favorite, created_at, updated_at FROM subagents WHERE id = ?")
+        .prepare("SELECT id, name, description, content, tools, model, permission_mode, skills, tags, source, source_path, is_favorite, disallowed_tools, max_turns, memory, background, effort, isolation, hooks, mcp_servers, initial_prompt, created_at, updated_at, file_name FROM subagents WHERE id = ?")
         .map_err(|e| e.to_string())?;
 
     let subagent: SubAgent = stmt
@@ -195,13 +216,13 @@ pub fn remove_global_subagent(
 ) -> Result<(), String> {
     let db_guard = db.lock().map_err(|e| e.to_string())?;
 
-    // Get the subagent name for file deletion
-    let name: String = db_guard
+    // Get the subagent name and file stem for file deletion
+    let (name, file_stem): (String, String) = db_guard
         .conn()
         .query_row(
-            "SELECT name FROM subagents WHERE id = ?",
+            "SELECT name, COALESCE(file_name, name) FROM subagents WHERE id = ?",
             [subagent_id],
-            |row| row.get(0),
+            |row| Ok((row.get(0)?, row.get(1)?)),
         )
         .map_err(|e| e.to_string())?;
 
@@ -218,9 +239,9 @@ pub fn remove_global_subagent(
     for editor in &enabled_editors {
         match editor.as_str() {
             "claude_code" => {
-                subagent_writer::delete_global_subagent(&name).map_err(|e| e.to_string())?
+                subagent_writer::delete_global_subagent(&file_stem).map_err(|e| e.to_string())?
             }
-            "opencode" => subagent_writer::delete_global_subagent_opencode(&name)
+            "opencode" => subagent_writer::delete_global_subagent_opencode(&file_stem)
                 .map_err(|e| e.to_string())?,
             unknown => warn!(
                 "[SubAgents] Unknown editor type '{}' for subagent '{}'. Skipping.",
@@ -251,7 +272,7 @@ pub fn toggle_global_subagent(
     // Get the subagent details
     let mut stmt = db_guard.conn()
         .prepare(
-            "SELECT s.id, s.name, s.description, s.content, s.tools, s.model, s.permission_mode, s.skills, s.tags, s.source, s.source_path, s.is_favorite, s.created_at, s.updated_at
+            "SELECT s.id, s.name, s.description, s.content, s.tools, s.model, s.permission_mode, s.skills, s.tags, s.source, s.source_path, s.is_favorite, s.disallowed_tools, s.max_turns, s.memory, s.background, s.effort, s.isolation, s.hooks, s.mcp_servers, s.initial_prompt, s.created_at, s.updated_at, s.file_name
              FROM global_subagents gs
              JOIN subagents s ON gs.subagent_id = s.id
              WHERE gs.id = ?"
@@ -279,10 +300,14 @@ pub fn toggle_global_subagent(
             }
         } else {
             match editor.as_str() {
-                "claude_code" => subagent_writer::delete_global_subagent(&subagent.name)
-                    .map_err(|e| e.to_string())?,
-                "opencode" => subagent_writer::delete_global_subagent_opencode(&subagent.name)
-                    .map_err(|e| e.to_string())?,
+                "claude_code" => subagent_writer::delete_global_subagent(
+                    subagent_writer::subagent_file_stem(&subagent),
+                )
+                .map_err(|e| e.to_string())?,
+                "opencode" => subagent_writer::delete_global_subagent_opencode(
+                    subagent_writer::subagent_file_stem(&subagent),
+                )
+                .map_err(|e| e.to_string())?,
                 unknown => warn!(
                     "[SubAgents] Unknown editor type '{}' for subagent '{}'. Skipping.",
                     unknown, subagent.name
@@ -314,7 +339,7 @@ pub fn assign_subagent_to_project(
         .map_err(|e| e.to_string())?;
 
     let mut stmt = db_guard.conn()
-        .prepare("SELECT id, name, description, content, tools, model, permission_mode, skills, tags, source, source_path, is_favorite, created_at, updated_at FROM subagents WHERE id = ?")
+        .prepare("SELECT id, name, description, content, tools, model, permission_mode, skills, tags, source, source_path, is_favorite, disallowed_tools, max_turns, memory, background, effort, isolation, hooks, mcp_servers, initial_prompt, created_at, updated_at, file_name FROM subagents WHERE id = ?")
         .map_err(|e| e.to_string())?;
 
     let subagent: SubAgent = stmt
@@ -370,12 +395,12 @@ pub fn remove_subagent_from_project(
         )
         .map_err(|e| e.to_string())?;
 
-    let name: String = db_guard
+    let (name, file_stem): (String, String) = db_guard
         .conn()
         .query_row(
-            "SELECT name FROM subagents WHERE id = ?",
+            "SELECT name, COALESCE(file_name, name) FROM subagents WHERE id = ?",
             [subagent_id],
-            |row| row.get(0),
+            |row| Ok((row.get(0)?, row.get(1)?)),
         )
         .map_err(|e| e.to_string())?;
 
@@ -392,13 +417,14 @@ pub fn remove_subagent_from_project(
     for editor in &enabled_editors {
         match editor.as_str() {
             "claude_code" => {
-                subagent_writer::delete_project_subagent(Path::new(&project_path), &name)
-                    .map_err(|e| e.to_string())?
-            }
-            "opencode" => {
-                subagent_writer::delete_project_subagent_opencode(Path::new(&project_path), &name)
+                subagent_writer::delete_project_subagent(Path::new(&project_path), &file_stem)
                     .map_err(|e| e.to_string())?
             }
+            "opencode" => subagent_writer::delete_project_subagent_opencode(
+                Path::new(&project_path),
+                &file_stem,
+            )
+            .map_err(|e| e.to_string())?,
             unknown => warn!(
                 "[SubAgents] Unknown editor type '{}' for subagent '{}'. Skipping.",
                 unknown, name
@@ -428,7 +454,7 @@ pub fn toggle_project_subagent(
     // Get project path and subagent details
     let mut stmt = db_guard.conn()
         .prepare(
-            "SELECT p.path, s.id, s.name, s.description, s.content, s.tools, s.model, s.permission_mode, s.skills, s.tags, s.source, s.source_path, s.is_favorite, s.created_at, s.updated_at
+            "SELECT p.path, s.id, s.name, s.description, s.content, s.tools, s.model, s.permission_mode, s.skills, s.tags, s.source, s.source_path, s.is_favorite, s.disallowed_tools, s.max_turns, s.memory, s.background, s.effort, s.isolation, s.hooks, s.mcp_servers, s.initial_prompt, s.created_at, s.updated_at, s.file_name
              FROM project_subagents ps
              JOIN projects p ON ps.project_id = p.id
              JOIN subagents s ON ps.subagent_id = s.id
@@ -465,12 +491,12 @@ pub fn toggle_project_subagent(
             match editor.as_str() {
                 "claude_code" => subagent_writer::delete_project_subagent(
                     Path::new(&project_path),
-                    &subagent.name,
+                    subagent_writer::subagent_file_stem(&subagent),
                 )
                 .map_err(|e| e.to_string())?,
                 "opencode" => subagent_writer::delete_project_subagent_opencode(
                     Path::new(&project_path),
-                    &subagent.name,
+                    subagent_writer::subagent_file_stem(&subagent),
                 )
                 .map_err(|e| e.to_string())?,
                 unknown => warn!(
@@ -496,7 +522,7 @@ pub fn get_project_subagents(
         .conn()
         .prepare(
             "SELECT ps.id, ps.subagent_id, ps.is_enabled,
-                    s.id, s.name, s.description, s.content, s.tools, s.model, s.permission_mode, s.skills, s.tags, s.source, s.source_path, s.is_favorite, s.created_at, s.updated_at
+                    s.id, s.name, s.description, s.content, s.tools, s.model, s.permission_mode, s.skills, s.tags, s.source, s.source_path, s.is_favorite, s.disallowed_tools, s.max_turns, s.memory, s.background, s.effort, s.isolation, s.hooks, s.mcp_servers, s.initial_prompt, s.created_at, s.updated_at, s.file_name
              FROM project_subagents ps
              JOIN subagents s ON ps.subagent_id = s.id
              WHERE ps.project_id = ?
@@ -563,7 +589,7 @@ pub(crate) fn get_subagent_by_id(db: &Database, id: i64) -> Result<SubAgent, Str
     let mut stmt = db
         .conn()
         .prepare(
-            "SELECT id, name, description, content, tools, model, permission_mode, skills, tags, source, source_path, is_favorite, disallowed_tools, max_turns, memory, background, effort, isolation, hooks, mcp_servers, initial_prompt, created_at, updated_at
+            "SELECT id, name, description, content, tools, model, permission_mode, skills, tags, source, source_path, is_favorite, disallowed_tools, max_turns, memory, background, effort, isolation, hooks, mcp_servers, initial_prompt, created_at, updated_at, file_name
              FROM subagents WHERE id = ?",
         )
         .map_err(|e| e.to_string())?;
@@ -577,8 +603,8 @@ pub(crate) fn get_all_subagents_from_db(db: &Database) -> Result<Vec<SubAgent>,
     let mut stmt = db
         .conn()
         .prepare(
-            "SELECT id, name, description, content, tools, model, permission_mode, skills, tags, source, source_path, is_favorite, disallowed_tools, max_turns, memory, background, effort, isolation, hooks, mcp_servers, initial_prompt, created_at, updated_at
-             FROM subagents ORDER BY name",
+            "SELECT id, name, description, content, tools, model, permission_mode, skills, tags, source, source_path, is_favorite, disallowed_tools, max_turns, memory, background, effort, isolation, hooks, mcp_servers, initial_prompt, created_at, updated_at, file_name
+             FROM subagents WHERE removed_at IS NULL ORDER BY name",
         )
         .map_err(|e| e.to_string())?;
 
@@ -1120,6 +1146,7 @@ mod tests {
             initial_prompt: None,
             created_at: "2024-01-01".to_string(),
             updated_at: "2024-01-01".to_string(),
+            file_name: None,
         };
         let json = serde_json::to_string(&subagent).unwrap();
         let deserialized: SubAgent = serde_json::from_str(&json).unwrap();
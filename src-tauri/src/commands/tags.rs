@@ -0,0 +1,255 @@
+use crate::db::schema::Database;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// Library tables with a JSON `tags` array column
+const TAGGED_TABLES: &[&str] = &[
+    "mcps",
+    "skills",
+    "commands",
+    "subagents",
+    "hooks",
+    "rules",
+    "statuslines",
+    "permission_templates",
+    "containers",
+];
+
+/// One spelling of a tag and how many entities use it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagVariant {
+    pub tag: String,
+    pub count: usize,
+}
+
+/// Tags that differ only in case, whitespace or separators
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagVariantGroup {
+    /// Most used spelling, a reasonable default for `merge_tags`
+    pub suggested: String,
+    /// Every spelling, most used first
+    pub variants: Vec<TagVariant>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagMergeResult {
+    /// Entities whose tags changed
+    pub updated: usize,
+}
+
+/// `Code Review`, `code-review` and `codereview` all map to `codereview`
+fn tag_key(tag: &str) -> String {
+    tag.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// `(table, id, tags)` of a tagged entity
+type TaggedRow = (&'static str, i64, Vec<String>);
+
+/// Every entity that has tags
+fn tagged_rows(db: &Database) -> Result<Vec<TaggedRow>, String> {
+    let mut rows = Vec::new();
+    for table in TAGGED_TABLES {
+        let mut stmt = db
+            .conn()
+            .prepare(&format!(
+                "SELECT id, tags FROM {table} WHERE tags IS NOT NULL AND tags != ''"
+            ))
+            .map_err(|e| e.to_string())?;
+        let tagged = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .filter_map(|(id, json)| {
+                serde_json::from_str::<Vec<String>>(&json)
+                    .ok()
+                    .map(|tags| (*table, id, tags))
+            });
+        rows.extend(tagged);
+    }
+    Ok(rows)
+}
+
+/// Cluster the library's tags into groups of near-duplicate spellings
+#[tauri::command]
+pub fn list_tag_variants(
+    db: State<'_, Arc<Mutex<Database>>>,
+) -> Result<Vec<TagVariantGroup>, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    list_tag_variants_from_db(&db)
+}
+
+pub(crate) fn list_tag_variants_from_db(db: &Database) -> Result<Vec<TagVariantGroup>, String> {
+    let mut groups: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+    for (_, _, tags) in tagged_rows(db)? {
+        for tag in tags {
+            let key = tag_key(&tag);
+            if key.is_empty() {
+                continue;
+            }
+            *groups.entry(key).or_default().entry(tag).or_default() += 1;
+        }
+    }
+
+    Ok(groups
+        .into_values()
+        .filter(|spellings| spellings.len() > 1)
+        .map(|spellings| {
+            let mut variants: Vec<TagVariant> = spellings
+                .into_iter()
+                .map(|(tag, count)| TagVariant { tag, count })
+                .collect();
+            variants.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+            TagVariantGroup {
+                suggested: variants[0].tag.clone(),
+                variants,
+            }
+        })
+        .collect())
+}
+
+/// Replace every tag in `variants` with `canonical` across all entity types
+#[tauri::command]
+pub fn merge_tags(
+    db: State<'_, Arc<Mutex<Database>>>,
+    canonical: String,
+    variants: Vec<String>,
+) -> Result<TagMergeResult, String> {
+    let db = db.lock().map_err(|e| e.to_string())?;
+    merge_tags_in_db(&db, &canonical, &variants)
+}
+
+pub(crate) fn merge_tags_in_db(
+    db: &Database,
+    canonical: &str,
+    variants: &[String],
+) -> Result<TagMergeResult, String> {
+    let canonical = canonical.trim();
+    if canonical.is_empty() {
+        return Err("Canonical tag cannot be empty".to_string());
+    }
+
+    let tx = db
+        .conn()
+        .unchecked_transaction()
+        .map_err(|e| e.to_string())?;
+    let mut updated = 0;
+    for (table, id, tags) in tagged_rows(db)? {
+        let mut merged: Vec<String> = Vec::with_capacity(tags.len());
+        for tag in &tags {
+            let tag = if variants.contains(tag) {
+                canonical
+            } else {
+                tag.as_str()
+            };
+            if !merged.iter().any(|t| t == tag) {
+                merged.push(tag.to_string());
+            }
+        }
+        if merged == tags {
+            continue;
+        }
+        let json = serde_json::to_string(&merged).map_err(|e| e.to_string())?;
+        db.conn()
+            .execute(
+                &format!("UPDATE {table} SET tags = ? WHERE id = ?"),
+                rusqlite::params![json, id],
+            )
+            .map_err(|e| e.to_string())?;
+        updated += 1;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    info!(
+        "[Tags] Merged {} variants into '{}' on {} entities",
+        variants.len(),
+        canonical,
+        updated
+    );
+    Ok(TagMergeResult { updated })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn insert_tagged(db: &Database, sql: &str, tags: &str) -> i64 {
+        db.conn().execute(sql, [tags]).unwrap();
+        db.conn().last_insert_rowid()
+    }
+
+    fn tags_of(db: &Database, table: &str, id: i64) -> Vec<String> {
+        let json: String = db
+            .conn()
+            .query_row(
+                &format!("SELECT tags FROM {table} WHERE id = ?"),
+                [id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn test_tag_key_ignores_case_whitespace_and_separators() {
+        assert_eq!(tag_key("Code Review"), "codereview");
+        assert_eq!(tag_key("code-review"), "codereview");
+        assert_eq!(tag_key(" code_review "), "codereview");
+        assert_ne!(tag_key("code"), tag_key("review"));
+    }
+
+    #[test]
+    fn test_list_and_merge_tag_variants() {
+        let db = Database::in_memory().unwrap();
+        let mcp = insert_tagged(
+            &db,
+            "INSERT INTO mcps (name, type, command, tags) VALUES ('m', 'stdio', 'npx', ?)",
+            r#"["code-review", "Code Review", "git"]"#,
+        );
+        let skill = insert_tagged(
+            &db,
+            "INSERT INTO skills (name, content, tags) VALUES ('s', 'x', ?)",
+            r#"["code-review"]"#,
+        );
+        let hook = insert_tagged(
+            &db,
+            "INSERT INTO hooks (name, event_type, hook_type, command, tags) VALUES ('h', 'Stop', 'command', 'true', ?)",
+            r#"["codereview", "Git"]"#,
+        );
+
+        let groups = list_tag_variants_from_db(&db).unwrap();
+        assert_eq!(groups.len(), 2);
+        let review = groups
+            .iter()
+            .find(|g| g.suggested == "code-review")
+            .unwrap();
+        assert_eq!(review.variants.len(), 3);
+        assert_eq!(review.variants[0].count, 2);
+
+        let result = merge_tags_in_db(
+            &db,
+            "code-review",
+            &["Code Review".to_string(), "codereview".to_string()],
+        )
+        .unwrap();
+        assert_eq!(result.updated, 2);
+        assert_eq!(tags_of(&db, "mcps", mcp), vec!["code-review", "git"]);
+        assert_eq!(tags_of(&db, "skills", skill), vec!["code-review"]);
+        assert_eq!(tags_of(&db, "hooks", hook), vec!["code-review", "Git"]);
+
+        let remaining = list_tag_variants_from_db(&db).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].variants.len(), 2);
+        assert!(merge_tags_in_db(&db, " ", &[]).is_err());
+    }
+}
@@ -22,6 +22,28 @@ pub struct Mcp {
     pub is_favorite: bool,
     pub created_at: String,
     pub updated_at: String,
+    /// False for MCPs that are managed externally and must never be spawned
+    /// by tests or health checks
+    #[serde(default = "default_true")]
+    pub testable: bool,
+    /// Path to a `.env` file merged into the spawn environment (`env` wins)
+    #[serde(default)]
+    pub env_file: Option<String>,
+    /// Label shown in the app instead of `name`. Config files always use `name`.
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// Upstream homepage or repository, recorded when imported from the
+    /// registry or a marketplace repo
+    #[serde(default)]
+    pub homepage: Option<String>,
+    /// False to spawn the stdio server with only its configured `env` (plus
+    /// PATH and HOME) instead of the app's full environment
+    #[serde(default = "default_true")]
+    pub inherit_env: bool,
+    /// Shell the stdio command runs through: `none`, `sh`, `bash`, `zsh` or
+    /// an absolute path. `sh` when unset.
+    #[serde(default)]
+    pub shell: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +60,24 @@ pub struct CreateMcpRequest {
     pub env: Option<HashMap<String, String>>,
     pub icon: Option<String>,
     pub tags: Option<Vec<String>>,
+    /// Defaults to true on create; left unchanged on update when omitted
+    #[serde(default)]
+    pub testable: Option<bool>,
+    #[serde(default)]
+    pub env_file: Option<String>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// Defaults to true on create; left unchanged on update when omitted
+    #[serde(default)]
+    pub inherit_env: Option<bool>,
+    /// Shell the stdio command runs through: `none`, `sh`, `bash`, `zsh` or
+    /// an absolute path. `sh` when unset; blank counts as unset.
+    #[serde(default)]
+    pub shell: Option<String>,
+    /// Split a full command line pasted into `command` into the command and
+    /// leading args before saving
+    #[serde(default)]
+    pub split_command: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,6 +102,18 @@ fn default_editor_type() -> String {
     "claude_code".to_string()
 }
 
+fn default_true() -> bool {
+    true
+}
+
+/// Trim an MCP display name, storing blank names as unset
+pub fn normalize_display_name(display_name: Option<&str>) -> Option<String> {
+    display_name
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProjectMcp {
@@ -93,6 +145,37 @@ pub struct GatewayMcp {
     pub auto_restart: bool,
     pub display_order: i32,
     pub created_at: String,
+    #[serde(default)]
+    pub tool_filter: Option<GatewayToolFilter>,
+}
+
+/// How a gateway tool filter treats the listed tool names
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GatewayToolFilterMode {
+    /// Only the listed tools are exposed
+    Allow,
+    /// Every tool except the listed ones is exposed
+    Deny,
+}
+
+/// Per-backend filter on which tools the gateway exposes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayToolFilter {
+    pub mode: GatewayToolFilterMode,
+    pub tools: Vec<String>,
+}
+
+impl GatewayToolFilter {
+    /// Whether the gateway should expose `tool_name`
+    pub fn allows(&self, tool_name: &str) -> bool {
+        let listed = self.tools.iter().any(|t| t == tool_name);
+        match self.mode {
+            GatewayToolFilterMode::Allow => listed,
+            GatewayToolFilterMode::Deny => !listed,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -223,6 +306,30 @@ pub struct GlobalSkill {
     pub is_enabled: bool,
 }
 
+/// Where a skill is written on disk for one scope and editor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillDiskLocation {
+    pub scope: String, // "global" or "project"
+    pub project_id: Option<i64>,
+    pub project_name: Option<String>,
+    pub editor: String,
+    pub is_enabled: bool,
+    pub path: String,
+    pub exists: bool,
+    pub files: Vec<SkillDiskFile>,
+}
+
+/// A supporting skill file (reference, asset, script) at a disk location
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillDiskFile {
+    pub file_type: String,
+    pub name: String,
+    pub path: String,
+    pub exists: bool,
+}
+
 // Skill Files (references, assets, scripts)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -272,6 +379,10 @@ pub struct SubAgent {
     pub initial_prompt: Option<String>,
     pub created_at: String,
     pub updated_at: String,
+    /// Stem of the agent's file when it differs from `name` (imported agents
+    /// whose frontmatter `name:` doesn't match the filename)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_name: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -348,7 +459,7 @@ pub struct CreateRepoRequest {
 pub struct RepoItem {
     pub id: i64,
     pub repo_id: i64,
-    pub item_type: String, // "mcp", "skill", or "subagent"
+    pub item_type: String, // "mcp", "skill", "subagent", or "hook"
     pub name: String,
     pub description: Option<String>,
     pub source_url: Option<String>,
@@ -360,6 +471,12 @@ pub struct RepoItem {
     pub imported_item_id: Option<i64>,
     pub created_at: String,
     pub updated_at: String,
+    /// An imported entity from this item is still in the library
+    #[serde(default)]
+    pub installed: bool,
+    /// The item's content changed upstream since it was imported
+    #[serde(default)]
+    pub update_available: bool,
 }
 
 // Sync result
@@ -372,6 +489,59 @@ pub struct SyncResult {
     pub errors: Vec<String>,
 }
 
+// A repo affected by reset_repos_to_defaults
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoResetEntry {
+    pub name: String,
+    pub github_url: String,
+}
+
+// What reset_repos_to_defaults removes, re-adds and keeps
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoResetPlan {
+    pub dry_run: bool,
+    /// User-added repos that will be deleted
+    pub removed: Vec<RepoResetEntry>,
+    /// Default repos that are missing and will be restored
+    pub added: Vec<RepoResetEntry>,
+    /// Default repos already present (recreated with a fresh item cache)
+    pub kept: Vec<RepoResetEntry>,
+    pub cached_items_cleared: i64,
+}
+
+// One item a repo would expose, from preview_repo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoPreviewItem {
+    pub item_type: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub file_path: Option<String>,
+    pub source_url: Option<String>,
+    /// Bytes of fetched content; README-linked items are only fetched on import
+    pub size: Option<i64>,
+}
+
+// A repo's contents fetched into memory without adding it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoPreview {
+    pub name: String,
+    pub github_url: String,
+    pub git_ref: Option<String>,
+    pub repo_type: String,
+    pub content_type: String,
+    /// True when a repo with the same owner/name is already added
+    pub already_added: bool,
+    /// Item counts keyed by item type
+    pub counts: std::collections::BTreeMap<String, i32>,
+    pub total_bytes: i64,
+    pub items: Vec<RepoPreviewItem>,
+    pub warnings: Vec<String>,
+}
+
 // GitHub rate limit info
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -391,6 +561,34 @@ pub struct ImportResult {
     pub message: Option<String>,
 }
 
+// What a bulk import does when the library already has an entity with an item's name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportConflictPolicy {
+    /// Leave the existing entity alone and don't import the item
+    Skip,
+    /// Treat the existing entity as the imported copy of the item
+    Link,
+    /// Import the item under a free name (`name-2`, `name-3`, ...)
+    Rename,
+}
+
+// Outcome of one item in import_all_repo_items
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkImportItem {
+    pub repo_item_id: i64,
+    pub name: String,
+    pub item_type: String,
+    /// "imported", "renamed", "linked", "skipped", "already_imported" or "failed"
+    pub status: String,
+    /// The library entity created or linked for the item
+    pub entity_id: Option<i64>,
+    /// The name used when the item was imported under a different name
+    pub imported_as: Option<String>,
+    pub message: Option<String>,
+}
+
 // Hooks (Event-triggered actions)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -415,6 +613,8 @@ pub struct Hook {
     pub once: bool,
     pub async_mode: bool,
     pub shell: Option<String>,
+    /// Only run when the tool input's file path matches this glob (e.g. `src/**`)
+    pub path_pattern: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -439,6 +639,32 @@ pub struct CreateHookRequest {
     pub once: Option<bool>,
     pub async_mode: Option<bool>,
     pub shell: Option<String>,
+    pub path_pattern: Option<String>,
+}
+
+impl From<&Hook> for CreateHookRequest {
+    fn from(hook: &Hook) -> Self {
+        CreateHookRequest {
+            name: hook.name.clone(),
+            description: hook.description.clone(),
+            event_type: hook.event_type.clone(),
+            matcher: hook.matcher.clone(),
+            hook_type: hook.hook_type.clone(),
+            command: hook.command.clone(),
+            prompt: hook.prompt.clone(),
+            timeout: hook.timeout,
+            tags: hook.tags.clone(),
+            url: hook.url.clone(),
+            headers: hook.headers.clone(),
+            allowed_env_vars: hook.allowed_env_vars.clone(),
+            if_condition: hook.if_condition.clone(),
+            status_message: hook.status_message.clone(),
+            once: Some(hook.once),
+            async_mode: Some(hook.async_mode),
+            shell: hook.shell.clone(),
+            path_pattern: hook.path_pattern.clone(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -513,6 +739,11 @@ pub struct AppSettings {
     /// List of enabled editor IDs (e.g., ["claude_code", "opencode"])
     /// When an editor is enabled, skills/commands/subagents/MCPs sync to it
     pub enabled_editors: Vec<String>,
+    /// Copy the values of each MCP's `env_file` into the editor configs it
+    /// writes. Off by default: the variables are written as `${VAR}`
+    /// references so secrets stay in the `.env` file only.
+    #[serde(default)]
+    pub inline_env_files: bool,
 }
 
 impl Default for AppSettings {
@@ -520,6 +751,7 @@ impl Default for AppSettings {
         Self {
             // By default, only Claude Code is enabled
             enabled_editors: vec!["claude_code".to_string()],
+            inline_env_files: false,
         }
     }
 }
@@ -571,15 +803,28 @@ pub struct GeminiPaths {
     pub settings_file: String, // ~/.gemini/settings.json
 }
 
+// Windsurf paths (for Windsurf support)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindsurfPaths {
+    pub config_dir: String,           // ~/.codeium/windsurf/
+    pub mcp_config_file: String,      // ~/.codeium/windsurf/mcp_config.json
+    pub global_rules_file: String,    // ~/.codeium/windsurf/memories/global_rules.md
+    pub global_workflows_dir: String, // ~/.codeium/windsurf/global_workflows/
+}
+
 // Editor info for frontend
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EditorInfo {
     pub id: String,          // "claude_code" or "opencode"
     pub name: String,        // "Claude Code" or "OpenCode"
-    pub is_installed: bool,  // Whether config directory exists
+    pub is_installed: bool,  // Whether any install signal fired
     pub is_enabled: bool,    // Whether syncing to this editor is enabled
     pub config_path: String, // Path to main config file
+    /// Install signals that fired: "binary", "config_dir", "app_bundle"
+    #[serde(default)]
+    pub installed_via: Vec<String>,
 }
 
 // Configuration Profiles
@@ -998,6 +1243,12 @@ mod tests {
             is_favorite: false,
             created_at: "2024-01-01".to_string(),
             updated_at: "2024-01-01".to_string(),
+            testable: true,
+            env_file: None,
+            display_name: None,
+            homepage: None,
+            inherit_env: true,
+            shell: None,
         };
 
         let json = serde_json::to_string(&mcp).unwrap();
@@ -1060,6 +1311,12 @@ mod tests {
             env: None,
             icon: Some("icon".to_string()),
             tags: Some(vec!["tag1".to_string(), "tag2".to_string()]),
+            testable: None,
+            env_file: None,
+            display_name: None,
+            inherit_env: None,
+            shell: None,
+            split_command: None,
         };
 
         let json = serde_json::to_string(&req).unwrap();
@@ -1251,6 +1508,7 @@ mod tests {
             initial_prompt: None,
             created_at: "2024-01-01".to_string(),
             updated_at: "2024-01-01".to_string(),
+            file_name: None,
         };
 
         let json = serde_json::to_string(&agent).unwrap();
@@ -1301,6 +1559,7 @@ mod tests {
             once: false,
             async_mode: false,
             shell: None,
+            path_pattern: None,
             created_at: "2024-01-01".to_string(),
             updated_at: "2024-01-01".to_string(),
         };
@@ -1333,6 +1592,7 @@ mod tests {
             once: None,
             async_mode: None,
             shell: None,
+            path_pattern: None,
         };
 
         let json = serde_json::to_string(&req).unwrap();
@@ -1360,6 +1620,7 @@ mod tests {
             once: None,
             async_mode: None,
             shell: None,
+            path_pattern: None,
         };
 
         let json = serde_json::to_string(&req).unwrap();
@@ -1413,6 +1674,8 @@ mod tests {
             imported_item_id: None,
             created_at: "2024-01-01".to_string(),
             updated_at: "2024-01-01".to_string(),
+            installed: false,
+            update_available: false,
         };
 
         let json = serde_json::to_string(&item).unwrap();
@@ -1469,6 +1732,7 @@ mod tests {
     fn test_app_settings_serde() {
         let settings = AppSettings {
             enabled_editors: vec!["claude_code".to_string(), "opencode".to_string()],
+            inline_env_files: false,
         };
 
         let json = serde_json::to_string(&settings).unwrap();
@@ -1577,6 +1841,12 @@ mod tests {
             is_favorite: false,
             created_at: "2024".to_string(),
             updated_at: "2024".to_string(),
+            testable: true,
+            env_file: None,
+            display_name: None,
+            homepage: None,
+            inherit_env: true,
+            shell: None,
         };
 
         let project_mcp = ProjectMcp {
@@ -2059,10 +2329,12 @@ mod tests {
             is_installed: true,
             is_enabled: true,
             config_path: "/home/user/.claude.json".to_string(),
+            installed_via: vec!["binary".to_string()],
         };
 
         let json = serde_json::to_string(&info).unwrap();
         assert!(json.contains("isInstalled"));
+        assert!(json.contains("installedVia"));
         assert!(json.contains("isEnabled"));
         assert!(json.contains("configPath"));
 
@@ -2296,6 +2568,12 @@ mod tests {
             is_favorite: false,
             created_at: "2024".to_string(),
             updated_at: "2024".to_string(),
+            testable: true,
+            env_file: None,
+            display_name: None,
+            homepage: None,
+            inherit_env: true,
+            shell: None,
         };
 
         let global = GlobalMcp {
@@ -2331,6 +2609,12 @@ mod tests {
             is_favorite: false,
             created_at: "2024".to_string(),
             updated_at: "2024".to_string(),
+            testable: true,
+            env_file: None,
+            display_name: None,
+            homepage: None,
+            inherit_env: true,
+            shell: None,
         };
 
         let gw = GatewayMcp {
@@ -2341,6 +2625,7 @@ mod tests {
             auto_restart: true,
             display_order: 0,
             created_at: "2024".to_string(),
+            tool_filter: None,
         };
 
         let json = serde_json::to_string(&gw).unwrap();
@@ -2351,6 +2636,26 @@ mod tests {
         assert!(parsed.auto_restart);
     }
 
+    #[test]
+    fn test_gateway_tool_filter_allows() {
+        let allow = GatewayToolFilter {
+            mode: GatewayToolFilterMode::Allow,
+            tools: vec!["read_file".to_string()],
+        };
+        assert!(allow.allows("read_file"));
+        assert!(!allow.allows("delete_everything"));
+
+        let deny = GatewayToolFilter {
+            mode: GatewayToolFilterMode::Deny,
+            tools: vec!["delete_everything".to_string()],
+        };
+        assert!(deny.allows("read_file"));
+        assert!(!deny.allows("delete_everything"));
+
+        let json = serde_json::to_string(&deny).unwrap();
+        assert_eq!(json, r#"{"mode":"deny","tools":["delete_everything"]}"#);
+    }
+
     #[test]
     fn test_project_skill_serde() {
         let skill = Skill {
@@ -2450,6 +2755,7 @@ mod tests {
             initial_prompt: None,
             created_at: "2024".to_string(),
             updated_at: "2024".to_string(),
+            file_name: None,
         };
 
         let psa = ProjectSubAgent {
@@ -2489,6 +2795,7 @@ mod tests {
             initial_prompt: None,
             created_at: "2024".to_string(),
             updated_at: "2024".to_string(),
+            file_name: None,
         };
 
         let gsa = GlobalSubAgent {
@@ -2525,6 +2832,7 @@ mod tests {
             once: false,
             async_mode: false,
             shell: None,
+            path_pattern: None,
             created_at: "2024".to_string(),
             updated_at: "2024".to_string(),
         };
@@ -2563,6 +2871,7 @@ mod tests {
             once: false,
             async_mode: false,
             shell: None,
+            path_pattern: None,
             created_at: "2024".to_string(),
             updated_at: "2024".to_string(),
         };
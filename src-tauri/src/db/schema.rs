@@ -1,11 +1,126 @@
+use crate::db::models::normalize_display_name;
 use anyhow::Result;
-use rusqlite::Connection;
-use std::path::Path;
+use rusqlite::{Connection, ErrorCode, OptionalExtension};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Schema migrations run by `run_schema_migrations`, in order (version, description)
+pub const SCHEMA_MIGRATIONS: &[(i64, &str)] = &[
+    (
+        1,
+        "Add allowed_tools to skills table (for databases created before this column existed)",
+    ),
+    (2, "Add permission_mode and skills columns to subagents"),
+    (3, "Rename agents tables to subagents"),
+    (
+        4,
+        "Add model and disable_model_invocation columns to skills",
+    ),
+    (5, "Add editor_type column to projects for OpenCode support"),
+    (
+        6,
+        "Migrate commands from skills table to new commands table",
+    ),
+    (7, "Create app_settings table for application preferences"),
+    (8, "Add source_path column to skills and commands tables"),
+    (9, "Add source_path column to subagents table"),
+    (
+        10,
+        "Add is_favorite column to mcps, commands, skills, and subagents tables",
+    ),
+    (11, "Add profiles and profile_items tables"),
+    (12, "Add is_favorite column to projects table"),
+    (13, "Add statuslines table"),
+    (14, "Add spinner_verbs and spinner_verb_config tables"),
+    (15, "Add permission_templates table"),
+    (
+        16,
+        "Add docker_hosts, containers, and project_containers tables",
+    ),
+    (17, "Add repo_url column to containers table"),
+    (18, "Add new hook types and fields to hooks table"),
+    (19, "Add rules, global_rules, and project_rules tables"),
+    (20, "Add new frontmatter fields to subagents table"),
+    (21, "Add new frontmatter fields to skills table"),
+    (22, "Add effort column to skills table"),
+    (23, "Add 'ws' to MCP type CHECK constraint"),
+    (24, "Fix is_favorite column type in mcps table"),
+    (25, "Add per-backend tool filter columns to gateway_mcps"),
+    (26, "Cache of the tools each MCP last reported"),
+    (
+        27,
+        "Add testable flag to mcps (false = never spawned by tests)",
+    ),
+    (28, "Add env_file to mcps (.env file loaded when spawning)"),
+    (
+        29,
+        "Add path_pattern to hooks (file path guard wrapped around the command)",
+    ),
+    (
+        30,
+        "Add repo_imports linking imported entities to marketplace items",
+    ),
+    (31, "Add mcp_test_history recording each MCP test result"),
+    (
+        32,
+        "Add display_name to mcps (UI label separate from the config key)",
+    ),
+    (33, "Add homepage to mcps (upstream docs or repository URL)"),
+    (
+        34,
+        "Allow hook items in repo_items and repo_imports (plugin hooks)",
+    ),
+    (
+        35,
+        "Add inherit_env to mcps (spawn stdio servers with a clean environment)",
+    ),
+    (
+        36,
+        "Add file_name to subagents (file stem when it differs from the frontmatter name)",
+    ),
+    (
+        37,
+        "Add shell to mcps (shell stdio servers are spawned through)",
+    ),
+    (
+        38,
+        "Add mcp_oauth holding OAuth refresh settings for remote MCPs",
+    ),
+    (
+        39,
+        "Add source_hash to scanned tables (refresh auto-detected items changed on disk)",
+    ),
+    (
+        40,
+        "Add mcp_conflicts holding competing definitions of same-named MCPs",
+    ),
+    (
+        41,
+        "Add removed_at to auto-detected tables (source gone from disk)",
+    ),
+];
 
 pub struct Database {
     conn: Connection,
 }
 
+/// A corrupt database file that was moved aside during startup
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseRecovery {
+    pub backup_path: String,
+    pub reason: String,
+}
+
+/// Whether an error means the file itself is damaged (as opposed to e.g. a
+/// locked database or a failing migration)
+fn is_corruption_error(err: &rusqlite::Error) -> bool {
+    matches!(
+        err.sqlite_error_code(),
+        Some(ErrorCode::DatabaseCorrupt) | Some(ErrorCode::NotADatabase)
+    )
+}
+
 impl Database {
     pub fn new(path: &Path) -> Result<Self> {
         let conn = Connection::open(path)?;
@@ -13,6 +128,80 @@ impl Database {
         Ok(Self { conn })
     }
 
+    /// Open the database, moving a corrupt file aside and starting fresh.
+    ///
+    /// The file is only replaced when SQLite reports corruption (a failing
+    /// `PRAGMA integrity_check`, or a corrupt / not-a-database error); any
+    /// other failure is returned as-is so a healthy library is never discarded.
+    pub fn open_or_recover(path: &Path) -> Result<(Self, Option<DatabaseRecovery>)> {
+        let reason = match Self::new(path) {
+            Ok(db) => match db.integrity_check() {
+                Ok(None) => return Ok((db, None)),
+                Ok(Some(problem)) => problem,
+                Err(e) if is_corruption_error(&e) => e.to_string(),
+                Err(e) => return Err(e.into()),
+            },
+            Err(e) => match e.downcast_ref::<rusqlite::Error>() {
+                Some(sqlite_err) if is_corruption_error(sqlite_err) => sqlite_err.to_string(),
+                _ => return Err(e),
+            },
+        };
+
+        let backup_path = Self::move_aside(path)?;
+        log::error!(
+            "Database at {} is corrupt ({}); moved to {} and starting fresh",
+            path.display(),
+            reason,
+            backup_path.display()
+        );
+
+        let db = Self::new(path)?;
+        Ok((
+            db,
+            Some(DatabaseRecovery {
+                backup_path: backup_path.to_string_lossy().to_string(),
+                reason,
+            }),
+        ))
+    }
+
+    /// Run `PRAGMA integrity_check`. Returns `None` when the database is
+    /// healthy, otherwise the problems SQLite reported.
+    pub fn integrity_check(&self) -> rusqlite::Result<Option<String>> {
+        let mut stmt = self.conn.prepare("PRAGMA integrity_check")?;
+        let problems: Vec<String> = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        if problems.len() == 1 && problems[0] == "ok" {
+            Ok(None)
+        } else {
+            Ok(Some(problems.join("; ")))
+        }
+    }
+
+    /// Rename the database (and any WAL/SHM sidecars) to
+    /// `<name>.corrupt-<timestamp>`, returning the new database path
+    fn move_aside(path: &Path) -> Result<PathBuf> {
+        let suffix = format!("corrupt-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+        let with_suffix = |p: &Path| {
+            let mut name = p.as_os_str().to_owned();
+            name.push(format!(".{}", suffix));
+            PathBuf::from(name)
+        };
+
+        let backup_path = with_suffix(path);
+        std::fs::rename(path, &backup_path)?;
+        for sidecar in ["-wal", "-shm"] {
+            let mut name = path.as_os_str().to_owned();
+            name.push(sidecar);
+            let sidecar_path = PathBuf::from(name);
+            if sidecar_path.exists() {
+                std::fs::rename(&sidecar_path, with_suffix(&sidecar_path))?;
+            }
+        }
+        Ok(backup_path)
+    }
+
     /// Create an in-memory database for testing
     #[cfg(test)]
     pub fn in_memory() -> Result<Self> {
@@ -314,7 +503,7 @@ impl Database {
             CREATE TABLE IF NOT EXISTS repo_items (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
                 repo_id INTEGER NOT NULL,
-                item_type TEXT NOT NULL CHECK (item_type IN ('mcp', 'skill', 'subagent')),
+                item_type TEXT NOT NULL CHECK (item_type IN ('mcp', 'skill', 'subagent', 'hook')),
                 name TEXT NOT NULL,
                 description TEXT,
                 source_url TEXT,
@@ -374,6 +563,17 @@ impl Database {
     }
 
     fn run_schema_migrations(&self) -> Result<()> {
+        // Tracks which of SCHEMA_MIGRATIONS have run. Databases that predate the
+        // table record every migration the first time they open with it.
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS schema_migrations (
+                version INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                applied_at TEXT DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )?;
+
         // Migration 1: Add allowed_tools to skills table (for databases created before this column existed)
         // Note: skill_type and argument_hint were moved to the commands table in a later migration
         let has_allowed_tools: bool = self
@@ -392,6 +592,7 @@ impl Database {
                 "#,
             )?;
         }
+        self.record_migration(1)?;
 
         // Migration 2: Add permission_mode and skills columns to subagents
         let has_permission_mode: bool = self.conn.query_row(
@@ -408,6 +609,7 @@ impl Database {
                 "#,
             )?;
         }
+        self.record_migration(2)?;
 
         // Migration 3: Rename agents tables to subagents
         let has_agents_table: bool = self
@@ -444,6 +646,7 @@ impl Database {
                 "#,
             )?;
         }
+        self.record_migration(3)?;
 
         // Migration 4: Add model and disable_model_invocation columns to skills
         let has_skill_model: bool = self
@@ -463,6 +666,7 @@ impl Database {
                 "#,
             )?;
         }
+        self.record_migration(4)?;
 
         // Migration 5: Add editor_type column to projects for OpenCode support
         let has_editor_type: bool = self
@@ -481,6 +685,7 @@ impl Database {
                 "#,
             )?;
         }
+        self.record_migration(5)?;
 
         // Migration 6: Migrate commands from skills table to new commands table
         let has_commands_table: bool = self
@@ -539,6 +744,7 @@ impl Database {
                 "#,
             )?;
         }
+        self.record_migration(6)?;
 
         // Migration 7: Create app_settings table for application preferences
         self.conn.execute_batch(
@@ -558,6 +764,7 @@ impl Database {
             INSERT OR IGNORE INTO app_settings (key, value) VALUES ('gateway_auto_start', 'false');
             "#,
         )?;
+        self.record_migration(7)?;
 
         // Migration 8: Add source_path column to skills and commands tables
         let has_skills_source_path: bool = self
@@ -593,6 +800,7 @@ impl Database {
                 "#,
             )?;
         }
+        self.record_migration(8)?;
 
         // Migration 9: Add source_path column to subagents table
         let has_subagents_source_path: bool = self
@@ -611,6 +819,7 @@ impl Database {
                 "#,
             )?;
         }
+        self.record_migration(9)?;
 
         // Migration 10: Add is_favorite column to mcps, commands, skills, and subagents tables
         let has_mcps_favorite: bool = self
@@ -632,6 +841,7 @@ impl Database {
                 "#,
             )?;
         }
+        self.record_migration(10)?;
 
         // Migration 11: Add profiles and profile_items tables
         let has_profiles_table: bool = self
@@ -671,6 +881,7 @@ impl Database {
                 "#,
             )?;
         }
+        self.record_migration(11)?;
 
         // Migration 12: Add is_favorite column to projects table
         let has_projects_favorite: bool = self
@@ -688,6 +899,7 @@ impl Database {
                 [],
             )?;
         }
+        self.record_migration(12)?;
 
         // Migration 13: Add statuslines table
         let has_statuslines_table: bool = self
@@ -727,6 +939,7 @@ impl Database {
                 "#,
             )?;
         }
+        self.record_migration(13)?;
 
         // Migration 14: Add spinner_verbs and spinner_verb_config tables
         let has_spinner_verbs_table: bool = self
@@ -760,6 +973,7 @@ impl Database {
                 "#,
             )?;
         }
+        self.record_migration(14)?;
 
         // Migration 15: Add permission_templates table
         let has_permission_templates_table: bool = self
@@ -790,6 +1004,7 @@ impl Database {
                 "#,
             )?;
         }
+        self.record_migration(15)?;
 
         // Migration 16: Add docker_hosts, containers, and project_containers tables
         let has_docker_hosts_table: bool = self
@@ -867,6 +1082,7 @@ impl Database {
                 "#,
             )?;
         }
+        self.record_migration(16)?;
 
         // Migration 17: Add repo_url column to containers table
         let has_repo_url: bool = self
@@ -882,6 +1098,7 @@ impl Database {
             self.conn
                 .execute("ALTER TABLE containers ADD COLUMN repo_url TEXT", [])?;
         }
+        self.record_migration(17)?;
 
         // Migration 18: Add new hook types and fields to hooks table
         let has_hook_url: bool = self
@@ -930,6 +1147,7 @@ impl Database {
                 "#,
             )?;
         }
+        self.record_migration(18)?;
 
         // Migration 19: Add rules, global_rules, and project_rules tables
         let has_rules_table: bool = self
@@ -984,6 +1202,7 @@ impl Database {
                 "#,
             )?;
         }
+        self.record_migration(19)?;
 
         // Migration 20: Add new frontmatter fields to subagents table
         let has_subagents_max_turns: bool = self
@@ -1010,6 +1229,7 @@ impl Database {
                 "#,
             )?;
         }
+        self.record_migration(20)?;
 
         // Migration 21: Add new frontmatter fields to skills table
         let has_skills_context: bool = self
@@ -1033,6 +1253,7 @@ impl Database {
                 "#,
             )?;
         }
+        self.record_migration(21)?;
 
         // Migration 22: Add effort column to skills table
         let has_skills_effort: bool = self
@@ -1048,6 +1269,7 @@ impl Database {
             self.conn
                 .execute_batch("ALTER TABLE skills ADD COLUMN effort TEXT;")?;
         }
+        self.record_migration(22)?;
 
         // Migration 23: Add 'ws' to MCP type CHECK constraint
         // SQLite doesn't support ALTER CONSTRAINT, so we rebuild the table
@@ -1097,6 +1319,7 @@ impl Database {
                 "#,
             )?;
         }
+        self.record_migration(23)?;
 
         // Migration 24: Fix is_favorite column type in mcps table
         // Some databases ended up with Text type in is_favorite column; cast to integer
@@ -1120,7 +1343,394 @@ impl Database {
                 )?;
             }
         }
+        self.record_migration(24)?;
+
+        // Migration 25: Add per-backend tool filter columns to gateway_mcps
+        let has_gateway_tool_filter: bool = self
+            .conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM pragma_table_info('gateway_mcps') WHERE name = 'tool_filter_mode'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if !has_gateway_tool_filter {
+            self.conn.execute_batch(
+                r#"
+                ALTER TABLE gateway_mcps ADD COLUMN tool_filter_mode TEXT;
+                ALTER TABLE gateway_mcps ADD COLUMN tool_filter TEXT;
+                "#,
+            )?;
+        }
+        self.record_migration(25)?;
+
+        // Migration 26: Cache of the tools each MCP last reported
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS mcp_tools (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                mcp_id INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                description TEXT,
+                input_schema TEXT,
+                cached_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (mcp_id) REFERENCES mcps(id) ON DELETE CASCADE,
+                UNIQUE (mcp_id, name)
+            );
+            CREATE INDEX IF NOT EXISTS idx_mcp_tools_mcp_id ON mcp_tools(mcp_id);
+            "#,
+        )?;
+        self.record_migration(26)?;
+
+        // Migration 27: Add testable flag to mcps (false = never spawned by tests)
+        let has_mcp_testable: bool = self
+            .conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM pragma_table_info('mcps') WHERE name = 'testable'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if !has_mcp_testable {
+            self.conn
+                .execute("ALTER TABLE mcps ADD COLUMN testable INTEGER DEFAULT 1", [])?;
+        }
+        self.record_migration(27)?;
+
+        // Migration 28: Add env_file to mcps (.env file loaded when spawning)
+        let has_mcp_env_file: bool = self
+            .conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM pragma_table_info('mcps') WHERE name = 'env_file'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if !has_mcp_env_file {
+            self.conn
+                .execute("ALTER TABLE mcps ADD COLUMN env_file TEXT", [])?;
+        }
+        self.record_migration(28)?;
+
+        // Migration 29: Add path_pattern to hooks (file path guard wrapped around the command)
+        let has_hook_path_pattern: bool = self
+            .conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM pragma_table_info('hooks') WHERE name = 'path_pattern'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if !has_hook_path_pattern {
+            self.conn
+                .execute("ALTER TABLE hooks ADD COLUMN path_pattern TEXT", [])?;
+        }
+
+        self.record_migration(29)?;
+
+        // Migration 30: Link imported entities back to their marketplace item.
+        // Keyed by (repo, type, name) because repo sync recreates repo_items.
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS repo_imports (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                repo_id INTEGER NOT NULL,
+                item_type TEXT NOT NULL,
+                name TEXT NOT NULL,
+                entity_type TEXT NOT NULL CHECK (entity_type IN ('mcp', 'skill', 'command', 'subagent', 'hook')),
+                entity_id INTEGER NOT NULL,
+                content_hash TEXT NOT NULL,
+                imported_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+                UNIQUE (repo_id, item_type, name)
+            );
+            "#,
+        )?;
+        self.record_migration(30)?;
+
+        // Migration 31: History of MCP test results (for latency stats)
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS mcp_test_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                mcp_id INTEGER NOT NULL,
+                success INTEGER NOT NULL,
+                response_time_ms INTEGER NOT NULL,
+                error TEXT,
+                tested_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (mcp_id) REFERENCES mcps(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_mcp_test_history_mcp_id ON mcp_test_history(mcp_id);
+            "#,
+        )?;
+        self.record_migration(31)?;
+
+        // Migration 32: Add display_name to mcps (UI label separate from the config key)
+        let has_mcp_display_name: bool = self
+            .conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM pragma_table_info('mcps') WHERE name = 'display_name'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if !has_mcp_display_name {
+            self.conn
+                .execute("ALTER TABLE mcps ADD COLUMN display_name TEXT", [])?;
+        }
+        self.record_migration(32)?;
+
+        // Migration 33: Add homepage to mcps (upstream docs or repository URL)
+        let has_mcp_homepage: bool = self
+            .conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM pragma_table_info('mcps') WHERE name = 'homepage'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if !has_mcp_homepage {
+            self.conn
+                .execute("ALTER TABLE mcps ADD COLUMN homepage TEXT", [])?;
+            // Registry and repo imports kept the upstream URL in source_path
+            self.conn.execute(
+                "UPDATE mcps SET homepage = source_path
+                 WHERE source IN ('registry', 'imported') AND source_path LIKE 'http%'",
+                [],
+            )?;
+        }
+        self.record_migration(33)?;
+
+        // Migration 34: Allow plugin hooks as marketplace items. SQLite cannot
+        // ALTER CHECK constraints, so rebuild tables created before 'hook' existed.
+        let table_sql = |table: &str| -> String {
+            self.conn
+                .query_row(
+                    "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?",
+                    [table],
+                    |row| row.get(0),
+                )
+                .unwrap_or_default()
+        };
+
+        if !table_sql("repo_items").contains("'hook'") {
+            self.conn.execute_batch(
+                r#"
+                CREATE TABLE repo_items_new (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    repo_id INTEGER NOT NULL,
+                    item_type TEXT NOT NULL CHECK (item_type IN ('mcp', 'skill', 'subagent', 'hook')),
+                    name TEXT NOT NULL,
+                    description TEXT,
+                    source_url TEXT,
+                    raw_content TEXT,
+                    file_path TEXT,
+                    metadata TEXT,
+                    stars INTEGER,
+                    is_imported INTEGER DEFAULT 0,
+                    imported_item_id INTEGER,
+                    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                    updated_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+                    UNIQUE (repo_id, name, item_type)
+                );
+
+                INSERT INTO repo_items_new (id, repo_id, item_type, name, description, source_url, raw_content, file_path, metadata, stars, is_imported, imported_item_id, created_at, updated_at)
+                SELECT id, repo_id, item_type, name, description, source_url, raw_content, file_path, metadata, stars, is_imported, imported_item_id, created_at, updated_at FROM repo_items;
+
+                DROP TABLE repo_items;
+                ALTER TABLE repo_items_new RENAME TO repo_items;
+
+                CREATE INDEX IF NOT EXISTS idx_repo_items_repo ON repo_items(repo_id);
+                CREATE INDEX IF NOT EXISTS idx_repo_items_type ON repo_items(item_type);
+                CREATE INDEX IF NOT EXISTS idx_repo_items_imported ON repo_items(is_imported);
+                "#,
+            )?;
+        }
+
+        if !table_sql("repo_imports").contains("'hook'") {
+            self.conn.execute_batch(
+                r#"
+                CREATE TABLE repo_imports_new (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    repo_id INTEGER NOT NULL,
+                    item_type TEXT NOT NULL,
+                    name TEXT NOT NULL,
+                    entity_type TEXT NOT NULL CHECK (entity_type IN ('mcp', 'skill', 'command', 'subagent', 'hook')),
+                    entity_id INTEGER NOT NULL,
+                    content_hash TEXT NOT NULL,
+                    imported_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+                    UNIQUE (repo_id, item_type, name)
+                );
+
+                INSERT INTO repo_imports_new (id, repo_id, item_type, name, entity_type, entity_id, content_hash, imported_at)
+                SELECT id, repo_id, item_type, name, entity_type, entity_id, content_hash, imported_at FROM repo_imports;
+
+                DROP TABLE repo_imports;
+                ALTER TABLE repo_imports_new RENAME TO repo_imports;
+                "#,
+            )?;
+        }
+        self.record_migration(34)?;
+
+        // Migration 35: Add inherit_env to mcps (spawn stdio servers with a clean environment)
+        let has_mcp_inherit_env: bool = self
+            .conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM pragma_table_info('mcps') WHERE name = 'inherit_env'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if !has_mcp_inherit_env {
+            self.conn.execute(
+                "ALTER TABLE mcps ADD COLUMN inherit_env INTEGER DEFAULT 1",
+                [],
+            )?;
+        }
+        self.record_migration(35)?;
+
+        // Migration 36: Add file_name to subagents (file stem when it differs from the frontmatter name)
+        let has_subagent_file_name: bool = self
+            .conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM pragma_table_info('subagents') WHERE name = 'file_name'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if !has_subagent_file_name {
+            self.conn
+                .execute("ALTER TABLE subagents ADD COLUMN file_name TEXT", [])?;
+        }
+        self.record_migration(36)?;
+
+        // Migration 37: Add shell to mcps (shell stdio servers are spawned through)
+        let has_mcp_shell: bool = self
+            .conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM pragma_table_info('mcps') WHERE name = 'shell'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if !has_mcp_shell {
+            self.conn
+                .execute("ALTER TABLE mcps ADD COLUMN shell TEXT", [])?;
+        }
+        self.record_migration(37)?;
+
+        // Migration 38: OAuth refresh settings for remote MCPs
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS mcp_oauth (
+                mcp_id INTEGER PRIMARY KEY,
+                token_endpoint TEXT NOT NULL,
+                client_id TEXT,
+                refresh_token TEXT NOT NULL,
+                access_token TEXT,
+                expires_at TEXT,
+                FOREIGN KEY (mcp_id) REFERENCES mcps(id) ON DELETE CASCADE
+            );
+            "#,
+        )?;
+        self.record_migration(38)?;
+
+        // Migration 39: Add source_hash to tables the scanner imports into, so
+        // auto-detected rows can be refreshed when their file changes
+        for table in ["mcps", "skills", "subagents", "commands", "hooks"] {
+            let has_source_hash: bool = self
+                .conn
+                .query_row(
+                    &format!(
+                        "SELECT COUNT(*) > 0 FROM pragma_table_info('{}') WHERE name = 'source_hash'",
+                        table
+                    ),
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap_or(false);
+
+            if !has_source_hash {
+                self.conn.execute(
+                    &format!("ALTER TABLE {} ADD COLUMN source_hash TEXT", table),
+                    [],
+                )?;
+            }
+        }
+        self.record_migration(39)?;
+
+        // Migration 40: Definitions from other sources that disagree with the
+        // library MCP of the same name
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS mcp_conflicts (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                mcp_id INTEGER NOT NULL,
+                source_path TEXT NOT NULL,
+                definition TEXT NOT NULL,
+                resolved INTEGER DEFAULT 0,
+                detected_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (mcp_id) REFERENCES mcps(id) ON DELETE CASCADE,
+                UNIQUE(mcp_id, source_path)
+            );
+            "#,
+        )?;
+        self.record_migration(40)?;
 
+        // Migration 41: Auto-detected rows whose source disappeared are marked
+        // removed rather than deleted, so they come back with their source
+        for table in ["mcps", "skills", "subagents", "commands"] {
+            let has_removed_at: bool = self
+                .conn
+                .query_row(
+                    &format!(
+                        "SELECT COUNT(*) > 0 FROM pragma_table_info('{}') WHERE name = 'removed_at'",
+                        table
+                    ),
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap_or(false);
+
+            if !has_removed_at {
+                self.conn.execute(
+                    &format!("ALTER TABLE {} ADD COLUMN removed_at TEXT", table),
+                    [],
+                )?;
+            }
+        }
+        self.record_migration(41)?;
+
+        // user_version mirrors the latest applied migration
+        let latest = SCHEMA_MIGRATIONS.last().map(|(v, _)| *v).unwrap_or(0);
+        self.conn
+            .execute_batch(&format!("PRAGMA user_version = {}", latest))?;
+
+        Ok(())
+    }
+
+    /// Mark a migration from SCHEMA_MIGRATIONS as applied
+    fn record_migration(&self, version: i64) -> Result<()> {
+        let name = SCHEMA_MIGRATIONS
+            .iter()
+            .find(|(v, _)| *v == version)
+            .map(|(_, name)| *name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown schema migration {}", version))?;
+        self.conn.execute(
+            "INSERT OR IGNORE INTO schema_migrations (version, name) VALUES (?, ?)",
+            rusqlite::params![version, name],
+        )?;
         Ok(())
     }
 
@@ -1150,8 +1760,8 @@ impl Database {
     pub fn get_all_mcps(&self) -> Result<Vec<crate::db::models::Mcp>> {
         let mut stmt = self.conn.prepare(
             "SELECT id, name, description, type, command, args, url, headers, env,
-                    icon, tags, source, source_path, is_enabled_global, is_favorite, created_at, updated_at
-             FROM mcps ORDER BY name",
+                    icon, tags, source, source_path, is_enabled_global, is_favorite, created_at, updated_at, testable, env_file, display_name, homepage, inherit_env, shell
+             FROM mcps WHERE removed_at IS NULL ORDER BY name",
         )?;
 
         let mcps = stmt
@@ -1182,6 +1792,12 @@ impl Database {
                     is_favorite: row.get::<_, i32>(14)? != 0,
                     created_at: row.get(15)?,
                     updated_at: row.get(16)?,
+                    testable: row.get::<_, i32>(17)? != 0,
+                    env_file: row.get(18)?,
+                    display_name: row.get(19)?,
+                    homepage: row.get(20)?,
+                    inherit_env: row.get::<_, i32>(21)? != 0,
+                    shell: row.get(22)?,
                 })
             })?
             .filter_map(|r| r.ok())
@@ -1193,7 +1809,7 @@ impl Database {
     pub fn get_mcp_by_id(&self, id: i64) -> Result<Option<crate::db::models::Mcp>> {
         let result = self.conn.query_row(
             "SELECT id, name, description, type, command, args, url, headers, env,
-                    icon, tags, source, source_path, is_enabled_global, is_favorite, created_at, updated_at
+                    icon, tags, source, source_path, is_enabled_global, is_favorite, created_at, updated_at, testable, env_file, display_name, homepage, inherit_env, shell
              FROM mcps WHERE id = ?",
             [id],
             |row| {
@@ -1223,6 +1839,12 @@ impl Database {
                     is_favorite: row.get::<_, i32>(14)? != 0,
                     created_at: row.get(15)?,
                     updated_at: row.get(16)?,
+                    testable: row.get::<_, i32>(17)? != 0,
+                    env_file: row.get(18)?,
+                    display_name: row.get(19)?,
+                    homepage: row.get(20)?,
+                    inherit_env: row.get::<_, i32>(21)? != 0,
+                    shell: row.get(22)?,
                 })
             },
         );
@@ -1237,7 +1859,7 @@ impl Database {
     pub fn get_mcp_by_name(&self, name: &str) -> Result<Option<crate::db::models::Mcp>> {
         let result = self.conn.query_row(
             "SELECT id, name, description, type, command, args, url, headers, env,
-                    icon, tags, source, source_path, is_enabled_global, is_favorite, created_at, updated_at
+                    icon, tags, source, source_path, is_enabled_global, is_favorite, created_at, updated_at, testable, env_file, display_name, homepage, inherit_env, shell
              FROM mcps WHERE name = ?",
             [name],
             |row| {
@@ -1267,6 +1889,12 @@ impl Database {
                     is_favorite: row.get::<_, i32>(14)? != 0,
                     created_at: row.get(15)?,
                     updated_at: row.get(16)?,
+                    testable: row.get::<_, i32>(17)? != 0,
+                    env_file: row.get(18)?,
+                    display_name: row.get(19)?,
+                    homepage: row.get(20)?,
+                    inherit_env: row.get::<_, i32>(21)? != 0,
+                    shell: row.get(22)?,
                 })
             },
         );
@@ -1298,6 +1926,9 @@ impl Database {
         req: &crate::db::models::CreateMcpRequest,
         source: &str,
     ) -> Result<crate::db::models::Mcp> {
+        if let Some(shell) = req.shell.as_deref() {
+            crate::services::mcp_client::validate_spawn_shell(shell)?;
+        }
         let args_json = req.args.as_ref().map(|a| serde_json::to_string(a).unwrap());
         let headers_json = req
             .headers
@@ -1307,11 +1938,14 @@ impl Database {
         let tags_json = req.tags.as_ref().map(|t| serde_json::to_string(t).unwrap());
 
         self.conn.execute(
-            "INSERT INTO mcps (name, description, type, command, args, url, headers, env, icon, tags, source)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            "INSERT INTO mcps (name, description, type, command, args, url, headers, env, icon, tags, source, testable, env_file, display_name, inherit_env, shell)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
             rusqlite::params![
                 req.name, req.description, req.mcp_type, req.command,
-                args_json, req.url, headers_json, env_json, req.icon, tags_json, source
+                args_json, req.url, headers_json, env_json, req.icon, tags_json, source,
+                req.testable.unwrap_or(true), req.env_file,
+                normalize_display_name(req.display_name.as_deref()),
+                req.inherit_env.unwrap_or(true), req.shell
             ],
         )?;
 
@@ -1321,6 +1955,9 @@ impl Database {
     }
 
     pub fn update_mcp(&self, mcp: &crate::db::models::Mcp) -> Result<crate::db::models::Mcp> {
+        if let Some(shell) = mcp.shell.as_deref() {
+            crate::services::mcp_client::validate_spawn_shell(shell)?;
+        }
         let args_json = mcp.args.as_ref().map(|a| serde_json::to_string(a).unwrap());
         let headers_json = mcp
             .headers
@@ -1331,11 +1968,13 @@ impl Database {
 
         self.conn.execute(
             "UPDATE mcps SET name = ?, description = ?, type = ?, command = ?, args = ?,
-             url = ?, headers = ?, env = ?, icon = ?, tags = ?, source = ?, updated_at = CURRENT_TIMESTAMP
+             url = ?, headers = ?, env = ?, icon = ?, tags = ?, source = ?, testable = ?, env_file = ?, display_name = ?,
+             inherit_env = ?, shell = ?, updated_at = CURRENT_TIMESTAMP
              WHERE id = ?",
             rusqlite::params![
                 mcp.name, mcp.description, mcp.mcp_type, mcp.command, args_json,
-                mcp.url, headers_json, env_json, mcp.icon, tags_json, mcp.source, mcp.id
+                mcp.url, headers_json, env_json, mcp.icon, tags_json, mcp.source, mcp.testable, mcp.env_file,
+                normalize_display_name(mcp.display_name.as_deref()), mcp.inherit_env, mcp.shell, mcp.id
             ],
         )?;
 
@@ -1439,7 +2078,7 @@ impl Database {
         let mut stmt = self.conn.prepare(
             "SELECT gm.id, gm.mcp_id, gm.is_enabled, gm.env_overrides,
                     m.id, m.name, m.description, m.type, m.command, m.args, m.url, m.headers, m.env,
-                    m.icon, m.tags, m.source, m.source_path, m.is_enabled_global, m.is_favorite, m.created_at, m.updated_at
+                    m.icon, m.tags, m.source, m.source_path, m.is_enabled_global, m.is_favorite, m.created_at, m.updated_at, m.testable, m.env_file, m.display_name, m.homepage, m.inherit_env, m.shell
              FROM global_mcps gm
              JOIN mcps m ON gm.mcp_id = m.id
              ORDER BY gm.display_order"
@@ -1473,6 +2112,12 @@ impl Database {
                     is_favorite: row.get::<_, i32>(18)? != 0,
                     created_at: row.get(19)?,
                     updated_at: row.get(20)?,
+                    testable: row.get::<_, i32>(21)? != 0,
+                    env_file: row.get(22)?,
+                    display_name: row.get(23)?,
+                    homepage: row.get(24)?,
+                    inherit_env: row.get::<_, i32>(25)? != 0,
+                    shell: row.get(26)?,
                 };
 
                 Ok(crate::db::models::GlobalMcp {
@@ -1513,7 +2158,8 @@ impl Database {
         let mut stmt = self.conn.prepare(
             "SELECT gm.id, gm.mcp_id, gm.is_enabled, gm.auto_restart, gm.display_order, gm.created_at,
                     m.id, m.name, m.description, m.type, m.command, m.args, m.url, m.headers, m.env,
-                    m.icon, m.tags, m.source, m.source_path, m.is_enabled_global, m.is_favorite, m.created_at, m.updated_at
+                    m.icon, m.tags, m.source, m.source_path, m.is_enabled_global, m.is_favorite, m.created_at, m.updated_at, m.testable, m.env_file, m.display_name, m.homepage, m.inherit_env, m.shell,
+                    gm.tool_filter_mode, gm.tool_filter
              FROM gateway_mcps gm
              JOIN mcps m ON gm.mcp_id = m.id
              ORDER BY gm.display_order, m.name"
@@ -1547,6 +2193,12 @@ impl Database {
                     is_favorite: row.get::<_, i32>(20)? != 0,
                     created_at: row.get(21)?,
                     updated_at: row.get(22)?,
+                    testable: row.get::<_, i32>(23)? != 0,
+                    env_file: row.get(24)?,
+                    display_name: row.get(25)?,
+                    homepage: row.get(26)?,
+                    inherit_env: row.get::<_, i32>(27)? != 0,
+                    shell: row.get(28)?,
                 };
                 Ok(crate::db::models::GatewayMcp {
                     id: row.get(0)?,
@@ -1556,6 +2208,7 @@ impl Database {
                     auto_restart: row.get::<_, i32>(3)? != 0,
                     display_order: row.get(4)?,
                     created_at: row.get(5)?,
+                    tool_filter: tool_filter_from_columns(row.get(29)?, row.get(30)?),
                 })
             })?
             .filter_map(|r| r.ok())
@@ -1568,7 +2221,8 @@ impl Database {
         let mut stmt = self.conn.prepare(
             "SELECT gm.id, gm.mcp_id, gm.is_enabled, gm.auto_restart, gm.display_order, gm.created_at,
                     m.id, m.name, m.description, m.type, m.command, m.args, m.url, m.headers, m.env,
-                    m.icon, m.tags, m.source, m.source_path, m.is_enabled_global, m.is_favorite, m.created_at, m.updated_at
+                    m.icon, m.tags, m.source, m.source_path, m.is_enabled_global, m.is_favorite, m.created_at, m.updated_at, m.testable, m.env_file, m.display_name, m.homepage, m.inherit_env, m.shell,
+                    gm.tool_filter_mode, gm.tool_filter
              FROM gateway_mcps gm
              JOIN mcps m ON gm.mcp_id = m.id
              WHERE gm.is_enabled = 1
@@ -1603,6 +2257,12 @@ impl Database {
                     is_favorite: row.get::<_, i32>(20)? != 0,
                     created_at: row.get(21)?,
                     updated_at: row.get(22)?,
+                    testable: row.get::<_, i32>(23)? != 0,
+                    env_file: row.get(24)?,
+                    display_name: row.get(25)?,
+                    homepage: row.get(26)?,
+                    inherit_env: row.get::<_, i32>(27)? != 0,
+                    shell: row.get(28)?,
                 };
                 Ok(crate::db::models::GatewayMcp {
                     id: row.get(0)?,
@@ -1612,6 +2272,7 @@ impl Database {
                     auto_restart: row.get::<_, i32>(3)? != 0,
                     display_order: row.get(4)?,
                     created_at: row.get(5)?,
+                    tool_filter: tool_filter_from_columns(row.get(29)?, row.get(30)?),
                 })
             })?
             .filter_map(|r| r.ok())
@@ -1622,12 +2283,37 @@ impl Database {
 
     pub fn add_gateway_mcp(&self, mcp_id: i64) -> Result<()> {
         self.conn.execute(
-            "INSERT OR IGNORE INTO gateway_mcps (mcp_id) VALUES (?)",
+            "INSERT OR IGNORE INTO gateway_mcps (mcp_id, display_order)
+             VALUES (?, (SELECT COALESCE(MAX(display_order), -1) + 1 FROM gateway_mcps))",
             [mcp_id],
         )?;
         Ok(())
     }
 
+    /// Reorder gateway backends. `mcp_ids` come first, in the given order;
+    /// backends not listed keep their relative order after them.
+    pub fn set_gateway_backend_priority(&self, mcp_ids: &[i64]) -> Result<()> {
+        let current: Vec<i64> = self
+            .get_gateway_mcps()?
+            .into_iter()
+            .map(|gm| gm.mcp_id)
+            .collect();
+        let ordered = mcp_ids
+            .iter()
+            .filter(|id| current.contains(id))
+            .chain(current.iter().filter(|id| !mcp_ids.contains(id)));
+
+        let tx = self.conn.unchecked_transaction()?;
+        for (position, mcp_id) in ordered.enumerate() {
+            tx.execute(
+                "UPDATE gateway_mcps SET display_order = ? WHERE mcp_id = ?",
+                rusqlite::params![position as i64, mcp_id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
     pub fn remove_gateway_mcp(&self, mcp_id: i64) -> Result<()> {
         self.conn
             .execute("DELETE FROM gateway_mcps WHERE mcp_id = ?", [mcp_id])?;
@@ -1650,6 +2336,47 @@ impl Database {
         Ok(())
     }
 
+    /// Set (or clear, with `None`) the tool filter for a gateway MCP
+    pub fn set_gateway_mcp_tool_filter(
+        &self,
+        mcp_id: i64,
+        filter: Option<&crate::db::models::GatewayToolFilter>,
+    ) -> Result<()> {
+        let (mode, tools) = match filter {
+            Some(f) => (
+                Some(
+                    serde_json::to_value(f.mode)?
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                ),
+                Some(serde_json::to_string(&f.tools)?),
+            ),
+            None => (None, None),
+        };
+        self.conn.execute(
+            "UPDATE gateway_mcps SET tool_filter_mode = ?, tool_filter = ? WHERE mcp_id = ?",
+            rusqlite::params![mode, tools, mcp_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_gateway_mcp_tool_filter(
+        &self,
+        mcp_id: i64,
+    ) -> Result<Option<crate::db::models::GatewayToolFilter>> {
+        let filter = self
+            .conn
+            .query_row(
+                "SELECT tool_filter_mode, tool_filter FROM gateway_mcps WHERE mcp_id = ?",
+                [mcp_id],
+                |row| Ok(tool_filter_from_columns(row.get(0)?, row.get(1)?)),
+            )
+            .optional()?
+            .flatten();
+        Ok(filter)
+    }
+
     pub fn is_mcp_in_gateway(&self, mcp_id: i64) -> Result<bool> {
         let count: i64 = self.conn.query_row(
             "SELECT COUNT(*) FROM gateway_mcps WHERE mcp_id = ?",
@@ -1666,7 +2393,7 @@ impl Database {
     pub fn get_all_skills(&self) -> Result<Vec<crate::db::models::Skill>> {
         let mut stmt = self.conn.prepare(
             "SELECT id, name, description, content, allowed_tools, model, disable_model_invocation, tags, source, source_path, is_favorite, created_at, updated_at
-             FROM skills ORDER BY name"
+             FROM skills WHERE removed_at IS NULL ORDER BY name"
         )?;
 
         let skills = stmt
@@ -1792,8 +2519,8 @@ impl Database {
 
     pub fn get_all_subagents(&self) -> Result<Vec<crate::db::models::SubAgent>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, name, description, content, tools, model, permission_mode, skills, tags, source, source_path, is_favorite, created_at, updated_at
-             FROM subagents ORDER BY name"
+            "SELECT id, name, description, content, tools, model, permission_mode, skills, tags, source, source_path, is_favorite, created_at, updated_at, file_name
+             FROM subagents WHERE removed_at IS NULL ORDER BY name"
         )?;
 
         let subagents = stmt
@@ -1828,6 +2555,7 @@ impl Database {
                     hooks: None,
                     mcp_servers: None,
                     initial_prompt: None,
+                    file_name: row.get(14)?,
                 })
             })?
             .filter_map(|r| r.ok())
@@ -1838,7 +2566,7 @@ impl Database {
 
     pub fn get_subagent_by_id(&self, id: i64) -> Result<Option<crate::db::models::SubAgent>> {
         let result = self.conn.query_row(
-            "SELECT id, name, description, content, tools, model, permission_mode, skills, tags, source, source_path, is_favorite, created_at, updated_at
+            "SELECT id, name, description, content, tools, model, permission_mode, skills, tags, source, source_path, is_favorite, created_at, updated_at, file_name
              FROM subagents WHERE id = ?",
             [id],
             |row| {
@@ -1866,6 +2594,7 @@ impl Database {
                     hooks: None,
                     mcp_servers: None,
                     initial_prompt: None,
+                    file_name: row.get(14)?,
                 })
             },
         );
@@ -1933,7 +2662,7 @@ impl Database {
 
     pub fn get_all_hooks(&self) -> Result<Vec<crate::db::models::Hook>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, name, description, event_type, matcher, hook_type, command, prompt, timeout, url, headers, allowed_env_vars, if_condition, status_message, once, async_mode, shell, tags, source, is_template, created_at, updated_at
+            "SELECT id, name, description, event_type, matcher, hook_type, command, prompt, timeout, url, headers, allowed_env_vars, if_condition, status_message, once, async_mode, shell, tags, source, is_template, created_at, updated_at, path_pattern
              FROM hooks WHERE is_template = 0 ORDER BY name"
         )?;
 
@@ -1961,6 +2690,7 @@ impl Database {
                     once: row.get::<_, i32>(14).unwrap_or(0) != 0,
                     async_mode: row.get::<_, i32>(15).unwrap_or(0) != 0,
                     shell: row.get(16)?,
+                    path_pattern: row.get(22)?,
                     tags: row
                         .get::<_, Option<String>>(17)?
                         .and_then(|s| serde_json::from_str(&s).ok()),
@@ -1978,7 +2708,7 @@ impl Database {
 
     pub fn get_hook_by_id(&self, id: i64) -> Result<Option<crate::db::models::Hook>> {
         let result = self.conn.query_row(
-            "SELECT id, name, description, event_type, matcher, hook_type, command, prompt, timeout, url, headers, allowed_env_vars, if_condition, status_message, once, async_mode, shell, tags, source, is_template, created_at, updated_at
+            "SELECT id, name, description, event_type, matcher, hook_type, command, prompt, timeout, url, headers, allowed_env_vars, if_condition, status_message, once, async_mode, shell, tags, source, is_template, created_at, updated_at, path_pattern
              FROM hooks WHERE id = ?",
             [id],
             |row| {
@@ -2000,6 +2730,7 @@ impl Database {
                     once: row.get::<_, i32>(14).unwrap_or(0) != 0,
                     async_mode: row.get::<_, i32>(15).unwrap_or(0) != 0,
                     shell: row.get(16)?,
+                    path_pattern: row.get(22)?,
                     tags: row.get::<_, Option<String>>(17)?.and_then(|s| serde_json::from_str(&s).ok()),
                     source: row.get(18)?,
                     is_template: row.get::<_, i32>(19)? != 0,
@@ -2031,8 +2762,8 @@ impl Database {
             .map(|v| serde_json::to_string(v).unwrap());
 
         self.conn.execute(
-            "INSERT INTO hooks (name, description, event_type, matcher, hook_type, command, prompt, timeout, url, headers, allowed_env_vars, if_condition, status_message, once, async_mode, shell, tags, source)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'manual')",
+            "INSERT INTO hooks (name, description, event_type, matcher, hook_type, command, prompt, timeout, url, headers, allowed_env_vars, if_condition, status_message, once, async_mode, shell, path_pattern, tags, source)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'manual')",
             rusqlite::params![
                 req.name, req.description, req.event_type, req.matcher,
                 req.hook_type, req.command, req.prompt, req.timeout,
@@ -2040,7 +2771,7 @@ impl Database {
                 req.if_condition, req.status_message,
                 req.once.unwrap_or(false) as i32,
                 req.async_mode.unwrap_or(false) as i32,
-                req.shell, tags_json
+                req.shell, req.path_pattern, tags_json
             ],
         )?;
 
@@ -2383,6 +3114,18 @@ impl Database {
     }
 }
 
+/// Build a gateway tool filter from its stored mode / JSON tool list columns
+fn tool_filter_from_columns(
+    mode: Option<String>,
+    tools: Option<String>,
+) -> Option<crate::db::models::GatewayToolFilter> {
+    let mode = serde_json::from_value(serde_json::Value::String(mode?)).ok()?;
+    let tools = tools
+        .and_then(|t| serde_json::from_str(&t).ok())
+        .unwrap_or_default();
+    Some(crate::db::models::GatewayToolFilter { mode, tools })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2419,6 +3162,42 @@ mod tests {
         db.run_schema_migrations().unwrap();
     }
 
+    #[test]
+    fn test_open_or_recover_keeps_healthy_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mcp_library.db");
+        {
+            let db = Database::new(&path).unwrap();
+            db.run_migrations().unwrap();
+            db.set_setting("kept", "yes").unwrap();
+        }
+
+        let (db, recovery) = Database::open_or_recover(&path).unwrap();
+        assert!(recovery.is_none());
+        assert_eq!(db.get_setting("kept").as_deref(), Some("yes"));
+    }
+
+    #[test]
+    fn test_open_or_recover_moves_corrupt_file_aside() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mcp_library.db");
+        std::fs::write(&path, vec![0xABu8; 8192]).unwrap();
+
+        let (db, recovery) = Database::open_or_recover(&path).unwrap();
+        let recovery = recovery.expect("corrupt file should be recovered");
+
+        let backup = std::path::Path::new(&recovery.backup_path);
+        assert!(backup
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("mcp_library.db.corrupt-"));
+        assert_eq!(std::fs::read(backup).unwrap(), vec![0xABu8; 8192]);
+
+        db.run_migrations().unwrap();
+        assert!(db.integrity_check().unwrap().is_none());
+    }
+
     // =========================================================================
     // App Settings tests
     // =========================================================================
@@ -2463,6 +3242,12 @@ mod tests {
             env: None,
             icon: None,
             tags: None,
+            testable: None,
+            env_file: None,
+            display_name: None,
+            inherit_env: None,
+            shell: None,
+            split_command: None,
         }
     }
 
@@ -2697,6 +3482,59 @@ mod tests {
         assert!(!db.is_mcp_in_gateway(mcp.id).unwrap());
     }
 
+    #[test]
+    fn test_gateway_mcp_tool_filter_round_trip() {
+        let db = setup_db();
+        let mcp = db
+            .create_mcp(&create_test_mcp_request("gw-filter"))
+            .unwrap();
+        db.add_gateway_mcp(mcp.id).unwrap();
+        assert_eq!(db.get_gateway_mcp_tool_filter(mcp.id).unwrap(), None);
+
+        let filter = GatewayToolFilter {
+            mode: GatewayToolFilterMode::Deny,
+            tools: vec!["delete_everything".to_string()],
+        };
+        db.set_gateway_mcp_tool_filter(mcp.id, Some(&filter))
+            .unwrap();
+        assert_eq!(
+            db.get_gateway_mcp_tool_filter(mcp.id).unwrap(),
+            Some(filter.clone())
+        );
+        assert_eq!(db.get_gateway_mcps().unwrap()[0].tool_filter, Some(filter));
+
+        db.set_gateway_mcp_tool_filter(mcp.id, None).unwrap();
+        assert_eq!(db.get_enabled_gateway_mcps().unwrap()[0].tool_filter, None);
+    }
+
+    #[test]
+    fn test_gateway_backend_priority() {
+        let db = setup_db();
+        let ids: Vec<i64> = ["alpha", "beta", "gamma"]
+            .iter()
+            .map(|name| {
+                let mcp = db.create_mcp(&create_test_mcp_request(name)).unwrap();
+                db.add_gateway_mcp(mcp.id).unwrap();
+                mcp.id
+            })
+            .collect();
+        let order = |db: &Database| -> Vec<i64> {
+            db.get_gateway_mcps()
+                .unwrap()
+                .iter()
+                .map(|gm| gm.mcp_id)
+                .collect()
+        };
+        // New backends are appended in the order they were added
+        assert_eq!(order(&db), ids);
+
+        db.set_gateway_backend_priority(&[ids[2]]).unwrap();
+        assert_eq!(order(&db), vec![ids[2], ids[0], ids[1]]);
+
+        db.set_gateway_backend_priority(&[ids[1], ids[0]]).unwrap();
+        assert_eq!(order(&db), vec![ids[1], ids[0], ids[2]]);
+    }
+
     #[test]
     fn test_add_gateway_mcp_idempotent() {
         let db = setup_db();
@@ -2918,6 +3756,7 @@ mod tests {
             once: None,
             async_mode: None,
             shell: None,
+            path_pattern: None,
         }
     }
 
@@ -3248,6 +4087,12 @@ mod tests {
             env: Some(env.clone()),
             icon: None,
             tags: Some(vec!["web".to_string()]),
+            testable: None,
+            env_file: None,
+            display_name: None,
+            inherit_env: None,
+            shell: None,
+            split_command: None,
         };
 
         let mcp = db.create_mcp(&req).unwrap();
@@ -3481,6 +4326,12 @@ mod tests {
             }),
             icon: Some("🔌".to_string()),
             tags: Some(vec!["web".to_string(), "api".to_string()]),
+            testable: None,
+            env_file: None,
+            display_name: None,
+            inherit_env: None,
+            shell: None,
+            split_command: None,
         };
 
         let mut mcp = db.create_mcp(&req).unwrap();
@@ -3597,6 +4448,7 @@ mod tests {
             once: None,
             async_mode: None,
             shell: None,
+            path_pattern: None,
         };
 
         let hook = db.create_hook(&req).unwrap();
@@ -1,6 +1,7 @@
 use std::sync::{Arc, Mutex};
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 
+pub mod cli;
 mod commands;
 mod db;
 mod mcp_gateway;
@@ -9,7 +10,10 @@ mod services;
 mod utils;
 
 use db::Database;
-use mcp_gateway::server::{GatewayServerConfig, GatewayServerState, DEFAULT_GATEWAY_PORT};
+use mcp_gateway::server::{
+    GatewayServerConfig, GatewayServerState, DEFAULT_BACKEND_TIMEOUT_SECS, DEFAULT_GATEWAY_PORT,
+    DEFAULT_KEEP_ALIVE_INTERVAL_SECS, DEFAULT_MAX_CONCURRENT_CONNECTS,
+};
 use mcp_server::server::{McpServerConfig, McpServerState, DEFAULT_MCP_SERVER_PORT};
 use services::docker::client::DockerClientManager;
 use services::mcp_session::McpSessionManager;
@@ -22,6 +26,8 @@ pub fn run() {
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init());
 
@@ -44,8 +50,29 @@ pub fn run() {
             }
 
             let db_path = app_data_dir.join("mcp_library.db");
-            let database = Database::new(&db_path)?;
+            let (database, recovery) = Database::open_or_recover(&db_path)?;
             database.run_migrations()?;
+            if let Some(recovery) = &recovery {
+                let _ = app.emit("database-recovered", recovery);
+            }
+            app.manage(commands::debug::StartupDatabaseRecovery(recovery));
+
+            // Restore the "confirm before writing config files" mode and any
+            // changes still waiting for confirmation
+            services::pending_changes::store().set_confirm_writes(
+                commands::pending_changes::get_confirm_config_writes_from_db(&database),
+            );
+            if let Err(e) = services::pending_changes::store().restore_from(&app_data_dir) {
+                log::warn!("Failed to restore pending config changes: {}", e);
+            }
+
+            // Restore the secret pattern used to mask MCP traffic in the debug log
+            let log_secret_pattern = commands::debug::get_log_secret_pattern_from_db(&database);
+            if let Err(e) =
+                services::diagnostics::set_secret_key_pattern(Some(&log_secret_pattern))
+            {
+                log::warn!("Invalid log secret pattern, using default: {}", e);
+            }
 
             // Seed default repos
             if let Err(e) = services::repo_sync::seed_default_repos(&database) {
@@ -92,7 +119,23 @@ pub fn run() {
                 let auto_start = db.get_setting("gateway_auto_start")
                     .and_then(|s| s.parse().ok())
                     .unwrap_or(false);
-                GatewayServerConfig { enabled, port, auto_start }
+                let keep_alive_interval_secs = db.get_setting("gateway_keep_alive_interval_secs")
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(DEFAULT_KEEP_ALIVE_INTERVAL_SECS);
+                let max_concurrent_connects = db.get_setting("gateway_max_concurrent_connects")
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(DEFAULT_MAX_CONCURRENT_CONNECTS);
+                let backend_timeout_secs = db.get_setting("gateway_backend_timeout_secs")
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(DEFAULT_BACKEND_TIMEOUT_SECS);
+                GatewayServerConfig {
+                    enabled,
+                    port,
+                    auto_start,
+                    keep_alive_interval_secs,
+                    max_concurrent_connects,
+                    backend_timeout_secs,
+                }
             };
 
             let gateway_state = Arc::new(GatewayServerState::with_config(gateway_config.clone(), database_arc.clone()));
@@ -106,6 +149,16 @@ pub fn run() {
                 }
             });
 
+            // Detect the Claude Code version so diagnostics report it
+            let db_for_version = database_arc.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) =
+                    commands::config::detect_and_store_claude_code_version(&db_for_version).await
+                {
+                    log::warn!("[Config] Claude Code version detection failed: {}", e);
+                }
+            });
+
             // Auto-start MCP server if configured
             if mcp_server_config.enabled && mcp_server_config.auto_start {
                 let server_state = mcp_server_state.clone();
@@ -207,10 +260,21 @@ pub fn run() {
             commands::mcp::get_mcp,
             commands::mcp::create_mcp,
             commands::mcp::update_mcp,
+            commands::mcp::parse_command_line,
+            commands::mcp::import_mcps_from_json,
+            commands::mcp::import_mcps_from_url,
             commands::mcp::delete_mcp,
             commands::mcp::duplicate_mcp,
             commands::mcp::toggle_global_mcp,
             commands::mcp::toggle_mcp_favorite,
+            commands::mcp::set_mcp_display_name,
+            commands::mcp::change_mcp_transport,
+            commands::mcp::export_mcp_definition,
+            commands::mcp::copy_mcp_config_to_clipboard,
+            commands::mcp::open_mcp_homepage,
+            commands::mcp::audit_mcp_secrets,
+            commands::mcp::get_mcp_conflicts,
+            commands::mcp::resolve_mcp_conflict,
             // Project Commands
             commands::projects::get_all_projects,
             commands::projects::add_project,
@@ -221,29 +285,58 @@ pub fn run() {
             commands::projects::toggle_project_mcp,
             commands::projects::toggle_project_favorite,
             commands::projects::sync_project_config,
+            commands::projects::preview_project_mcp_config,
+            commands::projects::get_project_config_checksum,
+            commands::projects::sync_all_projects,
+            commands::batch::run_batch,
+            commands::projects::write_project_manifest,
+            commands::projects::sync_project_from_manifest,
+            commands::projects::validate_tool_references,
             commands::projects::open_folder,
+            commands::projects::get_git_info,
             commands::projects::update_project_editor_type,
             // Global Settings Commands
             commands::config::get_global_mcps,
+            commands::config::find_stale_global_mcps,
             commands::config::add_global_mcp,
             commands::config::remove_global_mcp,
             commands::config::toggle_global_mcp_assignment,
             commands::config::sync_global_config,
+            commands::config::preview_global_mcp_config,
+            commands::config::validate_editor_config,
             commands::config::get_claude_paths,
             commands::config::open_config_file,
             commands::config::backup_configs,
+            commands::config::generate_diagnostics_bundle,
+            commands::config::detect_claude_code_version,
+            commands::config::get_claude_code_version,
+            commands::config::get_migration_status,
+            commands::config::generate_setup_script,
             // Scanner Commands
             commands::scanner::scan_claude_directory,
+            commands::scanner::reconcile_from_disk,
+            commands::scanner::cancel_startup_scan,
             // Claude.json Commands
             commands::claude_json::get_claude_json_mcps,
             commands::claude_json::get_claude_json_projects,
+            commands::claude_json::preview_claude_json_change,
             commands::claude_json::add_mcp_to_claude_json,
             commands::claude_json::remove_mcp_from_claude_json,
             commands::claude_json::toggle_mcp_in_claude_json,
             commands::claude_json::add_global_mcp_to_claude_json,
             commands::claude_json::remove_global_mcp_from_claude_json,
+            commands::claude_json::find_unlinked_claude_json_mcps,
             // Skill Commands
+            commands::skills::get_skill_disk_locations,
+            commands::skills::open_skill_location,
             commands::skills::get_all_skills,
+            commands::content_limits::get_oversized_entities,
+            commands::content_limits::get_content_size_limit,
+            commands::content_limits::set_content_size_limit,
+            commands::content_limits::estimate_project_context_cost,
+            commands::caches::get_cache_summary,
+            commands::caches::clear_cache,
+            commands::caches::clear_all_caches,
             commands::skills::create_skill,
             commands::skills::update_skill,
             commands::skills::delete_skill,
@@ -259,7 +352,9 @@ pub fn run() {
             commands::skills::create_skill_file,
             commands::skills::update_skill_file,
             commands::skills::delete_skill_file,
+            commands::skills::run_skill_script,
             commands::skills::toggle_skill_favorite,
+            commands::skills::import_skill_from_directory,
             // Slash Command Commands
             commands::commands::get_all_commands,
             commands::commands::create_command,
@@ -274,6 +369,7 @@ pub fn run() {
             commands::commands::toggle_project_command,
             commands::commands::get_project_commands,
             commands::commands::toggle_command_favorite,
+            commands::commands::detect_command_conflicts,
             // Sub-Agent Commands
             commands::subagents::get_all_subagents,
             commands::subagents::create_subagent,
@@ -305,6 +401,8 @@ pub fn run() {
             commands::hooks::toggle_project_hook,
             commands::hooks::seed_hook_templates,
             commands::hooks::export_hooks_to_json,
+            commands::hooks::export_hooks_as_settings,
+            commands::hooks::export_hooks_as_plugin,
             commands::hooks::create_sound_notification_hooks,
             commands::hooks::duplicate_hook,
             // Rule Commands
@@ -341,8 +439,11 @@ pub fn run() {
             commands::repos::get_repo_items,
             commands::repos::get_all_repo_items,
             commands::repos::sync_repo,
+            commands::repos::preview_repo,
             commands::repos::sync_all_repos,
             commands::repos::import_repo_item,
+            commands::repos::update_imported_item,
+            commands::repos::import_all_repo_items,
             commands::repos::get_github_rate_limit,
             commands::repos::seed_default_repos,
             commands::repos::reset_repos_to_defaults,
@@ -354,18 +455,29 @@ pub fn run() {
             // Settings Commands
             commands::settings::get_app_settings,
             commands::settings::update_app_settings,
+            commands::settings::get_scan_editors,
+            commands::settings::set_scan_editors,
             commands::settings::get_available_editors,
             commands::settings::get_opencode_paths_cmd,
             commands::settings::get_codex_paths_cmd,
             commands::settings::get_copilot_paths_cmd,
             commands::settings::get_cursor_paths_cmd,
             commands::settings::get_gemini_paths_cmd,
+            commands::settings::get_windsurf_paths_cmd,
             commands::settings::toggle_editor,
             commands::settings::set_github_token,
             commands::settings::clear_github_token,
             commands::settings::has_github_token,
             commands::settings::get_container_claude_settings,
             commands::settings::set_container_claude_settings,
+            commands::settings::get_connectivity_settings,
+            commands::settings::set_connectivity_settings,
+            commands::settings::check_connectivity,
+            commands::pending_changes::get_confirm_config_writes,
+            commands::pending_changes::set_confirm_config_writes,
+            commands::pending_changes::list_pending_changes,
+            commands::pending_changes::confirm_pending_change,
+            commands::pending_changes::discard_pending_change,
             // Profile Commands
             commands::profiles::get_all_profiles,
             commands::profiles::get_profile,
@@ -384,6 +496,8 @@ pub fn run() {
             commands::statusline::delete_statusline,
             commands::statusline::activate_statusline,
             commands::statusline::deactivate_statusline,
+            commands::statusline::write_project_statusline,
+            commands::statusline::remove_project_statusline,
             commands::statusline::get_active_statusline,
             commands::statusline::fetch_statusline_gallery,
             commands::statusline::get_statusline_gallery_cache,
@@ -392,6 +506,13 @@ pub fn run() {
             commands::statusline::set_statusline_gallery_url,
             commands::statusline::generate_statusline_preview,
             commands::statusline::read_current_statusline_config,
+            commands::statusline::get_statusline_script_content,
+            commands::statusline::is_statusline_script_custom,
+            commands::statusline::set_statusline_script_content,
+            commands::statusline::regenerate_statusline_script,
+            commands::statusline::backup_statusline,
+            commands::statusline::list_statusline_backups,
+            commands::statusline::restore_statusline,
             // Spinner Verb Commands
             commands::spinner_verbs::get_all_spinner_verbs,
             commands::spinner_verbs::create_spinner_verb,
@@ -410,8 +531,29 @@ pub fn run() {
             commands::debug::open_logs_folder,
             commands::debug::write_frontend_log,
             commands::debug::write_invoke_log,
+            commands::debug::get_database_recovery,
+            commands::debug::get_log_secret_pattern,
+            commands::debug::set_log_secret_pattern,
             // MCP Test Commands
             commands::mcp_test::test_mcp,
+            commands::mcp_test::refresh_mcp_metadata,
+            commands::mcp_test::get_mcp_resources,
+            commands::mcp_test::read_mcp_resource,
+            commands::mcp_test::get_mcp_prompts,
+            commands::mcp_test::get_mcp_prompt,
+            commands::mcp_oauth::get_mcp_oauth,
+            commands::mcp_oauth::set_mcp_oauth,
+            commands::mcp_oauth::clear_mcp_oauth,
+            commands::mcp_test::health_check_all_global,
+            commands::mcp_test::health_check_mcps,
+            commands::mcp_test::test_all_mcps,
+            commands::mcp_test::diagnose_stdio_framing,
+            commands::mcp_test::get_mcp_latency_stats,
+            commands::mcp_test::search_cached_tools,
+            commands::mcp_test::get_cached_mcp_tools,
+            commands::mcp_test::get_full_tool_schema,
+            commands::mcp_test::get_tool_schema_summary_threshold,
+            commands::mcp_test::set_tool_schema_summary_threshold,
             commands::mcp_test::test_mcp_config,
             // MCP Session Commands
             commands::mcp_session::start_mcp_session,
@@ -421,6 +563,7 @@ pub fn run() {
             commands::mcp_session::get_mcp_session,
             commands::mcp_session::get_session_tools,
             commands::mcp_session::cleanup_idle_sessions,
+            commands::mcp_session::clear_tool_result_cache,
             // MCP Server Commands
             commands::mcp_server::get_mcp_server_status,
             commands::mcp_server::get_mcp_server_config,
@@ -446,6 +589,16 @@ pub fn run() {
             commands::agent_memory::save_agent_memory,
             commands::agent_memory::delete_agent_memory,
             commands::agent_memory::list_agent_memories,
+            // Entity Name Commands
+            commands::entity_names::validate_entity_names,
+            commands::entity_names::normalize_entity_name,
+            // Model Commands
+            commands::models::bulk_set_model,
+            // Tag Commands
+            commands::tags::list_tag_variants,
+            commands::tags::merge_tags,
+            // Entity Sync Commands
+            commands::entity_sync::get_entity_sync_status,
             // Memory Commands
             commands::memory::get_all_memory_files,
             commands::memory::get_memory_file,
@@ -470,7 +623,12 @@ pub fn run() {
             commands::mcp_gateway::remove_mcp_from_gateway,
             commands::mcp_gateway::toggle_gateway_mcp,
             commands::mcp_gateway::set_gateway_mcp_auto_restart,
+            commands::mcp_gateway::get_gateway_mcp_tool_filter,
+            commands::mcp_gateway::set_gateway_mcp_tool_filter,
             commands::mcp_gateway::is_mcp_in_gateway,
+            commands::mcp_gateway::detect_gateway_overlaps,
+            commands::mcp_gateway::get_gateway_backend_priority,
+            commands::mcp_gateway::set_gateway_backend_priority,
             commands::mcp_gateway::get_gateway_backends,
             commands::mcp_gateway::restart_gateway_backend,
             // Keybindings Commands
@@ -533,7 +691,16 @@ pub fn run() {
             commands::docker_hosts::update_docker_host,
             commands::docker_hosts::delete_docker_host,
             commands::docker_hosts::test_docker_host,
+            // Editor Bundle Commands
+            commands::editor_bundle::export_editor_bundle,
+            commands::editor_bundle::import_editor_bundle,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|_app, event| {
+            if let tauri::RunEvent::Exit = event {
+                // Pooled stdio MCP processes would otherwise outlive the app
+                services::mcp_client::STDIO_POOL.close_all();
+            }
+        });
 }
@@ -2,14 +2,29 @@
 //!
 //! Manages connections to backend MCP servers and aggregates their tools.
 
-use crate::db::models::{GatewayMcp, Mcp};
+use crate::db::models::{GatewayMcp, GatewayToolFilter, Mcp};
 use crate::db::Database;
-use crate::services::mcp_client::{McpServerInfo, McpTool, StdioMcpClient, ToolCallResult};
+use crate::mcp_gateway::server::{DEFAULT_BACKEND_TIMEOUT_SECS, DEFAULT_MAX_CONCURRENT_CONNECTS};
+use crate::services::mcp_client::{
+    resolve_env_file, McpServerInfo, McpTool, SpawnOptions, StdioMcpClient, ToolCallResult,
+};
+use crate::services::tool_cache;
 use anyhow::{anyhow, Result};
 use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// A freshly initialized stdio backend
+type ConnectedBackend = (StdioMcpClient, McpServerInfo, Vec<McpTool>);
+
+/// A backend's client, shared so keep-alive can ping it without holding the
+/// manager lock
+pub type SharedClient = Arc<Mutex<StdioMcpClient>>;
 
 /// Status of a backend MCP connection
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -61,10 +76,12 @@ pub struct ToolMapping {
 pub struct BackendConnection {
     pub mcp: Mcp,
     pub status: BackendStatus,
-    pub client: Option<StdioMcpClient>,
+    pub client: Option<SharedClient>,
     pub tools: Vec<McpTool>,
     pub server_info: Option<McpServerInfo>,
     pub restart_count: u32,
+    /// Which of the backend's tools the gateway exposes (all when `None`)
+    pub tool_filter: Option<GatewayToolFilter>,
 }
 
 impl BackendConnection {
@@ -76,16 +93,34 @@ impl BackendConnection {
             tools: Vec::new(),
             server_info: None,
             restart_count: 0,
+            tool_filter: None,
         }
     }
 
+    /// Whether the gateway exposes `tool_name` from this backend
+    pub fn exposes_tool(&self, tool_name: &str) -> bool {
+        self.tool_filter
+            .as_ref()
+            .map(|f| f.allows(tool_name))
+            .unwrap_or(true)
+    }
+
+    /// The backend's tools after applying the tool filter
+    pub fn visible_tools(&self) -> Vec<McpTool> {
+        self.tools
+            .iter()
+            .filter(|t| self.exposes_tool(&t.name))
+            .cloned()
+            .collect()
+    }
+
     pub fn to_info(&self) -> BackendInfo {
         BackendInfo {
             mcp_id: self.mcp.id,
             mcp_name: self.mcp.name.clone(),
             mcp_type: self.mcp.mcp_type.clone(),
             status: self.status.clone(),
-            tool_count: self.tools.len(),
+            tool_count: self.visible_tools().len(),
             server_info: self.server_info.clone(),
             error_message: match &self.status {
                 BackendStatus::Failed(msg) => Some(msg.clone()),
@@ -108,6 +143,10 @@ pub struct GatewayBackendManager {
     /// Tool index for connected backends
     tool_index: HashMap<String, ToolMapping>,
     db: Arc<Mutex<Database>>,
+    /// Stdio backends spawned at the same time when several connect at once
+    max_concurrent_connects: usize,
+    /// How long one backend may take to connect before it's marked failed
+    connect_timeout: Duration,
 }
 
 impl GatewayBackendManager {
@@ -117,9 +156,17 @@ impl GatewayBackendManager {
             backends: HashMap::new(),
             tool_index: HashMap::new(),
             db,
+            max_concurrent_connects: DEFAULT_MAX_CONCURRENT_CONNECTS,
+            connect_timeout: Duration::from_secs(DEFAULT_BACKEND_TIMEOUT_SECS),
         }
     }
 
+    /// Set the connect concurrency limit and per-backend connect timeout
+    pub fn set_connect_limits(&mut self, max_concurrent: usize, timeout: Duration) {
+        self.max_concurrent_connects = max_concurrent.max(1);
+        self.connect_timeout = timeout;
+    }
+
     /// Create a namespaced tool name from MCP name and original tool name
     pub fn namespace_tool(mcp_name: &str, tool_name: &str) -> String {
         // Sanitize MCP name: replace non-alphanumeric with underscore
@@ -165,6 +212,35 @@ impl GatewayBackendManager {
         Ok(())
     }
 
+    /// Reorder the available MCPs to match `mcp_ids` (highest priority
+    /// first). MCPs not listed keep their relative order after them.
+    pub fn set_backend_priority(&mut self, mcp_ids: &[i64]) {
+        self.available_mcps.sort_by_key(|mcp| {
+            mcp_ids
+                .iter()
+                .position(|id| *id == mcp.id)
+                .unwrap_or(usize::MAX)
+        });
+    }
+
+    /// Backends in priority order: the order of `available_mcps`, then any
+    /// backend no longer listed there, by name
+    fn backends_by_priority(&self) -> Vec<&BackendConnection> {
+        let mut backends: Vec<&BackendConnection> = self.backends.values().collect();
+        backends.sort_by(|a, b| {
+            let rank = |backend: &BackendConnection| {
+                self.available_mcps
+                    .iter()
+                    .position(|m| m.id == backend.mcp.id)
+                    .unwrap_or(usize::MAX)
+            };
+            rank(a)
+                .cmp(&rank(b))
+                .then_with(|| a.mcp.name.cmp(&b.mcp.name))
+        });
+        backends
+    }
+
     /// Get list of available MCPs (for list_available_mcps meta-tool)
     pub fn get_available_mcps(&self) -> Vec<AvailableMcp> {
         self.available_mcps
@@ -203,7 +279,7 @@ impl GatewayBackendManager {
                     "[Gateway] MCP '{}' already connected, returning cached tools",
                     mcp_name
                 );
-                return Ok(backend.tools.clone());
+                return Ok(backend.visible_tools());
             }
         }
 
@@ -228,7 +304,7 @@ impl GatewayBackendManager {
         // Return the tools
         self.backends
             .get(&mcp_meta.id)
-            .map(|b| b.tools.clone())
+            .map(|b| b.visible_tools())
             .ok_or_else(|| anyhow!("Failed to connect to MCP '{}'", mcp_name))
     }
 
@@ -238,59 +314,195 @@ impl GatewayBackendManager {
         self.backends
             .values()
             .find(|b| b.mcp.name == mcp_name && matches!(b.status, BackendStatus::Connected))
-            .map(|b| b.tools.clone())
+            .map(|b| b.visible_tools())
+    }
+
+    /// Connect several MCPs by name at once (for load_mcp_tools with
+    /// `mcp_names`). Each name gets its own result, so a slow or broken
+    /// backend doesn't hide the tools of the ones that answered in time.
+    pub async fn connect_backends_lazy(
+        &mut self,
+        mcp_names: &[String],
+    ) -> Vec<(String, Result<Vec<McpTool>>)> {
+        let gateway_mcps = match self.db.lock() {
+            Ok(db) => db.get_gateway_mcps().map_err(|e| e.to_string()),
+            Err(e) => Err(format!("Failed to lock database: {}", e)),
+        };
+        let gateway_mcps = match gateway_mcps {
+            Ok(gateway_mcps) => gateway_mcps,
+            Err(e) => {
+                return mcp_names
+                    .iter()
+                    .map(|name| (name.clone(), Err(anyhow!("{}", e))))
+                    .collect()
+            }
+        };
+
+        let to_connect: Vec<GatewayMcp> = gateway_mcps
+            .into_iter()
+            .filter(|gm| {
+                mcp_names.contains(&gm.mcp.name)
+                    && self.available_mcps.iter().any(|m| m.id == gm.mcp.id)
+                    && !self
+                        .backends
+                        .get(&gm.mcp.id)
+                        .is_some_and(|b| matches!(b.status, BackendStatus::Connected))
+            })
+            .collect();
+
+        info!(
+            "[Gateway] Lazy-connecting {} of {} requested MCPs",
+            to_connect.len(),
+            mcp_names.len()
+        );
+        self.add_backends(to_connect).await;
+        self.build_tool_index();
+
+        mcp_names
+            .iter()
+            .map(|name| (name.clone(), self.loaded_tools(name)))
+            .collect()
+    }
+
+    /// The visible tools of a connected MCP, or why it isn't connected
+    fn loaded_tools(&self, mcp_name: &str) -> Result<Vec<McpTool>> {
+        let mcp = self
+            .available_mcps
+            .iter()
+            .find(|m| m.name == mcp_name)
+            .ok_or_else(|| anyhow!("MCP '{}' not found in gateway", mcp_name))?;
+        match self.backends.get(&mcp.id).map(|b| (&b.status, b)) {
+            Some((BackendStatus::Connected, backend)) => Ok(backend.visible_tools()),
+            Some((BackendStatus::Failed(e), _)) => Err(anyhow!("{}", e)),
+            _ => Err(anyhow!("Failed to connect to MCP '{}'", mcp_name)),
+        }
     }
 
     /// Add a backend connection for an MCP
     async fn add_backend(&mut self, gateway_mcp: GatewayMcp) {
-        let mcp_id = gateway_mcp.mcp.id;
-        let mcp_name = gateway_mcp.mcp.name.clone();
-        let mcp_type = gateway_mcp.mcp.mcp_type.clone();
+        self.add_backends(vec![gateway_mcp]).await;
+    }
 
-        info!("[Gateway] Adding backend: {} ({})", mcp_name, mcp_type);
+    /// Add backend connections for several MCPs. At most
+    /// `max_concurrent_connects` stdio processes start at a time, and a backend
+    /// that doesn't finish its handshake within `connect_timeout` is marked
+    /// failed instead of holding up the others.
+    async fn add_backends(&mut self, gateway_mcps: Vec<GatewayMcp>) {
+        let semaphore = Arc::new(Semaphore::new(self.max_concurrent_connects));
+        let timeout = self.connect_timeout;
+        let mut pending = JoinSet::new();
+
+        for gateway_mcp in gateway_mcps {
+            let mcp_id = gateway_mcp.mcp.id;
+            info!(
+                "[Gateway] Adding backend: {} ({})",
+                gateway_mcp.mcp.name, gateway_mcp.mcp.mcp_type
+            );
 
-        let mut backend = BackendConnection::new(gateway_mcp.mcp.clone());
+            let mut backend = BackendConnection::new(gateway_mcp.mcp.clone());
+            backend.tool_filter = gateway_mcp.tool_filter;
+
+            // Only support stdio MCPs for now (HTTP/SSE would need different client handling)
+            if backend.mcp.mcp_type == "stdio" {
+                backend.status = BackendStatus::Connecting;
+                let mcp = gateway_mcp.mcp;
+                let semaphore = semaphore.clone();
+                pending.spawn(async move {
+                    let result = match semaphore.acquire_owned().await {
+                        Ok(_permit) => Self::connect_with_timeout(mcp, timeout).await,
+                        Err(e) => Err(anyhow!(e)),
+                    };
+                    (mcp_id, result)
+                });
+            } else {
+                // HTTP/SSE MCPs are not supported for gateway proxying yet
+                warn!(
+                    "[Gateway] Skipping {} - only stdio MCPs are supported for gateway",
+                    backend.mcp.name
+                );
+                backend.status = BackendStatus::Failed(
+                    "Only stdio MCPs are supported for gateway proxying".to_string(),
+                );
+            }
 
-        // Only support stdio MCPs for now (HTTP/SSE would need different client handling)
-        if mcp_type == "stdio" {
-            backend.status = BackendStatus::Connecting;
+            self.backends.insert(mcp_id, backend);
+        }
 
-            match self.connect_stdio_backend(&gateway_mcp.mcp).await {
-                Ok((client, server_info, tools)) => {
-                    info!(
-                        "[Gateway] Connected to {} with {} tools",
-                        mcp_name,
-                        tools.len()
-                    );
-                    backend.client = Some(client);
-                    backend.server_info = Some(server_info);
-                    backend.tools = tools;
-                    backend.status = BackendStatus::Connected;
-                }
-                Err(e) => {
-                    error!("[Gateway] Failed to connect to {}: {}", mcp_name, e);
-                    backend.status = BackendStatus::Failed(e.to_string());
+        while let Some(joined) = pending.join_next().await {
+            match joined {
+                Ok((mcp_id, result)) => self.finish_backend(mcp_id, result),
+                Err(e) => error!("[Gateway] Backend connect task failed: {}", e),
+            }
+        }
+    }
+
+    /// Record the outcome of a backend connect attempt
+    fn finish_backend(&mut self, mcp_id: i64, result: Result<ConnectedBackend>) {
+        let Some(backend) = self.backends.get_mut(&mcp_id) else {
+            return;
+        };
+        let mcp_name = backend.mcp.name.clone();
+        match result {
+            Ok((client, server_info, tools)) => {
+                info!(
+                    "[Gateway] Connected to {} with {} tools",
+                    mcp_name,
+                    tools.len()
+                );
+                if let Ok(db) = self.db.lock() {
+                    if let Err(e) = tool_cache::cache_mcp_tools(&db, mcp_id, &tools) {
+                        warn!("[Gateway] Failed to cache tools for {}: {}", mcp_name, e);
+                    }
                 }
+                backend.client = Some(Arc::new(Mutex::new(client)));
+                backend.server_info = Some(server_info);
+                backend.tools = tools;
+                backend.status = BackendStatus::Connected;
+            }
+            Err(e) => {
+                error!("[Gateway] Failed to connect to {}: {}", mcp_name, e);
+                backend.status = BackendStatus::Failed(e.to_string());
             }
-        } else {
-            // HTTP/SSE MCPs are not supported for gateway proxying yet
-            warn!(
-                "[Gateway] Skipping {} - only stdio MCPs are supported for gateway",
-                mcp_name
-            );
-            backend.status = BackendStatus::Failed(
-                "Only stdio MCPs are supported for gateway proxying".to_string(),
-            );
         }
+    }
 
-        self.backends.insert(mcp_id, backend);
+    /// Connect to a stdio MCP on a blocking thread, giving up after `timeout`.
+    /// A backend that answers after the deadline has its process closed.
+    async fn connect_with_timeout(mcp: Mcp, timeout: Duration) -> Result<ConnectedBackend> {
+        let mcp_name = mcp.name.clone();
+        let abandoned = Arc::new(AtomicBool::new(false));
+        let abandoned_flag = abandoned.clone();
+        let task = tokio::task::spawn_blocking(move || {
+            let result = Self::connect_stdio_backend(&mcp);
+            if abandoned_flag.load(Ordering::SeqCst) {
+                if let Ok((client, _, _)) = result {
+                    client.close();
+                }
+                return Err(anyhow!("Connection abandoned"));
+            }
+            result
+        });
+
+        match tokio::time::timeout(timeout, task).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(e)) => Err(anyhow!("Connect task failed: {}", e)),
+            Err(_) => {
+                abandoned.store(true, Ordering::SeqCst);
+                warn!(
+                    "[Gateway] {} did not connect within {}s, skipping it",
+                    mcp_name,
+                    timeout.as_secs()
+                );
+                Err(anyhow!(
+                    "Timed out after {}s waiting for the MCP to start",
+                    timeout.as_secs()
+                ))
+            }
+        }
     }
 
     /// Connect to a stdio-based MCP
-    async fn connect_stdio_backend(
-        &self,
-        mcp: &Mcp,
-    ) -> Result<(StdioMcpClient, McpServerInfo, Vec<McpTool>)> {
+    fn connect_stdio_backend(mcp: &Mcp) -> Result<ConnectedBackend> {
         let command = mcp
             .command
             .as_ref()
@@ -298,11 +510,26 @@ impl GatewayBackendManager {
 
         let args: Vec<String> = mcp.args.clone().unwrap_or_default();
         let env = mcp.env.clone();
+        let env_file = mcp.env_file.as_deref().map(|f| {
+            resolve_env_file(f, mcp.source_path.as_deref(), None)
+                .to_string_lossy()
+                .into_owned()
+        });
 
         info!("[Gateway] Starting stdio MCP: {} {:?}", command, args);
 
         // Spawn and initialize the client (spawn calls initialize internally)
-        let client = StdioMcpClient::spawn(command, &args, env.as_ref(), 30)?;
+        let client = StdioMcpClient::spawn(
+            &SpawnOptions {
+                command,
+                args: &args,
+                env: env.as_ref(),
+                env_file: env_file.as_deref(),
+                inherit_env: mcp.inherit_env,
+                shell: mcp.shell.as_deref(),
+            },
+            30,
+        )?;
 
         // Get server info and tools from the initialized client
         let server_info = client
@@ -311,6 +538,7 @@ impl GatewayBackendManager {
             .unwrap_or_else(|| McpServerInfo {
                 name: mcp.name.clone(),
                 version: None,
+                protocol_version: None,
             });
         let tools = client.tools().to_vec();
 
@@ -323,7 +551,11 @@ impl GatewayBackendManager {
 
         for (mcp_id, backend) in &self.backends {
             if matches!(backend.status, BackendStatus::Connected) {
-                for tool in &backend.tools {
+                for tool in backend
+                    .tools
+                    .iter()
+                    .filter(|t| backend.exposes_tool(&t.name))
+                {
                     let namespaced = Self::namespace_tool(&backend.mcp.name, &tool.name);
                     self.tool_index.insert(
                         namespaced,
@@ -339,22 +571,27 @@ impl GatewayBackendManager {
         }
     }
 
-    /// Get all aggregated tools with namespaced names
+    /// Get all aggregated tools with namespaced names, grouped by backend in
+    /// priority order and in each backend's own tool order
     #[allow(dead_code)]
     pub fn get_tools(&self) -> Vec<McpTool> {
-        self.tool_index
-            .values()
-            .map(|mapping| {
-                let mut tool = mapping.tool.clone();
-                // Update the name to the namespaced version
-                tool.name = Self::namespace_tool(&mapping.mcp_name, &mapping.original_name);
-                // Prepend MCP name to description
-                if let Some(desc) = &tool.description {
-                    tool.description = Some(format!("[{}] {}", mapping.mcp_name, desc));
-                } else {
-                    tool.description = Some(format!("[{}]", mapping.mcp_name));
-                }
-                tool
+        self.backends_by_priority()
+            .into_iter()
+            .filter(|backend| matches!(backend.status, BackendStatus::Connected))
+            .flat_map(|backend| {
+                let mcp_name = &backend.mcp.name;
+                backend.visible_tools().into_iter().map(move |mut tool| {
+                    // Update the name to the namespaced version
+                    let original_name = std::mem::take(&mut tool.name);
+                    tool.name = Self::namespace_tool(mcp_name, &original_name);
+                    // Prepend MCP name to description
+                    if let Some(desc) = &tool.description {
+                        tool.description = Some(format!("[{}] {}", mcp_name, desc));
+                    } else {
+                        tool.description = Some(format!("[{}]", mcp_name));
+                    }
+                    tool
+                })
             })
             .collect()
     }
@@ -387,9 +624,17 @@ impl GatewayBackendManager {
             ));
         }
 
+        if !backend.exposes_tool(tool_name) {
+            return Err(anyhow!(
+                "Tool '{}' on MCP '{}' is hidden by the gateway tool filter",
+                tool_name,
+                mcp_name
+            ));
+        }
+
         let client = backend
             .client
-            .as_mut()
+            .as_ref()
             .ok_or_else(|| anyhow!("MCP '{}' has no active client", mcp_name))?;
 
         info!(
@@ -397,7 +642,10 @@ impl GatewayBackendManager {
             tool_name, mcp_name
         );
 
-        client.call_tool(tool_name, arguments)
+        client
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock client for '{}': {}", mcp_name, e))?
+            .call_tool(tool_name, arguments)
     }
 
     /// Call a tool on the appropriate backend (legacy method for namespaced tools)
@@ -428,7 +676,7 @@ impl GatewayBackendManager {
 
         let client = backend
             .client
-            .as_mut()
+            .as_ref()
             .ok_or_else(|| anyhow!("Backend {} has no active client", mapping.mcp_name))?;
 
         info!(
@@ -436,12 +684,26 @@ impl GatewayBackendManager {
             mapping.original_name, mapping.mcp_name
         );
 
-        client.call_tool(&mapping.original_name, arguments)
+        client
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock client for {}: {}", mapping.mcp_name, e))?
+            .call_tool(&mapping.original_name, arguments)
     }
 
-    /// Get status of all backends
+    /// Update the tool filter of a connected backend in place
+    pub fn set_tool_filter(&mut self, mcp_id: i64, filter: Option<GatewayToolFilter>) {
+        if let Some(backend) = self.backends.get_mut(&mcp_id) {
+            backend.tool_filter = filter;
+        }
+        self.build_tool_index();
+    }
+
+    /// Get status of all backends, in priority order
     pub fn get_backends_info(&self) -> Vec<BackendInfo> {
-        self.backends.values().map(|b| b.to_info()).collect()
+        self.backends_by_priority()
+            .into_iter()
+            .map(|b| b.to_info())
+            .collect()
     }
 
     /// Get the total number of aggregated tools
@@ -462,6 +724,120 @@ impl GatewayBackendManager {
         self.tool_index.clear();
     }
 
+    /// Per-backend timeout for connects and keep-alive pings
+    pub fn connect_timeout(&self) -> Duration {
+        self.connect_timeout
+    }
+
+    /// Clients of the connected backends, collected under the manager lock so
+    /// [`Self::ping_clients`] can check them after it's released. Only stdio
+    /// backends ever connect, so these are all stdio sessions.
+    pub fn keep_alive_targets(&self) -> Vec<(i64, SharedClient)> {
+        self.backends
+            .iter()
+            .filter(|(_, b)| matches!(b.status, BackendStatus::Connected))
+            .filter_map(|(id, b)| b.client.clone().map(|c| (*id, c)))
+            .collect()
+    }
+
+    /// Ping every client at once and return the ones that didn't answer
+    pub async fn ping_clients(
+        targets: Vec<(i64, SharedClient)>,
+        timeout: Duration,
+    ) -> Vec<(i64, SharedClient)> {
+        let mut pending = JoinSet::new();
+        for (mcp_id, client) in targets {
+            pending.spawn(async move {
+                let result = Self::ping_with_timeout(client.clone(), timeout).await;
+                (mcp_id, client, result)
+            });
+        }
+
+        let mut dead = Vec::new();
+        while let Some(joined) = pending.join_next().await {
+            match joined {
+                Ok((_, _, Ok(()))) => {}
+                Ok((mcp_id, client, Err(e))) => {
+                    warn!("[Gateway] Keep-alive ping to MCP {} failed: {}", mcp_id, e);
+                    dead.push((mcp_id, client));
+                }
+                Err(e) => error!("[Gateway] Keep-alive ping task failed: {}", e),
+            }
+        }
+        dead
+    }
+
+    /// Reconnect the backends whose clients failed a keep-alive ping. A backend
+    /// that was restarted or removed since the ping is left alone. Returns the
+    /// ids of backends that were restarted.
+    pub async fn reconnect_dead(&mut self, dead: Vec<(i64, SharedClient)>) -> Vec<i64> {
+        let mut restarted = Vec::new();
+        for (mcp_id, client) in dead {
+            let Some(backend) = self.backends.get_mut(&mcp_id) else {
+                continue;
+            };
+            if !backend
+                .client
+                .as_ref()
+                .is_some_and(|current| Arc::ptr_eq(current, &client))
+            {
+                continue;
+            }
+            let restart_count = backend.restart_count;
+            Self::close_shared(backend.client.take());
+            Self::close_shared(Some(client));
+
+            match self.restart_backend(mcp_id).await {
+                Ok(_) => {
+                    if let Some(backend) = self.backends.get_mut(&mcp_id) {
+                        backend.restart_count = restart_count + 1;
+                    }
+                    restarted.push(mcp_id);
+                }
+                Err(e) => {
+                    error!(
+                        "[Gateway] Keep-alive reconnect of MCP {} failed: {}",
+                        mcp_id, e
+                    );
+                    if let Some(backend) = self.backends.get_mut(&mcp_id) {
+                        backend.client = None;
+                        backend.status = BackendStatus::Failed(e.to_string());
+                    }
+                    self.build_tool_index();
+                }
+            }
+        }
+        restarted
+    }
+
+    /// Ping a client on a blocking thread, giving up after `timeout` so a hung
+    /// backend can't stall the keep-alive loop
+    async fn ping_with_timeout(client: SharedClient, timeout: Duration) -> Result<()> {
+        let task = tokio::task::spawn_blocking(move || {
+            client
+                .lock()
+                .map_err(|e| anyhow!("Failed to lock client: {}", e))?
+                .ping()
+        });
+
+        match tokio::time::timeout(timeout, task).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(e)) => Err(anyhow!("Ping task failed: {}", e)),
+            Err(_) => Err(anyhow!("No answer within {}s", timeout.as_secs())),
+        }
+    }
+
+    /// Close a shared client's process once the last handle to it is dropped.
+    /// Handles still held elsewhere (a ping that timed out) just drop theirs.
+    fn close_shared(client: Option<SharedClient>) {
+        if let Some(client) = client.and_then(Arc::into_inner) {
+            client
+                .into_inner()
+                .unwrap_or_else(|e| e.into_inner())
+                .close();
+        }
+    }
+
     /// Restart a specific backend
     pub async fn restart_backend(&mut self, mcp_id: i64) -> Result<BackendInfo> {
         let gateway_mcp = {
@@ -518,6 +894,12 @@ mod tests {
             is_favorite: false,
             created_at: "2024-01-01".to_string(),
             updated_at: "2024-01-01".to_string(),
+            testable: true,
+            env_file: None,
+            display_name: None,
+            homepage: None,
+            inherit_env: true,
+            shell: None,
         }
     }
 
@@ -590,6 +972,7 @@ mod tests {
         conn.server_info = Some(McpServerInfo {
             name: "toolbox".to_string(),
             version: Some("1.0.0".to_string()),
+            protocol_version: None,
         });
 
         let info = conn.to_info();
@@ -711,6 +1094,7 @@ mod tests {
             server_info: Some(McpServerInfo {
                 name: "server".to_string(),
                 version: Some("2.0".to_string()),
+                protocol_version: None,
             }),
             error_message: None,
             restart_count: 0,
@@ -835,6 +1219,44 @@ mod tests {
         assert_eq!(infos.len(), 2);
     }
 
+    #[test]
+    fn test_manager_tool_filter_hides_tools() {
+        use crate::db::models::GatewayToolFilterMode;
+
+        let mut manager = make_test_manager();
+        let mut conn = BackendConnection::new(make_test_mcp(1, "fs", "stdio"));
+        conn.status = BackendStatus::Connected;
+        conn.tools = vec![
+            make_test_tool("read_file", None),
+            make_test_tool("delete_everything", None),
+        ];
+        manager.backends.insert(1, conn);
+
+        manager.set_tool_filter(
+            1,
+            Some(GatewayToolFilter {
+                mode: GatewayToolFilterMode::Deny,
+                tools: vec!["delete_everything".to_string()],
+            }),
+        );
+
+        assert_eq!(manager.tool_count(), 1);
+        let visible = manager.get_backend_tools("fs").unwrap();
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].name, "read_file");
+        assert_eq!(manager.get_backends_info()[0].tool_count, 1);
+
+        let err = manager
+            .call_tool_on_mcp("fs", "delete_everything", serde_json::json!({}))
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("hidden by the gateway tool filter"));
+
+        manager.set_tool_filter(1, None);
+        assert_eq!(manager.tool_count(), 2);
+    }
+
     #[test]
     fn test_manager_tool_count_empty() {
         let manager = make_test_manager();
@@ -932,6 +1354,44 @@ mod tests {
         assert!(names.contains(&"db__query".to_string()));
     }
 
+    #[test]
+    fn test_manager_get_tools_follows_backend_priority() {
+        let mut manager = make_test_manager();
+        for (id, name, tools) in [
+            (1, "zeta", vec!["b_tool", "a_tool"]),
+            (2, "alpha", vec!["query"]),
+        ] {
+            manager.available_mcps.push(AvailableMcp {
+                id,
+                name: name.to_string(),
+                description: None,
+                mcp_type: "stdio".to_string(),
+                status: BackendStatus::Disconnected,
+            });
+            let mut conn = BackendConnection::new(make_test_mcp(id, name, "stdio"));
+            conn.status = BackendStatus::Connected;
+            conn.tools = tools.into_iter().map(|t| make_test_tool(t, None)).collect();
+            manager.backends.insert(id, conn);
+        }
+        manager.build_tool_index();
+
+        let names = |manager: &GatewayBackendManager| -> Vec<String> {
+            manager.get_tools().into_iter().map(|t| t.name).collect()
+        };
+        assert_eq!(
+            names(&manager),
+            vec!["zeta__b_tool", "zeta__a_tool", "alpha__query"]
+        );
+
+        manager.set_backend_priority(&[2]);
+        assert_eq!(
+            names(&manager),
+            vec!["alpha__query", "zeta__b_tool", "zeta__a_tool"]
+        );
+        assert_eq!(manager.get_available_mcps()[0].name, "alpha");
+        assert_eq!(manager.get_backends_info()[0].mcp_name, "alpha");
+    }
+
     #[test]
     fn test_manager_get_backend_tools_connected() {
         let mut manager = make_test_manager();
@@ -1071,4 +1531,84 @@ mod tests {
         assert!(result.is_ok());
         assert!(manager.get_available_mcps().is_empty());
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_keep_alive_marks_dead_backend_failed() {
+        // Answers initialize and tools/list, then exits so the ping fails
+        let script = r#"for n in 1 2; do
+  IFS= read -r line
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  printf '{"jsonrpc":"2.0","id":%s,"result":{"tools":[]}}\n' "$id"
+  [ "$n" = 1 ] && IFS= read -r line
+done"#;
+        let client = StdioMcpClient::spawn(&SpawnOptions::new(script, &[]), 5).unwrap();
+
+        let mut manager = make_test_manager();
+        let mut backend = BackendConnection::new(make_test_mcp(1, "flaky", "stdio"));
+        backend.status = BackendStatus::Connected;
+        backend.client = Some(Arc::new(Mutex::new(client)));
+        manager.backends.insert(1, backend);
+
+        let targets = manager.keep_alive_targets();
+        assert_eq!(targets.len(), 1);
+
+        // Pinging doesn't need the manager
+        let dead = GatewayBackendManager::ping_clients(targets, manager.connect_timeout()).await;
+        assert_eq!(dead.len(), 1);
+
+        // The MCP isn't in the gateway DB, so the reconnect fails
+        assert!(manager.reconnect_dead(dead).await.is_empty());
+        let backend = manager.backends.get(&1).unwrap();
+        assert!(matches!(backend.status, BackendStatus::Failed(_)));
+        assert!(backend.client.is_none());
+    }
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_add_backends_skips_backends_that_time_out() {
+        let gateway_mcp = |id: i64, name: &str, command: &str| GatewayMcp {
+            id,
+            mcp_id: id,
+            mcp: Mcp {
+                command: Some(command.to_string()),
+                args: None,
+                ..make_test_mcp(id, name, "stdio")
+            },
+            is_enabled: true,
+            auto_restart: false,
+            display_order: 0,
+            created_at: "2024-01-01".to_string(),
+            tool_filter: None,
+        };
+        // Answers initialize and tools/list with one tool
+        let fast = r#"for n in 1 2; do
+  IFS= read -r line
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  printf '{"jsonrpc":"2.0","id":%s,"result":{"tools":[{"name":"echo","inputSchema":{}}]}}\n' "$id"
+  [ "$n" = 1 ] && IFS= read -r line
+done"#;
+
+        let mut manager = make_test_manager();
+        manager.set_connect_limits(2, Duration::from_secs(1));
+        let started = std::time::Instant::now();
+        manager
+            .add_backends(vec![
+                gateway_mcp(1, "slow", "sleep 3"),
+                gateway_mcp(2, "fast", fast),
+                gateway_mcp(3, "broken", "exit 1"),
+            ])
+            .await;
+        assert!(started.elapsed() < Duration::from_secs(3));
+
+        let slow = &manager.backends[&1];
+        assert!(matches!(&slow.status, BackendStatus::Failed(e) if e.contains("Timed out")));
+        let fast = &manager.backends[&2];
+        assert_eq!(fast.status, BackendStatus::Connected);
+        assert_eq!(fast.tools.len(), 1);
+        assert!(matches!(
+            manager.backends[&3].status,
+            BackendStatus::Failed(_)
+        ));
+        manager.shutdown();
+    }
 }
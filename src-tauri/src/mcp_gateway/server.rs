@@ -13,6 +13,7 @@ use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::net::TcpListener;
 use tokio::sync::oneshot;
 use tower_http::cors::{Any, CorsLayer};
@@ -27,6 +28,37 @@ pub struct GatewayServerConfig {
     pub enabled: bool,
     pub port: u16,
     pub auto_start: bool,
+    /// Seconds between keep-alive pings to connected stdio backends (0
+    /// disables). Read when the gateway starts.
+    #[serde(default = "default_keep_alive_interval_secs")]
+    pub keep_alive_interval_secs: u64,
+    /// Most stdio backends started at once when several are loaded together
+    #[serde(default = "default_max_concurrent_connects")]
+    pub max_concurrent_connects: usize,
+    /// Seconds a backend may take to connect before it's skipped
+    #[serde(default = "default_backend_timeout_secs")]
+    pub backend_timeout_secs: u64,
+}
+
+/// Default keep-alive interval for stdio backends
+pub const DEFAULT_KEEP_ALIVE_INTERVAL_SECS: u64 = 60;
+
+/// Default limit on backends connecting at the same time
+pub const DEFAULT_MAX_CONCURRENT_CONNECTS: usize = 4;
+
+/// Default per-backend connect timeout
+pub const DEFAULT_BACKEND_TIMEOUT_SECS: u64 = 30;
+
+fn default_keep_alive_interval_secs() -> u64 {
+    DEFAULT_KEEP_ALIVE_INTERVAL_SECS
+}
+
+fn default_max_concurrent_connects() -> usize {
+    DEFAULT_MAX_CONCURRENT_CONNECTS
+}
+
+fn default_backend_timeout_secs() -> u64 {
+    DEFAULT_BACKEND_TIMEOUT_SECS
 }
 
 impl Default for GatewayServerConfig {
@@ -35,6 +67,9 @@ impl Default for GatewayServerConfig {
             enabled: false,
             port: DEFAULT_GATEWAY_PORT,
             auto_start: false,
+            keep_alive_interval_secs: DEFAULT_KEEP_ALIVE_INTERVAL_SECS,
+            max_concurrent_connects: DEFAULT_MAX_CONCURRENT_CONNECTS,
+            backend_timeout_secs: DEFAULT_BACKEND_TIMEOUT_SECS,
         }
     }
 }
@@ -117,14 +152,21 @@ impl GatewayServerState {
             return Err("Gateway server is already running".to_string());
         }
 
-        let port = {
+        let (port, keep_alive_secs, max_concurrent, backend_timeout_secs) = {
             let config = self.config.lock().map_err(|e| e.to_string())?;
-            config.port
+            (
+                config.port,
+                config.keep_alive_interval_secs,
+                config.max_concurrent_connects,
+                config.backend_timeout_secs,
+            )
         };
 
         // Load available MCPs (lazy mode - no connections yet)
         {
             let mut backend_manager = self.backend_manager.lock().await;
+            backend_manager
+                .set_connect_limits(max_concurrent, Duration::from_secs(backend_timeout_secs));
             backend_manager
                 .load_available_mcps()
                 .map_err(|e| e.to_string())?;
@@ -194,6 +236,10 @@ impl GatewayServerState {
             log::info!("[Gateway] Server stopped");
         });
 
+        if keep_alive_secs > 0 {
+            self.spawn_keep_alive(keep_alive_secs);
+        }
+
         let status = self.get_status().await;
         log::info!(
             "[Gateway] MCP Gateway started in lazy mode on port {} with {} available MCPs",
@@ -203,6 +249,41 @@ impl GatewayServerState {
         Ok(())
     }
 
+    /// Periodically ping connected backends so idle sessions stay warm and
+    /// dead ones are reconnected before the next tool call. Exits once the
+    /// server stops.
+    fn spawn_keep_alive(&self, interval_secs: u64) {
+        let is_running = self.is_running.clone();
+        let backend_manager = self.backend_manager.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(interval_secs));
+            // The first tick completes immediately; nothing is connected yet
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                if !is_running.load(Ordering::SeqCst) {
+                    break;
+                }
+                // Ping without the manager lock so tool calls aren't held up
+                let (targets, timeout) = {
+                    let manager = backend_manager.lock().await;
+                    (manager.keep_alive_targets(), manager.connect_timeout())
+                };
+                let dead = GatewayBackendManager::ping_clients(targets, timeout).await;
+                if dead.is_empty() {
+                    continue;
+                }
+                let restarted = backend_manager.lock().await.reconnect_dead(dead).await;
+                if !restarted.is_empty() {
+                    log::info!(
+                        "[Gateway] Keep-alive reconnected {} backend(s)",
+                        restarted.len()
+                    );
+                }
+            }
+        });
+    }
+
     /// Stop the Gateway server
     pub async fn stop(&self) -> Result<(), String> {
         if !self.is_running() {
@@ -255,10 +336,13 @@ impl GatewayServerState {
     }
 }
 
+/// Library name of the Gateway's system MCP
+pub const GATEWAY_MCP_NAME: &str = "MCP Gateway";
+
 /// Generate an MCP entry for the Gateway server to add to the library as a system MCP
 pub fn generate_gateway_mcp_entry(port: u16) -> crate::db::models::CreateMcpRequest {
     crate::db::models::CreateMcpRequest {
-        name: "MCP Gateway".to_string(),
+        name: GATEWAY_MCP_NAME.to_string(),
         description: Some("Lazy-loading MCP gateway. Use list_available_mcps to discover MCPs, load_mcp_tools to connect and get tools, and call_mcp_tool to execute tools.".to_string()),
         mcp_type: "http".to_string(),  // Uses HTTP transport (Streamable HTTP)
         command: None,
@@ -268,6 +352,12 @@ pub fn generate_gateway_mcp_entry(port: u16) -> crate::db::models::CreateMcpRequ
         env: None,
         icon: Some("📡".to_string()),
         tags: Some(vec!["gateway".to_string(), "lazy".to_string(), "meta-tools".to_string()]),
+        testable: None,
+        env_file: None,
+        display_name: None,
+        inherit_env: None,
+        shell: None,
+        split_command: None,
     }
 }
 
@@ -289,6 +379,7 @@ mod tests {
             enabled: true,
             port: 9999,
             auto_start: true,
+            ..Default::default()
         };
         let json = serde_json::to_string(&config).unwrap();
         assert!(json.contains("autoStart")); // camelCase
@@ -352,6 +443,7 @@ mod tests {
             enabled: true,
             port: 5555,
             auto_start: true,
+            ..Default::default()
         };
         state.update_config(new_config).unwrap();
 
@@ -383,6 +475,7 @@ mod tests {
             enabled: true,
             port: 8080,
             auto_start: false,
+            ..Default::default()
         };
         let state = GatewayServerState::with_config(config, db_arc);
         assert_eq!(state.get_port(), 8080);
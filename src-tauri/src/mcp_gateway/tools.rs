@@ -22,7 +22,11 @@ use std::sync::Arc;
 /// Arguments for load_mcp_tools meta-tool
 #[derive(Debug, Deserialize)]
 struct LoadMcpToolsArgs {
+    #[serde(default)]
     mcp_name: String,
+    /// Load several MCPs at once; takes precedence over `mcp_name`
+    #[serde(default)]
+    mcp_names: Vec<String>,
 }
 
 /// Arguments for call_mcp_tool meta-tool
@@ -43,6 +47,26 @@ struct ToolInfo {
     input_schema: Option<Value>,
 }
 
+impl From<crate::services::mcp_client::McpTool> for ToolInfo {
+    fn from(tool: crate::services::mcp_client::McpTool) -> Self {
+        Self {
+            name: tool.name,
+            description: tool.description,
+            input_schema: tool.input_schema,
+        }
+    }
+}
+
+/// Per-MCP result of load_mcp_tools with `mcp_names`
+#[derive(Debug, Serialize)]
+struct McpToolsLoad {
+    mcp_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<ToolInfo>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
 /// The Gateway MCP Server handler
 ///
 /// Uses lazy-loading meta-tools instead of exposing all backend tools upfront.
@@ -109,7 +133,9 @@ impl ServerHandler for GatewayServer {
                     "load_mcp_tools",
                     "Load and return all tools from a specific MCP server. \
                     The MCP will be connected if not already. \
-                    Call this after list_available_mcps to see what tools an MCP offers.",
+                    Call this after list_available_mcps to see what tools an MCP offers. \
+                    Pass mcp_names instead to load several MCPs at once; MCPs that \
+                    fail or time out are reported with an error next to the others' tools.",
                     Arc::new(serde_json::Map::from_iter([
                         ("type".to_string(), json!("object")),
                         (
@@ -118,10 +144,15 @@ impl ServerHandler for GatewayServer {
                                 "mcp_name": {
                                     "type": "string",
                                     "description": "Name of the MCP to load tools from"
+                                },
+                                "mcp_names": {
+                                    "type": "array",
+                                    "items": { "type": "string" },
+                                    "description": "Names of several MCPs to load at once"
                                 }
                             }),
                         ),
-                        ("required".to_string(), json!(["mcp_name"])),
+                        ("required".to_string(), json!([])),
                     ])),
                 ),
                 Tool::new(
@@ -191,16 +222,33 @@ impl ServerHandler for GatewayServer {
                     })?;
 
                     let mut backend_manager = self.backend_manager.lock().await;
+                    if !args.mcp_names.is_empty() {
+                        let loads: Vec<McpToolsLoad> = backend_manager
+                            .connect_backends_lazy(&args.mcp_names)
+                            .await
+                            .into_iter()
+                            .map(|(mcp_name, result)| match result {
+                                Ok(tools) => McpToolsLoad {
+                                    mcp_name,
+                                    tools: Some(tools.into_iter().map(ToolInfo::from).collect()),
+                                    error: None,
+                                },
+                                Err(e) => McpToolsLoad {
+                                    mcp_name,
+                                    tools: None,
+                                    error: Some(e.to_string()),
+                                },
+                            })
+                            .collect();
+                        let result = serde_json::to_string_pretty(&loads)
+                            .unwrap_or_else(|e| format!("Error serializing tools: {}", e));
+                        return Ok(CallToolResult::success(vec![Content::text(result)]));
+                    }
+
                     match backend_manager.connect_backend_lazy(&args.mcp_name).await {
                         Ok(tools) => {
-                            let tool_infos: Vec<ToolInfo> = tools
-                                .into_iter()
-                                .map(|t| ToolInfo {
-                                    name: t.name,
-                                    description: t.description,
-                                    input_schema: t.input_schema,
-                                })
-                                .collect();
+                            let tool_infos: Vec<ToolInfo> =
+                                tools.into_iter().map(ToolInfo::from).collect();
                             let result = serde_json::to_string_pretty(&tool_infos)
                                 .unwrap_or_else(|e| format!("Error serializing tools: {}", e));
                             Ok(CallToolResult::success(vec![Content::text(result)]))
@@ -242,6 +242,12 @@ pub fn generate_self_mcp_entry(port: u16) -> crate::db::models::CreateMcpRequest
         env: None,
         icon: Some("🔧".to_string()),
         tags: Some(vec!["tool-manager".to_string(), "self".to_string(), "management".to_string()]),
+        testable: None,
+        env_file: None,
+        display_name: None,
+        inherit_env: None,
+        shell: None,
+        split_command: None,
     }
 }
 
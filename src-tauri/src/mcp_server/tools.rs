@@ -312,6 +312,12 @@ impl ToolManagerServer {
             env: params.env,
             icon: params.icon,
             tags: params.tags,
+            testable: None,
+            env_file: None,
+            display_name: None,
+            inherit_env: None,
+            shell: None,
+            split_command: None,
         };
 
         let db = self.get_db()?;
@@ -740,6 +746,7 @@ impl ToolManagerServer {
             once: None,
             async_mode: None,
             shell: None,
+            path_pattern: None,
         };
 
         let db = self.get_db()?;
@@ -924,6 +931,12 @@ mod tests {
                             .filter_map(|v| v.as_str().map(|s| s.to_string()))
                             .collect()
                     }),
+                    testable: None,
+                    env_file: None,
+                    display_name: None,
+                    inherit_env: None,
+                    shell: None,
+                    split_command: None,
                 };
                 let mcp = db.create_mcp(&request).map_err(|e| e.to_string())?;
                 let json = serde_json::to_string_pretty(&mcp).map_err(|e| e.to_string())?;
@@ -1304,6 +1317,7 @@ mod tests {
                     once: None,
                     async_mode: None,
                     shell: None,
+                    path_pattern: None,
                 };
                 let item = db.create_hook(&request).map_err(|e| e.to_string())?;
                 let json = serde_json::to_string_pretty(&item).map_err(|e| e.to_string())?;
@@ -1,10 +1,12 @@
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::collections::HashMap;
-use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
 
-use crate::utils::paths::{get_claude_paths, normalize_path};
+use crate::services::pending_changes::{read_config_file, write_config_file, PendingChange};
+use crate::utils::paths::{find_equivalent_path, get_claude_paths, normalize_path};
 
 /// Default MCP type when not specified (stdio is the Claude Code default)
 pub(crate) fn default_mcp_type() -> String {
@@ -32,6 +34,10 @@ pub struct ClaudeJsonMcpServer {
     // common
     #[serde(skip_serializing_if = "Option::is_none")]
     pub env: Option<HashMap<String, String>>,
+
+    /// Entry-level disable flag, honored alongside `disabledMcpServers`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
 }
 
 /// Project-specific settings in claude.json
@@ -72,18 +78,52 @@ pub struct DetectedMcp {
 /// Read the claude.json file
 pub fn read_claude_json() -> Result<Value> {
     let paths = get_claude_paths()?;
-    let content = fs::read_to_string(&paths.claude_json)
-        .with_context(|| format!("Failed to read {:?}", paths.claude_json))?;
+    let content = read_config_file(&paths.claude_json)
+        .with_context(|| format!("Failed to read {:?}", paths.claude_json))?
+        .with_context(|| format!("{:?} does not exist", paths.claude_json))?;
     let json: Value = serde_json::from_str(&content)?;
     Ok(json)
 }
 
-/// Write to claude.json file (preserving all other fields)
-pub fn write_claude_json(json: &Value) -> Result<()> {
-    let paths = get_claude_paths()?;
-    let content = serde_json::to_string_pretty(json)?;
-    fs::write(&paths.claude_json, content)?;
-    Ok(())
+/// Serializes read-modify-write cycles on claude.json, which the global
+/// config and every project share
+static CLAUDE_JSON_WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Read the claude.json at `path`; a missing file reads as `{}`. A file that
+/// doesn't parse is an error so it never gets overwritten.
+pub(crate) fn read_claude_json_at(path: &Path) -> Result<Value> {
+    match read_config_file(path)? {
+        Some(content) => serde_json::from_str(&content).map_err(|e| {
+            anyhow!(
+                "Failed to parse existing Claude config at {}: {}. \
+                 Refusing to overwrite to prevent data loss.",
+                path.display(),
+                e
+            )
+        }),
+        None => Ok(json!({})),
+    }
+}
+
+/// Apply `edit` to the claude.json at `path` and write it back (preserving
+/// all other fields). Concurrent updates can't drop each other's edits.
+pub(crate) fn update_claude_json_at(
+    path: &Path,
+    edit: impl FnOnce(&mut Value) -> Result<()>,
+) -> Result<Option<PendingChange>> {
+    let _guard = CLAUDE_JSON_WRITE_LOCK
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let mut json = read_claude_json_at(path)?;
+    edit(&mut json)?;
+    write_config_file(path, &serde_json::to_string_pretty(&json)?)
+}
+
+/// [`update_claude_json_at`] for the user's `~/.claude.json`
+pub fn update_claude_json(
+    edit: impl FnOnce(&mut Value) -> Result<()>,
+) -> Result<Option<PendingChange>> {
+    update_claude_json_at(&get_claude_paths()?.claude_json, edit)
 }
 
 /// Get all MCPs from claude.json (both global and project-specific)
@@ -117,7 +157,8 @@ pub fn get_all_mcps_from_claude_json() -> Result<Vec<DetectedMcp>> {
             if let Ok(project) = serde_json::from_value::<ClaudeJsonProject>(project_value.clone())
             {
                 for (name, server) in project.mcp_servers {
-                    let is_enabled = !project.disabled_mcp_servers.contains(&name);
+                    let is_enabled =
+                        !server.disabled && !project.disabled_mcp_servers.contains(&name);
                     mcps.push(DetectedMcp {
                         name: name.clone(),
                         mcp_type: server.mcp_type,
@@ -177,8 +218,17 @@ pub fn add_mcp_to_project_in_claude_json(
     project_path: &str,
     mcp_name: &str,
     server: ClaudeJsonMcpServer,
+) -> Result<Option<PendingChange>> {
+    update_claude_json(|json| apply_add_mcp_to_project(json, project_path, mcp_name, server))
+}
+
+/// Add an MCP to a project in an in-memory claude.json
+pub(crate) fn apply_add_mcp_to_project(
+    json: &mut Value,
+    project_path: &str,
+    mcp_name: &str,
+    server: ClaudeJsonMcpServer,
 ) -> Result<()> {
-    let mut json = read_claude_json()?;
     let normalized_path = normalize_path(project_path);
 
     // Ensure projects object exists
@@ -189,10 +239,8 @@ pub fn add_mcp_to_project_in_claude_json(
     // Find or create project entry (check both path formats)
     let projects = json.get_mut("projects").unwrap().as_object_mut().unwrap();
 
-    let project_key = if projects.contains_key(project_path) {
-        project_path.to_string()
-    } else if projects.contains_key(&normalized_path) {
-        normalized_path
+    let project_key = if let Some(key) = find_equivalent_path(projects.keys(), project_path) {
+        key.clone()
     } else {
         // Create new project entry
         projects.insert(
@@ -219,19 +267,35 @@ pub fn add_mcp_to_project_in_claude_json(
         project["mcpServers"] = serde_json::json!({});
     }
     project["mcpServers"][mcp_name] = serde_json::to_value(server)?;
-
-    write_claude_json(&json)?;
     Ok(())
 }
 
 /// Remove an MCP from a project in claude.json
-pub fn remove_mcp_from_project_in_claude_json(project_path: &str, mcp_name: &str) -> Result<()> {
-    let mut json = read_claude_json()?;
+pub fn remove_mcp_from_project_in_claude_json(
+    project_path: &str,
+    mcp_name: &str,
+) -> Result<Option<PendingChange>> {
+    update_claude_json(|json| {
+        apply_remove_mcp_from_project(json, project_path, mcp_name);
+        Ok(())
+    })
+}
+
+/// Remove an MCP from a project in an in-memory claude.json
+pub(crate) fn apply_remove_mcp_from_project(json: &mut Value, project_path: &str, mcp_name: &str) {
     let normalized_path = normalize_path(project_path);
 
     if let Some(projects) = json.get_mut("projects").and_then(|v| v.as_object_mut()) {
-        // Try both path formats
-        for key in [project_path, &normalized_path] {
+        // Try both path formats, plus any WSL/Windows spelling of the same directory
+        let equivalent = find_equivalent_path(projects.keys(), project_path).cloned();
+        for key in [
+            Some(project_path),
+            Some(normalized_path.as_str()),
+            equivalent.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        {
             if let Some(project) = projects.get_mut(key) {
                 if let Some(servers) = project
                     .get_mut("mcpServers")
@@ -249,9 +313,6 @@ pub fn remove_mcp_from_project_in_claude_json(project_path: &str, mcp_name: &str
             }
         }
     }
-
-    write_claude_json(&json)?;
-    Ok(())
 }
 
 /// Toggle an MCP's enabled state in a project
@@ -259,12 +320,32 @@ pub fn toggle_mcp_in_project_claude_json(
     project_path: &str,
     mcp_name: &str,
     enabled: bool,
-) -> Result<()> {
-    let mut json = read_claude_json()?;
+) -> Result<Option<PendingChange>> {
+    update_claude_json(|json| {
+        apply_toggle_mcp_in_project(json, project_path, mcp_name, enabled);
+        Ok(())
+    })
+}
+
+/// Toggle an MCP's enabled state in a project of an in-memory claude.json
+pub(crate) fn apply_toggle_mcp_in_project(
+    json: &mut Value,
+    project_path: &str,
+    mcp_name: &str,
+    enabled: bool,
+) {
     let normalized_path = normalize_path(project_path);
 
     if let Some(projects) = json.get_mut("projects").and_then(|v| v.as_object_mut()) {
-        for key in [project_path, &normalized_path] {
+        let equivalent = find_equivalent_path(projects.keys(), project_path).cloned();
+        for key in [
+            Some(project_path),
+            Some(normalized_path.as_str()),
+            equivalent.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        {
             if let Some(project) = projects.get_mut(key) {
                 // Ensure disabledMcpServers array exists
                 if project.get("disabledMcpServers").is_none() {
@@ -289,35 +370,68 @@ pub fn toggle_mcp_in_project_claude_json(
             }
         }
     }
-
-    write_claude_json(&json)?;
-    Ok(())
 }
 
 /// Add a global MCP to claude.json
-pub fn add_global_mcp_to_claude_json(mcp_name: &str, server: ClaudeJsonMcpServer) -> Result<()> {
-    let mut json = read_claude_json()?;
+pub fn add_global_mcp_to_claude_json(
+    mcp_name: &str,
+    server: ClaudeJsonMcpServer,
+) -> Result<Option<PendingChange>> {
+    update_claude_json(|json| apply_add_global_mcp(json, mcp_name, server))
+}
 
+/// Add a global MCP to an in-memory claude.json
+pub(crate) fn apply_add_global_mcp(
+    json: &mut Value,
+    mcp_name: &str,
+    server: ClaudeJsonMcpServer,
+) -> Result<()> {
     if json.get("mcpServers").is_none() {
         json["mcpServers"] = serde_json::json!({});
     }
 
     json["mcpServers"][mcp_name] = serde_json::to_value(server)?;
-
-    write_claude_json(&json)?;
     Ok(())
 }
 
 /// Remove a global MCP from claude.json
-pub fn remove_global_mcp_from_claude_json(mcp_name: &str) -> Result<()> {
-    let mut json = read_claude_json()?;
+pub fn remove_global_mcp_from_claude_json(mcp_name: &str) -> Result<Option<PendingChange>> {
+    update_claude_json(|json| {
+        apply_remove_global_mcp(json, mcp_name);
+        Ok(())
+    })
+}
 
+/// Remove a global MCP from an in-memory claude.json
+pub(crate) fn apply_remove_global_mcp(json: &mut Value, mcp_name: &str) {
     if let Some(servers) = json.get_mut("mcpServers").and_then(|v| v.as_object_mut()) {
         servers.remove(mcp_name);
     }
+}
 
-    write_claude_json(&json)?;
-    Ok(())
+/// claude.json before and after a proposed change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeJsonPreview {
+    pub before: Value,
+    pub after: Value,
+    pub changed: bool,
+}
+
+/// Run `apply` against a copy of `json` and return both versions. Mutations
+/// share their `apply_*` function with the real write, so the preview is
+/// exactly what would be written.
+pub fn preview_claude_json_change(
+    json: Value,
+    apply: impl FnOnce(&mut Value) -> Result<()>,
+) -> Result<ClaudeJsonPreview> {
+    let mut after = json.clone();
+    apply(&mut after)?;
+    Ok(ClaudeJsonPreview {
+        changed: after != json,
+        before: json,
+        after,
+    })
 }
 
 #[cfg(test)]
@@ -450,6 +564,7 @@ mod tests {
             url: None,
             headers: None,
             env: None,
+            disabled: false,
         };
 
         let json = serde_json::to_string(&server).unwrap();
@@ -474,6 +589,7 @@ mod tests {
             url: Some("https://example.com/sse".to_string()),
             headers: Some(headers),
             env: None,
+            disabled: false,
         };
 
         let json = serde_json::to_string(&server).unwrap();
@@ -629,6 +745,7 @@ mod tests {
             url: None,
             headers: None,
             env: Some(env),
+            disabled: false,
         };
 
         let json = serde_json::to_string(&original).unwrap();
@@ -760,6 +877,7 @@ mod tests {
             url: Some("https://example.com".to_string()),
             headers: Some(headers),
             env: Some(env),
+            disabled: false,
         };
 
         let json = serde_json::to_string(&server).unwrap();
@@ -923,6 +1041,7 @@ mod tests {
             url: None,
             headers: None,
             env: None,
+            disabled: false,
         };
 
         let cloned = server.clone();
@@ -1031,7 +1150,8 @@ mod tests {
                     serde_json::from_value::<ClaudeJsonProject>(project_value.clone())
                 {
                     for (name, server) in project.mcp_servers {
-                        let is_enabled = !project.disabled_mcp_servers.contains(&name);
+                        let is_enabled =
+                            !server.disabled && !project.disabled_mcp_servers.contains(&name);
                         mcps.push(DetectedMcp {
                             name: name.clone(),
                             mcp_type: server.mcp_type,
@@ -1142,6 +1262,7 @@ mod tests {
             url: None,
             headers: None,
             env: None,
+            disabled: false,
         };
         let project = projects.get_mut(project_path).unwrap();
         project["mcpServers"]["test-mcp"] = serde_json::to_value(&server).unwrap();
@@ -1305,6 +1426,7 @@ mod tests {
             url: None,
             headers: None,
             env: None,
+            disabled: false,
         };
 
         if json.get("mcpServers").is_none() {
@@ -1475,6 +1597,7 @@ mod tests {
             url: None,
             headers: None,
             env: None,
+            disabled: false,
         };
         project["mcpServers"]["new-mcp"] = serde_json::to_value(&server).unwrap();
 
@@ -1488,4 +1611,39 @@ mod tests {
         // allowedTools should be preserved
         assert!(result["projects"]["/tmp/proj"]["allowedTools"].is_array());
     }
+
+    #[test]
+    fn test_concurrent_updates_keep_every_edit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("claude.json");
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    update_claude_json_at(&path, |json| {
+                        json[format!("key{}", i)] = json!(i);
+                        Ok(())
+                    })
+                    .unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let result = read_claude_json_at(&path).unwrap();
+        assert_eq!(result.as_object().unwrap().len(), 8);
+    }
+
+    #[test]
+    fn test_update_refuses_to_overwrite_invalid_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("claude.json");
+        std::fs::write(&path, "not json").unwrap();
+
+        assert!(update_claude_json_at(&path, |_| Ok(())).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "not json");
+    }
 }
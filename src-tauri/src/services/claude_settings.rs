@@ -1,3 +1,4 @@
+use crate::services::pending_changes::PendingChange;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
@@ -98,23 +99,16 @@ pub struct AllClaudeSettings {
 
 /// Read an existing settings.json file or return an empty object
 fn read_settings_file(path: &Path) -> Result<Value> {
-    if path.exists() {
-        let content = std::fs::read_to_string(path)?;
-        Ok(serde_json::from_str(&content).unwrap_or(json!({})))
-    } else {
-        Ok(json!({}))
+    match crate::services::pending_changes::read_config_file(path)? {
+        Some(content) => Ok(serde_json::from_str(&content).unwrap_or(json!({}))),
+        None => Ok(json!({})),
     }
 }
 
 /// Write settings.json file
-fn write_settings_file(path: &Path, settings: &Value) -> Result<()> {
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
-    crate::utils::backup::backup_file(path)?;
+fn write_settings_file(path: &Path, settings: &Value) -> Result<Option<PendingChange>> {
     let content = serde_json::to_string_pretty(settings)?;
-    std::fs::write(path, content)?;
-    Ok(())
+    crate::services::pending_changes::write_config_file(path, &content)
 }
 
 /// Helper: extract a string array from a JSON value by key
@@ -456,7 +450,7 @@ pub fn write_claude_settings(
     scope: &PermissionScope,
     project_path: Option<&Path>,
     settings: &ClaudeSettings,
-) -> Result<()> {
+) -> Result<Option<PendingChange>> {
     let path = resolve_settings_path(scope, project_path)?;
     let mut file_settings = read_settings_file(&path)?;
 
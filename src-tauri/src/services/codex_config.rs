@@ -1,3 +1,4 @@
+use crate::services::mcp_json_config::backup_config_file;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -163,42 +164,30 @@ fn parse_mcp_entry(name: &str, config: &toml::Value) -> Result<ParsedCodexMcp> {
     }
 }
 
-/// MCP tuple for writing (same format used by other editors)
-pub type McpTuple = (
-    String,         // name
-    String,         // type (stdio, sse, http)
-    Option<String>, // command
-    Option<String>, // args (JSON)
-    Option<String>, // url
-    Option<String>, // headers (JSON)
-    Option<String>, // env (JSON)
-);
-
-/// Create a backup of the config file before modifying it
-fn backup_config_file(path: &Path) -> Result<()> {
-    if !path.exists() {
+pub use crate::services::mcp_json_config::McpTuple;
+
+/// Write MCP servers to Codex config.toml, preserving existing content
+pub fn write_codex_config(path: &Path, mcps: &[McpTuple]) -> Result<()> {
+    let Some(content) = render_codex_config(path, mcps)? else {
         return Ok(());
+    };
+
+    // Ensure parent directory exists
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
     }
 
-    let backup_ext = if path.extension().and_then(|e| e.to_str()) == Some("toml") {
-        "toml.bak"
-    } else {
-        "bak"
-    };
-    let backup_path = path.with_extension(backup_ext);
-    std::fs::copy(path, &backup_path).map_err(|e| {
-        anyhow::anyhow!(
-            "Failed to create backup of {} before writing: {}",
-            path.display(),
-            e
-        )
-    })?;
+    // Back up the existing file before modifying it
+    backup_config_file(path)?;
+
+    // Write back
+    std::fs::write(path, content)?;
 
     Ok(())
 }
 
-/// Write MCP servers to Codex config.toml, preserving existing content
-pub fn write_codex_config(path: &Path, mcps: &[McpTuple]) -> Result<()> {
+/// The content [`write_codex_config`] would write; `None` leaves the file as is
+pub fn render_codex_config(path: &Path, mcps: &[McpTuple]) -> Result<Option<String>> {
     // Read existing config or create new
     let content = if path.exists() {
         std::fs::read_to_string(path)?
@@ -217,7 +206,7 @@ pub fn write_codex_config(path: &Path, mcps: &[McpTuple]) -> Result<()> {
 
     // Skip overwrite when DB has no MCPs — preserves externally-managed configs
     if mcps.is_empty() {
-        return Ok(());
+        return Ok(None);
     }
 
     // Create or get mcp_servers table
@@ -304,18 +293,7 @@ pub fn write_codex_config(path: &Path, mcps: &[McpTuple]) -> Result<()> {
         mcp_servers.insert(name, Item::Table(server_table));
     }
 
-    // Ensure parent directory exists
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
-
-    // Back up the existing file before modifying it
-    backup_config_file(path)?;
-
-    // Write back
-    std::fs::write(path, doc.to_string())?;
-
-    Ok(())
+    Ok(Some(doc.to_string()))
 }
 
 /// Add a single MCP to Codex config
@@ -849,8 +827,8 @@ url = "https://example.com/mcp"
 
         backup_config_file(&config_path).unwrap();
 
-        // Non-toml files should get .bak extension
-        let backup_path = config_path.with_extension("bak");
+        // The backup keeps the full file name and appends .bak
+        let backup_path = config_path.with_extension("json.bak");
         assert!(backup_path.exists());
     }
 
@@ -899,4 +877,94 @@ url = "https://example.com/mcp"
         assert!(content.contains("command = \"node\""));
         assert!(!content.contains("args"));
     }
+
+    // =========================================================================
+    // round-trip tests
+    // =========================================================================
+
+    fn parsed_to_tuple(mcp: &ParsedCodexMcp) -> McpTuple {
+        (
+            mcp.name.clone(),
+            mcp.mcp_type.clone(),
+            mcp.command.clone(),
+            mcp.args.as_ref().map(|a| serde_json::to_string(a).unwrap()),
+            mcp.url.clone(),
+            mcp.headers
+                .as_ref()
+                .map(|h| serde_json::to_string(h).unwrap()),
+            mcp.env.as_ref().map(|e| serde_json::to_string(e).unwrap()),
+        )
+    }
+
+    #[test]
+    fn test_round_trip_stdio_mcp() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        fs::write(
+            &config_path,
+            r#"model = "o3"
+
+[mcp_servers.fs]
+command = "npx"
+args = ["-y", "@modelcontextprotocol/server-filesystem", "/tmp"]
+env = { LOG_LEVEL = "debug" }
+"#,
+        )
+        .unwrap();
+
+        let parsed = parse_codex_mcps(&config_path).unwrap();
+        let tuples: Vec<McpTuple> = parsed.iter().map(parsed_to_tuple).collect();
+        write_codex_config(&config_path, &tuples).unwrap();
+
+        let content = fs::read_to_string(&config_path).unwrap();
+        let doc: toml::Value = toml::from_str(&content).unwrap();
+        assert_eq!(doc["model"].as_str(), Some("o3"));
+
+        let reparsed = parse_codex_mcps(&config_path).unwrap();
+        assert_eq!(reparsed.len(), 1);
+        assert_eq!(reparsed[0].name, "fs");
+        assert_eq!(reparsed[0].mcp_type, "stdio");
+        assert_eq!(reparsed[0].command, parsed[0].command);
+        assert_eq!(reparsed[0].args, parsed[0].args);
+        assert_eq!(reparsed[0].env, parsed[0].env);
+    }
+
+    #[test]
+    fn test_round_trip_remote_mcp() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        fs::write(
+            &config_path,
+            r#"approval_policy = "on-request"
+
+[mcp_servers.github]
+url = "https://api.example.com/mcp"
+bearer_token_env_var = "GITHUB_TOKEN"
+http_headers = { "X-Custom" = "value" }
+
+[profiles.fast]
+model = "o4-mini"
+"#,
+        )
+        .unwrap();
+
+        let parsed = parse_codex_mcps(&config_path).unwrap();
+        let tuples: Vec<McpTuple> = parsed.iter().map(parsed_to_tuple).collect();
+        write_codex_config(&config_path, &tuples).unwrap();
+
+        let content = fs::read_to_string(&config_path).unwrap();
+        let doc: toml::Value = toml::from_str(&content).unwrap();
+        assert_eq!(doc["approval_policy"].as_str(), Some("on-request"));
+        assert_eq!(doc["profiles"]["fast"]["model"].as_str(), Some("o4-mini"));
+        assert_eq!(
+            doc["mcp_servers"]["github"]["bearer_token_env_var"].as_str(),
+            Some("GITHUB_TOKEN")
+        );
+
+        let reparsed = parse_codex_mcps(&config_path).unwrap();
+        assert_eq!(reparsed.len(), 1);
+        assert_eq!(reparsed[0].mcp_type, "http");
+        assert_eq!(reparsed[0].url, parsed[0].url);
+        assert_eq!(reparsed[0].headers, parsed[0].headers);
+    }
 }
@@ -1,8 +1,9 @@
 use crate::db::models::Command;
-use crate::utils::opencode_paths::get_opencode_paths;
+use crate::utils::opencode_paths::{get_opencode_paths, project_opencode_dir};
+use crate::utils::windsurf_paths::get_windsurf_paths;
 use anyhow::Result;
 use directories::BaseDirs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 // ============================================================================
 // Claude Code Command Writer
@@ -51,13 +52,22 @@ pub(crate) fn generate_command_markdown(command: &Command) -> String {
     format!("{}{}", frontmatter, command.content)
 }
 
+/// A command's file: {base_path}/.claude/commands/{name}.md
+pub fn command_file_path(base_path: &Path, name: &str) -> PathBuf {
+    base_path
+        .join(".claude")
+        .join("commands")
+        .join(format!("{}.md", name))
+}
+
 /// Write a command to the appropriate location
 /// Commands go to {base_path}/.claude/commands/{name}.md
 pub fn write_command_file(base_path: &Path, command: &Command) -> Result<()> {
-    let commands_dir = base_path.join(".claude").join("commands");
-    std::fs::create_dir_all(&commands_dir)?;
+    let file_path = command_file_path(base_path, &command.name);
+    if let Some(commands_dir) = file_path.parent() {
+        std::fs::create_dir_all(commands_dir)?;
+    }
 
-    let file_path = commands_dir.join(format!("{}.md", command.name));
     crate::utils::backup::backup_file(&file_path)?;
     let content = generate_command_markdown(command);
     std::fs::write(file_path, content)?;
@@ -67,21 +77,18 @@ pub fn write_command_file(base_path: &Path, command: &Command) -> Result<()> {
 
 /// Delete a command file from the appropriate location
 pub fn delete_command_file(base_path: &Path, command: &Command) -> Result<()> {
-    let file_path = base_path
-        .join(".claude")
-        .join("commands")
-        .join(format!("{}.md", command.name));
+    let file_path = command_file_path(base_path, &command.name);
 
     if file_path.exists() {
         std::fs::remove_file(&file_path)?;
     }
 
     // Clean up empty commands directory
-    let commands_dir = base_path.join(".claude").join("commands");
+    let commands_dir = file_path.parent().unwrap_or(base_path);
     if commands_dir.exists() {
-        if let Ok(entries) = std::fs::read_dir(&commands_dir) {
+        if let Ok(entries) = std::fs::read_dir(commands_dir) {
             if entries.count() == 0 {
-                let _ = std::fs::remove_dir(&commands_dir);
+                let _ = std::fs::remove_dir(commands_dir);
             }
         }
     }
@@ -146,13 +153,19 @@ pub(crate) fn generate_command_markdown_opencode(command: &Command) -> String {
     format!("{}{}", frontmatter, command.content)
 }
 
+/// A command's OpenCode file: {base_path}/command/{name}.md
+pub fn command_file_path_opencode(base_path: &Path, name: &str) -> PathBuf {
+    base_path.join("command").join(format!("{}.md", name))
+}
+
 /// Write a command to OpenCode's format
 /// Commands go to {base_path}/command/{name}.md
 pub fn write_command_file_opencode(base_path: &Path, command: &Command) -> Result<()> {
-    let command_dir = base_path.join("command");
-    std::fs::create_dir_all(&command_dir)?;
+    let file_path = command_file_path_opencode(base_path, &command.name);
+    if let Some(command_dir) = file_path.parent() {
+        std::fs::create_dir_all(command_dir)?;
+    }
 
-    let file_path = command_dir.join(format!("{}.md", command.name));
     crate::utils::backup::backup_file(&file_path)?;
     let content = generate_command_markdown_opencode(command);
     std::fs::write(file_path, content)?;
@@ -162,9 +175,7 @@ pub fn write_command_file_opencode(base_path: &Path, command: &Command) -> Resul
 
 /// Delete a command from OpenCode's format
 pub fn delete_command_file_opencode(base_path: &Path, command: &Command) -> Result<()> {
-    let file_path = base_path
-        .join("command")
-        .join(format!("{}.md", command.name));
+    let file_path = command_file_path_opencode(base_path, &command.name);
 
     if file_path.exists() {
         std::fs::remove_file(&file_path)?;
@@ -187,16 +198,84 @@ pub fn delete_global_command_opencode(command: &Command) -> Result<()> {
 
 /// Write a command to a project's OpenCode config ({project}/.opencode/)
 pub fn write_project_command_opencode(project_path: &Path, command: &Command) -> Result<()> {
-    let opencode_dir = project_path.join(".opencode");
+    let opencode_dir = project_opencode_dir(project_path);
     write_command_file_opencode(&opencode_dir, command)
 }
 
 /// Delete a command from a project's OpenCode config
 pub fn delete_project_command_opencode(project_path: &Path, command: &Command) -> Result<()> {
-    let opencode_dir = project_path.join(".opencode");
+    let opencode_dir = project_opencode_dir(project_path);
     delete_command_file_opencode(&opencode_dir, command)
 }
 
+// ============================================================================
+// Windsurf Support
+// ============================================================================
+// Windsurf has no slash commands; the closest match is a workflow, a single
+// markdown file in .windsurf/workflows/{name}.md invoked as /{name}.
+// Workflows only take a description in their frontmatter.
+
+/// Generate markdown content for a Windsurf workflow
+pub(crate) fn generate_workflow_markdown_windsurf(command: &Command) -> String {
+    let mut frontmatter = String::from("---\n");
+
+    if let Some(ref desc) = command.description {
+        if !desc.is_empty() {
+            frontmatter.push_str(&format!("description: {}\n", desc));
+        }
+    }
+
+    frontmatter.push_str("---\n\n");
+    format!("{}{}", frontmatter, command.content)
+}
+
+/// Write a command as a Windsurf workflow into `workflows_dir`
+pub fn write_command_file_windsurf(workflows_dir: &Path, command: &Command) -> Result<()> {
+    std::fs::create_dir_all(workflows_dir)?;
+
+    let file_path = workflows_dir.join(format!("{}.md", command.name));
+    crate::utils::backup::backup_file(&file_path)?;
+    let content = generate_workflow_markdown_windsurf(command);
+    std::fs::write(file_path, content)?;
+
+    Ok(())
+}
+
+/// Delete a command's Windsurf workflow from `workflows_dir`
+pub fn delete_command_file_windsurf(workflows_dir: &Path, command: &Command) -> Result<()> {
+    let file_path = workflows_dir.join(format!("{}.md", command.name));
+
+    if file_path.exists() {
+        std::fs::remove_file(&file_path)?;
+    }
+
+    Ok(())
+}
+
+/// Write a command to Windsurf's global workflows (~/.codeium/windsurf/global_workflows/)
+pub fn write_global_command_windsurf(command: &Command) -> Result<()> {
+    let paths = get_windsurf_paths()?;
+    write_command_file_windsurf(&paths.global_workflows_dir, command)
+}
+
+/// Delete a command from Windsurf's global workflows
+pub fn delete_global_command_windsurf(command: &Command) -> Result<()> {
+    let paths = get_windsurf_paths()?;
+    delete_command_file_windsurf(&paths.global_workflows_dir, command)
+}
+
+/// Write a command to a project's Windsurf workflows ({project}/.windsurf/workflows/)
+pub fn write_project_command_windsurf(project_path: &Path, command: &Command) -> Result<()> {
+    let workflows_dir = project_path.join(".windsurf").join("workflows");
+    write_command_file_windsurf(&workflows_dir, command)
+}
+
+/// Delete a command from a project's Windsurf workflows
+pub fn delete_project_command_windsurf(project_path: &Path, command: &Command) -> Result<()> {
+    let workflows_dir = project_path.join(".windsurf").join("workflows");
+    delete_command_file_windsurf(&workflows_dir, command)
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -576,6 +655,20 @@ mod tests {
         assert!(!file_path.exists());
     }
 
+    #[test]
+    fn test_project_command_layout_per_editor() {
+        let temp_dir = TempDir::new().unwrap();
+        let command = sample_command();
+        write_project_command(temp_dir.path(), &command).unwrap();
+        write_project_command_opencode(temp_dir.path(), &command).unwrap();
+
+        // Both editors keep commands flat; only the directory name differs
+        let root = temp_dir.path();
+        assert!(root.join(".claude/commands/test-command.md").is_file());
+        assert!(root.join(".opencode/command/test-command.md").is_file());
+        assert!(!root.join(".opencode/commands").exists());
+    }
+
     #[test]
     fn test_opencode_command_content_uses_correct_format() {
         let temp_dir = TempDir::new().unwrap();
@@ -592,4 +685,29 @@ mod tests {
         assert!(!content.contains("allowed-tools:"));
         assert!(!content.contains("argument-hint:"));
     }
+
+    // =========================================================================
+    // Windsurf workflow tests
+    // =========================================================================
+
+    #[test]
+    fn test_windsurf_workflow_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let command = sample_command();
+
+        write_project_command_windsurf(temp_dir.path(), &command).unwrap();
+        let file_path = temp_dir
+            .path()
+            .join(".windsurf")
+            .join("workflows")
+            .join("test-command.md");
+        let content = std::fs::read_to_string(&file_path).unwrap();
+        assert_eq!(
+            content,
+            "---\ndescription: A test slash command\n---\n\nExecute this task for the user."
+        );
+
+        delete_project_command_windsurf(temp_dir.path(), &command).unwrap();
+        assert!(!file_path.exists());
+    }
 }
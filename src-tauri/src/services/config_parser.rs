@@ -22,6 +22,8 @@ pub enum McpConfig {
         args: Vec<String>,
         #[serde(default)]
         env: HashMap<String, String>,
+        #[serde(default)]
+        disabled: bool,
     },
     Remote {
         #[serde(rename = "type")]
@@ -29,6 +31,8 @@ pub enum McpConfig {
         url: String,
         #[serde(default)]
         headers: HashMap<String, String>,
+        #[serde(default)]
+        disabled: bool,
     },
 }
 
@@ -41,11 +45,18 @@ pub struct ParsedMcp {
     pub url: Option<String>,
     pub headers: Option<HashMap<String, String>>,
     pub env: Option<HashMap<String, String>>,
+    /// Entry-level `"disabled": true`, separate from the project's disabled lists
+    pub disabled: bool,
 }
 
 pub fn parse_mcp_file(path: &Path) -> Result<Vec<ParsedMcp>> {
     let content = std::fs::read_to_string(path)?;
-    let config: McpConfigFile = serde_json::from_str(&content)?;
+    parse_mcp_json(&content)
+}
+
+/// Parse an `mcp.json` document, with or without the `mcpServers` wrapper
+pub fn parse_mcp_json(content: &str) -> Result<Vec<ParsedMcp>> {
+    let config: McpConfigFile = serde_json::from_str(content)?;
 
     let servers = match config {
         McpConfigFile::WithServers { mcp_servers } => mcp_servers,
@@ -56,7 +67,12 @@ pub fn parse_mcp_file(path: &Path) -> Result<Vec<ParsedMcp>> {
 
     for (name, config) in servers {
         let mcp = match config {
-            McpConfig::Stdio { command, args, env } => ParsedMcp {
+            McpConfig::Stdio {
+                command,
+                args,
+                env,
+                disabled,
+            } => ParsedMcp {
                 name,
                 mcp_type: "stdio".to_string(),
                 command: Some(command),
@@ -64,11 +80,13 @@ pub fn parse_mcp_file(path: &Path) -> Result<Vec<ParsedMcp>> {
                 url: None,
                 headers: None,
                 env: if env.is_empty() { None } else { Some(env) },
+                disabled,
             },
             McpConfig::Remote {
                 mcp_type,
                 url,
                 headers,
+                disabled,
             } => ParsedMcp {
                 name,
                 mcp_type: if mcp_type == "sse" {
@@ -85,6 +103,7 @@ pub fn parse_mcp_file(path: &Path) -> Result<Vec<ParsedMcp>> {
                     Some(headers)
                 },
                 env: None,
+                disabled,
             },
         };
         mcps.push(mcp);
@@ -130,6 +149,26 @@ mod tests {
         assert!(mcps[0].env.is_some());
     }
 
+    #[test]
+    fn test_parse_entry_level_disabled_flag() {
+        let json = r#"{
+            "mcpServers": {
+                "off": { "command": "npx", "disabled": true },
+                "remote-off": { "type": "http", "url": "https://example.com", "disabled": true },
+                "on": { "command": "npx" }
+            }
+        }"#;
+
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "{}", json).unwrap();
+
+        let mcps = parse_mcp_file(file.path()).unwrap();
+        let disabled = |name: &str| mcps.iter().find(|m| m.name == name).unwrap().disabled;
+        assert!(disabled("off"));
+        assert!(disabled("remote-off"));
+        assert!(!disabled("on"));
+    }
+
     #[test]
     fn test_parse_stdio_mcp_direct_format() {
         let json = r#"{
@@ -300,7 +339,9 @@ mod tests {
         let config: McpConfig = serde_json::from_str(json).unwrap();
 
         match config {
-            McpConfig::Stdio { command, args, env } => {
+            McpConfig::Stdio {
+                command, args, env, ..
+            } => {
                 assert_eq!(command, "test");
                 assert_eq!(args, vec!["arg1"]);
                 assert_eq!(env.get("KEY"), Some(&"value".to_string()));
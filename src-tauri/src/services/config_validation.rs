@@ -0,0 +1,437 @@
+//! Checks an editor's MCP config for anything a sync would drop
+//!
+//! The config writers only carry over the fields they understand, so a
+//! hand-edited entry that doesn't match the editor's schema is silently lost
+//! or rewritten on the next sync. Validation reports those spots up front.
+
+use crate::services::mcp_json_config::mcp_config_key;
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::path::Path;
+
+/// Something in a config file that won't survive a sync as written
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigWarning {
+    /// Dotted location in the file, e.g. `mcp.github.command`
+    pub location: String,
+    pub message: String,
+    pub severity: Severity,
+}
+
+/// Whether the editor itself rejects the entry (`Error`) or only a sync would
+/// lose part of it (`Warning`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// Top-level keys OpenCode's `opencode.json` schema defines
+const OPENCODE_TOP_LEVEL_KEYS: &[&str] = &[
+    "$schema",
+    "agent",
+    "autoshare",
+    "autoupdate",
+    "command",
+    "disabled_providers",
+    "enabled_providers",
+    "experimental",
+    "formatter",
+    "instructions",
+    "keybinds",
+    "layout",
+    "lsp",
+    "mcp",
+    "mode",
+    "model",
+    "permission",
+    "plugin",
+    "provider",
+    "share",
+    "small_model",
+    "snapshot",
+    "theme",
+    "tools",
+    "tui",
+    "username",
+    "watcher",
+];
+
+/// Parse `editor`'s config at `path` and report entries that don't fit its
+/// schema. Unparseable files are an error rather than a warning.
+pub fn validate_config(editor: &str, path: &Path) -> Result<Vec<ConfigWarning>> {
+    let content = std::fs::read_to_string(path)?;
+
+    let (key, root) = if editor == "codex" {
+        let doc: toml::Value = toml::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", path.display(), e))?;
+        ("mcp_servers", serde_json::to_value(doc)?)
+    } else {
+        let key = mcp_config_key(editor)
+            .ok_or_else(|| anyhow::anyhow!("Unknown editor type '{}'", editor))?;
+        let root: Value = serde_json::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", path.display(), e))?;
+        (key, root)
+    };
+
+    let mut warnings = Vec::new();
+    let Some(root) = root.as_object() else {
+        warn(&mut warnings, "", "Top level should be an object");
+        return Ok(warnings);
+    };
+
+    if let Some(known) = known_top_level_keys(editor, path) {
+        for name in root.keys().filter(|k| !known.contains(&k.as_str())) {
+            warn(&mut warnings, name, "Unknown top-level key");
+        }
+    }
+
+    match root.get(key) {
+        None => {}
+        Some(Value::Object(servers)) => {
+            for (name, entry) in servers {
+                let location = format!("{}.{}", key, name);
+                match entry.as_object() {
+                    Some(entry) if editor == "opencode" => {
+                        check_opencode_entry(&location, entry, &mut warnings)
+                    }
+                    Some(entry) => check_entry(editor, &location, entry, &mut warnings),
+                    None => warn(&mut warnings, &location, "MCP entry should be an object"),
+                }
+            }
+        }
+        Some(_) => warn(&mut warnings, key, "Should be an object of MCP servers"),
+    }
+
+    Ok(warnings)
+}
+
+/// Top-level keys for files that only hold MCP config. Shared settings files
+/// (`~/.claude.json`, Gemini's `settings.json`, Codex's `config.toml`) carry
+/// plenty of unrelated keys, so they aren't checked.
+fn known_top_level_keys(editor: &str, path: &Path) -> Option<&'static [&'static str]> {
+    match editor {
+        "claude_code" if path.file_name().is_some_and(|n| n == ".mcp.json") => {
+            Some(&["mcpServers"])
+        }
+        "cursor" | "windsurf" => Some(&["mcpServers"]),
+        "copilot" => Some(&["servers", "inputs"]),
+        "opencode" => Some(OPENCODE_TOP_LEVEL_KEYS),
+        _ => None,
+    }
+}
+
+/// Keys an editor reads a remote server's URL from
+fn url_keys(editor: &str) -> &'static [&'static str] {
+    match editor {
+        "windsurf" => &["serverUrl", "url"],
+        "gemini" => &["httpUrl", "url"],
+        _ => &["url"],
+    }
+}
+
+/// An `mcpServers`-style entry: `command`/`args`/`env` or a URL with headers
+fn check_entry(
+    editor: &str,
+    location: &str,
+    entry: &Map<String, Value>,
+    warnings: &mut Vec<ConfigWarning>,
+) {
+    let url_keys = url_keys(editor);
+    let headers_key = if editor == "codex" {
+        "http_headers"
+    } else {
+        "headers"
+    };
+
+    let has_command = check_string(location, entry, "command", warnings);
+    let mut has_url = false;
+    for key in url_keys {
+        has_url |= check_string(location, entry, key, warnings);
+    }
+    if !has_command && !has_url {
+        warn(
+            warnings,
+            location,
+            &format!("Needs `command` or `{}`", url_keys.join("` or `")),
+        );
+    }
+
+    check_string_array(location, entry, "args", warnings);
+    check_string_map(location, entry, "env", warnings);
+    check_string_map(location, entry, headers_key, warnings);
+
+    if editor == "claude_code" {
+        if let Some(t) = entry.get("type") {
+            let location = format!("{}.type", location);
+            match t.as_str() {
+                Some("stdio" | "sse" | "http") => {}
+                // This app's own transport; Claude Code can't connect to it
+                Some("ws") => error(
+                    warnings,
+                    &location,
+                    "Claude Code doesn't support ws; use stdio, sse or http",
+                ),
+                _ => warn(warnings, &location, "Should be one of stdio, sse or http"),
+            }
+        }
+    }
+}
+
+/// An OpenCode `mcp` entry: `type` local (command array) or remote (url)
+fn check_opencode_entry(
+    location: &str,
+    entry: &Map<String, Value>,
+    warnings: &mut Vec<ConfigWarning>,
+) {
+    match entry.get("type").and_then(|v| v.as_str()) {
+        Some("local") => match entry.get("command") {
+            Some(Value::Array(cmd)) if !cmd.is_empty() => {
+                check_string_array(location, entry, "command", warnings);
+            }
+            Some(_) => warn(
+                warnings,
+                &format!("{}.command", location),
+                "Should be a non-empty array of strings",
+            ),
+            None => warn(warnings, location, "Local MCP needs `command`"),
+        },
+        Some("remote") => {
+            if !check_string(location, entry, "url", warnings) {
+                warn(warnings, location, "Remote MCP needs `url`");
+            }
+        }
+        _ => warn(
+            warnings,
+            &format!("{}.type", location),
+            "Should be \"local\" or \"remote\"",
+        ),
+    }
+
+    check_string_map(location, entry, "environment", warnings);
+    check_string_map(location, entry, "headers", warnings);
+    if entry.get("enabled").is_some_and(|v| !v.is_boolean()) {
+        warn(
+            warnings,
+            &format!("{}.enabled", location),
+            "Should be true or false",
+        );
+    }
+}
+
+/// Whether `key` holds a string; warns when it's present but isn't one
+fn check_string(
+    location: &str,
+    entry: &Map<String, Value>,
+    key: &str,
+    warnings: &mut Vec<ConfigWarning>,
+) -> bool {
+    match entry.get(key) {
+        Some(Value::String(_)) => true,
+        Some(_) => {
+            warn(
+                warnings,
+                &format!("{}.{}", location, key),
+                "Should be a string",
+            );
+            false
+        }
+        None => false,
+    }
+}
+
+fn check_string_array(
+    location: &str,
+    entry: &Map<String, Value>,
+    key: &str,
+    warnings: &mut Vec<ConfigWarning>,
+) {
+    let Some(value) = entry.get(key) else {
+        return;
+    };
+    let valid = value
+        .as_array()
+        .is_some_and(|items| items.iter().all(Value::is_string));
+    if !valid {
+        warn(
+            warnings,
+            &format!("{}.{}", location, key),
+            "Should be an array of strings",
+        );
+    }
+}
+
+fn check_string_map(
+    location: &str,
+    entry: &Map<String, Value>,
+    key: &str,
+    warnings: &mut Vec<ConfigWarning>,
+) {
+    let Some(value) = entry.get(key) else {
+        return;
+    };
+    let valid = value
+        .as_object()
+        .is_some_and(|map| map.values().all(Value::is_string));
+    if !valid {
+        warn(
+            warnings,
+            &format!("{}.{}", location, key),
+            "Should be an object of string values",
+        );
+    }
+}
+
+fn warn(warnings: &mut Vec<ConfigWarning>, location: &str, message: &str) {
+    warnings.push(ConfigWarning {
+        location: location.to_string(),
+        message: message.to_string(),
+        severity: Severity::Warning,
+    });
+}
+
+fn error(warnings: &mut Vec<ConfigWarning>, location: &str, message: &str) {
+    warnings.push(ConfigWarning {
+        location: location.to_string(),
+        message: message.to_string(),
+        severity: Severity::Error,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn locations(warnings: &[ConfigWarning]) -> Vec<&str> {
+        warnings.iter().map(|w| w.location.as_str()).collect()
+    }
+
+    #[test]
+    fn test_validate_opencode_config_reports_dropped_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("opencode.json");
+        fs::write(
+            &path,
+            r#"{
+                "$schema": "https://opencode.ai/config.json",
+                "theme": "dark",
+                "mcps": {},
+                "mcp": {
+                    "ok": {"type": "local", "command": ["npx", "server"], "enabled": true},
+                    "no-command": {"type": "local"},
+                    "string-command": {"type": "local", "command": "npx server"},
+                    "stdio": {"type": "stdio", "command": ["npx"]},
+                    "remote": {"type": "remote", "url": "https://x", "headers": {"n": 1}}
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let warnings = validate_config("opencode", &path).unwrap();
+        let mut found = locations(&warnings);
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                "mcp.no-command",
+                "mcp.remote.headers",
+                "mcp.stdio.type",
+                "mcp.string-command.command",
+                "mcps",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_cursor_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("mcp.json");
+        fs::write(
+            &path,
+            r#"{"mcpServers": {
+                "local": {"command": "npx", "args": ["-y", "pkg"], "env": {"A": "1"}},
+                "remote": {"url": "https://example.com/mcp"},
+                "bad": {"args": "-y", "serverUrl": "https://example.com"}
+            }}"#,
+        )
+        .unwrap();
+
+        let warnings = validate_config("cursor", &path).unwrap();
+        let mut found = locations(&warnings);
+        found.sort();
+        assert_eq!(found, vec!["mcpServers.bad", "mcpServers.bad.args"]);
+
+        // serverUrl is Windsurf's key, so the same entry only misses its args there
+        let warnings = validate_config("windsurf", &path).unwrap();
+        assert_eq!(locations(&warnings), vec!["mcpServers.bad.args"]);
+
+        fs::write(&path, "{ not json").unwrap();
+        assert!(validate_config("cursor", &path).is_err());
+        assert!(validate_config("unknown", &path).is_err());
+    }
+
+    #[test]
+    fn test_validate_claude_code_rejects_ws() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(".mcp.json");
+        fs::write(
+            &path,
+            r#"{"mcpServers": {
+                "remote": {"type": "http", "url": "https://example.com/mcp"},
+                "socket": {"type": "ws", "url": "ws://localhost:3000"},
+                "odd": {"type": "grpc", "url": "https://example.com"}
+            }}"#,
+        )
+        .unwrap();
+
+        let warnings = validate_config("claude_code", &path).unwrap();
+        let severities: Vec<(&str, Severity)> = warnings
+            .iter()
+            .map(|w| (w.location.as_str(), w.severity))
+            .collect();
+        assert_eq!(
+            severities,
+            vec![
+                ("mcpServers.odd.type", Severity::Warning),
+                ("mcpServers.socket.type", Severity::Error),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_codex_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(
+            &path,
+            r#"
+model = "o3"
+
+[mcp_servers.local]
+command = "npx"
+args = ["-y", "pkg"]
+
+[mcp_servers.remote]
+url = "https://example.com/mcp"
+http_headers = { "X-Key" = 1 }
+
+[mcp_servers.empty]
+enabled = true
+"#,
+        )
+        .unwrap();
+
+        let warnings = validate_config("codex", &path).unwrap();
+        let mut found = locations(&warnings);
+        found.sort();
+        assert_eq!(
+            found,
+            vec!["mcp_servers.empty", "mcp_servers.remote.http_headers"]
+        );
+    }
+}
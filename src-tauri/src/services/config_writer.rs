@@ -1,167 +1,151 @@
+use crate::services::claude_json::{read_claude_json_at, update_claude_json_at};
+use crate::services::mcp_json_config::{
+    args_value, mcp_config_key, render_mcp_servers, render_servers, string_map_value, McpTuple,
+};
+use crate::services::pending_changes::{read_config_file, write_config_file, PendingChange};
 use crate::utils::paths::ClaudePathsInternal;
 use anyhow::Result;
 use serde_json::{json, Map, Value};
 use std::path::Path;
 
-use crate::utils::backup::backup_file as backup_config_file;
-
-type McpTuple = (
-    String,         // name
-    String,         // type
-    Option<String>, // command
-    Option<String>, // args (JSON)
-    Option<String>, // url
-    Option<String>, // headers (JSON)
-    Option<String>, // env (JSON)
-);
-
-pub fn generate_mcp_config(mcps: &[McpTuple]) -> Value {
-    let mut servers = Map::new();
-
-    for mcp in mcps {
-        let (name, mcp_type, command, args, url, headers, env) = mcp;
+/// Merge the variables of an MCP's `.env` file into its env JSON for the
+/// config writers. With `inline` the values are copied; otherwise each
+/// variable is written as a `${VAR}` reference so the secret stays in the
+/// environment. Explicit env entries win; an unreadable file is logged and
+/// leaves the env untouched.
+pub fn merge_env_file(
+    env_json: Option<String>,
+    env_file: Option<&Path>,
+    inline: bool,
+) -> Option<String> {
+    let Some(path) = env_file else {
+        return env_json;
+    };
+    let mut merged = match crate::services::mcp_client::load_env_file(path) {
+        Ok(vars) => vars,
+        Err(e) => {
+            log::warn!("[ConfigWriter] {}", e);
+            return env_json;
+        }
+    };
+    if !inline {
+        for (key, value) in merged.iter_mut() {
+            *value = format!("${{{}}}", key);
+        }
+    }
+    let explicit: std::collections::HashMap<String, String> = env_json
+        .as_deref()
+        .and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or_default();
+    merged.extend(explicit);
+    serde_json::to_string(&merged).ok()
+}
 
-        let config = match mcp_type.as_str() {
-            "stdio" => {
-                let mut obj = Map::new();
-                if let Some(cmd) = command {
-                    obj.insert("command".to_string(), json!(cmd));
-                }
-                if let Some(args_json) = args {
-                    if let Ok(args_val) = serde_json::from_str::<Vec<String>>(args_json) {
-                        obj.insert("args".to_string(), json!(args_val));
-                    }
-                }
-                if let Some(env_json) = env {
-                    if let Ok(env_val) = serde_json::from_str::<Map<String, Value>>(env_json) {
-                        obj.insert("env".to_string(), Value::Object(env_val));
-                    }
-                }
-                Value::Object(obj)
+/// One server as Claude Code writes it: stdio servers have no `type` per the
+/// official spec, remote ones carry `type` and `url`. `None` for transports
+/// Claude Code doesn't support.
+pub fn claude_server_entry(mcp: &McpTuple) -> Option<Value> {
+    let (_, mcp_type, command, args, url, headers, env) = mcp;
+    let mut obj = Map::new();
+    match mcp_type.as_str() {
+        "stdio" => {
+            if let Some(cmd) = command {
+                obj.insert("command".to_string(), json!(cmd));
             }
-            "sse" => {
-                let mut obj = Map::new();
-                obj.insert("type".to_string(), json!("sse"));
-                if let Some(u) = url {
-                    obj.insert("url".to_string(), json!(u));
-                }
-                // SSE supports headers per official spec
-                if let Some(headers_json) = headers {
-                    if let Ok(headers_val) =
-                        serde_json::from_str::<Map<String, Value>>(headers_json)
-                    {
-                        obj.insert("headers".to_string(), Value::Object(headers_val));
-                    }
-                }
-                Value::Object(obj)
+            if let Some(args) = args_value(args.as_deref()) {
+                obj.insert("args".to_string(), args);
             }
-            "http" => {
-                let mut obj = Map::new();
-                obj.insert("type".to_string(), json!("http"));
-                if let Some(u) = url {
-                    obj.insert("url".to_string(), json!(u));
-                }
-                if let Some(headers_json) = headers {
-                    if let Ok(headers_val) =
-                        serde_json::from_str::<Map<String, Value>>(headers_json)
-                    {
-                        obj.insert("headers".to_string(), Value::Object(headers_val));
-                    }
-                }
-                Value::Object(obj)
+            if let Some(env) = string_map_value(env.as_deref()) {
+                obj.insert("env".to_string(), env);
             }
-            "ws" => {
-                let mut obj = Map::new();
-                obj.insert("type".to_string(), json!("ws"));
-                if let Some(u) = url {
-                    obj.insert("url".to_string(), json!(u));
-                }
-                if let Some(headers_json) = headers {
-                    if let Ok(headers_val) =
-                        serde_json::from_str::<Map<String, Value>>(headers_json)
-                    {
-                        obj.insert("headers".to_string(), Value::Object(headers_val));
-                    }
-                }
-                Value::Object(obj)
+        }
+        "sse" | "http" => {
+            obj.insert("type".to_string(), json!(mcp_type));
+            if let Some(u) = url {
+                obj.insert("url".to_string(), json!(u));
             }
-            _ => continue,
-        };
-
-        servers.insert(name.clone(), config);
+            if let Some(headers) = string_map_value(headers.as_deref()) {
+                obj.insert("headers".to_string(), headers);
+            }
+        }
+        _ => return None,
     }
-
-    json!({ "mcpServers": servers })
+    Some(Value::Object(obj))
 }
 
-pub fn write_project_config(project_path: &Path, mcps: &[McpTuple]) -> Result<()> {
-    let config_path = project_path.join(".mcp.json");
-
-    // Read existing .mcp.json or create new
-    let mut existing: Value = if config_path.exists() {
-        let content = std::fs::read_to_string(&config_path)?;
-        serde_json::from_str(&content).map_err(|e| {
-            anyhow::anyhow!(
-                "Failed to parse existing .mcp.json at {}: {}. \
-                 Refusing to overwrite to prevent data loss.",
-                config_path.display(),
-                e
-            )
-        })?
-    } else {
-        json!({})
-    };
+pub fn generate_mcp_config(mcps: &[McpTuple]) -> Value {
+    json!({ "mcpServers": render_servers(mcps, claude_server_entry) })
+}
 
-    // Merge DB-managed mcpServers into existing config
-    // Skip overwrite when DB has no servers — preserves externally-managed .mcp.json
-    let mcp_config = generate_mcp_config(mcps);
-    if let Some(Value::Object(servers)) = mcp_config.get("mcpServers") {
-        if !servers.is_empty() {
-            existing["mcpServers"] = Value::Object(servers.clone());
-        }
+/// Write a project's `.mcp.json` with the enabled `mcps`, additionally keeping
+/// the `disabled` MCPs whose existing entry carries `"disabled": true`. Disabled
+/// MCPs are otherwise left out of the file, so this only preserves the
+/// entry-level flag for projects that already use it.
+pub fn write_project_config_with_disabled(
+    project_path: &Path,
+    mcps: &[McpTuple],
+    disabled: &[McpTuple],
+) -> Result<Option<PendingChange>> {
+    match render_project_config_with_disabled(project_path, mcps, disabled)? {
+        Some(content) => write_config_file(&project_path.join(".mcp.json"), &content),
+        None => Ok(None),
     }
+}
 
-    // Back up existing file before writing
-    backup_config_file(&config_path)?;
+/// The exact `.mcp.json` that [`write_project_config_with_disabled`] would
+/// write, merged into the project's current file, without writing it. `None`
+/// when the file would be left as is.
+pub fn render_project_config_with_disabled(
+    project_path: &Path,
+    mcps: &[McpTuple],
+    disabled: &[McpTuple],
+) -> Result<Option<String>> {
+    let config_path = project_path.join(".mcp.json");
+    let existing: Value = read_config_file(&config_path)?
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    let flagged: Vec<McpTuple> = disabled
+        .iter()
+        .filter(|mcp| existing["mcpServers"][&mcp.0]["disabled"].as_bool() == Some(true))
+        .cloned()
+        .collect();
+    let servers: Vec<McpTuple> = mcps.iter().chain(&flagged).cloned().collect();
+
+    render_mcp_servers(&config_path, "claude_code", ".mcp.json", &servers, |mcp| {
+        let mut entry = claude_server_entry(mcp)?;
+        if flagged.iter().any(|f| f.0 == mcp.0) {
+            entry["disabled"] = json!(true);
+        }
+        Some(entry)
+    })
+}
 
-    let content = serde_json::to_string_pretty(&existing)?;
-    std::fs::write(&config_path, content)?;
-    Ok(())
+pub fn write_global_config(
+    paths: &ClaudePathsInternal,
+    mcps: &[McpTuple],
+) -> Result<Option<PendingChange>> {
+    update_claude_json_at(&paths.claude_json, |claude_json| {
+        apply_global_config(claude_json, mcps);
+        Ok(())
+    })
 }
 
-pub fn write_global_config(paths: &ClaudePathsInternal, mcps: &[McpTuple]) -> Result<()> {
-    // Read existing ~/.claude.json or create new
-    let mut claude_json: Value = if paths.claude_json.exists() {
-        let content = std::fs::read_to_string(&paths.claude_json)?;
-        serde_json::from_str(&content).map_err(|e| {
-            anyhow::anyhow!(
-                "Failed to parse existing Claude config at {}: {}. \
-                 Refusing to overwrite to prevent data loss.",
-                paths.claude_json.display(),
-                e
-            )
-        })?
-    } else {
-        json!({})
-    };
+/// The exact `~/.claude.json` that [`write_global_config`] would write,
+/// without writing it
+pub fn render_global_config(paths: &ClaudePathsInternal, mcps: &[McpTuple]) -> Result<String> {
+    let mut claude_json = read_claude_json_at(&paths.claude_json)?;
+    apply_global_config(&mut claude_json, mcps);
+    Ok(serde_json::to_string_pretty(&claude_json)?)
+}
 
-    // Build mcpServers object
+fn apply_global_config(claude_json: &mut Value, mcps: &[McpTuple]) {
     // Skip overwrite when DB has no servers — preserves externally-managed config
-    let mcp_config = generate_mcp_config(mcps);
-    if let Some(Value::Object(servers)) = mcp_config.get("mcpServers") {
-        if !servers.is_empty() {
-            claude_json["mcpServers"] = Value::Object(servers.clone());
-        }
+    if mcps.is_empty() {
+        return;
+    }
+    if let Some(key) = mcp_config_key("claude_code") {
+        claude_json[key] = Value::Object(render_servers(mcps, claude_server_entry));
     }
-
-    // Back up the existing file before modifying it
-    backup_config_file(&paths.claude_json)?;
-
-    // Write back to ~/.claude.json
-    let content = serde_json::to_string_pretty(&claude_json)?;
-    std::fs::write(&paths.claude_json, content)?;
-
-    Ok(())
 }
 
 /// Tuple for MCP with enabled state for claude.json
@@ -181,23 +165,31 @@ pub fn write_project_to_claude_json(
     paths: &ClaudePathsInternal,
     project_path: &str,
     mcps: &[McpWithEnabledTuple],
-) -> Result<()> {
-    use crate::utils::paths::normalize_path;
-
-    // Read existing claude.json
-    let mut claude_json: Value = if paths.claude_json.exists() {
-        let content = std::fs::read_to_string(&paths.claude_json)?;
-        serde_json::from_str(&content).map_err(|e| {
-            anyhow::anyhow!(
-                "Failed to parse existing Claude config at {}: {}. \
-                 Refusing to overwrite to prevent data loss.",
-                paths.claude_json.display(),
-                e
-            )
-        })?
-    } else {
-        json!({})
-    };
+) -> Result<Option<PendingChange>> {
+    update_claude_json_at(&paths.claude_json, |claude_json| {
+        apply_project_to_claude_json(claude_json, project_path, mcps);
+        Ok(())
+    })
+}
+
+/// The ~/.claude.json `write_project_to_claude_json` would write, without
+/// touching disk
+pub fn render_project_to_claude_json(
+    paths: &ClaudePathsInternal,
+    project_path: &str,
+    mcps: &[McpWithEnabledTuple],
+) -> Result<String> {
+    let mut claude_json = read_claude_json_at(&paths.claude_json)?;
+    apply_project_to_claude_json(&mut claude_json, project_path, mcps);
+    Ok(serde_json::to_string_pretty(&claude_json)?)
+}
+
+fn apply_project_to_claude_json(
+    claude_json: &mut Value,
+    project_path: &str,
+    mcps: &[McpWithEnabledTuple],
+) {
+    use crate::utils::paths::{find_equivalent_path, normalize_path};
 
     // Ensure projects object exists
     if claude_json.get("projects").is_none() {
@@ -212,10 +204,8 @@ pub fn write_project_to_claude_json(
         .unwrap();
 
     // Find or create project entry (check both path formats)
-    let project_key = if projects.contains_key(project_path) {
-        project_path.to_string()
-    } else if projects.contains_key(&normalized_path) {
-        normalized_path.clone()
+    let project_key = if let Some(key) = find_equivalent_path(projects.keys(), project_path) {
+        key.clone()
     } else {
         // Create new project entry
         projects.insert(
@@ -244,60 +234,16 @@ pub fn write_project_to_claude_json(
     let mut disabled_mcps: Vec<String> = Vec::new();
 
     for (name, mcp_type, command, args, url, headers, env, is_enabled) in mcps {
-        let config = match mcp_type.as_str() {
-            "stdio" => {
-                // stdio servers don't have explicit type field per official spec
-                let mut obj = Map::new();
-                if let Some(cmd) = command {
-                    obj.insert("command".to_string(), json!(cmd));
-                }
-                if let Some(args_json) = args {
-                    if let Ok(args_val) = serde_json::from_str::<Vec<String>>(args_json) {
-                        obj.insert("args".to_string(), json!(args_val));
-                    }
-                }
-                if let Some(env_json) = env {
-                    if let Ok(env_val) = serde_json::from_str::<Map<String, Value>>(env_json) {
-                        obj.insert("env".to_string(), Value::Object(env_val));
-                    }
-                }
-                Some(Value::Object(obj))
-            }
-            "sse" => {
-                let mut obj = Map::new();
-                obj.insert("type".to_string(), json!("sse"));
-                if let Some(u) = url {
-                    obj.insert("url".to_string(), json!(u));
-                }
-                // SSE supports headers per official spec
-                if let Some(headers_json) = headers {
-                    if let Ok(headers_val) =
-                        serde_json::from_str::<Map<String, Value>>(headers_json)
-                    {
-                        obj.insert("headers".to_string(), Value::Object(headers_val));
-                    }
-                }
-                Some(Value::Object(obj))
-            }
-            "http" => {
-                let mut obj = Map::new();
-                obj.insert("type".to_string(), json!("http"));
-                if let Some(u) = url {
-                    obj.insert("url".to_string(), json!(u));
-                }
-                if let Some(headers_json) = headers {
-                    if let Ok(headers_val) =
-                        serde_json::from_str::<Map<String, Value>>(headers_json)
-                    {
-                        obj.insert("headers".to_string(), Value::Object(headers_val));
-                    }
-                }
-                Some(Value::Object(obj))
-            }
-            _ => None,
-        };
-
-        if let Some(cfg) = config {
+        let mcp = (
+            name.clone(),
+            mcp_type.clone(),
+            command.clone(),
+            args.clone(),
+            url.clone(),
+            headers.clone(),
+            env.clone(),
+        );
+        if let Some(cfg) = claude_server_entry(&mcp) {
             mcp_servers.insert(name.clone(), cfg);
             if !*is_enabled {
                 disabled_mcps.push(name.clone());
@@ -312,20 +258,12 @@ pub fn write_project_to_claude_json(
     if !disabled_mcps.is_empty() {
         project["disabledMcpServers"] = json!(disabled_mcps);
     }
-
-    // Back up the existing file before modifying it
-    backup_config_file(&paths.claude_json)?;
-
-    // Write back
-    let content = serde_json::to_string_pretty(&claude_json)?;
-    std::fs::write(&paths.claude_json, content)?;
-
-    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::backup::backup_file as backup_config_file;
     use insta::assert_json_snapshot;
     use tempfile::TempDir;
 
@@ -472,7 +410,7 @@ mod tests {
     }
 
     // =========================================================================
-    // write_project_config tests
+    // write_project_config_with_disabled tests
     // =========================================================================
 
     #[test]
@@ -480,7 +418,7 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let mcps = vec![sample_stdio_mcp()];
 
-        write_project_config(temp_dir.path(), &mcps).unwrap();
+        write_project_config_with_disabled(temp_dir.path(), &mcps, &[]).unwrap();
 
         // Per official spec, .mcp.json should be in project root, not .claude/
         let config_path = temp_dir.path().join(".mcp.json");
@@ -492,7 +430,7 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let mcps = vec![sample_stdio_mcp()];
 
-        write_project_config(temp_dir.path(), &mcps).unwrap();
+        write_project_config_with_disabled(temp_dir.path(), &mcps, &[]).unwrap();
 
         let config_path = temp_dir.path().join(".mcp.json");
         let content = std::fs::read_to_string(config_path).unwrap();
@@ -507,11 +445,11 @@ mod tests {
 
         // Write first config
         let mcps1 = vec![sample_stdio_mcp()];
-        write_project_config(temp_dir.path(), &mcps1).unwrap();
+        write_project_config_with_disabled(temp_dir.path(), &mcps1, &[]).unwrap();
 
         // Write second config
         let mcps2 = vec![sample_sse_mcp()];
-        write_project_config(temp_dir.path(), &mcps2).unwrap();
+        write_project_config_with_disabled(temp_dir.path(), &mcps2, &[]).unwrap();
 
         // Verify second config is written
         let config_path = temp_dir.path().join(".mcp.json");
@@ -543,7 +481,7 @@ mod tests {
 
         // Sync with new MCPs
         let mcps = vec![sample_stdio_mcp()];
-        write_project_config(temp_dir.path(), &mcps).unwrap();
+        write_project_config_with_disabled(temp_dir.path(), &mcps, &[]).unwrap();
 
         let content = std::fs::read_to_string(&config_path).unwrap();
         let parsed: Value = serde_json::from_str(&content).unwrap();
@@ -567,7 +505,7 @@ mod tests {
         std::fs::write(&config_path, r#"{"mcpServers": {}}"#).unwrap();
 
         let mcps = vec![sample_stdio_mcp()];
-        write_project_config(temp_dir.path(), &mcps).unwrap();
+        write_project_config_with_disabled(temp_dir.path(), &mcps, &[]).unwrap();
 
         assert!(backup_path.exists());
     }
@@ -581,7 +519,7 @@ mod tests {
         std::fs::write(&config_path, "not valid json {{{").unwrap();
 
         let mcps = vec![sample_stdio_mcp()];
-        let result = write_project_config(temp_dir.path(), &mcps);
+        let result = write_project_config_with_disabled(temp_dir.path(), &mcps, &[]);
 
         assert!(result.is_err());
         assert!(result
@@ -610,7 +548,7 @@ mod tests {
 
         // Sync with empty MCPs (the original bug scenario)
         let mcps: Vec<McpTuple> = vec![];
-        write_project_config(temp_dir.path(), &mcps).unwrap();
+        write_project_config_with_disabled(temp_dir.path(), &mcps, &[]).unwrap();
 
         let content = std::fs::read_to_string(&config_path).unwrap();
         let parsed: Value = serde_json::from_str(&content).unwrap();
@@ -1331,7 +1269,7 @@ mod tests {
     }
 
     // =========================================================================
-    // Additional coverage: write_project_config with multiple mcps
+    // Additional coverage: write_project_config_with_disabled with multiple mcps
     // =========================================================================
 
     #[test]
@@ -1339,7 +1277,7 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let mcps = vec![sample_stdio_mcp(), sample_sse_mcp(), sample_http_mcp()];
 
-        write_project_config(temp_dir.path(), &mcps).unwrap();
+        write_project_config_with_disabled(temp_dir.path(), &mcps, &[]).unwrap();
 
         let config_path = temp_dir.path().join(".mcp.json");
         let content = std::fs::read_to_string(config_path).unwrap();
@@ -1388,4 +1326,32 @@ mod tests {
             .to_string()
             .contains("Refusing to overwrite"));
     }
+
+    #[test]
+    fn test_merge_env_file_explicit_env_wins() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let env_file = temp_dir.path().join(".env");
+        std::fs::write(&env_file, "TOKEN=from-file\nREGION=eu\n").unwrap();
+        let env = Some(r#"{"TOKEN":"explicit"}"#.to_string());
+        let parse = |json: Option<String>| -> std::collections::HashMap<String, String> {
+            serde_json::from_str(&json.unwrap()).unwrap()
+        };
+
+        let merged = parse(merge_env_file(env.clone(), Some(&env_file), true));
+        assert_eq!(merged["TOKEN"], "explicit");
+        assert_eq!(merged["REGION"], "eu");
+
+        // Without inlining the file's variables become references
+        let merged = parse(merge_env_file(env, Some(&env_file), false));
+        assert_eq!(merged["TOKEN"], "explicit");
+        assert_eq!(merged["REGION"], "${REGION}");
+
+        // A missing file leaves the env as it was
+        let env = Some(r#"{"A":"1"}"#.to_string());
+        assert_eq!(
+            merge_env_file(env.clone(), Some(Path::new("/nonexistent/.env")), true),
+            env
+        );
+        assert_eq!(merge_env_file(None, None, true), None);
+    }
 }
@@ -0,0 +1,148 @@
+//! Network connectivity pre-check
+//!
+//! A quick probe run before bulk remote work (repo syncs, remote MCP health
+//! checks) so the UI can say "you appear offline" instead of surfacing a wall
+//! of timeouts. Any HTTP response counts as reachable; only connection
+//! failures and timeouts count as down.
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// GitHub endpoint probed by default
+pub const DEFAULT_GITHUB_ENDPOINT: &str = "https://api.github.com";
+
+/// Generic endpoint probed by default; answers with an empty 204
+pub const DEFAULT_PROBE_ENDPOINT: &str = "https://connectivitycheck.gstatic.com/generate_204";
+
+/// How long each probe may take
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+const USER_AGENT: &str = "claude-code-tool-manager/1.0";
+
+/// Result of a connectivity check
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityStatus {
+    /// False when the check is disabled; the other fields then assume online
+    pub checked: bool,
+    pub online: bool,
+    pub github_reachable: bool,
+    /// Fastest successful probe
+    pub latency_ms: Option<u64>,
+}
+
+impl ConnectivityStatus {
+    /// Status reported when the user has turned the check off
+    pub fn unchecked() -> Self {
+        Self {
+            checked: false,
+            online: true,
+            github_reachable: true,
+            latency_ms: None,
+        }
+    }
+}
+
+/// Time a HEAD request to `url`, or `None` if it never got a response
+async fn probe(client: &Client, url: &str) -> Option<Duration> {
+    let started = Instant::now();
+    match client.head(url).send().await {
+        Ok(_) => Some(started.elapsed()),
+        Err(e) => {
+            log::debug!("[Connectivity] {} unreachable: {}", url, e);
+            None
+        }
+    }
+}
+
+/// Probe both endpoints concurrently. Online means either answered.
+pub async fn check_connectivity(github_url: &str, probe_url: &str) -> ConnectivityStatus {
+    let client = match Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(PROBE_TIMEOUT)
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            log::warn!("[Connectivity] Failed to build HTTP client: {}", e);
+            return ConnectivityStatus {
+                checked: true,
+                online: false,
+                github_reachable: false,
+                latency_ms: None,
+            };
+        }
+    };
+
+    let (github, generic) = tokio::join!(probe(&client, github_url), probe(&client, probe_url));
+    ConnectivityStatus {
+        checked: true,
+        online: github.is_some() || generic.is_some(),
+        github_reachable: github.is_some(),
+        latency_ms: github
+            .into_iter()
+            .chain(generic)
+            .min()
+            .map(|d| d.as_millis() as u64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serve `204 No Content` to every connection and return its URL
+    async fn serve_204() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let _ = socket.read(&mut buf).await;
+                let _ = socket
+                    .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n")
+                    .await;
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    /// A local URL nothing is listening on
+    async fn closed_url() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_check_connectivity_online() {
+        let url = serve_204().await;
+        let status = check_connectivity(&url, &url).await;
+        assert!(status.checked);
+        assert!(status.online);
+        assert!(status.github_reachable);
+        assert!(status.latency_ms.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_check_connectivity_github_down() {
+        let up = serve_204().await;
+        let down = closed_url().await;
+        let status = check_connectivity(&down, &up).await;
+        assert!(status.online);
+        assert!(!status.github_reachable);
+    }
+
+    #[tokio::test]
+    async fn test_check_connectivity_offline() {
+        let down = closed_url().await;
+        let status = check_connectivity(&down, &down).await;
+        assert!(!status.online);
+        assert!(!status.github_reachable);
+        assert_eq!(status.latency_ms, None);
+    }
+}
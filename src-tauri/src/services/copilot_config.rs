@@ -1,3 +1,6 @@
+use crate::services::mcp_json_config::{
+    args_value, backup_config_file, render_mcp_servers, string_map_value, write_mcp_servers,
+};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
@@ -163,132 +166,61 @@ fn parse_mcp_entry(name: &str, config: &Value) -> Result<ParsedCopilotMcp> {
     }
 }
 
-/// MCP tuple for writing (same format used by other editors)
-pub type McpTuple = (
-    String,         // name
-    String,         // type (stdio, sse, http)
-    Option<String>, // command
-    Option<String>, // args (JSON)
-    Option<String>, // url
-    Option<String>, // headers (JSON)
-    Option<String>, // env (JSON)
-);
-
-/// Create a backup of the config file before modifying it
-fn backup_config_file(path: &Path) -> Result<()> {
-    if !path.exists() {
-        return Ok(());
-    }
-
-    let backup_path = path.with_extension("json.bak");
-    std::fs::copy(path, &backup_path).map_err(|e| {
-        anyhow::anyhow!(
-            "Failed to create backup of {} before writing: {}",
-            path.display(),
-            e
-        )
-    })?;
-
-    Ok(())
-}
+pub use crate::services::mcp_json_config::McpTuple;
 
 /// Write MCP servers to Copilot mcp-config.json, preserving existing content
 pub fn write_copilot_config(path: &Path, mcps: &[McpTuple]) -> Result<()> {
-    // Read existing config or create new
-    let mut config: CopilotMcpConfig = if path.exists() {
-        let content = std::fs::read_to_string(path)?;
-        serde_json::from_str(&content).map_err(|e| {
-            anyhow::anyhow!(
-                "Failed to parse existing Copilot config at {}: {}. \
-                 Refusing to overwrite to prevent data loss.",
-                path.display(),
-                e
-            )
-        })?
-    } else {
-        CopilotMcpConfig::default()
-    };
-
-    // Skip overwrite when DB has no MCPs — preserves externally-managed configs
-    if mcps.is_empty() {
-        return Ok(());
-    }
-
-    // Back up the existing file before modifying it
-    backup_config_file(path)?;
-
-    // Clear existing servers (we'll rebuild them)
-    config.servers.clear();
-
-    // Add each MCP
-    for mcp in mcps {
-        let (name, mcp_type, command, args, url, headers, env) = mcp;
-
-        let mut server_obj = Map::new();
+    write_mcp_servers(
+        path,
+        "copilot",
+        "Copilot config",
+        mcps,
+        copilot_server_entry,
+    )
+}
 
-        match mcp_type.as_str() {
-            "stdio" => {
-                if let Some(cmd) = command {
-                    server_obj.insert("command".to_string(), Value::String(cmd.clone()));
-                }
+/// The content [`write_copilot_config`] would write; `None` leaves the file as is
+pub fn render_copilot_config(path: &Path, mcps: &[McpTuple]) -> Result<Option<String>> {
+    render_mcp_servers(
+        path,
+        "copilot",
+        "Copilot config",
+        mcps,
+        copilot_server_entry,
+    )
+}
 
-                if let Some(args_json) = args {
-                    if let Ok(args_vec) = serde_json::from_str::<Vec<String>>(args_json) {
-                        server_obj.insert(
-                            "args".to_string(),
-                            Value::Array(args_vec.into_iter().map(Value::String).collect()),
-                        );
-                    }
-                }
+/// Copilot entry for one MCP (headers go under `requestInit`)
+fn copilot_server_entry(mcp: &McpTuple) -> Option<Value> {
+    let (_, mcp_type, command, args, url, headers, env) = mcp;
+    let mut server_obj = Map::new();
 
-                if let Some(env_json) = env {
-                    if let Ok(env_map) = serde_json::from_str::<HashMap<String, String>>(env_json) {
-                        let env_obj: Map<String, Value> = env_map
-                            .into_iter()
-                            .map(|(k, v)| (k, Value::String(v)))
-                            .collect();
-                        server_obj.insert("env".to_string(), Value::Object(env_obj));
-                    }
-                }
+    match mcp_type.as_str() {
+        "stdio" => {
+            if let Some(cmd) = command {
+                server_obj.insert("command".to_string(), Value::String(cmd.clone()));
             }
-            "http" | "sse" => {
-                if let Some(u) = url {
-                    server_obj.insert("url".to_string(), Value::String(u.clone()));
-                }
-
-                if let Some(headers_json) = headers {
-                    if let Ok(headers_map) =
-                        serde_json::from_str::<HashMap<String, String>>(headers_json)
-                    {
-                        let headers_obj: Map<String, Value> = headers_map
-                            .into_iter()
-                            .map(|(k, v)| (k, Value::String(v)))
-                            .collect();
-
-                        let mut request_init = Map::new();
-                        request_init.insert("headers".to_string(), Value::Object(headers_obj));
-                        server_obj.insert("requestInit".to_string(), Value::Object(request_init));
-                    }
-                }
+            if let Some(args) = args_value(args.as_deref()) {
+                server_obj.insert("args".to_string(), args);
+            }
+            if let Some(env) = string_map_value(env.as_deref()) {
+                server_obj.insert("env".to_string(), env);
             }
-            _ => continue,
         }
-
-        config
-            .servers
-            .insert(name.clone(), Value::Object(server_obj));
-    }
-
-    // Ensure parent directory exists
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
+        "http" | "sse" => {
+            if let Some(u) = url {
+                server_obj.insert("url".to_string(), Value::String(u.clone()));
+            }
+            if let Some(headers) = string_map_value(headers.as_deref()) {
+                let mut request_init = Map::new();
+                request_init.insert("headers".to_string(), headers);
+                server_obj.insert("requestInit".to_string(), Value::Object(request_init));
+            }
+        }
+        _ => return None,
     }
 
-    // Write back with pretty formatting
-    let json = serde_json::to_string_pretty(&config)?;
-    std::fs::write(path, json)?;
-
-    Ok(())
+    Some(Value::Object(server_obj))
 }
 
 /// Add a single MCP to Copilot config
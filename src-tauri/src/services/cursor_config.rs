@@ -1,3 +1,6 @@
+use crate::services::mcp_json_config::{
+    args_value, backup_config_file, render_mcp_servers, string_map_value, write_mcp_servers,
+};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
@@ -143,130 +146,47 @@ fn parse_mcp_entry(name: &str, config: &Value) -> Result<ParsedCursorMcp> {
     }
 }
 
-/// MCP tuple for writing (same format used by other editors)
-pub type McpTuple = (
-    String,         // name
-    String,         // type (stdio, sse, http)
-    Option<String>, // command
-    Option<String>, // args (JSON)
-    Option<String>, // url
-    Option<String>, // headers (JSON)
-    Option<String>, // env (JSON)
-);
-
-/// Create a backup of the config file before modifying it
-fn backup_config_file(path: &Path) -> Result<()> {
-    if !path.exists() {
-        return Ok(());
-    }
-
-    let backup_path = path.with_extension("json.bak");
-    std::fs::copy(path, &backup_path).map_err(|e| {
-        anyhow::anyhow!(
-            "Failed to create backup of {} before writing: {}",
-            path.display(),
-            e
-        )
-    })?;
-
-    Ok(())
-}
+pub use crate::services::mcp_json_config::McpTuple;
 
 /// Write MCP servers to Cursor mcp.json, preserving existing content
 pub fn write_cursor_config(path: &Path, mcps: &[McpTuple]) -> Result<()> {
-    // Read existing config or create new
-    let mut config: CursorMcpConfig = if path.exists() {
-        let content = std::fs::read_to_string(path)?;
-        serde_json::from_str(&content).map_err(|e| {
-            anyhow::anyhow!(
-                "Failed to parse existing Cursor config at {}: {}. \
-                 Refusing to overwrite to prevent data loss.",
-                path.display(),
-                e
-            )
-        })?
-    } else {
-        CursorMcpConfig::default()
-    };
-
-    // Skip overwrite when DB has no MCPs — preserves externally-managed configs
-    if mcps.is_empty() {
-        return Ok(());
-    }
-
-    // Back up the existing file before modifying it
-    backup_config_file(path)?;
-
-    // Clear existing servers (we'll rebuild them)
-    config.mcp_servers.clear();
-
-    // Add each MCP
-    for mcp in mcps {
-        let (name, mcp_type, command, args, url, headers, env) = mcp;
-
-        let mut server_obj = Map::new();
+    write_mcp_servers(path, "cursor", "Cursor config", mcps, cursor_server_entry)
+}
 
-        match mcp_type.as_str() {
-            "stdio" => {
-                if let Some(cmd) = command {
-                    server_obj.insert("command".to_string(), Value::String(cmd.clone()));
-                }
+/// The content [`write_cursor_config`] would write; `None` leaves the file as is
+pub fn render_cursor_config(path: &Path, mcps: &[McpTuple]) -> Result<Option<String>> {
+    render_mcp_servers(path, "cursor", "Cursor config", mcps, cursor_server_entry)
+}
 
-                if let Some(args_json) = args {
-                    if let Ok(args_vec) = serde_json::from_str::<Vec<String>>(args_json) {
-                        server_obj.insert(
-                            "args".to_string(),
-                            Value::Array(args_vec.into_iter().map(Value::String).collect()),
-                        );
-                    }
-                }
+/// Cursor entry for one MCP (uses direct headers like Claude Code)
+fn cursor_server_entry(mcp: &McpTuple) -> Option<Value> {
+    let (_, mcp_type, command, args, url, headers, env) = mcp;
+    let mut server_obj = Map::new();
 
-                if let Some(env_json) = env {
-                    if let Ok(env_map) = serde_json::from_str::<HashMap<String, String>>(env_json) {
-                        let env_obj: Map<String, Value> = env_map
-                            .into_iter()
-                            .map(|(k, v)| (k, Value::String(v)))
-                            .collect();
-                        server_obj.insert("env".to_string(), Value::Object(env_obj));
-                    }
-                }
+    match mcp_type.as_str() {
+        "stdio" => {
+            if let Some(cmd) = command {
+                server_obj.insert("command".to_string(), Value::String(cmd.clone()));
             }
-            "http" | "sse" => {
-                if let Some(u) = url {
-                    server_obj.insert("url".to_string(), Value::String(u.clone()));
-                }
-
-                // Cursor uses direct headers (like Claude Code)
-                if let Some(headers_json) = headers {
-                    if let Ok(headers_map) =
-                        serde_json::from_str::<HashMap<String, String>>(headers_json)
-                    {
-                        let headers_obj: Map<String, Value> = headers_map
-                            .into_iter()
-                            .map(|(k, v)| (k, Value::String(v)))
-                            .collect();
-                        server_obj.insert("headers".to_string(), Value::Object(headers_obj));
-                    }
-                }
+            if let Some(args) = args_value(args.as_deref()) {
+                server_obj.insert("args".to_string(), args);
+            }
+            if let Some(env) = string_map_value(env.as_deref()) {
+                server_obj.insert("env".to_string(), env);
             }
-            _ => continue,
         }
-
-        config
-            .mcp_servers
-            .insert(name.clone(), Value::Object(server_obj));
-    }
-
-    // Ensure parent directory exists
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
+        "http" | "sse" => {
+            if let Some(u) = url {
+                server_obj.insert("url".to_string(), Value::String(u.clone()));
+            }
+            if let Some(headers) = string_map_value(headers.as_deref()) {
+                server_obj.insert("headers".to_string(), headers);
+            }
+        }
+        _ => return None,
     }
 
-    // Write back with pretty formatting
-    let json = serde_json::to_string_pretty(&config)?;
-    std::fs::write(path, json)?;
-
-    Ok(())
+    Some(Value::Object(server_obj))
 }
 
 /// Add a single MCP to Cursor config
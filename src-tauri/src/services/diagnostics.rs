@@ -0,0 +1,401 @@
+//! Diagnostics bundle
+//!
+//! Builds a zip users can attach to bug reports. Everything that goes into the
+//! bundle is passed through the secret redaction below first, so API keys in
+//! MCP env vars, headers or args never leave the machine.
+
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+use std::io::Write;
+use std::path::Path;
+use std::sync::RwLock;
+use toml_edit::visit_mut::{self, VisitMut};
+use toml_edit::{DocumentMut, Formatted, Item, KeyMut};
+use zip::write::SimpleFileOptions;
+
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Key pattern used until the user configures their own
+pub const DEFAULT_SECRET_KEY_PATTERN: &str =
+    r"(?i)token|secret|password|passwd|api[_-]?key|authorization|credential|private[_-]?key|cookie";
+
+/// Header names that are secret regardless of the configured pattern
+const ALWAYS_SECRET_HEADERS: &[&str] = &["authorization", "x-api-key", "cookie"];
+
+/// Object keys whose values are all treated as secrets (MCP env vars and
+/// headers, including Codex's `http_headers`)
+const SECRET_MAPS: &[&str] = &["env", "headers", "http_headers"];
+
+static SECRET_KEY_PATTERN: Lazy<RwLock<Regex>> =
+    Lazy::new(|| RwLock::new(Regex::new(DEFAULT_SECRET_KEY_PATTERN).unwrap()));
+
+/// Well-known token formats (OpenAI/Anthropic, GitHub, Slack, AWS)
+static TOKEN_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(?:sk-[a-z0-9_-]{16,}|gh[pousr]_[a-z0-9]{20,}|github_pat_[a-z0-9_]{20,}|xox[abprs]-[a-z0-9-]{10,}|AKIA[0-9A-Z]{16})\b",
+    )
+    .unwrap()
+});
+
+/// `key=value` / `key: value` assignments and bearer tokens in free text
+static ASSIGNMENT_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"(?i)((?:bearer\s+)|(?:[a-z0-9_-]*(?:token|secret|password|passwd|api[_-]?key|authorization)[a-z0-9_-]*["']?\s*[:=]\s*["']?(?:bearer\s+)?))[^\s"',;&]+"#,
+    )
+    .unwrap()
+});
+
+/// Replace the secret key pattern; `None` or a blank pattern restores the default
+pub fn set_secret_key_pattern(pattern: Option<&str>) -> Result<()> {
+    let pattern = pattern
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .unwrap_or(DEFAULT_SECRET_KEY_PATTERN);
+    let regex = Regex::new(pattern).map_err(|e| anyhow!("Invalid secret key pattern: {}", e))?;
+    *SECRET_KEY_PATTERN
+        .write()
+        .map_err(|e| anyhow!("Lock error: {}", e))? = regex;
+    Ok(())
+}
+
+/// Whether an object key or header name names a credential
+pub fn is_secret_key(key: &str) -> bool {
+    if ALWAYS_SECRET_HEADERS
+        .iter()
+        .any(|h| key.eq_ignore_ascii_case(h))
+    {
+        return true;
+    }
+    SECRET_KEY_PATTERN
+        .read()
+        .map(|pattern| pattern.is_match(key))
+        .unwrap_or(true)
+}
+
+/// Whether a string value looks like it contains a credential
+pub fn looks_like_secret(value: &str) -> bool {
+    TOKEN_PATTERN.is_match(value) || ASSIGNMENT_PATTERN.is_match(value)
+}
+
+/// Replace credentials in free text (log lines, unparsable configs, command lines)
+pub fn redact_text(text: &str) -> String {
+    let text = TOKEN_PATTERN.replace_all(text, REDACTED);
+    ASSIGNMENT_PATTERN
+        .replace_all(&text, format!("${{1}}{}", REDACTED))
+        .into_owned()
+}
+
+/// Redact a JSON config in place: every value under `env`/`headers`, every
+/// string under a secret-looking key, and token-shaped substrings elsewhere.
+pub fn redact_json(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                if SECRET_MAPS.contains(&key.as_str()) && child.is_object() {
+                    redact_all_strings(child);
+                } else if is_secret_key(key) && !child.is_object() && !child.is_array() {
+                    *child = Value::String(REDACTED.to_string());
+                } else {
+                    redact_json(child);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_json),
+        Value::String(s) if looks_like_secret(s) => *s = redact_text(s),
+        _ => {}
+    }
+}
+
+fn redact_all_strings(value: &mut Value) {
+    match value {
+        Value::String(s) => *s = REDACTED.to_string(),
+        Value::Object(map) => map.values_mut().for_each(redact_all_strings),
+        Value::Array(items) => items.iter_mut().for_each(redact_all_strings),
+        _ => {}
+    }
+}
+
+/// Redact a TOML config (Codex `config.toml`) in place with the same rules as
+/// [`redact_json`], keeping its formatting
+pub fn redact_toml(doc: &mut DocumentMut) {
+    TomlRedactor { mask_all: false }.visit_document_mut(doc);
+}
+
+struct TomlRedactor {
+    /// Inside an `env`/`headers` table, where every string is a secret
+    mask_all: bool,
+}
+
+impl VisitMut for TomlRedactor {
+    fn visit_table_like_kv_mut(&mut self, key: KeyMut<'_>, node: &mut Item) {
+        if SECRET_MAPS.contains(&key.get()) && node.is_table_like() {
+            let outer = std::mem::replace(&mut self.mask_all, true);
+            self.visit_item_mut(node);
+            self.mask_all = outer;
+        } else if is_secret_key(key.get())
+            && node
+                .as_value()
+                .is_some_and(|v| !v.is_array() && !v.is_inline_table())
+        {
+            if let Some(value) = node.as_value_mut() {
+                let decor = value.decor().clone();
+                *value = REDACTED.into();
+                *value.decor_mut() = decor;
+            }
+        } else {
+            visit_mut::visit_table_like_kv_mut(self, key, node);
+        }
+    }
+
+    fn visit_string_mut(&mut self, node: &mut Formatted<String>) {
+        let redacted = if self.mask_all {
+            REDACTED.to_string()
+        } else if looks_like_secret(node.value()) {
+            redact_text(node.value())
+        } else {
+            return;
+        };
+        let decor = node.decor().clone();
+        *node = Formatted::new(redacted);
+        *node.decor_mut() = decor;
+    }
+}
+
+/// Redact a config file's contents, parsing it as JSON or TOML when possible
+pub fn redact_config(contents: &str) -> String {
+    if let Ok(mut json) = serde_json::from_str::<Value>(contents) {
+        redact_json(&mut json);
+        return serde_json::to_string_pretty(&json).unwrap_or_else(|_| redact_text(contents));
+    }
+    match contents.parse::<DocumentMut>() {
+        Ok(mut doc) => {
+            redact_toml(&mut doc);
+            doc.to_string()
+        }
+        Err(_) => redact_text(contents),
+    }
+}
+
+/// A header value as it may appear in the debug log
+pub fn redact_header_value<'a>(name: &str, value: &'a str) -> &'a str {
+    if is_secret_key(name) {
+        REDACTED
+    } else {
+        value
+    }
+}
+
+/// Redact an MCP request/response body for the debug log (JSON, SSE `data:`
+/// lines, or plain text)
+pub fn redact_body(body: &str) -> String {
+    if let Ok(json) = serde_json::from_str::<Value>(body) {
+        return redact_value(&json);
+    }
+    body.lines()
+        .map(|line| match line.strip_prefix("data:") {
+            Some(data) => match serde_json::from_str::<Value>(data.trim_start()) {
+                Ok(json) => format!("data: {}", redact_value(&json)),
+                Err(_) => redact_text(line),
+            },
+            None => redact_text(line),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Redacted body cut to at most `max_chars` characters
+pub fn body_snippet(body: &str, max_chars: usize) -> String {
+    let redacted = redact_body(body);
+    match redacted.char_indices().nth(max_chars) {
+        Some((end, _)) => redacted[..end].to_string(),
+        None => redacted,
+    }
+}
+
+/// Redacted JSON value on one line (e.g. tool call arguments)
+pub fn redact_value(value: &Value) -> String {
+    let mut value = value.clone();
+    redact_json(&mut value);
+    value.to_string()
+}
+
+/// The last `max_lines` lines of `text`
+pub fn tail_lines(text: &str, max_lines: usize) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines.len().saturating_sub(max_lines);
+    lines[start..].join("\n")
+}
+
+/// Write `entries` (archive name, contents) into a new zip at `dest`
+pub fn write_bundle(dest: &Path, entries: &[(String, String)]) -> Result<()> {
+    let file = std::fs::File::create(dest)?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (name, contents) in entries {
+        zip.start_file(name.as_str(), options)?;
+        zip.write_all(contents.as_bytes())?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Read;
+
+    #[test]
+    fn test_redact_json_masks_env_headers_and_secret_keys() {
+        let mut config = json!({
+            "mcpServers": {
+                "github": {
+                    "command": "npx",
+                    "args": ["-y", "server", "--token=abc123"],
+                    "env": {"GITHUB_TOKEN": "ghp_aaaaaaaaaaaaaaaaaaaaaaaa", "LOG_LEVEL": "debug"},
+                },
+                "remote": {
+                    "url": "https://example.com/mcp",
+                    "headers": {"Authorization": "Bearer xyz"},
+                }
+            },
+            "primaryApiKey": "sk-ant-REDACTED",
+            "theme": "dark"
+        });
+
+        redact_json(&mut config);
+
+        let github = &config["mcpServers"]["github"];
+        assert_eq!(github["env"]["GITHUB_TOKEN"], REDACTED);
+        assert_eq!(github["env"]["LOG_LEVEL"], REDACTED);
+        assert_eq!(github["args"][2], format!("--token={}", REDACTED));
+        assert_eq!(github["args"][1], "server");
+        assert_eq!(
+            config["mcpServers"]["remote"]["headers"]["Authorization"],
+            REDACTED
+        );
+        assert_eq!(
+            config["mcpServers"]["remote"]["url"],
+            "https://example.com/mcp"
+        );
+        assert_eq!(config["primaryApiKey"], REDACTED);
+        assert_eq!(config["theme"], "dark");
+    }
+
+    #[test]
+    fn test_redact_config_masks_codex_toml_by_structure() {
+        let config = r#"model = "o3"
+
+[mcp_servers.github]
+command = "npx"
+args = ["-y", "server", "--token=abc123"]
+
+[mcp_servers.github.env]
+GITHUB_PAT = "plain-looking-value"
+LOG_LEVEL = "debug"
+
+[mcp_servers.remote]
+url = "https://example.com/mcp"
+http_headers = { "X-Team" = "team-secret-value" }
+bearer_token = "opaque"
+"#;
+
+        let redacted = redact_config(config);
+        let doc: toml::Table = toml::from_str(&redacted).unwrap();
+        let servers = doc["mcp_servers"].as_table().unwrap();
+        let github = servers["github"].as_table().unwrap();
+        assert_eq!(github["env"]["GITHUB_PAT"].as_str(), Some(REDACTED));
+        assert_eq!(github["env"]["LOG_LEVEL"].as_str(), Some(REDACTED));
+        assert_eq!(
+            github["args"][2].as_str(),
+            Some(format!("--token={}", REDACTED).as_str())
+        );
+        let remote = servers["remote"].as_table().unwrap();
+        assert_eq!(remote["http_headers"]["X-Team"].as_str(), Some(REDACTED));
+        assert_eq!(remote["bearer_token"].as_str(), Some(REDACTED));
+        assert_eq!(remote["url"].as_str(), Some("https://example.com/mcp"));
+        assert_eq!(doc["model"].as_str(), Some("o3"));
+        // Formatting survives
+        assert!(redacted.contains("[mcp_servers.github.env]\n"));
+    }
+
+    #[test]
+    fn test_redact_text_masks_tokens_in_free_text() {
+        let log = "connecting with Authorization: Bearer abc.def\napi_key=hunter2 and sk-proj-abcdefghijklmnopqrstuv\nplain line";
+        let redacted = redact_text(log);
+        assert!(!redacted.contains("abc.def"));
+        assert!(!redacted.contains("hunter2"));
+        assert!(!redacted.contains("sk-proj-abcdefghijklmnopqrstuv"));
+        assert!(redacted.contains("plain line"));
+    }
+
+    #[test]
+    fn test_tail_lines() {
+        assert_eq!(tail_lines("a\nb\nc", 2), "b\nc");
+        assert_eq!(tail_lines("a", 5), "a");
+    }
+
+    #[test]
+    fn test_write_bundle_round_trip() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dest = temp_dir.path().join("bundle.zip");
+        write_bundle(
+            &dest,
+            &[
+                ("info.json".to_string(), "{}".to_string()),
+                ("configs/claude.json".to_string(), "{\"a\":1}".to_string()),
+            ],
+        )
+        .unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&dest).unwrap()).unwrap();
+        assert_eq!(archive.len(), 2);
+        let mut contents = String::new();
+        archive
+            .by_name("configs/claude.json")
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents, "{\"a\":1}");
+    }
+
+    #[test]
+    fn test_redact_body_masks_json_sse_and_text() {
+        let body = json!({
+            "method": "tools/call",
+            "params": {
+                "arguments": {"apiKey": "sk-live", "region": "eu"},
+                "headers": {"Authorization": "Bearer y"}
+            }
+        })
+        .to_string();
+        let json: Value = serde_json::from_str(&redact_body(&body)).unwrap();
+        assert_eq!(json["params"]["arguments"]["apiKey"], REDACTED);
+        assert_eq!(json["params"]["arguments"]["region"], "eu");
+        assert_eq!(json["params"]["headers"]["Authorization"], REDACTED);
+        assert_eq!(json["method"], "tools/call");
+
+        let sse = redact_body("event: message\ndata: {\"result\":{\"access_token\":\"abc\"}}");
+        assert!(sse.contains("event: message") && !sse.contains("abc"));
+        assert!(!redact_body("upstream said: Authorization: Bearer abc.def").contains("abc.def"));
+        assert_eq!(body_snippet("héllo world", 2), "hé");
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_set_secret_key_pattern() {
+        assert!(set_secret_key_pattern(Some("(unclosed")).is_err());
+        // Extend rather than replace the default so tests running alongside
+        // still see every usual secret masked
+        let pattern = format!("{}|^x-tenant$", DEFAULT_SECRET_KEY_PATTERN);
+        set_secret_key_pattern(Some(&pattern)).unwrap();
+        assert!(is_secret_key("X-Tenant"));
+        assert_eq!(redact_header_value("X-Tenant", "acme"), REDACTED);
+        set_secret_key_pattern(None).unwrap();
+        assert!(!is_secret_key("X-Tenant"));
+        assert!(is_secret_key("Cookie") && is_secret_key("GITHUB_TOKEN"));
+        assert_eq!(redact_header_value("Accept", "*/*"), "*/*");
+    }
+}
@@ -1,3 +1,6 @@
+use crate::services::mcp_json_config::{
+    args_value, backup_config_file, render_mcp_servers, string_map_value, write_mcp_servers,
+};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
@@ -163,149 +166,49 @@ fn parse_mcp_entry(name: &str, config: &Value) -> Result<ParsedGeminiMcp> {
     }
 }
 
-/// MCP tuple for writing (same format used by other editors)
-pub type McpTuple = (
-    String,         // name
-    String,         // type (stdio, sse, http)
-    Option<String>, // command
-    Option<String>, // args (JSON)
-    Option<String>, // url
-    Option<String>, // headers (JSON)
-    Option<String>, // env (JSON)
-);
-
-/// Create a backup of the settings file before modifying it
-fn backup_settings_file(path: &Path) -> Result<()> {
-    if !path.exists() {
-        return Ok(());
-    }
-
-    let backup_path = path.with_extension("json.bak");
-    std::fs::copy(path, &backup_path).map_err(|e| {
-        anyhow::anyhow!(
-            "Failed to create backup of {} before writing: {}",
-            path.display(),
-            e
-        )
-    })?;
-
-    Ok(())
-}
+pub use crate::services::mcp_json_config::McpTuple;
 
 /// Write MCP servers to Gemini settings.json, preserving existing content
 pub fn write_gemini_config(path: &Path, mcps: &[McpTuple]) -> Result<()> {
-    // Read existing config or create new
-    let mut config: GeminiSettingsConfig = if path.exists() {
-        let content = std::fs::read_to_string(path)?;
-        serde_json::from_str(&content).map_err(|e| {
-            anyhow::anyhow!(
-                "Failed to parse existing Gemini settings at {}: {}. \
-                 Refusing to overwrite to prevent data loss. \
-                 Please fix or remove the file manually.",
-                path.display(),
-                e
-            )
-        })?
-    } else {
-        GeminiSettingsConfig::default()
-    };
-
-    // Skip overwrite when DB has no MCPs — preserves externally-managed configs
-    if mcps.is_empty() {
-        return Ok(());
-    }
-
-    // Back up the existing file before modifying it
-    backup_settings_file(path)?;
-
-    // Clear existing servers (we'll rebuild them)
-    config.mcp_servers.clear();
-
-    // Add each MCP
-    for mcp in mcps {
-        let (name, mcp_type, command, args, url, headers, env) = mcp;
-
-        let mut server_obj = Map::new();
+    write_mcp_servers(path, "gemini", "Gemini settings", mcps, gemini_server_entry)
+}
 
-        match mcp_type.as_str() {
-            "stdio" => {
-                if let Some(cmd) = command {
-                    server_obj.insert("command".to_string(), Value::String(cmd.clone()));
-                }
+/// The content [`write_gemini_config`] would write; `None` leaves the file as is
+pub fn render_gemini_config(path: &Path, mcps: &[McpTuple]) -> Result<Option<String>> {
+    render_mcp_servers(path, "gemini", "Gemini settings", mcps, gemini_server_entry)
+}
 
-                if let Some(args_json) = args {
-                    if let Ok(args_vec) = serde_json::from_str::<Vec<String>>(args_json) {
-                        server_obj.insert(
-                            "args".to_string(),
-                            Value::Array(args_vec.into_iter().map(Value::String).collect()),
-                        );
-                    }
-                }
+/// Gemini entry for one MCP (`httpUrl` for HTTP, `url` for SSE)
+fn gemini_server_entry(mcp: &McpTuple) -> Option<Value> {
+    let (_, mcp_type, command, args, url, headers, env) = mcp;
+    let mut server_obj = Map::new();
 
-                if let Some(env_json) = env {
-                    if let Ok(env_map) = serde_json::from_str::<HashMap<String, String>>(env_json) {
-                        let env_obj: Map<String, Value> = env_map
-                            .into_iter()
-                            .map(|(k, v)| (k, Value::String(v)))
-                            .collect();
-                        server_obj.insert("env".to_string(), Value::Object(env_obj));
-                    }
-                }
+    let url_key = match mcp_type.as_str() {
+        "stdio" => {
+            if let Some(cmd) = command {
+                server_obj.insert("command".to_string(), Value::String(cmd.clone()));
             }
-            "http" => {
-                // Use httpUrl for HTTP type
-                if let Some(u) = url {
-                    server_obj.insert("httpUrl".to_string(), Value::String(u.clone()));
-                }
-
-                if let Some(headers_json) = headers {
-                    if let Ok(headers_map) =
-                        serde_json::from_str::<HashMap<String, String>>(headers_json)
-                    {
-                        let headers_obj: Map<String, Value> = headers_map
-                            .into_iter()
-                            .map(|(k, v)| (k, Value::String(v)))
-                            .collect();
-                        server_obj.insert("headers".to_string(), Value::Object(headers_obj));
-                    }
-                }
+            if let Some(args) = args_value(args.as_deref()) {
+                server_obj.insert("args".to_string(), args);
             }
-            "sse" => {
-                // Use url for SSE type
-                if let Some(u) = url {
-                    server_obj.insert("url".to_string(), Value::String(u.clone()));
-                }
-
-                if let Some(headers_json) = headers {
-                    if let Ok(headers_map) =
-                        serde_json::from_str::<HashMap<String, String>>(headers_json)
-                    {
-                        let headers_obj: Map<String, Value> = headers_map
-                            .into_iter()
-                            .map(|(k, v)| (k, Value::String(v)))
-                            .collect();
-                        server_obj.insert("headers".to_string(), Value::Object(headers_obj));
-                    }
-                }
+            if let Some(env) = string_map_value(env.as_deref()) {
+                server_obj.insert("env".to_string(), env);
             }
-            _ => continue,
+            return Some(Value::Object(server_obj));
         }
+        "http" => "httpUrl",
+        "sse" => "url",
+        _ => return None,
+    };
 
-        config
-            .mcp_servers
-            .insert(name.clone(), Value::Object(server_obj));
+    if let Some(u) = url {
+        server_obj.insert(url_key.to_string(), Value::String(u.clone()));
     }
-
-    // Ensure parent directory exists
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
+    if let Some(headers) = string_map_value(headers.as_deref()) {
+        server_obj.insert("headers".to_string(), headers);
     }
 
-    // Write back with pretty formatting
-    let json = serde_json::to_string_pretty(&config)?;
-    std::fs::write(path, json)?;
-
-    Ok(())
+    Some(Value::Object(server_obj))
 }
 
 /// Add a single MCP to Gemini config
@@ -361,7 +264,7 @@ pub fn remove_mcp_from_gemini_config(path: &Path, name: &str) -> Result<()> {
     config.mcp_servers.remove(name);
 
     // Back up before writing
-    backup_settings_file(path)?;
+    backup_config_file(path)?;
 
     // Write back
     let json = serde_json::to_string_pretty(&config)?;
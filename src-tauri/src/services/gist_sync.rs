@@ -641,6 +641,12 @@ pub fn apply_pulled_payload(
                             env: entry.env.clone(),
                             icon: entry.icon.clone(),
                             tags: entry.tags.clone(),
+                            testable: None,
+                            env_file: None,
+                            display_name: None,
+                            inherit_env: None,
+                            shell: None,
+                            split_command: None,
                         };
                         if let Err(e) = db.create_mcp(&req) {
                             conflicts.push(format!("MCP '{}': {}", entry.name, e));
@@ -13,6 +13,8 @@ pub struct GitHubClient {
     token: Option<String>,
     api_base: String,
     raw_base: String,
+    /// Branch, tag or commit to read instead of main/master
+    git_ref: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -79,6 +81,21 @@ impl GitHubClient {
             token,
             api_base: GITHUB_API_BASE.to_string(),
             raw_base: RAW_GITHUB_BASE.to_string(),
+            git_ref: None,
+        }
+    }
+
+    /// Read files at `git_ref` instead of trying main, then master
+    pub fn with_ref(mut self, git_ref: Option<String>) -> Self {
+        self.git_ref = git_ref.filter(|r| !r.trim().is_empty());
+        self
+    }
+
+    /// Branches tried, in order, when reading raw files
+    fn branches(&self) -> Vec<&str> {
+        match self.git_ref.as_deref() {
+            Some(git_ref) => vec![git_ref],
+            None => vec!["main", "master"],
         }
     }
 
@@ -95,6 +112,7 @@ impl GitHubClient {
             token,
             api_base,
             raw_base,
+            git_ref: None,
         }
     }
 
@@ -152,12 +170,11 @@ impl GitHubClient {
             )
         };
 
-        let response = self
-            .client
-            .get(&url)
-            .headers(self.build_headers())
-            .send()
-            .await?;
+        let mut request = self.client.get(&url).headers(self.build_headers());
+        if let Some(git_ref) = &self.git_ref {
+            request = request.query(&[("ref", git_ref)]);
+        }
+        let response = request.send().await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -172,7 +189,7 @@ impl GitHubClient {
     /// Get README content using raw.githubusercontent.com (no rate limit)
     pub async fn get_readme(&self, owner: &str, repo: &str) -> Result<String> {
         // Try main branch first, then master
-        for branch in ["main", "master"] {
+        for branch in self.branches() {
             let url = format!("{}/{}/{}/{}/README.md", self.raw_base, owner, repo, branch);
 
             let response = self.client.get(&url).send().await?;
@@ -182,13 +199,16 @@ impl GitHubClient {
             }
         }
 
-        Err(anyhow!("Could not find README.md in main or master branch"))
+        Err(anyhow!(
+            "Could not find README.md in {} branch",
+            self.branches().join(" or ")
+        ))
     }
 
     /// Get file content by path using raw.githubusercontent.com (no rate limit)
     pub async fn get_file(&self, owner: &str, repo: &str, path: &str) -> Result<String> {
         // Try main branch first, then master
-        for branch in ["main", "master"] {
+        for branch in self.branches() {
             let url = format!("{}/{}/{}/{}/{}", self.raw_base, owner, repo, branch, path);
 
             let response = self.client.get(&url).send().await?;
@@ -199,8 +219,42 @@ impl GitHubClient {
         }
 
         Err(anyhow!(
-            "Could not find file {} in main or master branch",
-            path
+            "Could not find file {} in {} branch",
+            path,
+            self.branches().join(" or ")
+        ))
+    }
+
+    /// Get a binary file by path using raw.githubusercontent.com, refusing
+    /// anything larger than `max_bytes`
+    pub async fn get_file_bytes(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: &str,
+        max_bytes: u64,
+    ) -> Result<Vec<u8>> {
+        for branch in self.branches() {
+            let url = format!("{}/{}/{}/{}/{}", self.raw_base, owner, repo, branch, path);
+
+            let response = self.client.get(&url).send().await?;
+
+            if response.status().is_success() {
+                if response.content_length().is_some_and(|len| len > max_bytes) {
+                    return Err(anyhow!("File {} exceeds {} bytes", path, max_bytes));
+                }
+                let bytes = response.bytes().await?;
+                if bytes.len() as u64 > max_bytes {
+                    return Err(anyhow!("File {} exceeds {} bytes", path, max_bytes));
+                }
+                return Ok(bytes.to_vec());
+            }
+        }
+
+        Err(anyhow!(
+            "Could not find file {} in {} branch",
+            path,
+            self.branches().join(" or ")
         ))
     }
 
@@ -313,7 +367,7 @@ pub fn parse_github_url(url: &str) -> Option<(String, String)> {
 mod tests {
     use super::*;
     use rstest::rstest;
-    use wiremock::matchers::{method, path};
+    use wiremock::matchers::{method, path, query_param};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     // =========================================================================
@@ -647,6 +701,43 @@ mod tests {
         assert!(content.contains(r#""key": "value""#));
     }
 
+    #[tokio::test]
+    async fn test_with_ref_reads_only_that_ref() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/owner/repo/v1.2/agents/reviewer.md"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("# Reviewer"))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/contents/agents"))
+            .and(query_param("ref", "v1.2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .mount(&mock_server)
+            .await;
+
+        let client = GitHubClient::with_base_urls(None, mock_server.uri(), mock_server.uri())
+            .with_ref(Some("v1.2".to_string()));
+
+        let content = client
+            .get_file("owner", "repo", "agents/reviewer.md")
+            .await
+            .unwrap();
+        assert_eq!(content, "# Reviewer");
+        assert!(client
+            .get_contents("owner", "repo", "agents")
+            .await
+            .unwrap()
+            .is_empty());
+
+        let err = client
+            .get_file("owner", "repo", "missing.md")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("in v1.2 branch"));
+    }
+
     #[tokio::test]
     async fn test_get_file_not_found() {
         let mock_server = MockServer::start().await;
@@ -673,6 +764,30 @@ mod tests {
             .contains("Could not find file"));
     }
 
+    #[tokio::test]
+    async fn test_get_file_bytes_respects_limit() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/owner/repo/main/dist/bundle.zip"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![7u8; 64]))
+            .mount(&mock_server)
+            .await;
+
+        let client = GitHubClient::with_base_urls(None, mock_server.uri(), mock_server.uri());
+
+        let bytes = client
+            .get_file_bytes("owner", "repo", "dist/bundle.zip", 64)
+            .await
+            .unwrap();
+        assert_eq!(bytes.len(), 64);
+
+        let result = client
+            .get_file_bytes("owner", "repo", "dist/bundle.zip", 32)
+            .await;
+        assert!(result.unwrap_err().to_string().contains("exceeds"));
+    }
+
     #[tokio::test]
     async fn test_get_rate_limit_success() {
         let mock_server = MockServer::start().await;
@@ -1,10 +1,117 @@
-use crate::db::models::Hook;
+use crate::db::models::{CreateHookRequest, Hook};
+use crate::services::pending_changes::PendingChange;
+use crate::utils::command_line::shell_escape;
+use crate::utils::install::find_on_path;
 use anyhow::Result;
 use directories::BaseDirs;
 use serde_json::{json, Map, Value};
 use std::collections::HashMap;
 use std::path::Path;
 
+/// Hook actions that share one matcher within an event
+type MatcherGroup<'a> = (Option<&'a str>, Vec<Value>);
+
+/// Placeholder for the project root in hook commands and prompts. The library
+/// stores it as written and expands it when hooks are written out.
+pub const PROJECT_DIR_VAR: &str = "{{project_dir}}";
+
+/// Where hooks are being written, which decides how `{{project_dir}}` expands
+#[derive(Debug, Clone, Copy)]
+pub enum HookScope<'a> {
+    /// Global settings and exports: the project isn't known until Claude Code
+    /// runs the hook, so commands read `$CLAUDE_PROJECT_DIR`
+    Global,
+    /// A specific project's settings: expands to its absolute path
+    Project(&'a Path),
+}
+
+/// Quote a path for a POSIX shell only when it needs it
+fn shell_path(path: &str) -> String {
+    let plain = !path.is_empty()
+        && path
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-./:@%+,~".contains(c));
+    if plain {
+        path.to_string()
+    } else {
+        shell_escape(path)
+    }
+}
+
+/// Which kind of quotes a point in a shell command is inside
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ShellQuote {
+    None,
+    Single,
+    Double,
+}
+
+/// The project directory as text that reads as that one path at a point in
+/// a command with the given quoting
+fn project_dir_in(scope: HookScope, quote: ShellQuote) -> String {
+    match (scope, quote) {
+        (HookScope::Global, ShellQuote::None) => "\"$CLAUDE_PROJECT_DIR\"".to_string(),
+        (HookScope::Global, ShellQuote::Double) => "$CLAUDE_PROJECT_DIR".to_string(),
+        // Step out of the single quotes so the variable expands
+        (HookScope::Global, ShellQuote::Single) => "'\"$CLAUDE_PROJECT_DIR\"'".to_string(),
+        (HookScope::Project(path), quote) => {
+            let path = path.to_string_lossy();
+            match quote {
+                ShellQuote::None => shell_path(&path),
+                ShellQuote::Double => path
+                    .chars()
+                    .flat_map(|c| {
+                        let escape = matches!(c, '\\' | '"' | '$' | '`').then_some('\\');
+                        escape.into_iter().chain(std::iter::once(c))
+                    })
+                    .collect(),
+                ShellQuote::Single => path.replace('\'', "'\\''"),
+            }
+        }
+    }
+}
+
+/// Expand `{{project_dir}}` in a command hook for `scope`. Each occurrence is
+/// quoted for where it sits, so it also works inside single or double quotes.
+pub fn expand_hook_command(command: &str, scope: HookScope) -> String {
+    if !command.contains(PROJECT_DIR_VAR) {
+        return command.to_string();
+    }
+    let mut expanded = String::with_capacity(command.len());
+    let mut quote = ShellQuote::None;
+    let mut rest = command;
+    while let Some(c) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix(PROJECT_DIR_VAR) {
+            expanded.push_str(&project_dir_in(scope, quote));
+            rest = after;
+            continue;
+        }
+        let mut len = c.len_utf8();
+        match (quote, c) {
+            // A backslash escapes the next character except inside single quotes
+            (ShellQuote::None | ShellQuote::Double, '\\') => {
+                len += rest[len..].chars().next().map_or(0, char::len_utf8);
+            }
+            (ShellQuote::None, '\'') => quote = ShellQuote::Single,
+            (ShellQuote::None, '"') => quote = ShellQuote::Double,
+            (ShellQuote::Single, '\'') | (ShellQuote::Double, '"') => quote = ShellQuote::None,
+            _ => {}
+        }
+        expanded.push_str(&rest[..len]);
+        rest = &rest[len..];
+    }
+    expanded
+}
+
+/// Expand `{{project_dir}}` in a prompt hook for `scope`. Prompts aren't run
+/// by a shell, so globally it becomes a plain description instead.
+pub fn expand_hook_prompt(prompt: &str, scope: HookScope) -> String {
+    match scope {
+        HookScope::Global => prompt.replace(PROJECT_DIR_VAR, "the project root directory"),
+        HookScope::Project(path) => prompt.replace(PROJECT_DIR_VAR, &path.to_string_lossy()),
+    }
+}
+
 /// Generate the hooks configuration format for settings.json
 ///
 /// Claude Code hooks format:
@@ -20,124 +127,220 @@ use std::path::Path;
 ///   }
 /// }
 /// ```
-fn generate_hooks_config(hooks: &[Hook]) -> Value {
-    // Group hooks by event_type
-    let mut by_event: HashMap<String, Vec<&Hook>> = HashMap::new();
+fn generate_hooks_config(hooks: &[Hook], scope: HookScope) -> Value {
+    // Group hooks by event_type, then by matcher, so hooks sharing a matcher
+    // end up in a single entry's inner "hooks" array (first-seen order)
+    let mut by_event: HashMap<String, Vec<MatcherGroup>> = HashMap::new();
     for hook in hooks {
-        by_event
-            .entry(hook.event_type.clone())
-            .or_default()
-            .push(hook);
+        let matcher = hook.matcher.as_deref().filter(|m| !m.is_empty());
+        let groups = by_event.entry(hook.event_type.clone()).or_default();
+        match groups.iter_mut().find(|(m, _)| *m == matcher) {
+            Some((_, actions)) => actions.push(hook_action(hook, scope)),
+            None => groups.push((matcher, vec![hook_action(hook, scope)])),
+        }
     }
 
     let mut hooks_obj = Map::new();
 
-    for (event_type, event_hooks) in by_event {
-        let mut event_array: Vec<Value> = Vec::new();
-
-        for hook in event_hooks {
-            let mut hook_entry = Map::new();
-
-            // Add matcher if present
-            if let Some(ref matcher) = hook.matcher {
-                if !matcher.is_empty() {
+    for (event_type, groups) in by_event {
+        let event_array: Vec<Value> = groups
+            .into_iter()
+            .map(|(matcher, actions)| {
+                let mut hook_entry = Map::new();
+                if let Some(matcher) = matcher {
                     hook_entry.insert("matcher".to_string(), json!(matcher));
                 }
-            }
+                hook_entry.insert("hooks".to_string(), Value::Array(actions));
+                Value::Object(hook_entry)
+            })
+            .collect();
 
-            // Build the hook action object
-            let mut hook_action = Map::new();
-            hook_action.insert("type".to_string(), json!(hook.hook_type));
-
-            match hook.hook_type.as_str() {
-                "command" => {
-                    if let Some(ref cmd) = hook.command {
-                        hook_action.insert("command".to_string(), json!(cmd));
-                    }
-                    if let Some(timeout) = hook.timeout {
-                        hook_action.insert("timeout".to_string(), json!(timeout));
-                    }
-                }
-                "prompt" => {
-                    if let Some(ref prompt) = hook.prompt {
-                        hook_action.insert("prompt".to_string(), json!(prompt));
-                    }
-                }
-                "http" => {
-                    if let Some(ref url) = hook.url {
-                        hook_action.insert("url".to_string(), json!(url));
-                    }
-                    if let Some(ref headers) = hook.headers {
-                        hook_action.insert("headers".to_string(), headers.clone());
-                    }
-                    if let Some(ref env_vars) = hook.allowed_env_vars {
-                        hook_action.insert("allowedEnvVars".to_string(), json!(env_vars));
-                    }
-                    if let Some(timeout) = hook.timeout {
-                        hook_action.insert("timeout".to_string(), json!(timeout));
-                    }
-                }
-                "agent" => {
-                    // agent type has no additional type-specific fields
-                }
-                _ => {}
-            }
+        hooks_obj.insert(event_type, Value::Array(event_array));
+    }
+
+    Value::Object(hooks_obj)
+}
+
+/// Build the inner hook action object (`{"type": ..., ...}`) for one hook
+fn hook_action(hook: &Hook, scope: HookScope) -> Value {
+    let mut hook_action = Map::new();
+    hook_action.insert("type".to_string(), json!(hook.hook_type));
 
-            // Universal fields (all hook types)
-            if let Some(ref if_cond) = hook.if_condition {
-                hook_action.insert("if".to_string(), json!(if_cond));
+    match hook.hook_type.as_str() {
+        "command" => {
+            if let Some(ref cmd) = hook.command {
+                let cmd = expand_hook_command(cmd, scope);
+                let cmd = match hook
+                    .path_pattern
+                    .as_deref()
+                    .filter(|p| !p.trim().is_empty())
+                {
+                    Some(pattern) => wrap_with_path_guard(&cmd, pattern),
+                    None => cmd,
+                };
+                hook_action.insert("command".to_string(), json!(cmd));
             }
-            if let Some(ref status) = hook.status_message {
-                hook_action.insert("statusMessage".to_string(), json!(status));
+            if let Some(timeout) = hook.timeout {
+                hook_action.insert("timeout".to_string(), json!(timeout));
             }
-            if hook.once {
-                hook_action.insert("once".to_string(), json!(true));
+        }
+        "prompt" => {
+            if let Some(ref prompt) = hook.prompt {
+                hook_action.insert(
+                    "prompt".to_string(),
+                    json!(expand_hook_prompt(prompt, scope)),
+                );
             }
-            if hook.async_mode {
-                hook_action.insert("async".to_string(), json!(true));
+            if let Some(timeout) = hook.timeout {
+                hook_action.insert("timeout".to_string(), json!(timeout));
             }
-            if let Some(ref shell) = hook.shell {
-                if shell != "bash" {
-                    hook_action.insert("shell".to_string(), json!(shell));
-                }
+        }
+        "http" => {
+            if let Some(ref url) = hook.url {
+                hook_action.insert("url".to_string(), json!(url));
             }
+            if let Some(ref headers) = hook.headers {
+                hook_action.insert("headers".to_string(), headers.clone());
+            }
+            if let Some(ref env_vars) = hook.allowed_env_vars {
+                hook_action.insert("allowedEnvVars".to_string(), json!(env_vars));
+            }
+            if let Some(timeout) = hook.timeout {
+                hook_action.insert("timeout".to_string(), json!(timeout));
+            }
+        }
+        "agent" => {
+            // agent type has no additional type-specific fields
+        }
+        _ => {}
+    }
 
-            hook_entry.insert("hooks".to_string(), json!([Value::Object(hook_action)]));
-            event_array.push(Value::Object(hook_entry));
+    // Universal fields (all hook types)
+    if let Some(ref if_cond) = hook.if_condition {
+        hook_action.insert("if".to_string(), json!(if_cond));
+    }
+    if let Some(ref status) = hook.status_message {
+        hook_action.insert("statusMessage".to_string(), json!(status));
+    }
+    if hook.once {
+        hook_action.insert("once".to_string(), json!(true));
+    }
+    if hook.async_mode {
+        hook_action.insert("async".to_string(), json!(true));
+    }
+    if let Some(ref shell) = hook.shell {
+        if shell != "bash" {
+            hook_action.insert("shell".to_string(), json!(shell));
         }
+    }
 
-        hooks_obj.insert(event_type, Value::Array(event_array));
+    Value::Object(hook_action)
+}
+
+/// Reads the hook's stdin JSON and exits 0 only when the tool input's file
+/// path (relative to the session cwd) matches the glob in argv[1]
+const PATH_GUARD_SCRIPT: &str = r#"import sys, json, os, fnmatch
+d = json.load(sys.stdin)
+t = d.get("tool_input") or {}
+p = t.get("file_path") or t.get("notebook_path") or t.get("path") or ""
+if p and os.path.isabs(p):
+    p = os.path.relpath(p, d.get("cwd") or os.getcwd())
+sys.exit(0 if p and fnmatch.fnmatch(p, sys.argv[1]) else 1)"#;
+
+/// Prefix a command hook with a guard that skips it (exit 0) unless the edited
+/// file matches `pattern`. The hook input is buffered so the user's command
+/// still receives it on stdin. The guard is POSIX `sh` and runs `python3`,
+/// so both must be on the PATH Claude Code runs hooks with.
+pub fn wrap_with_path_guard(command: &str, pattern: &str) -> String {
+    format!(
+        "input=$(cat); printf '%s' \"$input\" | python3 -c {} {} || exit 0; printf '%s' \"$input\" | {{\n{}\n}}",
+        shell_escape(PATH_GUARD_SCRIPT),
+        shell_escape(pattern.trim()),
+        command
+    )
+}
+
+/// [`validate_hook`] for a stored hook, so an exported settings fragment never
+/// contains a hook that would be rejected
+pub fn validate_hook_for_export(hook: &Hook) -> Result<(), String> {
+    validate_hook(&hook.into())
+}
+
+/// Check that a hook has everything Claude Code needs to run it
+pub fn validate_hook(hook: &CreateHookRequest) -> Result<(), String> {
+    let missing = |field: &Option<String>| field.as_deref().map(str::trim).unwrap_or("").is_empty();
+
+    if hook.event_type.trim().is_empty() {
+        return Err(format!("Hook '{}' has no event type", hook.name));
+    }
+    if !missing(&hook.path_pattern) {
+        if hook.hook_type != "command" {
+            return Err(format!(
+                "Hook '{}' has a path pattern, which only applies to command hooks",
+                hook.name
+            ));
+        }
+        if hook.shell.as_deref().is_some_and(|s| s != "bash") {
+            return Err(format!(
+                "Hook '{}' has a path pattern, which requires the bash shell",
+                hook.name
+            ));
+        }
+    }
+    match hook.hook_type.as_str() {
+        "command" if missing(&hook.command) => {
+            Err(format!("Command hook '{}' has no command", hook.name))
+        }
+        "prompt" if missing(&hook.prompt) => {
+            Err(format!("Prompt hook '{}' has no prompt", hook.name))
+        }
+        "http" if missing(&hook.url) => Err(format!("HTTP hook '{}' has no URL", hook.name)),
+        "command" | "prompt" | "http" | "agent" => match hook.timeout {
+            Some(t) if t <= 0 => Err(format!(
+                "Hook '{}' has an invalid timeout ({}); it must be positive",
+                hook.name, t
+            )),
+            _ => Ok(()),
+        },
+        other => Err(format!("Hook '{}' has unknown type '{}'", hook.name, other)),
     }
+}
 
-    Value::Object(hooks_obj)
+/// Path-pattern guards need `python3`; warn when writing them to a machine
+/// that doesn't have it, since the hook would then never run
+fn warn_if_path_guard_unavailable(hooks: &[Hook]) {
+    let guarded = hooks.iter().find(|h| {
+        h.hook_type == "command"
+            && h.path_pattern
+                .as_deref()
+                .is_some_and(|p| !p.trim().is_empty())
+    });
+    if let Some(hook) = guarded {
+        if find_on_path("python3").is_none() {
+            log::warn!(
+                "[HookWriter] Hook '{}' has a path pattern, but python3 isn't on PATH; its guard will skip it every time",
+                hook.name
+            );
+        }
+    }
 }
 
 /// Read an existing settings.json file or return an empty object
 fn read_settings_file(path: &Path) -> Result<Value> {
-    if path.exists() {
-        let content = std::fs::read_to_string(path)?;
-        Ok(serde_json::from_str(&content).unwrap_or(json!({})))
-    } else {
-        Ok(json!({}))
+    match crate::services::pending_changes::read_config_file(path)? {
+        Some(content) => Ok(serde_json::from_str(&content).unwrap_or(json!({}))),
+        None => Ok(json!({})),
     }
 }
 
 /// Write settings.json file, preserving other settings
-fn write_settings_file(path: &Path, settings: &Value) -> Result<()> {
-    // Ensure parent directory exists
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
-
-    crate::utils::backup::backup_file(path)?;
-
+fn write_settings_file(path: &Path, settings: &Value) -> Result<Option<PendingChange>> {
     let content = serde_json::to_string_pretty(settings)?;
-    std::fs::write(path, content)?;
-    Ok(())
+    crate::services::pending_changes::write_config_file(path, &content)
 }
 
 /// Write hooks to the global settings file (~/.claude/settings.json)
-pub fn write_global_hooks(hooks: &[Hook]) -> Result<()> {
+pub fn write_global_hooks(hooks: &[Hook]) -> Result<Option<PendingChange>> {
     let base_dirs =
         BaseDirs::new().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
     let home = base_dirs.home_dir();
@@ -151,14 +354,15 @@ pub fn write_global_hooks(hooks: &[Hook]) -> Result<()> {
             obj.remove("hooks");
         }
     } else {
-        settings["hooks"] = generate_hooks_config(hooks);
+        settings["hooks"] = generate_hooks_config(hooks, HookScope::Global);
+        warn_if_path_guard_unavailable(hooks);
     }
 
     write_settings_file(&settings_path, &settings)
 }
 
 /// Write hooks to a project's settings file ({project}/.claude/settings.local.json)
-pub fn write_project_hooks(project_path: &Path, hooks: &[Hook]) -> Result<()> {
+pub fn write_project_hooks(project_path: &Path, hooks: &[Hook]) -> Result<Option<PendingChange>> {
     let settings_path = project_path.join(".claude").join("settings.local.json");
 
     let mut settings = read_settings_file(&settings_path)?;
@@ -169,17 +373,19 @@ pub fn write_project_hooks(project_path: &Path, hooks: &[Hook]) -> Result<()> {
             obj.remove("hooks");
         }
     } else {
-        settings["hooks"] = generate_hooks_config(hooks);
+        settings["hooks"] = generate_hooks_config(hooks, HookScope::Project(project_path));
+        warn_if_path_guard_unavailable(hooks);
     }
 
     write_settings_file(&settings_path, &settings)
 }
 
 /// Convert hooks to Claude Code settings.json format for export
-/// This returns a serde_json::Value that can be serialized for export
+/// This returns a serde_json::Value that can be serialized for export.
+/// `{{project_dir}}` expands as for global hooks, since the target is unknown.
 pub fn hooks_to_settings_format(hooks: &[Hook]) -> Value {
     json!({
-        "hooks": generate_hooks_config(hooks)
+        "hooks": generate_hooks_config(hooks, HookScope::Global)
     })
 }
 
@@ -211,6 +417,7 @@ mod tests {
                 once: false,
                 async_mode: false,
                 shell: None,
+                path_pattern: None,
                 created_at: "2024-01-01".to_string(),
                 updated_at: "2024-01-01".to_string(),
             },
@@ -235,12 +442,13 @@ mod tests {
                 once: false,
                 async_mode: false,
                 shell: None,
+                path_pattern: None,
                 created_at: "2024-01-01".to_string(),
                 updated_at: "2024-01-01".to_string(),
             },
         ];
 
-        let config = generate_hooks_config(&hooks);
+        let config = generate_hooks_config(&hooks, HookScope::Global);
 
         assert!(config.get("PostToolUse").is_some());
         assert!(config.get("PreToolUse").is_some());
@@ -273,11 +481,12 @@ mod tests {
             once: false,
             async_mode: false,
             shell: None,
+            path_pattern: None,
             created_at: "2024-01-01".to_string(),
             updated_at: "2024-01-01".to_string(),
         }];
 
-        let config = generate_hooks_config(&hooks);
+        let config = generate_hooks_config(&hooks, HookScope::Global);
 
         let session_start = config.get("SessionStart").unwrap().as_array().unwrap();
         assert_eq!(session_start.len(), 1);
@@ -315,6 +524,7 @@ mod tests {
                 once: false,
                 async_mode: false,
                 shell: None,
+                path_pattern: None,
                 created_at: "2024-01-01".to_string(),
                 updated_at: "2024-01-01".to_string(),
             },
@@ -339,20 +549,219 @@ mod tests {
                 once: false,
                 async_mode: false,
                 shell: None,
+                path_pattern: None,
                 created_at: "2024-01-01".to_string(),
                 updated_at: "2024-01-01".to_string(),
             },
         ];
 
-        let config = generate_hooks_config(&hooks);
+        let config = generate_hooks_config(&hooks, HookScope::Global);
         let pre_tool = config.get("PreToolUse").unwrap().as_array().unwrap();
         assert_eq!(pre_tool.len(), 2);
     }
 
+    #[test]
+    fn test_same_matcher_hooks_share_one_entry() {
+        let make = |id: i64, command: &str| Hook {
+            id,
+            name: format!("hook-{}", id),
+            description: None,
+            event_type: "PreToolUse".to_string(),
+            matcher: Some("Bash".to_string()),
+            hook_type: "command".to_string(),
+            command: Some(command.to_string()),
+            prompt: None,
+            timeout: None,
+            tags: None,
+            source: "manual".to_string(),
+            is_template: false,
+            url: None,
+            headers: None,
+            allowed_env_vars: None,
+            if_condition: None,
+            status_message: None,
+            once: false,
+            async_mode: false,
+            shell: None,
+            path_pattern: None,
+            created_at: "2024-01-01".to_string(),
+            updated_at: "2024-01-01".to_string(),
+        };
+        let hooks = vec![make(1, "echo a"), make(2, "echo b")];
+
+        let config = generate_hooks_config(&hooks, HookScope::Global);
+        let pre_tool = config.get("PreToolUse").unwrap().as_array().unwrap();
+        assert_eq!(pre_tool.len(), 1);
+        let actions = pre_tool[0].get("hooks").unwrap().as_array().unwrap();
+        assert_eq!(actions.len(), 2);
+        assert_eq!(actions[0]["command"], "echo a");
+        assert_eq!(actions[1]["command"], "echo b");
+
+        let mut missing = make(3, "");
+        missing.command = None;
+        assert!(validate_hook_for_export(&hooks[0]).is_ok());
+        assert!(validate_hook_for_export(&missing).is_err());
+    }
+
+    #[test]
+    fn test_project_dir_expands_per_scope() {
+        let mut hook = Hook {
+            id: 1,
+            name: "lint".to_string(),
+            description: None,
+            event_type: "PostToolUse".to_string(),
+            matcher: Some("Edit".to_string()),
+            hook_type: "command".to_string(),
+            command: Some("{{project_dir}}/scripts/lint.sh".to_string()),
+            prompt: None,
+            timeout: None,
+            tags: None,
+            source: "manual".to_string(),
+            is_template: false,
+            url: None,
+            headers: None,
+            allowed_env_vars: None,
+            if_condition: None,
+            status_message: None,
+            once: false,
+            async_mode: false,
+            shell: None,
+            path_pattern: None,
+            created_at: "2024-01-01".to_string(),
+            updated_at: "2024-01-01".to_string(),
+        };
+        let command_for = |hook: &Hook, scope| {
+            generate_hooks_config(std::slice::from_ref(hook), scope)["PostToolUse"][0]["hooks"][0]
+                ["command"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(
+            command_for(&hook, HookScope::Global),
+            "\"$CLAUDE_PROJECT_DIR\"/scripts/lint.sh"
+        );
+        assert_eq!(
+            command_for(&hook, HookScope::Project(Path::new("/home/dev/app"))),
+            "/home/dev/app/scripts/lint.sh"
+        );
+        assert_eq!(
+            command_for(&hook, HookScope::Project(Path::new("/home/dev/my app"))),
+            "'/home/dev/my app'/scripts/lint.sh"
+        );
+
+        let spaced = HookScope::Project(Path::new("/home/dev/it's \"my\" $app"));
+        assert_eq!(
+            expand_hook_command("cd \"{{project_dir}}/src\" && ls", spaced),
+            "cd \"/home/dev/it's \\\"my\\\" \\$app/src\" && ls"
+        );
+        assert_eq!(
+            expand_hook_command("cat '{{project_dir}}/a b'", spaced),
+            "cat '/home/dev/it'\\''s \"my\" $app/a b'"
+        );
+        assert_eq!(
+            expand_hook_command(
+                "echo \"{{project_dir}}\" '{{project_dir}}'",
+                HookScope::Global
+            ),
+            "echo \"$CLAUDE_PROJECT_DIR\" ''\"$CLAUDE_PROJECT_DIR\"''"
+        );
+
+        hook.hook_type = "prompt".to_string();
+        hook.command = None;
+        hook.prompt = Some("Only review files under {{project_dir}}".to_string());
+        let prompt = generate_hooks_config(
+            std::slice::from_ref(&hook),
+            HookScope::Project(Path::new("/home/dev/app")),
+        );
+        assert_eq!(
+            prompt["PostToolUse"][0]["hooks"][0]["prompt"],
+            "Only review files under /home/dev/app"
+        );
+        assert_eq!(
+            expand_hook_prompt("Check {{project_dir}}", HookScope::Global),
+            "Check the project root directory"
+        );
+    }
+
+    #[test]
+    fn test_path_pattern_wraps_command_in_guard() {
+        let mut hook = Hook {
+            id: 1,
+            name: "lint-src".to_string(),
+            description: None,
+            event_type: "PostToolUse".to_string(),
+            matcher: Some("Edit|Write".to_string()),
+            hook_type: "command".to_string(),
+            command: Some("cat > /dev/null; echo linted".to_string()),
+            prompt: None,
+            timeout: None,
+            tags: None,
+            source: "manual".to_string(),
+            is_template: false,
+            url: None,
+            headers: None,
+            allowed_env_vars: None,
+            if_condition: None,
+            status_message: None,
+            once: false,
+            async_mode: false,
+            shell: None,
+            path_pattern: Some("src/*".to_string()),
+            created_at: "2024-01-01".to_string(),
+            updated_at: "2024-01-01".to_string(),
+        };
+
+        let config = generate_hooks_config(std::slice::from_ref(&hook), HookScope::Global);
+        let command = config["PostToolUse"][0]["hooks"][0]["command"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert!(command.starts_with("input=$(cat);"));
+        assert!(command.contains("'src/*'"));
+        assert!(command.ends_with("{\ncat > /dev/null; echo linted\n}"));
+        assert!(validate_hook_for_export(&hook).is_ok());
+
+        #[cfg(unix)]
+        if std::process::Command::new("python3")
+            .arg("--version")
+            .output()
+            .is_ok()
+        {
+            let run = |file_path: &str| {
+                use std::io::Write;
+                let mut child = std::process::Command::new("sh")
+                    .arg("-c")
+                    .arg(&command)
+                    .stdin(std::process::Stdio::piped())
+                    .stdout(std::process::Stdio::piped())
+                    .spawn()
+                    .unwrap();
+                let input = json!({"cwd": "/repo", "tool_input": {"file_path": file_path}});
+                child
+                    .stdin
+                    .take()
+                    .unwrap()
+                    .write_all(input.to_string().as_bytes())
+                    .unwrap();
+                let output = child.wait_with_output().unwrap();
+                assert!(output.status.success());
+                String::from_utf8_lossy(&output.stdout).trim().to_string()
+            };
+            assert_eq!(run("/repo/src/lib/mod.rs"), "linted");
+            assert_eq!(run("/repo/docs/readme.md"), "");
+        }
+
+        hook.hook_type = "prompt".to_string();
+        hook.prompt = Some("Check it".to_string());
+        assert!(validate_hook_for_export(&hook).is_err());
+    }
+
     #[test]
     fn test_empty_hooks_produces_empty_object() {
         let hooks: Vec<Hook> = vec![];
-        let config = generate_hooks_config(&hooks);
+        let config = generate_hooks_config(&hooks, HookScope::Global);
         assert!(config.as_object().unwrap().is_empty());
     }
 
@@ -379,11 +788,12 @@ mod tests {
             once: false,
             async_mode: false,
             shell: None,
+            path_pattern: None,
             created_at: "2024-01-01".to_string(),
             updated_at: "2024-01-01".to_string(),
         }];
 
-        let config = generate_hooks_config(&hooks);
+        let config = generate_hooks_config(&hooks, HookScope::Global);
         let post_tool = config.get("PostToolUse").unwrap().as_array().unwrap();
         let hook_actions = post_tool[0].get("hooks").unwrap().as_array().unwrap();
         assert_eq!(hook_actions[0].get("timeout").unwrap(), 60);
@@ -412,6 +822,7 @@ mod tests {
             once: false,
             async_mode: false,
             shell: None,
+            path_pattern: None,
             created_at: "2024-01-01".to_string(),
             updated_at: "2024-01-01".to_string(),
         }];
@@ -448,6 +859,7 @@ mod tests {
             once: false,
             async_mode: false,
             shell: None,
+            path_pattern: None,
             created_at: "2024-01-01".to_string(),
             updated_at: "2024-01-01".to_string(),
         }];
@@ -500,6 +912,7 @@ mod tests {
             once: false,
             async_mode: false,
             shell: None,
+            path_pattern: None,
             created_at: "2024-01-01".to_string(),
             updated_at: "2024-01-01".to_string(),
         }];
@@ -560,11 +973,12 @@ mod tests {
             once: false,
             async_mode: false,
             shell: None,
+            path_pattern: None,
             created_at: "2024-01-01".to_string(),
             updated_at: "2024-01-01".to_string(),
         }];
 
-        let config = generate_hooks_config(&hooks);
+        let config = generate_hooks_config(&hooks, HookScope::Global);
         let pre_tool = config.get("PreToolUse").unwrap().as_array().unwrap();
         // Empty matcher should not be included
         assert!(pre_tool[0].get("matcher").is_none());
@@ -593,11 +1007,12 @@ mod tests {
             once: false,
             async_mode: false,
             shell: None,
+            path_pattern: None,
             created_at: "2024-01-01".to_string(),
             updated_at: "2024-01-01".to_string(),
         }];
 
-        let config = generate_hooks_config(&hooks);
+        let config = generate_hooks_config(&hooks, HookScope::Global);
         let pre_tool = config.get("PreToolUse").unwrap().as_array().unwrap();
         let hook_actions = pre_tool[0].get("hooks").unwrap().as_array().unwrap();
         assert_eq!(hook_actions[0]["type"], "unknown_type");
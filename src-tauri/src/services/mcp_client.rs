@@ -3,24 +3,36 @@
 //! Implements the MCP protocol to connect to servers, perform handshake,
 //! and retrieve available tools.
 
+use crate::services::diagnostics::{body_snippet, redact_body, redact_header_value, redact_value};
 use anyhow::{anyhow, Result};
 use futures::StreamExt;
-use log::{error, info};
+use log::{debug, error, info};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::io::{BufRead, BufReader, Write};
 use std::process::{Child, Command, Stdio};
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use tokio::time::timeout;
 
-// Global request ID counter
-static REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+/// JSON-RPC request id counter owned by a single connection. Each client
+/// (and each one-shot test session) gets its own id space, so response
+/// correlation never depends on what sibling sessions are doing.
+#[derive(Debug)]
+struct RequestIds(AtomicU64);
+
+impl RequestIds {
+    fn new() -> Self {
+        Self(AtomicU64::new(1))
+    }
 
-fn next_request_id() -> u64 {
-    REQUEST_ID.fetch_add(1, Ordering::SeqCst)
+    fn next(&self) -> u64 {
+        self.0.fetch_add(1, Ordering::SeqCst)
+    }
 }
 
 // ============================================================================
@@ -37,6 +49,39 @@ pub struct McpTool {
     pub input_schema: Option<Value>,
 }
 
+/// A resource listed by `resources/list`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpResource {
+    pub uri: String,
+    pub name: String,
+    #[serde(default)]
+    pub mime_type: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// A prompt template listed by `prompts/list`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpPrompt {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// The declared arguments, exactly as the server sent them
+    #[serde(default)]
+    pub arguments: Option<Value>,
+}
+
+/// One message of a prompt rendered by `prompts/get`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpPromptMessage {
+    pub role: String,
+    /// Text, image or embedded resource content, as sent by the server
+    pub content: Value,
+}
+
 // ============================================================================
 // Tool Execution Types
 // ============================================================================
@@ -69,7 +114,34 @@ pub struct ToolCallResult {
     pub is_error: bool,
     #[serde(default)]
     pub error: Option<String>,
+    /// JSON-RPC error code when the server rejected the call (e.g. -32602)
+    #[serde(default)]
+    pub error_code: Option<i64>,
+    /// The `data` member of that JSON-RPC error
+    #[serde(default)]
+    pub error_data: Option<Value>,
     pub execution_time_ms: u64,
+    /// True when served from the session manager's opt-in result cache
+    #[serde(default)]
+    pub cached: bool,
+}
+
+impl ToolCallResult {
+    /// Result for a call that failed with `err`, keeping any JSON-RPC error
+    /// code and data
+    fn failed(err: &anyhow::Error, execution_time_ms: u64) -> Self {
+        let (error_code, error_data) = rpc_error_details(err);
+        Self {
+            success: false,
+            content: vec![],
+            is_error: true,
+            error: Some(err.to_string()),
+            error_code,
+            error_data,
+            execution_time_ms,
+            cached: false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -78,6 +150,9 @@ pub struct McpServerInfo {
     pub name: String,
     #[serde(default)]
     pub version: Option<String>,
+    /// Protocol version the server chose in its `initialize` result
+    #[serde(default)]
+    pub protocol_version: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -94,8 +169,20 @@ pub struct McpTestResult {
     pub prompts_supported: bool,
     #[serde(default)]
     pub error: Option<String>,
+    /// JSON-RPC error code when the server rejected the handshake
+    #[serde(default)]
+    pub error_code: Option<i64>,
+    /// The `data` member of that JSON-RPC error
+    #[serde(default)]
+    pub error_data: Option<Value>,
     #[serde(default)]
     pub response_time_ms: u64,
+    /// True when the MCP is marked as not testable and was never spawned
+    #[serde(default)]
+    pub skipped: bool,
+    /// Connection attempts retried before this result under a [`RetryPolicy`]
+    #[serde(default)]
+    pub retries: u32,
 }
 
 impl McpTestResult {
@@ -113,7 +200,11 @@ impl McpTestResult {
             resources_supported,
             prompts_supported,
             error: None,
+            error_code: None,
+            error_data: None,
             response_time_ms,
+            skipped: false,
+            retries: 0,
         }
     }
 
@@ -125,7 +216,146 @@ impl McpTestResult {
             resources_supported: false,
             prompts_supported: false,
             error: Some(message),
+            error_code: None,
+            error_data: None,
             response_time_ms,
+            skipped: false,
+            retries: 0,
+        }
+    }
+
+    /// Failed result for `err`, keeping any JSON-RPC error code and data
+    pub fn from_error(err: &anyhow::Error, response_time_ms: u64) -> Self {
+        let (error_code, error_data) = rpc_error_details(err);
+        Self {
+            error_code,
+            error_data,
+            ..Self::error(err.to_string(), response_time_ms)
+        }
+    }
+
+    /// Result for an MCP that is managed externally and must not be spawned
+    pub fn not_testable() -> Self {
+        Self {
+            success: false,
+            server_info: None,
+            tools: vec![],
+            resources_supported: false,
+            prompts_supported: false,
+            error: Some("MCP is marked as not testable".to_string()),
+            error_code: None,
+            error_data: None,
+            response_time_ms: 0,
+            skipped: true,
+            retries: 0,
+        }
+    }
+}
+
+// ============================================================================
+// Connection Retries
+// ============================================================================
+
+/// How often to retry a test whose connection failed transiently, e.g.
+/// because the server is still starting up
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryPolicy {
+    /// Total attempts, including the first
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled for each further retry
+    pub base_delay_ms: u64,
+}
+
+impl RetryPolicy {
+    fn delay(&self, retry: u32) -> Duration {
+        Duration::from_millis(
+            self.base_delay_ms
+                .saturating_mul(1 << retry.saturating_sub(1).min(16)),
+        )
+    }
+}
+
+/// Whether a failed connection is worth retrying: refused, timed out, or a
+/// temporary DNS failure. HTTP error statuses and protocol errors are not.
+fn is_transient_error(err: &anyhow::Error) -> bool {
+    let text = format!("{:#}", err).to_lowercase();
+    if text.contains("dns error") || text.contains("failed to lookup address") {
+        return text.contains("temporary failure") || text.contains("try again");
+    }
+    err.chain().any(|cause| {
+        if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+            return e.is_connect() || e.is_timeout();
+        }
+        if let Some(e) = cause.downcast_ref::<std::io::Error>() {
+            return matches!(
+                e.kind(),
+                std::io::ErrorKind::ConnectionRefused
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::TimedOut
+            );
+        }
+        false
+    })
+}
+
+/// Error for a connection that didn't complete within the test timeout
+fn connection_timeout(timeout_secs: u64) -> anyhow::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::TimedOut,
+        format!("Connection timeout after {}s", timeout_secs),
+    )
+    .into()
+}
+
+/// Run `attempt` until it succeeds, fails with a non-transient error, or the
+/// policy's attempts run out. Returns the last result and the retry count.
+fn with_retry<T>(
+    retry: Option<RetryPolicy>,
+    mut attempt: impl FnMut() -> Result<T>,
+) -> (Result<T>, u32) {
+    let max_attempts = retry.map(|p| p.max_attempts.max(1)).unwrap_or(1);
+    let mut retries = 0;
+    loop {
+        let result = attempt();
+        match (&result, retry) {
+            (Err(e), Some(policy)) if retries + 1 < max_attempts && is_transient_error(e) => {
+                retries += 1;
+                info!(
+                    "[MCP Client] Transient failure ({}); retry {} of {}",
+                    e,
+                    retries,
+                    max_attempts - 1
+                );
+                std::thread::sleep(policy.delay(retries));
+            }
+            _ => return (result, retries),
+        }
+    }
+}
+
+/// Async counterpart of [`with_retry`]
+async fn with_retry_async<T, F, Fut>(retry: Option<RetryPolicy>, mut attempt: F) -> (Result<T>, u32)
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    let max_attempts = retry.map(|p| p.max_attempts.max(1)).unwrap_or(1);
+    let mut retries = 0;
+    loop {
+        let result = attempt().await;
+        match (&result, retry) {
+            (Err(e), Some(policy)) if retries + 1 < max_attempts && is_transient_error(e) => {
+                retries += 1;
+                info!(
+                    "[MCP Client] Transient failure ({}); retry {} of {}",
+                    e,
+                    retries,
+                    max_attempts - 1
+                );
+                tokio::time::sleep(policy.delay(retries)).await;
+            }
+            _ => return (result, retries),
         }
     }
 }
@@ -144,6 +374,21 @@ struct JsonRpcRequest {
 struct JsonRpcNotification {
     jsonrpc: &'static str,
     method: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    params: Option<Value>,
+}
+
+/// `notifications/cancelled` for a request the client no longer wants answered
+fn cancelled_notification(request_id: u64, reason: Option<&str>) -> JsonRpcNotification {
+    let mut params = json!({ "requestId": request_id });
+    if let Some(reason) = reason {
+        params["reason"] = json!(reason);
+    }
+    JsonRpcNotification {
+        jsonrpc: "2.0",
+        method: "notifications/cancelled".to_string(),
+        params: Some(params),
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -158,67 +403,420 @@ struct JsonRpcResponse {
 
 #[derive(Debug, Deserialize)]
 struct JsonRpcError {
-    #[allow(dead_code)]
     code: i64,
     message: String,
-    #[allow(dead_code)]
     data: Option<Value>,
 }
 
+impl JsonRpcError {
+    /// Turn into an error displayed as `"{context}: {message}"` that keeps the
+    /// code and data for [`rpc_error_details`]
+    fn into_error(self, context: &'static str) -> anyhow::Error {
+        McpRpcError {
+            context,
+            code: self.code,
+            message: self.message,
+            data: self.data,
+        }
+        .into()
+    }
+}
+
+/// A JSON-RPC error response from the server
+#[derive(Debug)]
+struct McpRpcError {
+    context: &'static str,
+    code: i64,
+    message: String,
+    data: Option<Value>,
+}
+
+impl std::fmt::Display for McpRpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.context, self.message)
+    }
+}
+
+impl std::error::Error for McpRpcError {}
+
+/// The JSON-RPC error code and data behind `err`, if the server sent one
+fn rpc_error_details(err: &anyhow::Error) -> (Option<i64>, Option<Value>) {
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<McpRpcError>())
+        .map(|e| (Some(e.code), e.data.clone()))
+        .unwrap_or_default()
+}
+
+// ============================================================================
+// Resources and Prompts
+// ============================================================================
+
+/// Upper bound on `resources/list` or `prompts/list` pages followed, in case
+/// a server keeps returning a cursor
+const MAX_LIST_PAGES: usize = 100;
+
+/// Call `list_page` with each paginated list request's params, collecting
+/// the `items_key` array of every page and following `nextCursor` until the
+/// server stops returning one. A missing or null array counts as empty.
+fn collect_pages<T: DeserializeOwned>(
+    items_key: &str,
+    mut list_page: impl FnMut(Value) -> Result<Value>,
+) -> Result<Vec<T>> {
+    let mut collected = Vec::new();
+    let mut cursor: Option<String> = None;
+    for _ in 0..MAX_LIST_PAGES {
+        let params = match &cursor {
+            Some(cursor) => json!({ "cursor": cursor }),
+            None => json!({}),
+        };
+        let page = list_page(params)?;
+        if let Some(items) = page.get(items_key).filter(|items| !items.is_null()) {
+            let items: Vec<T> = serde_json::from_value(items.clone())
+                .map_err(|e| anyhow!("Invalid {} list: {}", items_key, e))?;
+            collected.extend(items);
+        }
+        cursor = page
+            .get("nextCursor")
+            .and_then(|c| c.as_str())
+            .map(|c| c.to_string());
+        if cursor.is_none() {
+            break;
+        }
+    }
+    Ok(collected)
+}
+
+/// The `contents` of a `resources/read` result. Text contents become
+/// `Resource` items and image blobs become `Image` items; other binary
+/// contents are kept as `Resource` items without a body.
+fn parse_resource_contents(result: &Value) -> Vec<ToolContent> {
+    let Some(items) = result.get("contents").and_then(|c| c.as_array()) else {
+        return vec![];
+    };
+    items
+        .iter()
+        .filter_map(|item| {
+            let uri = item.get("uri")?.as_str()?.to_string();
+            let mime_type = item
+                .get("mimeType")
+                .and_then(|m| m.as_str())
+                .map(|m| m.to_string());
+            let blob = item.get("blob").and_then(|b| b.as_str());
+            Some(match (blob, mime_type) {
+                (Some(data), Some(mime_type)) if mime_type.starts_with("image/") => {
+                    ToolContent::Image {
+                        data: data.to_string(),
+                        mime_type,
+                    }
+                }
+                (_, mime_type) => ToolContent::Resource {
+                    uri,
+                    mime_type,
+                    text: item
+                        .get("text")
+                        .and_then(|t| t.as_str())
+                        .map(|t| t.to_string()),
+                },
+            })
+        })
+        .collect()
+}
+
+/// The `messages` of a `prompts/get` result
+fn parse_prompt_messages(result: &Value) -> Result<Vec<McpPromptMessage>> {
+    match result.get("messages") {
+        Some(messages) if !messages.is_null() => serde_json::from_value(messages.clone())
+            .map_err(|e| anyhow!("Invalid prompts/get result: {}", e)),
+        _ => Ok(vec![]),
+    }
+}
+
+// ============================================================================
+// Initialize Handshake
+// ============================================================================
+
+/// Protocol version this client declares in `initialize`
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// Versions a server may negotiate that this client accepts
+const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// Sent on every HTTP request after `initialize` with the negotiated version
+const PROTOCOL_VERSION_HEADER: &str = "mcp-protocol-version";
+
+/// The version to send in [`PROTOCOL_VERSION_HEADER`]; `None` when the server
+/// didn't negotiate one
+fn negotiated_version(server_info: Option<&McpServerInfo>) -> Option<&str> {
+    server_info.and_then(|info| info.protocol_version.as_deref())
+}
+
+/// The protocol version a server chose in its `initialize` result; `None` if
+/// it didn't say. Errors if it's a version this client can't speak.
+fn negotiated_protocol_version(init_result: &Value) -> Result<Option<String>> {
+    let Some(version) = init_result.get("protocolVersion").and_then(|v| v.as_str()) else {
+        return Ok(None);
+    };
+    if !SUPPORTED_PROTOCOL_VERSIONS.contains(&version) {
+        return Err(anyhow!(
+            "Server negotiated MCP protocol version {}, which this client doesn't support (supported: {})",
+            version,
+            SUPPORTED_PROTOCOL_VERSIONS.join(", ")
+        ));
+    }
+    Ok(Some(version.to_string()))
+}
+
+/// The `serverInfo` of an `initialize` result along with the negotiated
+/// protocol version; `None` when the server sent neither
+fn parse_server_info(init_result: &Value) -> Result<Option<McpServerInfo>> {
+    let protocol_version = negotiated_protocol_version(init_result)?;
+    let info = init_result.get("serverInfo");
+    if info.is_none() && protocol_version.is_none() {
+        return Ok(None);
+    }
+    let field = |key: &str| info.and_then(|i| i.get(key)).and_then(|v| v.as_str());
+    Ok(Some(McpServerInfo {
+        name: field("name").unwrap_or("unknown").to_string(),
+        version: field("version").map(|s| s.to_string()),
+        protocol_version,
+    }))
+}
+
+// ============================================================================
+// Package Runner Auth Failures
+// ============================================================================
+
+/// A known stderr signature for an authentication failure raised by the
+/// package runner (npx, uvx, pip, cargo) that launches a stdio MCP.
+struct AuthFailureSignature {
+    tool: &'static str,
+    patterns: &'static [&'static str],
+    hint: &'static str,
+}
+
+/// Checked in order; the more specific runners come before npm because
+/// generic "401" wording appears in several of them.
+const AUTH_FAILURE_SIGNATURES: &[AuthFailureSignature] = &[
+    AuthFailureSignature {
+        tool: "uvx",
+        patterns: &[
+            "HTTP status client error (401",
+            "HTTP status client error (403",
+            "Missing credentials for",
+        ],
+        hint: "Re-authenticate uv against your package index:\n  uv auth login <index-url>\n\nOr set the index credentials:\n  export UV_INDEX_<NAME>_USERNAME=...\n  export UV_INDEX_<NAME>_PASSWORD=...",
+    },
+    AuthFailureSignature {
+        tool: "pip",
+        patterns: &[
+            "401 Client Error: Unauthorized",
+            "HTTP error 401 while getting",
+            "403 Client Error: Forbidden",
+        ],
+        hint: "Re-authenticate pip against your package index:\n  pip config set global.index-url https://<user>:<token>@<index-host>/simple\n\nOr refresh the credentials stored in your keyring / ~/.netrc",
+    },
+    AuthFailureSignature {
+        tool: "cargo",
+        patterns: &[
+            "failed to authenticate when downloading repository",
+            "no token found for",
+            "token rejected for",
+        ],
+        hint: "Re-authenticate cargo against the registry:\n  cargo login\n\nOr for a private registry:\n  cargo login --registry <name>",
+    },
+    AuthFailureSignature {
+        tool: "npm",
+        patterns: &[
+            "Access token expired",
+            "token revoked",
+            "npm ERR! code E401",
+            "npm error code E401",
+        ],
+        hint: "Try running:\n  npm logout\n  npm login\n\nOr remove the expired token:\n  npm config delete //registry.npmjs.org/:_authToken",
+    },
+];
+
+/// Scan captured stderr for a known package-runner auth failure and build an
+/// actionable error message for it.
+pub(crate) fn detect_auth_failure(stderr: &str) -> Option<String> {
+    AUTH_FAILURE_SIGNATURES.iter().find_map(|sig| {
+        let line = stderr
+            .lines()
+            .find(|line| sig.patterns.iter().any(|p| line.contains(p)))?;
+        Some(format!(
+            "{} authentication error: {}\n\n{}",
+            sig.tool,
+            line.trim(),
+            sig.hint
+        ))
+    })
+}
+
+/// How long a timed-out stdio server gets to read `notifications/cancelled`
+/// before it is killed
+const CANCEL_GRACE_PERIOD: Duration = Duration::from_millis(200);
+
+/// Upper bound on the stderr retained per stdio process
+const MAX_CAPTURED_STDERR: usize = 64 * 1024;
+
+/// Variables kept when a stdio server is spawned without inheriting the
+/// app's environment; enough for the shell to find and run the command
+#[cfg(not(windows))]
+const CLEAN_ENV_KEEP: &[&str] = &["PATH", "HOME"];
+#[cfg(windows)]
+const CLEAN_ENV_KEEP: &[&str] = &[
+    "PATH",
+    "HOME",
+    "USERPROFILE",
+    "SystemRoot",
+    "ComSpec",
+    "PATHEXT",
+];
+
 // ============================================================================
 // STDIO MCP Client
 // ============================================================================
 
+/// Shells an MCP's `shell` may name besides a custom absolute path
+pub const SPAWN_SHELLS: &[&str] = &["none", "sh", "bash", "zsh"];
+
+/// Check an MCP's `shell` setting: one of `SPAWN_SHELLS` or an absolute path
+pub fn validate_spawn_shell(shell: &str) -> Result<()> {
+    if SPAWN_SHELLS.contains(&shell) || std::path::Path::new(shell).is_absolute() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "Shell must be one of {} or an absolute path, got '{}'",
+            SPAWN_SHELLS.join(", "),
+            shell
+        ))
+    }
+}
+
+/// How to launch a stdio MCP server
+#[derive(Debug, Clone, Copy)]
+pub struct SpawnOptions<'a> {
+    pub command: &'a str,
+    pub args: &'a [String],
+    pub env: Option<&'a HashMap<String, String>>,
+    /// `.env` file whose variables `env` can override
+    pub env_file: Option<&'a str>,
+    /// Start from the app's environment rather than a clean one
+    pub inherit_env: bool,
+    /// One of [`SPAWN_SHELLS`] or an absolute path; `sh` when unset
+    pub shell: Option<&'a str>,
+}
+
+impl<'a> SpawnOptions<'a> {
+    /// Run `command` through the default shell with the app's environment
+    #[cfg(test)]
+    pub fn new(command: &'a str, args: &'a [String]) -> Self {
+        Self {
+            command,
+            args,
+            env: None,
+            env_file: None,
+            inherit_env: true,
+            shell: None,
+        }
+    }
+}
+
+/// Build the command that launches a stdio server. `shell` of `none` runs the
+/// command directly with its args; anything else runs the joined command line
+/// through that shell with `-c` (`sh` when unset). Windows runs it through
+/// `cmd /c` and accepts no other shell.
+fn spawn_command(command: &str, args: &[String], shell: Option<&str>) -> Result<Command> {
+    if shell == Some("none") {
+        let mut c = Command::new(command);
+        c.args(args);
+        return Ok(c);
+    }
+
+    let full_command = if args.is_empty() {
+        command.to_string()
+    } else {
+        format!("{} {}", command, args.join(" "))
+    };
+
+    // On Windows, run through cmd.exe to properly resolve PATH
+    #[cfg(windows)]
+    {
+        if let Some(shell) = shell {
+            return Err(anyhow!(
+                "Shell '{}' isn't supported on Windows; use 'none' or leave it unset",
+                shell
+            ));
+        }
+        let mut c = Command::new("cmd");
+        c.args(["/c", &full_command]);
+        Ok(c)
+    }
+
+    // On Unix, run through a shell for the same reason
+    #[cfg(not(windows))]
+    {
+        let mut c = Command::new(shell.unwrap_or("sh"));
+        c.args(["-c", &full_command]);
+        Ok(c)
+    }
+}
+
 /// Client for communicating with stdio-based MCP servers
 pub struct StdioMcpClient {
     child: Child,
+    ids: RequestIds,
+    /// Lines read from the child's stdout by a background reader thread
+    stdout_lines: Mutex<std::sync::mpsc::Receiver<String>>,
+    /// Stderr drained continuously so auth failures surface while the process is alive
+    stderr: Arc<Mutex<String>>,
     timeout: Duration,
     server_info: Option<McpServerInfo>,
     tools: Vec<McpTool>,
     resources_supported: bool,
     prompts_supported: bool,
+    /// Every non-empty stdout line read, when recording for diagnostics
+    transcript: Option<Vec<String>>,
 }
 
 impl StdioMcpClient {
-    fn spawn_process(
-        command: &str,
-        args: &[String],
-        env: Option<&HashMap<String, String>>,
-        timeout_secs: u64,
-    ) -> Result<Self> {
-        info!("[MCP Client] Spawning process: {} {:?}", command, args);
-
-        // On Windows, run through cmd.exe to properly resolve PATH
-        // On Unix, run through sh -c for the same reason
-        #[cfg(windows)]
-        let mut cmd = {
-            let mut c = Command::new("cmd");
-            // Build the full command string
-            let full_command = if args.is_empty() {
-                command.to_string()
-            } else {
-                format!("{} {}", command, args.join(" "))
-            };
-            c.args(["/c", &full_command]);
-            c
-        };
+    fn spawn_process(options: &SpawnOptions, timeout_secs: u64) -> Result<Self> {
+        let SpawnOptions {
+            command,
+            args,
+            env,
+            env_file,
+            inherit_env,
+            shell,
+        } = *options;
+        info!(
+            "[MCP Client] Spawning process: {} {:?} (shell: {})",
+            command,
+            args,
+            shell.unwrap_or("sh")
+        );
 
-        #[cfg(not(windows))]
-        let mut cmd = {
-            let mut c = Command::new("sh");
-            let full_command = if args.is_empty() {
-                command.to_string()
-            } else {
-                format!("{} {}", command, args.join(" "))
-            };
-            c.args(["-c", &full_command]);
-            c
-        };
+        let mut cmd = spawn_command(command, args, shell)?;
 
         cmd.stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
+        // A clean environment keeps only what's needed to launch the command
+        if !inherit_env {
+            cmd.env_clear();
+            for key in CLEAN_ENV_KEEP {
+                if let Some(value) = std::env::var_os(key) {
+                    cmd.env(key, value);
+                }
+            }
+        }
+
+        // Variables from the env file first, so the explicit env map wins
+        if let Some(path) = env_file {
+            cmd.envs(load_env_file(std::path::Path::new(path))?);
+        }
+
         // Add environment variables
         if let Some(env_vars) = env {
             for (key, value) in env_vars {
@@ -233,7 +831,7 @@ impl StdioMcpClient {
             cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
         }
 
-        let child = cmd.spawn().map_err(|e| {
+        let mut child = cmd.spawn().map_err(|e| {
             anyhow!(
                 "Failed to spawn MCP process '{}': {}. Make sure the command is installed and in PATH.",
                 command,
@@ -241,24 +839,57 @@ impl StdioMcpClient {
             )
         })?;
 
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| anyhow!("Failed to get stdout"))?;
+        let (stdout_tx, stdout_lines) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                match line {
+                    Ok(line) => {
+                        if stdout_tx.send(line).is_err() {
+                            break;
+                        }
+                    }
+                    Err(_) => break,
+                }
+            }
+        });
+
+        let stderr = Arc::new(Mutex::new(String::new()));
+        if let Some(child_stderr) = child.stderr.take() {
+            let stderr_buf = stderr.clone();
+            std::thread::spawn(move || {
+                for line in BufReader::new(child_stderr).lines() {
+                    let Ok(line) = line else { break };
+                    if let Ok(mut buf) = stderr_buf.lock() {
+                        if buf.len() < MAX_CAPTURED_STDERR {
+                            buf.push_str(&line);
+                            buf.push('\n');
+                        }
+                    }
+                }
+            });
+        }
+
         Ok(Self {
             child,
+            ids: RequestIds::new(),
+            stdout_lines: Mutex::new(stdout_lines),
+            stderr,
             timeout: Duration::from_secs(timeout_secs),
             server_info: None,
             tools: vec![],
             resources_supported: false,
             prompts_supported: false,
+            transcript: None,
         })
     }
 
     /// Spawn and initialize an MCP client, returning a fully connected session
-    pub fn spawn(
-        command: &str,
-        args: &[String],
-        env: Option<&HashMap<String, String>>,
-        timeout_secs: u64,
-    ) -> Result<Self> {
-        let mut client = Self::spawn_process(command, args, env, timeout_secs)?;
+    pub fn spawn(options: &SpawnOptions, timeout_secs: u64) -> Result<Self> {
+        let mut client = Self::spawn_process(options, timeout_secs)?;
         client.initialize()?;
         Ok(client)
     }
@@ -267,7 +898,7 @@ impl StdioMcpClient {
     fn initialize(&mut self) -> Result<()> {
         info!("[MCP Client] Sending initialize request...");
         let init_params = json!({
-            "protocolVersion": "2024-11-05",
+            "protocolVersion": PROTOCOL_VERSION,
             "capabilities": {},
             "clientInfo": {
                 "name": "claude-code-tool-manager",
@@ -278,17 +909,7 @@ impl StdioMcpClient {
         let init_result = self.send_request("initialize", Some(init_params))?;
 
         // Parse server info and capabilities
-        self.server_info = init_result.get("serverInfo").map(|info| McpServerInfo {
-            name: info
-                .get("name")
-                .and_then(|v| v.as_str())
-                .unwrap_or("unknown")
-                .to_string(),
-            version: info
-                .get("version")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string()),
-        });
+        self.server_info = parse_server_info(&init_result)?;
 
         let capabilities = init_result.get("capabilities");
         self.resources_supported = capabilities.and_then(|c| c.get("resources")).is_some();
@@ -339,7 +960,8 @@ impl StdioMcpClient {
     pub fn call_tool(&mut self, name: &str, arguments: Value) -> Result<ToolCallResult> {
         info!(
             "[MCP Client] Calling tool: {} with args: {}",
-            name, arguments
+            name,
+            redact_value(&arguments)
         );
 
         let params = json!({
@@ -347,20 +969,75 @@ impl StdioMcpClient {
             "arguments": arguments
         });
 
+        let id = self.ids.next();
         let start = Instant::now();
-        let result = self.send_request("tools/call", Some(params));
+        let result = self.send_request_with_id(id, "tools/call", Some(params));
+        if result.is_err() && start.elapsed() >= self.timeout {
+            self.abort_timed_out_call(id);
+        }
         let elapsed = start.elapsed().as_millis() as u64;
 
         match result {
             Ok(response) => Self::parse_tool_result(response, elapsed),
-            Err(e) => Ok(ToolCallResult {
-                success: false,
-                content: vec![],
-                is_error: true,
-                error: Some(e.to_string()),
-                execution_time_ms: elapsed,
-            }),
+            Err(e) => Ok(ToolCallResult::failed(&e, elapsed)),
+        }
+    }
+
+    /// Cancel a tool call that hit the timeout, then stop the server so it
+    /// can't carry on with the operation. The session is unusable afterwards.
+    fn abort_timed_out_call(&mut self, request_id: u64) {
+        let notification = cancelled_notification(request_id, Some("Request timed out"));
+        if let Err(e) = self.write_notification(&notification) {
+            error!(
+                "[MCP Client] Failed to cancel request {}: {}",
+                request_id, e
+            );
+        }
+        // Let the server read the cancellation before it is killed
+        std::thread::sleep(CANCEL_GRACE_PERIOD);
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+
+    /// List the server's resources; empty when it doesn't advertise any
+    pub fn list_resources(&mut self) -> Result<Vec<McpResource>> {
+        if !self.resources_supported {
+            return Ok(vec![]);
         }
+        collect_pages("resources", |params| {
+            self.send_request("resources/list", Some(params))
+        })
+    }
+
+    /// Read the contents of the resource at `uri`
+    pub fn read_resource(&mut self, uri: &str) -> Result<Vec<ToolContent>> {
+        let result = self.send_request("resources/read", Some(json!({ "uri": uri })))?;
+        Ok(parse_resource_contents(&result))
+    }
+
+    /// List the server's prompt templates; empty when it doesn't advertise any
+    pub fn list_prompts(&mut self) -> Result<Vec<McpPrompt>> {
+        if !self.prompts_supported {
+            return Ok(vec![]);
+        }
+        collect_pages("prompts", |params| {
+            self.send_request("prompts/list", Some(params))
+        })
+    }
+
+    /// Render the prompt `name` with `arguments` (an object of strings)
+    pub fn get_prompt(&mut self, name: &str, arguments: Value) -> Result<Vec<McpPromptMessage>> {
+        let params = json!({
+            "name": name,
+            "arguments": arguments
+        });
+        let result = self.send_request("prompts/get", Some(params))?;
+        parse_prompt_messages(&result)
+    }
+
+    /// Send an MCP `ping` request; errors if the server doesn't answer
+    pub fn ping(&mut self) -> Result<()> {
+        self.send_request("ping", None).map(|_| ())
     }
 
     /// Parse the result of a tool call
@@ -381,18 +1058,30 @@ impl StdioMcpClient {
             content,
             is_error,
             error: None,
+            error_code: None,
+            error_data: None,
             execution_time_ms: elapsed,
+            cached: false,
         })
     }
 
     fn send_request(&mut self, method: &str, params: Option<Value>) -> Result<Value> {
+        let id = self.ids.next();
+        self.send_request_with_id(id, method, params)
+    }
+
+    fn send_request_with_id(
+        &mut self,
+        id: u64,
+        method: &str,
+        params: Option<Value>,
+    ) -> Result<Value> {
         let stdin = self
             .child
             .stdin
             .as_mut()
             .ok_or_else(|| anyhow!("Failed to get stdin"))?;
 
-        let id = next_request_id();
         let request = JsonRpcRequest {
             jsonrpc: "2.0",
             id,
@@ -401,7 +1090,10 @@ impl StdioMcpClient {
         };
 
         let request_str = serde_json::to_string(&request)?;
-        info!("[MCP Client] Sending request: {}", request_str);
+        info!(
+            "[MCP Client] Sending request: {}",
+            redact_body(&request_str)
+        );
 
         writeln!(stdin, "{}", request_str)?;
         stdin.flush()?;
@@ -410,19 +1102,25 @@ impl StdioMcpClient {
     }
 
     fn send_notification(&mut self, method: &str) -> Result<()> {
+        self.write_notification(&JsonRpcNotification {
+            jsonrpc: "2.0",
+            method: method.to_string(),
+            params: None,
+        })
+    }
+
+    fn write_notification(&mut self, notification: &JsonRpcNotification) -> Result<()> {
         let stdin = self
             .child
             .stdin
             .as_mut()
             .ok_or_else(|| anyhow!("Failed to get stdin"))?;
 
-        let notification = JsonRpcNotification {
-            jsonrpc: "2.0",
-            method: method.to_string(),
-        };
-
-        let notification_str = serde_json::to_string(&notification)?;
-        info!("[MCP Client] Sending notification: {}", notification_str);
+        let notification_str = serde_json::to_string(notification)?;
+        info!(
+            "[MCP Client] Sending notification: {}",
+            redact_body(&notification_str)
+        );
 
         writeln!(stdin, "{}", notification_str)?;
         stdin.flush()?;
@@ -431,86 +1129,299 @@ impl StdioMcpClient {
     }
 
     fn read_response(&mut self, expected_id: u64) -> Result<Value> {
-        let stdout = self
-            .child
-            .stdout
-            .as_mut()
-            .ok_or_else(|| anyhow!("Failed to get stdout"))?;
-
-        let mut reader = BufReader::new(stdout);
         let start = Instant::now();
 
         loop {
-            if start.elapsed() > self.timeout {
+            if let Some(auth_error) = self.stderr_auth_failure() {
+                return Err(anyhow!(auth_error));
+            }
+
+            let remaining = self.timeout.saturating_sub(start.elapsed());
+            if remaining.is_zero() {
                 return Err(anyhow!(
                     "Timeout waiting for response ({}s)",
                     self.timeout.as_secs()
                 ));
             }
 
-            let mut line = String::new();
-            match reader.read_line(&mut line) {
-                Ok(0) => {
-                    // EOF - check stderr for error message
-                    if let Some(stderr) = self.child.stderr.as_mut() {
-                        let mut stderr_reader = BufReader::new(stderr);
-                        let mut stderr_content = String::new();
-                        let _ = stderr_reader.read_line(&mut stderr_content);
-                        if !stderr_content.is_empty() {
-                            let stderr_msg = stderr_content.trim();
-                            // Check for common npm authentication errors
-                            if stderr_msg.contains("Access token expired")
-                                || stderr_msg.contains("token revoked")
-                            {
-                                return Err(anyhow!(
-                                    "npm authentication error: {}\n\nTry running:\n  npm logout\n  npm login\n\nOr remove the expired token:\n  npm config delete //registry.npmjs.org/:_authToken",
-                                    stderr_msg
-                                ));
-                            }
-                            return Err(anyhow!("Process stderr: {}", stderr_msg));
-                        }
-                    }
-                    return Err(anyhow!("Process closed stdout unexpectedly"));
+            let received = self
+                .stdout_lines
+                .get_mut()
+                .map_err(|_| anyhow!("stdout reader lock poisoned"))?
+                .recv_timeout(remaining.min(Duration::from_millis(100)));
+            let line = match received {
+                Ok(line) => line,
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => continue,
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+                    return Err(self.stdout_closed_error());
                 }
-                Ok(_) => {
-                    let line = line.trim();
-                    if line.is_empty() {
-                        continue;
-                    }
+            };
 
-                    info!("[MCP Client] Received: {}", line);
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
 
-                    // Try to parse as JSON-RPC response
-                    match serde_json::from_str::<JsonRpcResponse>(line) {
-                        Ok(response) => {
-                            // Check if this is a response to our request
-                            if let Some(id) = response.id {
-                                if id == expected_id {
-                                    if let Some(error) = response.error {
-                                        return Err(anyhow!("MCP error: {}", error.message));
-                                    }
-                                    return response
-                                        .result
-                                        .ok_or_else(|| anyhow!("Empty result in response"));
-                                }
+            info!("[MCP Client] Received: {}", redact_body(line));
+            if let Some(transcript) = self.transcript.as_mut() {
+                transcript.push(line.to_string());
+            }
+
+            // Try to parse as JSON-RPC response
+            match serde_json::from_str::<JsonRpcResponse>(line) {
+                Ok(response) => {
+                    // Check if this is a response to our request
+                    if let Some(id) = response.id {
+                        if id == expected_id {
+                            if let Some(error) = response.error {
+                                return Err(error.into_error("MCP error"));
                             }
-                            // Not our response, might be a notification - continue reading
-                        }
-                        Err(_) => {
-                            // Not valid JSON-RPC, might be debug output - continue
+                            return response
+                                .result
+                                .ok_or_else(|| anyhow!("Empty result in response"));
                         }
                     }
+                    // Not our response, might be a notification - continue reading
+                }
+                Err(_) => {
+                    // Not valid JSON-RPC, might be debug output - continue
                 }
-                Err(e) => return Err(anyhow!("Failed to read stdout: {}", e)),
             }
         }
     }
 
+    /// Check the stderr captured so far for a package-runner auth failure
+    fn stderr_auth_failure(&self) -> Option<String> {
+        let stderr = self.stderr.lock().ok()?;
+        detect_auth_failure(&stderr)
+    }
+
+    /// Build the error for a process that closed stdout before answering
+    fn stdout_closed_error(&mut self) -> anyhow::Error {
+        // Give the stderr thread a moment to drain what the process wrote before exiting
+        let deadline = Instant::now() + Duration::from_millis(500);
+        while Instant::now() < deadline {
+            if matches!(self.child.try_wait(), Ok(Some(_))) {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        std::thread::sleep(Duration::from_millis(50));
+
+        let stderr = self
+            .stderr
+            .lock()
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default();
+        if let Some(auth_error) = detect_auth_failure(&stderr) {
+            return anyhow!(auth_error);
+        }
+        if !stderr.is_empty() {
+            return anyhow!("Process stderr: {}", stderr);
+        }
+        anyhow!("Process closed stdout unexpectedly")
+    }
+
     /// Close the client and terminate the process
     pub fn close(mut self) {
         let _ = self.child.kill();
         let _ = self.child.wait();
     }
+
+    /// Whether the server process is still running
+    pub fn is_alive(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+}
+
+// ============================================================================
+// STDIO Connection Pool
+// ============================================================================
+
+/// How long an unused stdio session stays in [`STDIO_POOL`]
+const STDIO_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// App-wide pool behind stdio sessions; emptied when the app exits
+pub static STDIO_POOL: once_cell::sync::Lazy<StdioConnectionPool> =
+    once_cell::sync::Lazy::new(|| StdioConnectionPool::new(STDIO_POOL_IDLE_TIMEOUT));
+
+/// Pool key for a stdio server: a hash of how it is launched
+fn stdio_pool_key(options: &SpawnOptions) -> u64 {
+    use std::hash::{Hash, Hasher};
+
+    let mut env: Vec<(&String, &String)> =
+        options.env.map(|e| e.iter().collect()).unwrap_or_default();
+    env.sort();
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    (
+        options.command,
+        options.args,
+        env,
+        options.env_file,
+        options.inherit_env,
+        options.shell,
+    )
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+struct PooledStdio {
+    client: StdioMcpClient,
+    last_used: Instant,
+}
+
+/// Keeps initialized stdio sessions alive between tool calls, keyed by how
+/// the server is launched. A session is checked out while its guard is held;
+/// a second caller for the same server meanwhile gets its own process, and
+/// only one of the two is kept when both are returned.
+pub struct StdioConnectionPool {
+    clients: Mutex<HashMap<u64, PooledStdio>>,
+    idle_timeout: Duration,
+}
+
+impl StdioConnectionPool {
+    pub fn new(idle_timeout: Duration) -> Self {
+        Self {
+            clients: Mutex::new(HashMap::new()),
+            idle_timeout,
+        }
+    }
+
+    /// Check out the pooled session for this server, spawning one if there is
+    /// none or the pooled process has exited
+    pub fn get_or_spawn(
+        &self,
+        options: &SpawnOptions,
+        timeout_secs: u64,
+    ) -> Result<PooledStdioClient<'_>> {
+        self.evict_idle();
+        let key = stdio_pool_key(options);
+
+        let mut pooled = self.lock()?.remove(&key).map(|p| p.client);
+        if pooled.as_mut().is_some_and(|client| !client.is_alive()) {
+            info!(
+                "[Stdio Pool] Process for '{}' exited; respawning",
+                options.command
+            );
+            if let Some(client) = pooled.take() {
+                client.close();
+            }
+        }
+        let client = match pooled {
+            Some(client) => client,
+            None => StdioMcpClient::spawn(options, timeout_secs)?,
+        };
+
+        Ok(PooledStdioClient {
+            pool: self,
+            key,
+            client: Some(client),
+        })
+    }
+
+    /// Close sessions unused for longer than the idle timeout. Returns how
+    /// many were closed.
+    pub fn evict_idle(&self) -> usize {
+        let Ok(mut clients) = self.lock() else {
+            return 0;
+        };
+        let idle: Vec<u64> = clients
+            .iter()
+            .filter(|(_, pooled)| pooled.last_used.elapsed() > self.idle_timeout)
+            .map(|(key, _)| *key)
+            .collect();
+        for key in &idle {
+            if let Some(pooled) = clients.remove(key) {
+                pooled.client.close();
+            }
+        }
+        idle.len()
+    }
+
+    /// Kill every pooled process; used on shutdown
+    pub fn close_all(&self) {
+        let Ok(mut clients) = self.lock() else {
+            return;
+        };
+        for (_, pooled) in clients.drain() {
+            pooled.client.close();
+        }
+    }
+
+    /// Number of sessions currently idle in the pool
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.lock().map(|c| c.len()).unwrap_or(0)
+    }
+
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, HashMap<u64, PooledStdio>>> {
+        self.clients
+            .lock()
+            .map_err(|_| anyhow!("stdio pool lock poisoned"))
+    }
+
+    fn check_in(&self, key: u64, mut client: StdioMcpClient) {
+        if !client.is_alive() {
+            client.close();
+            return;
+        }
+        let Ok(mut clients) = self.lock() else {
+            client.close();
+            return;
+        };
+        if clients.contains_key(&key) {
+            client.close();
+            return;
+        }
+        clients.insert(
+            key,
+            PooledStdio {
+                client,
+                last_used: Instant::now(),
+            },
+        );
+    }
+}
+
+impl Drop for StdioConnectionPool {
+    fn drop(&mut self) {
+        self.close_all();
+    }
+}
+
+/// A stdio session checked out of a [`StdioConnectionPool`]; returned to the
+/// pool when dropped
+pub struct PooledStdioClient<'a> {
+    pool: &'a StdioConnectionPool,
+    key: u64,
+    client: Option<StdioMcpClient>,
+}
+
+impl std::ops::Deref for PooledStdioClient<'_> {
+    type Target = StdioMcpClient;
+
+    fn deref(&self) -> &StdioMcpClient {
+        self.client.as_ref().expect("client present until drop")
+    }
+}
+
+impl std::ops::DerefMut for PooledStdioClient<'_> {
+    fn deref_mut(&mut self) -> &mut StdioMcpClient {
+        self.client.as_mut().expect("client present until drop")
+    }
+}
+
+impl Drop for PooledStdioClient<'_> {
+    fn drop(&mut self) {
+        if let Some(client) = self.client.take() {
+            self.pool.check_in(self.key, client);
+        }
+    }
 }
 
 // ============================================================================
@@ -519,6 +1430,7 @@ impl StdioMcpClient {
 
 /// Client for communicating with HTTP-based MCP servers
 pub struct HttpMcpClient {
+    ids: RequestIds,
     client: reqwest::blocking::Client,
     url: String,
     session_id: Option<String>,
@@ -539,10 +1451,12 @@ impl HttpMcpClient {
         info!("[HTTP MCP Client] Connecting to: {}", url);
 
         let client = reqwest::blocking::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
             .timeout(Duration::from_secs(timeout_secs))
             .build()?;
 
         let mut instance = Self {
+            ids: RequestIds::new(),
             client,
             url: url.to_string(),
             session_id: None,
@@ -562,10 +1476,10 @@ impl HttpMcpClient {
 
         let init_request = json!({
             "jsonrpc": "2.0",
-            "id": next_request_id(),
+            "id": self.ids.next(),
             "method": "initialize",
             "params": {
-                "protocolVersion": "2024-11-05",
+                "protocolVersion": PROTOCOL_VERSION,
                 "capabilities": {},
                 "clientInfo": {
                     "name": "claude-code-tool-manager",
@@ -582,20 +1496,8 @@ impl HttpMcpClient {
             info!("[HTTP MCP Client] Got session ID: {:?}", self.session_id);
         }
 
-        // Parse server info
-        if let Some(info) = response.body.get("serverInfo") {
-            self.server_info = Some(McpServerInfo {
-                name: info
-                    .get("name")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("unknown")
-                    .to_string(),
-                version: info
-                    .get("version")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string()),
-            });
-        }
+        // Parse server info and the negotiated protocol version
+        self.server_info = parse_server_info(&response.body)?;
 
         // Parse capabilities
         if let Some(caps) = response.body.get("capabilities") {
@@ -612,7 +1514,7 @@ impl HttpMcpClient {
         info!("[HTTP MCP Client] Requesting tools list...");
         let tools_request = json!({
             "jsonrpc": "2.0",
-            "id": next_request_id(),
+            "id": self.ids.next(),
             "method": "tools/list",
             "params": {}
         });
@@ -650,12 +1552,13 @@ impl HttpMcpClient {
     pub fn call_tool(&mut self, name: &str, arguments: Value) -> Result<ToolCallResult> {
         info!(
             "[HTTP MCP Client] Calling tool: {} with args: {}",
-            name, arguments
+            name,
+            redact_value(&arguments)
         );
 
         let request = json!({
             "jsonrpc": "2.0",
-            "id": next_request_id(),
+            "id": self.ids.next(),
             "method": "tools/call",
             "params": {
                 "name": name,
@@ -669,22 +1572,55 @@ impl HttpMcpClient {
 
         match result {
             Ok(response) => StdioMcpClient::parse_tool_result(response.body, elapsed),
-            Err(e) => Ok(ToolCallResult {
-                success: false,
-                content: vec![],
-                is_error: true,
-                error: Some(e.to_string()),
-                execution_time_ms: elapsed,
-            }),
+            Err(e) => Ok(ToolCallResult::failed(&e, elapsed)),
         }
     }
 
+    /// List the server's resources; empty when it doesn't advertise any
+    pub fn list_resources(&self) -> Result<Vec<McpResource>> {
+        if !self.resources_supported {
+            return Ok(vec![]);
+        }
+        collect_pages("resources", |params| self.request("resources/list", params))
+    }
+
+    /// Read the contents of the resource at `uri`
+    pub fn read_resource(&self, uri: &str) -> Result<Vec<ToolContent>> {
+        let result = self.request("resources/read", json!({ "uri": uri }))?;
+        Ok(parse_resource_contents(&result))
+    }
+
+    /// List the server's prompt templates; empty when it doesn't advertise any
+    pub fn list_prompts(&self) -> Result<Vec<McpPrompt>> {
+        if !self.prompts_supported {
+            return Ok(vec![]);
+        }
+        collect_pages("prompts", |params| self.request("prompts/list", params))
+    }
+
+    /// Render the prompt `name` with `arguments` (an object of strings)
+    pub fn get_prompt(&self, name: &str, arguments: Value) -> Result<Vec<McpPromptMessage>> {
+        let params = json!({
+            "name": name,
+            "arguments": arguments
+        });
+        let result = self.request("prompts/get", params)?;
+        parse_prompt_messages(&result)
+    }
+
+    fn request(&self, method: &str, params: Value) -> Result<Value> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": self.ids.next(),
+            "method": method,
+            "params": params
+        });
+        self.send_request(&request).map(|response| response.body)
+    }
+
     fn send_request(&self, request: &Value) -> Result<HttpResponse> {
         let body = serde_json::to_string(request)?;
-        info!(
-            "[HTTP MCP Client] Sending: {}",
-            &body[..body.len().min(200)]
-        );
+        info!("[HTTP MCP Client] Sending: {}", body_snippet(&body, 200));
 
         let mut builder = self
             .client
@@ -696,6 +1632,9 @@ impl HttpMcpClient {
         if let Some(sid) = &self.session_id {
             builder = builder.header("mcp-session-id", sid);
         }
+        if let Some(version) = negotiated_version(self.server_info.as_ref()) {
+            builder = builder.header(PROTOCOL_VERSION_HEADER, version);
+        }
 
         if let Some(hdrs) = &self.headers {
             for (key, value) in hdrs {
@@ -703,15 +1642,18 @@ impl HttpMcpClient {
             }
         }
 
-        let response = builder
-            .send()
-            .map_err(|e| anyhow!("HTTP request failed: {}", e))?;
+        let response =
+            send_following_redirects(builder).map_err(|e| anyhow!("HTTP request failed: {}", e))?;
         let headers = response.headers().clone();
         let status = response.status();
 
         if !status.is_success() {
             let body = response.text().unwrap_or_default();
-            return Err(anyhow!("HTTP error {}: {}", status, body));
+            return Err(anyhow!(
+                "HTTP error {}: {}",
+                status,
+                body_snippet(&body, 500)
+            ));
         }
 
         let content_type = headers
@@ -721,10 +1663,7 @@ impl HttpMcpClient {
             .to_string();
 
         let text = response.text()?;
-        info!(
-            "[HTTP MCP Client] Response: {}",
-            &text[..text.len().min(200)]
-        );
+        info!("[HTTP MCP Client] Response: {}", body_snippet(&text, 200));
 
         let json_response: JsonRpcResponse = if content_type.contains("text/event-stream") {
             parse_sse_response(&text)?
@@ -733,7 +1672,7 @@ impl HttpMcpClient {
         };
 
         if let Some(error) = json_response.error {
-            return Err(anyhow!("MCP error: {}", error.message));
+            return Err(error.into_error("MCP error"));
         }
 
         Ok(HttpResponse {
@@ -759,6 +1698,9 @@ impl HttpMcpClient {
         if let Some(sid) = &self.session_id {
             builder = builder.header("mcp-session-id", sid);
         }
+        if let Some(version) = negotiated_version(self.server_info.as_ref()) {
+            builder = builder.header(PROTOCOL_VERSION_HEADER, version);
+        }
 
         if let Some(hdrs) = &self.headers {
             for (key, value) in hdrs {
@@ -766,7 +1708,7 @@ impl HttpMcpClient {
             }
         }
 
-        let _ = builder.send();
+        let _ = send_following_redirects(builder);
         Ok(())
     }
 
@@ -787,6 +1729,7 @@ struct HttpResponse {
 
 /// Client for communicating with SSE-based MCP servers
 pub struct SseMcpClient {
+    ids: RequestIds,
     client: reqwest::blocking::Client,
     messages_endpoint: String,
     headers: Option<HashMap<String, String>>,
@@ -807,6 +1750,7 @@ impl SseMcpClient {
 
         // Use a short timeout for the initial connection since we'll read incrementally
         let client = reqwest::blocking::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
             .connect_timeout(Duration::from_secs(timeout_secs))
             .build()?;
 
@@ -820,10 +1764,11 @@ impl SseMcpClient {
         }
 
         info!("[SSE MCP Client] Connecting to SSE endpoint via GET...");
-        let response = request_builder
-            .send()
+        let response = send_following_redirects(request_builder)
             .map_err(|e| anyhow!("SSE connection failed: {}", e))?;
 
+        // Relative message endpoints resolve against the post-redirect URL
+        let stream_url = response.url().clone();
         let status = response.status();
         if !status.is_success() {
             return Err(anyhow!("SSE connection failed with status {}", status));
@@ -866,10 +1811,14 @@ impl SseMcpClient {
                 Ok(_) => {
                     buffer.push_str(&line);
                     let trimmed = line.trim();
-                    info!("[SSE MCP Client] SSE line: {}", trimmed);
+                    debug!("[SSE MCP Client] SSE line: {}", redact_body(trimmed));
 
                     if let Some(event) = parse_sse_line(trimmed, &mut current_event) {
-                        info!("[SSE MCP Client] Parsed event: {:?}", event);
+                        debug!(
+                            "[SSE MCP Client] Parsed {} event: {}",
+                            event.event_type.as_deref().unwrap_or("message"),
+                            body_snippet(event.data.as_deref().unwrap_or_default(), 200)
+                        );
                         if event.event_type.as_deref() == Some("endpoint") {
                             if let Some(data) = &event.data {
                                 // Parse the endpoint URL - may be JSON-encoded
@@ -918,8 +1867,7 @@ impl SseMcpClient {
             if endpoint_url.starts_with("http://") || endpoint_url.starts_with("https://") {
                 endpoint_url
             } else {
-                let base_url = reqwest::Url::parse(url)?;
-                base_url.join(&endpoint_url)?.to_string()
+                stream_url.join(&endpoint_url)?.to_string()
             };
 
         info!(
@@ -929,10 +1877,12 @@ impl SseMcpClient {
 
         // Create a new client for the session (the reader consumed the response)
         let session_client = reqwest::blocking::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
             .timeout(Duration::from_secs(timeout_secs))
             .build()?;
 
         let mut instance = Self {
+            ids: RequestIds::new(),
             client: session_client,
             messages_endpoint: full_endpoint_url,
             headers: headers.cloned(),
@@ -951,10 +1901,10 @@ impl SseMcpClient {
 
         let init_request = json!({
             "jsonrpc": "2.0",
-            "id": next_request_id(),
+            "id": self.ids.next(),
             "method": "initialize",
             "params": {
-                "protocolVersion": "2024-11-05",
+                "protocolVersion": PROTOCOL_VERSION,
                 "capabilities": {},
                 "clientInfo": {
                     "name": "claude-code-tool-manager",
@@ -967,20 +1917,8 @@ impl SseMcpClient {
         // In blocking mode, we'll use synchronous POST and read the direct response
         let response = self.send_request(&init_request)?;
 
-        // Parse server info
-        if let Some(info) = response.get("serverInfo") {
-            self.server_info = Some(McpServerInfo {
-                name: info
-                    .get("name")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("unknown")
-                    .to_string(),
-                version: info
-                    .get("version")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string()),
-            });
-        }
+        // Parse server info and the negotiated protocol version
+        self.server_info = parse_server_info(&response)?;
 
         // Parse capabilities
         if let Some(caps) = response.get("capabilities") {
@@ -997,7 +1935,7 @@ impl SseMcpClient {
         info!("[SSE MCP Client] Requesting tools list...");
         let tools_request = json!({
             "jsonrpc": "2.0",
-            "id": next_request_id(),
+            "id": self.ids.next(),
             "method": "tools/list",
             "params": {}
         });
@@ -1035,12 +1973,13 @@ impl SseMcpClient {
     pub fn call_tool(&mut self, name: &str, arguments: Value) -> Result<ToolCallResult> {
         info!(
             "[SSE MCP Client] Calling tool: {} with args: {}",
-            name, arguments
+            name,
+            redact_value(&arguments)
         );
 
         let request = json!({
             "jsonrpc": "2.0",
-            "id": next_request_id(),
+            "id": self.ids.next(),
             "method": "tools/call",
             "params": {
                 "name": name,
@@ -1054,14 +1993,50 @@ impl SseMcpClient {
 
         match result {
             Ok(response) => StdioMcpClient::parse_tool_result(response, elapsed),
-            Err(e) => Ok(ToolCallResult {
-                success: false,
-                content: vec![],
-                is_error: true,
-                error: Some(e.to_string()),
-                execution_time_ms: elapsed,
-            }),
+            Err(e) => Ok(ToolCallResult::failed(&e, elapsed)),
+        }
+    }
+
+    /// List the server's resources; empty when it doesn't advertise any
+    pub fn list_resources(&self) -> Result<Vec<McpResource>> {
+        if !self.resources_supported {
+            return Ok(vec![]);
+        }
+        collect_pages("resources", |params| self.request("resources/list", params))
+    }
+
+    /// Read the contents of the resource at `uri`
+    pub fn read_resource(&self, uri: &str) -> Result<Vec<ToolContent>> {
+        let result = self.request("resources/read", json!({ "uri": uri }))?;
+        Ok(parse_resource_contents(&result))
+    }
+
+    /// List the server's prompt templates; empty when it doesn't advertise any
+    pub fn list_prompts(&self) -> Result<Vec<McpPrompt>> {
+        if !self.prompts_supported {
+            return Ok(vec![]);
         }
+        collect_pages("prompts", |params| self.request("prompts/list", params))
+    }
+
+    /// Render the prompt `name` with `arguments` (an object of strings)
+    pub fn get_prompt(&self, name: &str, arguments: Value) -> Result<Vec<McpPromptMessage>> {
+        let params = json!({
+            "name": name,
+            "arguments": arguments
+        });
+        let result = self.request("prompts/get", params)?;
+        parse_prompt_messages(&result)
+    }
+
+    fn request(&self, method: &str, params: Value) -> Result<Value> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": self.ids.next(),
+            "method": method,
+            "params": params
+        });
+        self.send_request(&request)
     }
 
     fn send_request(&self, request: &Value) -> Result<Value> {
@@ -1069,7 +2044,7 @@ impl SseMcpClient {
         info!(
             "[SSE MCP Client] Sending to {}: {}",
             self.messages_endpoint,
-            &body[..body.len().min(200)]
+            body_snippet(&body, 200)
         );
 
         let mut builder = self
@@ -1083,9 +2058,7 @@ impl SseMcpClient {
             }
         }
 
-        let response = builder
-            .body(body)
-            .send()
+        let response = send_following_redirects(builder.body(body))
             .map_err(|e| anyhow!("SSE POST request failed: {}", e))?;
 
         let status = response.status();
@@ -1095,15 +2068,16 @@ impl SseMcpClient {
         // For simplicity in blocking mode, we'll accept both 200 and 202
         if !status.is_success() && status.as_u16() != 202 {
             let body = response.text().unwrap_or_default();
-            return Err(anyhow!("SSE POST error {}: {}", status, body));
+            return Err(anyhow!(
+                "SSE POST error {}: {}",
+                status,
+                body_snippet(&body, 500)
+            ));
         }
 
         // Read the response body
         let text = response.text()?;
-        info!(
-            "[SSE MCP Client] Response: {}",
-            &text[..text.len().min(200)]
-        );
+        info!("[SSE MCP Client] Response: {}", body_snippet(&text, 200));
 
         // Check for empty or 202-style responses (notifications)
         if text.trim().is_empty() {
@@ -1122,7 +2096,7 @@ impl SseMcpClient {
             serde_json::from_str(&text).map_err(|e| anyhow!("Invalid JSON response: {}", e))?;
 
         if let Some(error) = json_response.error {
-            return Err(anyhow!("MCP error: {}", error.message));
+            return Err(error.into_error("MCP error"));
         }
 
         Ok(json_response.result.unwrap_or(Value::Null))
@@ -1148,7 +2122,7 @@ impl SseMcpClient {
             }
         }
 
-        let _ = builder.send();
+        let _ = send_following_redirects(builder);
         Ok(())
     }
 
@@ -1165,6 +2139,7 @@ impl SseMcpClient {
 /// Client for communicating with Streamable HTTP-based MCP servers
 /// This is used for rmcp-based servers that use SSE responses
 pub struct StreamableHttpMcpClient {
+    ids: RequestIds,
     url: String,
     session_id: Option<String>,
     headers: Option<HashMap<String, String>>,
@@ -1185,6 +2160,7 @@ impl StreamableHttpMcpClient {
         info!("[Streamable HTTP Client] Connecting to: {}", url);
 
         let mut instance = Self {
+            ids: RequestIds::new(),
             url: url.to_string(),
             session_id: None,
             headers: headers.cloned(),
@@ -1205,16 +2181,18 @@ impl StreamableHttpMcpClient {
     }
 
     async fn initialize_async(&mut self) -> Result<()> {
-        let client = reqwest::Client::builder().build()?;
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
         let timeout = Duration::from_secs(self.timeout_secs);
 
         // Step 1: Send initialize request
         let init_request = json!({
             "jsonrpc": "2.0",
-            "id": next_request_id(),
+            "id": self.ids.next(),
             "method": "initialize",
             "params": {
-                "protocolVersion": "2024-11-05",
+                "protocolVersion": PROTOCOL_VERSION,
                 "capabilities": {},
                 "clientInfo": {
                     "name": "claude-code-tool-manager",
@@ -1236,15 +2214,20 @@ impl StreamableHttpMcpClient {
         }
 
         info!("[Streamable HTTP Client] Sending initialize request...");
-        let response = tokio::time::timeout(timeout, request_builder.send())
-            .await
-            .map_err(|_| anyhow!("Connection timeout after {}s", self.timeout_secs))?
-            .map_err(|e| anyhow!("HTTP request failed: {}", e))?;
+        let response =
+            tokio::time::timeout(timeout, send_following_redirects_async(request_builder))
+                .await
+                .map_err(|_| anyhow!("Connection timeout after {}s", self.timeout_secs))?
+                .map_err(|e| anyhow!("HTTP request failed: {}", e))?;
 
         let status = response.status();
         if !status.is_success() {
             let body = response.text().await.unwrap_or_default();
-            return Err(anyhow!("HTTP error {}: {}", status, body));
+            return Err(anyhow!(
+                "HTTP error {}: {}",
+                status,
+                body_snippet(&body, 500)
+            ));
         }
 
         // Extract session ID
@@ -1262,25 +2245,15 @@ impl StreamableHttpMcpClient {
         let init_response = Self::read_sse_response_static(response).await?;
 
         if let Some(error) = init_response.error {
-            return Err(anyhow!("MCP initialize error: {}", error.message));
+            return Err(error.into_error("MCP initialize error"));
         }
 
         let init_result = init_response
             .result
-            .ok_or_else(|| anyhow!("Empty initialize result"))?;
-
-        // Parse server info
-        self.server_info = init_result.get("serverInfo").map(|info| McpServerInfo {
-            name: info
-                .get("name")
-                .and_then(|v| v.as_str())
-                .unwrap_or("unknown")
-                .to_string(),
-            version: info
-                .get("version")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string()),
-        });
+            .ok_or_else(|| anyhow!("Empty initialize result"))?;
+
+        // Parse server info
+        self.server_info = parse_server_info(&init_result)?;
 
         let capabilities = init_result.get("capabilities");
         self.resources_supported = capabilities.and_then(|c| c.get("resources")).is_some();
@@ -1300,10 +2273,13 @@ impl StreamableHttpMcpClient {
         if let Some(ref sid) = self.session_id {
             notify_builder = notify_builder.header("mcp-session-id", sid);
         }
+        if let Some(version) = negotiated_version(self.server_info.as_ref()) {
+            notify_builder = notify_builder.header(PROTOCOL_VERSION_HEADER, version);
+        }
 
         notify_builder = notify_builder.body(serde_json::to_string(&notify_request)?);
 
-        if let Err(e) = notify_builder.send().await {
+        if let Err(e) = send_following_redirects_async(notify_builder).await {
             error!(
                 "[Streamable HTTP Client] Failed to send initialized notification: {}",
                 e
@@ -1316,7 +2292,7 @@ impl StreamableHttpMcpClient {
         // Step 3: List tools
         let tools_request = json!({
             "jsonrpc": "2.0",
-            "id": next_request_id(),
+            "id": self.ids.next(),
             "method": "tools/list",
             "params": {}
         });
@@ -1330,22 +2306,26 @@ impl StreamableHttpMcpClient {
         if let Some(ref sid) = self.session_id {
             tools_builder = tools_builder.header("mcp-session-id", sid);
         }
+        if let Some(version) = negotiated_version(self.server_info.as_ref()) {
+            tools_builder = tools_builder.header(PROTOCOL_VERSION_HEADER, version);
+        }
 
         info!("[Streamable HTTP Client] Sending tools/list request...");
-        let tools_response = tokio::time::timeout(timeout, tools_builder.send())
-            .await
-            .map_err(|_| anyhow!("Tools request timeout"))?
-            .map_err(|e| anyhow!("Tools request failed: {}", e))?;
+        let tools_response =
+            tokio::time::timeout(timeout, send_following_redirects_async(tools_builder))
+                .await
+                .map_err(|_| anyhow!("Tools request timeout"))?
+                .map_err(|e| anyhow!("Tools request failed: {}", e))?;
 
         if !tools_response.status().is_success() {
             let body = tools_response.text().await.unwrap_or_default();
-            return Err(anyhow!("Tools request error: {}", body));
+            return Err(anyhow!("Tools request error: {}", body_snippet(&body, 500)));
         }
 
         let tools_json = Self::read_sse_response_static(tools_response).await?;
 
         if let Some(error) = tools_json.error {
-            return Err(anyhow!("MCP tools/list error: {}", error.message));
+            return Err(error.into_error("MCP tools/list error"));
         }
 
         let tools_result = tools_json
@@ -1389,7 +2369,8 @@ impl StreamableHttpMcpClient {
     pub fn call_tool(&mut self, name: &str, arguments: Value) -> Result<ToolCallResult> {
         info!(
             "[Streamable HTTP Client] Calling tool: {} with args: {}",
-            name, arguments
+            name,
+            redact_value(&arguments)
         );
 
         let rt = tokio::runtime::Runtime::new()
@@ -1399,12 +2380,14 @@ impl StreamableHttpMcpClient {
     }
 
     async fn call_tool_async(&self, name: &str, arguments: Value) -> Result<ToolCallResult> {
-        let client = reqwest::Client::builder().build()?;
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
         let timeout = Duration::from_secs(self.timeout_secs);
 
         let request = json!({
             "jsonrpc": "2.0",
-            "id": next_request_id(),
+            "id": self.ids.next(),
             "method": "tools/call",
             "params": {
                 "name": name,
@@ -1423,6 +2406,9 @@ impl StreamableHttpMcpClient {
         if let Some(ref sid) = self.session_id {
             request_builder = request_builder.header("mcp-session-id", sid);
         }
+        if let Some(version) = negotiated_version(self.server_info.as_ref()) {
+            request_builder = request_builder.header(PROTOCOL_VERSION_HEADER, version);
+        }
 
         if let Some(ref hdrs) = self.headers {
             for (key, value) in hdrs {
@@ -1430,10 +2416,11 @@ impl StreamableHttpMcpClient {
             }
         }
 
-        let response = tokio::time::timeout(timeout, request_builder.send())
-            .await
-            .map_err(|_| anyhow!("Tool call timeout"))?
-            .map_err(|e| anyhow!("Tool call failed: {}", e))?;
+        let response =
+            tokio::time::timeout(timeout, send_following_redirects_async(request_builder))
+                .await
+                .map_err(|_| anyhow!("Tool call timeout"))?
+                .map_err(|e| anyhow!("Tool call failed: {}", e))?;
 
         let elapsed = start.elapsed().as_millis() as u64;
 
@@ -1443,8 +2430,11 @@ impl StreamableHttpMcpClient {
                 success: false,
                 content: vec![],
                 is_error: true,
-                error: Some(format!("HTTP error: {}", body)),
+                error: Some(format!("HTTP error: {}", body_snippet(&body, 500))),
+                error_code: None,
+                error_data: None,
                 execution_time_ms: elapsed,
+                cached: false,
             });
         }
 
@@ -1458,21 +2448,118 @@ impl StreamableHttpMcpClient {
                         content: vec![],
                         is_error: true,
                         error: Some(error.message),
+                        error_code: Some(error.code),
+                        error_data: error.data,
                         execution_time_ms: elapsed,
+                        cached: false,
                     });
                 }
 
                 let result = resp.result.unwrap_or(Value::Null);
                 StdioMcpClient::parse_tool_result(result, elapsed)
             }
-            Err(e) => Ok(ToolCallResult {
-                success: false,
-                content: vec![],
-                is_error: true,
-                error: Some(e.to_string()),
-                execution_time_ms: elapsed,
-            }),
+            Err(e) => Ok(ToolCallResult::failed(&e, elapsed)),
+        }
+    }
+
+    /// List the server's resources; empty when it doesn't advertise any
+    pub fn list_resources(&self) -> Result<Vec<McpResource>> {
+        if !self.resources_supported {
+            return Ok(vec![]);
+        }
+        let rt = tokio::runtime::Runtime::new()
+            .map_err(|e| anyhow!("Failed to create async runtime: {}", e))?;
+        collect_pages("resources", |params| {
+            rt.block_on(self.request_async("resources/list", params))
+        })
+    }
+
+    /// Read the contents of the resource at `uri`
+    pub fn read_resource(&self, uri: &str) -> Result<Vec<ToolContent>> {
+        let rt = tokio::runtime::Runtime::new()
+            .map_err(|e| anyhow!("Failed to create async runtime: {}", e))?;
+        let result = rt.block_on(self.request_async("resources/read", json!({ "uri": uri })))?;
+        Ok(parse_resource_contents(&result))
+    }
+
+    /// List the server's prompt templates; empty when it doesn't advertise any
+    pub fn list_prompts(&self) -> Result<Vec<McpPrompt>> {
+        if !self.prompts_supported {
+            return Ok(vec![]);
+        }
+        let rt = tokio::runtime::Runtime::new()
+            .map_err(|e| anyhow!("Failed to create async runtime: {}", e))?;
+        collect_pages("prompts", |params| {
+            rt.block_on(self.request_async("prompts/list", params))
+        })
+    }
+
+    /// Render the prompt `name` with `arguments` (an object of strings)
+    pub fn get_prompt(&self, name: &str, arguments: Value) -> Result<Vec<McpPromptMessage>> {
+        let rt = tokio::runtime::Runtime::new()
+            .map_err(|e| anyhow!("Failed to create async runtime: {}", e))?;
+        let params = json!({
+            "name": name,
+            "arguments": arguments
+        });
+        let result = rt.block_on(self.request_async("prompts/get", params))?;
+        parse_prompt_messages(&result)
+    }
+
+    async fn request_async(&self, method: &str, params: Value) -> Result<Value> {
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": self.ids.next(),
+            "method": method,
+            "params": params
+        });
+
+        let mut request_builder = client
+            .post(&self.url)
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json, text/event-stream")
+            .body(serde_json::to_string(&request)?);
+
+        if let Some(ref sid) = self.session_id {
+            request_builder = request_builder.header("mcp-session-id", sid);
+        }
+        if let Some(version) = negotiated_version(self.server_info.as_ref()) {
+            request_builder = request_builder.header(PROTOCOL_VERSION_HEADER, version);
+        }
+
+        if let Some(ref hdrs) = self.headers {
+            for (key, value) in hdrs {
+                request_builder = request_builder.header(key, value);
+            }
+        }
+
+        let response = tokio::time::timeout(
+            Duration::from_secs(self.timeout_secs),
+            send_following_redirects_async(request_builder),
+        )
+        .await
+        .map_err(|_| anyhow!("{} timeout", method))?
+        .map_err(|e| anyhow!("{} failed: {}", method, e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!(
+                "HTTP error {}: {}",
+                status,
+                body_snippet(&body, 500)
+            ));
+        }
+
+        let resp = Self::read_sse_response_static(response).await?;
+        if let Some(error) = resp.error {
+            return Err(error.into_error("MCP error"));
         }
+        Ok(resp.result.unwrap_or(Value::Null))
     }
 
     /// Static helper to read SSE response (used in async contexts)
@@ -1488,7 +2575,7 @@ impl StreamableHttpMcpClient {
         info!(
             "[Streamable HTTP Client] Response body ({} bytes): {}",
             body_text.len(),
-            &body_text[..body_text.len().min(500)]
+            body_snippet(&body_text, 500)
         );
 
         if body_text.is_empty() {
@@ -1504,7 +2591,7 @@ impl StreamableHttpMcpClient {
                     if !json_str.is_empty() && json_str != "[DONE]" {
                         info!(
                             "[Streamable HTTP Client] Found SSE data: {}",
-                            &json_str[..json_str.len().min(200)]
+                            body_snippet(json_str, 200)
                         );
                         if let Ok(response) = serde_json::from_str::<JsonRpcResponse>(json_str) {
                             return Ok(response);
@@ -1519,7 +2606,7 @@ impl StreamableHttpMcpClient {
             anyhow!(
                 "Could not parse response: {}. Body: {}",
                 e,
-                &body_text[..body_text.len().min(200)]
+                body_snippet(&body_text, 200)
             )
         })
     }
@@ -1530,6 +2617,312 @@ impl StreamableHttpMcpClient {
     }
 }
 
+// ============================================================================
+// WebSocket MCP Client
+// ============================================================================
+
+type WsStream =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// Open a `ws://` or `wss://` connection, sending `headers` with the upgrade
+async fn ws_connect(url: &str, headers: Option<&HashMap<String, String>>) -> Result<WsStream> {
+    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+    use tokio_tungstenite::tungstenite::http::{HeaderName, HeaderValue};
+
+    if !(url.starts_with("ws://") || url.starts_with("wss://")) {
+        return Err(anyhow!("WebSocket MCP URL must start with ws:// or wss://"));
+    }
+    let mut request = url
+        .into_client_request()
+        .map_err(|e| anyhow!("Invalid WebSocket URL: {}", e))?;
+    if let Some(hdrs) = headers {
+        for (key, value) in hdrs {
+            request.headers_mut().insert(
+                HeaderName::from_bytes(key.as_bytes())
+                    .map_err(|e| anyhow!("Invalid header name '{}': {}", key, e))?,
+                HeaderValue::from_str(value)
+                    .map_err(|e| anyhow!("Invalid value for header '{}': {}", key, e))?,
+            );
+        }
+    }
+    let (socket, _) = tokio_tungstenite::connect_async(request)
+        .await
+        .map_err(|e| anyhow!("WebSocket connection failed: {}", e))?;
+    Ok(socket)
+}
+
+async fn ws_send<T: Serialize>(socket: &mut WsStream, message: &T) -> Result<()> {
+    use futures::SinkExt;
+    use tokio_tungstenite::tungstenite::Message;
+
+    let text = serde_json::to_string(message)?;
+    debug!("[WebSocket MCP Client] Sending: {}", redact_body(&text));
+    socket
+        .send(Message::Text(text.into()))
+        .await
+        .map_err(|e| anyhow!("WebSocket send failed: {}", e))
+}
+
+/// Send a request and wait for the response with the same id. Notifications
+/// and responses to other ids that arrive in between are skipped.
+async fn ws_request(
+    socket: &mut WsStream,
+    ids: &RequestIds,
+    method: &str,
+    params: Option<Value>,
+    timeout_dur: Duration,
+) -> Result<Value> {
+    use tokio_tungstenite::tungstenite::Message;
+
+    let id = ids.next();
+    let request = JsonRpcRequest {
+        jsonrpc: "2.0",
+        id,
+        method: method.to_string(),
+        params,
+    };
+    ws_send(socket, &request).await?;
+
+    let deadline = tokio::time::Instant::now() + timeout_dur;
+    loop {
+        let message = tokio::time::timeout_at(deadline, socket.next())
+            .await
+            .map_err(|_| {
+                anyhow!(
+                    "Timeout waiting for {} response ({}s)",
+                    method,
+                    timeout_dur.as_secs()
+                )
+            })?
+            .ok_or_else(|| anyhow!("WebSocket closed before responding to {}", method))?
+            .map_err(|e| anyhow!("WebSocket receive failed: {}", e))?;
+
+        let text = match message {
+            Message::Text(text) => text.to_string(),
+            Message::Binary(bytes) => match String::from_utf8(bytes.to_vec()) {
+                Ok(text) => text,
+                Err(_) => continue,
+            },
+            Message::Close(_) => {
+                return Err(anyhow!("WebSocket closed before responding to {}", method))
+            }
+            // Pings are answered by tungstenite itself
+            _ => continue,
+        };
+        debug!("[WebSocket MCP Client] Received: {}", redact_body(&text));
+
+        let Ok(response) = serde_json::from_str::<JsonRpcResponse>(&text) else {
+            continue;
+        };
+        if response.id != Some(id) {
+            continue;
+        }
+        if let Some(error) = response.error {
+            return Err(error.into_error("MCP error"));
+        }
+        return response
+            .result
+            .ok_or_else(|| anyhow!("Empty result in response"));
+    }
+}
+
+/// Run the initialize handshake, send `notifications/initialized` and fetch
+/// `tools/list`, all over one socket
+async fn ws_initialize(
+    socket: &mut WsStream,
+    ids: &RequestIds,
+    timeout_dur: Duration,
+) -> Result<(Option<McpServerInfo>, Vec<McpTool>, bool, bool)> {
+    let init_params = json!({
+        "protocolVersion": PROTOCOL_VERSION,
+        "capabilities": {},
+        "clientInfo": {
+            "name": "claude-code-tool-manager",
+            "version": env!("CARGO_PKG_VERSION")
+        }
+    });
+    let init_result = ws_request(socket, ids, "initialize", Some(init_params), timeout_dur).await?;
+
+    let server_info = parse_server_info(&init_result)?;
+    let capabilities = init_result.get("capabilities");
+    let resources_supported = capabilities.and_then(|c| c.get("resources")).is_some();
+    let prompts_supported = capabilities.and_then(|c| c.get("prompts")).is_some();
+
+    let notification = JsonRpcNotification {
+        jsonrpc: "2.0",
+        method: "notifications/initialized".to_string(),
+        params: None,
+    };
+    ws_send(socket, &notification).await?;
+
+    let tools_result = ws_request(socket, ids, "tools/list", Some(json!({})), timeout_dur).await?;
+    let tools = if let Some(tools_array) = tools_result.get("tools") {
+        serde_json::from_value(tools_array.clone()).unwrap_or_default()
+    } else {
+        vec![]
+    };
+
+    Ok((server_info, tools, resources_supported, prompts_supported))
+}
+
+/// Client for MCP servers that speak JSON-RPC over a WebSocket. The socket
+/// lives on the client's own runtime so it survives between calls.
+pub struct WebSocketMcpClient {
+    runtime: tokio::runtime::Runtime,
+    socket: WsStream,
+    ids: RequestIds,
+    server_info: Option<McpServerInfo>,
+    tools: Vec<McpTool>,
+    resources_supported: bool,
+    prompts_supported: bool,
+    timeout: Duration,
+}
+
+impl WebSocketMcpClient {
+    /// Connect to a WebSocket MCP server and initialize the session
+    pub fn connect(
+        url: &str,
+        headers: Option<&HashMap<String, String>>,
+        timeout_secs: u64,
+    ) -> Result<Self> {
+        info!("[WebSocket MCP Client] Connecting to: {}", url);
+
+        let runtime = tokio::runtime::Runtime::new()
+            .map_err(|e| anyhow!("Failed to create async runtime: {}", e))?;
+        let timeout_dur = Duration::from_secs(timeout_secs);
+        let socket = runtime.block_on(async {
+            timeout(timeout_dur, ws_connect(url, headers))
+                .await
+                .map_err(|_| anyhow!("Connection timeout after {}s", timeout_secs))?
+        })?;
+
+        let mut client = Self {
+            runtime,
+            socket,
+            ids: RequestIds::new(),
+            server_info: None,
+            tools: vec![],
+            resources_supported: false,
+            prompts_supported: false,
+            timeout: timeout_dur,
+        };
+        client.initialize()?;
+        Ok(client)
+    }
+
+    /// Perform the MCP handshake and fetch the tool list
+    fn initialize(&mut self) -> Result<()> {
+        let (server_info, tools, resources, prompts) =
+            self.runtime
+                .block_on(ws_initialize(&mut self.socket, &self.ids, self.timeout))?;
+        self.server_info = server_info;
+        self.tools = tools;
+        self.resources_supported = resources;
+        self.prompts_supported = prompts;
+
+        info!(
+            "[WebSocket MCP Client] Connected with {} tools",
+            self.tools.len()
+        );
+        Ok(())
+    }
+
+    fn send_request(&mut self, method: &str, params: Option<Value>) -> Result<Value> {
+        self.runtime.block_on(ws_request(
+            &mut self.socket,
+            &self.ids,
+            method,
+            params,
+            self.timeout,
+        ))
+    }
+
+    /// Get server info
+    pub fn server_info(&self) -> Option<&McpServerInfo> {
+        self.server_info.as_ref()
+    }
+
+    /// Get available tools
+    pub fn tools(&self) -> &[McpTool] {
+        &self.tools
+    }
+
+    /// Check if resources are supported
+    pub fn resources_supported(&self) -> bool {
+        self.resources_supported
+    }
+
+    /// Check if prompts are supported
+    pub fn prompts_supported(&self) -> bool {
+        self.prompts_supported
+    }
+
+    /// Call a tool with the given arguments
+    pub fn call_tool(&mut self, name: &str, arguments: Value) -> Result<ToolCallResult> {
+        info!(
+            "[WebSocket MCP Client] Calling tool: {} with args: {}",
+            name,
+            redact_value(&arguments)
+        );
+
+        let params = json!({
+            "name": name,
+            "arguments": arguments
+        });
+
+        let start = Instant::now();
+        let result = self.send_request("tools/call", Some(params));
+        let elapsed = start.elapsed().as_millis() as u64;
+
+        match result {
+            Ok(response) => StdioMcpClient::parse_tool_result(response, elapsed),
+            Err(e) => Ok(ToolCallResult::failed(&e, elapsed)),
+        }
+    }
+
+    /// List the server's resources; empty when it doesn't advertise any
+    pub fn list_resources(&mut self) -> Result<Vec<McpResource>> {
+        if !self.resources_supported {
+            return Ok(vec![]);
+        }
+        collect_pages("resources", |params| {
+            self.send_request("resources/list", Some(params))
+        })
+    }
+
+    /// Read the contents of the resource at `uri`
+    pub fn read_resource(&mut self, uri: &str) -> Result<Vec<ToolContent>> {
+        let result = self.send_request("resources/read", Some(json!({ "uri": uri })))?;
+        Ok(parse_resource_contents(&result))
+    }
+
+    /// List the server's prompt templates; empty when it doesn't advertise any
+    pub fn list_prompts(&mut self) -> Result<Vec<McpPrompt>> {
+        if !self.prompts_supported {
+            return Ok(vec![]);
+        }
+        collect_pages("prompts", |params| {
+            self.send_request("prompts/list", Some(params))
+        })
+    }
+
+    /// Render the prompt `name` with `arguments` (an object of strings)
+    pub fn get_prompt(&mut self, name: &str, arguments: Value) -> Result<Vec<McpPromptMessage>> {
+        let params = json!({
+            "name": name,
+            "arguments": arguments
+        });
+        let result = self.send_request("prompts/get", Some(params))?;
+        parse_prompt_messages(&result)
+    }
+
+    /// Close the client, sending a close frame to the server
+    pub fn close(mut self) {
+        let _ = self.runtime.block_on(self.socket.close(None));
+        info!("[WebSocket MCP Client] Session closed");
+    }
+}
+
 // ============================================================================
 // SSE Response Parsing
 // ============================================================================
@@ -1551,7 +2944,7 @@ fn parse_sse_response(sse_text: &str) -> Result<JsonRpcResponse> {
             if !json_str.is_empty() && json_str != "[DONE]" {
                 info!(
                     "[MCP Client] Found SSE data: {}",
-                    &json_str[..json_str.len().min(200)]
+                    body_snippet(json_str, 200)
                 );
                 match serde_json::from_str::<JsonRpcResponse>(json_str) {
                     Ok(response) => return Ok(response),
@@ -1574,16 +2967,86 @@ fn parse_sse_response(sse_text: &str) -> Result<JsonRpcResponse> {
 // Public API
 // ============================================================================
 
+/// Parse `.env` style contents: `KEY=VALUE` lines, optionally prefixed with
+/// `export`, with `#` comments and single/double-quoted values
+pub fn parse_env_file(contents: &str) -> HashMap<String, String> {
+    let mut vars = HashMap::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        if key.is_empty() {
+            continue;
+        }
+        let value = value.trim();
+        let quoted = value.len() >= 2
+            && ((value.starts_with('"') && value.ends_with('"'))
+                || (value.starts_with('\'') && value.ends_with('\'')));
+        let value = if quoted {
+            &value[1..value.len() - 1]
+        } else {
+            // Unquoted values may carry a trailing comment
+            value.split(" #").next().unwrap_or(value).trim_end()
+        };
+        vars.insert(key.to_string(), value.to_string());
+    }
+    vars
+}
+
+/// Where an MCP's `env_file` lives. `~/` expands to the home dir; a relative
+/// path is taken from the directory of the config the MCP was imported from
+/// (`source_path`), else `fallback_dir`, else `~/.claude`.
+pub fn resolve_env_file(
+    env_file: &str,
+    source_path: Option<&str>,
+    fallback_dir: Option<&std::path::Path>,
+) -> std::path::PathBuf {
+    use std::path::Path;
+
+    if let Some(rest) = env_file.strip_prefix("~/") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest);
+        }
+    }
+    let path = Path::new(env_file);
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    let base = source_path
+        .filter(|s| !s.is_empty())
+        .and_then(|s| Path::new(s).parent())
+        .filter(|dir| dir.is_dir())
+        .map(Path::to_path_buf)
+        .or_else(|| fallback_dir.map(Path::to_path_buf))
+        .or_else(|| {
+            crate::utils::paths::get_claude_paths()
+                .ok()
+                .map(|p| p.claude_dir)
+        });
+    match base {
+        Some(base) => base.join(path),
+        None => path.to_path_buf(),
+    }
+}
+
+/// Read and parse an MCP's `.env` file
+pub fn load_env_file(path: &std::path::Path) -> Result<HashMap<String, String>> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read env file '{}': {}", path.display(), e))?;
+    Ok(parse_env_file(&contents))
+}
+
 /// Test a stdio-based MCP server
-pub fn test_stdio_mcp(
-    command: &str,
-    args: &[String],
-    env: Option<&HashMap<String, String>>,
-    timeout_secs: u64,
-) -> McpTestResult {
+pub fn test_stdio_mcp(options: &SpawnOptions, timeout_secs: u64) -> McpTestResult {
     let start = Instant::now();
 
-    let result = test_stdio_mcp_internal(command, args, env, timeout_secs);
+    let result = test_stdio_mcp_internal(options, timeout_secs);
 
     let elapsed_ms = start.elapsed().as_millis() as u64;
 
@@ -1598,19 +3061,17 @@ pub fn test_stdio_mcp(
         }
         Err(e) => {
             error!("[MCP Client] Test failed: {}", e);
-            McpTestResult::error(e.to_string(), elapsed_ms)
+            McpTestResult::from_error(&e, elapsed_ms)
         }
     }
 }
 
 fn test_stdio_mcp_internal(
-    command: &str,
-    args: &[String],
-    env: Option<&HashMap<String, String>>,
+    options: &SpawnOptions,
     timeout_secs: u64,
 ) -> Result<(McpServerInfo, Vec<McpTool>, bool, bool)> {
     // Use the new spawn method which handles initialize + tools/list
-    let client = StdioMcpClient::spawn(command, args, env, timeout_secs)?;
+    let client = StdioMcpClient::spawn(options, timeout_secs)?;
 
     let server_info = client
         .server_info()
@@ -1618,15 +3079,118 @@ fn test_stdio_mcp_internal(
         .unwrap_or_else(|| McpServerInfo {
             name: "unknown".to_string(),
             version: None,
+            protocol_version: None,
         });
     let tools = client.tools().to_vec();
     let resources_supported = client.resources_supported();
     let prompts_supported = client.prompts_supported();
 
-    // Clean up
+    // Clean up
+    client.close();
+
+    Ok((server_info, tools, resources_supported, prompts_supported))
+}
+
+/// Stdout lines kept in a framing report
+const MAX_FRAMING_LINES: usize = 500;
+
+/// How long to keep reading stdout after the handshake, to catch noise
+/// printed after the last response
+const FRAMING_TRAILING_WINDOW: Duration = Duration::from_millis(500);
+
+/// One line a stdio server wrote to stdout
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StdioFramingLine {
+    pub text: String,
+    pub is_json_rpc: bool,
+}
+
+/// What a stdio server wrote to stdout during the handshake. Anything that
+/// isn't a JSON-RPC message (banners, logs, progress output) corrupts the
+/// stream for clients that don't skip it, so it belongs on stderr.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StdioFramingReport {
+    pub json_rpc_lines: usize,
+    pub noise_lines: usize,
+    /// True when any non-JSON-RPC line appeared on stdout
+    pub pollutes_stdout: bool,
+    /// Whether initialize and tools/list both got answers
+    pub handshake_succeeded: bool,
+    pub error: Option<String>,
+    /// Every stdout line in order, up to `MAX_FRAMING_LINES`
+    pub lines: Vec<StdioFramingLine>,
+}
+
+/// Whether `line` is a JSON-RPC 2.0 message
+fn is_json_rpc_line(line: &str) -> bool {
+    serde_json::from_str::<Value>(line)
+        .ok()
+        .and_then(|v| {
+            v.get("jsonrpc")
+                .and_then(|j| j.as_str())
+                .map(|j| j == "2.0")
+        })
+        .unwrap_or(false)
+}
+
+fn framing_report(transcript: Vec<String>, error: Option<String>) -> StdioFramingReport {
+    let lines: Vec<StdioFramingLine> = transcript
+        .into_iter()
+        .map(|text| StdioFramingLine {
+            is_json_rpc: is_json_rpc_line(&text),
+            text,
+        })
+        .collect();
+    let json_rpc_lines = lines.iter().filter(|l| l.is_json_rpc).count();
+    let noise_lines = lines.len() - json_rpc_lines;
+    StdioFramingReport {
+        json_rpc_lines,
+        noise_lines,
+        pollutes_stdout: noise_lines > 0,
+        handshake_succeeded: error.is_none(),
+        error,
+        lines: lines.into_iter().take(MAX_FRAMING_LINES).collect(),
+    }
+}
+
+/// Spawn a stdio server, run the handshake and report every line it wrote to
+/// stdout, split into JSON-RPC messages and noise
+pub fn diagnose_stdio_framing(options: &SpawnOptions, timeout_secs: u64) -> StdioFramingReport {
+    let mut client = match StdioMcpClient::spawn_process(options, timeout_secs) {
+        Ok(client) => client,
+        Err(e) => return framing_report(Vec::new(), Some(e.to_string())),
+    };
+    client.transcript = Some(Vec::new());
+
+    let error = client.initialize().err().map(|e| e.to_string());
+
+    // Pick up anything printed after the last response
+    if let Ok(stdout_lines) = client.stdout_lines.get_mut() {
+        let deadline = Instant::now() + FRAMING_TRAILING_WINDOW;
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            match stdout_lines.recv_timeout(remaining) {
+                Ok(line) if !line.trim().is_empty() => {
+                    if let Some(transcript) = client.transcript.as_mut() {
+                        transcript.push(line.trim().to_string());
+                    }
+                }
+                Ok(_) => {}
+                Err(_) => break,
+            }
+        }
+    }
+
+    let transcript = client.transcript.take().unwrap_or_default();
     client.close();
 
-    Ok((server_info, tools, resources_supported, prompts_supported))
+    let report = framing_report(transcript, error);
+    info!(
+        "[MCP Client] Framing check: {} JSON-RPC lines, {} noise lines",
+        report.json_rpc_lines, report.noise_lines
+    );
+    report
 }
 
 /// Test an SSE-based MCP server (async version)
@@ -1639,14 +3203,18 @@ pub async fn test_sse_mcp_async(
     url: &str,
     headers: Option<&HashMap<String, String>>,
     timeout_secs: u64,
+    retry: Option<RetryPolicy>,
 ) -> McpTestResult {
     let start = Instant::now();
 
-    let result = test_sse_mcp_internal_async(url, headers, timeout_secs).await;
+    let (result, retries) = with_retry_async(retry, || {
+        test_sse_mcp_internal_async(url, headers, timeout_secs)
+    })
+    .await;
 
     let elapsed_ms = start.elapsed().as_millis() as u64;
 
-    match result {
+    let result = match result {
         Ok((server_info, tools, resources, prompts)) => {
             info!(
                 "[MCP Client] SSE test successful: {} tools found in {}ms",
@@ -1657,9 +3225,10 @@ pub async fn test_sse_mcp_async(
         }
         Err(e) => {
             error!("[MCP Client] SSE test failed: {}", e);
-            McpTestResult::error(e.to_string(), elapsed_ms)
+            McpTestResult::from_error(&e, elapsed_ms)
         }
-    }
+    };
+    McpTestResult { retries, ..result }
 }
 
 /// Synchronous wrapper for async SSE test (for use from sync contexts)
@@ -1667,6 +3236,7 @@ pub fn test_sse_mcp(
     url: &str,
     headers: Option<&HashMap<String, String>>,
     timeout_secs: u64,
+    retry: Option<RetryPolicy>,
 ) -> McpTestResult {
     // Create a new tokio runtime for the async SSE test
     let rt = match tokio::runtime::Runtime::new() {
@@ -1676,7 +3246,7 @@ pub fn test_sse_mcp(
         }
     };
 
-    rt.block_on(test_sse_mcp_async(url, headers, timeout_secs))
+    rt.block_on(test_sse_mcp_async(url, headers, timeout_secs, retry))
 }
 
 /// SSE Event parsed from the stream
@@ -1693,11 +3263,13 @@ fn parse_sse_line(line: &str, current_event: &mut SseEvent) -> Option<SseEvent>
     if line.is_empty() {
         // Empty line means end of event
         if current_event.data.is_some() || current_event.event_type.is_some() {
-            let event = SseEvent {
-                event_type: current_event.event_type.take(),
-                data: current_event.data.take(),
-            };
-            return Some(event);
+            let data = current_event.data.take();
+            // Per the SSE spec, an event without an `event:` field is a "message"
+            let event_type = current_event
+                .event_type
+                .take()
+                .or_else(|| data.as_ref().map(|_| "message".to_string()));
+            return Some(SseEvent { event_type, data });
         }
         return None;
     }
@@ -1718,9 +3290,12 @@ async fn test_sse_mcp_internal_async(
     headers: Option<&HashMap<String, String>>,
     timeout_secs: u64,
 ) -> Result<(McpServerInfo, Vec<McpTool>, bool, bool)> {
+    let ids = RequestIds::new();
     info!("[MCP Client] Testing SSE MCP at: {} (async)", url);
 
-    let client = reqwest::Client::builder().build()?;
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?;
 
     // SSE uses GET to establish connection
     let mut request_builder = client.get(url).header("Accept", "text/event-stream");
@@ -1732,20 +3307,25 @@ async fn test_sse_mcp_internal_async(
     }
 
     info!("[MCP Client] Connecting to SSE endpoint via GET...");
-    let response = timeout(Duration::from_secs(timeout_secs), request_builder.send())
-        .await
-        .map_err(|_| anyhow!("Connection timeout after {}s", timeout_secs))?
-        .map_err(|e| {
-            let err_str = e.to_string();
-            if err_str.contains("dns error") || err_str.contains("resolve") {
-                anyhow!("Cannot resolve host. Check that the URL is correct.")
-            } else if err_str.contains("connection refused") {
-                anyhow!("Connection refused. The server may not be running.")
-            } else {
-                anyhow!("SSE connection failed: {}", err_str)
-            }
-        })?;
+    let response = timeout(
+        Duration::from_secs(timeout_secs),
+        send_following_redirects_async(request_builder),
+    )
+    .await
+    .map_err(|_| connection_timeout(timeout_secs))?
+    .map_err(|e| {
+        let err_str = e.to_string();
+        if err_str.contains("dns error") || err_str.contains("resolve") {
+            e.context("Cannot resolve host. Check that the URL is correct.")
+        } else if err_str.contains("connection refused") {
+            e.context("Connection refused. The server may not be running.")
+        } else {
+            e.context(format!("SSE connection failed: {}", err_str))
+        }
+    })?;
 
+    // Relative message endpoints resolve against the post-redirect URL
+    let stream_url = response.url().clone();
     let status = response.status();
     let content_type = response
         .headers()
@@ -1767,7 +3347,7 @@ async fn test_sse_mcp_internal_async(
             if body.is_empty() {
                 status.canonical_reason().unwrap_or("Unknown").to_string()
             } else {
-                body[..body.len().min(200)].to_string()
+                body_snippet(&body, 200)
             }
         ));
     }
@@ -1808,7 +3388,11 @@ async fn test_sse_mcp_internal_async(
                     buffer = buffer[newline_pos + 1..].to_string();
 
                     if let Some(event) = parse_sse_line(&line, &mut current_event) {
-                        info!("[MCP Client] SSE event: {:?}", event);
+                        debug!(
+                            "[MCP Client] SSE {} event: {}",
+                            event.event_type.as_deref().unwrap_or("message"),
+                            body_snippet(event.data.as_deref().unwrap_or_default(), 200)
+                        );
 
                         if event.event_type.as_deref() == Some("endpoint") {
                             if let Some(data) = &event.data {
@@ -1862,8 +3446,7 @@ async fn test_sse_mcp_internal_async(
         if endpoint_url.starts_with("http://") || endpoint_url.starts_with("https://") {
             reqwest::Url::parse(&endpoint_url)?
         } else {
-            let base_url = reqwest::Url::parse(url)?;
-            base_url.join(&endpoint_url)?
+            stream_url.join(&endpoint_url)?
         };
     info!(
         "[MCP Client] Using messages endpoint: {}",
@@ -1907,13 +3490,13 @@ async fn test_sse_mcp_internal_async(
     };
 
     // Send initialize request
-    let init_id = next_request_id();
+    let init_id = ids.next();
     let init_request = json!({
         "jsonrpc": "2.0",
         "id": init_id,
         "method": "initialize",
         "params": {
-            "protocolVersion": "2024-11-05",
+            "protocolVersion": PROTOCOL_VERSION,
             "capabilities": {},
             "clientInfo": {
                 "name": "claude-code-tool-manager",
@@ -1939,8 +3522,7 @@ async fn test_sse_mcp_internal_async(
         }
     }
 
-    let _init_response = post_builder
-        .send()
+    let _init_response = send_following_redirects_async(post_builder)
         .await
         .map_err(|e| anyhow!("Failed to send initialize: {}", e))?;
 
@@ -1948,6 +3530,7 @@ async fn test_sse_mcp_internal_async(
     let mut server_info = McpServerInfo {
         name: "SSE Server".to_string(),
         version: None,
+        protocol_version: None,
     };
     let mut resources_supported = false;
     let mut prompts_supported = false;
@@ -1964,21 +3547,11 @@ async fn test_sse_mcp_internal_async(
                             if response.id == Some(init_id) {
                                 info!("[MCP Client] Received initialize response");
                                 if let Some(error) = response.error {
-                                    return Err(anyhow!("Initialize error: {}", error.message));
+                                    return Err(error.into_error("Initialize error"));
                                 }
                                 if let Some(result) = response.result {
-                                    if let Some(info) = result.get("serverInfo") {
-                                        server_info = McpServerInfo {
-                                            name: info
-                                                .get("name")
-                                                .and_then(|v| v.as_str())
-                                                .unwrap_or("SSE Server")
-                                                .to_string(),
-                                            version: info
-                                                .get("version")
-                                                .and_then(|v| v.as_str())
-                                                .map(|s| s.to_string()),
-                                        };
+                                    if let Some(info) = parse_server_info(&result)? {
+                                        server_info = info;
                                     }
                                     if let Some(caps) = result.get("capabilities") {
                                         resources_supported = caps.get("resources").is_some();
@@ -2013,10 +3586,10 @@ async fn test_sse_mcp_internal_async(
         }
     }
 
-    let _ = notify_builder.send().await;
+    let _ = send_following_redirects_async(notify_builder).await;
 
     // Send tools/list request
-    let tools_id = next_request_id();
+    let tools_id = ids.next();
     let tools_request = json!({
         "jsonrpc": "2.0",
         "id": tools_id,
@@ -2036,7 +3609,7 @@ async fn test_sse_mcp_internal_async(
         }
     }
 
-    let _ = tools_builder.send().await;
+    let _ = send_following_redirects_async(tools_builder).await;
 
     // Wait for tools/list response
     let mut tools: Vec<McpTool> = vec![];
@@ -2052,7 +3625,7 @@ async fn test_sse_mcp_internal_async(
                             if response.id == Some(tools_id) {
                                 info!("[MCP Client] Received tools/list response");
                                 if let Some(error) = response.error {
-                                    return Err(anyhow!("tools/list error: {}", error.message));
+                                    return Err(error.into_error("tools/list error"));
                                 }
                                 if let Some(result) = response.result {
                                     if let Some(tools_array) = result.get("tools") {
@@ -2088,14 +3661,16 @@ pub fn test_http_mcp(
     url: &str,
     headers: Option<&HashMap<String, String>>,
     timeout_secs: u64,
+    retry: Option<RetryPolicy>,
 ) -> McpTestResult {
     let start = Instant::now();
 
-    let result = test_http_mcp_internal(url, headers, timeout_secs);
+    let (result, retries) =
+        with_retry(retry, || test_http_mcp_internal(url, headers, timeout_secs));
 
     let elapsed_ms = start.elapsed().as_millis() as u64;
 
-    match result {
+    let result = match result {
         Ok((server_info, tools, resources, prompts)) => {
             info!(
                 "[MCP Client] HTTP test successful: {} tools found in {}ms",
@@ -2106,9 +3681,10 @@ pub fn test_http_mcp(
         }
         Err(e) => {
             error!("[MCP Client] HTTP test failed: {}", e);
-            McpTestResult::error(e.to_string(), elapsed_ms)
+            McpTestResult::from_error(&e, elapsed_ms)
         }
-    }
+    };
+    McpTestResult { retries, ..result }
 }
 
 /// Test a Streamable HTTP MCP server (async version with proper SSE handling)
@@ -2117,14 +3693,18 @@ pub async fn test_streamable_http_mcp_async(
     url: &str,
     headers: Option<&HashMap<String, String>>,
     timeout_secs: u64,
+    retry: Option<RetryPolicy>,
 ) -> McpTestResult {
     let start = Instant::now();
 
-    let result = test_streamable_http_internal_async(url, headers, timeout_secs).await;
+    let (result, retries) = with_retry_async(retry, || {
+        test_streamable_http_internal_async(url, headers, timeout_secs)
+    })
+    .await;
 
     let elapsed_ms = start.elapsed().as_millis() as u64;
 
-    match result {
+    let result = match result {
         Ok((server_info, tools, resources, prompts)) => {
             info!(
                 "[MCP Client] Streamable HTTP test successful: {} tools found in {}ms",
@@ -2135,9 +3715,10 @@ pub async fn test_streamable_http_mcp_async(
         }
         Err(e) => {
             error!("[MCP Client] Streamable HTTP test failed: {}", e);
-            McpTestResult::error(e.to_string(), elapsed_ms)
+            McpTestResult::from_error(&e, elapsed_ms)
         }
-    }
+    };
+    McpTestResult { retries, ..result }
 }
 
 /// Synchronous wrapper for async Streamable HTTP test
@@ -2145,6 +3726,56 @@ pub fn test_streamable_http_mcp(
     url: &str,
     headers: Option<&HashMap<String, String>>,
     timeout_secs: u64,
+    retry: Option<RetryPolicy>,
+) -> McpTestResult {
+    let rt = match tokio::runtime::Runtime::new() {
+        Ok(rt) => rt,
+        Err(e) => {
+            return McpTestResult::error(format!("Failed to create async runtime: {}", e), 0);
+        }
+    };
+
+    rt.block_on(test_streamable_http_mcp_async(
+        url,
+        headers,
+        timeout_secs,
+        retry,
+    ))
+}
+
+/// Test a WebSocket MCP server (`ws://` or `wss://`)
+pub async fn test_websocket_mcp_async(
+    url: &str,
+    headers: Option<&HashMap<String, String>>,
+    timeout_secs: u64,
+) -> McpTestResult {
+    let start = Instant::now();
+
+    let result = test_websocket_internal_async(url, headers, timeout_secs).await;
+
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+
+    match result {
+        Ok((server_info, tools, resources, prompts)) => {
+            info!(
+                "[MCP Client] WebSocket test successful: {} tools found in {}ms",
+                tools.len(),
+                elapsed_ms
+            );
+            McpTestResult::success(server_info, tools, resources, prompts, elapsed_ms)
+        }
+        Err(e) => {
+            error!("[MCP Client] WebSocket test failed: {}", e);
+            McpTestResult::from_error(&e, elapsed_ms)
+        }
+    }
+}
+
+/// Synchronous wrapper for async WebSocket test
+pub fn test_websocket_mcp(
+    url: &str,
+    headers: Option<&HashMap<String, String>>,
+    timeout_secs: u64,
 ) -> McpTestResult {
     let rt = match tokio::runtime::Runtime::new() {
         Ok(rt) => rt,
@@ -2153,7 +3784,30 @@ pub fn test_streamable_http_mcp(
         }
     };
 
-    rt.block_on(test_streamable_http_mcp_async(url, headers, timeout_secs))
+    rt.block_on(test_websocket_mcp_async(url, headers, timeout_secs))
+}
+
+async fn test_websocket_internal_async(
+    url: &str,
+    headers: Option<&HashMap<String, String>>,
+    timeout_secs: u64,
+) -> Result<(McpServerInfo, Vec<McpTool>, bool, bool)> {
+    info!("[MCP Client] Testing WebSocket MCP at: {}", url);
+    let timeout_dur = Duration::from_secs(timeout_secs);
+
+    let mut socket = timeout(timeout_dur, ws_connect(url, headers))
+        .await
+        .map_err(|_| anyhow!("Connection timeout after {}s", timeout_secs))??;
+    let result = ws_initialize(&mut socket, &RequestIds::new(), timeout_dur).await;
+    let _ = socket.close(None).await;
+
+    let (server_info, tools, resources, prompts) = result?;
+    let server_info = server_info.unwrap_or_else(|| McpServerInfo {
+        name: "unknown".to_string(),
+        version: None,
+        protocol_version: None,
+    });
+    Ok((server_info, tools, resources, prompts))
 }
 
 /// Internal async implementation for Streamable HTTP
@@ -2162,17 +3816,20 @@ async fn test_streamable_http_internal_async(
     headers: Option<&HashMap<String, String>>,
     timeout_secs: u64,
 ) -> Result<(McpServerInfo, Vec<McpTool>, bool, bool)> {
+    let ids = RequestIds::new();
     info!("[MCP Client] Testing Streamable HTTP MCP at: {}", url);
 
-    let client = reqwest::Client::builder().build()?;
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?;
 
     // Step 1: Send initialize request
     let init_request = json!({
         "jsonrpc": "2.0",
-        "id": next_request_id(),
+        "id": ids.next(),
         "method": "initialize",
         "params": {
-            "protocolVersion": "2024-11-05",
+            "protocolVersion": PROTOCOL_VERSION,
             "capabilities": {},
             "clientInfo": {
                 "name": "claude-code-tool-manager",
@@ -2194,15 +3851,25 @@ async fn test_streamable_http_internal_async(
     }
 
     info!("[MCP Client] Sending Streamable HTTP initialize request...");
-    let response = timeout(Duration::from_secs(timeout_secs), request_builder.send())
-        .await
-        .map_err(|_| anyhow!("Connection timeout after {}s", timeout_secs))?
-        .map_err(|e| anyhow!("HTTP request failed: {}", e))?;
+    let response = timeout(
+        Duration::from_secs(timeout_secs),
+        send_following_redirects_async(request_builder),
+    )
+    .await
+    .map_err(|_| connection_timeout(timeout_secs))?
+    .map_err(|e| {
+        let message = format!("HTTP request failed: {}", e);
+        e.context(message)
+    })?;
 
     let status = response.status();
     if !status.is_success() {
         let body = response.text().await.unwrap_or_default();
-        return Err(anyhow!("HTTP error {}: {}", status, body));
+        return Err(anyhow!(
+            "HTTP error {}: {}",
+            status,
+            body_snippet(&body, 500)
+        ));
     }
 
     // Extract session ID from headers
@@ -2221,7 +3888,7 @@ async fn test_streamable_http_internal_async(
     info!("[MCP Client] Initialize response received");
 
     if let Some(error) = init_response.error {
-        return Err(anyhow!("MCP initialize error: {}", error.message));
+        return Err(error.into_error("MCP initialize error"));
     }
 
     let init_result = init_response
@@ -2229,24 +3896,11 @@ async fn test_streamable_http_internal_async(
         .ok_or_else(|| anyhow!("Empty initialize result"))?;
 
     // Parse server info
-    let server_info = if let Some(info) = init_result.get("serverInfo") {
-        McpServerInfo {
-            name: info
-                .get("name")
-                .and_then(|v| v.as_str())
-                .unwrap_or("unknown")
-                .to_string(),
-            version: info
-                .get("version")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string()),
-        }
-    } else {
-        McpServerInfo {
-            name: "unknown".to_string(),
-            version: None,
-        }
-    };
+    let server_info = parse_server_info(&init_result)?.unwrap_or_else(|| McpServerInfo {
+        name: "unknown".to_string(),
+        version: None,
+        protocol_version: None,
+    });
 
     let capabilities = init_result.get("capabilities");
     let resources_supported = capabilities.and_then(|c| c.get("resources")).is_some();
@@ -2275,11 +3929,14 @@ async fn test_streamable_http_internal_async(
     } else {
         info!("[MCP Client] WARNING: No session ID for notification!");
     }
+    if let Some(version) = negotiated_version(Some(&server_info)) {
+        notify_builder = notify_builder.header(PROTOCOL_VERSION_HEADER, version);
+    }
 
     notify_builder = notify_builder.body(serde_json::to_string(&notify_request)?);
 
     // Send notification and wait for it to complete (don't ignore errors)
-    match notify_builder.send().await {
+    match send_following_redirects_async(notify_builder).await {
         Ok(resp) => {
             let notify_status = resp.status();
             info!(
@@ -2291,7 +3948,7 @@ async fn test_streamable_http_internal_async(
                 if !body.is_empty() {
                     info!(
                         "[MCP Client] Notification response: {}",
-                        &body[..body.len().min(200)]
+                        body_snippet(&body, 200)
                     );
                 }
             }
@@ -2310,7 +3967,7 @@ async fn test_streamable_http_internal_async(
     // Step 3: List tools
     let tools_request = json!({
         "jsonrpc": "2.0",
-        "id": next_request_id(),
+        "id": ids.next(),
         "method": "tools/list",
         "params": {}
     });
@@ -2324,22 +3981,28 @@ async fn test_streamable_http_internal_async(
     if let Some(ref sid) = session_id {
         tools_builder = tools_builder.header("mcp-session-id", sid);
     }
+    if let Some(version) = negotiated_version(Some(&server_info)) {
+        tools_builder = tools_builder.header(PROTOCOL_VERSION_HEADER, version);
+    }
 
     info!("[MCP Client] Sending tools/list request...");
-    let tools_response = timeout(Duration::from_secs(timeout_secs), tools_builder.send())
-        .await
-        .map_err(|_| anyhow!("Tools request timeout"))?
-        .map_err(|e| anyhow!("Tools request failed: {}", e))?;
+    let tools_response = timeout(
+        Duration::from_secs(timeout_secs),
+        send_following_redirects_async(tools_builder),
+    )
+    .await
+    .map_err(|_| anyhow!("Tools request timeout"))?
+    .map_err(|e| anyhow!("Tools request failed: {}", e))?;
 
     if !tools_response.status().is_success() {
         let body = tools_response.text().await.unwrap_or_default();
-        return Err(anyhow!("Tools request error: {}", body));
+        return Err(anyhow!("Tools request error: {}", body_snippet(&body, 500)));
     }
 
     let tools_json = read_sse_response(tools_response).await?;
 
     if let Some(error) = tools_json.error {
-        return Err(anyhow!("MCP tools/list error: {}", error.message));
+        return Err(error.into_error("MCP tools/list error"));
     }
 
     let tools_result = tools_json
@@ -2381,9 +4044,13 @@ async fn read_sse_response(response: reqwest::Response) -> Result<JsonRpcRespons
         status, content_type, content_length
     );
 
-    // Log all headers for debugging
+    // Log all headers for debugging, without credential values
     for (name, value) in headers.iter() {
-        info!("[MCP Client] Header: {} = {:?}", name, value);
+        info!(
+            "[MCP Client] Header: {} = {}",
+            name,
+            redact_header_value(name.as_str(), value.to_str().unwrap_or("<binary>"))
+        );
     }
 
     // First, try reading the entire body as text regardless of content-type
@@ -2396,7 +4063,7 @@ async fn read_sse_response(response: reqwest::Response) -> Result<JsonRpcRespons
     info!(
         "[MCP Client] Response body ({} bytes): {}",
         body_text.len(),
-        &body_text[..body_text.len().min(1000)]
+        body_snippet(&body_text, 1000)
     );
 
     if body_text.is_empty() {
@@ -2413,7 +4080,7 @@ async fn read_sse_response(response: reqwest::Response) -> Result<JsonRpcRespons
                 if !json_str.is_empty() && json_str != "[DONE]" {
                     info!(
                         "[MCP Client] Found SSE data: {}",
-                        &json_str[..json_str.len().min(200)]
+                        body_snippet(json_str, 200)
                     );
                     if let Ok(response) = serde_json::from_str::<JsonRpcResponse>(json_str) {
                         return Ok(response);
@@ -2428,17 +4095,170 @@ async fn read_sse_response(response: reqwest::Response) -> Result<JsonRpcRespons
         anyhow!(
             "Could not parse response: {}. Body: {}",
             e,
-            &body_text[..body_text.len().min(200)]
+            body_snippet(&body_text, 200)
         )
     })
 }
 
+/// Maximum redirect hops followed for a single MCP request
+const MAX_REDIRECTS: usize = 5;
+
+/// Where a redirect response points, `Ok(None)` when `status` is not a
+/// redirect, or an error when following it would change what the transport
+/// expects (method, scheme or hop budget).
+fn next_redirect_hop(
+    method: &reqwest::Method,
+    from: &reqwest::Url,
+    status: reqwest::StatusCode,
+    location: Option<&reqwest::header::HeaderValue>,
+    hops: usize,
+) -> Result<Option<reqwest::Url>> {
+    if !status.is_redirection() || status == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(None);
+    }
+    let location = location
+        .and_then(|l| l.to_str().ok())
+        .ok_or_else(|| anyhow!("{} redirect from {} has no Location header", status, from))?;
+    let to = from
+        .join(location)
+        .map_err(|e| anyhow!("Invalid redirect Location '{}': {}", location, e))?;
+
+    if hops >= MAX_REDIRECTS {
+        return Err(anyhow!(
+            "Too many redirects (more than {}) starting from {}",
+            MAX_REDIRECTS,
+            from
+        ));
+    }
+    if !matches!(to.scheme(), "http" | "https") {
+        return Err(anyhow!(
+            "Refusing to follow redirect to non-HTTP URL {}",
+            to
+        ));
+    }
+    if from.scheme() == "https" && to.scheme() == "http" {
+        return Err(anyhow!(
+            "Refusing to follow redirect from {} to insecure {}",
+            from,
+            to
+        ));
+    }
+    // 303 means "fetch the result with GET", which a JSON-RPC POST can't do.
+    // 301/302 are replayed with the original method and body instead of the
+    // browser-style downgrade to GET.
+    if status == reqwest::StatusCode::SEE_OTHER && method != reqwest::Method::GET {
+        return Err(anyhow!(
+            "Server answered {} {} with 303 See Other to {}; MCP requests can't be retried as GET. Update the MCP URL.",
+            method,
+            from,
+            to
+        ));
+    }
+
+    info!("[MCP Client] Redirect {} {} -> {}", status, from, to);
+    Ok(Some(to))
+}
+
+/// Headers that carry credentials or a session: never logged, and dropped
+/// on cross-origin redirects
+fn is_sensitive_header(name: &str) -> bool {
+    crate::services::diagnostics::is_secret_key(name) || name == "mcp-session-id"
+}
+
+/// Drop credentials when a redirect leaves the original origin. Headers are
+/// kept unchanged for same-origin hops (e.g. an added trailing slash).
+fn strip_cross_origin_headers(
+    from: &reqwest::Url,
+    to: &reqwest::Url,
+    headers: &mut reqwest::header::HeaderMap,
+) {
+    if from.origin() == to.origin() {
+        return;
+    }
+    let sensitive: Vec<_> = headers
+        .keys()
+        .filter(|name| is_sensitive_header(name.as_str()))
+        .cloned()
+        .collect();
+    for name in sensitive {
+        info!(
+            "[MCP Client] Dropping header '{}' on cross-origin redirect to {}",
+            name,
+            to.origin().ascii_serialization()
+        );
+        headers.remove(&name);
+    }
+}
+
+/// Send a request, following redirects by hand so the method, body and
+/// (same-origin) headers survive each hop. MCP clients are built with
+/// `redirect::Policy::none()` so reqwest never follows them itself.
+fn send_following_redirects(
+    builder: reqwest::blocking::RequestBuilder,
+) -> Result<reqwest::blocking::Response> {
+    let (client, request) = builder.build_split();
+    let mut request = request?;
+    let mut hops = 0;
+    loop {
+        let replay = request.try_clone();
+        let method = request.method().clone();
+        let response = client.execute(request)?;
+        let Some(to) = next_redirect_hop(
+            &method,
+            response.url(),
+            response.status(),
+            response.headers().get(reqwest::header::LOCATION),
+            hops,
+        )?
+        else {
+            return Ok(response);
+        };
+        let mut next =
+            replay.ok_or_else(|| anyhow!("Cannot replay request body after redirect to {}", to))?;
+        strip_cross_origin_headers(response.url(), &to, next.headers_mut());
+        *next.url_mut() = to;
+        request = next;
+        hops += 1;
+    }
+}
+
+/// Async counterpart of [`send_following_redirects`]
+async fn send_following_redirects_async(
+    builder: reqwest::RequestBuilder,
+) -> Result<reqwest::Response> {
+    let (client, request) = builder.build_split();
+    let mut request = request?;
+    let mut hops = 0;
+    loop {
+        let replay = request.try_clone();
+        let method = request.method().clone();
+        let response = client.execute(request).await?;
+        let Some(to) = next_redirect_hop(
+            &method,
+            response.url(),
+            response.status(),
+            response.headers().get(reqwest::header::LOCATION),
+            hops,
+        )?
+        else {
+            return Ok(response);
+        };
+        let mut next =
+            replay.ok_or_else(|| anyhow!("Cannot replay request body after redirect to {}", to))?;
+        strip_cross_origin_headers(response.url(), &to, next.headers_mut());
+        *next.url_mut() = to;
+        request = next;
+        hops += 1;
+    }
+}
+
 /// Helper to build an HTTP request with common headers
 fn build_http_request(
     client: &reqwest::blocking::Client,
     url: &str,
     body: String,
     session_id: Option<&str>,
+    protocol_version: Option<&str>,
     custom_headers: Option<&HashMap<String, String>>,
 ) -> reqwest::blocking::RequestBuilder {
     let mut builder = client
@@ -2451,6 +4271,9 @@ fn build_http_request(
     if let Some(sid) = session_id {
         builder = builder.header("mcp-session-id", sid);
     }
+    if let Some(version) = protocol_version {
+        builder = builder.header(PROTOCOL_VERSION_HEADER, version);
+    }
 
     // Add custom headers
     if let Some(hdrs) = custom_headers {
@@ -2467,19 +4290,21 @@ fn test_http_mcp_internal(
     headers: Option<&HashMap<String, String>>,
     timeout_secs: u64,
 ) -> Result<(McpServerInfo, Vec<McpTool>, bool, bool)> {
+    let ids = RequestIds::new();
     info!("[MCP Client] Testing HTTP MCP at: {}", url);
 
     let client = reqwest::blocking::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
         .timeout(Duration::from_secs(timeout_secs))
         .build()?;
 
     // Step 1: Initialize
     let init_request = json!({
         "jsonrpc": "2.0",
-        "id": next_request_id(),
+        "id": ids.next(),
         "method": "initialize",
         "params": {
-            "protocolVersion": "2024-11-05",
+            "protocolVersion": PROTOCOL_VERSION,
             "capabilities": {},
             "clientInfo": {
                 "name": "claude-code-tool-manager",
@@ -2491,38 +4316,44 @@ fn test_http_mcp_internal(
     let request_body = serde_json::to_string(&init_request)?;
     info!(
         "[MCP Client] Sending HTTP initialize request: {}",
-        request_body
+        redact_body(&request_body)
     );
 
-    let response = build_http_request(&client, url, request_body, None, headers)
-        .send()
-        .map_err(|e| {
-            let err_str = e.to_string();
-            if err_str.contains("dns error")
-                || err_str.contains("resolve")
-                || err_str.contains("No such host")
-            {
-                anyhow!(
-                    "Cannot resolve host. Check that the URL is correct and the server is online."
-                )
-            } else if err_str.contains("connection refused") {
-                anyhow!(
-                    "Connection refused. The server may not be running or the port is incorrect."
-                )
-            } else if err_str.contains("timed out") || err_str.contains("timeout") {
-                anyhow!("Connection timed out. The server may be slow or unreachable.")
-            } else if err_str.contains("certificate")
-                || err_str.contains("SSL")
-                || err_str.contains("TLS")
-            {
-                anyhow!(
-                    "SSL/TLS error: {}. The server may have an invalid certificate.",
-                    err_str
-                )
-            } else {
-                anyhow!("HTTP request failed: {}", err_str)
-            }
-        })?;
+    let response = send_following_redirects(build_http_request(
+        &client,
+        url,
+        request_body,
+        None,
+        None,
+        headers,
+    ))
+    .map_err(|e| {
+        let err_str = e.to_string();
+        if err_str.contains("dns error")
+            || err_str.contains("resolve")
+            || err_str.contains("No such host")
+        {
+            e.context(
+                "Cannot resolve host. Check that the URL is correct and the server is online.",
+            )
+        } else if err_str.contains("connection refused") {
+            e.context("Connection refused. The server may not be running or the port is incorrect.")
+        } else if err_str.contains("timed out") || err_str.contains("timeout") {
+            e.context("Connection timed out. The server may be slow or unreachable.")
+        } else if err_str.contains("certificate")
+            || err_str.contains("SSL")
+            || err_str.contains("TLS")
+        {
+            let message = format!(
+                "SSL/TLS error: {}. The server may have an invalid certificate.",
+                err_str
+            );
+            e.context(message)
+        } else {
+            let message = format!("HTTP request failed: {}", err_str);
+            e.context(message)
+        }
+    })?;
 
     let status = response.status();
 
@@ -2570,7 +4401,7 @@ fn test_http_mcp_internal(
         .map_err(|e| anyhow!("Failed to read response: {}", e))?;
     info!(
         "[MCP Client] Response body: {}",
-        &response_text[..response_text.len().min(500)]
+        body_snippet(&response_text, 500)
     );
 
     // Parse response - handle both JSON and SSE formats
@@ -2582,13 +4413,13 @@ fn test_http_mcp_internal(
             anyhow!(
                 "Invalid JSON response: {}. Response was: {}",
                 e,
-                &response_text[..response_text.len().min(200)]
+                body_snippet(&response_text, 200)
             )
         })?
     };
 
     if let Some(error) = init_response.error {
-        return Err(anyhow!("MCP initialize error: {}", error.message));
+        return Err(error.into_error("MCP initialize error"));
     }
 
     let init_result = init_response
@@ -2596,24 +4427,11 @@ fn test_http_mcp_internal(
         .ok_or_else(|| anyhow!("Empty initialize result"))?;
 
     // Parse server info
-    let server_info = if let Some(info) = init_result.get("serverInfo") {
-        McpServerInfo {
-            name: info
-                .get("name")
-                .and_then(|v: &Value| v.as_str())
-                .unwrap_or("unknown")
-                .to_string(),
-            version: info
-                .get("version")
-                .and_then(|v: &Value| v.as_str())
-                .map(|s: &str| s.to_string()),
-        }
-    } else {
-        McpServerInfo {
-            name: "unknown".to_string(),
-            version: None,
-        }
-    };
+    let server_info = parse_server_info(&init_result)?.unwrap_or_else(|| McpServerInfo {
+        name: "unknown".to_string(),
+        version: None,
+        protocol_version: None,
+    });
 
     let capabilities = init_result.get("capabilities");
     let resources_supported = capabilities
@@ -2633,12 +4451,19 @@ fn test_http_mcp_internal(
         session_id
     );
 
-    let _ = build_http_request(&client, url, notify_body, session_id.as_deref(), headers).send();
+    let _ = send_following_redirects(build_http_request(
+        &client,
+        url,
+        notify_body,
+        session_id.as_deref(),
+        negotiated_version(Some(&server_info)),
+        headers,
+    ));
 
     // Step 3: List tools
     let tools_request = json!({
         "jsonrpc": "2.0",
-        "id": next_request_id(),
+        "id": ids.next(),
         "method": "tools/list",
         "params": {}
     });
@@ -2648,10 +4473,15 @@ fn test_http_mcp_internal(
         session_id
     );
 
-    let tools_response =
-        build_http_request(&client, url, tools_body, session_id.as_deref(), headers)
-            .send()
-            .map_err(|e| anyhow!("HTTP tools/list request failed: {}", e))?;
+    let tools_response = send_following_redirects(build_http_request(
+        &client,
+        url,
+        tools_body,
+        session_id.as_deref(),
+        negotiated_version(Some(&server_info)),
+        headers,
+    ))
+    .map_err(|e| anyhow!("HTTP tools/list request failed: {}", e))?;
 
     let tools_content_type = tools_response
         .headers()
@@ -2665,7 +4495,7 @@ fn test_http_mcp_internal(
         .map_err(|e| anyhow!("Failed to read tools response: {}", e))?;
     info!(
         "[MCP Client] Tools response: {}",
-        &tools_text[..tools_text.len().min(500)]
+        body_snippet(&tools_text, 500)
     );
 
     let tools_json: JsonRpcResponse = if tools_content_type.contains("text/event-stream") {
@@ -2676,7 +4506,7 @@ fn test_http_mcp_internal(
     };
 
     if let Some(error) = tools_json.error {
-        return Err(anyhow!("MCP tools/list error: {}", error.message));
+        return Err(error.into_error("MCP tools/list error"));
     }
 
     let tools_result = tools_json
@@ -2706,6 +4536,7 @@ mod tests {
             McpServerInfo {
                 name: "test".to_string(),
                 version: Some("1.0.0".to_string()),
+                protocol_version: None,
             },
             vec![],
             true,
@@ -2739,6 +4570,7 @@ mod tests {
             McpServerInfo {
                 name: "filesystem".to_string(),
                 version: Some("1.0.0".to_string()),
+                protocol_version: None,
             },
             tools,
             true,
@@ -2763,6 +4595,14 @@ mod tests {
         assert!(!result.prompts_supported);
     }
 
+    #[test]
+    fn test_mcp_test_result_not_testable() {
+        let result = McpTestResult::not_testable();
+        assert!(!result.success);
+        assert!(result.skipped);
+        assert_eq!(result.response_time_ms, 0);
+    }
+
     #[test]
     fn test_mcp_test_result_error_preserves_time() {
         let result = McpTestResult::error("Timeout".to_string(), 30000);
@@ -2894,14 +4734,15 @@ data: {"jsonrpc":"2.0","id":1,"result":{}}
     }
 
     // =========================================================================
-    // next_request_id tests
+    // RequestIds tests
     // =========================================================================
 
     #[test]
-    fn test_next_request_id_increments() {
-        let id1 = next_request_id();
-        let id2 = next_request_id();
-        let id3 = next_request_id();
+    fn test_request_ids_increments() {
+        let ids = RequestIds::new();
+        let id1 = ids.next();
+        let id2 = ids.next();
+        let id3 = ids.next();
 
         // IDs should be strictly increasing
         assert!(id2 > id1);
@@ -2930,43 +4771,350 @@ data: {"jsonrpc":"2.0","id":1,"result":{}}
     }
 
     #[test]
-    fn test_json_rpc_request_serialization() {
-        let request = JsonRpcRequest {
-            jsonrpc: "2.0",
-            id: 42,
-            method: "initialize".to_string(),
-            params: Some(json!({"test": true})),
-        };
-        let json = serde_json::to_string(&request).unwrap();
-        assert!(json.contains("\"jsonrpc\":\"2.0\""));
-        assert!(json.contains("\"id\":42"));
-        assert!(json.contains("\"method\":\"initialize\""));
-        assert!(json.contains("\"params\":{\"test\":true}"));
+    fn test_json_rpc_request_serialization() {
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0",
+            id: 42,
+            method: "initialize".to_string(),
+            params: Some(json!({"test": true})),
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains("\"jsonrpc\":\"2.0\""));
+        assert!(json.contains("\"id\":42"));
+        assert!(json.contains("\"method\":\"initialize\""));
+        assert!(json.contains("\"params\":{\"test\":true}"));
+    }
+
+    #[test]
+    fn test_json_rpc_request_without_params() {
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0",
+            id: 1,
+            method: "test".to_string(),
+            params: None,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        // params should be omitted when None
+        assert!(!json.contains("params"));
+    }
+
+    #[test]
+    fn test_json_rpc_notification_serialization() {
+        let notification = JsonRpcNotification {
+            jsonrpc: "2.0",
+            method: "notifications/initialized".to_string(),
+            params: None,
+        };
+        let json = serde_json::to_string(&notification).unwrap();
+        assert!(json.contains("\"method\":\"notifications/initialized\""));
+        // Notifications don't have an id field
+        assert!(!json.contains("\"id\""));
+    }
+
+    #[test]
+    fn test_cancelled_notification_serialization() {
+        let json = serde_json::to_value(cancelled_notification(7, None)).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/cancelled",
+                "params": {"requestId": 7}
+            })
+        );
+        let json =
+            serde_json::to_value(cancelled_notification(8, Some("Request timed out"))).unwrap();
+        assert_eq!(json["params"]["requestId"], 8);
+        assert_eq!(json["params"]["reason"], "Request timed out");
+        assert!(json.get("id").is_none());
+    }
+
+    /// Answers the handshake, never answers `tools/call`, and logs every line
+    /// it reads to `$LOG`
+    #[cfg(unix)]
+    const HANGING_TOOL_SERVER: &str = r#"while IFS= read -r line; do
+  printf '%s\n' "$line" >> "$LOG"
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  case "$line" in
+    *'"tools/call"'*) ;;
+    *'"initialize"'*) printf '{"jsonrpc":"2.0","id":%s,"result":{"capabilities":{}}}\n' "$id" ;;
+    *) [ -n "$id" ] && printf '{"jsonrpc":"2.0","id":%s,"result":{"tools":[]}}\n' "$id" ;;
+  esac
+done"#;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_call_tool_timeout_sends_cancellation() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let log = dir.path().join("stdin.log");
+        let env: HashMap<String, String> =
+            [("LOG".to_string(), log.to_string_lossy().to_string())].into();
+        let mut client = StdioMcpClient::spawn(
+            &SpawnOptions {
+                env: Some(&env),
+                ..SpawnOptions::new(HANGING_TOOL_SERVER, &[])
+            },
+            1,
+        )
+        .unwrap();
+
+        let result = client.call_tool("slow", json!({})).unwrap();
+        assert!(result.is_error);
+        assert!(!client.is_alive());
+
+        let cancelled = std::fs::read_to_string(&log)
+            .unwrap()
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+            .find(|msg| msg["method"] == "notifications/cancelled")
+            .expect("cancellation sent");
+        // initialize = 1, tools/list = 2, tools/call = 3
+        assert_eq!(cancelled["params"]["requestId"], 3);
+    }
+
+    const INVALID_PARAMS_SERVER: &str = r#"while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  case "$line" in
+    *'"tools/call"'*) printf '{"jsonrpc":"2.0","id":%s,"error":{"code":-32602,"message":"Invalid params","data":{"field":"path","reason":"required"}}}\n' "$id" ;;
+    *'"initialize"'*) printf '{"jsonrpc":"2.0","id":%s,"result":{"capabilities":{}}}\n' "$id" ;;
+    *) [ -n "$id" ] && printf '{"jsonrpc":"2.0","id":%s,"result":{"tools":[]}}\n' "$id" ;;
+  esac
+done"#;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_call_tool_surfaces_error_code_and_data() {
+        let mut client =
+            StdioMcpClient::spawn(&SpawnOptions::new(INVALID_PARAMS_SERVER, &[]), 10).unwrap();
+
+        let result = client.call_tool("read", json!({})).unwrap();
+        assert!(result.is_error);
+        assert_eq!(result.error.as_deref(), Some("MCP error: Invalid params"));
+        assert_eq!(result.error_code, Some(-32602));
+        assert_eq!(
+            result.error_data,
+            Some(json!({"field": "path", "reason": "required"}))
+        );
+        client.close();
+    }
+
+    #[test]
+    fn test_mcp_test_result_from_rpc_error() {
+        let error: JsonRpcError = serde_json::from_value(json!({
+            "code": -32001,
+            "message": "Unauthorized",
+            "data": {"scope": "tools"}
+        }))
+        .unwrap();
+        let err = error
+            .into_error("Initialize error")
+            .context("Failed to initialize");
+
+        let result = McpTestResult::from_error(&err, 7);
+        assert!(!result.success);
+        assert_eq!(result.error.as_deref(), Some("Failed to initialize"));
+        assert_eq!(result.error_code, Some(-32001));
+        assert_eq!(result.error_data, Some(json!({"scope": "tools"})));
+
+        let plain = McpTestResult::from_error(&anyhow!("Connection refused"), 7);
+        assert_eq!(plain.error_code, None);
+        assert_eq!(plain.error_data, None);
+    }
+
+    const RESOURCE_SERVER: &str = r#"while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  case "$line" in
+    *'"initialize"'*) printf '{"jsonrpc":"2.0","id":%s,"result":{"capabilities":{"resources":{}}}}\n' "$id" ;;
+    *'"resources/list"'*'"cursor":"page2"'*) printf '{"jsonrpc":"2.0","id":%s,"result":{"resources":[{"uri":"file:///logo.png","name":"logo","mimeType":"image/png"}]}}\n' "$id" ;;
+    *'"resources/list"'*) printf '{"jsonrpc":"2.0","id":%s,"result":{"resources":[{"uri":"file:///README.md","name":"README","description":"Project readme"}],"nextCursor":"page2"}}\n' "$id" ;;
+    *'"resources/read"'*) printf '{"jsonrpc":"2.0","id":%s,"result":{"contents":[{"uri":"file:///README.md","mimeType":"text/markdown","text":"Hello"}]}}\n' "$id" ;;
+    *) [ -n "$id" ] && printf '{"jsonrpc":"2.0","id":%s,"result":{"tools":[]}}\n' "$id" ;;
+  esac
+done"#;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stdio_list_and_read_resources() {
+        let mut client =
+            StdioMcpClient::spawn(&SpawnOptions::new(RESOURCE_SERVER, &[]), 10).unwrap();
+        assert!(client.resources_supported());
+
+        let resources = client.list_resources().unwrap();
+        let uris: Vec<&str> = resources.iter().map(|r| r.uri.as_str()).collect();
+        assert_eq!(uris, ["file:///README.md", "file:///logo.png"]);
+        assert_eq!(resources[0].description.as_deref(), Some("Project readme"));
+        assert_eq!(resources[1].mime_type.as_deref(), Some("image/png"));
+
+        let contents = client.read_resource("file:///README.md").unwrap();
+        match contents.as_slice() {
+            [ToolContent::Resource {
+                uri,
+                mime_type,
+                text,
+            }] => {
+                assert_eq!(uri, "file:///README.md");
+                assert_eq!(mime_type.as_deref(), Some("text/markdown"));
+                assert_eq!(text.as_deref(), Some("Hello"));
+            }
+            other => panic!("Unexpected contents: {:?}", other),
+        }
+        client.close();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_list_resources_empty_when_not_advertised() {
+        let mut client =
+            StdioMcpClient::spawn(&SpawnOptions::new(ECHO_ID_SERVER, &[]), 10).unwrap();
+        assert!(client.list_resources().unwrap().is_empty());
+        client.close();
+    }
+
+    #[test]
+    fn test_parse_resource_contents() {
+        let contents = parse_resource_contents(&json!({
+            "contents": [
+                {"uri": "file:///a.png", "mimeType": "image/png", "blob": "iVBORw0"},
+                {"uri": "file:///a.bin", "mimeType": "application/octet-stream", "blob": "AAAA"},
+                {"mimeType": "text/plain", "text": "no uri"}
+            ]
+        }));
+        assert_eq!(contents.len(), 2);
+        assert!(
+            matches!(&contents[0], ToolContent::Image { data, mime_type } if data == "iVBORw0" && mime_type == "image/png")
+        );
+        assert!(
+            matches!(&contents[1], ToolContent::Resource { uri, text: None, .. } if uri == "file:///a.bin")
+        );
+        assert!(parse_resource_contents(&json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_collect_pages_stops_at_page_limit() {
+        let mut calls = 0;
+        let resources: Vec<McpResource> = collect_pages("resources", |_| {
+            calls += 1;
+            Ok(json!({
+                "resources": [{"uri": format!("mem://{}", calls), "name": "r"}],
+                "nextCursor": "again"
+            }))
+        })
+        .unwrap();
+        assert_eq!(calls, MAX_LIST_PAGES);
+        assert_eq!(resources.len(), MAX_LIST_PAGES);
+    }
+
+    const PROMPT_SERVER: &str = r#"while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  case "$line" in
+    *'"initialize"'*) printf '{"jsonrpc":"2.0","id":%s,"result":{"capabilities":{"prompts":{}}}}\n' "$id" ;;
+    *'"prompts/list"'*) printf '{"jsonrpc":"2.0","id":%s,"result":{"prompts":[{"name":"review","description":"Review code","arguments":[{"name":"lang","required":true,"x-enum":["rust","go"]}]}]}}\n' "$id" ;;
+    *'"prompts/get"'*'"lang":"rust"'*) printf '{"jsonrpc":"2.0","id":%s,"result":{"messages":[{"role":"user","content":{"type":"text","text":"Review this rust code"}}]}}\n' "$id" ;;
+    *'"prompts/get"'*) printf '{"jsonrpc":"2.0","id":%s,"error":{"code":-32602,"message":"Missing lang"}}\n' "$id" ;;
+    *) [ -n "$id" ] && printf '{"jsonrpc":"2.0","id":%s,"result":{"tools":[]}}\n' "$id" ;;
+  esac
+done"#;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stdio_list_and_get_prompts() {
+        let mut client = StdioMcpClient::spawn(&SpawnOptions::new(PROMPT_SERVER, &[]), 10).unwrap();
+
+        let prompts = client.list_prompts().unwrap();
+        assert_eq!(prompts.len(), 1);
+        assert_eq!(prompts[0].name, "review");
+        // Argument schemas keep fields the client doesn't know about
+        assert_eq!(
+            prompts[0].arguments,
+            Some(json!([{"name": "lang", "required": true, "x-enum": ["rust", "go"]}]))
+        );
+
+        let messages = client
+            .get_prompt("review", json!({"lang": "rust"}))
+            .unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].role, "user");
+        assert_eq!(messages[0].content["text"], "Review this rust code");
+
+        let err = client.get_prompt("review", json!({})).unwrap_err();
+        assert_eq!(rpc_error_details(&err).0, Some(-32602));
+        client.close();
     }
 
     #[test]
-    fn test_json_rpc_request_without_params() {
-        let request = JsonRpcRequest {
-            jsonrpc: "2.0",
-            id: 1,
-            method: "test".to_string(),
-            params: None,
-        };
-        let json = serde_json::to_string(&request).unwrap();
-        // params should be omitted when None
-        assert!(!json.contains("params"));
+    fn test_collect_pages_treats_empty_and_missing_lists_as_empty() {
+        for page in [json!({"prompts": []}), json!({"prompts": null}), json!({})] {
+            let prompts: Vec<McpPrompt> = collect_pages("prompts", |_| Ok(page.clone())).unwrap();
+            assert!(prompts.is_empty());
+        }
+        assert!(parse_prompt_messages(&json!({})).unwrap().is_empty());
+    }
+
+    /// Answers `initialize` with protocol version `$VERSION`
+    const VERSIONED_SERVER: &str = r#"while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  case "$line" in
+    *'"initialize"'*) printf '{"jsonrpc":"2.0","id":%s,"result":{"protocolVersion":"%s","capabilities":{},"serverInfo":{"name":"versioned"}}}\n' "$id" "$VERSION" ;;
+    *) [ -n "$id" ] && printf '{"jsonrpc":"2.0","id":%s,"result":{"tools":[]}}\n' "$id" ;;
+  esac
+done"#;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stdio_captures_negotiated_protocol_version() {
+        let env: HashMap<String, String> =
+            [("VERSION".to_string(), "2025-06-18".to_string())].into();
+        let client = StdioMcpClient::spawn(
+            &SpawnOptions {
+                env: Some(&env),
+                ..SpawnOptions::new(VERSIONED_SERVER, &[])
+            },
+            10,
+        )
+        .unwrap();
+        let info = client.server_info().unwrap();
+        assert_eq!(info.name, "versioned");
+        assert_eq!(info.protocol_version.as_deref(), Some("2025-06-18"));
+        client.close();
+
+        let env: HashMap<String, String> =
+            [("VERSION".to_string(), "1999-01-01".to_string())].into();
+        let err = StdioMcpClient::spawn(
+            &SpawnOptions {
+                env: Some(&env),
+                ..SpawnOptions::new(VERSIONED_SERVER, &[])
+            },
+            10,
+        )
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("protocol version 1999-01-01"));
     }
 
     #[test]
-    fn test_json_rpc_notification_serialization() {
-        let notification = JsonRpcNotification {
-            jsonrpc: "2.0",
-            method: "notifications/initialized".to_string(),
-        };
-        let json = serde_json::to_string(&notification).unwrap();
-        assert!(json.contains("\"method\":\"notifications/initialized\""));
-        // Notifications don't have an id field
-        assert!(!json.contains("\"id\""));
+    fn test_parse_server_info_negotiated_version() {
+        let info = parse_server_info(&json!({
+            "protocolVersion": "2025-03-26",
+            "serverInfo": {"name": "srv", "version": "1.0"}
+        }))
+        .unwrap()
+        .unwrap();
+        assert_eq!(info.protocol_version.as_deref(), Some("2025-03-26"));
+
+        // Servers that don't echo a version are taken at their word
+        let info = parse_server_info(&json!({"serverInfo": {"name": "srv"}}))
+            .unwrap()
+            .unwrap();
+        assert_eq!(info.protocol_version, None);
+
+        // The version survives a missing serverInfo
+        let info = parse_server_info(&json!({"protocolVersion": "2025-06-18"}))
+            .unwrap()
+            .unwrap();
+        assert_eq!(info.name, "unknown");
+        assert_eq!(info.protocol_version.as_deref(), Some("2025-06-18"));
+        assert!(parse_server_info(&json!({})).unwrap().is_none());
+        assert!(parse_server_info(&json!({"protocolVersion": "2023-01-01"})).is_err());
     }
 
     // =========================================================================
@@ -2979,6 +5127,7 @@ data: {"jsonrpc":"2.0","id":1,"result":{}}
             McpServerInfo {
                 name: "test".to_string(),
                 version: Some("1.0".to_string()),
+                protocol_version: None,
             },
             vec![McpTool {
                 name: "tool1".to_string(),
@@ -3070,6 +5219,7 @@ data: {"jsonrpc":"2.0","id":1,"result":{}}
             McpServerInfo {
                 name: "round-trip".to_string(),
                 version: Some("2.5.0".to_string()),
+                protocol_version: None,
             },
             vec![
                 McpTool {
@@ -3134,6 +5284,7 @@ data: "result":{}}
         let info = McpServerInfo {
             name: "test-server".to_string(),
             version: Some("1.2.3".to_string()),
+            protocol_version: None,
         };
 
         let json = serde_json::to_string(&info).unwrap();
@@ -3147,6 +5298,7 @@ data: "result":{}}
         let info = McpServerInfo {
             name: "test".to_string(),
             version: None,
+            protocol_version: None,
         };
 
         let json = serde_json::to_string(&info).unwrap();
@@ -3312,7 +5464,10 @@ data: "result":{}}
             }],
             is_error: false,
             error: None,
+            error_code: None,
+            error_data: None,
             execution_time_ms: 42,
+            cached: false,
         };
         let json = serde_json::to_string(&result).unwrap();
         assert!(json.contains("\"success\":true"));
@@ -3497,6 +5652,7 @@ data: "result":{}}
             McpServerInfo {
                 name: "full".to_string(),
                 version: Some("3.0".to_string()),
+                protocol_version: None,
             },
             vec![McpTool {
                 name: "t1".to_string(),
@@ -3684,7 +5840,10 @@ data: "result":{}}
             ],
             is_error: false,
             error: None,
+            error_code: None,
+            error_data: None,
             execution_time_ms: 123,
+            cached: false,
         };
 
         let json = serde_json::to_string(&original).unwrap();
@@ -3716,7 +5875,10 @@ data: "result":{}}
             }],
             is_error: true,
             error: Some("Process exited with code 1".to_string()),
+            error_code: None,
+            error_data: None,
             execution_time_ms: 5000,
+            cached: false,
         };
 
         let json = serde_json::to_string(&result).unwrap();
@@ -3736,6 +5898,7 @@ data: "result":{}}
         let original = McpServerInfo {
             name: "my-mcp-server".to_string(),
             version: Some("0.1.0-beta.3".to_string()),
+            protocol_version: None,
         };
         let json = serde_json::to_string(&original).unwrap();
         let parsed: McpServerInfo = serde_json::from_str(&json).unwrap();
@@ -3748,6 +5911,7 @@ data: "result":{}}
         let original = McpServerInfo {
             name: "bare".to_string(),
             version: None,
+            protocol_version: None,
         };
         let json = serde_json::to_string(&original).unwrap();
         let parsed: McpServerInfo = serde_json::from_str(&json).unwrap();
@@ -3763,21 +5927,220 @@ data: "result":{}}
         assert_eq!(info.name, "s");
     }
 
+    #[test]
+    fn test_request_ids_are_per_connection() {
+        let a = RequestIds::new();
+        let b = RequestIds::new();
+        assert_eq!(a.next(), 1);
+        assert_eq!(a.next(), 2);
+        // Drawing from `a` does not advance `b`
+        assert_eq!(b.next(), 1);
+    }
+
+    /// Minimal stdio MCP server: answers every request with its own id, and
+    /// echoes the id back as the text of a `tools/call` result.
+    #[cfg(unix)]
+    const ECHO_ID_SERVER: &str = r#"while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  [ -n "$id" ] || continue
+  case "$line" in
+    *'"initialize"'*) printf '{"jsonrpc":"2.0","id":%s,"result":{"capabilities":{},"serverInfo":{"name":"echo-id"}}}\n' "$id" ;;
+    *'"tools/call"'*) printf '{"jsonrpc":"2.0","id":%s,"result":{"content":[{"type":"text","text":"%s"}]}}\n' "$id" "$id" ;;
+    *) printf '{"jsonrpc":"2.0","id":%s,"result":{"tools":[]}}\n' "$id" ;;
+  esac
+done"#;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_concurrent_stdio_sessions_have_independent_ids() {
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                std::thread::spawn(|| {
+                    let mut client =
+                        StdioMcpClient::spawn(&SpawnOptions::new(ECHO_ID_SERVER, &[]), 10).unwrap();
+                    let texts: Vec<String> = (0..3)
+                        .map(|_| {
+                            let result = client.call_tool("echo", json!({})).unwrap();
+                            match &result.content[0] {
+                                ToolContent::Text { text } => text.clone(),
+                                other => panic!("unexpected content: {:?}", other),
+                            }
+                        })
+                        .collect();
+                    client.close();
+                    texts
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            // initialize = 1, tools/list = 2, so the calls are 3, 4, 5 in every session
+            assert_eq!(handle.join().unwrap(), vec!["3", "4", "5"]);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stdio_pool_reuses_and_respawns_sessions() {
+        let pool = StdioConnectionPool::new(Duration::from_secs(60));
+        let call = |pool: &StdioConnectionPool| {
+            let mut client = pool
+                .get_or_spawn(&SpawnOptions::new(ECHO_ID_SERVER, &[]), 10)
+                .unwrap();
+            match &client.call_tool("echo", json!({})).unwrap().content[0] {
+                ToolContent::Text { text } => text.clone(),
+                other => panic!("unexpected content: {:?}", other),
+            }
+        };
+
+        // The second call runs on the same process, so its id continues
+        assert_eq!(call(&pool), "3");
+        assert_eq!(call(&pool), "4");
+        assert_eq!(pool.len(), 1);
+
+        // A process that died while pooled is replaced transparently
+        {
+            let mut client = pool
+                .get_or_spawn(&SpawnOptions::new(ECHO_ID_SERVER, &[]), 10)
+                .unwrap();
+            client.child.kill().unwrap();
+            client.child.wait().unwrap();
+        }
+        assert!(pool.is_empty());
+        assert_eq!(call(&pool), "3");
+
+        pool.close_all();
+        assert!(pool.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stdio_pool_evicts_idle_sessions() {
+        let pool = StdioConnectionPool::new(Duration::ZERO);
+        drop(
+            pool.get_or_spawn(&SpawnOptions::new(ECHO_ID_SERVER, &[]), 10)
+                .unwrap(),
+        );
+        assert_eq!(pool.len(), 1);
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(pool.evict_idle(), 1);
+        assert!(pool.is_empty());
+    }
+
+    #[test]
+    fn test_stdio_pool_key_covers_launch_options() {
+        let a: HashMap<String, String> =
+            [("A".into(), "1".into()), ("B".into(), "2".into())].into();
+        let b: HashMap<String, String> =
+            [("B".into(), "2".into()), ("A".into(), "1".into())].into();
+        let args = vec!["-y".to_string()];
+        let key = |env, env_file, inherit_env, shell| {
+            stdio_pool_key(&SpawnOptions {
+                command: "npx",
+                args: &args,
+                env,
+                env_file,
+                inherit_env,
+                shell,
+            })
+        };
+        assert_eq!(
+            key(Some(&a), None, true, None),
+            key(Some(&b), None, true, None)
+        );
+        assert_ne!(key(Some(&a), None, true, None), key(None, None, true, None));
+        // Servers launched differently never share a process
+        assert_ne!(
+            key(None, Some(".env"), true, None),
+            key(None, None, true, None)
+        );
+        assert_ne!(key(None, None, false, None), key(None, None, true, None));
+        assert_ne!(
+            key(None, None, true, Some("bash")),
+            key(None, None, true, None)
+        );
+    }
+
+    #[test]
+    fn test_is_transient_error() {
+        let refused: anyhow::Error =
+            std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "refused").into();
+        assert!(is_transient_error(&refused.context("Connection refused")));
+        assert!(is_transient_error(&connection_timeout(5)));
+        assert!(!is_transient_error(&anyhow!("HTTP error 404 Not Found: ")));
+        assert!(!is_transient_error(&anyhow!(
+            "dns error: failed to lookup address information: Name or service not known"
+        )));
+        assert!(is_transient_error(&anyhow!(
+            "dns error: failed to lookup address information: Temporary failure in name resolution"
+        )));
+    }
+
+    #[test]
+    fn test_with_retry_only_retries_transient_failures() {
+        let policy = Some(RetryPolicy {
+            max_attempts: 5,
+            base_delay_ms: 1,
+        });
+
+        let mut calls = 0;
+        let (result, retries) = with_retry(policy, || {
+            calls += 1;
+            if calls < 3 {
+                Err(connection_timeout(1))
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(retries, 2);
+
+        let (result, retries) = with_retry(policy, || -> Result<()> { Err(anyhow!("MCP error")) });
+        assert!(result.is_err());
+        assert_eq!(retries, 0);
+
+        let (result, retries) = with_retry(None, || -> Result<()> { Err(connection_timeout(1)) });
+        assert!(result.is_err());
+        assert_eq!(retries, 0);
+    }
+
+    #[test]
+    fn test_http_mcp_retries_refused_connections() {
+        // Bind and release a port so nothing is listening on it
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let url = format!("http://127.0.0.1:{}/mcp", port);
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            base_delay_ms: 1,
+        };
+
+        let result = test_http_mcp(&url, None, 5, Some(policy));
+        assert!(!result.success);
+        assert_eq!(result.retries, 2);
+        assert_eq!(test_http_mcp(&url, None, 5, None).retries, 0);
+    }
+
     // =========================================================================
-    // next_request_id additional tests
+    // RequestIds additional tests
     // =========================================================================
 
     #[test]
-    fn test_next_request_id_always_positive() {
+    fn test_request_ids_always_positive() {
+        let ids = RequestIds::new();
         for _ in 0..10 {
-            let id = next_request_id();
+            let id = ids.next();
             assert!(id > 0);
         }
     }
 
     #[test]
-    fn test_next_request_id_no_duplicates() {
-        let ids: Vec<u64> = (0..100).map(|_| next_request_id()).collect();
+    fn test_request_ids_no_duplicates() {
+        let counter = RequestIds::new();
+        let ids: Vec<u64> = (0..100).map(|_| counter.next()).collect();
         let mut unique = ids.clone();
         unique.sort();
         unique.dedup();
@@ -3846,6 +6209,7 @@ data: "result":{}}
         let notification = JsonRpcNotification {
             jsonrpc: "2.0",
             method: "initialized".to_string(),
+            params: None,
         };
         let json = serde_json::to_string(&notification).unwrap();
         let parsed: Value = serde_json::from_str(&json).unwrap();
@@ -3980,7 +6344,8 @@ data: "result":{}}
         };
         parse_sse_line("data: some payload", &mut current);
         let event = parse_sse_line("", &mut current).unwrap();
-        assert!(event.event_type.is_none());
+        // Defaults to "message" per the SSE spec
+        assert_eq!(event.event_type, Some("message".to_string()));
         assert_eq!(event.data, Some("some payload".to_string()));
     }
 
@@ -4069,7 +6434,10 @@ data: "result":{}}
             content: vec![],
             is_error: false,
             error: None,
+            error_code: None,
+            error_data: None,
             execution_time_ms: 1,
+            cached: false,
         };
         let json = serde_json::to_value(&result).unwrap();
         assert!(json.get("isError").is_some());
@@ -4088,6 +6456,7 @@ data: "result":{}}
             McpServerInfo {
                 name: "s".to_string(),
                 version: None,
+                protocol_version: None,
             },
             vec![],
             true,
@@ -4120,6 +6489,7 @@ data: "result":{}}
             body.clone(),
             None,
             None,
+            None,
         );
         let req = builder.build().unwrap();
         assert_eq!(req.method(), "POST");
@@ -4144,6 +6514,7 @@ data: "result":{}}
             "http://localhost:8080/mcp",
             body,
             Some("session-abc-123"),
+            Some("2025-06-18"),
             None,
         );
         let req = builder.build().unwrap();
@@ -4154,6 +6525,7 @@ data: "result":{}}
             .to_str()
             .unwrap();
         assert_eq!(sid, "session-abc-123");
+        assert_eq!(req.headers()[PROTOCOL_VERSION_HEADER], "2025-06-18");
     }
 
     #[test]
@@ -4168,6 +6540,7 @@ data: "result":{}}
             "http://localhost:8080/mcp",
             body,
             None,
+            None,
             Some(&custom_headers),
         );
         let req = builder.build().unwrap();
@@ -4193,6 +6566,7 @@ data: "result":{}}
             "http://example.com/mcp",
             body,
             Some("sess-xyz"),
+            None,
             Some(&custom_headers),
         );
         let req = builder.build().unwrap();
@@ -4443,6 +6817,7 @@ data: "result":{}}
         let notification = JsonRpcNotification {
             jsonrpc: "2.0",
             method: "initialized".to_string(),
+            params: None,
         };
         let json = serde_json::to_value(&notification).unwrap();
         assert_eq!(json["jsonrpc"], "2.0");
@@ -4456,6 +6831,7 @@ data: "result":{}}
         let notification = JsonRpcNotification {
             jsonrpc: "2.0",
             method: "notifications/initialized".to_string(),
+            params: None,
         };
         let json = serde_json::to_value(&notification).unwrap();
         assert_eq!(json["method"], "notifications/initialized");
@@ -4615,7 +6991,10 @@ data: "result":{}}
             content: vec![],
             is_error: true,
             error: Some(error_msg.clone()),
+            error_code: None,
+            error_data: None,
             execution_time_ms: elapsed,
+            cached: false,
         };
         assert!(!result.success);
         assert!(result.is_error);
@@ -4633,8 +7012,11 @@ data: "result":{}}
             success: false,
             content: vec![],
             is_error: true,
-            error: Some(format!("HTTP error: {}", body)),
+            error: Some(format!("HTTP error: {}", body_snippet(body, 500))),
+            error_code: None,
+            error_data: None,
             execution_time_ms: elapsed,
+            cached: false,
         };
         assert_eq!(
             result.error,
@@ -4699,6 +7081,7 @@ data: "result":{}}
             McpServerInfo {
                 name: "unknown".to_string(),
                 version: None,
+                protocol_version: None,
             },
             vec![],
             false,
@@ -4740,6 +7123,7 @@ data: "result":{}}
                 .get("version")
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string()),
+            protocol_version: None,
         });
         assert!(server_info.is_some());
         let si = server_info.unwrap();
@@ -4769,6 +7153,7 @@ data: "result":{}}
                 .get("version")
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string()),
+            protocol_version: None,
         });
         assert!(server_info.is_none());
     }
@@ -5021,6 +7406,274 @@ data: "result":{}}
         assert!(!contains_auth_error3);
     }
 
+    #[test]
+    fn test_detect_auth_failure_npm() {
+        let err = detect_auth_failure("npm ERR! code E401\nnpm ERR! Unable to authenticate")
+            .expect("npm E401 should be detected");
+        assert!(err.starts_with("npm authentication error: npm ERR! code E401"));
+        assert!(err.contains("npm login"));
+
+        let err = detect_auth_failure("Access token expired for @company/mcp-server").unwrap();
+        assert!(err.contains("Access token expired for @company/mcp-server"));
+    }
+
+    #[test]
+    fn test_detect_auth_failure_uvx() {
+        let stderr = "  × Failed to download `private-mcp==1.0.0`\n  ╰─▶ HTTP status client error (401 Unauthorized) for url (https://pypi.example.com/simple/private-mcp/)";
+        let err = detect_auth_failure(stderr).expect("uv 401 should be detected");
+        assert!(err.starts_with("uvx authentication error:"));
+        assert!(err.contains("uv auth login"));
+    }
+
+    #[test]
+    fn test_detect_auth_failure_pip() {
+        let stderr = "ERROR: HTTP error 401 while getting https://pypi.example.com/packages/x.whl";
+        let err = detect_auth_failure(stderr).expect("pip 401 should be detected");
+        assert!(err.starts_with("pip authentication error:"));
+    }
+
+    #[test]
+    fn test_detect_auth_failure_cargo() {
+        let stderr = "error: no token found for `my-registry`, please run `cargo login --registry my-registry`";
+        let err = detect_auth_failure(stderr).expect("cargo missing token should be detected");
+        assert!(err.starts_with("cargo authentication error:"));
+        assert!(err.contains("cargo login"));
+    }
+
+    #[test]
+    fn test_detect_auth_failure_ignores_unrelated_stderr() {
+        assert!(detect_auth_failure("").is_none());
+        assert!(detect_auth_failure("Server listening on stdio\nsome other error").is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stdio_auth_failure_detected_while_process_alive() {
+        // The runner prints an auth error but never exits, like npx waiting on a retry
+        let mut client = StdioMcpClient::spawn_process(
+            &SpawnOptions::new("echo 'npm ERR! code E401' >&2; sleep 30", &[]),
+            10,
+        )
+        .unwrap();
+
+        let start = Instant::now();
+        let err = client.read_response(1).unwrap_err().to_string();
+        assert!(err.starts_with("npm authentication error:"), "{}", err);
+        assert!(start.elapsed() < Duration::from_secs(5));
+        client.close();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stdio_exit_reports_full_stderr() {
+        let mut client = StdioMcpClient::spawn_process(
+            &SpawnOptions::new("echo 'first line' >&2; echo 'second line' >&2", &[]),
+            10,
+        )
+        .unwrap();
+
+        let err = client.read_response(1).unwrap_err().to_string();
+        assert_eq!(err, "Process stderr: first line\nsecond line");
+        client.close();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_diagnose_stdio_framing_flags_noise() {
+        let script = concat!(
+            "echo 'Starting server...'; read l; ",
+            r#"echo '{"jsonrpc":"2.0","id":1,"result":{"serverInfo":{"name":"s"},"capabilities":{}}}'; "#,
+            "read l; read l; echo 'debug: listing tools'; ",
+            r#"echo '{"jsonrpc":"2.0","id":2,"result":{"tools":[]}}'; "#,
+            "echo bye",
+        );
+        let report = diagnose_stdio_framing(&SpawnOptions::new(script, &[]), 10);
+        assert!(report.handshake_succeeded, "{:?}", report.error);
+        assert_eq!(report.json_rpc_lines, 2);
+        assert_eq!(report.noise_lines, 3);
+        assert!(report.pollutes_stdout);
+        let noise: Vec<&str> = report
+            .lines
+            .iter()
+            .filter(|l| !l.is_json_rpc)
+            .map(|l| l.text.as_str())
+            .collect();
+        assert_eq!(
+            noise,
+            vec!["Starting server...", "debug: listing tools", "bye"]
+        );
+
+        let clean = concat!(
+            "read l; ",
+            r#"echo '{"jsonrpc":"2.0","id":1,"result":{"capabilities":{}}}'; "#,
+            "read l; read l; ",
+            r#"echo '{"jsonrpc":"2.0","id":2,"result":{"tools":[]}}'"#,
+        );
+        let report = diagnose_stdio_framing(&SpawnOptions::new(clean, &[]), 10);
+        assert!(report.handshake_succeeded);
+        assert!(!report.pollutes_stdout);
+    }
+
+    #[test]
+    fn test_is_json_rpc_line() {
+        assert!(is_json_rpc_line(r#"{"jsonrpc":"2.0","method":"ping"}"#));
+        assert!(!is_json_rpc_line(r#"{"level":"info"}"#));
+        assert!(!is_json_rpc_line("Server ready"));
+    }
+
+    #[test]
+    fn test_parse_env_file() {
+        let vars = parse_env_file(
+            "# comment\nexport API_KEY=abc123\nQUOTED=\"with spaces\"\nSINGLE='x=y'\nTRAILING=value # note\n\nnot a var\n",
+        );
+        assert_eq!(vars.len(), 4);
+        assert_eq!(vars["API_KEY"], "abc123");
+        assert_eq!(vars["QUOTED"], "with spaces");
+        assert_eq!(vars["SINGLE"], "x=y");
+        assert_eq!(vars["TRAILING"], "value");
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_resolve_env_file_relative_to_source_config() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let project = temp_dir.path().to_str().unwrap();
+        let source = temp_dir.path().join(".mcp.json");
+        let source = source.to_str().unwrap();
+
+        assert_eq!(
+            resolve_env_file(".env", Some(source), None),
+            temp_dir.path().join(".env")
+        );
+        // Without a source config the fallback directory is used
+        let fallback = temp_dir.path().join("fallback");
+        assert_eq!(
+            resolve_env_file("secrets/.env", None, Some(&fallback)),
+            fallback.join("secrets/.env")
+        );
+        assert_eq!(
+            resolve_env_file("/etc/mcp.env", Some(source), None),
+            std::path::PathBuf::from("/etc/mcp.env")
+        );
+        assert!(resolve_env_file(".env", Some(project), None).is_absolute());
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_spawn_process_merges_env_file_under_explicit_env() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let env_file = temp_dir.path().join(".env");
+        std::fs::write(&env_file, "FROM_FILE=file\nOVERRIDDEN=file\n").unwrap();
+        let env = HashMap::from([("OVERRIDDEN".to_string(), "explicit".to_string())]);
+
+        let mut client = StdioMcpClient::spawn_process(
+            &SpawnOptions {
+                env: Some(&env),
+                env_file: Some(env_file.to_str().unwrap()),
+                ..SpawnOptions::new("echo \"$FROM_FILE $OVERRIDDEN\" >&2", &[])
+            },
+            10,
+        )
+        .unwrap();
+
+        let err = client.read_response(1).unwrap_err().to_string();
+        assert_eq!(err, "Process stderr: file explicit");
+        client.close();
+
+        assert!(StdioMcpClient::spawn_process(
+            &SpawnOptions {
+                env_file: Some(temp_dir.path().join("missing.env").to_str().unwrap()),
+                ..SpawnOptions::new("true", &[])
+            },
+            10
+        )
+        .is_err());
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_spawn_process_clean_env_keeps_only_configured_vars() {
+        // Any variable of ours that a clean spawn should not pass on
+        let (inherited, _) = std::env::vars()
+            .find(|(k, v)| {
+                !CLEAN_ENV_KEEP.contains(&k.as_str())
+                    && !v.is_empty()
+                    && k.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            })
+            .expect("test process has some environment");
+        let env = HashMap::from([("EXPLICIT".to_string(), "set".to_string())]);
+        let script = format!(
+            "echo \"[${{{}}}] $EXPLICIT ${{PATH:+path}}\" >&2",
+            inherited
+        );
+
+        let stderr_of = |inherit_env: bool| {
+            let mut client = StdioMcpClient::spawn_process(
+                &SpawnOptions {
+                    env: Some(&env),
+                    inherit_env,
+                    ..SpawnOptions::new(&script, &[])
+                },
+                10,
+            )
+            .unwrap();
+            let err = client.read_response(1).unwrap_err().to_string();
+            client.close();
+            err
+        };
+
+        assert_eq!(stderr_of(false), "Process stderr: [] set path");
+        assert_ne!(stderr_of(true), "Process stderr: [] set path");
+    }
+
+    #[test]
+    fn test_validate_spawn_shell() {
+        for shell in SPAWN_SHELLS {
+            assert!(validate_spawn_shell(shell).is_ok());
+        }
+        #[cfg(not(windows))]
+        assert!(validate_spawn_shell("/usr/local/bin/fish").is_ok());
+        assert!(validate_spawn_shell("fish").is_err());
+        assert!(validate_spawn_shell("").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_spawn_process_shell_selection() {
+        let stderr_of = |command: &str, args: &[String], shell: Option<&str>| {
+            let mut client = StdioMcpClient::spawn_process(
+                &SpawnOptions {
+                    shell,
+                    ..SpawnOptions::new(command, args)
+                },
+                10,
+            )
+            .unwrap();
+            let err = client.read_response(1).unwrap_err().to_string();
+            client.close();
+            err
+        };
+
+        // `$0` names the shell running the command line
+        assert_eq!(stderr_of("echo $0 >&2", &[], None), "Process stderr: sh");
+        assert_eq!(
+            stderr_of("echo $0 >&2", &[], Some("/bin/sh")),
+            "Process stderr: /bin/sh"
+        );
+
+        // Without a shell, args reach the program untouched
+        let args = vec!["-c".to_string(), "echo 'a  b' >&2".to_string()];
+        assert_eq!(stderr_of("sh", &args, Some("none")), "Process stderr: a  b");
+        assert!(StdioMcpClient::spawn_process(
+            &SpawnOptions {
+                shell: Some("none"),
+                ..SpawnOptions::new("echo hi", &[])
+            },
+            10
+        )
+        .is_err());
+    }
+
     #[test]
     fn test_http_error_categorization_dns() {
         let err_str = "dns error: failed to lookup hostname";
@@ -5095,6 +7748,7 @@ data: "result":{}}
             McpServerInfo {
                 name: "mega-server".to_string(),
                 version: Some("5.0.0".to_string()),
+                protocol_version: None,
             },
             tools,
             true,
@@ -5134,6 +7788,7 @@ data: "result":{}}
                 .get("version")
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string()),
+            protocol_version: None,
         };
         assert_eq!(server_info.name, "my-mcp");
         assert_eq!(server_info.version, Some("1.2.3".to_string()));
@@ -5213,6 +7868,7 @@ data: "result":{}}
         let info = McpServerInfo {
             name: "server/with-special_chars.v2".to_string(),
             version: Some("1.0.0-rc.1+build.123".to_string()),
+            protocol_version: None,
         };
         let json = serde_json::to_string(&info).unwrap();
         let parsed: McpServerInfo = serde_json::from_str(&json).unwrap();
@@ -5266,4 +7922,213 @@ data: "result":{}}
             Some("accepted")
         );
     }
+
+    // =========================================================================
+    // Redirect handling tests
+    // =========================================================================
+
+    #[test]
+    fn test_next_redirect_hop_rules() {
+        use reqwest::header::HeaderValue;
+        use reqwest::{Method, StatusCode, Url};
+
+        let from = Url::parse("https://mcp.example.com/mcp").unwrap();
+        let slash = HeaderValue::from_static("/mcp/");
+
+        let hop = next_redirect_hop(
+            &Method::POST,
+            &from,
+            StatusCode::MOVED_PERMANENTLY,
+            Some(&slash),
+            0,
+        )
+        .unwrap();
+        assert_eq!(hop.unwrap().as_str(), "https://mcp.example.com/mcp/");
+        assert!(
+            next_redirect_hop(&Method::POST, &from, StatusCode::OK, None, 0)
+                .unwrap()
+                .is_none()
+        );
+
+        let insecure = HeaderValue::from_static("http://mcp.example.com/mcp");
+        let err = next_redirect_hop(&Method::GET, &from, StatusCode::FOUND, Some(&insecure), 0)
+            .unwrap_err();
+        assert!(err.to_string().contains("insecure"));
+
+        let err = next_redirect_hop(&Method::POST, &from, StatusCode::SEE_OTHER, Some(&slash), 0)
+            .unwrap_err();
+        assert!(err.to_string().contains("303"));
+
+        assert!(next_redirect_hop(
+            &Method::POST,
+            &from,
+            StatusCode::TEMPORARY_REDIRECT,
+            Some(&slash),
+            MAX_REDIRECTS
+        )
+        .is_err());
+        assert!(next_redirect_hop(&Method::POST, &from, StatusCode::FOUND, None, 0).is_err());
+    }
+
+    #[test]
+    fn test_strip_cross_origin_headers() {
+        use reqwest::header::{HeaderMap, HeaderValue};
+        use reqwest::Url;
+
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", HeaderValue::from_static("Bearer x"));
+        headers.insert("x-api-key", HeaderValue::from_static("k"));
+        headers.insert("x-region", HeaderValue::from_static("eu"));
+
+        let from = Url::parse("https://a.example.com/mcp").unwrap();
+        let mut same = headers.clone();
+        strip_cross_origin_headers(
+            &from,
+            &Url::parse("https://a.example.com/mcp/").unwrap(),
+            &mut same,
+        );
+        assert_eq!(same.len(), 3);
+
+        strip_cross_origin_headers(
+            &from,
+            &Url::parse("https://b.example.com/mcp").unwrap(),
+            &mut headers,
+        );
+        assert!(headers.get("authorization").is_none());
+        assert!(headers.get("x-api-key").is_none());
+        assert_eq!(headers.get("x-region").unwrap(), "eu");
+    }
+
+    #[tokio::test]
+    async fn test_send_following_redirects_replays_post_and_scopes_headers() {
+        use wiremock::matchers::{body_string, header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let origin = MockServer::start().await;
+        let other = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/mcp"))
+            .respond_with(ResponseTemplate::new(301).insert_header("location", "/mcp/"))
+            .mount(&origin)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/mcp/"))
+            .and(header("x-api-key", "secret"))
+            .and(body_string("{}"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+            .mount(&origin)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/moved"))
+            .respond_with(
+                ResponseTemplate::new(307)
+                    .insert_header("location", format!("{}/mcp", other.uri())),
+            )
+            .mount(&origin)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/mcp"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&other)
+            .await;
+
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .unwrap();
+
+        let response = send_following_redirects_async(
+            client
+                .post(format!("{}/mcp", origin.uri()))
+                .header("x-api-key", "secret")
+                .body("{}"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.text().await.unwrap(), "ok");
+
+        let response = send_following_redirects_async(
+            client
+                .post(format!("{}/moved", origin.uri()))
+                .header("x-api-key", "secret")
+                .body("{}"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), 200);
+        let received = other.received_requests().await.unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].body, b"{}");
+        assert!(!received[0].headers.contains_key("x-api-key"));
+    }
+
+    /// A WebSocket MCP server on its own thread that sends a notification
+    /// and a response to an unrelated id ahead of every real response
+    fn spawn_websocket_mcp_server() -> String {
+        use futures::SinkExt;
+        use tokio_tungstenite::tungstenite::Message;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        listener.set_nonblocking(true).unwrap();
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            rt.block_on(async move {
+                let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+                while let Ok((stream, _)) = listener.accept().await {
+                    let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                    while let Some(Ok(Message::Text(text))) = ws.next().await {
+                        let request: Value = serde_json::from_str(text.as_str()).unwrap();
+                        let Some(id) = request.get("id").cloned() else {
+                            continue;
+                        };
+                        let result = match request["method"].as_str().unwrap() {
+                            "initialize" => json!({
+                                "serverInfo": {"name": "ws-server", "version": "1.0"},
+                                "capabilities": {"tools": {}, "prompts": {}}
+                            }),
+                            "tools/list" => json!({"tools": [{"name": "echo"}]}),
+                            _ => json!({
+                                "content": [{"type": "text", "text": request["params"]["arguments"]["msg"]}]
+                            }),
+                        };
+                        for noise in [
+                            json!({"jsonrpc": "2.0", "method": "notifications/message", "params": {}}),
+                            json!({"jsonrpc": "2.0", "id": 999, "result": {}}),
+                            json!({"jsonrpc": "2.0", "id": id, "result": result}),
+                        ] {
+                            ws.send(Message::Text(noise.to_string().into())).await.unwrap();
+                        }
+                    }
+                }
+            });
+        });
+        format!("ws://{}", addr)
+    }
+
+    #[test]
+    fn test_websocket_mcp_skips_unrelated_messages() {
+        let url = spawn_websocket_mcp_server();
+
+        let result = test_websocket_mcp(&url, None, 5);
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.server_info.unwrap().name, "ws-server");
+        assert_eq!(result.tools.len(), 1);
+        assert!(result.prompts_supported && !result.resources_supported);
+
+        let mut client = WebSocketMcpClient::connect(&url, None, 5).unwrap();
+        assert_eq!(client.tools()[0].name, "echo");
+        let call = client.call_tool("echo", json!({"msg": "hi"})).unwrap();
+        assert!(call.success);
+        assert!(matches!(&call.content[0], ToolContent::Text { text } if text == "hi"));
+        client.close();
+    }
+
+    #[test]
+    fn test_websocket_mcp_rejects_non_ws_url() {
+        let result = test_websocket_mcp("http://127.0.0.1:1/mcp", None, 1);
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("ws://"));
+    }
 }
@@ -0,0 +1,352 @@
+use crate::db::Database;
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// How an MCP is launched, normalized so JSON key order and empty vs missing
+/// collections don't count as differences
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpDefinition {
+    #[serde(rename = "type")]
+    pub mcp_type: String,
+    pub command: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    pub url: Option<String>,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+}
+
+impl McpDefinition {
+    pub fn new(
+        mcp_type: &str,
+        command: Option<&str>,
+        args: Option<&Vec<String>>,
+        url: Option<&str>,
+        headers: Option<&HashMap<String, String>>,
+        env: Option<&HashMap<String, String>>,
+    ) -> Self {
+        let sorted = |map: Option<&HashMap<String, String>>| {
+            map.map(|m| m.clone().into_iter().collect())
+                .unwrap_or_default()
+        };
+        Self {
+            mcp_type: mcp_type.to_string(),
+            command: command.map(str::to_string),
+            args: args.cloned().unwrap_or_default(),
+            url: url.map(str::to_string),
+            headers: sorted(headers),
+            env: sorted(env),
+        }
+    }
+
+    /// The `args` column, NULL when there are none
+    pub(crate) fn args_json(&self) -> Option<String> {
+        json_or_null(&self.args, self.args.is_empty())
+    }
+
+    /// The `headers` column, NULL when there are none
+    pub(crate) fn headers_json(&self) -> Option<String> {
+        json_or_null(&self.headers, self.headers.is_empty())
+    }
+
+    /// The `env` column, NULL when there are none
+    pub(crate) fn env_json(&self) -> Option<String> {
+        json_or_null(&self.env, self.env.is_empty())
+    }
+
+    /// The definition currently stored on a library MCP
+    fn load(db: &Database, mcp_id: i64) -> Result<Self> {
+        Ok(db.conn().query_row(
+            "SELECT type, command, args, url, headers, env FROM mcps WHERE id = ?",
+            [mcp_id],
+            |row| {
+                Ok(Self {
+                    mcp_type: row.get(0)?,
+                    command: row.get(1)?,
+                    args: parse_or_default(row.get(2)?),
+                    url: row.get(3)?,
+                    headers: parse_or_default(row.get(4)?),
+                    env: parse_or_default(row.get(5)?),
+                })
+            },
+        )?)
+    }
+}
+
+fn parse_or_default<T: DeserializeOwned + Default>(json: Option<String>) -> T {
+    json.and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+/// JSON for a collection column, or NULL when it's empty
+fn json_or_null<T: Serialize>(value: &T, is_empty: bool) -> Option<String> {
+    (!is_empty).then(|| serde_json::to_string(value).unwrap())
+}
+
+/// A definition from another source that disagrees with the library MCP
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompetingDefinition {
+    pub id: i64,
+    pub source_path: String,
+    pub definition: McpDefinition,
+    pub detected_at: String,
+}
+
+/// A library MCP whose name is defined differently elsewhere
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpConflict {
+    pub mcp_id: i64,
+    pub name: String,
+    pub source_path: Option<String>,
+    pub current: McpDefinition,
+    pub competing: Vec<CompetingDefinition>,
+}
+
+/// Whether the library MCP was imported from `source_path`. An MCP with no
+/// source yet counts, since the scanner adopts it.
+pub(crate) fn is_same_source(db: &Database, mcp_id: i64, source_path: &str) -> Result<bool> {
+    let stored: Option<String> = db.conn().query_row(
+        "SELECT source_path FROM mcps WHERE id = ?",
+        [mcp_id],
+        |row| row.get(0),
+    )?;
+    Ok(stored.is_none_or(|s| s.is_empty() || s == source_path))
+}
+
+/// Compare a definition scanned from `source_path` with the library MCP it
+/// matched by name. A different one is recorded as a conflict instead of
+/// being applied; a matching one clears any conflict from that source.
+pub(crate) fn note_scanned_definition(
+    db: &Database,
+    mcp_id: i64,
+    name: &str,
+    source_path: &str,
+    scanned: &McpDefinition,
+) -> Result<()> {
+    if McpDefinition::load(db, mcp_id)? == *scanned {
+        db.conn().execute(
+            "DELETE FROM mcp_conflicts WHERE mcp_id = ? AND source_path = ?",
+            params![mcp_id, source_path],
+        )?;
+        return Ok(());
+    }
+
+    let recorded: Option<String> = db
+        .conn()
+        .query_row(
+            "SELECT definition FROM mcp_conflicts WHERE mcp_id = ? AND source_path = ?",
+            params![mcp_id, source_path],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let definition = serde_json::to_string(scanned)?;
+    if recorded.as_deref() == Some(definition.as_str()) {
+        return Ok(());
+    }
+
+    log::warn!(
+        "MCP '{}' in {} differs from the library definition; keeping the existing one",
+        name,
+        source_path
+    );
+    record_conflict(db, mcp_id, source_path, scanned, false)
+}
+
+fn record_conflict(
+    db: &Database,
+    mcp_id: i64,
+    source_path: &str,
+    definition: &McpDefinition,
+    resolved: bool,
+) -> Result<()> {
+    db.conn().execute(
+        "INSERT INTO mcp_conflicts (mcp_id, source_path, definition, resolved) VALUES (?, ?, ?, ?)
+         ON CONFLICT(mcp_id, source_path) DO UPDATE SET
+             definition = excluded.definition,
+             resolved = excluded.resolved,
+             detected_at = CURRENT_TIMESTAMP",
+        params![
+            mcp_id,
+            source_path,
+            serde_json::to_string(definition)?,
+            resolved
+        ],
+    )?;
+    Ok(())
+}
+
+/// Unresolved conflicts, grouped by library MCP
+pub fn get_mcp_conflicts(db: &Database) -> Result<Vec<McpConflict>> {
+    let mut stmt = db.conn().prepare(
+        "SELECT c.id, c.mcp_id, m.name, m.source_path, c.source_path, c.definition, c.detected_at
+         FROM mcp_conflicts c
+         JOIN mcps m ON m.id = c.mcp_id
+         WHERE c.resolved = 0
+         ORDER BY m.name, c.mcp_id, c.detected_at",
+    )?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, String>(5)?,
+                row.get::<_, String>(6)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut conflicts: Vec<McpConflict> = Vec::new();
+    for (id, mcp_id, name, mcp_source_path, source_path, definition, detected_at) in rows {
+        let Ok(definition) = serde_json::from_str(&definition) else {
+            log::warn!("Skipping unreadable conflict {} for MCP '{}'", id, name);
+            continue;
+        };
+        if conflicts.last().is_none_or(|c| c.mcp_id != mcp_id) {
+            conflicts.push(McpConflict {
+                mcp_id,
+                name,
+                source_path: mcp_source_path,
+                current: McpDefinition::load(db, mcp_id)?,
+                competing: Vec::new(),
+            });
+        }
+        if let Some(conflict) = conflicts.last_mut() {
+            conflict.competing.push(CompetingDefinition {
+                id,
+                source_path,
+                definition,
+                detected_at,
+            });
+        }
+    }
+    Ok(conflicts)
+}
+
+/// Settle an MCP's conflicts. `keep` is the id of a competing definition to
+/// apply to the library MCP, or `None` to keep the current one. The losing
+/// definitions are marked resolved so rescans only raise them again if they
+/// change.
+pub fn resolve_mcp_conflict(db: &Database, mcp_id: i64, keep: Option<i64>) -> Result<()> {
+    if let Some(conflict_id) = keep {
+        let (source_path, definition): (String, String) = db
+            .conn()
+            .query_row(
+                "SELECT source_path, definition FROM mcp_conflicts WHERE id = ? AND mcp_id = ?",
+                params![conflict_id, mcp_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?
+            .ok_or_else(|| {
+                anyhow::anyhow!("Conflict {} not found for MCP {}", conflict_id, mcp_id)
+            })?;
+        let chosen: McpDefinition = serde_json::from_str(&definition)?;
+
+        // The definition being replaced becomes a resolved conflict of its own
+        let current_source: Option<String> = db.conn().query_row(
+            "SELECT source_path FROM mcps WHERE id = ?",
+            [mcp_id],
+            |row| row.get(0),
+        )?;
+        if let Some(current_source) = current_source.filter(|s| !s.is_empty()) {
+            let current = McpDefinition::load(db, mcp_id)?;
+            record_conflict(db, mcp_id, &current_source, &current, true)?;
+        }
+
+        db.conn().execute(
+            "UPDATE mcps SET type = ?, command = ?, args = ?, url = ?, headers = ?, env = ?,
+                    source_path = ?, source_hash = NULL, updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?",
+            params![
+                chosen.mcp_type,
+                chosen.command,
+                chosen.args_json(),
+                chosen.url,
+                chosen.headers_json(),
+                chosen.env_json(),
+                source_path,
+                mcp_id
+            ],
+        )?;
+        db.conn()
+            .execute("DELETE FROM mcp_conflicts WHERE id = ?", [conflict_id])?;
+    }
+
+    db.conn().execute(
+        "UPDATE mcp_conflicts SET resolved = 1 WHERE mcp_id = ?",
+        [mcp_id],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn insert_mcp(db: &Database, name: &str, command: &str, source_path: &str) -> i64 {
+        db.conn()
+            .execute(
+                "INSERT INTO mcps (name, type, command, args, source, source_path)
+                 VALUES (?, 'stdio', ?, '[\"--root\",\"/\"]', 'auto-detected', ?)",
+                params![name, command, source_path],
+            )
+            .unwrap();
+        db.conn().last_insert_rowid()
+    }
+
+    fn stdio(command: &str) -> McpDefinition {
+        let args = vec!["--root".to_string(), "/".to_string()];
+        McpDefinition::new("stdio", Some(command), Some(&args), None, None, None)
+    }
+
+    #[test]
+    fn test_different_definition_is_recorded_not_applied() {
+        let db = Database::in_memory().unwrap();
+        let id = insert_mcp(&db, "filesystem", "npx", "~/.claude.json");
+
+        assert!(!is_same_source(&db, id, "/opencode.json").unwrap());
+        note_scanned_definition(&db, id, "filesystem", "/opencode.json", &stdio("uvx")).unwrap();
+        // Seeing the same definition again doesn't duplicate it
+        note_scanned_definition(&db, id, "filesystem", "/opencode.json", &stdio("uvx")).unwrap();
+        // An agreeing source isn't a conflict
+        note_scanned_definition(&db, id, "filesystem", "/cursor.json", &stdio("npx")).unwrap();
+
+        let conflicts = get_mcp_conflicts(&db).unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].current, stdio("npx"));
+        assert_eq!(conflicts[0].competing.len(), 1);
+        assert_eq!(conflicts[0].competing[0].source_path, "/opencode.json");
+        assert_eq!(conflicts[0].competing[0].definition, stdio("uvx"));
+    }
+
+    #[test]
+    fn test_resolve_applies_chosen_definition_and_stays_quiet_on_rescan() {
+        let db = Database::in_memory().unwrap();
+        let id = insert_mcp(&db, "filesystem", "npx", "~/.claude.json");
+        note_scanned_definition(&db, id, "filesystem", "/opencode.json", &stdio("uvx")).unwrap();
+        let conflict_id = get_mcp_conflicts(&db).unwrap()[0].competing[0].id;
+
+        resolve_mcp_conflict(&db, id, Some(conflict_id)).unwrap();
+
+        assert_eq!(McpDefinition::load(&db, id).unwrap(), stdio("uvx"));
+        assert!(is_same_source(&db, id, "/opencode.json").unwrap());
+        assert!(get_mcp_conflicts(&db).unwrap().is_empty());
+
+        // The replaced definition was remembered as resolved
+        note_scanned_definition(&db, id, "filesystem", "~/.claude.json", &stdio("npx")).unwrap();
+        assert!(get_mcp_conflicts(&db).unwrap().is_empty());
+        // ...until it changes
+        note_scanned_definition(&db, id, "filesystem", "~/.claude.json", &stdio("bunx")).unwrap();
+        assert_eq!(get_mcp_conflicts(&db).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,140 @@
+//! Fetch an `mcp.json` document published at a URL
+//!
+//! MCP configs are often shared as a raw GitHub file or a gist. The fetch is
+//! bounded in time and size, and refuses responses that are clearly not JSON
+//! (an HTML page is the usual result of pasting a non-raw link).
+
+use anyhow::{anyhow, bail, Result};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Client;
+use std::time::Duration;
+
+/// How long the whole request may take
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Largest document accepted; real configs are a few KB
+pub const MAX_MCP_JSON_BYTES: usize = 1024 * 1024;
+
+const USER_AGENT: &str = "claude-code-tool-manager/1.0";
+
+/// Whether a Content-Type may hold JSON. Raw GitHub and gists serve
+/// `text/plain`, so only markup types are rejected.
+fn is_acceptable_content_type(content_type: &str) -> bool {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    !matches!(
+        mime.as_str(),
+        "text/html" | "application/xhtml+xml" | "text/xml" | "application/xml"
+    )
+}
+
+/// Download the document at `url` as text
+pub async fn fetch_mcp_json(url: &str) -> Result<String> {
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|e| anyhow!("Invalid URL: {}", e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        bail!("Only http and https URLs can be imported");
+    }
+    // GitHub blob pages are HTML; fetch the raw file instead
+    let raw_url = crate::commands::repos::convert_to_raw_url(parsed.as_str());
+
+    let client = Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(FETCH_TIMEOUT)
+        .build()?;
+    let mut response = client.get(&raw_url).send().await.map_err(|e| {
+        if e.is_timeout() {
+            anyhow!("Timed out fetching {}", raw_url)
+        } else {
+            anyhow!("Failed to fetch {}: {}", raw_url, e)
+        }
+    })?;
+
+    if !response.status().is_success() {
+        bail!("Failed to fetch {}: HTTP {}", raw_url, response.status());
+    }
+    if let Some(content_type) = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+    {
+        if !is_acceptable_content_type(content_type) {
+            bail!(
+                "{} returned {} instead of JSON; use the raw file URL",
+                raw_url,
+                content_type
+            );
+        }
+    }
+    if response
+        .content_length()
+        .is_some_and(|len| len > MAX_MCP_JSON_BYTES as u64)
+    {
+        bail!("Document exceeds {} bytes", MAX_MCP_JSON_BYTES);
+    }
+
+    // Content-Length may be missing, so enforce the cap while reading
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > MAX_MCP_JSON_BYTES {
+            bail!("Document exceeds {} bytes", MAX_MCP_JSON_BYTES);
+        }
+        body.extend_from_slice(&chunk);
+    }
+    String::from_utf8(body).map_err(|_| anyhow!("Document is not valid UTF-8"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Answer every connection with `content_type` and `body`
+    async fn serve(content_type: &'static str, body: String) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let _ = socket.read(&mut buf).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    content_type,
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        format!("http://{}/mcp.json", addr)
+    }
+
+    #[test]
+    fn test_is_acceptable_content_type() {
+        assert!(is_acceptable_content_type("application/json"));
+        assert!(is_acceptable_content_type("text/plain; charset=utf-8"));
+        assert!(!is_acceptable_content_type("text/html; charset=utf-8"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_mcp_json() {
+        let json = r#"{"mcpServers":{"memory":{"command":"npx"}}}"#.to_string();
+        let url = serve("text/plain; charset=utf-8", json.clone()).await;
+        assert_eq!(fetch_mcp_json(&url).await.unwrap(), json);
+
+        let url = serve("text/html", "<html></html>".to_string()).await;
+        let err = fetch_mcp_json(&url).await.unwrap_err().to_string();
+        assert!(err.contains("instead of JSON"));
+
+        let url = serve("application/json", "x".repeat(MAX_MCP_JSON_BYTES + 1)).await;
+        let err = fetch_mcp_json(&url).await.unwrap_err().to_string();
+        assert!(err.contains("exceeds"));
+
+        assert!(fetch_mcp_json("file:///etc/passwd").await.is_err());
+        assert!(fetch_mcp_json("not a url").await.is_err());
+    }
+}
@@ -0,0 +1,220 @@
+//! Shared writer for editors that keep MCP servers in a JSON config
+//!
+//! Editor config modules only decide how a single MCP is rendered. Reading
+//! the existing file, refusing to clobber JSON we can't parse, backing up, and
+//! replacing the servers object under the editor's key all happen here.
+
+use crate::services::pending_changes::read_config_file;
+use anyhow::Result;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// MCP tuple for writing (same format used by other editors)
+pub type McpTuple = (
+    String,         // name
+    String,         // type (stdio, sse, http)
+    Option<String>, // command
+    Option<String>, // args (JSON)
+    Option<String>, // url
+    Option<String>, // headers (JSON)
+    Option<String>, // env (JSON)
+);
+
+/// Key holding the MCP servers object in an editor's JSON config. `None` for
+/// editors whose config isn't JSON (Codex uses TOML) and unknown editors.
+pub fn mcp_config_key(editor: &str) -> Option<&'static str> {
+    match editor {
+        "claude_code" | "cursor" | "gemini" | "windsurf" => Some("mcpServers"),
+        "copilot" => Some("servers"),
+        "opencode" => Some("mcp"),
+        _ => None,
+    }
+}
+
+/// Replace the MCP servers in `editor`'s JSON config at `path` with `mcps`,
+/// each rendered by `render` (`None` skips it). Other top-level keys are kept.
+/// An empty `mcps` leaves the file untouched so externally-managed configs
+/// survive; a file that isn't valid JSON is never overwritten.
+pub fn write_mcp_servers(
+    path: &Path,
+    editor: &str,
+    label: &str,
+    mcps: &[McpTuple],
+    render: impl Fn(&McpTuple) -> Option<Value>,
+) -> Result<()> {
+    let Some(json) = render_mcp_servers(path, editor, label, mcps, render)? else {
+        return Ok(());
+    };
+
+    backup_config_file(path)?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    std::fs::write(path, json)?;
+
+    Ok(())
+}
+
+/// The content [`write_mcp_servers`] would write, without touching disk.
+/// `None` when the file would be left as is.
+pub fn render_mcp_servers(
+    path: &Path,
+    editor: &str,
+    label: &str,
+    mcps: &[McpTuple],
+    render: impl Fn(&McpTuple) -> Option<Value>,
+) -> Result<Option<String>> {
+    let key = mcp_config_key(editor)
+        .ok_or_else(|| anyhow::anyhow!("Editor '{}' has no JSON MCP config", editor))?;
+
+    // Read existing config (including a queued change) or create new
+    let mut config: Map<String, Value> = if let Some(content) = read_config_file(path)? {
+        serde_json::from_str(&content).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to parse existing {} at {}: {}. \
+                 Refusing to overwrite to prevent data loss.",
+                label,
+                path.display(),
+                e
+            )
+        })?
+    } else {
+        Map::new()
+    };
+
+    // Skip overwrite when DB has no MCPs — preserves externally-managed configs
+    if mcps.is_empty() {
+        return Ok(None);
+    }
+
+    config.insert(key.to_string(), Value::Object(render_servers(mcps, render)));
+
+    Ok(Some(serde_json::to_string_pretty(&config)?))
+}
+
+/// `mcps` as a servers object keyed by name, each rendered by `render`
+/// (`None` skips it)
+pub fn render_servers(
+    mcps: &[McpTuple],
+    render: impl Fn(&McpTuple) -> Option<Value>,
+) -> Map<String, Value> {
+    mcps.iter()
+        .filter_map(|mcp| render(mcp).map(|server| (mcp.0.clone(), server)))
+        .collect()
+}
+
+/// Create a backup (`<file>.bak`) of an editor config before modifying it
+pub fn backup_config_file(path: &Path) -> Result<()> {
+    crate::utils::backup::backup_file(path)
+}
+
+/// A stored args column (JSON array of strings) as a JSON array
+pub fn args_value(args_json: Option<&str>) -> Option<Value> {
+    let args = serde_json::from_str::<Vec<String>>(args_json?).ok()?;
+    Some(Value::Array(args.into_iter().map(Value::String).collect()))
+}
+
+/// A stored env/headers column (JSON object of strings) as a JSON object
+pub fn string_map_value(map_json: Option<&str>) -> Option<Value> {
+    let map = serde_json::from_str::<HashMap<String, String>>(map_json?).ok()?;
+    Some(Value::Object(
+        map.into_iter()
+            .map(|(k, v)| (k, Value::String(v)))
+            .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn stdio(name: &str) -> McpTuple {
+        (
+            name.to_string(),
+            "stdio".to_string(),
+            Some("node".to_string()),
+            Some(r#"["server.js"]"#.to_string()),
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_mcp_config_key() {
+        assert_eq!(mcp_config_key("claude_code"), Some("mcpServers"));
+        assert_eq!(mcp_config_key("windsurf"), Some("mcpServers"));
+        assert_eq!(mcp_config_key("copilot"), Some("servers"));
+        assert_eq!(mcp_config_key("opencode"), Some("mcp"));
+        assert_eq!(mcp_config_key("codex"), None);
+    }
+
+    #[test]
+    fn test_write_mcp_servers_replaces_only_the_servers_key() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("nested").join("config.json");
+        let render = |mcp: &McpTuple| {
+            (mcp.1 == "stdio")
+                .then(|| json!({ "command": mcp.2, "args": args_value(mcp.3.as_deref()) }))
+        };
+
+        write_mcp_servers(&path, "copilot", "Copilot config", &[stdio("a")], render).unwrap();
+        let mut written: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written["servers"]["a"]["args"][0], "server.js");
+
+        written["inputs"] = json!([{"type": "test"}]);
+        fs::write(&path, written.to_string()).unwrap();
+        let mut remote = stdio("remote");
+        remote.1 = "http".to_string();
+        write_mcp_servers(
+            &path,
+            "copilot",
+            "Copilot config",
+            &[stdio("b"), remote],
+            render,
+        )
+        .unwrap();
+
+        let written: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written["inputs"][0]["type"], "test");
+        assert!(written["servers"].get("a").is_none());
+        assert!(written["servers"].get("remote").is_none());
+        assert_eq!(written["servers"]["b"]["command"], "node");
+        assert!(path.with_extension("json.bak").exists());
+
+        // Nothing to write leaves the file alone
+        assert!(
+            render_mcp_servers(&path, "copilot", "Copilot config", &[], render)
+                .unwrap()
+                .is_none()
+        );
+        write_mcp_servers(&path, "copilot", "Copilot config", &[], render).unwrap();
+        assert!(
+            serde_json::from_str::<Value>(&fs::read_to_string(&path).unwrap()).unwrap()["servers"]
+                .get("b")
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_write_mcp_servers_refuses_bad_json_and_unknown_editors() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("mcp.json");
+        fs::write(&path, "{ not json").unwrap();
+
+        let err = write_mcp_servers(&path, "cursor", "Cursor config", &[stdio("a")], |_| None)
+            .unwrap_err();
+        assert!(err.to_string().contains("Cursor config"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{ not json");
+
+        assert!(
+            write_mcp_servers(&path, "codex", "Codex config", &[stdio("a")], |_| None).is_err()
+        );
+    }
+}
@@ -0,0 +1,374 @@
+//! OAuth bearer tokens for remote MCPs
+//!
+//! A remote MCP can keep an OAuth token endpoint and refresh token next to its
+//! config. Before connecting, an expired (or missing) access token is renewed
+//! with the `refresh_token` grant and stored back, and the MCP's headers get
+//! an `Authorization: Bearer` entry. Tokens are never written to the log.
+
+use crate::db::Database;
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use log::info;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// How long a token refresh may take
+const REFRESH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Tokens this close to expiry are refreshed, so one doesn't lapse mid-handshake
+const EXPIRY_MARGIN_SECS: i64 = 60;
+
+/// OAuth settings stored for an MCP
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpOAuthConfig {
+    pub token_endpoint: String,
+    #[serde(default)]
+    pub client_id: Option<String>,
+    pub refresh_token: String,
+    #[serde(default)]
+    pub access_token: Option<String>,
+    /// RFC 3339 expiry of `access_token`; `None` when the server didn't say
+    #[serde(default)]
+    pub expires_at: Option<String>,
+}
+
+impl McpOAuthConfig {
+    /// Whether the access token is missing or expires within the margin.
+    /// A token without a known expiry is used until the server rejects it.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        if self.access_token.is_none() {
+            return true;
+        }
+        match &self.expires_at {
+            None => false,
+            Some(expires_at) => DateTime::parse_from_rfc3339(expires_at)
+                .map(|at| {
+                    at.with_timezone(&Utc) - ChronoDuration::seconds(EXPIRY_MARGIN_SECS) <= now
+                })
+                .unwrap_or(true),
+        }
+    }
+}
+
+/// What the UI sees of an MCP's OAuth settings; the tokens stay in the database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpOAuthStatus {
+    pub token_endpoint: String,
+    pub client_id: Option<String>,
+    pub has_access_token: bool,
+    pub expires_at: Option<String>,
+}
+
+impl From<&McpOAuthConfig> for McpOAuthStatus {
+    fn from(config: &McpOAuthConfig) -> Self {
+        Self {
+            token_endpoint: config.token_endpoint.clone(),
+            client_id: config.client_id.clone(),
+            has_access_token: config.access_token.is_some(),
+            expires_at: config.expires_at.clone(),
+        }
+    }
+}
+
+/// A token endpoint's answer to a `refresh_token` grant
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    expires_in: Option<i64>,
+    /// Set when the server rotates refresh tokens
+    #[serde(default)]
+    refresh_token: Option<String>,
+}
+
+/// An OAuth error response (RFC 6749 section 5.2)
+#[derive(Debug, Deserialize)]
+struct TokenError {
+    error: String,
+    #[serde(default)]
+    error_description: Option<String>,
+}
+
+pub fn get_oauth_config(db: &Database, mcp_id: i64) -> Result<Option<McpOAuthConfig>> {
+    Ok(db
+        .conn()
+        .query_row(
+            "SELECT token_endpoint, client_id, refresh_token, access_token, expires_at
+             FROM mcp_oauth WHERE mcp_id = ?",
+            [mcp_id],
+            |row| {
+                Ok(McpOAuthConfig {
+                    token_endpoint: row.get(0)?,
+                    client_id: row.get(1)?,
+                    refresh_token: row.get(2)?,
+                    access_token: row.get(3)?,
+                    expires_at: row.get(4)?,
+                })
+            },
+        )
+        .optional()?)
+}
+
+pub fn set_oauth_config(db: &Database, mcp_id: i64, config: &McpOAuthConfig) -> Result<()> {
+    let endpoint = reqwest::Url::parse(&config.token_endpoint)
+        .map_err(|e| anyhow!("Invalid token endpoint: {}", e))?;
+    if !matches!(endpoint.scheme(), "http" | "https") {
+        bail!("Token endpoint must be an http or https URL");
+    }
+    if config.refresh_token.trim().is_empty() {
+        bail!("A refresh token is required");
+    }
+    db.conn().execute(
+        "INSERT OR REPLACE INTO mcp_oauth
+             (mcp_id, token_endpoint, client_id, refresh_token, access_token, expires_at)
+         VALUES (?, ?, ?, ?, ?, ?)",
+        params![
+            mcp_id,
+            config.token_endpoint,
+            config.client_id,
+            config.refresh_token,
+            config.access_token,
+            config.expires_at
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn clear_oauth_config(db: &Database, mcp_id: i64) -> Result<()> {
+    db.conn()
+        .execute("DELETE FROM mcp_oauth WHERE mcp_id = ?", [mcp_id])?;
+    Ok(())
+}
+
+/// Exchange the stored refresh token for a new access token. Returns `config`
+/// updated with the new token, its expiry and any rotated refresh token.
+pub fn refresh_bearer_token(config: &McpOAuthConfig) -> Result<McpOAuthConfig> {
+    let mut form = vec![
+        ("grant_type", "refresh_token"),
+        ("refresh_token", config.refresh_token.as_str()),
+    ];
+    if let Some(client_id) = &config.client_id {
+        form.push(("client_id", client_id.as_str()));
+    }
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(REFRESH_TIMEOUT)
+        .build()?;
+    let response = client
+        .post(&config.token_endpoint)
+        .header("Accept", "application/json")
+        .form(&form)
+        .send()
+        .map_err(|e| anyhow!("Token refresh failed: {}", e))?;
+
+    let status = response.status();
+    let body = response.text().unwrap_or_default();
+    if !status.is_success() {
+        // Only the OAuth error fields are shown; the body could echo credentials
+        return Err(match serde_json::from_str::<TokenError>(&body) {
+            Ok(TokenError {
+                error,
+                error_description: Some(description),
+            }) => anyhow!("Token refresh failed: {} ({})", error, description),
+            Ok(TokenError { error, .. }) => anyhow!("Token refresh failed: {}", error),
+            Err(_) => anyhow!("Token refresh failed: HTTP {}", status),
+        });
+    }
+
+    let token: TokenResponse = serde_json::from_str(&body)
+        .map_err(|_| anyhow!("Token endpoint returned an unexpected response"))?;
+    Ok(McpOAuthConfig {
+        access_token: Some(token.access_token),
+        expires_at: token
+            .expires_in
+            .map(|secs| (Utc::now() + ChronoDuration::seconds(secs)).to_rfc3339()),
+        refresh_token: token
+            .refresh_token
+            .unwrap_or_else(|| config.refresh_token.clone()),
+        ..config.clone()
+    })
+}
+
+/// `headers` with an `Authorization: Bearer` entry from the MCP's stored
+/// OAuth settings, refreshing the access token first if it has expired.
+/// MCPs without OAuth settings get `headers` back unchanged. The database
+/// lock is released while the token endpoint is called.
+pub fn authorize_headers(
+    db: &Mutex<Database>,
+    mcp_id: i64,
+    headers: Option<HashMap<String, String>>,
+) -> Result<Option<HashMap<String, String>>> {
+    let config = {
+        let db = db.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+        get_oauth_config(&db, mcp_id)?
+    };
+    let Some(mut config) = config else {
+        return Ok(headers);
+    };
+
+    if config.is_expired(Utc::now()) {
+        info!("[MCP OAuth] Refreshing access token for MCP id={}", mcp_id);
+        config = refresh_bearer_token(&config)?;
+        let db = db.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+        set_oauth_config(&db, mcp_id, &config)?;
+    }
+
+    let token = config
+        .access_token
+        .ok_or_else(|| anyhow!("No access token for MCP {}", mcp_id))?;
+    let mut headers = headers.unwrap_or_default();
+    headers.retain(|key, _| !key.eq_ignore_ascii_case("authorization"));
+    headers.insert("Authorization".to_string(), format!("Bearer {}", token));
+    Ok(Some(headers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    /// Answer one request with `status` and `body`, sending the request
+    /// body back over the returned channel
+    fn serve_once(status: &'static str, body: &'static str) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let n = socket.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some(end) = text.find("\r\n\r\n") {
+                    let length = text
+                        .lines()
+                        .find_map(|l| {
+                            l.to_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().to_string())
+                        })
+                        .and_then(|v| v.parse::<usize>().ok())
+                        .unwrap_or(0);
+                    if request.len() >= end + 4 + length {
+                        let _ = tx.send(text[end + 4..].to_string());
+                        break;
+                    }
+                }
+            }
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            let _ = socket.write_all(response.as_bytes());
+        });
+        (format!("http://{}/token", addr), rx)
+    }
+
+    fn config(endpoint: &str) -> McpOAuthConfig {
+        McpOAuthConfig {
+            token_endpoint: endpoint.to_string(),
+            client_id: Some("cli".to_string()),
+            refresh_token: "old-refresh".to_string(),
+            access_token: None,
+            expires_at: None,
+        }
+    }
+
+    #[test]
+    fn test_is_expired() {
+        let now = Utc::now();
+        let mut config = config("https://auth.example.com/token");
+        assert!(config.is_expired(now));
+
+        config.access_token = Some("tok".to_string());
+        assert!(!config.is_expired(now));
+        config.expires_at = Some((now + ChronoDuration::hours(1)).to_rfc3339());
+        assert!(!config.is_expired(now));
+        config.expires_at = Some((now + ChronoDuration::seconds(30)).to_rfc3339());
+        assert!(config.is_expired(now));
+        config.expires_at = Some("not a date".to_string());
+        assert!(config.is_expired(now));
+    }
+
+    #[test]
+    fn test_authorize_headers_refreshes_expired_token_and_stores_it() {
+        let (endpoint, requests) = serve_once(
+            "200 OK",
+            r#"{"access_token":"new-access","token_type":"Bearer","expires_in":3600,"refresh_token":"rotated"}"#,
+        );
+        let db = Database::in_memory().unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO mcps (name, type, url) VALUES ('remote', 'http', 'https://mcp.example.com')",
+                [],
+            )
+            .unwrap();
+        let mut stored = config(&endpoint);
+        stored.access_token = Some("stale".to_string());
+        stored.expires_at = Some((Utc::now() - ChronoDuration::minutes(5)).to_rfc3339());
+        set_oauth_config(&db, 1, &stored).unwrap();
+        let db = Mutex::new(db);
+
+        let existing = HashMap::from([("authorization".to_string(), "Bearer pasted".to_string())]);
+        let headers = authorize_headers(&db, 1, Some(existing)).unwrap().unwrap();
+        assert_eq!(headers.len(), 1);
+        assert_eq!(headers["Authorization"], "Bearer new-access");
+
+        let body = requests.recv().unwrap();
+        assert!(body.contains("grant_type=refresh_token"));
+        assert!(body.contains("refresh_token=old-refresh"));
+        assert!(body.contains("client_id=cli"));
+
+        let saved = get_oauth_config(&db.lock().unwrap(), 1).unwrap().unwrap();
+        assert_eq!(saved.refresh_token, "rotated");
+        assert!(!saved.is_expired(Utc::now()));
+
+        // The fresh token is reused without another round trip
+        let headers = authorize_headers(&db, 1, None).unwrap().unwrap();
+        assert_eq!(headers["Authorization"], "Bearer new-access");
+    }
+
+    #[test]
+    fn test_refresh_error_hides_response_body() {
+        let (endpoint, _requests) = serve_once(
+            "400 Bad Request",
+            r#"{"error":"invalid_grant","error_description":"Refresh token revoked","echo":"old-refresh"}"#,
+        );
+        let err = refresh_bearer_token(&config(&endpoint))
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "Token refresh failed: invalid_grant (Refresh token revoked)"
+        );
+    }
+
+    #[test]
+    fn test_authorize_headers_without_oauth_config() {
+        let db = Mutex::new(Database::in_memory().unwrap());
+        let headers = HashMap::from([("X-Api-Key".to_string(), "k".to_string())]);
+        let result = authorize_headers(&db, 1, Some(headers.clone())).unwrap();
+        assert_eq!(result, Some(headers));
+        assert_eq!(authorize_headers(&db, 1, None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_set_oauth_config_validates() {
+        let db = Database::in_memory().unwrap();
+        assert!(set_oauth_config(&db, 1, &config("ftp://auth.example.com")).is_err());
+        let mut blank = config("https://auth.example.com/token");
+        blank.refresh_token = " ".to_string();
+        assert!(set_oauth_config(&db, 1, &blank).is_err());
+    }
+}
@@ -9,12 +9,12 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 use super::mcp_client::{
-    HttpMcpClient, McpServerInfo, McpTool, SseMcpClient, StdioMcpClient, StreamableHttpMcpClient,
-    ToolCallResult,
+    HttpMcpClient, McpServerInfo, McpTool, PooledStdioClient, SpawnOptions, SseMcpClient,
+    StreamableHttpMcpClient, ToolCallResult, WebSocketMcpClient, STDIO_POOL,
 };
 
 // ============================================================================
@@ -56,12 +56,14 @@ enum McpSession {
     Http(HttpSession),
     Sse(SseSession),
     StreamableHttp(StreamableHttpSession),
+    WebSocket(WebSocketSession),
 }
 
 struct StdioSession {
     mcp_id: i64,
     mcp_name: String,
-    client: StdioMcpClient,
+    /// Returned to the pool, still running, when the session ends
+    client: PooledStdioClient<'static>,
     created_at: Instant,
     last_used_at: Instant,
 }
@@ -90,6 +92,14 @@ struct StreamableHttpSession {
     last_used_at: Instant,
 }
 
+struct WebSocketSession {
+    mcp_id: i64,
+    mcp_name: String,
+    client: Box<WebSocketMcpClient>,
+    created_at: Instant,
+    last_used_at: Instant,
+}
+
 impl McpSession {
     fn mcp_id(&self) -> i64 {
         match self {
@@ -97,6 +107,7 @@ impl McpSession {
             McpSession::Http(s) => s.mcp_id,
             McpSession::Sse(s) => s.mcp_id,
             McpSession::StreamableHttp(s) => s.mcp_id,
+            McpSession::WebSocket(s) => s.mcp_id,
         }
     }
 
@@ -106,6 +117,7 @@ impl McpSession {
             McpSession::Http(s) => &s.mcp_name,
             McpSession::Sse(s) => &s.mcp_name,
             McpSession::StreamableHttp(s) => &s.mcp_name,
+            McpSession::WebSocket(s) => &s.mcp_name,
         }
     }
 
@@ -115,6 +127,7 @@ impl McpSession {
             McpSession::Http(_) => "http",
             McpSession::Sse(_) => "sse",
             McpSession::StreamableHttp(_) => "streamable_http",
+            McpSession::WebSocket(_) => "ws",
         }
     }
 
@@ -124,6 +137,7 @@ impl McpSession {
             McpSession::Http(s) => s.client.server_info(),
             McpSession::Sse(s) => s.client.server_info(),
             McpSession::StreamableHttp(s) => s.client.server_info(),
+            McpSession::WebSocket(s) => s.client.server_info(),
         }
     }
 
@@ -133,6 +147,7 @@ impl McpSession {
             McpSession::Http(s) => s.client.tools(),
             McpSession::Sse(s) => s.client.tools(),
             McpSession::StreamableHttp(s) => s.client.tools(),
+            McpSession::WebSocket(s) => s.client.tools(),
         }
     }
 
@@ -154,6 +169,10 @@ impl McpSession {
                 s.last_used_at = Instant::now();
                 s.client.call_tool(name, arguments)
             }
+            McpSession::WebSocket(s) => {
+                s.last_used_at = Instant::now();
+                s.client.call_tool(name, arguments)
+            }
         }
     }
 
@@ -163,6 +182,7 @@ impl McpSession {
             McpSession::Http(s) => s.created_at,
             McpSession::Sse(s) => s.created_at,
             McpSession::StreamableHttp(s) => s.created_at,
+            McpSession::WebSocket(s) => s.created_at,
         }
     }
 
@@ -172,15 +192,17 @@ impl McpSession {
             McpSession::Http(s) => s.last_used_at,
             McpSession::Sse(s) => s.last_used_at,
             McpSession::StreamableHttp(s) => s.last_used_at,
+            McpSession::WebSocket(s) => s.last_used_at,
         }
     }
 
     fn close(self) {
         match self {
-            McpSession::Stdio(s) => s.client.close(),
+            McpSession::Stdio(s) => drop(s.client),
             McpSession::Http(s) => s.client.close(),
             McpSession::Sse(s) => s.client.close(),
             McpSession::StreamableHttp(s) => s.client.close(),
+            McpSession::WebSocket(s) => s.client.close(),
         }
     }
 }
@@ -189,15 +211,56 @@ impl McpSession {
 // Session Manager
 // ============================================================================
 
+/// Longest a cached tool result is kept, whatever TTL a caller asks for
+const MAX_RESULT_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// A successful tool result stored by [`McpSessionManager::call_tool_cached`]
+struct CachedToolResult {
+    stored_at: Instant,
+    result: ToolCallResult,
+}
+
 /// Manages multiple active MCP sessions
 pub struct McpSessionManager {
     sessions: Arc<Mutex<HashMap<String, McpSession>>>,
+    /// Opt-in tool result cache
+    result_cache: Mutex<HashMap<ToolResultCacheKey, CachedToolResult>>,
+}
+
+/// A tool call on an MCP: (mcp_id, tool name, canonical JSON arguments)
+type ToolResultCacheKey = (i64, String, String);
+
+/// Serialize `value` with object keys sorted so equal arguments always
+/// produce the same string regardless of key order
+fn canonical_json(value: &Value) -> String {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            let fields: Vec<String> = keys
+                .into_iter()
+                .map(|k| format!("{}:{}", Value::String(k.clone()), canonical_json(&map[k])))
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(canonical_json).collect();
+            format!("[{}]", items.join(","))
+        }
+        other => other.to_string(),
+    }
+}
+
+/// Cache key for a tool call on an MCP
+fn tool_result_cache_key(mcp_id: i64, tool_name: &str, arguments: &Value) -> ToolResultCacheKey {
+    (mcp_id, tool_name.to_string(), canonical_json(arguments))
 }
 
 impl McpSessionManager {
     pub fn new() -> Self {
         Self {
             sessions: Arc::new(Mutex::new(HashMap::new())),
+            result_cache: Mutex::new(HashMap::new()),
         }
     }
 
@@ -206,9 +269,7 @@ impl McpSessionManager {
         &self,
         mcp_id: i64,
         mcp_name: &str,
-        command: &str,
-        args: &[String],
-        env: Option<&HashMap<String, String>>,
+        options: &SpawnOptions,
         timeout_secs: u64,
     ) -> Result<StartSessionResult> {
         info!(
@@ -216,8 +277,8 @@ impl McpSessionManager {
             mcp_id, mcp_name
         );
 
-        // Spawn and initialize the client
-        let client = StdioMcpClient::spawn(command, args, env, timeout_secs)?;
+        // Reuse a pooled process for this server, or spawn and initialize one
+        let client = STDIO_POOL.get_or_spawn(options, timeout_secs)?;
 
         let session_id = Uuid::new_v4().to_string();
         let now = Instant::now();
@@ -352,6 +413,56 @@ impl McpSessionManager {
         Ok(result)
     }
 
+    /// Start a new WebSocket-based MCP session
+    pub fn start_ws_session(
+        &self,
+        mcp_id: i64,
+        mcp_name: &str,
+        url: &str,
+        headers: Option<&HashMap<String, String>>,
+        timeout_secs: u64,
+    ) -> Result<StartSessionResult> {
+        info!(
+            "[Session Manager] Starting WebSocket session for MCP {} ({})",
+            mcp_id, mcp_name
+        );
+
+        // Connect and initialize the client
+        let client = Box::new(WebSocketMcpClient::connect(url, headers, timeout_secs)?);
+
+        let session_id = Uuid::new_v4().to_string();
+        let now = Instant::now();
+
+        let result = StartSessionResult {
+            session_id: session_id.clone(),
+            server_info: client.server_info().cloned(),
+            tools: client.tools().to_vec(),
+            resources_supported: client.resources_supported(),
+            prompts_supported: client.prompts_supported(),
+        };
+
+        let session = McpSession::WebSocket(WebSocketSession {
+            mcp_id,
+            mcp_name: mcp_name.to_string(),
+            client,
+            created_at: now,
+            last_used_at: now,
+        });
+
+        self.sessions
+            .lock()
+            .unwrap()
+            .insert(session_id.clone(), session);
+
+        info!(
+            "[Session Manager] WebSocket session {} started with {} tools",
+            session_id,
+            result.tools.len()
+        );
+
+        Ok(result)
+    }
+
     /// Start a new Streamable HTTP-based MCP session (for system MCPs like Tool Manager and Gateway)
     pub fn start_streamable_http_session(
         &self,
@@ -422,6 +533,69 @@ impl McpSessionManager {
         session.call_tool(tool_name, arguments)
     }
 
+    /// Execute a tool, reusing a result from the same MCP, tool and arguments
+    /// that is younger than `ttl`. Only for read-only tools: callers opt in per
+    /// call since most tools are not idempotent. Errors are never cached.
+    pub fn call_tool_cached(
+        &self,
+        session_id: &str,
+        tool_name: &str,
+        arguments: Value,
+        ttl: Duration,
+    ) -> Result<ToolCallResult> {
+        let mcp_id = self
+            .sessions
+            .lock()
+            .unwrap()
+            .get(session_id)
+            .map(|s| s.mcp_id())
+            .ok_or_else(|| anyhow!("Session not found: {}", session_id))?;
+        let key = tool_result_cache_key(mcp_id, tool_name, &arguments);
+
+        if let Some(result) = self.cached_result(&key, ttl) {
+            info!(
+                "[Session Manager] Serving cached result for tool '{}' in session {}",
+                tool_name, session_id
+            );
+            return Ok(result);
+        }
+
+        let result = self.call_tool(session_id, tool_name, arguments)?;
+        if result.success && !result.is_error {
+            self.store_result(key, result.clone());
+        }
+        Ok(result)
+    }
+
+    fn cached_result(&self, key: &ToolResultCacheKey, ttl: Duration) -> Option<ToolCallResult> {
+        let ttl = ttl.min(MAX_RESULT_CACHE_TTL);
+        let cache = self.result_cache.lock().unwrap();
+        cache
+            .get(key)
+            .filter(|entry| entry.stored_at.elapsed() < ttl)
+            .map(|entry| ToolCallResult {
+                cached: true,
+                ..entry.result.clone()
+            })
+    }
+
+    fn store_result(&self, key: ToolResultCacheKey, result: ToolCallResult) {
+        let mut cache = self.result_cache.lock().unwrap();
+        cache.retain(|_, entry| entry.stored_at.elapsed() < MAX_RESULT_CACHE_TTL);
+        cache.insert(
+            key,
+            CachedToolResult {
+                stored_at: Instant::now(),
+                result,
+            },
+        );
+    }
+
+    /// Drop every cached tool result
+    pub fn clear_tool_result_cache(&self) {
+        self.result_cache.lock().unwrap().clear();
+    }
+
     /// Get information about a specific session
     pub fn get_session_info(&self, session_id: &str) -> Option<SessionInfo> {
         let sessions = self.sessions.lock().unwrap();
@@ -567,6 +741,7 @@ mod tests {
             server_info: Some(McpServerInfo {
                 name: "Test Server".to_string(),
                 version: Some("1.0.0".to_string()),
+                protocol_version: None,
             }),
             tool_count: 5,
             created_at: "0s".to_string(),
@@ -633,6 +808,7 @@ mod tests {
             server_info: Some(McpServerInfo {
                 name: "test".to_string(),
                 version: Some("1.0".to_string()),
+                protocol_version: None,
             }),
             tools: vec![McpTool {
                 name: "read_file".to_string(),
@@ -660,4 +836,69 @@ mod tests {
         let manager = McpSessionManager::new();
         assert!(manager.get_session_tools("none").is_none());
     }
+
+    #[test]
+    fn test_tool_result_cache_key_ignores_argument_order() {
+        let a = serde_json::json!({"path": "/tmp", "opts": {"depth": 1, "hidden": false}});
+        let b = serde_json::json!({"opts": {"hidden": false, "depth": 1}, "path": "/tmp"});
+        assert_eq!(
+            tool_result_cache_key(1, "list_files", &a),
+            tool_result_cache_key(1, "list_files", &b)
+        );
+        assert_ne!(
+            tool_result_cache_key(1, "list_files", &a),
+            tool_result_cache_key(2, "list_files", &a)
+        );
+        assert_ne!(
+            tool_result_cache_key(1, "list_files", &a),
+            tool_result_cache_key(1, "read_file", &a)
+        );
+    }
+
+    #[test]
+    fn test_cached_result_respects_ttl_and_marks_cached() {
+        let manager = McpSessionManager::new();
+        let key = tool_result_cache_key(1, "list_files", &serde_json::json!({}));
+        assert!(manager
+            .cached_result(&key, Duration::from_secs(60))
+            .is_none());
+
+        manager.store_result(
+            key.clone(),
+            ToolCallResult {
+                success: true,
+                content: vec![],
+                is_error: false,
+                error: None,
+                error_code: None,
+                error_data: None,
+                execution_time_ms: 12,
+                cached: false,
+            },
+        );
+
+        let hit = manager
+            .cached_result(&key, Duration::from_secs(60))
+            .unwrap();
+        assert!(hit.cached);
+        assert_eq!(hit.execution_time_ms, 12);
+        assert!(manager.cached_result(&key, Duration::ZERO).is_none());
+
+        manager.clear_tool_result_cache();
+        assert!(manager
+            .cached_result(&key, Duration::from_secs(60))
+            .is_none());
+    }
+
+    #[test]
+    fn test_call_tool_cached_unknown_session() {
+        let manager = McpSessionManager::new();
+        let result = manager.call_tool_cached(
+            "missing",
+            "list_files",
+            serde_json::json!({}),
+            Duration::from_secs(60),
+        );
+        assert!(result.is_err());
+    }
 }
@@ -4,10 +4,13 @@ pub mod claude_settings;
 pub mod codex_config;
 pub mod command_writer;
 pub mod config_parser;
+pub mod config_validation;
 pub mod config_writer;
+pub mod connectivity;
 pub mod copilot_config;
 pub mod cursor_config;
 pub mod debug_logger;
+pub mod diagnostics;
 pub mod docker;
 pub mod gemini_config;
 pub mod gist_sync;
@@ -17,16 +20,28 @@ pub mod insights;
 pub mod keybindings_writer;
 pub mod managed_settings;
 pub mod mcp_client;
+pub mod mcp_conflicts;
+pub mod mcp_import;
+pub mod mcp_json_config;
+pub mod mcp_oauth;
 pub mod mcp_registry;
 pub mod mcp_session;
 pub mod memory_writer;
 pub mod opencode_config;
+pub mod pending_changes;
 pub mod permission_writer;
+pub mod plugin_hooks;
+pub mod project_manifest;
+pub mod reconcile;
+pub mod repo_archive;
 pub mod repo_parser;
 pub mod repo_sync;
 pub mod rule_writer;
+pub mod scan_ignore;
 pub mod scanner;
 pub mod session_explorer;
+pub mod setup_script;
+pub mod skill_script;
 pub mod skill_writer;
 pub mod sound_player;
 pub mod spinner_verb_writer;
@@ -34,3 +49,7 @@ pub mod stats_cache;
 pub mod statusline_gallery;
 pub mod statusline_writer;
 pub mod subagent_writer;
+pub mod test_history;
+pub mod tool_cache;
+pub mod tool_references;
+pub mod windsurf_config;
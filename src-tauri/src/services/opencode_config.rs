@@ -1,27 +1,10 @@
+use crate::services::mcp_json_config::{backup_config_file, McpTuple};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Map, Value};
 use std::collections::HashMap;
 use std::path::Path;
 
-/// Create a backup of the config file before modifying it
-fn backup_config_file(path: &Path) -> Result<()> {
-    if !path.exists() {
-        return Ok(());
-    }
-
-    let backup_path = path.with_extension("json.bak");
-    std::fs::copy(path, &backup_path).map_err(|e| {
-        anyhow::anyhow!(
-            "Failed to create backup of {} before writing: {}",
-            path.display(),
-            e
-        )
-    })?;
-
-    Ok(())
-}
-
 /// OpenCode MCP server configuration (local/stdio)
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct OpenCodeMcpLocal {
@@ -150,17 +133,6 @@ pub fn parse_opencode_mcps(path: &Path) -> Result<Vec<ParsedOpenCodeMcp>> {
     Ok(mcps)
 }
 
-/// MCP tuple for writing (same format as Claude Code)
-type McpTuple = (
-    String,         // name
-    String,         // type (stdio, sse, http)
-    Option<String>, // command
-    Option<String>, // args (JSON)
-    Option<String>, // url
-    Option<String>, // headers (JSON)
-    Option<String>, // env (JSON)
-);
-
 /// Generate OpenCode MCP config object from MCP tuples
 pub fn generate_opencode_mcp_config(mcps: &[McpTuple]) -> Value {
     let mut mcp_obj = Map::new();
@@ -232,35 +204,12 @@ pub fn write_opencode_global_config(config_path: &Path, mcps: &[McpTuple]) -> Re
         std::fs::create_dir_all(parent)?;
     }
 
-    // Read existing config or create new
-    let mut config: Value = if config_path.exists() {
-        let content = std::fs::read_to_string(config_path)?;
-        serde_json::from_str(&content).map_err(|e| {
-            anyhow::anyhow!(
-                "Failed to parse existing OpenCode config at {}: {}. \
-                 Refusing to overwrite to prevent data loss.",
-                config_path.display(),
-                e
-            )
-        })?
-    } else {
-        json!({})
-    };
-
-    // Build MCP object
-    // Skip overwrite when DB has no MCPs — preserves externally-managed configs
-    let mcp_config = generate_opencode_mcp_config(mcps);
-    if let Some(Value::Object(mcp)) = mcp_config.get("mcp") {
-        if !mcp.is_empty() {
-            config["mcp"] = Value::Object(mcp.clone());
-        }
-    }
+    let content = render_opencode_config(config_path, mcps)?;
 
     // Back up the existing file before modifying it
     backup_config_file(config_path)?;
 
     // Write back
-    let content = serde_json::to_string_pretty(&config)?;
     std::fs::write(config_path, content)?;
 
     Ok(())
@@ -268,12 +217,29 @@ pub fn write_opencode_global_config(config_path: &Path, mcps: &[McpTuple]) -> Re
 
 /// Write project-level OpenCode config
 pub fn write_opencode_project_config(project_path: &Path, mcps: &[McpTuple]) -> Result<()> {
-    // OpenCode uses opencode.json in project root (not .opencode/opencode.json)
-    let config_path = project_path.join("opencode.json");
+    let config_path = opencode_project_config_path(project_path);
+    let content = render_opencode_config(&config_path, mcps)?;
 
+    // Back up the existing file before modifying it
+    backup_config_file(&config_path)?;
+
+    // Write back
+    std::fs::write(&config_path, content)?;
+
+    Ok(())
+}
+
+/// OpenCode uses opencode.json in project root (not .opencode/opencode.json)
+pub fn opencode_project_config_path(project_path: &Path) -> std::path::PathBuf {
+    project_path.join("opencode.json")
+}
+
+/// The content the OpenCode writers would write to `config_path`, merged
+/// into its current file, without touching disk
+pub fn render_opencode_config(config_path: &Path, mcps: &[McpTuple]) -> Result<String> {
     // Read existing config or create new
     let mut config: Value = if config_path.exists() {
-        let content = std::fs::read_to_string(&config_path)?;
+        let content = std::fs::read_to_string(config_path)?;
         serde_json::from_str(&content).map_err(|e| {
             anyhow::anyhow!(
                 "Failed to parse existing OpenCode config at {}: {}. \
@@ -295,14 +261,7 @@ pub fn write_opencode_project_config(project_path: &Path, mcps: &[McpTuple]) ->
         }
     }
 
-    // Back up the existing file before modifying it
-    backup_config_file(&config_path)?;
-
-    // Write back
-    let content = serde_json::to_string_pretty(&config)?;
-    std::fs::write(&config_path, content)?;
-
-    Ok(())
+    Ok(serde_json::to_string_pretty(&config)?)
 }
 
 #[cfg(test)]
@@ -0,0 +1,345 @@
+//! Optional confirmation step before writing Claude config files
+//!
+//! When confirmation is on, writes to `~/.claude.json`, `settings.json`,
+//! `.mcp.json` and status line scripts are queued as pending changes instead
+//! of touching disk. Reads of a file with a queued change see the queued
+//! content, so several edits to one file stack into a single pending change
+//! that is written when confirmed. Queued changes are saved to the app data
+//! dir so they survive a restart.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Lines of unchanged context shown around a diff
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// File in the app data dir holding the queued changes
+const PENDING_CHANGES_FILE: &str = "pending_changes.json";
+
+/// A config write waiting for confirmation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingChange {
+    pub token: String,
+    pub path: String,
+    /// File content when the change was first queued; `None` if it didn't exist
+    pub before: Option<String>,
+    pub after: String,
+    pub diff: String,
+}
+
+/// A command's result plus the config writes it queued for confirmation
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WithPendingChanges<T> {
+    pub item: T,
+    pub pending_changes: Vec<PendingChange>,
+}
+
+pub struct PendingChangeStore {
+    confirm_writes: AtomicBool,
+    changes: Mutex<Vec<PendingChange>>,
+    /// Where queued changes are saved; `None` keeps them in memory only
+    persist_path: Mutex<Option<PathBuf>>,
+}
+
+static STORE: PendingChangeStore = PendingChangeStore::new();
+
+impl PendingChangeStore {
+    const fn new() -> Self {
+        Self {
+            confirm_writes: AtomicBool::new(false),
+            changes: Mutex::new(Vec::new()),
+            persist_path: Mutex::new(None),
+        }
+    }
+
+    /// Load changes queued before the last shutdown from `app_data_dir` and
+    /// keep saving them there
+    pub fn restore_from(&self, app_data_dir: &Path) -> Result<()> {
+        let path = app_data_dir.join(PENDING_CHANGES_FILE);
+        let restored: Vec<PendingChange> = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+        *self
+            .persist_path
+            .lock()
+            .map_err(|e| anyhow!("Lock error: {}", e))? = Some(path);
+        *self.lock()? = restored;
+        Ok(())
+    }
+
+    /// Save the queue, removing the file once nothing is pending
+    fn persist(&self, changes: &[PendingChange]) -> Result<()> {
+        let persist_path = self
+            .persist_path
+            .lock()
+            .map_err(|e| anyhow!("Lock error: {}", e))?;
+        let Some(path) = persist_path.as_ref() else {
+            return Ok(());
+        };
+        if changes.is_empty() {
+            if path.exists() {
+                std::fs::remove_file(path)?;
+            }
+            return Ok(());
+        }
+        write_file(path, &serde_json::to_string_pretty(changes)?)
+    }
+
+    pub fn confirm_writes(&self) -> bool {
+        self.confirm_writes.load(Ordering::SeqCst)
+    }
+
+    pub fn set_confirm_writes(&self, enabled: bool) {
+        self.confirm_writes.store(enabled, Ordering::SeqCst);
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Vec<PendingChange>>> {
+        self.changes
+            .lock()
+            .map_err(|e| anyhow!("Lock error: {}", e))
+    }
+
+    /// The content a reader should see: the queued change if there is one,
+    /// otherwise the file on disk (`None` if it doesn't exist)
+    pub fn read(&self, path: &Path) -> Result<Option<String>> {
+        let key = path.to_string_lossy();
+        if let Some(change) = self.lock()?.iter().find(|c| c.path == key) {
+            return Ok(Some(change.after.clone()));
+        }
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(std::fs::read_to_string(path)?))
+    }
+
+    /// Write `content` to `path`, backing up the current file first, or
+    /// queue it when confirmation is on. Returns the pending change when the
+    /// write was queued.
+    pub fn write(&self, path: &Path, content: &str) -> Result<Option<PendingChange>> {
+        if !self.confirm_writes() {
+            crate::utils::backup::backup_file(path)?;
+            write_file(path, content)?;
+            return Ok(None);
+        }
+
+        let key = path.to_string_lossy().to_string();
+        let mut changes = self.lock()?;
+        let existing = changes.iter().position(|c| c.path == key);
+        let (token, before) = match existing {
+            Some(i) => {
+                let change = changes.remove(i);
+                (change.token, change.before)
+            }
+            None => (
+                uuid::Uuid::new_v4().to_string(),
+                std::fs::read_to_string(path).ok(),
+            ),
+        };
+        // Edits that net out to the current file leave nothing to confirm
+        if before.as_deref() == Some(content) {
+            self.persist(&changes)?;
+            return Ok(None);
+        }
+
+        let change = PendingChange {
+            diff: line_diff(before.as_deref().unwrap_or(""), content),
+            token,
+            path: key,
+            before,
+            after: content.to_string(),
+        };
+        changes.push(change.clone());
+        self.persist(&changes)?;
+        Ok(Some(change))
+    }
+
+    pub fn list(&self) -> Result<Vec<PendingChange>> {
+        Ok(self.lock()?.clone())
+    }
+
+    /// Back up the file and write a queued change to disk. Refuses if the
+    /// file changed on disk since the change was queued, leaving the change
+    /// pending.
+    pub fn confirm(&self, token: &str) -> Result<PendingChange> {
+        let mut changes = self.lock()?;
+        let index = changes
+            .iter()
+            .position(|c| c.token == token)
+            .ok_or_else(|| anyhow!("No pending change with token {}", token))?;
+        let change = &changes[index];
+        let path = PathBuf::from(&change.path);
+        if std::fs::read_to_string(&path).ok() != change.before {
+            return Err(anyhow!(
+                "{} changed on disk since the change was queued; discard it and try again",
+                change.path
+            ));
+        }
+        crate::utils::backup::backup_file(&path)?;
+        write_file(&path, &change.after)?;
+        let change = changes.remove(index);
+        self.persist(&changes)?;
+        Ok(change)
+    }
+
+    pub fn discard(&self, token: &str) -> Result<()> {
+        let mut changes = self.lock()?;
+        let before = changes.len();
+        changes.retain(|c| c.token != token);
+        if changes.len() == before {
+            return Err(anyhow!("No pending change with token {}", token));
+        }
+        self.persist(&changes)
+    }
+}
+
+fn write_file(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+/// The app-wide store used by the config writers
+pub fn store() -> &'static PendingChangeStore {
+    &STORE
+}
+
+/// Read a config file through the app-wide store
+pub fn read_config_file(path: &Path) -> Result<Option<String>> {
+    STORE.read(path)
+}
+
+/// Write a config file through the app-wide store. Returns the pending
+/// change when the write was queued for confirmation.
+pub fn write_config_file(path: &Path, content: &str) -> Result<Option<PendingChange>> {
+    STORE.write(path, content)
+}
+
+/// A unified-style diff of the changed region: the common leading and
+/// trailing lines are trimmed and everything between is shown as removed
+/// and added, with a few lines of context.
+pub fn line_diff(before: &str, after: &str) -> String {
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut diff = String::new();
+    for line in &old[prefix.saturating_sub(DIFF_CONTEXT_LINES)..prefix] {
+        diff.push_str(&format!(" {}\n", line));
+    }
+    for line in &old[prefix..old.len() - suffix] {
+        diff.push_str(&format!("-{}\n", line));
+    }
+    for line in &new[prefix..new.len() - suffix] {
+        diff.push_str(&format!("+{}\n", line));
+    }
+    let tail = old.len() - suffix;
+    for line in &old[tail..(tail + DIFF_CONTEXT_LINES).min(old.len())] {
+        diff.push_str(&format!(" {}\n", line));
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_line_diff() {
+        let diff = line_diff("a\nb\nc\nd\n", "a\nb\nX\nd\n");
+        assert_eq!(diff, " a\n b\n-c\n+X\n d\n");
+        assert_eq!(line_diff("", "new\n"), "+new\n");
+    }
+
+    #[test]
+    fn test_writes_directly_when_confirmation_is_off() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(".claude").join("settings.json");
+        let store = PendingChangeStore::new();
+
+        assert!(store.write(&path, "{}").unwrap().is_none());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{}");
+        assert!(store.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_queued_changes_stack_until_confirmed() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("settings.json");
+        std::fs::write(&path, "one\n").unwrap();
+        let store = PendingChangeStore::new();
+        store.set_confirm_writes(true);
+
+        let first = store.write(&path, "two\n").unwrap().unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "one\n");
+        assert_eq!(store.read(&path).unwrap().as_deref(), Some("two\n"));
+
+        // A second edit to the same file folds into the same change
+        let second = store.write(&path, "three\n").unwrap().unwrap();
+        assert_eq!(second.token, first.token);
+        assert_eq!(second.diff, "-one\n+three\n");
+        assert_eq!(store.list().unwrap().len(), 1);
+
+        store.confirm(&first.token).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "three\n");
+        assert!(store.list().unwrap().is_empty());
+        assert!(store.confirm(&first.token).is_err());
+    }
+
+    #[test]
+    fn test_queued_changes_survive_restart_and_back_up_on_confirm() {
+        let dir = TempDir::new().unwrap();
+        let data_dir = dir.path().join("app-data");
+        let path = dir.path().join("settings.json");
+        std::fs::write(&path, "old\n").unwrap();
+        let backup = dir.path().join("settings.json.bak");
+
+        let store = PendingChangeStore::new();
+        store.restore_from(&data_dir).unwrap();
+        store.set_confirm_writes(true);
+        let change = store.write(&path, "new\n").unwrap().unwrap();
+        // Queuing leaves the file and its backup alone
+        assert!(!backup.exists());
+
+        let restarted = PendingChangeStore::new();
+        restarted.restore_from(&data_dir).unwrap();
+        assert_eq!(restarted.list().unwrap().len(), 1);
+
+        restarted.confirm(&change.token).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new\n");
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "old\n");
+        assert!(!data_dir.join(PENDING_CHANGES_FILE).exists());
+    }
+
+    #[test]
+    fn test_confirm_refuses_when_file_changed_on_disk() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(".mcp.json");
+        let store = PendingChangeStore::new();
+        store.set_confirm_writes(true);
+
+        let change = store.write(&path, "{}").unwrap().unwrap();
+        assert!(change.before.is_none());
+        std::fs::write(&path, "edited elsewhere").unwrap();
+        assert!(store.confirm(&change.token).is_err());
+
+        store.discard(&change.token).unwrap();
+        assert!(store.list().unwrap().is_empty());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "edited elsewhere");
+    }
+}
@@ -1,3 +1,4 @@
+use crate::services::pending_changes::PendingChange;
 use anyhow::Result;
 use directories::BaseDirs;
 use serde::{Deserialize, Serialize};
@@ -36,23 +37,16 @@ pub struct AllPermissions {
 
 /// Read an existing settings.json file or return an empty object
 fn read_settings_file(path: &Path) -> Result<Value> {
-    if path.exists() {
-        let content = std::fs::read_to_string(path)?;
-        Ok(serde_json::from_str(&content).unwrap_or(json!({})))
-    } else {
-        Ok(json!({}))
+    match crate::services::pending_changes::read_config_file(path)? {
+        Some(content) => Ok(serde_json::from_str(&content).unwrap_or(json!({}))),
+        None => Ok(json!({})),
     }
 }
 
 /// Write settings.json file, preserving other settings
-fn write_settings_file(path: &Path, settings: &Value) -> Result<()> {
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
-    crate::utils::backup::backup_file(path)?;
+fn write_settings_file(path: &Path, settings: &Value) -> Result<Option<PendingChange>> {
     let content = serde_json::to_string_pretty(settings)?;
-    std::fs::write(path, content)?;
-    Ok(())
+    crate::services::pending_changes::write_config_file(path, &content)
 }
 
 /// Resolve the settings file path for a given scope
@@ -161,7 +155,7 @@ pub fn write_permission_rules(
     project_path: Option<&Path>,
     category: &str,
     rules: &[String],
-) -> Result<()> {
+) -> Result<Option<PendingChange>> {
     let path = resolve_settings_path(scope, project_path)?;
     let mut settings = read_settings_file(&path)?;
 
@@ -196,7 +190,7 @@ pub fn write_default_mode(
     scope: &PermissionScope,
     project_path: Option<&Path>,
     mode: Option<&str>,
-) -> Result<()> {
+) -> Result<Option<PendingChange>> {
     let path = resolve_settings_path(scope, project_path)?;
     let mut settings = read_settings_file(&path)?;
 
@@ -226,7 +220,7 @@ pub fn write_additional_directories(
     scope: &PermissionScope,
     project_path: Option<&Path>,
     dirs: &[String],
-) -> Result<()> {
+) -> Result<Option<PendingChange>> {
     let path = resolve_settings_path(scope, project_path)?;
     let mut settings = read_settings_file(&path)?;
 
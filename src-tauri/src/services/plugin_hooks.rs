@@ -0,0 +1,284 @@
+//! Claude Code plugin hooks
+//!
+//! Plugins ship hooks as `hooks/hooks.json` next to a `.claude-plugin/plugin.json`
+//! manifest. The hooks file uses the same `{"hooks": {Event: [...]}}` shape as
+//! settings.json, so exporting reuses the settings writer; importing splits the
+//! file into one single-hook bundle per action so each can be a marketplace item.
+
+use crate::db::models::{CreateHookRequest, Hook};
+use crate::services::hook_writer;
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+/// Where a plugin keeps its hooks, relative to the plugin root
+pub const PLUGIN_HOOKS_PATH: &str = "hooks/hooks.json";
+
+/// The plugin manifest, relative to the plugin root
+pub const PLUGIN_MANIFEST_PATH: &str = ".claude-plugin/plugin.json";
+
+/// One hook action split out of a plugin's hooks.json
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginHookEntry {
+    pub name: String,
+    pub description: Option<String>,
+    /// A `{"hooks": {...}}` document holding only this hook
+    pub bundle: String,
+}
+
+/// `PreToolUse` -> `pre-tool-use`
+fn event_slug(event_type: &str) -> String {
+    let mut slug = String::new();
+    for c in event_type.chars() {
+        if c.is_ascii_uppercase() && !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
+
+/// Write `hooks` as a plugin under `output_dir`: the manifest plus
+/// `hooks/hooks.json`. Returns the plugin root.
+pub fn write_hooks_plugin(
+    output_dir: &Path,
+    plugin_name: &str,
+    description: Option<&str>,
+    hooks: &[Hook],
+) -> Result<PathBuf> {
+    let plugin_name = plugin_name.trim();
+    if plugin_name.is_empty() {
+        return Err(anyhow!("Plugin name cannot be empty"));
+    }
+
+    let manifest_path = output_dir.join(PLUGIN_MANIFEST_PATH);
+    let hooks_path = output_dir.join(PLUGIN_HOOKS_PATH);
+    for path in [&manifest_path, &hooks_path] {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+
+    let mut manifest = json!({ "name": plugin_name, "version": "1.0.0" });
+    if let Some(description) = description.filter(|d| !d.trim().is_empty()) {
+        manifest["description"] = json!(description);
+    }
+    std::fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+
+    let hooks_json = hook_writer::hooks_to_settings_format(hooks);
+    std::fs::write(&hooks_path, serde_json::to_string_pretty(&hooks_json)?)?;
+
+    Ok(output_dir.to_path_buf())
+}
+
+/// The `name` from a plugin manifest, if it has one
+pub fn plugin_name_from_manifest(manifest: &str) -> Option<String> {
+    serde_json::from_str::<Value>(manifest)
+        .ok()?
+        .get("name")?
+        .as_str()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map(str::to_string)
+}
+
+/// Split a plugin's hooks.json into one bundle per hook action. Names are
+/// `<plugin>-<event>`, suffixed `-2`, `-3`... when an event has several hooks.
+pub fn split_plugin_hooks(plugin_name: &str, hooks_json: &str) -> Result<Vec<PluginHookEntry>> {
+    let value: Value = serde_json::from_str(hooks_json)?;
+    let events = value
+        .get("hooks")
+        .and_then(|h| h.as_object())
+        .ok_or_else(|| anyhow!("No \"hooks\" object in plugin hooks file"))?;
+
+    let mut entries = Vec::new();
+    for (event_type, groups) in events {
+        let mut count = 0;
+        for group in groups.as_array().into_iter().flatten() {
+            let matcher = group.get("matcher").and_then(|m| m.as_str());
+            for action in group
+                .get("hooks")
+                .and_then(|h| h.as_array())
+                .into_iter()
+                .flatten()
+            {
+                count += 1;
+                let name = match count {
+                    1 => format!("{}-{}", plugin_name, event_slug(event_type)),
+                    n => format!("{}-{}-{}", plugin_name, event_slug(event_type), n),
+                };
+
+                let mut entry = json!({ "hooks": [action] });
+                if let Some(matcher) = matcher.filter(|m| !m.is_empty()) {
+                    entry["matcher"] = json!(matcher);
+                }
+                let bundle = json!({ "hooks": { event_type.as_str(): [entry] } });
+
+                entries.push(PluginHookEntry {
+                    description: Some(format!(
+                        "{} hook from the {} plugin",
+                        event_type, plugin_name
+                    )),
+                    name,
+                    bundle: serde_json::to_string_pretty(&bundle)?,
+                });
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// Turn a single-hook bundle (as produced by [`split_plugin_hooks`]) back
+/// into a hook definition named `name`
+pub fn parse_hook_bundle(
+    name: &str,
+    description: Option<String>,
+    bundle: &str,
+) -> Result<CreateHookRequest> {
+    let value: Value = serde_json::from_str(bundle)?;
+    let (event_type, groups) = value
+        .get("hooks")
+        .and_then(|h| h.as_object())
+        .and_then(|events| events.iter().next())
+        .ok_or_else(|| anyhow!("Hook bundle has no events"))?;
+    let group = groups
+        .as_array()
+        .and_then(|g| g.first())
+        .ok_or_else(|| anyhow!("Hook bundle has no entries for {}", event_type))?;
+    let action = group
+        .get("hooks")
+        .and_then(|h| h.as_array())
+        .and_then(|a| a.first())
+        .ok_or_else(|| anyhow!("Hook bundle has no hook actions for {}", event_type))?;
+
+    let text = |key: &str| action.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    let flag = |key: &str| action.get(key).and_then(|v| v.as_bool());
+
+    Ok(CreateHookRequest {
+        name: name.to_string(),
+        description,
+        event_type: event_type.clone(),
+        matcher: group
+            .get("matcher")
+            .and_then(|m| m.as_str())
+            .map(str::to_string),
+        hook_type: text("type").unwrap_or_else(|| "command".to_string()),
+        command: text("command"),
+        prompt: text("prompt"),
+        timeout: action
+            .get("timeout")
+            .and_then(|t| t.as_i64())
+            .map(|t| t as i32),
+        tags: None,
+        url: text("url"),
+        headers: action.get("headers").cloned(),
+        allowed_env_vars: action
+            .get("allowedEnvVars")
+            .and_then(|v| serde_json::from_value(v.clone()).ok()),
+        if_condition: text("if"),
+        status_message: text("statusMessage"),
+        once: flag("once"),
+        async_mode: flag("async"),
+        shell: text("shell"),
+        path_pattern: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn hook(name: &str, event_type: &str, matcher: Option<&str>, command: &str) -> Hook {
+        Hook {
+            id: 0,
+            name: name.to_string(),
+            description: None,
+            event_type: event_type.to_string(),
+            matcher: matcher.map(str::to_string),
+            hook_type: "command".to_string(),
+            command: Some(command.to_string()),
+            prompt: None,
+            timeout: Some(30),
+            tags: None,
+            source: "manual".to_string(),
+            is_template: false,
+            url: None,
+            headers: None,
+            allowed_env_vars: None,
+            if_condition: None,
+            status_message: Some("Formatting".to_string()),
+            once: false,
+            async_mode: true,
+            shell: None,
+            path_pattern: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_event_slug() {
+        assert_eq!(event_slug("PreToolUse"), "pre-tool-use");
+        assert_eq!(event_slug("SessionStart"), "session-start");
+        assert_eq!(event_slug("Stop"), "stop");
+    }
+
+    #[test]
+    fn test_plugin_hooks_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let hooks = vec![
+            hook(
+                "fmt",
+                "PostToolUse",
+                Some("Edit|Write"),
+                "prettier --write .",
+            ),
+            hook("lint", "PostToolUse", Some("Edit|Write"), "eslint --fix ."),
+            hook("hello", "SessionStart", None, "echo hi"),
+        ];
+        write_hooks_plugin(dir.path(), "formatters", Some("Format on save"), &hooks).unwrap();
+
+        let manifest = std::fs::read_to_string(dir.path().join(PLUGIN_MANIFEST_PATH)).unwrap();
+        assert_eq!(
+            plugin_name_from_manifest(&manifest).as_deref(),
+            Some("formatters")
+        );
+
+        let hooks_json = std::fs::read_to_string(dir.path().join(PLUGIN_HOOKS_PATH)).unwrap();
+        let mut entries = split_plugin_hooks("formatters", &hooks_json).unwrap();
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "formatters-post-tool-use",
+                "formatters-post-tool-use-2",
+                "formatters-session-start"
+            ]
+        );
+
+        let lint = parse_hook_bundle(&entries[1].name, None, &entries[1].bundle).unwrap();
+        assert_eq!(lint.event_type, "PostToolUse");
+        assert_eq!(lint.matcher.as_deref(), Some("Edit|Write"));
+        assert_eq!(lint.hook_type, "command");
+        assert_eq!(lint.command.as_deref(), Some("eslint --fix ."));
+        assert_eq!(lint.timeout, Some(30));
+        assert_eq!(lint.status_message.as_deref(), Some("Formatting"));
+        assert_eq!(lint.async_mode, Some(true));
+
+        let hello = parse_hook_bundle(&entries[2].name, None, &entries[2].bundle).unwrap();
+        assert_eq!(hello.event_type, "SessionStart");
+        assert_eq!(hello.matcher, None);
+    }
+
+    #[test]
+    fn test_split_plugin_hooks_rejects_non_hook_files() {
+        assert!(split_plugin_hooks("p", r#"{"name": "p"}"#).is_err());
+        assert!(split_plugin_hooks("p", "not json").is_err());
+    }
+}
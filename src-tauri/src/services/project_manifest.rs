@@ -0,0 +1,328 @@
+//! Project manifest (`.cctm.json`)
+//!
+//! A committable file in the project root naming the library entities the
+//! project should have. Entities are referenced by name so the manifest is
+//! portable between machines whose databases use different ids.
+
+use crate::db::Database;
+use crate::services::pending_changes::PendingChange;
+use anyhow::{anyhow, Context, Result};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_FILE: &str = ".cctm.json";
+
+const MANIFEST_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectManifest {
+    #[serde(default = "default_version")]
+    pub version: u32,
+    #[serde(default)]
+    pub mcps: Vec<String>,
+    #[serde(default)]
+    pub skills: Vec<String>,
+    #[serde(default)]
+    pub subagents: Vec<String>,
+    #[serde(default)]
+    pub commands: Vec<String>,
+    #[serde(default)]
+    pub hooks: Vec<String>,
+}
+
+fn default_version() -> u32 {
+    MANIFEST_VERSION
+}
+
+/// A library entity named by a manifest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestEntity {
+    /// "mcp", "skill", "subagent", "command" or "hook"
+    pub entity_type: String,
+    pub name: String,
+    /// None when the library has no entity of that name
+    pub entity_id: Option<i64>,
+}
+
+/// What applying a manifest changed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestSyncResult {
+    /// Newly assigned, or re-enabled, entities
+    pub assigned: Vec<ManifestEntity>,
+    /// Assignments the manifest no longer lists
+    pub removed: Vec<ManifestEntity>,
+    /// Names in the manifest that the library doesn't have
+    pub missing: Vec<ManifestEntity>,
+    /// Claude config writes queued for confirmation
+    #[serde(default)]
+    pub pending_changes: Vec<PendingChange>,
+}
+
+/// (entity type, library table, assignment table, assignment column)
+const KINDS: &[(&str, &str, &str, &str)] = &[
+    ("mcp", "mcps", "project_mcps", "mcp_id"),
+    ("skill", "skills", "project_skills", "skill_id"),
+    ("subagent", "subagents", "project_subagents", "subagent_id"),
+    ("command", "commands", "project_commands", "command_id"),
+    ("hook", "hooks", "project_hooks", "hook_id"),
+];
+
+impl ProjectManifest {
+    fn names(&self, entity_type: &str) -> &[String] {
+        match entity_type {
+            "mcp" => &self.mcps,
+            "skill" => &self.skills,
+            "subagent" => &self.subagents,
+            "command" => &self.commands,
+            _ => &self.hooks,
+        }
+    }
+
+    fn names_mut(&mut self, entity_type: &str) -> &mut Vec<String> {
+        match entity_type {
+            "mcp" => &mut self.mcps,
+            "skill" => &mut self.skills,
+            "subagent" => &mut self.subagents,
+            "command" => &mut self.commands,
+            _ => &mut self.hooks,
+        }
+    }
+}
+
+pub fn manifest_path(project_path: &Path) -> PathBuf {
+    project_path.join(MANIFEST_FILE)
+}
+
+pub fn read_manifest(project_path: &Path) -> Result<ProjectManifest> {
+    let path = manifest_path(project_path);
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("No {} found at {}", MANIFEST_FILE, path.display()))?;
+    let manifest: ProjectManifest =
+        serde_json::from_str(&content).with_context(|| format!("Invalid {}", path.display()))?;
+    if manifest.version > MANIFEST_VERSION {
+        return Err(anyhow!(
+            "{} is version {}; this app only understands version {}",
+            MANIFEST_FILE,
+            manifest.version,
+            MANIFEST_VERSION
+        ));
+    }
+    Ok(manifest)
+}
+
+pub fn write_manifest(project_path: &Path, manifest: &ProjectManifest) -> Result<PathBuf> {
+    let path = manifest_path(project_path);
+    let mut content = serde_json::to_string_pretty(manifest)?;
+    content.push('\n');
+    std::fs::write(&path, content)?;
+    Ok(path)
+}
+
+/// The manifest describing a project's enabled assignments, names sorted
+pub fn manifest_from_db(db: &Database, project_id: i64) -> Result<ProjectManifest> {
+    let mut manifest = ProjectManifest {
+        version: MANIFEST_VERSION,
+        ..Default::default()
+    };
+    for (entity_type, table, assignments, column) in KINDS {
+        let mut stmt = db.conn().prepare(&format!(
+            "SELECT e.name FROM {assignments} a JOIN {table} e ON e.id = a.{column}
+             WHERE a.project_id = ? AND a.is_enabled = 1 ORDER BY e.name"
+        ))?;
+        *manifest.names_mut(entity_type) = stmt
+            .query_map([project_id], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+    }
+    Ok(manifest)
+}
+
+/// Make a project's assignments match `manifest`: listed entities are
+/// assigned (or re-enabled) and unlisted assignments removed. Only the
+/// database changes; the caller rewrites the project's config files.
+pub fn apply_manifest(
+    db: &Database,
+    project_id: i64,
+    manifest: &ProjectManifest,
+) -> Result<ManifestSyncResult> {
+    let mut result = ManifestSyncResult::default();
+    let tx = db.conn().unchecked_transaction()?;
+
+    for (entity_type, table, assignments, column) in KINDS {
+        let entity = |name: &str, entity_id: Option<i64>| ManifestEntity {
+            entity_type: entity_type.to_string(),
+            name: name.to_string(),
+            entity_id,
+        };
+
+        let mut wanted = Vec::new();
+        for name in manifest.names(entity_type) {
+            let id: Option<i64> = db
+                .conn()
+                .query_row(
+                    &format!("SELECT id FROM {table} WHERE name = ?"),
+                    [name],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let Some(id) = id else {
+                result.missing.push(entity(name, None));
+                continue;
+            };
+            wanted.push(id);
+
+            let enabled: Option<bool> = db
+                .conn()
+                .query_row(
+                    &format!(
+                        "SELECT is_enabled FROM {assignments} WHERE project_id = ? AND {column} = ?"
+                    ),
+                    params![project_id, id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            match enabled {
+                Some(true) => {}
+                Some(false) => {
+                    db.conn().execute(
+                        &format!(
+                            "UPDATE {assignments} SET is_enabled = 1 WHERE project_id = ? AND {column} = ?"
+                        ),
+                        params![project_id, id],
+                    )?;
+                    result.assigned.push(entity(name, Some(id)));
+                }
+                None => {
+                    db.conn().execute(
+                        &format!("INSERT INTO {assignments} (project_id, {column}) VALUES (?, ?)"),
+                        params![project_id, id],
+                    )?;
+                    result.assigned.push(entity(name, Some(id)));
+                }
+            }
+        }
+
+        let mut stmt = db.conn().prepare(&format!(
+            "SELECT e.id, e.name FROM {assignments} a JOIN {table} e ON e.id = a.{column}
+             WHERE a.project_id = ? ORDER BY e.name"
+        ))?;
+        let current = stmt
+            .query_map([project_id], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        for (id, name) in current.into_iter().filter(|(id, _)| !wanted.contains(id)) {
+            db.conn().execute(
+                &format!("DELETE FROM {assignments} WHERE project_id = ? AND {column} = ?"),
+                params![project_id, id],
+            )?;
+            result.removed.push(entity(&name, Some(id)));
+        }
+    }
+
+    tx.commit()?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn insert(db: &Database, sql: &str, values: impl rusqlite::Params) -> i64 {
+        db.conn().execute(sql, values).unwrap();
+        db.conn().last_insert_rowid()
+    }
+
+    #[test]
+    fn test_manifest_round_trip_and_apply() {
+        let db = Database::in_memory().unwrap();
+        let project = insert(
+            &db,
+            "INSERT INTO projects (name, path) VALUES ('p', '/tmp/p')",
+            [],
+        );
+        let github = insert(
+            &db,
+            "INSERT INTO mcps (name, type, command) VALUES ('github', 'stdio', 'npx')",
+            [],
+        );
+        let slack = insert(
+            &db,
+            "INSERT INTO mcps (name, type, command) VALUES ('slack', 'stdio', 'npx')",
+            [],
+        );
+        let skill = insert(
+            &db,
+            "INSERT INTO skills (name, content) VALUES ('review', 'x')",
+            [],
+        );
+        insert(
+            &db,
+            "INSERT INTO project_mcps (project_id, mcp_id) VALUES (?, ?)",
+            params![project, github],
+        );
+        insert(
+            &db,
+            "INSERT INTO project_mcps (project_id, mcp_id, is_enabled) VALUES (?, ?, 0)",
+            params![project, slack],
+        );
+        insert(
+            &db,
+            "INSERT INTO project_skills (project_id, skill_id) VALUES (?, ?)",
+            params![project, skill],
+        );
+
+        let manifest = manifest_from_db(&db, project).unwrap();
+        assert_eq!(manifest.mcps, vec!["github"]);
+        assert_eq!(manifest.skills, vec!["review"]);
+
+        let dir = TempDir::new().unwrap();
+        write_manifest(dir.path(), &manifest).unwrap();
+        assert_eq!(read_manifest(dir.path()).unwrap(), manifest);
+
+        // A teammate's manifest: slack instead of github, plus an unknown hook
+        let wanted = ProjectManifest {
+            mcps: vec!["slack".to_string()],
+            skills: vec!["review".to_string()],
+            hooks: vec!["format-on-save".to_string()],
+            ..Default::default()
+        };
+        let result = apply_manifest(&db, project, &wanted).unwrap();
+        let names = |entities: &[ManifestEntity]| {
+            entities
+                .iter()
+                .map(|e| format!("{}:{}", e.entity_type, e.name))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&result.assigned), vec!["mcp:slack"]);
+        assert_eq!(names(&result.removed), vec!["mcp:github"]);
+        assert_eq!(names(&result.missing), vec!["hook:format-on-save"]);
+
+        let applied = manifest_from_db(&db, project).unwrap();
+        assert_eq!(applied.mcps, vec!["slack"]);
+        assert_eq!(applied.skills, vec!["review"]);
+
+        // Applying again is a no-op
+        let again = apply_manifest(&db, project, &wanted).unwrap();
+        assert!(again.assigned.is_empty() && again.removed.is_empty());
+    }
+
+    #[test]
+    fn test_read_manifest_defaults_and_version_check() {
+        let dir = TempDir::new().unwrap();
+        assert!(read_manifest(dir.path()).is_err());
+
+        std::fs::write(manifest_path(dir.path()), r#"{"mcps": ["github"]}"#).unwrap();
+        let manifest = read_manifest(dir.path()).unwrap();
+        assert_eq!(manifest.version, 1);
+        assert_eq!(manifest.mcps, vec!["github"]);
+        assert!(manifest.hooks.is_empty());
+
+        std::fs::write(manifest_path(dir.path()), r#"{"version": 2}"#).unwrap();
+        assert!(read_manifest(dir.path()).is_err());
+    }
+}
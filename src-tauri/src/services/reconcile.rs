@@ -0,0 +1,1025 @@
+//! Reconcile the library against what is actually on disk.
+//!
+//! The startup scan only ever adds rows, so deleting an entity in the app
+//! (or deleting its file by hand) leaves the library and the filesystem out
+//! of sync. `reconcile_from_disk` re-walks the file-backed entity directories
+//! and reports the drift; with `apply` it also fixes it.
+//! `prune_missing_auto_detected` is the unattended half the startup scan
+//! runs: it only removes auto-detected rows whose source is gone.
+
+use crate::db::Database;
+use crate::services::scanner::{
+    assign_agent_to_project, assign_command_to_project, assign_skill_to_project,
+    get_or_create_agent, get_or_create_command, get_or_create_skill, insert_skill_files,
+    parse_agent_file, parse_agent_skill_dir, parse_rule_file, parse_skill_file, refresh_agent_row,
+    refresh_command_row, refresh_rule_row, refresh_skill_row, upsert_rule_from_file, walk_md_dir,
+    SOURCE_AUTO_DETECTED,
+};
+use crate::services::{claude_json, config_parser};
+use crate::utils::paths::get_claude_paths;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Which entity kinds a reconcile pass covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReconcileScope {
+    All,
+    Commands,
+    Skills,
+    Agents,
+    Rules,
+}
+
+impl ReconcileScope {
+    fn kinds(self) -> &'static [EntityKind] {
+        match self {
+            ReconcileScope::All => &[
+                EntityKind::Command,
+                EntityKind::Skill,
+                EntityKind::Agent,
+                EntityKind::Rule,
+            ],
+            ReconcileScope::Commands => &[EntityKind::Command],
+            ReconcileScope::Skills => &[EntityKind::Skill],
+            ReconcileScope::Agents => &[EntityKind::Agent],
+            ReconcileScope::Rules => &[EntityKind::Rule],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EntityKind {
+    Command,
+    Skill,
+    Agent,
+    Rule,
+}
+
+impl EntityKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            EntityKind::Command => "command",
+            EntityKind::Skill => "skill",
+            EntityKind::Agent => "agent",
+            EntityKind::Rule => "rule",
+        }
+    }
+
+    fn table(self) -> &'static str {
+        match self {
+            EntityKind::Command => "commands",
+            EntityKind::Skill => "skills",
+            EntityKind::Agent => "subagents",
+            EntityKind::Rule => "rules",
+        }
+    }
+
+    /// Subdirectory of a `.claude` directory holding this kind
+    fn dir_name(self) -> &'static str {
+        match self {
+            EntityKind::Command => "commands",
+            EntityKind::Skill => "skills",
+            EntityKind::Agent => "agents",
+            EntityKind::Rule => "rules",
+        }
+    }
+}
+
+/// A single entity that differs between the library and disk
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcileEntry {
+    pub entity_type: String,
+    pub name: String,
+    /// Library row id, when the entity exists in the library
+    pub id: Option<i64>,
+    pub source_path: String,
+    /// Project whose `.claude` directory the file lives in, if any
+    pub project_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcileReport {
+    /// On disk but not in the library (re-importable)
+    pub missing_from_library: Vec<ReconcileEntry>,
+    /// Auto-detected library rows whose source file no longer exists
+    pub missing_from_disk: Vec<ReconcileEntry>,
+    /// Library rows whose content differs from their source file
+    pub content_mismatches: Vec<ReconcileEntry>,
+    /// Whether the changes above were written to the library
+    pub applied: bool,
+}
+
+/// An entity parsed from disk
+struct DiskEntity {
+    kind: EntityKind,
+    name: String,
+    content: String,
+    path: PathBuf,
+    project_id: Option<i64>,
+}
+
+/// Reconcile the library against `~/.claude` and every registered project's
+/// `.claude` directory. Nothing is written unless `apply` is set.
+pub fn reconcile_from_disk(
+    db: &Database,
+    scope: ReconcileScope,
+    apply: bool,
+) -> Result<ReconcileReport> {
+    let paths = get_claude_paths()?;
+    let mut roots = vec![(None, paths.claude_dir)];
+
+    let mut stmt = db.conn().prepare("SELECT id, path FROM projects")?;
+    let projects: Vec<(i64, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .filter_map(|r| r.ok())
+        .collect();
+    for (id, path) in projects {
+        roots.push((Some(id), Path::new(&path).join(".claude")));
+    }
+
+    reconcile_roots(db, scope, &roots, apply)
+}
+
+/// Reconcile against an explicit set of `.claude` roots
+pub(crate) fn reconcile_roots(
+    db: &Database,
+    scope: ReconcileScope,
+    roots: &[(Option<i64>, PathBuf)],
+    apply: bool,
+) -> Result<ReconcileReport> {
+    let mut report = ReconcileReport::default();
+    let mut to_import = Vec::new();
+    let mut to_refresh = Vec::new();
+
+    for &kind in scope.kinds() {
+        let library = load_library_rows(db, kind)?;
+
+        for (project_id, root) in roots {
+            for entity in collect_disk_entities(kind, &root.join(kind.dir_name()), *project_id)? {
+                let source_path = entity.path.to_string_lossy().to_string();
+                match library.get(&entity.name) {
+                    None => {
+                        report.missing_from_library.push(ReconcileEntry {
+                            entity_type: kind.as_str().to_string(),
+                            name: entity.name.clone(),
+                            id: None,
+                            source_path,
+                            project_id: entity.project_id,
+                        });
+                        to_import.push(entity);
+                    }
+                    Some(row) => {
+                        if row.source_path.as_deref() == Some(source_path.as_str())
+                            && row.content.as_deref().unwrap_or("") != entity.content
+                        {
+                            report.content_mismatches.push(ReconcileEntry {
+                                entity_type: kind.as_str().to_string(),
+                                name: entity.name,
+                                id: Some(row.id),
+                                source_path,
+                                project_id: entity.project_id,
+                            });
+                            to_refresh.push((kind, row.id, entity.path));
+                        }
+                    }
+                }
+            }
+        }
+
+        for (name, row) in &library {
+            let Some(source_path) = row.source_path.as_deref().filter(|p| !p.is_empty()) else {
+                continue;
+            };
+            if row.source == SOURCE_AUTO_DETECTED && !Path::new(source_path).exists() {
+                report.missing_from_disk.push(ReconcileEntry {
+                    entity_type: kind.as_str().to_string(),
+                    name: name.clone(),
+                    id: Some(row.id),
+                    source_path: source_path.to_string(),
+                    project_id: None,
+                });
+            }
+        }
+    }
+
+    report
+        .missing_from_disk
+        .sort_by(|a, b| (&a.entity_type, &a.name).cmp(&(&b.entity_type, &b.name)));
+
+    if apply {
+        apply_report(db, &report, &to_import, &to_refresh)?;
+        report.applied = true;
+    }
+
+    Ok(report)
+}
+
+struct LibraryRow {
+    id: i64,
+    content: Option<String>,
+    source: String,
+    source_path: Option<String>,
+}
+
+fn load_library_rows(db: &Database, kind: EntityKind) -> Result<HashMap<String, LibraryRow>> {
+    let query = format!(
+        "SELECT id, name, content, source, source_path FROM {}",
+        kind.table()
+    );
+    let mut stmt = db.conn().prepare(&query)?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(1)?,
+                LibraryRow {
+                    id: row.get(0)?,
+                    content: row.get(2)?,
+                    source: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                    source_path: row.get(4)?,
+                },
+            ))
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(rows)
+}
+
+fn collect_disk_entities(
+    kind: EntityKind,
+    dir: &Path,
+    project_id: Option<i64>,
+) -> Result<Vec<DiskEntity>> {
+    let mut entities = Vec::new();
+
+    if kind == EntityKind::Skill {
+        if !dir.exists() {
+            return Ok(entities);
+        }
+        for entry in std::fs::read_dir(dir)?.flatten() {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+            if let Some((skill, _)) = parse_agent_skill_dir(&path) {
+                entities.push(DiskEntity {
+                    kind,
+                    name: skill.name,
+                    content: skill.content,
+                    path,
+                    project_id,
+                });
+            }
+        }
+        return Ok(entities);
+    }
+
+    walk_md_dir(dir, |path| {
+        let parsed = match kind {
+            EntityKind::Command => parse_skill_file(path).map(|c| (c.name, c.content)),
+            EntityKind::Agent => parse_agent_file(path).map(|a| (a.name, a.content)),
+            EntityKind::Rule => parse_rule_file(path).map(|r| (r.name, r.content)),
+            EntityKind::Skill => unreachable!("skills are directory based"),
+        };
+        if let Some((name, content)) = parsed {
+            entities.push(DiskEntity {
+                kind,
+                name,
+                content,
+                path: path.to_path_buf(),
+                project_id,
+            });
+        }
+        Ok(true)
+    })?;
+
+    Ok(entities)
+}
+
+/// Write a report's fixes in one transaction, so a failure part way leaves the
+/// library as it was
+fn apply_report(
+    db: &Database,
+    report: &ReconcileReport,
+    to_import: &[DiskEntity],
+    to_refresh: &[(EntityKind, i64, PathBuf)],
+) -> Result<()> {
+    let tx = db.conn().unchecked_transaction()?;
+
+    for entity in to_import {
+        import_disk_entity(db, entity)?;
+    }
+
+    for entry in &report.missing_from_disk {
+        let (Some(id), Some(kind)) = (entry.id, kind_from_str(&entry.entity_type)) else {
+            continue;
+        };
+        db.conn()
+            .execute(&format!("DELETE FROM {} WHERE id = ?", kind.table()), [id])?;
+    }
+
+    for (kind, id, path) in to_refresh {
+        refresh_from_file(db, *kind, *id, path)?;
+    }
+
+    tx.commit()?;
+    Ok(())
+}
+
+/// Re-read a library row's source file and update its content and frontmatter
+/// fields from it
+fn refresh_from_file(db: &Database, kind: EntityKind, id: i64, path: &Path) -> Result<()> {
+    match kind {
+        EntityKind::Command => match parse_skill_file(path) {
+            Some(command) => refresh_command_row(db, id, &command),
+            None => Ok(()),
+        },
+        EntityKind::Skill => match parse_agent_skill_dir(path) {
+            Some((skill, _)) => refresh_skill_row(db, id, &skill),
+            None => Ok(()),
+        },
+        EntityKind::Agent => match parse_agent_file(path) {
+            Some(agent) => refresh_agent_row(db, id, &agent),
+            None => Ok(()),
+        },
+        EntityKind::Rule => match parse_rule_file(path) {
+            Some(rule) => refresh_rule_row(db, id, &rule),
+            None => Ok(()),
+        },
+    }
+}
+
+fn import_disk_entity(db: &Database, entity: &DiskEntity) -> Result<()> {
+    let source_path = entity.path.to_string_lossy().to_string();
+    match entity.kind {
+        EntityKind::Command => {
+            let Some(command) = parse_skill_file(&entity.path) else {
+                return Ok(());
+            };
+            let (command_id, _) = get_or_create_command(db, &command, &source_path)?;
+            if let Some(project_id) = entity.project_id {
+                assign_command_to_project(db, project_id, command_id)?;
+            }
+        }
+        EntityKind::Skill => {
+            let Some((skill, files)) = parse_agent_skill_dir(&entity.path) else {
+                return Ok(());
+            };
+            let (skill_id, was_created) = get_or_create_skill(db, &skill, &source_path)?;
+            if was_created && !files.is_empty() {
+                insert_skill_files(db, skill_id, &files)?;
+            }
+            if let Some(project_id) = entity.project_id {
+                assign_skill_to_project(db, project_id, skill_id)?;
+            }
+        }
+        EntityKind::Agent => {
+            let Some(agent) = parse_agent_file(&entity.path) else {
+                return Ok(());
+            };
+            let agent_id = get_or_create_agent(db, &agent, &source_path)?;
+            if let Some(project_id) = entity.project_id {
+                assign_agent_to_project(db, project_id, agent_id)?;
+            }
+        }
+        EntityKind::Rule => {
+            upsert_rule_from_file(db, &entity.path, entity.project_id)?;
+        }
+    }
+    Ok(())
+}
+
+/// Auto-detected rows marked removed because their file or config entry is
+/// gone
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneSummary {
+    pub mcps: usize,
+    pub skills: usize,
+    pub commands: usize,
+    pub subagents: usize,
+}
+
+impl PruneSummary {
+    pub fn total(&self) -> usize {
+        self.mcps + self.skills + self.commands + self.subagents
+    }
+}
+
+/// Source path the scanner records for MCPs read from claude.json
+const CLAUDE_JSON_SOURCE_PATH: &str = "~/.claude.json";
+
+/// SQL condition, over assignment `a` of row `t`, that holds when the scan
+/// made the assignment: the scanner assigns what it finds in a project's
+/// directory to that project
+const SCANNED_INTO_PROJECT: &str = "EXISTS (SELECT 1 FROM projects p \
+     WHERE p.id = a.project_id AND instr(t.source_path, p.path) = 1)";
+
+/// The scanner puts every MCP it reads from claude.json in `global_mcps`
+const SCANNED_INTO_GLOBAL_MCPS: &str = "t.source_path = '~/.claude.json'";
+
+/// Global skills, commands and subagents are never assigned by the scanner
+const NEVER_SCANNED: &str = "0";
+
+/// Mark auto-detected MCPs, skills, commands and subagents removed when their
+/// backing file or config entry no longer exists, and restore them when it
+/// comes back. Rows the user assigned to a project or globally, or disabled
+/// (which removes the file), are kept, as is anything created or imported by
+/// the user. The assignments the scan itself made are dropped with the row.
+/// Hooks have no source path to check and are left alone.
+pub fn prune_missing_auto_detected(db: &Database) -> Result<PruneSummary> {
+    let claude_json_mcps = match claude_json::get_all_mcps_from_claude_json() {
+        Ok(mcps) => Some(mcps.into_iter().map(|m| m.name).collect()),
+        Err(e) => {
+            log::warn!("Not pruning claude.json MCPs, couldn't read it: {}", e);
+            None
+        }
+    };
+    prune_missing(db, claude_json_mcps.as_ref())
+}
+
+/// [`prune_missing_auto_detected`] against the MCP names claude.json lists
+/// (`None` when it couldn't be read, so none of its MCPs are pruned)
+pub(crate) fn prune_missing(
+    db: &Database,
+    claude_json_mcps: Option<&HashSet<String>>,
+) -> Result<PruneSummary> {
+    let file_gone = |_: &str, source_path: &str| source_gone(Path::new(source_path));
+    Ok(PruneSummary {
+        mcps: prune_table(
+            db,
+            "mcps",
+            &[
+                ("project_mcps", "mcp_id", SCANNED_INTO_PROJECT),
+                ("global_mcps", "mcp_id", SCANNED_INTO_GLOBAL_MCPS),
+            ],
+            |name, source_path| mcp_entry_gone(name, source_path, claude_json_mcps),
+        )?,
+        skills: prune_table(
+            db,
+            "skills",
+            &[
+                ("project_skills", "skill_id", SCANNED_INTO_PROJECT),
+                ("global_skills", "skill_id", NEVER_SCANNED),
+            ],
+            file_gone,
+        )?,
+        commands: prune_table(
+            db,
+            "commands",
+            &[
+                ("project_commands", "command_id", SCANNED_INTO_PROJECT),
+                ("global_commands", "command_id", NEVER_SCANNED),
+            ],
+            file_gone,
+        )?,
+        subagents: prune_table(
+            db,
+            "subagents",
+            &[
+                ("project_subagents", "subagent_id", SCANNED_INTO_PROJECT),
+                ("global_subagents", "subagent_id", NEVER_SCANNED),
+            ],
+            file_gone,
+        )?,
+    })
+}
+
+/// Mark auto-detected rows of `table` removed when `gone(name, source_path)`
+/// holds, and clear the mark when it no longer does. Rows with an assignment
+/// in `assignments` that is disabled or that the scan didn't make (per its
+/// SQL condition) are skipped; the scan's own assignments are deleted with
+/// the row. Returns how many were newly marked.
+fn prune_table(
+    db: &Database,
+    table: &str,
+    assignments: &[(&str, &str, &str)],
+    gone: impl Fn(&str, &str) -> bool,
+) -> Result<usize> {
+    let unassigned: String = assignments
+        .iter()
+        .map(|(assignment_table, column, scanned)| {
+            format!(
+                " AND NOT EXISTS (SELECT 1 FROM {assignment_table} a WHERE a.{column} = t.id \
+                 AND (a.is_enabled = 0 OR NOT ({scanned})))"
+            )
+        })
+        .collect();
+    let mut stmt = db.conn().prepare(&format!(
+        "SELECT t.id, t.name, t.source_path, t.removed_at IS NOT NULL FROM {table} t
+         WHERE t.source = ? AND t.source_path IS NOT NULL AND t.source_path != ''{unassigned}"
+    ))?;
+    let rows: Vec<(i64, String, String, bool)> = stmt
+        .query_map([SOURCE_AUTO_DETECTED], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut pruned = 0;
+    for (id, name, source_path, removed) in rows {
+        match (gone(&name, &source_path), removed) {
+            (true, false) => {
+                db.conn().execute(
+                    &format!(
+                        "UPDATE {} SET removed_at = CURRENT_TIMESTAMP WHERE id = ?",
+                        table
+                    ),
+                    [id],
+                )?;
+                for (assignment_table, column, _) in assignments {
+                    db.conn().execute(
+                        &format!("DELETE FROM {assignment_table} WHERE {column} = ?"),
+                        [id],
+                    )?;
+                }
+                log::info!(
+                    "Marked '{}' in {} removed: {} is gone",
+                    name,
+                    table,
+                    source_path
+                );
+                pruned += 1;
+            }
+            (false, true) => {
+                db.conn().execute(
+                    &format!("UPDATE {} SET removed_at = NULL WHERE id = ?", table),
+                    [id],
+                )?;
+                log::info!("Restored '{}' in {}: {} is back", name, table, source_path);
+            }
+            _ => {}
+        }
+    }
+    Ok(pruned)
+}
+
+/// A source file or directory was deleted. A missing parent (an unmounted
+/// drive, a moved project) isn't taken as a deletion.
+fn source_gone(path: &Path) -> bool {
+    !path.exists() && path.parent().is_some_and(Path::exists)
+}
+
+/// Whether the config an MCP was detected in no longer lists it
+fn mcp_entry_gone(
+    name: &str,
+    source_path: &str,
+    claude_json_mcps: Option<&HashSet<String>>,
+) -> bool {
+    if source_path == CLAUDE_JSON_SOURCE_PATH {
+        return claude_json_mcps.is_some_and(|names| !names.contains(name));
+    }
+
+    let path = Path::new(source_path);
+    if path.file_name().is_some_and(|n| n == ".mcp.json") {
+        if !path.exists() {
+            return source_gone(path);
+        }
+        return config_parser::parse_mcp_file(path)
+            .is_ok_and(|mcps| !mcps.iter().any(|m| m.name == name));
+    }
+
+    // Project directories and other sources aren't tied to a single entry
+    false
+}
+
+fn kind_from_str(s: &str) -> Option<EntityKind> {
+    [
+        EntityKind::Command,
+        EntityKind::Skill,
+        EntityKind::Agent,
+        EntityKind::Rule,
+    ]
+    .into_iter()
+    .find(|k| k.as_str() == s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::scanner;
+    use rusqlite::params;
+    use serial_test::serial;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn setup() -> (Database, TempDir) {
+        let db = Database::in_memory().unwrap();
+        let dir = TempDir::new().unwrap();
+        (db, dir)
+    }
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn roots(dir: &TempDir) -> Vec<(Option<i64>, PathBuf)> {
+        vec![(None, dir.path().to_path_buf())]
+    }
+
+    #[test]
+    fn test_reports_file_missing_from_library_without_mutating() {
+        let (db, dir) = setup();
+        write(&dir.path().join("commands/deploy.md"), "Deploy it");
+
+        let report = reconcile_roots(&db, ReconcileScope::All, &roots(&dir), false).unwrap();
+
+        assert_eq!(report.missing_from_library.len(), 1);
+        assert_eq!(report.missing_from_library[0].entity_type, "command");
+        assert_eq!(report.missing_from_library[0].name, "deploy");
+        assert!(!report.applied);
+
+        let count: i64 = db
+            .conn()
+            .query_row("SELECT COUNT(*) FROM commands", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn test_apply_imports_missing_entities() {
+        let (db, dir) = setup();
+        write(
+            &dir.path().join("agents/reviewer.md"),
+            "---\ndescription: Reviews\n---\nReview code",
+        );
+        write(&dir.path().join("skills/pdf/SKILL.md"), "Handle PDFs");
+        write(&dir.path().join("rules/style.md"), "Use tabs");
+
+        let report = reconcile_roots(&db, ReconcileScope::All, &roots(&dir), true).unwrap();
+        assert!(report.applied);
+        assert_eq!(report.missing_from_library.len(), 3);
+
+        let again = reconcile_roots(&db, ReconcileScope::All, &roots(&dir), false).unwrap();
+        assert_eq!(again, ReconcileReport::default());
+    }
+
+    #[test]
+    fn test_reports_and_removes_auto_detected_rows_missing_on_disk() {
+        let (db, dir) = setup();
+        let gone = dir.path().join("commands/gone.md");
+        db.conn()
+            .execute(
+                "INSERT INTO commands (name, content, source, source_path) VALUES ('gone', 'x', ?, ?)",
+                params![SOURCE_AUTO_DETECTED, gone.to_string_lossy()],
+            )
+            .unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO commands (name, content, source, source_path) VALUES ('mine', 'x', 'manual', ?)",
+                [dir.path().join("commands/mine.md").to_string_lossy()],
+            )
+            .unwrap();
+
+        let report = reconcile_roots(&db, ReconcileScope::Commands, &roots(&dir), true).unwrap();
+        assert_eq!(report.missing_from_disk.len(), 1);
+        assert_eq!(report.missing_from_disk[0].name, "gone");
+
+        let names: Vec<String> = db
+            .conn()
+            .prepare("SELECT name FROM commands")
+            .unwrap()
+            .query_map([], |r| r.get(0))
+            .unwrap()
+            .filter_map(|r| r.ok())
+            .collect();
+        assert_eq!(names, vec!["mine".to_string()]);
+    }
+
+    fn command_names(db: &Database) -> Vec<String> {
+        db.conn()
+            .prepare("SELECT name FROM commands WHERE removed_at IS NULL ORDER BY name")
+            .unwrap()
+            .query_map([], |r| r.get(0))
+            .unwrap()
+            .filter_map(|r| r.ok())
+            .collect()
+    }
+
+    /// Point HOME at a temp dir for the duration of `f`, so the real scan
+    /// functions build the fixtures
+    #[cfg(unix)]
+    fn with_temp_home(f: impl FnOnce(&Path, &Database)) {
+        let home = TempDir::new().unwrap();
+        let saved = std::env::var_os("HOME");
+        std::env::set_var("HOME", home.path());
+
+        let db = Database::in_memory().unwrap();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f(home.path(), &db)));
+
+        match saved {
+            Some(value) => std::env::set_var("HOME", value),
+            None => std::env::remove_var("HOME"),
+        }
+        if let Err(panic) = result {
+            std::panic::resume_unwind(panic);
+        }
+    }
+
+    fn write_json(path: &Path, value: serde_json::Value) {
+        write(path, &value.to_string());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[serial]
+    fn test_prune_removes_only_unassigned_auto_detected_rows() {
+        with_temp_home(|home, db| {
+            let commands = home.join(".claude/commands");
+            for name in ["kept", "gone", "assigned"] {
+                write(&commands.join(format!("{}.md", name)), "Run it");
+            }
+            scanner::scan_global_commands(db).unwrap();
+
+            // The user adds one to an unrelated project
+            db.conn()
+                .execute(
+                    "INSERT INTO projects (name, path) VALUES ('other', '/srv/other')",
+                    [],
+                )
+                .unwrap();
+            db.conn()
+                .execute(
+                    "INSERT INTO project_commands (project_id, command_id)
+                     SELECT p.id, c.id FROM projects p, commands c WHERE c.name = 'assigned'",
+                    [],
+                )
+                .unwrap();
+            fs::remove_file(commands.join("gone.md")).unwrap();
+            fs::remove_file(commands.join("assigned.md")).unwrap();
+
+            let summary = prune_missing(db, None).unwrap();
+            assert_eq!(summary.commands, 1);
+            assert_eq!(summary.total(), 1);
+            assert_eq!(command_names(db), vec!["assigned", "kept"]);
+        });
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[serial]
+    fn test_prune_project_items_the_scan_assigned() {
+        with_temp_home(|home, db| {
+            let project = home.join("app");
+            let moved = home.join("moved");
+            write(&project.join(".claude/commands/build.md"), "Build it");
+            write(&project.join(".claude/commands/test.md"), "Test it");
+            write(&moved.join(".claude/commands/ship.md"), "Ship it");
+            write_json(
+                &home.join(".claude.json"),
+                serde_json::json!({"projects": {
+                    project.to_string_lossy(): {"mcpServers": {}},
+                    moved.to_string_lossy(): {"mcpServers": {}}
+                }}),
+            );
+            scanner::scan_claude_json(db).unwrap();
+            assert_eq!(command_names(db), vec!["build", "ship", "test"]);
+
+            fs::remove_file(project.join(".claude/commands/build.md")).unwrap();
+            // Disabling in the app deletes the file
+            db.conn()
+                .execute(
+                    "UPDATE project_commands SET is_enabled = 0
+                     WHERE command_id = (SELECT id FROM commands WHERE name = 'test')",
+                    [],
+                )
+                .unwrap();
+            fs::remove_file(project.join(".claude/commands/test.md")).unwrap();
+            // A moved project isn't taken as a deletion
+            fs::remove_dir_all(&moved).unwrap();
+
+            assert_eq!(prune_missing(db, None).unwrap().commands, 1);
+            assert_eq!(command_names(db), vec!["ship", "test"]);
+            let assignments: i64 = db
+                .conn()
+                .query_row(
+                    "SELECT COUNT(*) FROM project_commands pc JOIN commands c ON c.id = pc.command_id
+                     WHERE c.name = 'build'",
+                    [],
+                    |r| r.get(0),
+                )
+                .unwrap();
+            assert_eq!(assignments, 0);
+        });
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[serial]
+    fn test_prune_keeps_items_disabled_in_app() {
+        with_temp_home(|home, db| {
+            let skill_dir = home.join(".claude/skills/review");
+            write(&skill_dir.join("SKILL.md"), "Review code");
+            scanner::scan_global_skills(db).unwrap();
+
+            // Disabling a global skill deletes its file from ~/.claude/skills/
+            db.conn()
+                .execute(
+                    "INSERT INTO global_skills (skill_id, is_enabled) SELECT id, 0 FROM skills",
+                    [],
+                )
+                .unwrap();
+            fs::remove_dir_all(&skill_dir).unwrap();
+
+            assert_eq!(prune_missing(db, None).unwrap().skills, 0);
+            let names: Vec<String> = db
+                .get_all_skills()
+                .unwrap()
+                .into_iter()
+                .map(|s| s.name)
+                .collect();
+            assert_eq!(names, vec!["review"]);
+        });
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[serial]
+    fn test_pruned_item_is_restored_when_source_returns() {
+        with_temp_home(|home, db| {
+            let path = home.join(".claude/commands/deploy.md");
+            write(&path, "Deploy it");
+            scanner::scan_global_commands(db).unwrap();
+            fs::remove_file(&path).unwrap();
+
+            assert_eq!(prune_missing(db, None).unwrap().commands, 1);
+            assert!(command_names(db).is_empty());
+            // Already marked: not counted again
+            assert_eq!(prune_missing(db, None).unwrap().commands, 0);
+
+            write(&path, "Deploy it");
+            prune_missing(db, None).unwrap();
+            assert_eq!(command_names(db), vec!["deploy"]);
+        });
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[serial]
+    fn test_prune_mcps_missing_from_their_config() {
+        with_temp_home(|home, db| {
+            let claude_json = home.join(".claude.json");
+            let plugin_file = home.join(".claude/plugins/marketplaces/market/.mcp.json");
+            let server = serde_json::json!({"command": "npx"});
+            write_json(
+                &claude_json,
+                serde_json::json!({"mcpServers": {"kept": server, "gone": server}}),
+            );
+            write_json(
+                &plugin_file,
+                serde_json::json!({"mcpServers": {"listed": server, "unlisted": server}}),
+            );
+            scanner::scan_global_mcps_from_claude_json(db).unwrap();
+            scanner::scan_plugins(db).unwrap();
+
+            write_json(
+                &claude_json,
+                serde_json::json!({"mcpServers": {"kept": server}}),
+            );
+            write_json(
+                &plugin_file,
+                serde_json::json!({"mcpServers": {"listed": server}}),
+            );
+
+            // claude.json unreadable: its MCPs are left alone
+            assert_eq!(prune_missing(db, None).unwrap().mcps, 1);
+            assert_eq!(prune_missing_auto_detected(db).unwrap().mcps, 1);
+
+            let names = |sql: &str| -> Vec<String> {
+                db.conn()
+                    .prepare(sql)
+                    .unwrap()
+                    .query_map([], |r| r.get(0))
+                    .unwrap()
+                    .filter_map(|r| r.ok())
+                    .collect()
+            };
+            assert_eq!(
+                names("SELECT name FROM mcps WHERE removed_at IS NULL ORDER BY name"),
+                vec!["kept", "listed"]
+            );
+            assert_eq!(
+                names("SELECT m.name FROM global_mcps g JOIN mcps m ON m.id = g.mcp_id"),
+                vec!["kept"]
+            );
+
+            // Disabled in the app, so no longer in claude.json
+            db.conn()
+                .execute("UPDATE global_mcps SET is_enabled = 0", [])
+                .unwrap();
+            write_json(&claude_json, serde_json::json!({"mcpServers": {}}));
+            assert_eq!(prune_missing_auto_detected(db).unwrap().mcps, 0);
+        });
+    }
+
+    #[test]
+    fn test_content_mismatch_is_refreshed_on_apply() {
+        let (db, dir) = setup();
+        let path = dir.path().join("rules/style.md");
+        write(&path, "Use spaces");
+        db.conn()
+            .execute(
+                "INSERT INTO rules (name, content, source, source_path) VALUES ('style', 'Use tabs', ?, ?)",
+                params![SOURCE_AUTO_DETECTED, path.to_string_lossy()],
+            )
+            .unwrap();
+
+        let report = reconcile_roots(&db, ReconcileScope::Rules, &roots(&dir), false).unwrap();
+        assert_eq!(report.content_mismatches.len(), 1);
+        assert!(report.missing_from_library.is_empty());
+
+        reconcile_roots(&db, ReconcileScope::Rules, &roots(&dir), true).unwrap();
+        let content: String = db
+            .conn()
+            .query_row("SELECT content FROM rules WHERE name = 'style'", [], |r| {
+                r.get(0)
+            })
+            .unwrap();
+        assert_eq!(content, "Use spaces");
+    }
+
+    #[test]
+    fn test_content_mismatch_refresh_updates_frontmatter_fields() {
+        let (db, dir) = setup();
+        let path = dir.path().join("commands/deploy.md");
+        write(
+            &path,
+            "---\ndescription: Ship it\nallowed-tools: Bash\nmodel: opus\n---\nDeploy now",
+        );
+        db.conn()
+            .execute(
+                "INSERT INTO commands (name, description, content, model, source, source_path)
+                 VALUES ('deploy', 'Old', 'Deploy later', 'haiku', ?, ?)",
+                params![SOURCE_AUTO_DETECTED, path.to_string_lossy()],
+            )
+            .unwrap();
+
+        reconcile_roots(&db, ReconcileScope::Commands, &roots(&dir), true).unwrap();
+
+        let row: (String, String, Option<String>, String) = db
+            .conn()
+            .query_row(
+                "SELECT description, content, allowed_tools, model FROM commands WHERE name = 'deploy'",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!(
+            row,
+            (
+                "Ship it".to_string(),
+                "Deploy now".to_string(),
+                Some("Bash".to_string()),
+                "opus".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_scope_limits_entity_kinds() {
+        let (db, dir) = setup();
+        write(&dir.path().join("commands/deploy.md"), "Deploy it");
+        write(&dir.path().join("rules/style.md"), "Use tabs");
+
+        let report = reconcile_roots(&db, ReconcileScope::Rules, &roots(&dir), false).unwrap();
+        assert_eq!(report.missing_from_library.len(), 1);
+        assert_eq!(report.missing_from_library[0].entity_type, "rule");
+    }
+
+    #[test]
+    fn test_project_entities_are_assigned_on_import() {
+        let (db, dir) = setup();
+        db.conn()
+            .execute(
+                "INSERT INTO projects (name, path) VALUES ('p', ?)",
+                [dir.path().to_string_lossy()],
+            )
+            .unwrap();
+        let project_id = db.conn().last_insert_rowid();
+        write(&dir.path().join(".claude/commands/build.md"), "Build it");
+
+        let project_roots = vec![(Some(project_id), dir.path().join(".claude"))];
+        reconcile_roots(&db, ReconcileScope::Commands, &project_roots, true).unwrap();
+
+        let assigned: i64 = db
+            .conn()
+            .query_row(
+                "SELECT COUNT(*) FROM project_commands WHERE project_id = ?",
+                [project_id],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(assigned, 1);
+    }
+
+    #[test]
+    fn test_scope_deserializes_lowercase() {
+        let scope: ReconcileScope = serde_json::from_str("\"agents\"").unwrap();
+        assert_eq!(scope, ReconcileScope::Agents);
+    }
+}
@@ -0,0 +1,304 @@
+//! Archive bundles in marketplace repos
+//!
+//! A repo's plugin manifest may list `.zip` / `.tar.gz` bundles under
+//! `"bundles"`. Sync downloads each one and reads the markdown files inside as
+//! if they lived in the repo. Everything is decompressed in memory, so both the
+//! download and the total decompressed size are capped to keep a zip bomb from
+//! exhausting memory.
+
+use anyhow::{anyhow, Result};
+use flate2::read::GzDecoder;
+use serde_json::Value;
+use std::io::{Cursor, Read};
+use std::path::{Component, Path};
+
+/// Largest bundle we'll download
+pub const MAX_BUNDLE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Largest total size a bundle may decompress to
+pub const MAX_BUNDLE_EXTRACTED_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Most entries (files and directories) a bundle may contain
+pub const MAX_BUNDLE_ENTRIES: usize = 2000;
+
+/// Archive formats a bundle can use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BundleFormat {
+    Zip,
+    TarGz,
+}
+
+/// Pick the archive format from a bundle's file name
+pub fn bundle_format(path: &str) -> Option<BundleFormat> {
+    let lower = path.to_lowercase();
+    if lower.ends_with(".zip") {
+        Some(BundleFormat::Zip)
+    } else if lower.ends_with(".tar.gz") || lower.ends_with(".tgz") {
+        Some(BundleFormat::TarGz)
+    } else {
+        None
+    }
+}
+
+/// Bundle paths listed in a plugin manifest's `"bundles"` array. Entries in an
+/// unsupported format, absolute paths and paths escaping the repo are dropped.
+pub fn bundle_paths_from_manifest(manifest: &str) -> Vec<String> {
+    let Ok(value) = serde_json::from_str::<Value>(manifest) else {
+        return Vec::new();
+    };
+    value
+        .get("bundles")
+        .and_then(|b| b.as_array())
+        .map(|bundles| {
+            bundles
+                .iter()
+                .filter_map(|b| b.as_str())
+                .map(|b| b.trim().trim_start_matches("./").to_string())
+                .filter(|b| is_safe_relative_path(b) && bundle_format(b).is_some())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Relative path with no `..` or root components
+fn is_safe_relative_path(path: &str) -> bool {
+    !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+/// Tracks entry count and decompressed bytes across a whole bundle
+struct ExtractBudget {
+    entries: usize,
+    bytes: u64,
+}
+
+impl ExtractBudget {
+    fn new() -> Self {
+        Self {
+            entries: 0,
+            bytes: 0,
+        }
+    }
+
+    fn count_entry(&mut self) -> Result<()> {
+        self.entries += 1;
+        if self.entries > MAX_BUNDLE_ENTRIES {
+            return Err(anyhow!(
+                "Bundle has more than {} entries",
+                MAX_BUNDLE_ENTRIES
+            ));
+        }
+        Ok(())
+    }
+
+    /// Read an entry without trusting its declared size: stop one byte past
+    /// the remaining budget and fail if we got there.
+    fn read_entry(&mut self, reader: impl Read) -> Result<Vec<u8>> {
+        let remaining = MAX_BUNDLE_EXTRACTED_BYTES - self.bytes;
+        let mut buf = Vec::new();
+        reader.take(remaining + 1).read_to_end(&mut buf)?;
+        if buf.len() as u64 > remaining {
+            return Err(anyhow!(
+                "Bundle decompresses to more than {} bytes",
+                MAX_BUNDLE_EXTRACTED_BYTES
+            ));
+        }
+        self.bytes += buf.len() as u64;
+        Ok(buf)
+    }
+}
+
+/// Markdown files in a bundle as `(path inside the bundle, content)`.
+/// Non-markdown files still count towards the size cap; markdown that isn't
+/// UTF-8 is skipped.
+pub fn extract_markdown_files(bundle_path: &str, bytes: &[u8]) -> Result<Vec<(String, String)>> {
+    match bundle_format(bundle_path) {
+        Some(BundleFormat::Zip) => extract_zip(bytes),
+        Some(BundleFormat::TarGz) => extract_tar_gz(bytes),
+        None => Err(anyhow!("Unsupported bundle format: {}", bundle_path)),
+    }
+}
+
+fn is_markdown(path: &str) -> bool {
+    path.to_lowercase().ends_with(".md")
+}
+
+fn extract_zip(bytes: &[u8]) -> Result<Vec<(String, String)>> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes))?;
+    if archive.len() > MAX_BUNDLE_ENTRIES {
+        return Err(anyhow!(
+            "Bundle has more than {} entries",
+            MAX_BUNDLE_ENTRIES
+        ));
+    }
+
+    let mut budget = ExtractBudget::new();
+    let mut files = Vec::new();
+    for i in 0..archive.len() {
+        budget.count_entry()?;
+        let entry = archive.by_index(i)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        let data = budget.read_entry(entry)?;
+        if !is_markdown(&name) || !is_safe_relative_path(&name) {
+            continue;
+        }
+        if let Ok(content) = String::from_utf8(data) {
+            files.push((name, content));
+        }
+    }
+    Ok(files)
+}
+
+fn extract_tar_gz(bytes: &[u8]) -> Result<Vec<(String, String)>> {
+    let mut archive = tar::Archive::new(GzDecoder::new(bytes));
+
+    let mut budget = ExtractBudget::new();
+    let mut files = Vec::new();
+    for entry in archive.entries()? {
+        budget.count_entry()?;
+        let entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = entry.path()?.to_string_lossy().into_owned();
+        let name = name.trim_start_matches("./").to_string();
+        let data = budget.read_entry(entry)?;
+        if !is_markdown(&name) || !is_safe_relative_path(&name) {
+            continue;
+        }
+        if let Ok(content) = String::from_utf8(data) {
+            files.push((name, content));
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    fn zip_bundle(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+        for (name, data) in files {
+            zip.start_file(*name, options).unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    fn tar_gz_bundle(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        for (name, data) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, *data).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[test]
+    fn test_bundle_format() {
+        assert_eq!(bundle_format("dist/skills.zip"), Some(BundleFormat::Zip));
+        assert_eq!(bundle_format("skills.TAR.GZ"), Some(BundleFormat::TarGz));
+        assert_eq!(bundle_format("skills.tgz"), Some(BundleFormat::TarGz));
+        assert_eq!(bundle_format("skills.tar"), None);
+    }
+
+    #[test]
+    fn test_bundle_paths_from_manifest() {
+        let manifest = r#"{
+            "name": "demo",
+            "bundles": ["./dist/skills.zip", "agents.tar.gz", "../escape.zip", "/abs.zip", "notes.txt", 3]
+        }"#;
+        assert_eq!(
+            bundle_paths_from_manifest(manifest),
+            vec!["dist/skills.zip", "agents.tar.gz"]
+        );
+        assert!(bundle_paths_from_manifest(r#"{"name": "demo"}"#).is_empty());
+        assert!(bundle_paths_from_manifest("not json").is_empty());
+    }
+
+    #[test]
+    fn test_extract_zip_markdown_only() {
+        let bytes = zip_bundle(&[
+            ("skills/review.md", b"# Review"),
+            ("agents/helper.md", b"---\nname: helper\n---\nHi"),
+            ("scripts/run.sh", b"echo hi"),
+        ]);
+        let files = extract_markdown_files("bundle.zip", &bytes).unwrap();
+        assert_eq!(
+            files,
+            vec![
+                ("skills/review.md".to_string(), "# Review".to_string()),
+                (
+                    "agents/helper.md".to_string(),
+                    "---\nname: helper\n---\nHi".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_tar_gz_markdown_only() {
+        let bytes = tar_gz_bundle(&[
+            ("commands/deploy.md", b"# Deploy"),
+            ("README.txt", b"read me"),
+        ]);
+        let files = extract_markdown_files("bundle.tar.gz", &bytes).unwrap();
+        assert_eq!(
+            files,
+            vec![("commands/deploy.md".to_string(), "# Deploy".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_extract_rejects_oversized_zip() {
+        // Zeros deflate to almost nothing, so this is tiny on the wire
+        let big = vec![0u8; (MAX_BUNDLE_EXTRACTED_BYTES + 1) as usize];
+        let bytes = zip_bundle(&[("bomb.md", &big)]);
+        assert!((bytes.len() as u64) < MAX_BUNDLE_BYTES);
+
+        let err = extract_markdown_files("bundle.zip", &bytes).unwrap_err();
+        assert!(err.to_string().contains("decompresses to more than"));
+    }
+
+    #[test]
+    fn test_extract_rejects_oversized_tar_gz_across_entries() {
+        let half = vec![0u8; (MAX_BUNDLE_EXTRACTED_BYTES / 2 + 1) as usize];
+        let bytes = tar_gz_bundle(&[("a.bin", &half), ("b.bin", &half)]);
+
+        let err = extract_markdown_files("bundle.tgz", &bytes).unwrap_err();
+        assert!(err.to_string().contains("decompresses to more than"));
+    }
+
+    #[test]
+    fn test_extract_rejects_too_many_entries() {
+        let names: Vec<String> = (0..=MAX_BUNDLE_ENTRIES)
+            .map(|i| format!("f{}.md", i))
+            .collect();
+        let files: Vec<(&str, &[u8])> = names.iter().map(|n| (n.as_str(), &b"x"[..])).collect();
+        let bytes = zip_bundle(&files);
+
+        let err = extract_markdown_files("bundle.zip", &bytes).unwrap_err();
+        assert!(err.to_string().contains("more than"));
+    }
+
+    #[test]
+    fn test_extract_invalid_archive() {
+        assert!(extract_markdown_files("bundle.zip", b"not a zip").is_err());
+        assert!(extract_markdown_files("bundle.tar.gz", b"not gzip").is_err());
+        assert!(extract_markdown_files("bundle.rar", b"").is_err());
+    }
+}
@@ -77,7 +77,7 @@ pub struct ParsedItem {
     pub source_url: Option<String>,
     pub raw_content: Option<String>,
     pub file_path: Option<String>,
-    pub item_type: String, // "mcp", "skill", "subagent"
+    pub item_type: String, // "mcp", "skill", "subagent", "hook"
     pub metadata: Option<String>,
 }
 
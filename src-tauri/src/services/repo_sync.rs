@@ -1,12 +1,15 @@
-use crate::db::{Database, Repo, RepoItem, SyncResult};
+use crate::db::{Database, Repo, RepoItem, RepoPreview, RepoPreviewItem, SyncResult};
 use crate::services::github_client::{parse_github_url, GitHubClient};
+use crate::services::plugin_hooks::{self, PLUGIN_HOOKS_PATH, PLUGIN_MANIFEST_PATH};
+use crate::services::repo_archive;
 use crate::services::repo_parser::{
     detect_item_type, parse_readme_for_mcps, parse_readme_for_skills, parse_skill_file,
     parse_subagent_file, should_skip_file, ParsedItem,
 };
 use anyhow::Result;
 use chrono::Utc;
-use rusqlite::params;
+use rusqlite::{params, OptionalExtension};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 /// Default repositories to seed on first run
 pub const DEFAULT_REPOS: &[(&str, &str, &str, &str, &str)] = &[
@@ -52,7 +55,16 @@ pub fn seed_default_repos(db: &Database) -> Result<()> {
 
 /// Fetch items from a repository (async, no database access)
 pub async fn fetch_repo_items(repo: &Repo, token: Option<String>) -> Result<Vec<ParsedItem>> {
-    let client = GitHubClient::new(token);
+    fetch_repo_items_at(repo, token, None).await
+}
+
+/// Fetch items from a repository at `git_ref` (main, then master, when `None`)
+pub async fn fetch_repo_items_at(
+    repo: &Repo,
+    token: Option<String>,
+    git_ref: Option<String>,
+) -> Result<Vec<ParsedItem>> {
+    let client = GitHubClient::new(token).with_ref(git_ref);
 
     match repo.repo_type.as_str() {
         "file_based" => sync_file_based_repo(&client, repo).await,
@@ -111,33 +123,139 @@ async fn sync_file_based_repo(client: &GitHubClient, repo: &Repo) -> Result<Vec<
         .await
     {
         for (path, content) in files {
-            // Skip junk files like README.md, CONTRIBUTING.md, etc.
-            if should_skip_file(&path) {
-                continue;
-            }
+            // Build GitHub URL for the file
+            let source_url = format!(
+                "https://github.com/{}/{}/blob/main/{}",
+                repo.owner, repo.repo, path
+            );
+            items.extend(parse_markdown_item(&path, &content, source_url));
+        }
+    }
 
-            let item_type = detect_item_type(&path, &content);
+    items.extend(fetch_plugin_hooks(client, repo).await);
+    items.extend(fetch_bundle_items(client, repo).await);
 
-            let parsed = match item_type.as_str() {
-                "subagent" => parse_subagent_file(&content, &path),
-                _ => parse_skill_file(&content, &path),
-            };
+    Ok(items)
+}
 
-            if let Some(mut item) = parsed {
-                item.item_type = item_type;
-                // Build GitHub URL for the file
-                item.source_url = Some(format!(
-                    "https://github.com/{}/{}/blob/main/{}",
-                    repo.owner, repo.repo, path
-                ));
-                items.push(item);
-            }
-        }
+/// Parse one markdown file as a skill or subagent, skipping junk files like
+/// README.md, CONTRIBUTING.md, etc.
+fn parse_markdown_item(path: &str, content: &str, source_url: String) -> Option<ParsedItem> {
+    if should_skip_file(path) {
+        return None;
+    }
+
+    let item_type = detect_item_type(path, content);
+
+    let parsed = match item_type.as_str() {
+        "subagent" => parse_subagent_file(content, path),
+        _ => parse_skill_file(content, path),
+    };
+
+    parsed.map(|mut item| {
+        item.item_type = item_type;
+        item.source_url = Some(source_url);
+        item
+    })
+}
+
+/// Skills and agents packed in the `.zip` / `.tar.gz` bundles listed by the
+/// repo's plugin manifest. Bundles that are missing, too big or corrupt are
+/// skipped.
+async fn fetch_bundle_items(client: &GitHubClient, repo: &Repo) -> Vec<ParsedItem> {
+    let Ok(manifest) = client
+        .get_file(&repo.owner, &repo.repo, PLUGIN_MANIFEST_PATH)
+        .await
+    else {
+        return Vec::new();
+    };
+
+    let mut items = Vec::new();
+    for bundle_path in repo_archive::bundle_paths_from_manifest(&manifest) {
+        let Ok(bytes) = client
+            .get_file_bytes(
+                &repo.owner,
+                &repo.repo,
+                &bundle_path,
+                repo_archive::MAX_BUNDLE_BYTES,
+            )
+            .await
+        else {
+            continue;
+        };
+        items.extend(bundle_items(repo, &bundle_path, &bytes).unwrap_or_default());
     }
+    items
+}
 
+/// Turn the markdown files inside one bundle into marketplace items. Items
+/// link to the bundle itself and keep `<bundle>/<entry>` as their file path.
+pub(crate) fn bundle_items(
+    repo: &Repo,
+    bundle_path: &str,
+    bytes: &[u8],
+) -> Result<Vec<ParsedItem>> {
+    let source_url = format!(
+        "https://github.com/{}/{}/blob/main/{}",
+        repo.owner, repo.repo, bundle_path
+    );
+    let items = repo_archive::extract_markdown_files(bundle_path, bytes)?
+        .into_iter()
+        .filter_map(|(entry_path, content)| {
+            let mut item = parse_markdown_item(&entry_path, &content, source_url.clone())?;
+            item.file_path = Some(format!("{}/{}", bundle_path, entry_path));
+            Some(item)
+        })
+        .collect();
     Ok(items)
 }
 
+/// Hooks of a repo laid out as a Claude Code plugin (`hooks/hooks.json`),
+/// one item per hook. Repos without the file yield nothing.
+async fn fetch_plugin_hooks(client: &GitHubClient, repo: &Repo) -> Vec<ParsedItem> {
+    let Ok(hooks_json) = client
+        .get_file(&repo.owner, &repo.repo, PLUGIN_HOOKS_PATH)
+        .await
+    else {
+        return Vec::new();
+    };
+    let plugin_name = match client
+        .get_file(&repo.owner, &repo.repo, PLUGIN_MANIFEST_PATH)
+        .await
+    {
+        Ok(manifest) => plugin_hooks::plugin_name_from_manifest(&manifest),
+        Err(_) => None,
+    }
+    .unwrap_or_else(|| repo.repo.clone());
+
+    plugin_hook_items(repo, &plugin_name, &hooks_json)
+}
+
+/// Turn a plugin hooks file into marketplace items
+pub(crate) fn plugin_hook_items(
+    repo: &Repo,
+    plugin_name: &str,
+    hooks_json: &str,
+) -> Vec<ParsedItem> {
+    let source_url = format!(
+        "https://github.com/{}/{}/blob/main/{}",
+        repo.owner, repo.repo, PLUGIN_HOOKS_PATH
+    );
+    plugin_hooks::split_plugin_hooks(plugin_name, hooks_json)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|entry| ParsedItem {
+            name: entry.name,
+            description: entry.description,
+            source_url: Some(source_url.clone()),
+            raw_content: Some(entry.bundle),
+            file_path: Some(PLUGIN_HOOKS_PATH.to_string()),
+            item_type: "hook".to_string(),
+            metadata: None,
+        })
+        .collect()
+}
+
 /// Sync a README-based repository (parses README for links)
 async fn sync_readme_based_repo(client: &GitHubClient, repo: &Repo) -> Result<Vec<ParsedItem>> {
     let readme = client.get_readme(&repo.owner, &repo.repo).await?;
@@ -170,6 +288,85 @@ async fn sync_readme_based_repo(client: &GitHubClient, repo: &Repo) -> Result<Ve
     Ok(items)
 }
 
+/// Summarize fetched items for preview_repo: counts, sizes and anything
+/// that would surprise the user after syncing
+pub fn build_repo_preview(
+    repo: &Repo,
+    git_ref: Option<&str>,
+    items: Vec<ParsedItem>,
+    already_added: bool,
+) -> RepoPreview {
+    let mut counts = BTreeMap::new();
+    let mut seen = HashSet::new();
+    let mut warnings = Vec::new();
+    let mut total_bytes = 0;
+    let mut unfetched = 0;
+
+    let blob_main = format!("https://github.com/{}/{}/blob/main/", repo.owner, repo.repo);
+    let preview_items: Vec<RepoPreviewItem> = items
+        .into_iter()
+        .map(|item| {
+            *counts.entry(item.item_type.clone()).or_insert(0) += 1;
+            if !seen.insert((item.item_type.clone(), item.name.clone())) {
+                warnings.push(format!(
+                    "{} '{}' appears more than once",
+                    item.item_type, item.name
+                ));
+            }
+            let size = item.raw_content.as_ref().map(|c| c.len() as i64);
+            match size {
+                Some(size) => total_bytes += size,
+                None => unfetched += 1,
+            }
+            // Fetchers link to main; point at the previewed ref instead
+            let source_url = match (item.source_url, git_ref) {
+                (Some(url), Some(git_ref)) if url.starts_with(&blob_main) => Some(format!(
+                    "https://github.com/{}/{}/blob/{}/{}",
+                    repo.owner,
+                    repo.repo,
+                    git_ref,
+                    &url[blob_main.len()..]
+                )),
+                (url, _) => url,
+            };
+            RepoPreviewItem {
+                item_type: item.item_type,
+                name: item.name,
+                description: item.description,
+                file_path: item.file_path,
+                source_url,
+                size,
+            }
+        })
+        .collect();
+
+    if preview_items.is_empty() {
+        warnings.push(format!(
+            "No items found for repo type '{}' and content type '{}'",
+            repo.repo_type, repo.content_type
+        ));
+    }
+    if unfetched > 0 {
+        warnings.push(format!(
+            "{} item(s) link to external content that is only fetched on import",
+            unfetched
+        ));
+    }
+
+    RepoPreview {
+        name: repo.name.clone(),
+        github_url: repo.github_url.clone(),
+        git_ref: git_ref.map(str::to_string),
+        repo_type: repo.repo_type.clone(),
+        content_type: repo.content_type.clone(),
+        already_added,
+        counts,
+        total_bytes,
+        items: preview_items,
+        warnings,
+    }
+}
+
 /// Update repository items in the database
 fn update_repo_items(db: &Database, repo_id: i64, items: &[ParsedItem]) -> Result<SyncResult> {
     // Don't delete existing items if we got nothing new (likely a fetch error)
@@ -258,7 +455,7 @@ pub fn get_repo_items(db: &Database, repo_id: i64) -> Result<Vec<RepoItem>> {
            FROM repo_items WHERE repo_id = ? ORDER BY name ASC"#,
     )?;
 
-    let items = stmt
+    let mut items: Vec<RepoItem> = stmt
         .query_map(params![repo_id], |row| {
             Ok(RepoItem {
                 id: row.get(0)?,
@@ -275,11 +472,14 @@ pub fn get_repo_items(db: &Database, repo_id: i64) -> Result<Vec<RepoItem>> {
                 imported_item_id: row.get(11)?,
                 created_at: row.get(12)?,
                 updated_at: row.get(13)?,
+                installed: false,
+                update_available: false,
             })
         })?
         .filter_map(|r| r.ok())
         .collect();
 
+    annotate_install_status(db, &mut items)?;
     Ok(items)
 }
 
@@ -297,7 +497,7 @@ pub fn get_all_repo_items(db: &Database, item_type: Option<String>) -> Result<Ve
 
     let mut stmt = db.conn().prepare(sql)?;
 
-    let items: Vec<RepoItem> = if let Some(ref t) = item_type {
+    let mut items: Vec<RepoItem> = if let Some(ref t) = item_type {
         stmt.query_map(params![t], |row| {
             Ok(RepoItem {
                 id: row.get(0)?,
@@ -314,6 +514,8 @@ pub fn get_all_repo_items(db: &Database, item_type: Option<String>) -> Result<Ve
                 imported_item_id: row.get(11)?,
                 created_at: row.get(12)?,
                 updated_at: row.get(13)?,
+                installed: false,
+                update_available: false,
             })
         })?
         .filter_map(|r| r.ok())
@@ -335,15 +537,165 @@ pub fn get_all_repo_items(db: &Database, item_type: Option<String>) -> Result<Ve
                 imported_item_id: row.get(11)?,
                 created_at: row.get(12)?,
                 updated_at: row.get(13)?,
+                installed: false,
+                update_available: false,
             })
         })?
         .filter_map(|r| r.ok())
         .collect()
     };
 
+    annotate_install_status(db, &mut items)?;
     Ok(items)
 }
 
+/// The library entity a marketplace item was imported as
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepoImport {
+    /// "mcp", "skill", "command" or "subagent"
+    pub entity_type: String,
+    pub entity_id: i64,
+    pub content_hash: String,
+}
+
+/// Stable SHA-256 hash of item content. Stored with each import, so it must
+/// not change between releases (unlike `DefaultHasher`).
+pub fn content_hash(content: &str) -> String {
+    use sha2::{Digest, Sha256};
+
+    Sha256::digest(content.trim().as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// The library table holding entities of `entity_type`
+pub fn entity_table(entity_type: &str) -> Option<&'static str> {
+    match entity_type {
+        "mcp" => Some("mcps"),
+        "skill" => Some("skills"),
+        "command" => Some("commands"),
+        "subagent" => Some("subagents"),
+        "hook" => Some("hooks"),
+        _ => None,
+    }
+}
+
+/// Record that `item` was imported (or updated) as `entity_type`/`entity_id`
+/// from `content`
+pub fn record_import(
+    db: &Database,
+    item: &RepoItem,
+    entity_type: &str,
+    entity_id: i64,
+    content: &str,
+) -> Result<()> {
+    db.conn().execute(
+        r#"INSERT INTO repo_imports (repo_id, item_type, name, entity_type, entity_id, content_hash)
+           VALUES (?, ?, ?, ?, ?, ?)
+           ON CONFLICT(repo_id, item_type, name) DO UPDATE SET
+               entity_type = excluded.entity_type,
+               entity_id = excluded.entity_id,
+               content_hash = excluded.content_hash,
+               imported_at = CURRENT_TIMESTAMP"#,
+        params![
+            item.repo_id,
+            item.item_type,
+            item.name,
+            entity_type,
+            entity_id,
+            content_hash(content)
+        ],
+    )?;
+    Ok(())
+}
+
+/// The entity `item` was imported as, if it is still in the library
+pub fn get_import(db: &Database, item: &RepoItem) -> Result<Option<RepoImport>> {
+    let import = db
+        .conn()
+        .query_row(
+            "SELECT entity_type, entity_id, content_hash FROM repo_imports
+             WHERE repo_id = ? AND item_type = ? AND name = ?",
+            params![item.repo_id, item.item_type, item.name],
+            |row| {
+                Ok(RepoImport {
+                    entity_type: row.get(0)?,
+                    entity_id: row.get(1)?,
+                    content_hash: row.get(2)?,
+                })
+            },
+        )
+        .optional()?;
+
+    let Some(import) = import else {
+        return Ok(None);
+    };
+    let Some(table) = entity_table(&import.entity_type) else {
+        return Ok(None);
+    };
+    let exists: bool = db.conn().query_row(
+        &format!("SELECT COUNT(*) > 0 FROM {} WHERE id = ?", table),
+        params![import.entity_id],
+        |row| row.get(0),
+    )?;
+    Ok(exists.then_some(import))
+}
+
+/// Fill in `installed`/`update_available` from repo_imports. Items whose
+/// content is only fetched on import (no raw_content) never report updates.
+fn annotate_install_status(db: &Database, items: &mut [RepoItem]) -> Result<()> {
+    let imports = load_imports(db)?;
+    for item in items.iter_mut() {
+        let key = (item.repo_id, item.item_type.clone(), item.name.clone());
+        let Some(import) = imports.get(&key) else {
+            continue;
+        };
+        item.installed = true;
+        item.is_imported = true;
+        item.imported_item_id = Some(import.entity_id);
+        item.update_available = item
+            .raw_content
+            .as_deref()
+            .filter(|c| !c.trim().is_empty())
+            .is_some_and(|c| content_hash(c) != import.content_hash);
+    }
+    Ok(())
+}
+
+/// Every import whose entity is still in the library, keyed by repo id,
+/// item type and name. One query per entity kind.
+fn load_imports(db: &Database) -> Result<HashMap<(i64, String, String), RepoImport>> {
+    let mut imports = HashMap::new();
+    for entity_type in ["mcp", "skill", "command", "subagent", "hook"] {
+        let Some(table) = entity_table(entity_type) else {
+            continue;
+        };
+        let mut stmt = db.conn().prepare(&format!(
+            "SELECT ri.repo_id, ri.item_type, ri.name, ri.entity_id, ri.content_hash
+             FROM repo_imports ri
+             JOIN {} e ON e.id = ri.entity_id
+             WHERE ri.entity_type = ?",
+            table
+        ))?;
+        let rows = stmt.query_map([entity_type], |row| {
+            Ok((
+                (row.get(0)?, row.get(1)?, row.get(2)?),
+                RepoImport {
+                    entity_type: entity_type.to_string(),
+                    entity_id: row.get(3)?,
+                    content_hash: row.get(4)?,
+                },
+            ))
+        })?;
+        for row in rows {
+            let (key, import) = row?;
+            imports.insert(key, import);
+        }
+    }
+    Ok(imports)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1019,4 +1371,95 @@ mod tests {
         let all = get_repo_items(&db, repo_id).unwrap();
         assert_eq!(all.len(), 3);
     }
+
+    #[test]
+    fn test_bundle_items_from_zip() {
+        use std::io::Write;
+
+        let db = Database::in_memory().unwrap();
+        create_test_repo(&db);
+        let repo = get_all_repos(&db).unwrap().remove(0);
+
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, body) in [
+            (
+                "skills/review.md",
+                "---\ndescription: Review a PR\n---\nReview the diff",
+            ),
+            (
+                "agents/helper.md",
+                "---\nname: helper\ndescription: Helps\nmodel: sonnet\n---\nHelp out",
+            ),
+            ("README.md", "# Bundle"),
+        ] {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(body.as_bytes()).unwrap();
+        }
+        let bytes = zip.finish().unwrap().into_inner();
+
+        let mut items = bundle_items(&repo, "dist/kit.zip", &bytes).unwrap();
+        items.sort_by(|a, b| a.item_type.cmp(&b.item_type));
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].item_type, "skill");
+        assert_eq!(
+            items[0].file_path.as_deref(),
+            Some("dist/kit.zip/skills/review.md")
+        );
+        assert_eq!(items[1].item_type, "subagent");
+        assert!(items[1]
+            .source_url
+            .as_deref()
+            .unwrap()
+            .ends_with("/blob/main/dist/kit.zip"));
+
+        assert!(bundle_items(&repo, "dist/kit.zip", b"garbage").is_err());
+    }
+
+    #[test]
+    fn test_build_repo_preview() {
+        let db = Database::in_memory().unwrap();
+        create_test_repo(&db);
+        let repo = get_all_repos(&db).unwrap().remove(0);
+        let item = |item_type: &str, name: &str, raw: Option<&str>| ParsedItem {
+            name: name.to_string(),
+            description: None,
+            item_type: item_type.to_string(),
+            source_url: Some(format!(
+                "https://github.com/{}/{}/blob/main/{}.md",
+                repo.owner, repo.repo, name
+            )),
+            raw_content: raw.map(str::to_string),
+            file_path: Some(format!("{}.md", name)),
+            metadata: None,
+        };
+
+        let preview = build_repo_preview(
+            &repo,
+            Some("v2"),
+            vec![
+                item("skill", "review", Some("Review it")),
+                item("skill", "review", Some("Again")),
+                item("subagent", "helper", Some("Help")),
+                item("mcp", "linked", None),
+            ],
+            true,
+        );
+        assert!(preview.already_added);
+        assert_eq!(preview.counts.get("skill"), Some(&2));
+        assert_eq!(preview.counts.get("subagent"), Some(&1));
+        assert_eq!(preview.total_bytes, 18);
+        assert_eq!(preview.items[3].size, None);
+        assert!(preview.items[0]
+            .source_url
+            .as_deref()
+            .unwrap()
+            .ends_with("/blob/v2/review.md"));
+        assert_eq!(preview.warnings.len(), 2);
+        assert!(preview.warnings[0].contains("'review' appears more than once"));
+        assert!(preview.warnings[1].contains("1 item(s)"));
+
+        let empty = build_repo_preview(&repo, None, Vec::new(), false);
+        assert!(empty.warnings[0].starts_with("No items found"));
+    }
 }
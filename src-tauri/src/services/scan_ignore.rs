@@ -0,0 +1,206 @@
+use std::path::{Path, PathBuf};
+use walkdir::{DirEntry, WalkDir};
+
+/// Ignore file read from a marketplace or project root
+pub const IGNORE_FILE_NAME: &str = ".cctmignore";
+
+/// One line of an ignore file
+#[derive(Debug, Clone)]
+struct IgnorePattern {
+    glob: String,
+    /// Leading `!`: re-include a path an earlier pattern ignored
+    negated: bool,
+    /// Trailing `/`: only matches directories
+    dir_only: bool,
+    /// Contains a `/`: matched against the path from the root rather than
+    /// any single name
+    anchored: bool,
+}
+
+/// Gitignore-style patterns from a root's `.cctmignore`. Supports `*`, `?`,
+/// `**`, `#` comments, `!` negation and trailing `/` for directories.
+#[derive(Debug, Clone, Default)]
+pub struct ScanIgnore {
+    root: PathBuf,
+    patterns: Vec<IgnorePattern>,
+}
+
+impl ScanIgnore {
+    /// Load `<root>/.cctmignore`. A missing or unreadable file ignores nothing.
+    pub fn load(root: &Path) -> Self {
+        let file = root.join(IGNORE_FILE_NAME);
+        let content = match std::fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(e) => {
+                if file.exists() {
+                    log::warn!("Failed to read {}: {}", file.display(), e);
+                }
+                String::new()
+            }
+        };
+        Self::parse(root, &content)
+    }
+
+    pub fn parse(root: &Path, content: &str) -> Self {
+        let patterns = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let (negated, line) = match line.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                let (dir_only, line) = match line.strip_suffix('/') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                let anchored = line.contains('/');
+                let glob = line.trim_start_matches('/').to_string();
+                (!glob.is_empty()).then_some(IgnorePattern {
+                    glob,
+                    negated,
+                    dir_only,
+                    anchored,
+                })
+            })
+            .collect();
+        Self {
+            root: root.to_path_buf(),
+            patterns,
+        }
+    }
+
+    /// Whether `path` (under the root) is excluded. Paths outside the root are
+    /// never ignored. The last matching pattern wins, as in `.gitignore`.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return false;
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        if relative.is_empty() {
+            return false;
+        }
+        let name = relative.rsplit('/').next().unwrap_or(&relative);
+
+        let mut ignored = false;
+        for pattern in &self.patterns {
+            if pattern.dir_only && !is_dir {
+                continue;
+            }
+            let target = if pattern.anchored { &relative } else { name };
+            if glob_matches(&pattern.glob, target) {
+                ignored = !pattern.negated;
+            }
+        }
+        ignored
+    }
+
+    /// Whether `path` is ignored itself or sits inside an ignored directory
+    pub fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        path.ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(&self.root))
+            .any(|dir| self.is_ignored(dir, true))
+            || self.is_ignored(path, is_dir)
+    }
+}
+
+/// Walk `root` up to `max_depth`, not descending into anything its
+/// `.cctmignore` excludes
+pub fn walk(root: &Path, max_depth: usize) -> impl Iterator<Item = DirEntry> {
+    let ignore = ScanIgnore::load(root);
+    WalkDir::new(root)
+        .max_depth(max_depth)
+        .into_iter()
+        .filter_entry(move |entry| !ignore.is_ignored(entry.path(), entry.file_type().is_dir()))
+        .filter_map(|e| e.ok())
+}
+
+/// Match a glob against a `/`-separated path. `*` and `?` stay within one
+/// segment; `**` spans any number of segments.
+fn glob_matches(glob: &str, path: &str) -> bool {
+    fn matches(glob: &[u8], path: &[u8]) -> bool {
+        match glob {
+            [] => path.is_empty(),
+            [b'*', b'*', rest @ ..] => {
+                let rest = rest.strip_prefix(b"/").unwrap_or(rest);
+                (0..=path.len()).any(|i| {
+                    (i == 0 || path[i - 1] == b'/' || rest.is_empty()) && matches(rest, &path[i..])
+                })
+            }
+            [b'*', rest @ ..] => (0..=path.len())
+                .take_while(|&i| i == 0 || path[i - 1] != b'/')
+                .any(|i| matches(rest, &path[i..])),
+            [b'?', rest @ ..] => {
+                matches!(path.first(), Some(&c) if c != b'/') && matches(rest, &path[1..])
+            }
+            [c, rest @ ..] => path.first() == Some(c) && matches(rest, &path[1..]),
+        }
+    }
+    matches(glob.as_bytes(), path.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("node_modules", "node_modules"));
+        assert!(glob_matches("*.png", "logo.png"));
+        assert!(!glob_matches("*.png", "assets/logo.png"));
+        assert!(glob_matches("assets/**", "assets/img/logo.png"));
+        assert!(glob_matches("**/vendor", "vendor"));
+        assert!(glob_matches("**/vendor", "a/b/vendor"));
+        assert!(glob_matches("plugins/*/dist", "plugins/foo/dist"));
+        assert!(!glob_matches("plugins/*/dist", "plugins/foo/bar/dist"));
+        assert!(glob_matches("build-?", "build-1"));
+    }
+
+    #[test]
+    fn test_is_ignored_by_name_path_and_negation() {
+        let root = Path::new("/repo");
+        let ignore = ScanIgnore::parse(root, "# deps\nnode_modules/\n/assets\n*.log\n!keep.log\n");
+
+        assert!(ignore.is_ignored(&root.join("node_modules"), true));
+        assert!(ignore.is_ignored(&root.join("plugins/a/node_modules"), true));
+        // Directory-only pattern doesn't match a file of the same name
+        assert!(!ignore.is_ignored(&root.join("node_modules"), false));
+        assert!(ignore.is_ignored(&root.join("assets"), true));
+        assert!(!ignore.is_ignored(&root.join("plugins/assets"), true));
+        assert!(ignore.is_ignored(&root.join("debug.log"), false));
+        assert!(!ignore.is_ignored(&root.join("keep.log"), false));
+        assert!(!ignore.is_ignored(root, true));
+        assert!(!ignore.is_ignored(Path::new("/elsewhere/node_modules"), true));
+    }
+
+    #[test]
+    fn test_is_excluded_checks_parent_dirs() {
+        let root = Path::new("/repo");
+        let ignore = ScanIgnore::parse(root, ".claude/\n");
+
+        assert!(!ignore.is_ignored(&root.join(".claude/commands"), true));
+        assert!(ignore.is_excluded(&root.join(".claude/commands"), true));
+        assert!(ignore.is_excluded(&root.join(".claude/settings.json"), false));
+        assert!(!ignore.is_excluded(&root.join("src/commands"), true));
+    }
+
+    #[test]
+    fn test_walk_skips_ignored_subtrees() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("plugin")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules/dep")).unwrap();
+        std::fs::write(root.join("plugin/.mcp.json"), "{}").unwrap();
+        std::fs::write(root.join("node_modules/dep/.mcp.json"), "{}").unwrap();
+        std::fs::write(root.join(IGNORE_FILE_NAME), "node_modules/\n").unwrap();
+
+        let found: Vec<_> = walk(root, 6)
+            .filter(|e| e.file_name() == ".mcp.json")
+            .map(|e| e.into_path())
+            .collect();
+
+        assert_eq!(found, vec![root.join("plugin/.mcp.json")]);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::commands::settings::get_scan_editors_from_db;
 use crate::db::Database;
 use crate::services::claude_json;
 use crate::services::codex_config;
@@ -5,24 +6,109 @@ use crate::services::config_parser;
 use crate::services::copilot_config;
 use crate::services::cursor_config;
 use crate::services::gemini_config;
+use crate::services::mcp_conflicts::{self, McpDefinition};
 use crate::services::opencode_config;
+use crate::services::reconcile::{self, PruneSummary};
+use crate::services::repo_sync::content_hash;
+use crate::services::scan_ignore::{self, ScanIgnore};
+use crate::services::windsurf_config;
 use crate::utils::codex_paths::get_codex_paths;
 use crate::utils::copilot_paths::get_copilot_paths;
 use crate::utils::cursor_paths::get_cursor_paths;
 use crate::utils::gemini_paths::get_gemini_paths;
 use crate::utils::opencode_paths::get_opencode_paths;
-use crate::utils::paths::{get_claude_paths, normalize_path};
+use crate::utils::paths::{get_claude_paths, normalize_path, to_host_path};
+use crate::utils::windsurf_paths::get_windsurf_paths;
 use anyhow::Result;
 use rusqlite::params;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path;
-use tauri::Manager;
-use walkdir::WalkDir;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{Emitter, Manager};
 
 /// Source literal used for rows populated by the auto-scan path (as opposed
 /// to UI-created rows which carry `source = 'manual'`).
 pub(crate) const SOURCE_AUTO_DETECTED: &str = "auto-detected";
 
+/// Event emitted after the startup scan removes stale auto-detected items
+pub const SCAN_PRUNED_EVENT: &str = "scan-pruned";
+
+/// Whether the row `id` in `table` should be refreshed from a definition
+/// hashing to `hash`. Only auto-detected rows are ever refreshed so library
+/// items created in the app aren't clobbered; rows scanned before hashes were
+/// stored just get `hash` recorded.
+fn needs_refresh(db: &Database, table: &str, id: i64, hash: &str) -> Result<bool> {
+    let (source, stored): (Option<String>, Option<String>) = db.conn().query_row(
+        &format!("SELECT source, source_hash FROM {} WHERE id = ?", table),
+        [id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    if source.as_deref() != Some(SOURCE_AUTO_DETECTED) {
+        return Ok(false);
+    }
+    match stored {
+        Some(stored) => Ok(stored != hash),
+        None => {
+            db.conn().execute(
+                &format!("UPDATE {} SET source_hash = ? WHERE id = ?", table),
+                params![hash, id],
+            )?;
+            Ok(false)
+        }
+    }
+}
+
+/// Hash of an MCP definition as the scanner stores it
+fn mcp_source_hash(mcp: &McpDefinition) -> String {
+    content_hash(&serde_json::to_string(mcp).unwrap())
+}
+
+/// Update an auto-detected MCP whose definition changed on disk
+fn refresh_mcp(db: &Database, id: i64, mcp: &McpDefinition) -> Result<()> {
+    let hash = mcp_source_hash(mcp);
+    if needs_refresh(db, "mcps", id, &hash)? {
+        db.conn().execute(
+            "UPDATE mcps SET type = ?, command = ?, args = ?, url = ?, headers = ?, env = ?,
+                    source_hash = ?, updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?",
+            params![
+                mcp.mcp_type,
+                mcp.command,
+                mcp.args_json(),
+                mcp.url,
+                mcp.headers_json(),
+                mcp.env_json(),
+                hash,
+                id
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// Apply a definition scanned from `source_path` to the library MCP it
+/// matched by name: refresh it when it came from the same source, otherwise
+/// note it as a conflict
+fn note_or_refresh_mcp(
+    db: &Database,
+    id: i64,
+    name: &str,
+    source_path: &str,
+    scanned: &McpDefinition,
+) -> Result<()> {
+    // Update source_path if not already set
+    db.conn().execute(
+        "UPDATE mcps SET source_path = ? WHERE id = ? AND (source_path IS NULL OR source_path = '')",
+        params![source_path, id],
+    )?;
+    if mcp_conflicts::is_same_source(db, id, source_path)? {
+        refresh_mcp(db, id, scanned)
+    } else {
+        mcp_conflicts::note_scanned_definition(db, id, name, source_path, scanned)
+    }
+}
+
 /// Walk a directory for `*.md` files, invoking `process(&Path)` for each.
 /// Returns the count of files for which `process` returned `Ok(true)`.
 /// A missing directory is not an error (returns 0). Per-file errors are
@@ -55,94 +141,211 @@ where
     Ok(count)
 }
 
-pub async fn run_startup_scan(app: &tauri::AppHandle) -> Result<()> {
-    let db = app.state::<std::sync::Arc<std::sync::Mutex<Database>>>();
-    let db = db.lock().map_err(|e| anyhow::anyhow!("{}", e))?;
-
-    // First scan global MCPs from claude.json
-    let global_mcp_count = scan_global_mcps_from_claude_json(&db)?;
-    log::info!("Imported {} global MCPs from claude.json", global_mcp_count);
+/// Event emitted as each startup scan phase begins
+pub const SCAN_PROGRESS_EVENT: &str = "scan-progress";
+/// Event emitted once the startup scan finishes or is cancelled
+pub const SCAN_COMPLETE_EVENT: &str = "scan-complete";
 
-    // Then scan claude.json for projects and their MCPs
-    let claude_json_count = scan_claude_json(&db)?;
-    log::info!(
-        "Imported {} project MCPs from claude.json",
-        claude_json_count
-    );
-
-    // Then scan plugins/marketplaces for additional MCPs
-    let plugin_count = scan_plugins(&db)?;
-    log::info!("Found {} MCPs from plugins", plugin_count);
-
-    // Scan global commands from ~/.claude/commands/
-    let command_count = scan_global_commands(&db)?;
-    log::info!("Found {} commands from ~/.claude/commands/", command_count);
-
-    // Scan global skills from ~/.claude/skills/
-    let skill_count = scan_global_skills(&db)?;
-    log::info!("Found {} skills from ~/.claude/skills/", skill_count);
-
-    // Scan global agents from ~/.claude/agents/
-    let agent_count = scan_global_agents(&db)?;
-    log::info!("Found {} agents from ~/.claude/agents/", agent_count);
-
-    // Scan global hooks from ~/.claude/settings.json
-    let hook_count = scan_global_hooks(&db)?;
-    log::info!("Found {} hooks from ~/.claude/settings.json", hook_count);
-
-    // Scan global rules from ~/.claude/rules/
-    let rule_count = scan_global_rules(&db)?;
-    log::info!("Found {} rules from ~/.claude/rules/", rule_count);
+/// Set by `cancel_startup_scan`; checked between phases
+static SCAN_CANCELLED: AtomicBool = AtomicBool::new(false);
 
-    // ============================================================================
-    // OpenCode Scanning
-    // ============================================================================
-
-    // Scan OpenCode global config for MCPs
-    let opencode_mcp_count = scan_opencode_config(&db)?;
-    log::info!("Found {} MCPs from OpenCode config", opencode_mcp_count);
+/// Ask a running startup scan to stop before its next phase
+pub fn cancel_startup_scan() {
+    SCAN_CANCELLED.store(true, Ordering::SeqCst);
+}
 
-    // Scan OpenCode global commands from ~/.config/opencode/command/
-    let opencode_command_count = scan_opencode_global_commands(&db)?;
-    log::info!("Found {} commands from OpenCode", opencode_command_count);
+/// Payload of [`SCAN_PROGRESS_EVENT`]
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanProgress {
+    pub phase: String,
+    /// Phases finished before this one
+    pub done: usize,
+    pub total: usize,
+}
 
-    // Scan OpenCode global agents from ~/.config/opencode/agent/
-    let opencode_agent_count = scan_opencode_global_agents(&db)?;
-    log::info!("Found {} agents from OpenCode", opencode_agent_count);
+/// Payload of [`SCAN_COMPLETE_EVENT`]
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanSummary {
+    /// Items found by each phase that ran
+    pub counts: BTreeMap<String, usize>,
+    pub total_found: usize,
+    pub pruned: PruneSummary,
+    pub cancelled: bool,
+}
 
-    // ============================================================================
-    // Codex CLI Scanning
-    // ============================================================================
+pub async fn run_startup_scan(app: &tauri::AppHandle) -> Result<()> {
+    SCAN_CANCELLED.store(false, Ordering::SeqCst);
+
+    let summary = {
+        let db = app.state::<std::sync::Arc<std::sync::Mutex<Database>>>();
+        let db = db.lock().map_err(|e| anyhow::anyhow!("{}", e))?;
+        let editors = get_scan_editors_from_db(&db);
+        run_scan(&db, editors.as_deref(), |progress| {
+            let _ = app.emit(SCAN_PROGRESS_EVENT, progress);
+        })?
+    };
 
-    // Scan Codex global config for MCPs
-    let codex_mcp_count = scan_codex_config(&db)?;
-    log::info!("Found {} MCPs from Codex config", codex_mcp_count);
+    if summary.pruned.total() > 0 {
+        log::info!(
+            "Pruned {} stale auto-detected items",
+            summary.pruned.total()
+        );
+        let _ = app.emit(SCAN_PRUNED_EVENT, &summary.pruned);
+    }
+    let _ = app.emit(SCAN_COMPLETE_EVENT, &summary);
+    Ok(())
+}
 
-    // ============================================================================
-    // GitHub Copilot CLI Scanning
-    // ============================================================================
+/// Whether `editor` is included by the scan filter (`None` scans everything)
+fn should_scan(editors: Option<&[String]>, editor: &str) -> bool {
+    let included = editors.is_none_or(|list| list.iter().any(|e| e == editor));
+    if !included {
+        log::info!("Skipping {} scan (not in scan editors)", editor);
+    }
+    included
+}
 
-    // Scan Copilot global config for MCPs
-    let copilot_mcp_count = scan_copilot_config(&db)?;
-    log::info!("Found {} MCPs from Copilot CLI config", copilot_mcp_count);
+/// One step of the startup scan
+struct ScanPhase {
+    /// Name reported in progress events
+    name: &'static str,
+    /// What the phase finds, for the log
+    label: &'static str,
+    run: fn(&Database) -> Result<usize>,
+}
 
-    // ============================================================================
-    // Cursor IDE Scanning
-    // ============================================================================
+/// The scan phases for the given editors, in the order they run
+fn scan_phases(editors: Option<&[String]>) -> Vec<ScanPhase> {
+    let phase = |name, label, run| ScanPhase { name, label, run };
+    let mut phases = Vec::new();
+
+    if should_scan(editors, "claude_code") {
+        phases.extend([
+            phase(
+                "claude_json_global",
+                "global MCPs from claude.json",
+                scan_global_mcps_from_claude_json,
+            ),
+            phase(
+                "claude_json_projects",
+                "project MCPs from claude.json",
+                scan_claude_json,
+            ),
+            phase("plugins", "MCPs from plugins", scan_plugins),
+            phase(
+                "commands",
+                "commands from ~/.claude/commands/",
+                scan_global_commands,
+            ),
+            phase(
+                "skills",
+                "skills from ~/.claude/skills/",
+                scan_global_skills,
+            ),
+            phase(
+                "agents",
+                "agents from ~/.claude/agents/",
+                scan_global_agents,
+            ),
+            phase(
+                "hooks",
+                "hooks from ~/.claude/settings.json",
+                scan_global_hooks,
+            ),
+            phase("rules", "rules from ~/.claude/rules/", scan_global_rules),
+        ]);
+    }
+    if should_scan(editors, "opencode") {
+        phases.extend([
+            phase(
+                "opencode_mcps",
+                "MCPs from OpenCode config",
+                scan_opencode_config,
+            ),
+            phase(
+                "opencode_commands",
+                "commands from OpenCode",
+                scan_opencode_global_commands,
+            ),
+            phase(
+                "opencode_agents",
+                "agents from OpenCode",
+                scan_opencode_global_agents,
+            ),
+        ]);
+    }
+    if should_scan(editors, "codex") {
+        phases.push(phase("codex", "MCPs from Codex config", scan_codex_config));
+    }
+    if should_scan(editors, "copilot") {
+        phases.push(phase(
+            "copilot",
+            "MCPs from Copilot CLI config",
+            scan_copilot_config,
+        ));
+    }
+    if should_scan(editors, "cursor") {
+        phases.push(phase(
+            "cursor",
+            "MCPs from Cursor config",
+            scan_cursor_config,
+        ));
+    }
+    if should_scan(editors, "gemini") {
+        phases.push(phase(
+            "gemini",
+            "MCPs from Gemini CLI config",
+            scan_gemini_config,
+        ));
+    }
+    if should_scan(editors, "windsurf") {
+        phases.push(phase(
+            "windsurf",
+            "MCPs from Windsurf config",
+            scan_windsurf_config,
+        ));
+    }
+
+    phases
+}
 
-    // Scan Cursor global config for MCPs
-    let cursor_mcp_count = scan_cursor_config(&db)?;
-    log::info!("Found {} MCPs from Cursor config", cursor_mcp_count);
+/// Import everything the given editors have configured globally, then prune
+/// auto-detected items whose source is gone. Stops between phases once
+/// [`cancel_startup_scan`] is called.
+fn run_scan(
+    db: &Database,
+    editors: Option<&[String]>,
+    mut on_progress: impl FnMut(&ScanProgress),
+) -> Result<ScanSummary> {
+    let phases = scan_phases(editors);
+    let mut summary = ScanSummary::default();
+
+    for (done, phase) in phases.iter().enumerate() {
+        if SCAN_CANCELLED.load(Ordering::SeqCst) {
+            log::info!("Startup scan cancelled before {}", phase.name);
+            summary.cancelled = true;
+            return Ok(summary);
+        }
+        on_progress(&ScanProgress {
+            phase: phase.name.to_string(),
+            done,
+            total: phases.len(),
+        });
 
-    // ============================================================================
-    // Gemini CLI Scanning
-    // ============================================================================
+        let count = (phase.run)(db)?;
+        log::info!("Found {} {}", count, phase.label);
+        summary.counts.insert(phase.name.to_string(), count);
+        summary.total_found += count;
+    }
 
-    // Scan Gemini global config for MCPs
-    let gemini_mcp_count = scan_gemini_config(&db)?;
-    log::info!("Found {} MCPs from Gemini CLI config", gemini_mcp_count);
+    // Auto-detected rows all come from Claude Code's config
+    if editors.is_none_or(|list| list.iter().any(|e| e == "claude_code")) {
+        summary.pruned = reconcile::prune_missing_auto_detected(db)?;
+    }
 
-    Ok(())
+    Ok(summary)
 }
 
 /// Scan global MCPs from claude.json (root mcpServers)
@@ -202,9 +405,61 @@ pub fn scan_global_mcps_from_claude_json(db: &Database) -> Result<usize> {
         count += 1;
     }
 
+    let present: HashSet<String> = all_mcps
+        .iter()
+        .filter(|m| m.project_path.is_none())
+        .map(|m| m.name.clone())
+        .collect();
+    for stale in find_stale_global_mcps(db, &present)? {
+        log::warn!(
+            "Global MCP '{}' is enabled in the library but missing from claude.json",
+            stale.name
+        );
+    }
+
     Ok(count)
 }
 
+/// An enabled global MCP that claude.json no longer lists, usually because it
+/// was removed with the `claude` CLI or by hand
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StaleGlobalMcp {
+    /// Row id in `global_mcps`
+    pub global_mcp_id: i64,
+    pub mcp_id: i64,
+    pub name: String,
+}
+
+/// Enabled `global_mcps` rows whose MCP isn't among `present` (the root
+/// `mcpServers` names in claude.json). Disabled rows are expected to be absent
+/// and never count as stale. Nothing is deleted.
+pub fn find_stale_global_mcps(
+    db: &Database,
+    present: &HashSet<String>,
+) -> Result<Vec<StaleGlobalMcp>> {
+    let mut stmt = db.conn().prepare(
+        "SELECT gm.id, gm.mcp_id, m.name
+         FROM global_mcps gm
+         JOIN mcps m ON gm.mcp_id = m.id
+         WHERE gm.is_enabled = 1
+         ORDER BY gm.display_order",
+    )?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(StaleGlobalMcp {
+                global_mcp_id: row.get(0)?,
+                mcp_id: row.get(1)?,
+                name: row.get(2)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(rows
+        .into_iter()
+        .filter(|row| !present.contains(&row.name))
+        .collect())
+}
+
 /// Scan claude.json for projects and their MCPs
 pub fn scan_claude_json(db: &Database) -> Result<usize> {
     let all_projects = match claude_json::get_all_projects_from_claude_json() {
@@ -219,20 +474,15 @@ pub fn scan_claude_json(db: &Database) -> Result<usize> {
     let mut seen_projects: HashSet<String> = HashSet::new();
 
     for (project_path, project_config) in all_projects {
-        // Normalize path for deduplication
-        let normalized_path = normalize_path(&project_path);
+        // Resolve for this host first so WSL and Windows spellings of the
+        // same project deduplicate, then normalize for comparison
+        let path_to_check = to_host_path(&project_path);
+        let normalized_path = normalize_path(&path_to_check);
         if seen_projects.contains(&normalized_path) {
             continue;
         }
         seen_projects.insert(normalized_path.clone());
 
-        // Check if project directory exists
-        let path_to_check = if project_path.starts_with("C:") || project_path.starts_with("c:") {
-            project_path.clone()
-        } else {
-            normalized_path.clone()
-        };
-
         if !Path::new(&path_to_check).exists() {
             log::debug!("Skipping non-existent project: {}", project_path);
             continue;
@@ -244,11 +494,12 @@ pub fn scan_claude_json(db: &Database) -> Result<usize> {
             .map(|n| n.to_string_lossy().to_string())
             .unwrap_or_else(|| project_path.clone());
 
-        let project_id = get_or_create_project(db, &project_name, &project_path)?;
+        let project_id = get_or_create_project(db, &project_name, &path_to_check)?;
 
         // Import each MCP (if any)
         for (mcp_name, mcp_server) in project_config.mcp_servers {
-            let is_disabled = project_config.disabled_mcp_servers.contains(&mcp_name);
+            let is_disabled =
+                mcp_server.disabled || project_config.disabled_mcp_servers.contains(&mcp_name);
 
             // Get or create the MCP in the library
             let mcp_id = get_or_create_mcp(
@@ -269,27 +520,31 @@ pub fn scan_claude_json(db: &Database) -> Result<usize> {
             mcp_count += 1;
         }
 
+        // Skip whatever the project's .cctmignore excludes
+        let ignore = ScanIgnore::load(Path::new(&path_to_check));
+        let wanted = |path: &Path, is_dir: bool| path.exists() && !ignore.is_excluded(path, is_dir);
+
         // Scan project-level commands from .claude/commands/
         let project_commands_dir = Path::new(&path_to_check).join(".claude").join("commands");
-        if project_commands_dir.exists() {
+        if wanted(&project_commands_dir, true) {
             scan_project_commands(db, project_id, &project_commands_dir)?;
         }
 
         // Scan project-level skills from .claude/skills/
         let project_skills_dir = Path::new(&path_to_check).join(".claude").join("skills");
-        if project_skills_dir.exists() {
+        if wanted(&project_skills_dir, true) {
             scan_project_skills(db, project_id, &project_skills_dir)?;
         }
 
         // Scan project-level agents from .claude/agents/
         let project_agents_dir = Path::new(&path_to_check).join(".claude").join("agents");
-        if project_agents_dir.exists() {
+        if wanted(&project_agents_dir, true) {
             scan_project_agents(db, project_id, &project_agents_dir)?;
         }
 
         // Scan project-level rules from .claude/rules/
         let project_rules_dir = Path::new(&path_to_check).join(".claude").join("rules");
-        if project_rules_dir.exists() {
+        if wanted(&project_rules_dir, true) {
             scan_project_rules(db, project_id, &project_rules_dir)?;
         }
 
@@ -297,13 +552,13 @@ pub fn scan_claude_json(db: &Database) -> Result<usize> {
         let project_settings_file = Path::new(&path_to_check)
             .join(".claude")
             .join("settings.json");
-        if project_settings_file.exists() {
+        if wanted(&project_settings_file, false) {
             scan_project_hooks(db, project_id, &project_settings_file)?;
         }
         let project_settings_local_file = Path::new(&path_to_check)
             .join(".claude")
             .join("settings.local.json");
-        if project_settings_local_file.exists() {
+        if wanted(&project_settings_local_file, false) {
             scan_project_hooks(db, project_id, &project_settings_local_file)?;
         }
     }
@@ -347,10 +602,12 @@ fn get_or_create_mcp(
     command: Option<&str>,
     args: Option<&Vec<String>>,
     url: Option<&str>,
-    headers: Option<&std::collections::HashMap<String, String>>,
-    env: Option<&std::collections::HashMap<String, String>>,
+    headers: Option<&HashMap<String, String>>,
+    env: Option<&HashMap<String, String>>,
     source_path: &str,
 ) -> Result<i64> {
+    let scanned = McpDefinition::new(mcp_type, command, args, url, headers, env);
+
     // Try to find existing MCP by name
     let existing_id: Option<i64> = db
         .conn()
@@ -360,31 +617,24 @@ fn get_or_create_mcp(
         .ok();
 
     if let Some(id) = existing_id {
-        // Update source_path if not already set
-        db.conn().execute(
-            "UPDATE mcps SET source_path = ? WHERE id = ? AND (source_path IS NULL OR source_path = '')",
-            params![source_path, id],
-        )?;
+        note_or_refresh_mcp(db, id, name, source_path, &scanned)?;
         return Ok(id);
     }
 
     // Create new MCP
-    let args_json = args.map(|a| serde_json::to_string(a).unwrap());
-    let headers_json = headers.map(|h| serde_json::to_string(h).unwrap());
-    let env_json = env.map(|e| serde_json::to_string(e).unwrap());
-
     db.conn().execute(
-        "INSERT INTO mcps (name, type, command, args, url, headers, env, source, source_path)
-         VALUES (?, ?, ?, ?, ?, ?, ?, 'auto-detected', ?)",
+        "INSERT INTO mcps (name, type, command, args, url, headers, env, source, source_path, source_hash)
+         VALUES (?, ?, ?, ?, ?, ?, ?, 'auto-detected', ?, ?)",
         params![
             name,
-            mcp_type,
-            command,
-            args_json,
-            url,
-            headers_json,
-            env_json,
-            source_path
+            scanned.mcp_type,
+            scanned.command,
+            scanned.args_json(),
+            scanned.url,
+            scanned.headers_json(),
+            scanned.env_json(),
+            source_path,
+            mcp_source_hash(&scanned)
         ],
     )?;
 
@@ -471,7 +721,7 @@ pub fn import_mcps_from_project_mcp_json(
             project_path,
         )?;
 
-        assign_mcp_to_project(db, project_id, mcp_id, true)?;
+        assign_mcp_to_project(db, project_id, mcp_id, !mcp.disabled)?;
         count += 1;
     }
 
@@ -489,16 +739,28 @@ pub fn scan_plugins(db: &Database) -> Result<usize> {
     let mut count = 0;
 
     if paths.marketplaces_dir.exists() {
-        for entry in WalkDir::new(&paths.marketplaces_dir)
-            .max_depth(6)
+        // Each marketplace is its own root for `.cctmignore`
+        let entries = std::fs::read_dir(&paths.marketplaces_dir)
             .into_iter()
+            .flatten()
             .filter_map(|e| e.ok())
-        {
+            .map(|e| e.path())
+            .filter(|p| p.is_dir())
+            .flat_map(|marketplace| scan_ignore::walk(&marketplace, 5));
+        for entry in entries {
             if entry.file_name() == ".mcp.json" {
                 match config_parser::parse_mcp_file(entry.path()) {
                     Ok(mcps) => {
                         for mcp in mcps {
                             let source_path = entry.path().to_string_lossy().to_string();
+                            let scanned = McpDefinition::new(
+                                &mcp.mcp_type,
+                                mcp.command.as_deref(),
+                                mcp.args.as_ref(),
+                                mcp.url.as_deref(),
+                                mcp.headers.as_ref(),
+                                mcp.env.as_ref(),
+                            );
 
                             // Check if already exists
                             let existing_id: Option<i64> = db
@@ -511,33 +773,21 @@ pub fn scan_plugins(db: &Database) -> Result<usize> {
                                 .ok();
 
                             if let Some(id) = existing_id {
-                                // Update source_path if not already set
-                                db.conn().execute(
-                                    "UPDATE mcps SET source_path = ? WHERE id = ? AND (source_path IS NULL OR source_path = '')",
-                                    params![&source_path, id],
-                                )?;
+                                note_or_refresh_mcp(db, id, &mcp.name, &source_path, &scanned)?;
                             } else {
-                                let args_json =
-                                    mcp.args.as_ref().map(|a| serde_json::to_string(a).unwrap());
-                                let headers_json = mcp
-                                    .headers
-                                    .as_ref()
-                                    .map(|h| serde_json::to_string(h).unwrap());
-                                let env_json =
-                                    mcp.env.as_ref().map(|e| serde_json::to_string(e).unwrap());
-
                                 let result = db.conn().execute(
-                                    "INSERT INTO mcps (name, type, command, args, url, headers, env, source, source_path)
-                                     VALUES (?, ?, ?, ?, ?, ?, ?, 'auto-detected', ?)",
+                                    "INSERT INTO mcps (name, type, command, args, url, headers, env, source, source_path, source_hash)
+                                     VALUES (?, ?, ?, ?, ?, ?, ?, 'auto-detected', ?, ?)",
                                     params![
                                         mcp.name,
-                                        mcp.mcp_type,
-                                        mcp.command,
-                                        args_json,
-                                        mcp.url,
-                                        headers_json,
-                                        env_json,
-                                        source_path
+                                        scanned.mcp_type,
+                                        scanned.command,
+                                        scanned.args_json(),
+                                        scanned.url,
+                                        scanned.headers_json(),
+                                        scanned.env_json(),
+                                        source_path,
+                                        mcp_source_hash(&scanned)
                                     ],
                                 );
 
@@ -612,20 +862,12 @@ pub fn scan_global_agents(db: &Database) -> Result<usize> {
         };
         let source_path = path.to_string_lossy().to_string();
 
-        let existing_id: Option<i64> = db
-            .conn()
-            .query_row(
-                "SELECT id FROM subagents WHERE name = ?",
-                [&agent.name],
-                |row| row.get(0),
-            )
-            .ok();
-
-        if let Some(id) = existing_id {
+        if let Some(id) = find_existing_agent(db, &agent, &source_path) {
             db.conn().execute(
                 "UPDATE subagents SET source_path = ? WHERE id = ? AND (source_path IS NULL OR source_path = '')",
                 params![&source_path, id],
             )?;
+            refresh_agent(db, id, &agent)?;
             return Ok(false);
         }
 
@@ -639,8 +881,8 @@ pub fn scan_global_agents(db: &Database) -> Result<usize> {
         let inserted = db
             .conn()
             .execute(
-                "INSERT INTO subagents (name, description, content, tools, model, permission_mode, skills, tags, source, source_path)
-                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                "INSERT INTO subagents (name, description, content, tools, model, permission_mode, skills, tags, source, source_path, file_name, source_hash)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
                 params![
                     agent.name,
                     agent.description,
@@ -651,7 +893,9 @@ pub fn scan_global_agents(db: &Database) -> Result<usize> {
                     skills_json,
                     tags_json,
                     SOURCE_AUTO_DETECTED,
-                    source_path
+                    source_path,
+                    agent.file_name_override(),
+                    agent.source_hash()
                 ],
             )
             .is_ok();
@@ -679,7 +923,11 @@ fn scan_project_rules(db: &Database, project_id: i64, rules_dir: &Path) -> Resul
 /// when a new row was inserted, `Ok(false)` when an existing row was
 /// source-path-backfilled. For project-scope scans (`project_id: Some`) a
 /// `project_rules` assignment row is also inserted via `INSERT OR IGNORE`.
-fn upsert_rule_from_file(db: &Database, path: &Path, project_id: Option<i64>) -> Result<bool> {
+pub(crate) fn upsert_rule_from_file(
+    db: &Database,
+    path: &Path,
+    project_id: Option<i64>,
+) -> Result<bool> {
     let Some(parsed) = parse_rule_file(path) else {
         return Ok(false);
     };
@@ -747,6 +995,17 @@ fn upsert_rule_from_file(db: &Database, path: &Path, project_id: Option<i64>) ->
     Ok(inserted)
 }
 
+/// Overwrite a rule row's file-backed fields with what was parsed from disk
+pub(crate) fn refresh_rule_row(db: &Database, id: i64, rule: &ParsedRule) -> Result<()> {
+    let paths_json = (!rule.paths.is_empty()).then(|| serde_json::to_string(&rule.paths).unwrap());
+    db.conn().execute(
+        "UPDATE rules SET description = ?, content = ?, paths = ?, updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?",
+        params![rule.description, rule.content, paths_json, id],
+    )?;
+    Ok(())
+}
+
 /// Parsed skill data from markdown file
 #[derive(Debug, PartialEq)]
 pub(crate) struct ParsedSkill {
@@ -772,7 +1031,10 @@ pub(crate) struct ParsedSkillFile {
 /// Parsed agent data from markdown file
 #[derive(Debug, PartialEq)]
 pub(crate) struct ParsedAgent {
+    /// Frontmatter `name:` when present, otherwise the file stem
     pub(crate) name: String,
+    /// File stem, kept so writes go back to the same file
+    pub(crate) file_name: String,
     pub(crate) description: String,
     pub(crate) content: String,
     pub(crate) tools: Vec<String>,
@@ -782,6 +1044,45 @@ pub(crate) struct ParsedAgent {
     pub(crate) tags: Vec<String>,
 }
 
+impl ParsedAgent {
+    /// The file stem when it differs from the agent's name, which is what
+    /// `subagents.file_name` stores
+    pub(crate) fn file_name_override(&self) -> Option<&str> {
+        (self.file_name != self.name).then_some(self.file_name.as_str())
+    }
+
+    /// Hash of the fields a rescan refreshes, stored as `source_hash`
+    pub(crate) fn source_hash(&self) -> String {
+        content_hash(
+            &serde_json::json!([
+                self.description,
+                self.content,
+                self.tools,
+                self.model,
+                self.permission_mode,
+                self.skills
+            ])
+            .to_string(),
+        )
+    }
+}
+
+impl ParsedSkill {
+    /// Hash of the fields a rescan refreshes, stored as `source_hash`
+    pub(crate) fn source_hash(&self) -> String {
+        content_hash(
+            &serde_json::json!([
+                self.description,
+                self.content,
+                self.allowed_tools,
+                self.model,
+                self.disable_model_invocation
+            ])
+            .to_string(),
+        )
+    }
+}
+
 /// Parsed rule data from a `.claude/rules/<name>.md` file.
 #[derive(Debug, PartialEq)]
 pub(crate) struct ParsedRule {
@@ -799,14 +1100,8 @@ pub(crate) fn parse_rule_file(path: &Path) -> Option<ParsedRule> {
     let content = std::fs::read_to_string(path).ok()?;
     let (frontmatter, body) = parse_frontmatter(&content);
     let description = frontmatter.get("description").cloned();
-    let paths = frontmatter
-        .get("paths")
-        .map(|v| parse_list_value(v))
-        .unwrap_or_default();
-    let tags = frontmatter
-        .get("tags")
-        .map(|v| parse_list_value(v))
-        .unwrap_or_default();
+    let paths = frontmatter.list("paths");
+    let tags = frontmatter.list("tags");
     Some(ParsedRule {
         name,
         description,
@@ -820,15 +1115,19 @@ pub(crate) fn parse_rule_file(path: &Path) -> Option<ParsedRule> {
 /// array form (`["a","b"]`) emitted by the current writer and the legacy
 /// comma-separated form (`a, b`) written by earlier versions.
 pub(crate) fn parse_list_value(raw: &str) -> Vec<String> {
-    let trimmed = raw.trim();
+    let mut trimmed = raw.trim();
     if trimmed.starts_with('[') {
         if let Ok(v) = serde_json::from_str::<Vec<String>>(trimmed) {
             return v;
         }
+        // A YAML flow list with unquoted items, e.g. `[review, "quality"]`
+        if let Some(inner) = trimmed.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+            trimmed = inner;
+        }
     }
     trimmed
         .split(',')
-        .map(|s| s.trim().to_string())
+        .map(|s| unquote(s.trim()).to_string())
         .filter(|s| !s.is_empty())
         .collect()
 }
@@ -867,15 +1166,7 @@ pub(crate) fn parse_skill_file(path: &Path) -> Option<ParsedSkill> {
         .or_else(|| frontmatter.get("disableModelInvocation"))
         .map(|v| v == "true" || v == "1")
         .unwrap_or(false);
-    let tags = frontmatter
-        .get("tags")
-        .map(|t| {
-            t.split(',')
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty())
-                .collect()
-        })
-        .unwrap_or_default();
+    let tags = frontmatter.list("tags");
 
     Some(ParsedSkill {
         name: file_name,
@@ -892,7 +1183,9 @@ pub(crate) fn parse_skill_file(path: &Path) -> Option<ParsedSkill> {
 
 /// Parse an agent skill directory (e.g., .claude/skills/my-skill/)
 /// Returns the skill from SKILL.md and any files from references/assets/scripts subdirs
-fn parse_agent_skill_dir(skill_dir: &Path) -> Option<(ParsedSkill, Vec<ParsedSkillFile>)> {
+pub(crate) fn parse_agent_skill_dir(
+    skill_dir: &Path,
+) -> Option<(ParsedSkill, Vec<ParsedSkillFile>)> {
     let skill_md_path = skill_dir.join("SKILL.md");
     if !skill_md_path.exists() {
         return None;
@@ -922,15 +1215,7 @@ fn parse_agent_skill_dir(skill_dir: &Path) -> Option<(ParsedSkill, Vec<ParsedSki
         .or_else(|| frontmatter.get("disableModelInvocation"))
         .map(|v| v == "true" || v == "1")
         .unwrap_or(false);
-    let tags = frontmatter
-        .get("tags")
-        .map(|t| {
-            t.split(',')
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty())
-                .collect()
-        })
-        .unwrap_or_default();
+    let tags = frontmatter.list("tags");
 
     let skill = ParsedSkill {
         name: skill_name,
@@ -988,11 +1273,25 @@ pub(crate) fn parse_agent_file(path: &Path) -> Option<ParsedAgent> {
     // Parse frontmatter if present
     let (frontmatter, body) = parse_frontmatter(&content);
 
+    // Claude Code resolves agents by their frontmatter name, not the filename
+    let name = frontmatter
+        .get("name")
+        .map(|n| n.trim_matches(|c| c == '"' || c == '\'').trim().to_string())
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| file_name.clone());
+    if name != file_name {
+        log::warn!(
+            "Agent {} is named '{}' in its frontmatter; using that instead of the filename",
+            path.display(),
+            name
+        );
+    }
+
     // Extract metadata from frontmatter
     let description = frontmatter
         .get("description")
         .cloned()
-        .unwrap_or_else(|| file_name.clone());
+        .unwrap_or_else(|| name.clone());
     let model = frontmatter.get("model").cloned();
     let permission_mode = frontmatter
         .get("permissionMode")
@@ -1000,34 +1299,14 @@ pub(crate) fn parse_agent_file(path: &Path) -> Option<ParsedAgent> {
         .cloned();
     let tools = frontmatter
         .get("tools")
-        .map(|t| {
-            t.split(',')
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty())
-                .collect()
-        })
-        .unwrap_or_default();
-    let skills = frontmatter
-        .get("skills")
-        .map(|t| {
-            t.split(',')
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty())
-                .collect()
-        })
-        .unwrap_or_default();
-    let tags = frontmatter
-        .get("tags")
-        .map(|t| {
-            t.split(',')
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty())
-                .collect()
-        })
+        .map(|t| parse_agent_tools(t))
         .unwrap_or_default();
+    let skills = frontmatter.list("skills");
+    let tags = frontmatter.list("tags");
 
     Some(ParsedAgent {
-        name: file_name,
+        name,
+        file_name,
         description,
         content: body,
         tools,
@@ -1038,36 +1317,255 @@ pub(crate) fn parse_agent_file(path: &Path) -> Option<ParsedAgent> {
     })
 }
 
-/// Parse YAML-like frontmatter from markdown content
-pub(crate) fn parse_frontmatter(
-    content: &str,
-) -> (std::collections::HashMap<String, String>, String) {
-    let mut frontmatter = std::collections::HashMap::new();
+/// True for `*` or `all`, which grant an agent every tool
+pub(crate) fn is_all_tools_wildcard(tool: &str) -> bool {
+    tool == "*" || tool.eq_ignore_ascii_case("all")
+}
+
+/// Split an agent's `tools:` value into tool names, dropping YAML quotes.
+/// A wildcard means every tool, which Claude Code expresses by omitting the
+/// field, so it parses to an empty list rather than a tool named `*`.
+pub(crate) fn parse_agent_tools(value: &str) -> Vec<String> {
+    let tools: Vec<String> = value
+        .split(',')
+        .map(|s| s.trim().trim_matches(['"', '\'']).trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if tools.iter().any(|t| is_all_tools_wildcard(t)) {
+        Vec::new()
+    } else {
+        tools
+    }
+}
+
+/// Frontmatter fields of a markdown file. Derefs to the scalar map; list
+/// fields appear there comma-joined and as items in `lists`.
+#[derive(Debug, Default)]
+pub(crate) struct Frontmatter {
+    values: HashMap<String, String>,
+    lists: HashMap<String, Vec<String>>,
+}
+
+impl std::ops::Deref for Frontmatter {
+    type Target = HashMap<String, String>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.values
+    }
+}
+
+impl Frontmatter {
+    /// A list field, from `- item` lines or a flow-list/comma-separated
+    /// scalar
+    pub(crate) fn list(&self, key: &str) -> Vec<String> {
+        match self.lists.get(key) {
+            Some(items) => items.clone(),
+            None => self
+                .values
+                .get(key)
+                .map(|v| parse_list_value(v))
+                .unwrap_or_default(),
+        }
+    }
+
+    fn insert_list(&mut self, key: String, items: Vec<String>) {
+        if !items.is_empty() {
+            self.values.insert(key.clone(), items.join(", "));
+            self.lists.insert(key, items);
+        }
+    }
+}
 
+/// Parse frontmatter from markdown content as YAML, falling back to
+/// line-by-line `key: value` parsing when the block is not valid YAML (e.g.
+/// an unquoted `: ` inside a description)
+pub(crate) fn parse_frontmatter(content: &str) -> (Frontmatter, String) {
     if let Some(after_prefix) = content.strip_prefix("---") {
         // Find the closing ---
         if let Some(end_pos) = after_prefix.find("\n---") {
             let fm_content = &after_prefix[..end_pos];
             let body = after_prefix[end_pos + 4..].trim_start().to_string();
+            let frontmatter = parse_yaml_frontmatter(fm_content)
+                .unwrap_or_else(|| parse_frontmatter_lines(fm_content));
+            return (frontmatter, body);
+        }
+    }
+
+    // No frontmatter, return content as-is
+    (Frontmatter::default(), content.to_string())
+}
 
-            // Parse simple key: value pairs
-            for line in fm_content.lines() {
-                let line = line.trim();
-                if let Some(colon_pos) = line.find(':') {
-                    let key = line[..colon_pos].trim().to_string();
-                    let value = line[colon_pos + 1..].trim().to_string();
-                    if !key.is_empty() && !value.is_empty() {
-                        frontmatter.insert(key, value);
+/// Structure comes from YAML, but a plain value written on its key's line is
+/// kept as written, so `argument-hint: [message]` stays a string and
+/// `description: Fix #123` isn't cut at the `#`
+fn parse_yaml_frontmatter(fm_content: &str) -> Option<Frontmatter> {
+    let serde_yaml::Value::Mapping(map) = serde_yaml::from_str(fm_content).ok()? else {
+        return None;
+    };
+    let inline = top_level_inline_values(fm_content);
+    let mut frontmatter = Frontmatter::default();
+    for (key, value) in map {
+        let Some(key) = yaml_scalar(&key).filter(|k| !k.is_empty()) else {
+            continue;
+        };
+        if let Some(raw) = inline.get(&key) {
+            if !value.is_mapping() {
+                frontmatter.values.insert(key, raw.to_string());
+                continue;
+            }
+        }
+        match value {
+            serde_yaml::Value::Sequence(items) => {
+                let items = items
+                    .iter()
+                    .filter_map(|item| yaml_scalar(item).or_else(|| yaml_json(item)))
+                    .filter(|item| !item.is_empty())
+                    .collect();
+                frontmatter.insert_list(key, items);
+            }
+            value => {
+                if let Some(value) = yaml_scalar(&value).or_else(|| yaml_json(&value)) {
+                    if !value.is_empty() {
+                        frontmatter.values.insert(key, value);
                     }
                 }
             }
+        }
+    }
+    Some(frontmatter)
+}
 
-            return (frontmatter, body);
+/// Unquoted, single-line values of top-level keys, as written
+fn top_level_inline_values(fm_content: &str) -> HashMap<String, &str> {
+    fm_content
+        .lines()
+        .filter(|line| !line.starts_with([' ', '\t', '#', '-']))
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let value = value.trim();
+            let plain =
+                !value.is_empty() && !value.starts_with(['"', '\'', '>', '|', '{', '&', '*', '!']);
+            plain.then(|| (key.trim().to_string(), value))
+        })
+        .collect()
+}
+
+/// String form of a YAML scalar; `None` for null and collections
+fn yaml_scalar(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(s) => Some(s.trim().to_string()),
+        serde_yaml::Value::Bool(b) => Some(b.to_string()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Nested values are kept as JSON so they survive the string-only map
+fn yaml_json(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::Null => None,
+        value => serde_json::to_string(value).ok(),
+    }
+}
+
+/// A multi-line value being collected under a key
+enum FrontmatterBlock {
+    List(String, Vec<String>),
+    /// `folded` joins lines with spaces (`>`), otherwise newlines (`|`)
+    Scalar {
+        key: String,
+        folded: bool,
+        lines: Vec<String>,
+    },
+}
+
+impl FrontmatterBlock {
+    fn finish(self, frontmatter: &mut Frontmatter) {
+        match self {
+            Self::List(key, items) => frontmatter.insert_list(key, items),
+            Self::Scalar { key, folded, lines } => {
+                let value = lines.join(if folded { " " } else { "\n" });
+                if !value.is_empty() {
+                    frontmatter.values.insert(key, value);
+                }
+            }
         }
     }
+}
 
-    // No frontmatter, return content as-is
-    (frontmatter, content.to_string())
+/// Line-based fallback: `key: value` pairs, `- item` lines under a key with
+/// no inline value, and folded or literal block scalars. Indented keys belong
+/// to a nested map and are skipped.
+fn parse_frontmatter_lines(fm_content: &str) -> Frontmatter {
+    let mut frontmatter = Frontmatter::default();
+    let mut block: Option<FrontmatterBlock> = None;
+
+    for raw_line in fm_content.lines() {
+        let indented = raw_line.starts_with([' ', '\t']);
+        let line = raw_line.trim();
+        if let Some(FrontmatterBlock::Scalar { lines, .. }) = block.as_mut() {
+            if indented || line.is_empty() {
+                if !line.is_empty() {
+                    lines.push(line.to_string());
+                }
+                continue;
+            }
+        }
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(item) = line.strip_prefix("- ") {
+            if let Some(FrontmatterBlock::List(_, items)) = block.as_mut() {
+                let item = unquote(item.trim());
+                if !item.is_empty() {
+                    items.push(item.to_string());
+                }
+            }
+            continue;
+        }
+        if let Some(colon_pos) = line.find(':') {
+            if indented {
+                continue;
+            }
+            if let Some(block) = block.take() {
+                block.finish(&mut frontmatter);
+            }
+            let key = line[..colon_pos].trim().to_string();
+            let value = line[colon_pos + 1..].trim();
+            if key.is_empty() {
+                continue;
+            }
+            match value {
+                "" => block = Some(FrontmatterBlock::List(key, Vec::new())),
+                ">" | ">-" | ">+" | "|" | "|-" | "|+" => {
+                    block = Some(FrontmatterBlock::Scalar {
+                        key,
+                        folded: value.starts_with('>'),
+                        lines: Vec::new(),
+                    })
+                }
+                value => {
+                    let value = unquote(value);
+                    if !value.is_empty() {
+                        frontmatter.values.insert(key, value.to_string());
+                    }
+                }
+            }
+        }
+    }
+    if let Some(block) = block {
+        block.finish(&mut frontmatter);
+    }
+
+    frontmatter
+}
+
+/// Strip one pair of matching surrounding quotes
+fn unquote(value: &str) -> &str {
+    ['"', '\'']
+        .iter()
+        .find_map(|&q| value.strip_prefix(q)?.strip_suffix(q))
+        .unwrap_or(value)
 }
 
 /// Scan project-level commands from .claude/commands/ and assign to project
@@ -1128,7 +1626,7 @@ fn scan_project_agents(db: &Database, project_id: i64, agents_dir: &Path) -> Res
 }
 
 /// Get or create a skill in the database, returning (skill_id, was_created)
-fn get_or_create_skill(
+pub(crate) fn get_or_create_skill(
     db: &Database,
     skill: &ParsedSkill,
     source_path: &str,
@@ -1149,6 +1647,9 @@ fn get_or_create_skill(
             "UPDATE skills SET source_path = ? WHERE id = ? AND (source_path IS NULL OR source_path = '')",
             params![source_path, id],
         )?;
+        if needs_refresh(db, "skills", id, &skill.source_hash())? {
+            refresh_skill_row(db, id, skill)?;
+        }
         return Ok((id, false));
     }
 
@@ -1160,8 +1661,8 @@ fn get_or_create_skill(
     };
 
     db.conn().execute(
-        "INSERT INTO skills (name, description, content, allowed_tools, model, disable_model_invocation, tags, source, source_path)
-         VALUES (?, ?, ?, ?, ?, ?, ?, 'auto-detected', ?)",
+        "INSERT INTO skills (name, description, content, allowed_tools, model, disable_model_invocation, tags, source, source_path, source_hash)
+         VALUES (?, ?, ?, ?, ?, ?, ?, 'auto-detected', ?, ?)",
         params![
             skill.name,
             skill.description,
@@ -1170,15 +1671,39 @@ fn get_or_create_skill(
             skill.model,
             skill.disable_model_invocation,
             tags_json,
-            source_path
+            source_path,
+            skill.source_hash()
         ],
     )?;
 
     Ok((db.conn().last_insert_rowid(), true))
 }
 
+/// Overwrite a skill row's file-backed fields with what was parsed from disk
+pub(crate) fn refresh_skill_row(db: &Database, id: i64, skill: &ParsedSkill) -> Result<()> {
+    db.conn().execute(
+        "UPDATE skills SET description = ?, content = ?, allowed_tools = ?, model = ?,
+                disable_model_invocation = ?, source_hash = ?, updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?",
+        params![
+            skill.description,
+            skill.content,
+            skill.allowed_tools,
+            skill.model,
+            skill.disable_model_invocation,
+            skill.source_hash(),
+            id
+        ],
+    )?;
+    Ok(())
+}
+
 /// Insert skill files into the database
-fn insert_skill_files(db: &Database, skill_id: i64, files: &[ParsedSkillFile]) -> Result<usize> {
+pub(crate) fn insert_skill_files(
+    db: &Database,
+    skill_id: i64,
+    files: &[ParsedSkillFile],
+) -> Result<usize> {
     let mut count = 0;
     for file in files {
         // Check if file already exists
@@ -1203,23 +1728,18 @@ fn insert_skill_files(db: &Database, skill_id: i64, files: &[ParsedSkillFile]) -
 }
 
 /// Get or create an agent in the database
-fn get_or_create_agent(db: &Database, agent: &ParsedAgent, source_path: &str) -> Result<i64> {
-    // Try to find existing agent by name
-    let existing_id: Option<i64> = db
-        .conn()
-        .query_row(
-            "SELECT id FROM subagents WHERE name = ?",
-            [&agent.name],
-            |row| row.get(0),
-        )
-        .ok();
-
-    if let Some(id) = existing_id {
+pub(crate) fn get_or_create_agent(
+    db: &Database,
+    agent: &ParsedAgent,
+    source_path: &str,
+) -> Result<i64> {
+    if let Some(id) = find_existing_agent(db, agent, source_path) {
         // Update source_path if not already set
         db.conn().execute(
             "UPDATE subagents SET source_path = ? WHERE id = ? AND (source_path IS NULL OR source_path = '')",
             params![source_path, id],
         )?;
+        refresh_agent(db, id, agent)?;
         return Ok(id);
     }
 
@@ -1241,8 +1761,8 @@ fn get_or_create_agent(db: &Database, agent: &ParsedAgent, source_path: &str) ->
     };
 
     db.conn().execute(
-        "INSERT INTO subagents (name, description, content, tools, model, permission_mode, skills, tags, source, source_path)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, 'auto-detected', ?)",
+        "INSERT INTO subagents (name, description, content, tools, model, permission_mode, skills, tags, source, source_path, file_name, source_hash)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, 'auto-detected', ?, ?, ?)",
         params![
             agent.name,
             agent.description,
@@ -1252,15 +1772,65 @@ fn get_or_create_agent(db: &Database, agent: &ParsedAgent, source_path: &str) ->
             agent.permission_mode,
             skills_json,
             tags_json,
-            source_path
+            source_path,
+            agent.file_name_override(),
+            agent.source_hash()
         ],
     )?;
 
     Ok(db.conn().last_insert_rowid())
 }
 
+/// The library row for a scanned agent: the one imported from this file,
+/// else one with the same name, else one stored under the file's stem
+/// (agents imported before frontmatter names were honored)
+fn find_existing_agent(db: &Database, agent: &ParsedAgent, source_path: &str) -> Option<i64> {
+    db.conn()
+        .query_row(
+            "SELECT id FROM subagents
+             WHERE source_path = ?1 OR name = ?2 OR COALESCE(file_name, name) = ?3
+             ORDER BY source_path = ?1 DESC, name = ?2 DESC
+             LIMIT 1",
+            params![source_path, agent.name, agent.file_name],
+            |row| row.get(0),
+        )
+        .ok()
+}
+
+/// Update an auto-detected agent whose file changed since the last scan
+fn refresh_agent(db: &Database, id: i64, agent: &ParsedAgent) -> Result<()> {
+    if !needs_refresh(db, "subagents", id, &agent.source_hash())? {
+        return Ok(());
+    }
+    refresh_agent_row(db, id, agent)
+}
+
+/// Overwrite an agent row's file-backed fields with what was parsed from disk
+pub(crate) fn refresh_agent_row(db: &Database, id: i64, agent: &ParsedAgent) -> Result<()> {
+    let tools_json =
+        (!agent.tools.is_empty()).then(|| serde_json::to_string(&agent.tools).unwrap());
+    let skills_json =
+        (!agent.skills.is_empty()).then(|| serde_json::to_string(&agent.skills).unwrap());
+    db.conn().execute(
+        "UPDATE subagents SET description = ?, content = ?, tools = ?, model = ?, permission_mode = ?,
+                skills = ?, source_hash = ?, updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?",
+        params![
+            agent.description,
+            agent.content,
+            tools_json,
+            agent.model,
+            agent.permission_mode,
+            skills_json,
+            agent.source_hash(),
+            id
+        ],
+    )?;
+    Ok(())
+}
+
 /// Assign a skill to a project
-fn assign_skill_to_project(db: &Database, project_id: i64, skill_id: i64) -> Result<()> {
+pub(crate) fn assign_skill_to_project(db: &Database, project_id: i64, skill_id: i64) -> Result<()> {
     // Check if already assigned
     let exists: bool = db
         .conn()
@@ -1282,7 +1852,7 @@ fn assign_skill_to_project(db: &Database, project_id: i64, skill_id: i64) -> Res
 }
 
 /// Get or create a command in the database, returning (command_id, was_created)
-fn get_or_create_command(
+pub(crate) fn get_or_create_command(
     db: &Database,
     command: &ParsedSkill,
     source_path: &str,
@@ -1303,6 +1873,9 @@ fn get_or_create_command(
             "UPDATE commands SET source_path = ? WHERE id = ? AND (source_path IS NULL OR source_path = '')",
             params![source_path, id],
         )?;
+        if needs_refresh(db, "commands", id, &command.source_hash())? {
+            refresh_command_row(db, id, command)?;
+        }
         return Ok((id, false));
     }
 
@@ -1314,8 +1887,8 @@ fn get_or_create_command(
     };
 
     db.conn().execute(
-        "INSERT INTO commands (name, description, content, allowed_tools, model, tags, source, source_path)
-         VALUES (?, ?, ?, ?, ?, ?, 'auto-detected', ?)",
+        "INSERT INTO commands (name, description, content, allowed_tools, model, tags, source, source_path, source_hash)
+         VALUES (?, ?, ?, ?, ?, ?, 'auto-detected', ?, ?)",
         params![
             command.name,
             command.description,
@@ -1323,17 +1896,40 @@ fn get_or_create_command(
             command.allowed_tools,
             command.model,
             tags_json,
-            source_path
+            source_path,
+            command.source_hash()
         ],
     )?;
 
     Ok((db.conn().last_insert_rowid(), true))
 }
 
-/// Assign a command to a project
-fn assign_command_to_project(db: &Database, project_id: i64, command_id: i64) -> Result<()> {
-    // Check if already assigned
-    let exists: bool = db
+/// Overwrite a command row's file-backed fields with what was parsed from disk
+pub(crate) fn refresh_command_row(db: &Database, id: i64, command: &ParsedSkill) -> Result<()> {
+    db.conn().execute(
+        "UPDATE commands SET description = ?, content = ?, allowed_tools = ?, model = ?,
+                source_hash = ?, updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?",
+        params![
+            command.description,
+            command.content,
+            command.allowed_tools,
+            command.model,
+            command.source_hash(),
+            id
+        ],
+    )?;
+    Ok(())
+}
+
+/// Assign a command to a project
+pub(crate) fn assign_command_to_project(
+    db: &Database,
+    project_id: i64,
+    command_id: i64,
+) -> Result<()> {
+    // Check if already assigned
+    let exists: bool = db
         .conn()
         .query_row(
             "SELECT 1 FROM project_commands WHERE project_id = ? AND command_id = ?",
@@ -1353,7 +1949,7 @@ fn assign_command_to_project(db: &Database, project_id: i64, command_id: i64) ->
 }
 
 /// Assign an agent to a project
-fn assign_agent_to_project(db: &Database, project_id: i64, agent_id: i64) -> Result<()> {
+pub(crate) fn assign_agent_to_project(db: &Database, project_id: i64, agent_id: i64) -> Result<()> {
     // Check if already assigned
     let exists: bool = db
         .conn()
@@ -1387,6 +1983,52 @@ pub(crate) struct ParsedHook {
     pub(crate) timeout: Option<i32>,
 }
 
+impl ParsedHook {
+    /// Hash of the fields a rescan refreshes, stored as `source_hash`
+    fn source_hash(&self) -> String {
+        content_hash(
+            &serde_json::json!([
+                self.description,
+                self.event_type,
+                self.matcher,
+                self.hook_type,
+                self.command,
+                self.prompt,
+                self.timeout
+            ])
+            .to_string(),
+        )
+    }
+}
+
+/// Matcher of a hook entry. Besides the usual `"matcher": "Write|Edit"`,
+/// accepts a list of tool names under `matcher` or `matchers`, which is
+/// joined into the equivalent `|` pattern.
+fn hook_entry_matcher(entry: &serde_json::Value) -> Option<String> {
+    let value = entry.get("matcher").or_else(|| entry.get("matchers"))?;
+    match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Array(items) => {
+            let names: Vec<&str> = items.iter().filter_map(|m| m.as_str()).collect();
+            (!names.is_empty()).then(|| names.join("|"))
+        }
+        _ => None,
+    }
+}
+
+/// Handlers of a hook entry: the nested `hooks` array, or the entry itself
+/// when it is written flat as `{ "matcher": ..., "type": ..., "command": ... }`
+fn hook_entry_handlers(entry: &serde_json::Value) -> Vec<&serde_json::Value> {
+    match entry.get("hooks") {
+        Some(inner) => inner
+            .as_array()
+            .map(|a| a.iter().collect())
+            .unwrap_or_default(),
+        None if entry.get("command").is_some() || entry.get("prompt").is_some() => vec![entry],
+        None => Vec::new(),
+    }
+}
+
 /// Parse hooks from a settings.json file
 pub(crate) fn parse_hooks_from_settings(path: &Path) -> Vec<ParsedHook> {
     let mut hooks = Vec::new();
@@ -1404,68 +2046,68 @@ pub(crate) fn parse_hooks_from_settings(path: &Path) -> Vec<ParsedHook> {
     // Parse the "hooks" object
     if let Some(hooks_obj) = json.get("hooks").and_then(|h| h.as_object()) {
         for (event_type, event_hooks) in hooks_obj {
-            if let Some(hook_array) = event_hooks.as_array() {
-                for (idx, hook_entry) in hook_array.iter().enumerate() {
-                    let matcher = hook_entry
-                        .get("matcher")
-                        .and_then(|m| m.as_str())
+            // A single entry may be written without the surrounding array
+            let hook_array: Vec<&serde_json::Value> = match event_hooks {
+                serde_json::Value::Array(entries) => entries.iter().collect(),
+                serde_json::Value::Object(_) => vec![event_hooks],
+                _ => Vec::new(),
+            };
+            for (idx, hook_entry) in hook_array.into_iter().enumerate() {
+                let matcher = hook_entry_matcher(hook_entry);
+                let handlers = hook_entry_handlers(hook_entry);
+
+                for (inner_idx, inner_hook) in handlers.into_iter().enumerate() {
+                    let hook_type = inner_hook
+                        .get("type")
+                        .and_then(|t| t.as_str())
+                        .unwrap_or("command")
+                        .to_string();
+
+                    let command = inner_hook
+                        .get("command")
+                        .and_then(|c| c.as_str())
+                        .map(|s| s.to_string());
+                    let prompt = inner_hook
+                        .get("prompt")
+                        .and_then(|p| p.as_str())
                         .map(|s| s.to_string());
+                    let timeout = inner_hook
+                        .get("timeout")
+                        .and_then(|t| t.as_i64())
+                        .map(|t| t as i32);
+
+                    // Generate a name from the event type and index
+                    let name = if let Some(ref m) = matcher {
+                        format!(
+                            "{}-{}-{}",
+                            event_type.to_lowercase(),
+                            m.replace('|', "-"),
+                            inner_idx
+                        )
+                    } else {
+                        format!("{}-{}-{}", event_type.to_lowercase(), idx, inner_idx)
+                    };
 
-                    if let Some(inner_hooks) = hook_entry.get("hooks").and_then(|h| h.as_array()) {
-                        for (inner_idx, inner_hook) in inner_hooks.iter().enumerate() {
-                            let hook_type = inner_hook
-                                .get("type")
-                                .and_then(|t| t.as_str())
-                                .unwrap_or("command")
-                                .to_string();
-
-                            let command = inner_hook
-                                .get("command")
-                                .and_then(|c| c.as_str())
-                                .map(|s| s.to_string());
-                            let prompt = inner_hook
-                                .get("prompt")
-                                .and_then(|p| p.as_str())
-                                .map(|s| s.to_string());
-                            let timeout = inner_hook
-                                .get("timeout")
-                                .and_then(|t| t.as_i64())
-                                .map(|t| t as i32);
-
-                            // Generate a name from the event type and index
-                            let name = if let Some(ref m) = matcher {
-                                format!(
-                                    "{}-{}-{}",
-                                    event_type.to_lowercase(),
-                                    m.replace('|', "-"),
-                                    inner_idx
-                                )
-                            } else {
-                                format!("{}-{}-{}", event_type.to_lowercase(), idx, inner_idx)
-                            };
-
-                            // Generate description
-                            let description = Some(format!(
-                                "{} hook{}",
-                                event_type,
-                                matcher
-                                    .as_ref()
-                                    .map(|m| format!(" for {}", m))
-                                    .unwrap_or_default()
-                            ));
-
-                            hooks.push(ParsedHook {
-                                name,
-                                description,
-                                event_type: event_type.clone(),
-                                matcher: matcher.clone(),
-                                hook_type,
-                                command,
-                                prompt,
-                                timeout,
-                            });
-                        }
-                    }
+                    // Generate description
+                    let description = Some(format!(
+                        "{} hook{}",
+                        event_type,
+                        matcher
+                            .as_ref()
+                            .map(|m| format!(" for {}", m))
+                            .unwrap_or_default()
+                    ));
+
+                    hooks.push(ParsedHook {
+                        name,
+                        description,
+                        event_type: event_type.clone(),
+                        matcher: matcher.clone(),
+                        hook_type,
+                        command,
+                        prompt,
+                        timeout,
+                    });
                 }
             }
         }
@@ -1487,34 +2129,7 @@ pub fn scan_global_hooks(db: &Database) -> Result<usize> {
     let mut count = 0;
 
     for hook in hooks {
-        // Check if already exists by name
-        let existing_id: Option<i64> = db
-            .conn()
-            .query_row("SELECT id FROM hooks WHERE name = ?", [&hook.name], |row| {
-                row.get(0)
-            })
-            .ok();
-
-        let hook_id = if let Some(id) = existing_id {
-            id
-        } else {
-            // Create new hook
-            db.conn().execute(
-                "INSERT INTO hooks (name, description, event_type, matcher, hook_type, command, prompt, timeout, source)
-                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, 'auto-detected')",
-                params![
-                    hook.name,
-                    hook.description,
-                    hook.event_type,
-                    hook.matcher,
-                    hook.hook_type,
-                    hook.command,
-                    hook.prompt,
-                    hook.timeout
-                ],
-            )?;
-            db.conn().last_insert_rowid()
-        };
+        let hook_id = get_or_create_hook(db, &hook)?;
 
         // Always ensure it's in global_hooks
         let _ = db.conn().execute(
@@ -1555,14 +2170,34 @@ fn get_or_create_hook(db: &Database, hook: &ParsedHook) -> Result<i64> {
         })
         .ok();
 
+    let hash = hook.source_hash();
     if let Some(id) = existing_id {
+        if needs_refresh(db, "hooks", id, &hash)? {
+            db.conn().execute(
+                "UPDATE hooks SET description = ?, event_type = ?, matcher = ?, hook_type = ?,
+                        command = ?, prompt = ?, timeout = ?, source_hash = ?,
+                        updated_at = CURRENT_TIMESTAMP
+                 WHERE id = ?",
+                params![
+                    hook.description,
+                    hook.event_type,
+                    hook.matcher,
+                    hook.hook_type,
+                    hook.command,
+                    hook.prompt,
+                    hook.timeout,
+                    hash,
+                    id
+                ],
+            )?;
+        }
         return Ok(id);
     }
 
     // Create new hook
     db.conn().execute(
-        "INSERT INTO hooks (name, description, event_type, matcher, hook_type, command, prompt, timeout, source)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, 'auto-detected')",
+        "INSERT INTO hooks (name, description, event_type, matcher, hook_type, command, prompt, timeout, source, source_hash)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, 'auto-detected', ?)",
         params![
             hook.name,
             hook.description,
@@ -1571,7 +2206,8 @@ fn get_or_create_hook(db: &Database, hook: &ParsedHook) -> Result<i64> {
             hook.hook_type,
             hook.command,
             hook.prompt,
-            hook.timeout
+            hook.timeout,
+            hash
         ],
     )?;
 
@@ -1650,11 +2286,15 @@ pub fn scan_opencode_config(db: &Database) -> Result<usize> {
             .ok();
 
         if let Some(id) = existing_id {
-            // Update source_path if not already set
-            db.conn().execute(
-                "UPDATE mcps SET source_path = ? WHERE id = ? AND (source_path IS NULL OR source_path = '')",
-                params![&source_path, id],
-            )?;
+            let scanned = McpDefinition::new(
+                mcp_type,
+                mcp.command.as_deref(),
+                mcp.args.as_ref(),
+                mcp.url.as_deref(),
+                mcp.headers.as_ref(),
+                mcp.env.as_ref(),
+            );
+            note_or_refresh_mcp(db, id, &mcp.name, &source_path, &scanned)?;
         } else {
             let args_json = match &mcp.args {
                 Some(args) if !args.is_empty() => Some(serde_json::to_string(args).unwrap()),
@@ -1811,8 +2451,8 @@ pub fn scan_opencode_global_agents(db: &Database) -> Result<usize> {
                     };
 
                     let result = db.conn().execute(
-                        "INSERT INTO subagents (name, description, content, tools, model, permission_mode, skills, tags, source, source_path)
-                         VALUES (?, ?, ?, ?, ?, ?, ?, ?, 'opencode', ?)",
+                        "INSERT INTO subagents (name, description, content, tools, model, permission_mode, skills, tags, source, source_path, file_name)
+                         VALUES (?, ?, ?, ?, ?, ?, ?, ?, 'opencode', ?, ?)",
                         params![
                             agent.name,
                             agent.description,
@@ -1822,7 +2462,8 @@ pub fn scan_opencode_global_agents(db: &Database) -> Result<usize> {
                             agent.permission_mode,
                             skills_json,
                             tags_json,
-                            source_path
+                            source_path,
+                            agent.file_name_override()
                         ],
                     );
 
@@ -1851,13 +2492,18 @@ pub fn scan_codex_config(db: &Database) -> Result<usize> {
         }
     };
 
-    if !paths.config_file.exists() {
-        log::debug!("Codex config not found at {:?}", paths.config_file);
+    scan_codex_config_file(db, &paths.config_file)
+}
+
+/// Import the `[mcp_servers.*]` tables of a Codex config.toml as `codex` MCPs
+pub(crate) fn scan_codex_config_file(db: &Database, config_file: &Path) -> Result<usize> {
+    if !config_file.exists() {
+        log::debug!("Codex config not found at {:?}", config_file);
         return Ok(0);
     }
 
     // Parse MCPs from config.toml
-    let mcps = match codex_config::parse_codex_mcps(&paths.config_file) {
+    let mcps = match codex_config::parse_codex_mcps(config_file) {
         Ok(m) => m,
         Err(e) => {
             log::warn!("Failed to parse Codex config: {}", e);
@@ -1868,7 +2514,7 @@ pub fn scan_codex_config(db: &Database) -> Result<usize> {
     let mut count = 0;
 
     for mcp in mcps {
-        let source_path = paths.config_file.to_string_lossy().to_string();
+        let source_path = config_file.to_string_lossy().to_string();
 
         // Check if already exists
         let existing_id: Option<i64> = db
@@ -1879,11 +2525,15 @@ pub fn scan_codex_config(db: &Database) -> Result<usize> {
             .ok();
 
         if let Some(id) = existing_id {
-            // Update source_path if not already set
-            db.conn().execute(
-                "UPDATE mcps SET source_path = ? WHERE id = ? AND (source_path IS NULL OR source_path = '')",
-                params![&source_path, id],
-            )?;
+            let scanned = McpDefinition::new(
+                &mcp.mcp_type,
+                mcp.command.as_deref(),
+                mcp.args.as_ref(),
+                mcp.url.as_deref(),
+                mcp.headers.as_ref(),
+                mcp.env.as_ref(),
+            );
+            note_or_refresh_mcp(db, id, &mcp.name, &source_path, &scanned)?;
         } else {
             let args_json = match &mcp.args {
                 Some(args) if !args.is_empty() => Some(serde_json::to_string(args).unwrap()),
@@ -1969,11 +2619,15 @@ pub fn scan_copilot_config(db: &Database) -> Result<usize> {
             .ok();
 
         if let Some(id) = existing_id {
-            // Update source_path if not already set
-            db.conn().execute(
-                "UPDATE mcps SET source_path = ? WHERE id = ? AND (source_path IS NULL OR source_path = '')",
-                params![&source_path, id],
-            )?;
+            let scanned = McpDefinition::new(
+                &mcp.mcp_type,
+                mcp.command.as_deref(),
+                mcp.args.as_ref(),
+                mcp.url.as_deref(),
+                mcp.headers.as_ref(),
+                mcp.env.as_ref(),
+            );
+            note_or_refresh_mcp(db, id, &mcp.name, &source_path, &scanned)?;
         } else {
             let args_json = match &mcp.args {
                 Some(args) if !args.is_empty() => Some(serde_json::to_string(args).unwrap()),
@@ -2056,11 +2710,15 @@ pub fn scan_cursor_config(db: &Database) -> Result<usize> {
             .ok();
 
         if let Some(id) = existing_id {
-            // Update source_path if not already set
-            db.conn().execute(
-                "UPDATE mcps SET source_path = ? WHERE id = ? AND (source_path IS NULL OR source_path = '')",
-                params![&source_path, id],
-            )?;
+            let scanned = McpDefinition::new(
+                &mcp.mcp_type,
+                mcp.command.as_deref(),
+                mcp.args.as_ref(),
+                mcp.url.as_deref(),
+                mcp.headers.as_ref(),
+                mcp.env.as_ref(),
+            );
+            note_or_refresh_mcp(db, id, &mcp.name, &source_path, &scanned)?;
         } else {
             let args_json = match &mcp.args {
                 Some(args) if !args.is_empty() => Some(serde_json::to_string(args).unwrap()),
@@ -2146,11 +2804,15 @@ pub fn scan_gemini_config(db: &Database) -> Result<usize> {
             .ok();
 
         if let Some(id) = existing_id {
-            // Update source_path if not already set
-            db.conn().execute(
-                "UPDATE mcps SET source_path = ? WHERE id = ? AND (source_path IS NULL OR source_path = '')",
-                params![&source_path, id],
-            )?;
+            let scanned = McpDefinition::new(
+                &mcp.mcp_type,
+                mcp.command.as_deref(),
+                mcp.args.as_ref(),
+                mcp.url.as_deref(),
+                mcp.headers.as_ref(),
+                mcp.env.as_ref(),
+            );
+            note_or_refresh_mcp(db, id, &mcp.name, &source_path, &scanned)?;
         } else {
             let args_json = match &mcp.args {
                 Some(args) if !args.is_empty() => Some(serde_json::to_string(args).unwrap()),
@@ -2191,12 +2853,143 @@ pub fn scan_gemini_config(db: &Database) -> Result<usize> {
     Ok(count)
 }
 
+// ============================================================================
+// Windsurf Scanning Functions
+// ============================================================================
+
+/// Scan Windsurf mcp_config.json for MCPs
+pub fn scan_windsurf_config(db: &Database) -> Result<usize> {
+    let paths = match get_windsurf_paths() {
+        Ok(p) => p,
+        Err(e) => {
+            log::debug!("Windsurf paths not available: {}", e);
+            return Ok(0);
+        }
+    };
+
+    if !paths.mcp_config_file.exists() {
+        log::debug!(
+            "Windsurf mcp_config.json not found at {:?}",
+            paths.mcp_config_file
+        );
+        return Ok(0);
+    }
+
+    // Parse MCPs from mcp_config.json
+    let mcps = match windsurf_config::parse_windsurf_mcps(&paths.mcp_config_file) {
+        Ok(m) => m,
+        Err(e) => {
+            log::warn!("Failed to parse Windsurf mcp_config.json: {}", e);
+            return Ok(0);
+        }
+    };
+
+    let mut count = 0;
+
+    for mcp in mcps {
+        let source_path = paths.mcp_config_file.to_string_lossy().to_string();
+
+        // Check if already exists
+        let existing_id: Option<i64> = db
+            .conn()
+            .query_row("SELECT id FROM mcps WHERE name = ?", [&mcp.name], |row| {
+                row.get(0)
+            })
+            .ok();
+
+        if let Some(id) = existing_id {
+            let scanned = McpDefinition::new(
+                &mcp.mcp_type,
+                mcp.command.as_deref(),
+                mcp.args.as_ref(),
+                mcp.url.as_deref(),
+                mcp.headers.as_ref(),
+                mcp.env.as_ref(),
+            );
+            note_or_refresh_mcp(db, id, &mcp.name, &source_path, &scanned)?;
+        } else {
+            let args_json = match &mcp.args {
+                Some(args) if !args.is_empty() => Some(serde_json::to_string(args).unwrap()),
+                _ => None,
+            };
+            let env_json = match &mcp.env {
+                Some(env) if !env.is_empty() => Some(serde_json::to_string(env).unwrap()),
+                _ => None,
+            };
+            let headers_json = match &mcp.headers {
+                Some(headers) if !headers.is_empty() => {
+                    Some(serde_json::to_string(headers).unwrap())
+                }
+                _ => None,
+            };
+
+            let result = db.conn().execute(
+                "INSERT INTO mcps (name, type, command, args, url, headers, env, source, source_path)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, 'windsurf', ?)",
+                params![
+                    mcp.name,
+                    mcp.mcp_type,
+                    mcp.command,
+                    args_json,
+                    mcp.url,
+                    headers_json,
+                    env_json,
+                    source_path
+                ],
+            );
+
+            if result.is_ok() {
+                count += 1;
+            }
+        }
+    }
+
+    Ok(count)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::fs;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_should_scan_filters_editors() {
+        assert!(should_scan(None, "opencode"));
+        let only_claude = vec!["claude_code".to_string()];
+        assert!(should_scan(Some(&only_claude), "claude_code"));
+        assert!(!should_scan(Some(&only_claude), "opencode"));
+        assert!(!should_scan(Some(&[]), "claude_code"));
+    }
+
+    #[test]
+    fn test_scan_phases_follow_editor_filter() {
+        let only_opencode = vec!["opencode".to_string()];
+        let names: Vec<_> = scan_phases(Some(&only_opencode))
+            .iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(
+            names,
+            vec!["opencode_mcps", "opencode_commands", "opencode_agents"]
+        );
+        assert!(scan_phases(Some(&[])).is_empty());
+    }
+
+    #[test]
+    fn test_cancelled_scan_stops_before_next_phase() {
+        let db = Database::in_memory().unwrap();
+        let mut progress = Vec::new();
+
+        cancel_startup_scan();
+        let summary = run_scan(&db, None, |p| progress.push(p.clone())).unwrap();
+        SCAN_CANCELLED.store(false, Ordering::SeqCst);
+
+        assert!(summary.cancelled);
+        assert!(summary.counts.is_empty());
+        assert!(progress.is_empty());
+    }
+
     // =========================================================================
     // parse_frontmatter tests
     // =========================================================================
@@ -2302,6 +3095,168 @@ Body"#;
         assert_eq!(fm.get("url"), Some(&"https://example.com:8080".to_string()));
     }
 
+    #[test]
+    fn test_parse_frontmatter_block_lists() {
+        let content = r#"---
+name: reviewer
+tools:
+  - Read
+  - Write
+tags: [review, "quality"]
+description: >
+  Reviews code
+  for bugs
+---
+Body"#;
+
+        let (fm, body) = parse_frontmatter(content);
+
+        assert_eq!(fm.list("tools"), vec!["Read", "Write"]);
+        assert_eq!(fm.get("tools"), Some(&"Read, Write".to_string()));
+        assert_eq!(fm.list("tags"), vec!["review", "quality"]);
+        assert_eq!(
+            fm.get("description"),
+            Some(&"Reviews code for bugs".to_string())
+        );
+        assert_eq!(body, "Body");
+    }
+
+    #[test]
+    fn test_parse_frontmatter_quoted_colon_value() {
+        let content = r#"---
+description: "Use when: the build fails"
+---
+Body"#;
+
+        let (fm, _) = parse_frontmatter(content);
+
+        assert_eq!(
+            fm.get("description"),
+            Some(&"Use when: the build fails".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_frontmatter_unquoted_colon_value() {
+        // An unquoted ": " is not valid YAML but was always accepted
+        let content = r#"---
+description: Use when: the build fails
+tools:
+  - Read
+  - Bash
+paths: ["src/**/*.{ts,tsx}"]
+---
+Body"#;
+
+        let (fm, _) = parse_frontmatter(content);
+
+        assert_eq!(
+            fm.get("description"),
+            Some(&"Use when: the build fails".to_string())
+        );
+        assert_eq!(fm.list("tools"), vec!["Read", "Bash"]);
+        assert_eq!(fm.list("paths"), vec!["src/**/*.{ts,tsx}"]);
+    }
+
+    #[test]
+    fn test_parse_frontmatter_keeps_argument_hint_brackets() {
+        let content = r#"---
+argument-hint: [message]
+allowed-tools: Bash(git add:*), Bash(git commit:*)
+---
+Body"#;
+
+        let (fm, _) = parse_frontmatter(content);
+
+        assert_eq!(fm.get("argument-hint"), Some(&"[message]".to_string()));
+        assert_eq!(
+            fm.get("allowed-tools"),
+            Some(&"Bash(git add:*), Bash(git commit:*)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_frontmatter_keeps_hash_in_value() {
+        let content = r#"---
+# Comment lines are skipped
+description: Fix #123 before release
+---
+Body"#;
+
+        let (fm, _) = parse_frontmatter(content);
+
+        assert_eq!(
+            fm.get("description"),
+            Some(&"Fix #123 before release".to_string())
+        );
+        assert_eq!(fm.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_frontmatter_nested_map_keeps_top_level_name() {
+        let content = r#"---
+name: real-skill
+metadata:
+  name: other
+  version: 2
+---
+Body"#;
+
+        let (fm, _) = parse_frontmatter(content);
+
+        assert_eq!(fm.get("name"), Some(&"real-skill".to_string()));
+        let metadata: serde_json::Value =
+            serde_json::from_str(fm.get("metadata").unwrap()).unwrap();
+        assert_eq!(metadata["name"], "other");
+        assert_eq!(metadata["version"], 2);
+
+        // The line fallback skips nested keys too
+        let invalid = content.replace(
+            "name: real-skill",
+            "name: real-skill\ndescription: Use when: x",
+        );
+        let (fm, _) = parse_frontmatter(&invalid);
+        assert_eq!(fm.get("name"), Some(&"real-skill".to_string()));
+        assert_eq!(fm.get("description"), Some(&"Use when: x".to_string()));
+    }
+
+    #[test]
+    fn test_parse_frontmatter_block_scalars() {
+        let content = r#"---
+description: >
+  Reviews code
+  for style issues
+notes: |
+  line one
+  line two
+---
+Body"#;
+
+        let (fm, _) = parse_frontmatter(content);
+
+        assert_eq!(
+            fm.get("description"),
+            Some(&"Reviews code for style issues".to_string())
+        );
+        assert_eq!(fm.get("notes"), Some(&"line one\nline two".to_string()));
+    }
+
+    #[test]
+    fn test_parse_frontmatter_yaml_lists() {
+        let content = r#"---
+tools:
+  - Read
+  - "Bash(git status:*)"
+skills: [review, "lint"]
+---
+Body"#;
+
+        let (fm, _) = parse_frontmatter(content);
+
+        assert_eq!(fm.list("tools"), vec!["Read", "Bash(git status:*)"]);
+        assert_eq!(fm.list("skills"), vec!["review", "lint"]);
+    }
+
     // =========================================================================
     // parse_skill_file tests
     // =========================================================================
@@ -2473,6 +3428,105 @@ You are a code review expert."#,
         assert!(agent.skills.is_empty());
     }
 
+    #[test]
+    fn test_parse_agent_file_frontmatter_name_wins() {
+        let temp_dir = TempDir::new().unwrap();
+        let agent_path = temp_dir.path().join("reviewer-v2.md");
+        fs::write(
+            &agent_path,
+            "---\nname: \"code-reviewer\"\ndescription: Reviews code\n---\nReview it.",
+        )
+        .unwrap();
+
+        let agent = parse_agent_file(&agent_path).unwrap();
+        assert_eq!(agent.name, "code-reviewer");
+        assert_eq!(agent.file_name, "reviewer-v2");
+        assert_eq!(agent.file_name_override(), Some("reviewer-v2"));
+
+        // Stored under the frontmatter name, written back to the original file
+        let db = Database::in_memory().unwrap();
+        let id = get_or_create_agent(&db, &agent, &agent_path.to_string_lossy()).unwrap();
+        let subagent = db.get_subagent_by_id(id).unwrap().unwrap();
+        assert_eq!(subagent.name, "code-reviewer");
+        assert_eq!(subagent.file_name.as_deref(), Some("reviewer-v2"));
+
+        let project = TempDir::new().unwrap();
+        crate::services::subagent_writer::write_project_subagent(project.path(), &subagent)
+            .unwrap();
+        let agents_dir = project.path().join(".claude").join("agents");
+        assert!(agents_dir.join("reviewer-v2.md").exists());
+        assert!(!agents_dir.join("code-reviewer.md").exists());
+
+        // A matching name doesn't record a separate file stem
+        fs::write(&agent_path, "---\nname: reviewer-v2\n---\nReview it.").unwrap();
+        assert_eq!(
+            parse_agent_file(&agent_path).unwrap().file_name_override(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rescan_matches_agent_stored_under_its_file_stem() {
+        let temp_dir = TempDir::new().unwrap();
+        let agent_path = temp_dir.path().join("reviewer-v2.md");
+        fs::write(
+            &agent_path,
+            "---\nname: code-reviewer\ndescription: Reviews code\n---\nReview it.",
+        )
+        .unwrap();
+
+        // Imported before frontmatter names were honored: stored by stem
+        let db = Database::in_memory().unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO subagents (name, description, content, source) VALUES ('reviewer-v2', 'Reviews code', 'Review it.', 'auto-detected')",
+                [],
+            )
+            .unwrap();
+        let legacy_id = db.conn().last_insert_rowid();
+
+        let agent = parse_agent_file(&agent_path).unwrap();
+        let id = get_or_create_agent(&db, &agent, &agent_path.to_string_lossy()).unwrap();
+        assert_eq!(id, legacy_id);
+        let count: i64 = db
+            .conn()
+            .query_row("SELECT COUNT(*) FROM subagents", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_parse_agent_tools_wildcard_means_all_tools() {
+        assert!(parse_agent_tools("\"*\"").is_empty());
+        assert!(parse_agent_tools("*").is_empty());
+        assert!(parse_agent_tools("All").is_empty());
+        assert_eq!(parse_agent_tools("'Read', Grep"), vec!["Read", "Grep"]);
+    }
+
+    #[test]
+    fn test_wildcard_tools_agent_round_trips_without_tools_field() {
+        let temp_dir = TempDir::new().unwrap();
+        let agent_path = temp_dir.path().join("helper.md");
+        fs::write(
+            &agent_path,
+            "---\ndescription: Helps\ntools: \"*\"\n---\nHelp with anything.",
+        )
+        .unwrap();
+
+        let db = Database::in_memory().unwrap();
+        let agent = parse_agent_file(&agent_path).unwrap();
+        assert!(agent.tools.is_empty());
+        let id = get_or_create_agent(&db, &agent, "helper.md").unwrap();
+
+        let subagent = db.get_subagent_by_id(id).unwrap().unwrap();
+        let markdown = crate::services::subagent_writer::generate_subagent_markdown(&subagent);
+        assert!(!markdown.contains("tools:"));
+
+        // Writing back and re-scanning keeps it a wildcard agent
+        fs::write(&agent_path, &markdown).unwrap();
+        assert!(parse_agent_file(&agent_path).unwrap().tools.is_empty());
+    }
+
     #[test]
     fn test_parse_agent_file_permission_mode_snake_case() {
         let temp_dir = TempDir::new().unwrap();
@@ -2726,6 +3780,65 @@ Content"#,
         assert_eq!(hooks[0].hook_type, "command");
     }
 
+    #[test]
+    fn test_parse_hooks_from_settings_schema_variants() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings_path = temp_dir.path().join("settings.json");
+
+        // Expected (event, matcher, command) per imported hook
+        type Expected<'a> = Vec<(&'a str, Option<&'a str>, &'a str)>;
+        let fixtures: [(&str, Expected); 5] = [
+            (
+                r#"{ "hooks": { "PreToolUse": [
+                    { "matcher": ["Write", "Edit"], "hooks": [{ "type": "command", "command": "lint" }] }
+                ] } }"#,
+                vec![("PreToolUse", Some("Write|Edit"), "lint")],
+            ),
+            (
+                r#"{ "hooks": { "PreToolUse": [
+                    { "matchers": ["Bash"], "hooks": [{ "type": "command", "command": "guard" }] }
+                ] } }"#,
+                vec![("PreToolUse", Some("Bash"), "guard")],
+            ),
+            (
+                r#"{ "hooks": { "PostToolUse": [
+                    { "matcher": "Write", "type": "command", "command": "fmt" }
+                ] } }"#,
+                vec![("PostToolUse", Some("Write"), "fmt")],
+            ),
+            (
+                r#"{ "hooks": { "Stop": { "hooks": [{ "type": "command", "command": "notify" }] } } }"#,
+                vec![("Stop", None, "notify")],
+            ),
+            (
+                r#"{ "hooks": { "PreToolUse": [
+                    { "matcher": "Bash", "hooks": [{ "command": "a" }] },
+                    { "matcher": ["Read", "Grep"], "command": "b" }
+                ] } }"#,
+                vec![
+                    ("PreToolUse", Some("Bash"), "a"),
+                    ("PreToolUse", Some("Read|Grep"), "b"),
+                ],
+            ),
+        ];
+
+        for (fixture, expected) in fixtures {
+            fs::write(&settings_path, fixture).unwrap();
+            let hooks = parse_hooks_from_settings(&settings_path);
+            let actual: Vec<_> = hooks
+                .iter()
+                .map(|h| {
+                    (
+                        h.event_type.as_str(),
+                        h.matcher.as_deref(),
+                        h.command.as_deref().unwrap(),
+                    )
+                })
+                .collect();
+            assert_eq!(actual, expected, "fixture: {}", fixture);
+        }
+    }
+
     // =========================================================================
     // DB-backed scanner function tests
     // =========================================================================
@@ -2812,6 +3925,56 @@ Content"#,
         assert_eq!(id1, id2);
     }
 
+    #[test]
+    fn test_get_or_create_mcp_keeps_first_definition_on_conflict() {
+        let db = setup_test_db();
+        let args = vec!["server".to_string()];
+        let first = get_or_create_mcp(
+            &db,
+            "filesystem",
+            "stdio",
+            Some("npx"),
+            Some(&args),
+            None,
+            None,
+            None,
+            "~/.claude.json",
+        )
+        .unwrap();
+        let second = get_or_create_mcp(
+            &db,
+            "filesystem",
+            "stdio",
+            Some("uvx"),
+            Some(&args),
+            None,
+            None,
+            None,
+            "/plugins/fs/.mcp.json",
+        )
+        .unwrap();
+        assert_eq!(first, second);
+
+        let command: String = db
+            .conn()
+            .query_row("SELECT command FROM mcps WHERE id = ?", [first], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(command, "npx");
+
+        let conflicts = mcp_conflicts::get_mcp_conflicts(&db).unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(
+            conflicts[0].competing[0].source_path,
+            "/plugins/fs/.mcp.json"
+        );
+        assert_eq!(
+            conflicts[0].competing[0].definition.command.as_deref(),
+            Some("uvx")
+        );
+    }
+
     #[test]
     fn test_assign_mcp_to_project() {
         let db = setup_test_db();
@@ -2957,6 +4120,7 @@ Content"#,
         let db = setup_test_db();
         let agent = ParsedAgent {
             name: "test-agent".to_string(),
+            file_name: "test-agent".to_string(),
             description: "An agent".to_string(),
             content: "Agent instructions".to_string(),
             tools: vec!["Read".to_string(), "Write".to_string()],
@@ -3042,6 +4206,7 @@ Content"#,
         let proj_id = get_or_create_project(&db, "proj", "/tmp/proj").unwrap();
         let agent = ParsedAgent {
             name: "ag".to_string(),
+            file_name: "ag".to_string(),
             description: "desc".to_string(),
             content: "content".to_string(),
             tools: vec![],
@@ -3193,6 +4358,196 @@ Deploy instructions here."#,
         assert_eq!(assigned, 2);
     }
 
+    #[test]
+    fn test_rescan_updates_auto_detected_items_changed_on_disk() {
+        let db = setup_test_db();
+        let proj_id = get_or_create_project(&db, "proj", "/tmp/proj").unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let commands_dir = temp_dir.path().join("commands");
+        fs::create_dir(&commands_dir).unwrap();
+        let file = commands_dir.join("deploy.md");
+
+        fs::write(&file, "---\ndescription: Deploy\n---\nOld steps.").unwrap();
+        scan_project_commands(&db, proj_id, &commands_dir).unwrap();
+        fs::write(
+            &file,
+            "---\ndescription: Deploy to prod\nallowed-tools: Bash\n---\nNew steps.",
+        )
+        .unwrap();
+        scan_project_commands(&db, proj_id, &commands_dir).unwrap();
+
+        let (description, content, tools): (String, String, Option<String>) = db
+            .conn()
+            .query_row(
+                "SELECT description, content, allowed_tools FROM commands WHERE name = 'deploy'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(description, "Deploy to prod");
+        assert_eq!(content, "New steps.");
+        assert_eq!(tools.as_deref(), Some("Bash"));
+
+        let mut hook = ParsedHook {
+            name: "PostToolUse - Write".to_string(),
+            description: None,
+            event_type: "PostToolUse".to_string(),
+            matcher: Some("Write".to_string()),
+            hook_type: "command".to_string(),
+            command: Some("npm run lint".to_string()),
+            prompt: None,
+            timeout: None,
+        };
+        let id = get_or_create_hook(&db, &hook).unwrap();
+        hook.command = Some("npm run lint -- --fix".to_string());
+        assert_eq!(get_or_create_hook(&db, &hook).unwrap(), id);
+        let command: String = db
+            .conn()
+            .query_row("SELECT command FROM hooks WHERE id = ?", [id], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(command, "npm run lint -- --fix");
+    }
+
+    #[test]
+    fn test_rescan_leaves_manually_created_items_untouched() {
+        let db = setup_test_db();
+        db.conn()
+            .execute(
+                "INSERT INTO commands (name, description, content, source) VALUES ('deploy', 'Mine', 'My steps.', 'manual')",
+                [],
+            )
+            .unwrap();
+        let proj_id = get_or_create_project(&db, "proj", "/tmp/proj").unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let commands_dir = temp_dir.path().join("commands");
+        fs::create_dir(&commands_dir).unwrap();
+        fs::write(
+            commands_dir.join("deploy.md"),
+            "---\ndescription: From disk\n---\nDisk steps.",
+        )
+        .unwrap();
+
+        scan_project_commands(&db, proj_id, &commands_dir).unwrap();
+
+        let (description, content, hash): (String, String, Option<String>) = db
+            .conn()
+            .query_row(
+                "SELECT description, content, source_hash FROM commands WHERE name = 'deploy'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(description, "Mine");
+        assert_eq!(content, "My steps.");
+        assert_eq!(hash, None);
+    }
+
+    #[test]
+    fn test_scan_codex_config_file_imports_with_codex_source() {
+        let db = setup_test_db();
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        fs::write(
+            &config_path,
+            r#"model = "o3"
+
+[mcp_servers.local]
+command = "npx"
+args = ["-y", "@test/server"]
+
+[mcp_servers.remote]
+url = "https://example.com/mcp"
+bearer_token_env_var = "REMOTE_TOKEN"
+"#,
+        )
+        .unwrap();
+
+        let count = scan_codex_config_file(&db, &config_path).unwrap();
+        assert_eq!(count, 2);
+
+        let (mcp_type, source): (String, String) = db
+            .conn()
+            .query_row(
+                "SELECT type, source FROM mcps WHERE name = 'remote'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(mcp_type, "http");
+        assert_eq!(source, "codex");
+
+        // A second scan doesn't duplicate entries
+        assert_eq!(scan_codex_config_file(&db, &config_path).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_mcp_json_entry_disabled_flag_round_trips() {
+        let db = setup_test_db();
+        let temp_dir = TempDir::new().unwrap();
+        let project_path = temp_dir.path().to_string_lossy().to_string();
+        fs::write(
+            temp_dir.path().join(".mcp.json"),
+            r#"{
+                "mcpServers": {
+                    "active": { "command": "npx", "args": ["active-server"] },
+                    "paused": { "command": "npx", "args": ["paused-server"], "disabled": true }
+                }
+            }"#,
+        )
+        .unwrap();
+        let project_id = get_or_create_project(&db, "proj", &project_path).unwrap();
+
+        assert_eq!(
+            import_mcps_from_project_mcp_json(&db, project_id, &project_path).unwrap(),
+            2
+        );
+        let enabled = |name: &str| -> bool {
+            db.conn()
+                .query_row(
+                    "SELECT pm.is_enabled FROM project_mcps pm JOIN mcps m ON m.id = pm.mcp_id
+                     WHERE pm.project_id = ? AND m.name = ?",
+                    params![project_id, name],
+                    |row| row.get(0),
+                )
+                .unwrap()
+        };
+        assert!(enabled("active"));
+        assert!(!enabled("paused"));
+
+        // Writing back keeps the disabled entry instead of dropping it
+        let tuple = |name: &str| {
+            (
+                name.to_string(),
+                "stdio".to_string(),
+                Some("npx".to_string()),
+                Some(format!("[\"{}-server\"]", name)),
+                None,
+                None,
+                None,
+            )
+        };
+        crate::services::config_writer::write_project_config_with_disabled(
+            temp_dir.path(),
+            &[tuple("active")],
+            &[tuple("paused")],
+        )
+        .unwrap();
+
+        let reparsed = config_parser::parse_mcp_file(&temp_dir.path().join(".mcp.json")).unwrap();
+        let paused = reparsed.iter().find(|m| m.name == "paused").unwrap();
+        assert!(paused.disabled);
+        assert_eq!(paused.args, Some(vec!["paused-server".to_string()]));
+        assert!(
+            !reparsed
+                .iter()
+                .find(|m| m.name == "active")
+                .unwrap()
+                .disabled
+        );
+    }
+
     #[test]
     fn test_scan_project_skills_from_dir() {
         let db = setup_test_db();
@@ -3788,6 +5143,7 @@ Body"#,
         let db = setup_test_db();
         let agent = ParsedAgent {
             name: "empty-agent".to_string(),
+            file_name: "empty-agent".to_string(),
             description: "desc".to_string(),
             content: "content".to_string(),
             tools: vec![],
@@ -4033,6 +5389,7 @@ Body"#,
 
         let agent = ParsedAgent {
             name: "agent-empty".to_string(),
+            file_name: "agent-empty".to_string(),
             description: "desc".to_string(),
             content: "content".to_string(),
             tools: vec![],
@@ -4131,6 +5488,7 @@ Body content."#,
         let db = setup_test_db();
         let agent = ParsedAgent {
             name: "full-agent".to_string(),
+            file_name: "full-agent".to_string(),
             description: "Full description".to_string(),
             content: "Detailed instructions".to_string(),
             tools: vec!["Read".to_string(), "Write".to_string(), "Bash".to_string()],
@@ -0,0 +1,367 @@
+//! Setup script export
+//!
+//! Renders the global MCP setup as a shell script of editor CLI calls
+//! (`claude mcp add ...`) so a new machine can be configured from a file the
+//! user can read before running. Credentials are never written into the
+//! script: secret env vars and headers become `${VAR}` references that the
+//! script checks for up front.
+
+use crate::db::Mcp;
+use crate::services::diagnostics::{is_secret_key, looks_like_secret};
+use anyhow::{bail, Result};
+use std::collections::BTreeSet;
+
+/// Items that have to be copied as files because no editor CLI creates them
+#[derive(Debug, Clone, Default)]
+pub struct FileCopyItems {
+    pub skills: Vec<String>,
+    pub subagents: Vec<String>,
+    pub commands: Vec<String>,
+}
+
+/// The CLI binary that manages MCP servers for an editor
+fn editor_cli(editor_id: &str) -> Result<&'static str> {
+    match editor_id {
+        "claude_code" => Ok("claude"),
+        "codex" => Ok("codex"),
+        "gemini" => Ok("gemini"),
+        other => bail!(
+            "Editor '{}' has no CLI for adding MCP servers; setup scripts support claude_code, codex and gemini",
+            other
+        ),
+    }
+}
+
+/// Quote `value` for a POSIX shell, leaving plain words untouched
+fn shell_quote(value: &str) -> String {
+    let plain = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-./:@%+=,".contains(c));
+    if plain {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// Environment variable name used as a placeholder for a secret header
+fn header_var_name(mcp_name: &str, header: &str) -> String {
+    format!("{}_{}", mcp_name, header)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// `KEY=value` for an env var, or `KEY="${KEY}"` when the value is a secret
+/// and `required` collects placeholders
+fn env_assignment(key: &str, value: &str, required: Option<&mut BTreeSet<String>>) -> String {
+    let secret = is_secret_key(key) || looks_like_secret(value);
+    if let Some(required) = required.filter(|_| secret) {
+        required.insert(key.to_string());
+        format!("{}=\"${{{}}}\"", key, key)
+    } else {
+        shell_quote(&format!("{}={}", key, value))
+    }
+}
+
+/// `Name: value` for a header, with secret values replaced by a variable
+/// when `required` collects placeholders
+fn header_value(
+    mcp: &Mcp,
+    key: &str,
+    value: &str,
+    required: Option<&mut BTreeSet<String>>,
+) -> String {
+    let secret = is_secret_key(key) || looks_like_secret(value);
+    if let Some(required) = required.filter(|_| secret) {
+        let var = header_var_name(&mcp.name, key);
+        let line = format!("\"{}: ${{{}}}\"", key, var);
+        required.insert(var);
+        line
+    } else {
+        shell_quote(&format!("{}: {}", key, value))
+    }
+}
+
+fn sorted<V>(map: &Option<std::collections::HashMap<String, V>>) -> Vec<(&String, &V)> {
+    let mut entries: Vec<_> = map.iter().flatten().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+}
+
+/// The CLI call that adds `mcp` to `editor_id` at user scope. Secret values
+/// referenced by the call are added to `required`.
+pub fn mcp_cli_command(
+    editor_id: &str,
+    mcp: &Mcp,
+    required: &mut BTreeSet<String>,
+) -> Result<String> {
+    build_cli_command(editor_id, mcp, Some(required))
+}
+
+/// The same call with every env var and header value written out as-is
+pub fn mcp_cli_command_inline(editor_id: &str, mcp: &Mcp) -> Result<String> {
+    build_cli_command(editor_id, mcp, None)
+}
+
+fn build_cli_command(
+    editor_id: &str,
+    mcp: &Mcp,
+    mut required: Option<&mut BTreeSet<String>>,
+) -> Result<String> {
+    let cli = editor_cli(editor_id)?;
+    let name = shell_quote(&mcp.name);
+    let env: Vec<String> = sorted(&mcp.env)
+        .into_iter()
+        .map(|(k, v)| env_assignment(k, v, required.as_deref_mut()))
+        .collect();
+    let headers: Vec<String> = sorted(&mcp.headers)
+        .into_iter()
+        .map(|(k, v)| header_value(mcp, k, v, required.as_deref_mut()))
+        .collect();
+
+    let mut parts: Vec<String> = vec![cli.to_string(), "mcp".to_string(), "add".to_string()];
+    match mcp.mcp_type.as_str() {
+        "stdio" => {
+            let Some(command) = mcp.command.as_deref() else {
+                bail!("MCP '{}' has no command", mcp.name);
+            };
+            let flag = if editor_id == "codex" { "--env" } else { "-e" };
+            if editor_id != "codex" {
+                parts.extend(["--scope".to_string(), "user".to_string()]);
+            }
+            parts.push(name);
+            for assignment in env {
+                parts.extend([flag.to_string(), assignment]);
+            }
+            parts.push("--".to_string());
+            parts.push(shell_quote(command));
+            parts.extend(mcp.args.iter().flatten().map(|a| shell_quote(a)));
+        }
+        "http" | "sse" => {
+            let Some(url) = mcp.url.as_deref() else {
+                bail!("MCP '{}' has no URL", mcp.name);
+            };
+            if editor_id == "codex" {
+                if mcp.mcp_type == "sse" {
+                    bail!("Codex CLI does not support SSE MCP '{}'", mcp.name);
+                }
+                if !headers.is_empty() {
+                    bail!(
+                        "Codex CLI cannot set headers for MCP '{}' from the command line",
+                        mcp.name
+                    );
+                }
+                parts.extend([name, "--url".to_string(), shell_quote(url)]);
+            } else {
+                parts.extend([
+                    "--scope".to_string(),
+                    "user".to_string(),
+                    "--transport".to_string(),
+                    mcp.mcp_type.clone(),
+                    name,
+                    shell_quote(url),
+                ]);
+                for header in headers {
+                    parts.extend(["--header".to_string(), header]);
+                }
+            }
+        }
+        other => bail!("Unknown MCP type '{}' for '{}'", other, mcp.name),
+    }
+    Ok(parts.join(" "))
+}
+
+/// Where an item lives on disk for the editor, when known
+fn file_copy_hint(editor_id: &str, kind: &str, name: &str) -> Option<String> {
+    if editor_id != "claude_code" {
+        return None;
+    }
+    Some(match kind {
+        "skill" => format!("~/.claude/skills/{}/", name),
+        "agent" => format!("~/.claude/agents/{}.md", name),
+        _ => format!("~/.claude/commands/{}.md", name),
+    })
+}
+
+/// Render a setup script that adds `mcps` with `editor_id`'s CLI. MCPs the
+/// CLI cannot express are left as comments rather than failing the script,
+/// and `files` are listed as items to copy by hand.
+pub fn render_setup_script(editor_id: &str, mcps: &[Mcp], files: &FileCopyItems) -> Result<String> {
+    let cli = editor_cli(editor_id)?;
+    let mut required = BTreeSet::new();
+    let mut body = Vec::new();
+
+    for mcp in mcps {
+        body.push(String::new());
+        body.push(format!("# {} ({})", mcp.name, mcp.mcp_type));
+        if let Some(env_file) = mcp.env_file.as_deref() {
+            body.push(format!(
+                "# Loads extra environment from {}; copy that file as well",
+                env_file
+            ));
+        }
+        match mcp_cli_command(editor_id, mcp, &mut required) {
+            Ok(line) => body.push(line),
+            Err(e) => body.push(format!("# Skipped: {}", e)),
+        }
+    }
+
+    let mut script = vec![
+        "#!/usr/bin/env bash".to_string(),
+        format!(
+            "# Recreates the global MCP setup with the {} CLI. Review before running.",
+            cli
+        ),
+        "set -euo pipefail".to_string(),
+        String::new(),
+        format!(
+            "command -v {cli} >/dev/null 2>&1 || {{ echo \"{cli} CLI not found on PATH\" >&2; exit 1; }}"
+        ),
+    ];
+
+    if !required.is_empty() {
+        script.push(String::new());
+        script.push("# Secrets are not stored in this script. Export these first:".to_string());
+        for var in &required {
+            script.push(format!(": \"${{{var}:?set {var} before running}}\""));
+        }
+    }
+
+    if mcps.is_empty() {
+        script.push(String::new());
+        script.push("# No enabled global MCPs".to_string());
+    }
+    script.extend(body);
+
+    let copies: Vec<(&str, &String)> = files
+        .skills
+        .iter()
+        .map(|n| ("skill", n))
+        .chain(files.subagents.iter().map(|n| ("agent", n)))
+        .chain(files.commands.iter().map(|n| ("command", n)))
+        .collect();
+    if !copies.is_empty() {
+        script.push(String::new());
+        script.push("# These cannot be added with a CLI; copy their files manually:".to_string());
+        for (kind, name) in copies {
+            match file_copy_hint(editor_id, kind, name) {
+                Some(path) => script.push(format!("#   {} {}: {}", kind, name, path)),
+                None => script.push(format!("#   {} {}", kind, name)),
+            }
+        }
+    }
+
+    script.push(String::new());
+    Ok(script.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn mcp(name: &str, mcp_type: &str) -> Mcp {
+        Mcp {
+            id: 1,
+            name: name.to_string(),
+            description: None,
+            mcp_type: mcp_type.to_string(),
+            command: None,
+            args: None,
+            url: None,
+            headers: None,
+            env: None,
+            icon: None,
+            tags: None,
+            source: "manual".to_string(),
+            source_path: None,
+            is_enabled_global: false,
+            is_favorite: false,
+            created_at: String::new(),
+            updated_at: String::new(),
+            testable: true,
+            env_file: None,
+            display_name: None,
+            homepage: None,
+            inherit_env: true,
+            shell: None,
+        }
+    }
+
+    #[test]
+    fn test_stdio_command_replaces_secret_env_with_variable() {
+        let mut github = mcp("github", "stdio");
+        github.command = Some("npx".to_string());
+        github.args = Some(vec!["-y".to_string(), "@scope/server github".to_string()]);
+        github.env = Some(HashMap::from([
+            ("GITHUB_TOKEN".to_string(), "ghp_x".to_string()),
+            ("LOG_LEVEL".to_string(), "debug".to_string()),
+        ]));
+
+        let mut required = BTreeSet::new();
+        let line = mcp_cli_command("claude_code", &github, &mut required).unwrap();
+        assert_eq!(
+            line,
+            "claude mcp add --scope user github -e GITHUB_TOKEN=\"${GITHUB_TOKEN}\" -e LOG_LEVEL=debug -- npx -y '@scope/server github'"
+        );
+        assert!(required.contains("GITHUB_TOKEN"));
+        assert!(!line.contains("ghp_x"));
+
+        let inline = mcp_cli_command_inline("claude_code", &github).unwrap();
+        assert!(inline.contains("-e GITHUB_TOKEN=ghp_x -e LOG_LEVEL=debug"));
+    }
+
+    #[test]
+    fn test_http_command_with_secret_header() {
+        let mut remote = mcp("my-api", "http");
+        remote.url = Some("https://example.com/mcp".to_string());
+        remote.headers = Some(HashMap::from([(
+            "Authorization".to_string(),
+            "Bearer abc".to_string(),
+        )]));
+
+        let mut required = BTreeSet::new();
+        let line = mcp_cli_command("gemini", &remote, &mut required).unwrap();
+        assert_eq!(
+            line,
+            "gemini mcp add --scope user --transport http my-api https://example.com/mcp --header \"Authorization: ${MY_API_AUTHORIZATION}\""
+        );
+        assert!(required.contains("MY_API_AUTHORIZATION"));
+
+        assert!(mcp_cli_command("codex", &remote, &mut required).is_err());
+    }
+
+    #[test]
+    fn test_render_setup_script() {
+        let mut fs = mcp("fs", "stdio");
+        fs.command = Some("npx".to_string());
+        fs.env = Some(HashMap::from([("API_KEY".to_string(), "k".to_string())]));
+        let broken = mcp("broken", "stdio");
+        let files = FileCopyItems {
+            skills: vec!["review".to_string()],
+            subagents: vec!["planner".to_string()],
+            commands: vec![],
+        };
+
+        let script = render_setup_script("claude_code", &[fs, broken], &files).unwrap();
+        assert!(script.starts_with("#!/usr/bin/env bash\n"));
+        assert!(script.contains(": \"${API_KEY:?set API_KEY before running}\""));
+        assert!(script.contains("claude mcp add --scope user fs -e API_KEY=\"${API_KEY}\" -- npx"));
+        assert!(script.contains("# Skipped: MCP 'broken' has no command"));
+        assert!(script.contains("#   skill review: ~/.claude/skills/review/"));
+        assert!(script.contains("#   agent planner: ~/.claude/agents/planner.md"));
+    }
+
+    #[test]
+    fn test_render_setup_script_rejects_editor_without_cli() {
+        assert!(render_setup_script("cursor", &[], &FileCopyItems::default()).is_err());
+    }
+}
@@ -0,0 +1,285 @@
+//! Skill script runner
+//!
+//! Lets skill authors smoke-test the files under a skill's `scripts/`. The
+//! skill's files are written to a scratch directory laid out like the skill
+//! on disk (`references/`, `assets/`, `scripts/`), so scripts that read their
+//! siblings by relative path behave as they would under Claude.
+
+use crate::db::models::SkillFile;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// How long a script may run before it is killed
+pub const SCRIPT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Output kept per stream; the rest is dropped
+const MAX_OUTPUT_BYTES: usize = 256 * 1024;
+
+static RUN_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptRunResult {
+    pub stdout: String,
+    pub stderr: String,
+    /// None when the script was killed (timeout or signal)
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub duration_ms: u64,
+}
+
+/// Directory a skill file lives in, relative to the skill root
+fn file_type_dir(file_type: &str) -> &'static str {
+    match file_type {
+        "reference" => "references",
+        "asset" => "assets",
+        _ => "scripts",
+    }
+}
+
+/// Reject names that would escape their directory
+fn check_file_name(name: &str) -> Result<()> {
+    let path = Path::new(name);
+    if name.is_empty()
+        || path.is_absolute()
+        || path
+            .components()
+            .any(|c| !matches!(c, std::path::Component::Normal(_)))
+    {
+        return Err(anyhow!("Invalid skill file name '{}'", name));
+    }
+    Ok(())
+}
+
+/// The interpreter (and its leading args) to run `script` with, from its
+/// extension or, failing that, its shebang line
+pub fn interpreter_for(name: &str, content: &str) -> Option<Vec<String>> {
+    let python = if cfg!(windows) { "python" } else { "python3" };
+    let extension = Path::new(name).extension().and_then(|e| e.to_str());
+    let by_extension = match extension.unwrap_or_default() {
+        "py" => Some(python),
+        "sh" => Some("sh"),
+        "bash" => Some("bash"),
+        "js" | "mjs" | "cjs" => Some("node"),
+        "rb" => Some("ruby"),
+        "pl" => Some("perl"),
+        "ps1" => Some("pwsh"),
+        _ => None,
+    };
+    if let Some(program) = by_extension {
+        return Some(vec![program.to_string()]);
+    }
+    shebang(content)
+}
+
+fn shebang(content: &str) -> Option<Vec<String>> {
+    let line = content.lines().next()?.strip_prefix("#!")?;
+    let mut parts = line.split_whitespace().map(str::to_string);
+    let program = parts.next()?;
+    let rest: Vec<String> = parts.collect();
+    // `#!/usr/bin/env python3` -> `python3`, so PATH lookup works everywhere
+    if program.ends_with("/env") && !rest.is_empty() {
+        return Some(rest);
+    }
+    Some(std::iter::once(program).chain(rest).collect())
+}
+
+/// Write a skill's files into a fresh scratch directory, returning it
+pub fn materialize_skill(files: &[SkillFile]) -> Result<PathBuf> {
+    let root = std::env::temp_dir().join(format!(
+        "cctm-skill-script-{}-{}",
+        std::process::id(),
+        RUN_COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    for file in files {
+        check_file_name(&file.name)?;
+    }
+    for file in files {
+        let path = root.join(file_type_dir(&file.file_type)).join(&file.name);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, &file.content)?;
+    }
+    std::fs::create_dir_all(&root)?;
+    Ok(root)
+}
+
+/// Read a stream on its own thread, keeping the first `MAX_OUTPUT_BYTES`
+fn read_capped(mut stream: impl Read + Send + 'static) -> mpsc::Receiver<String> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let mut chunk = [0u8; 8192];
+        while let Ok(n) = stream.read(&mut chunk) {
+            if n == 0 {
+                break;
+            }
+            if buffer.len() < MAX_OUTPUT_BYTES {
+                let keep = n.min(MAX_OUTPUT_BYTES - buffer.len());
+                buffer.extend_from_slice(&chunk[..keep]);
+            }
+        }
+        let _ = tx.send(String::from_utf8_lossy(&buffer).to_string());
+    });
+    rx
+}
+
+/// Kill the script and anything it started. On Unix the script leads its own
+/// process group, so the whole group is signalled.
+fn kill_script(child: &mut Child) {
+    #[cfg(unix)]
+    let _ = Command::new("kill")
+        .args(["-KILL", "--", &format!("-{}", child.id())])
+        .status();
+    let _ = child.kill();
+}
+
+/// Run `script_name` from the skill's files with `args`, from the skill
+/// root, killing it after `timeout`. The scratch directory is removed after.
+pub fn run_skill_script(
+    files: &[SkillFile],
+    script_name: &str,
+    args: &[String],
+    timeout: Duration,
+) -> Result<ScriptRunResult> {
+    let script = files
+        .iter()
+        .find(|f| f.file_type == "script" && f.name == script_name)
+        .ok_or_else(|| anyhow!("Skill has no script named '{}'", script_name))?;
+    let interpreter = interpreter_for(&script.name, &script.content)
+        .ok_or_else(|| anyhow!("Don't know how to run '{}'", script.name))?;
+
+    let root = materialize_skill(files)?;
+    let result = run_in(&root, &interpreter, script, args, timeout);
+    let _ = std::fs::remove_dir_all(&root);
+    result
+}
+
+fn run_in(
+    root: &Path,
+    interpreter: &[String],
+    script: &SkillFile,
+    args: &[String],
+    timeout: Duration,
+) -> Result<ScriptRunResult> {
+    let script_path = Path::new("scripts").join(&script.name);
+    let started = Instant::now();
+    let mut command = Command::new(&interpreter[0]);
+    command
+        .args(&interpreter[1..])
+        .arg(&script_path)
+        .args(args)
+        .current_dir(root)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+    let mut child = command
+        .spawn()
+        .map_err(|e| anyhow!("Failed to start '{}': {}", interpreter[0], e))?;
+
+    let stdout = read_capped(child.stdout.take().expect("stdout is piped"));
+    let stderr = read_capped(child.stderr.take().expect("stderr is piped"));
+
+    let mut timed_out = false;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if started.elapsed() >= timeout {
+            timed_out = true;
+            kill_script(&mut child);
+            break child.wait()?;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    // A background process that escaped the kill may still hold the pipes
+    // open; don't wait on it for long
+    let grace = Duration::from_secs(2);
+    Ok(ScriptRunResult {
+        stdout: stdout.recv_timeout(grace).unwrap_or_default(),
+        stderr: stderr.recv_timeout(grace).unwrap_or_default(),
+        exit_code: if timed_out { None } else { status.code() },
+        timed_out,
+        duration_ms,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(file_type: &str, name: &str, content: &str) -> SkillFile {
+        SkillFile {
+            id: 0,
+            skill_id: 1,
+            file_type: file_type.to_string(),
+            name: name.to_string(),
+            content: content.to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_interpreter_for() {
+        assert_eq!(interpreter_for("run.sh", ""), Some(vec!["sh".to_string()]));
+        assert_eq!(
+            interpreter_for("tool", "#!/usr/bin/env node\nconsole.log(1)"),
+            Some(vec!["node".to_string()])
+        );
+        assert_eq!(
+            interpreter_for("tool", "#!/bin/bash -e\n"),
+            Some(vec!["/bin/bash".to_string(), "-e".to_string()])
+        );
+        assert_eq!(interpreter_for("notes.txt", "hello"), None);
+    }
+
+    #[test]
+    fn test_materialize_rejects_escaping_names() {
+        assert!(materialize_skill(&[file("script", "../evil.sh", "")]).is_err());
+        assert!(materialize_skill(&[file("asset", "/etc/passwd", "")]).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_skill_script_reads_siblings_and_reports_exit_code() {
+        let files = vec![
+            file("reference", "guide.md", "be nice"),
+            file(
+                "script",
+                "check.sh",
+                "cat references/guide.md; echo \"args: $*\"; echo oops >&2; exit 3",
+            ),
+        ];
+        let result =
+            run_skill_script(&files, "check.sh", &["a".to_string()], SCRIPT_TIMEOUT).unwrap();
+        assert_eq!(result.stdout, "be niceargs: a\n");
+        assert_eq!(result.stderr, "oops\n");
+        assert_eq!(result.exit_code, Some(3));
+        assert!(!result.timed_out);
+
+        assert!(run_skill_script(&files, "missing.sh", &[], SCRIPT_TIMEOUT).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_skill_script_times_out() {
+        let files = vec![file("script", "hang.sh", "sleep 5")];
+        let result = run_skill_script(&files, "hang.sh", &[], Duration::from_millis(200)).unwrap();
+        assert!(result.timed_out);
+        assert_eq!(result.exit_code, None);
+        assert!(result.duration_ms < 5_000);
+    }
+}
@@ -1,8 +1,8 @@
 use crate::db::models::Skill;
-use crate::utils::opencode_paths::get_opencode_paths;
+use crate::utils::opencode_paths::{get_opencode_paths, project_opencode_dir};
 use anyhow::Result;
 use directories::BaseDirs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Generate markdown content for an agent skill (.claude/skills/name/SKILL.md)
 pub(crate) fn generate_skill_markdown(skill: &Skill) -> String {
@@ -78,13 +78,35 @@ pub(crate) fn generate_skill_markdown(skill: &Skill) -> String {
     format!("{}{}", frontmatter, skill.content)
 }
 
+/// Directory a skill is written to: {base_path}/.claude/skills/{name}
+pub fn skill_dir(base_path: &Path, skill_name: &str) -> PathBuf {
+    base_path.join(".claude").join("skills").join(skill_name)
+}
+
+/// A skill's file: {base_path}/.claude/skills/{name}/SKILL.md
+pub fn skill_file_path(base_path: &Path, skill_name: &str) -> PathBuf {
+    skill_dir(base_path, skill_name).join("SKILL.md")
+}
+
+/// Location of a supporting skill file inside the skill directory, using the
+/// same subdirectories the scanner reads (references/, assets/, scripts/)
+pub fn skill_support_file_path(skill_dir: &Path, file_type: &str, file_name: &str) -> PathBuf {
+    let subdir = match file_type {
+        "reference" => "references",
+        "asset" => "assets",
+        "script" => "scripts",
+        other => other,
+    };
+    skill_dir.join(subdir).join(file_name)
+}
+
 /// Write a skill to the appropriate location
 /// Skills go to {base_path}/.claude/skills/{name}/SKILL.md
 pub fn write_skill_file(base_path: &Path, skill: &Skill) -> Result<()> {
-    let skill_dir = base_path.join(".claude").join("skills").join(&skill.name);
+    let skill_dir = skill_dir(base_path, &skill.name);
     std::fs::create_dir_all(&skill_dir)?;
 
-    let file_path = skill_dir.join("SKILL.md");
+    let file_path = skill_file_path(base_path, &skill.name);
     crate::utils::backup::backup_file(&file_path)?;
     let content = generate_skill_markdown(skill);
     std::fs::write(file_path, content)?;
@@ -94,7 +116,7 @@ pub fn write_skill_file(base_path: &Path, skill: &Skill) -> Result<()> {
 
 /// Delete a skill file from the appropriate location
 pub fn delete_skill_file(base_path: &Path, skill: &Skill) -> Result<()> {
-    let skill_dir = base_path.join(".claude").join("skills").join(&skill.name);
+    let skill_dir = skill_dir(base_path, &skill.name);
     if skill_dir.exists() {
         std::fs::remove_dir_all(skill_dir)?;
     }
@@ -132,28 +154,53 @@ pub fn delete_project_skill(project_path: &Path, skill: &Skill) -> Result<()> {
 // OpenCode Support
 // ============================================================================
 
+/// Directory a skill is written to for OpenCode: {base_path}/skill/{name}.
+/// OpenCode's agent/ directory holds subagents, so skills must not go there.
+pub fn skill_dir_opencode(base_path: &Path, skill_name: &str) -> PathBuf {
+    base_path.join("skill").join(skill_name)
+}
+
+/// OpenCode file for a skill: {base_path}/skill/{name}/SKILL.md
+pub fn skill_file_path_opencode(base_path: &Path, skill_name: &str) -> PathBuf {
+    skill_dir_opencode(base_path, skill_name).join("SKILL.md")
+}
+
+/// Remove the {base_path}/agent/{name}.md that older versions wrote skills
+/// to. Only a file with the skill's `name:` frontmatter is removed, since
+/// OpenCode subagents (which have none) live in the same directory.
+fn remove_legacy_skill_file_opencode(base_path: &Path, skill_name: &str) -> Result<()> {
+    let legacy_path = base_path.join("agent").join(format!("{}.md", skill_name));
+    let Ok(content) = std::fs::read_to_string(&legacy_path) else {
+        return Ok(());
+    };
+    if content.starts_with(&format!("---\nname: {}\n", skill_name)) {
+        std::fs::remove_file(legacy_path)?;
+    }
+    Ok(())
+}
+
 /// Write a skill to OpenCode's format
-/// Agent skills go to {base_path}/agent/{name}.md (OpenCode uses agent/ not skills/)
+/// Agent skills go to {base_path}/skill/{name}/SKILL.md (OpenCode uses skill/ not skills/)
 pub fn write_skill_file_opencode(base_path: &Path, skill: &Skill) -> Result<()> {
-    let agent_dir = base_path.join("agent");
-    std::fs::create_dir_all(&agent_dir)?;
+    let skill_dir = skill_dir_opencode(base_path, &skill.name);
+    std::fs::create_dir_all(&skill_dir)?;
 
-    let file_path = agent_dir.join(format!("{}.md", skill.name));
+    let file_path = skill_dir.join("SKILL.md");
     crate::utils::backup::backup_file(&file_path)?;
     let content = generate_skill_markdown(skill);
     std::fs::write(file_path, content)?;
 
-    Ok(())
+    remove_legacy_skill_file_opencode(base_path, &skill.name)
 }
 
 /// Delete a skill from OpenCode's format
 pub fn delete_skill_file_opencode(base_path: &Path, skill: &Skill) -> Result<()> {
-    let file_path = base_path.join("agent").join(format!("{}.md", skill.name));
-    if file_path.exists() {
-        std::fs::remove_file(file_path)?;
+    let skill_dir = skill_dir_opencode(base_path, &skill.name);
+    if skill_dir.exists() {
+        std::fs::remove_dir_all(skill_dir)?;
     }
 
-    Ok(())
+    remove_legacy_skill_file_opencode(base_path, &skill.name)
 }
 
 /// Write a skill to the global OpenCode config (~/.config/opencode/)
@@ -170,13 +217,13 @@ pub fn delete_global_skill_opencode(skill: &Skill) -> Result<()> {
 
 /// Write a skill to a project's OpenCode config ({project}/.opencode/)
 pub fn write_project_skill_opencode(project_path: &Path, skill: &Skill) -> Result<()> {
-    let opencode_dir = project_path.join(".opencode");
+    let opencode_dir = project_opencode_dir(project_path);
     write_skill_file_opencode(&opencode_dir, skill)
 }
 
 /// Delete a skill from a project's OpenCode config
 pub fn delete_project_skill_opencode(project_path: &Path, skill: &Skill) -> Result<()> {
-    let opencode_dir = project_path.join(".opencode");
+    let opencode_dir = project_opencode_dir(project_path);
     delete_skill_file_opencode(&opencode_dir, skill)
 }
 
@@ -385,9 +432,14 @@ mod tests {
 
         write_skill_file_opencode(temp_dir.path(), &skill).unwrap();
 
-        // OpenCode uses "agent" not "skills"
-        let expected_path = temp_dir.path().join("agent").join("test-agent.md");
+        // OpenCode uses "skill" (singular); "agent" is for subagents
+        let expected_path = temp_dir
+            .path()
+            .join("skill")
+            .join("test-agent")
+            .join("SKILL.md");
         assert!(expected_path.exists());
+        assert!(!temp_dir.path().join("agent").exists());
     }
 
     #[test]
@@ -396,11 +448,49 @@ mod tests {
         let skill = sample_skill();
 
         write_skill_file_opencode(temp_dir.path(), &skill).unwrap();
-        let file_path = temp_dir.path().join("agent").join("test-agent.md");
-        assert!(file_path.exists());
+        let skill_dir = temp_dir.path().join("skill").join("test-agent");
+        assert!(skill_dir.exists());
 
         delete_skill_file_opencode(temp_dir.path(), &skill).unwrap();
-        assert!(!file_path.exists());
+        assert!(!skill_dir.exists());
+    }
+
+    #[test]
+    fn test_opencode_skill_removes_legacy_agent_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let skill = sample_skill();
+        let agent_dir = temp_dir.path().join("agent");
+        std::fs::create_dir_all(&agent_dir).unwrap();
+        let legacy = agent_dir.join("test-agent.md");
+
+        std::fs::write(&legacy, generate_skill_markdown(&skill)).unwrap();
+        write_skill_file_opencode(temp_dir.path(), &skill).unwrap();
+        assert!(!legacy.exists());
+
+        std::fs::write(&legacy, generate_skill_markdown(&skill)).unwrap();
+        delete_skill_file_opencode(temp_dir.path(), &skill).unwrap();
+        assert!(!legacy.exists());
+
+        // A subagent of the same name is left alone
+        std::fs::write(&legacy, "---\ndescription: \"A subagent\"\n---\n\nHi").unwrap();
+        write_skill_file_opencode(temp_dir.path(), &skill).unwrap();
+        delete_skill_file_opencode(temp_dir.path(), &skill).unwrap();
+        assert!(legacy.exists());
+    }
+
+    #[test]
+    fn test_project_skill_layout_per_editor() {
+        let temp_dir = TempDir::new().unwrap();
+        let skill = sample_skill();
+        write_project_skill(temp_dir.path(), &skill).unwrap();
+        write_project_skill_opencode(temp_dir.path(), &skill).unwrap();
+
+        let root = temp_dir.path();
+        assert!(root.join(".claude/skills/test-agent/SKILL.md").is_file());
+        assert!(root.join(".opencode/skill/test-agent/SKILL.md").is_file());
+        // Neither editor's skill lands where that editor keeps subagents
+        assert!(!root.join(".claude/agents").exists());
+        assert!(!root.join(".opencode/agent").exists());
     }
 
     // =========================================================================
@@ -469,8 +559,9 @@ mod tests {
         let expected = temp_dir
             .path()
             .join(".opencode")
-            .join("agent")
-            .join("test-agent.md");
+            .join("skill")
+            .join("test-agent")
+            .join("SKILL.md");
         assert!(expected.exists());
     }
 
@@ -484,8 +575,8 @@ mod tests {
         let expected = temp_dir
             .path()
             .join(".opencode")
-            .join("agent")
-            .join("test-agent.md");
+            .join("skill")
+            .join("test-agent");
         assert!(!expected.exists());
     }
 
@@ -1,3 +1,4 @@
+use crate::services::pending_changes::PendingChange;
 use anyhow::Result;
 use directories::BaseDirs;
 use serde_json::{json, Value};
@@ -5,27 +6,23 @@ use std::path::Path;
 
 /// Read an existing settings.json file or return an empty object
 fn read_settings_file(path: &Path) -> Result<Value> {
-    if path.exists() {
-        let content = std::fs::read_to_string(path)?;
-        Ok(serde_json::from_str(&content).unwrap_or(json!({})))
-    } else {
-        Ok(json!({}))
+    match crate::services::pending_changes::read_config_file(path)? {
+        Some(content) => Ok(serde_json::from_str(&content).unwrap_or(json!({}))),
+        None => Ok(json!({})),
     }
 }
 
 /// Write settings.json file, preserving other settings
-fn write_settings_file(path: &Path, settings: &Value) -> Result<()> {
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
-    crate::utils::backup::backup_file(path)?;
+fn write_settings_file(path: &Path, settings: &Value) -> Result<Option<PendingChange>> {
     let content = serde_json::to_string_pretty(settings)?;
-    std::fs::write(path, content)?;
-    Ok(())
+    crate::services::pending_changes::write_config_file(path, &content)
 }
 
 /// Write the spinnerVerbs key to ~/.claude/settings.json
-pub fn write_spinner_verbs_to_settings(mode: &str, verbs: &[String]) -> Result<()> {
+pub fn write_spinner_verbs_to_settings(
+    mode: &str,
+    verbs: &[String],
+) -> Result<Option<PendingChange>> {
     let base_dirs =
         BaseDirs::new().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
     let home = base_dirs.home_dir();
@@ -43,7 +40,7 @@ pub fn write_spinner_verbs_to_settings(mode: &str, verbs: &[String]) -> Result<(
 }
 
 /// Remove the spinnerVerbs key from ~/.claude/settings.json
-pub fn remove_spinner_verbs_from_settings() -> Result<()> {
+pub fn remove_spinner_verbs_from_settings() -> Result<Option<PendingChange>> {
     let base_dirs =
         BaseDirs::new().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
     let home = base_dirs.home_dir();
@@ -79,7 +76,7 @@ mod tests {
         settings_path: &Path,
         mode: &str,
         verbs: &[String],
-    ) -> Result<()> {
+    ) -> Result<Option<PendingChange>> {
         let mut settings = read_settings_file(settings_path)?;
         let mut sv_config = serde_json::Map::new();
         sv_config.insert("mode".to_string(), json!(mode));
@@ -171,7 +168,7 @@ mod tests {
         );
     }
 
-    fn remove_spinner_verbs_from_path(settings_path: &Path) -> anyhow::Result<()> {
+    fn remove_spinner_verbs_from_path(settings_path: &Path) -> Result<Option<PendingChange>> {
         let mut settings = read_settings_file(settings_path)?;
         if let Some(obj) = settings.as_object_mut() {
             obj.remove("spinnerVerbs");
@@ -1,37 +1,43 @@
 use crate::db::models::StatusLineSegment;
-use anyhow::Result;
+use crate::services::pending_changes::{PendingChange, WithPendingChanges};
+use crate::utils::paths::get_claude_paths;
+use anyhow::{anyhow, Result};
 use directories::BaseDirs;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Read an existing settings.json file or return an empty object
 fn read_settings_file(path: &Path) -> Result<Value> {
-    if path.exists() {
-        let content = std::fs::read_to_string(path)?;
-        Ok(serde_json::from_str(&content).unwrap_or(json!({})))
-    } else {
-        Ok(json!({}))
+    match crate::services::pending_changes::read_config_file(path)? {
+        Some(content) => Ok(serde_json::from_str(&content).unwrap_or(json!({}))),
+        None => Ok(json!({})),
     }
 }
 
 /// Write settings.json file, preserving other settings
-fn write_settings_file(path: &Path, settings: &Value) -> Result<()> {
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
+fn write_settings_file(path: &Path, settings: &Value) -> Result<Option<PendingChange>> {
     let content = serde_json::to_string_pretty(settings)?;
-    std::fs::write(path, content)?;
-    Ok(())
+    crate::services::pending_changes::write_config_file(path, &content)
 }
 
 /// Write the statusLine key to ~/.claude/settings.json
-pub fn write_statusline_to_settings(command: &str, padding: i32) -> Result<()> {
+pub fn write_statusline_to_settings(command: &str, padding: i32) -> Result<Option<PendingChange>> {
     let base_dirs =
         BaseDirs::new().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
     let home = base_dirs.home_dir();
     let settings_path = home.join(".claude").join("settings.json");
 
-    let mut settings = read_settings_file(&settings_path)?;
+    write_statusline_to_settings_file(&settings_path, command, padding)
+}
+
+/// Write the statusLine key to a settings.json file, preserving other keys
+fn write_statusline_to_settings_file(
+    settings_path: &Path,
+    command: &str,
+    padding: i32,
+) -> Result<Option<PendingChange>> {
+    let mut settings = read_settings_file(settings_path)?;
 
     let mut sl_config = serde_json::Map::new();
     sl_config.insert("type".to_string(), json!("command"));
@@ -42,23 +48,69 @@ pub fn write_statusline_to_settings(command: &str, padding: i32) -> Result<()> {
 
     settings["statusLine"] = Value::Object(sl_config);
 
-    write_settings_file(&settings_path, &settings)
+    write_settings_file(settings_path, &settings)
 }
 
 /// Remove the statusLine key from ~/.claude/settings.json
-pub fn remove_statusline_from_settings() -> Result<()> {
+pub fn remove_statusline_from_settings() -> Result<Option<PendingChange>> {
     let base_dirs =
         BaseDirs::new().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
     let home = base_dirs.home_dir();
     let settings_path = home.join(".claude").join("settings.json");
 
-    let mut settings = read_settings_file(&settings_path)?;
+    remove_statusline_from_settings_file(&settings_path)
+}
+
+/// Remove the statusLine key from a settings.json file, preserving other keys
+fn remove_statusline_from_settings_file(settings_path: &Path) -> Result<Option<PendingChange>> {
+    let mut settings = read_settings_file(settings_path)?;
 
     if let Some(obj) = settings.as_object_mut() {
         obj.remove("statusLine");
     }
 
-    write_settings_file(&settings_path, &settings)
+    write_settings_file(settings_path, &settings)
+}
+
+/// Get the path to a project's generated statusline script
+pub fn get_project_statusline_script_path(project_path: &Path) -> PathBuf {
+    project_path.join(".claude").join("statusline.py")
+}
+
+/// Write the generated script to {project}/.claude/statusline.py
+pub fn write_project_statusline_script(
+    project_path: &Path,
+    script_content: &str,
+) -> Result<Option<PendingChange>> {
+    let script_path = get_project_statusline_script_path(project_path);
+    crate::services::pending_changes::write_config_file(&script_path, script_content)
+}
+
+/// Write the statusLine key to {project}/.claude/settings.json
+pub fn write_statusline_to_project_settings(
+    project_path: &Path,
+    command: &str,
+    padding: i32,
+) -> Result<Option<PendingChange>> {
+    let settings_path = project_path.join(".claude").join("settings.json");
+    write_statusline_to_settings_file(&settings_path, command, padding)
+}
+
+/// Remove the statusLine key from {project}/.claude/settings.json and delete
+/// the generated script. The script is kept while the settings change is
+/// waiting for confirmation, since the file on disk still runs it.
+pub fn remove_project_statusline(project_path: &Path) -> Result<Option<PendingChange>> {
+    let settings_path = project_path.join(".claude").join("settings.json");
+    let change = if settings_path.exists() {
+        remove_statusline_from_settings_file(&settings_path)?
+    } else {
+        None
+    };
+    let script_path = get_project_statusline_script_path(project_path);
+    if change.is_none() && script_path.exists() {
+        std::fs::remove_file(script_path)?;
+    }
+    Ok(change)
 }
 
 /// Read the current statusLine config from ~/.claude/settings.json
@@ -73,21 +125,165 @@ pub fn read_current_statusline_config() -> Result<Option<Value>> {
 }
 
 /// Get the path to the generated statusline script
-pub fn get_statusline_script_path() -> Result<std::path::PathBuf> {
+pub fn get_statusline_script_path() -> Result<PathBuf> {
     let base_dirs =
         BaseDirs::new().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
     let home = base_dirs.home_dir();
     Ok(home.join(".claude").join("statusline.py"))
 }
 
-/// Write the generated Python script to ~/.claude/statusline.py
-pub fn write_statusline_script(script_content: &str) -> Result<std::path::PathBuf> {
-    let script_path = get_statusline_script_path()?;
-    if let Some(parent) = script_path.parent() {
-        std::fs::create_dir_all(parent)?;
+/// Write a status line script to `script_path`, creating its directory
+pub fn write_statusline_script_at(
+    script_path: &Path,
+    script_content: &str,
+) -> Result<Option<PendingChange>> {
+    crate::services::pending_changes::write_config_file(script_path, script_content)
+}
+
+/// Read a status line script, or `None` if it doesn't exist
+pub fn read_statusline_script_at(script_path: &Path) -> Result<Option<String>> {
+    match std::fs::read_to_string(script_path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
     }
-    std::fs::write(&script_path, script_content)?;
-    Ok(script_path)
+}
+
+/// A snapshot of the global status line: settings.json's `statusLine` block
+/// and `statusline.py`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatuslineBackup {
+    /// Directory name under the statusline backups directory
+    pub id: String,
+    pub created_at: String,
+    pub has_status_line: bool,
+    pub has_script: bool,
+}
+
+const BACKUP_SETTINGS_FILE: &str = "statusLine.json";
+const BACKUP_SCRIPT_FILE: &str = "statusline.py";
+
+/// Where statusline backups live: ~/.claude/backups/statusline
+pub fn get_statusline_backups_dir() -> Result<PathBuf> {
+    Ok(get_claude_paths()?
+        .claude_dir
+        .join("backups")
+        .join("statusline"))
+}
+
+/// Copy the `statusLine` block of `settings_path` and the script at
+/// `script_path` into a new timestamped directory under `backups_dir`
+pub fn backup_statusline_at(
+    settings_path: &Path,
+    script_path: &Path,
+    backups_dir: &Path,
+) -> Result<StatuslineBackup> {
+    let now = chrono::Utc::now();
+    let stamp = now.format("statusline_%Y%m%d_%H%M%S").to_string();
+    let mut id = stamp.clone();
+    let mut n = 1;
+    while backups_dir.join(&id).exists() {
+        n += 1;
+        id = format!("{}_{}", stamp, n);
+    }
+    let backup_dir = backups_dir.join(&id);
+    std::fs::create_dir_all(&backup_dir)?;
+
+    let status_line = read_settings_file(settings_path)?
+        .get("statusLine")
+        .cloned()
+        .unwrap_or(Value::Null);
+    let created_at = now.to_rfc3339();
+    let snapshot = json!({ "createdAt": created_at, "statusLine": status_line });
+    std::fs::write(
+        backup_dir.join(BACKUP_SETTINGS_FILE),
+        serde_json::to_string_pretty(&snapshot)?,
+    )?;
+
+    let script = read_statusline_script_at(script_path)?;
+    if let Some(ref script) = script {
+        std::fs::write(backup_dir.join(BACKUP_SCRIPT_FILE), script)?;
+    }
+
+    Ok(StatuslineBackup {
+        id,
+        created_at,
+        has_status_line: !status_line.is_null(),
+        has_script: script.is_some(),
+    })
+}
+
+fn read_statusline_backup(backup_dir: &Path) -> Option<(StatuslineBackup, Value)> {
+    let id = backup_dir.file_name()?.to_str()?.to_string();
+    let content = std::fs::read_to_string(backup_dir.join(BACKUP_SETTINGS_FILE)).ok()?;
+    let snapshot: Value = serde_json::from_str(&content).ok()?;
+    let status_line = snapshot.get("statusLine").cloned().unwrap_or(Value::Null);
+    let backup = StatuslineBackup {
+        id,
+        created_at: snapshot
+            .get("createdAt")
+            .and_then(|c| c.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        has_status_line: !status_line.is_null(),
+        has_script: backup_dir.join(BACKUP_SCRIPT_FILE).exists(),
+    };
+    Some((backup, status_line))
+}
+
+/// Backups under `backups_dir`, newest first
+pub fn list_statusline_backups_at(backups_dir: &Path) -> Result<Vec<StatuslineBackup>> {
+    let Ok(entries) = std::fs::read_dir(backups_dir) else {
+        return Ok(Vec::new());
+    };
+    let mut backups: Vec<StatuslineBackup> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| read_statusline_backup(&e.path()).map(|(backup, _)| backup))
+        .collect();
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+    Ok(backups)
+}
+
+/// Put back the `statusLine` block and script saved in backup `backup_id`.
+/// Whatever the backup didn't have is removed, so the result matches the
+/// state at backup time; other settings.json keys are left alone.
+pub fn restore_statusline_at(
+    settings_path: &Path,
+    script_path: &Path,
+    backups_dir: &Path,
+    backup_id: &str,
+) -> Result<WithPendingChanges<StatuslineBackup>> {
+    if backup_id.is_empty() || backup_id.contains(['/', '\\']) || backup_id.starts_with('.') {
+        return Err(anyhow!("Invalid statusline backup id '{}'", backup_id));
+    }
+    let backup_dir = backups_dir.join(backup_id);
+    let (backup, status_line) = read_statusline_backup(&backup_dir)
+        .ok_or_else(|| anyhow!("Statusline backup '{}' not found", backup_id))?;
+
+    let mut settings = read_settings_file(settings_path)?;
+    if status_line.is_null() {
+        if let Some(obj) = settings.as_object_mut() {
+            obj.remove("statusLine");
+        }
+    } else {
+        settings["statusLine"] = status_line;
+    }
+    let mut pending_changes: Vec<PendingChange> = write_settings_file(settings_path, &settings)?
+        .into_iter()
+        .collect();
+
+    match read_statusline_script_at(&backup_dir.join(BACKUP_SCRIPT_FILE))? {
+        Some(script) => pending_changes.extend(write_statusline_script_at(script_path, &script)?),
+        None if script_path.exists() => std::fs::remove_file(script_path)?,
+        None => {}
+    }
+
+    Ok(WithPendingChanges {
+        item: backup,
+        pending_changes,
+    })
 }
 
 /// Generate a Python 3 script from status line segments
@@ -2312,20 +2508,16 @@ mod tests {
     }
 
     // =========================================================================
-    // Additional coverage: write_statusline_script
+    // Additional coverage: write_statusline_script_at
     // =========================================================================
 
     #[test]
-    fn test_write_statusline_script_creates_file() {
-        // This writes to ~/.claude/statusline.py in real fs
-        let result = write_statusline_script("#!/usr/bin/env python3\nprint('test')");
-        if let Ok(path) = result {
-            assert!(path.exists());
-            let content = std::fs::read_to_string(&path).unwrap();
-            assert!(content.contains("print('test')"));
-            // Clean up
-            let _ = std::fs::remove_file(&path);
-        }
+    fn test_write_statusline_script_at_creates_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".claude").join("statusline.py");
+        write_statusline_script_at(&path, "#!/usr/bin/env python3\nprint('test')").unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("print('test')"));
     }
 
     // =========================================================================
@@ -2496,4 +2688,97 @@ mod tests {
         assert_eq!(get_powerline_default_bg("version"), "gray");
         assert_eq!(get_powerline_default_bg("custom_text"), "gray");
     }
+
+    #[test]
+    fn test_project_statusline_preserves_other_settings() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let project = temp_dir.path();
+        let settings_path = project.join(".claude").join("settings.json");
+        std::fs::create_dir_all(settings_path.parent().unwrap()).unwrap();
+        std::fs::write(&settings_path, r#"{"model": "opus"}"#).unwrap();
+
+        write_project_statusline_script(project, "print('hi')").unwrap();
+        let script_path = get_project_statusline_script_path(project);
+        write_statusline_to_project_settings(project, "python3 statusline.py", 2).unwrap();
+
+        let settings: Value =
+            serde_json::from_str(&std::fs::read_to_string(&settings_path).unwrap()).unwrap();
+        assert_eq!(settings["model"], "opus");
+        assert_eq!(settings["statusLine"]["command"], "python3 statusline.py");
+        assert_eq!(settings["statusLine"]["padding"], 2);
+        assert!(script_path.exists());
+
+        remove_project_statusline(project).unwrap();
+        let settings: Value =
+            serde_json::from_str(&std::fs::read_to_string(&settings_path).unwrap()).unwrap();
+        assert_eq!(settings, json!({"model": "opus"}));
+        assert!(!script_path.exists());
+    }
+
+    #[test]
+    fn test_statusline_backup_and_restore() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let claude = temp_dir.path();
+        let settings_path = claude.join("settings.json");
+        let script_path = claude.join("statusline.py");
+        let backups = claude.join("backups").join("statusline");
+
+        std::fs::write(
+            &settings_path,
+            r#"{"model": "opus", "statusLine": {"type": "command", "command": "python3 statusline.py"}}"#,
+        )
+        .unwrap();
+        std::fs::write(&script_path, "print('v1')").unwrap();
+        let first = backup_statusline_at(&settings_path, &script_path, &backups).unwrap();
+        assert!(first.has_status_line && first.has_script);
+
+        // A risky experiment: new command, new script, and an unrelated change
+        write_statusline_to_settings_file(&settings_path, "bash experiment.sh", 0).unwrap();
+        std::fs::write(&script_path, "print('v2')").unwrap();
+        let mut settings = read_settings_file(&settings_path).unwrap();
+        settings["theme"] = json!("dark");
+        write_settings_file(&settings_path, &settings).unwrap();
+
+        let second = backup_statusline_at(&settings_path, &script_path, &backups).unwrap();
+        assert_ne!(first.id, second.id);
+        let listed = list_statusline_backups_at(&backups).unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].id, second.id);
+
+        restore_statusline_at(&settings_path, &script_path, &backups, &first.id).unwrap();
+        let settings = read_settings_file(&settings_path).unwrap();
+        assert_eq!(settings["statusLine"]["command"], "python3 statusline.py");
+        assert_eq!(settings["model"], "opus");
+        assert_eq!(settings["theme"], "dark");
+        assert_eq!(
+            std::fs::read_to_string(&script_path).unwrap(),
+            "print('v1')"
+        );
+
+        assert!(restore_statusline_at(&settings_path, &script_path, &backups, "../x").is_err());
+        assert!(restore_statusline_at(&settings_path, &script_path, &backups, "nope").is_err());
+    }
+
+    #[test]
+    fn test_restore_statusline_removes_what_backup_lacked() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let claude = temp_dir.path();
+        let settings_path = claude.join("settings.json");
+        let script_path = claude.join("statusline.py");
+        let backups = claude.join("backups");
+
+        std::fs::write(&settings_path, r#"{"model": "opus"}"#).unwrap();
+        let empty = backup_statusline_at(&settings_path, &script_path, &backups).unwrap();
+        assert!(!empty.has_status_line && !empty.has_script);
+
+        write_statusline_to_settings_file(&settings_path, "python3 statusline.py", 0).unwrap();
+        std::fs::write(&script_path, "print('new')").unwrap();
+
+        restore_statusline_at(&settings_path, &script_path, &backups, &empty.id).unwrap();
+        assert_eq!(
+            read_settings_file(&settings_path).unwrap(),
+            json!({"model": "opus"})
+        );
+        assert!(!script_path.exists());
+    }
 }
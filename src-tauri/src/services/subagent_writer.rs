@@ -1,8 +1,9 @@
 use crate::db::models::SubAgent;
-use crate::utils::opencode_paths::get_opencode_paths;
+use crate::services::scanner::is_all_tools_wildcard;
+use crate::utils::opencode_paths::{get_opencode_paths, project_opencode_dir};
 use anyhow::Result;
 use directories::BaseDirs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Generate markdown content for a sub-agent (.claude/agents/name.md)
 pub(crate) fn generate_subagent_markdown(subagent: &SubAgent) -> String {
@@ -11,8 +12,9 @@ pub(crate) fn generate_subagent_markdown(subagent: &SubAgent) -> String {
     frontmatter.push_str(&format!("name: {}\n", subagent.name));
     frontmatter.push_str(&format!("description: {}\n", subagent.description));
 
+    // A wildcard grants every tool, which Claude Code expects as no `tools:` field
     if let Some(ref tools) = subagent.tools {
-        if !tools.is_empty() {
+        if !tools.is_empty() && !tools.iter().any(|t| is_all_tools_wildcard(t)) {
             frontmatter.push_str(&format!("tools: {}\n", tools.join(", ")));
         }
     }
@@ -88,12 +90,27 @@ pub(crate) fn generate_subagent_markdown(subagent: &SubAgent) -> String {
     format!("{}{}", frontmatter, subagent.content)
 }
 
-/// Write a sub-agent to {base_path}/.claude/agents/{name}.md
+/// File stem for a sub-agent's file in either editor: the imported file's
+/// stem when its frontmatter name differs, otherwise the name
+pub fn subagent_file_stem(subagent: &SubAgent) -> &str {
+    subagent.file_name.as_deref().unwrap_or(&subagent.name)
+}
+
+/// A sub-agent's file: {base_path}/.claude/agents/{stem}.md
+pub fn subagent_file_path(base_path: &Path, stem: &str) -> PathBuf {
+    base_path
+        .join(".claude")
+        .join("agents")
+        .join(format!("{}.md", stem))
+}
+
+/// Write a sub-agent to {base_path}/.claude/agents/{file stem}.md
 pub fn write_subagent_file(base_path: &Path, subagent: &SubAgent) -> Result<()> {
-    let agents_dir = base_path.join(".claude").join("agents");
-    std::fs::create_dir_all(&agents_dir)?;
+    let file_path = subagent_file_path(base_path, subagent_file_stem(subagent));
+    if let Some(agents_dir) = file_path.parent() {
+        std::fs::create_dir_all(agents_dir)?;
+    }
 
-    let file_path = agents_dir.join(format!("{}.md", subagent.name));
     crate::utils::backup::backup_file(&file_path)?;
     let content = generate_subagent_markdown(subagent);
     std::fs::write(file_path, content)?;
@@ -103,10 +120,7 @@ pub fn write_subagent_file(base_path: &Path, subagent: &SubAgent) -> Result<()>
 
 /// Delete a sub-agent file from {base_path}/.claude/agents/{name}.md
 pub fn delete_subagent_file(base_path: &Path, name: &str) -> Result<()> {
-    let file_path = base_path
-        .join(".claude")
-        .join("agents")
-        .join(format!("{}.md", name));
+    let file_path = subagent_file_path(base_path, name);
     if file_path.exists() {
         std::fs::remove_file(file_path)?;
     }
@@ -148,6 +162,18 @@ pub fn delete_project_subagent(project_path: &Path, name: &str) -> Result<()> {
 // - No "name" field (filename is the name)
 // - No "skills" field
 
+/// OpenCode `permission` entries equivalent to a Claude Code `permissionMode`.
+/// `None` for modes OpenCode has no counterpart for.
+pub(crate) fn opencode_permission(mode: &str) -> Option<&'static [(&'static str, &'static str)]> {
+    match mode {
+        "default" => Some(&[]),
+        "acceptEdits" => Some(&[("edit", "allow")]),
+        "plan" => Some(&[("edit", "deny"), ("bash", "deny")]),
+        "bypassPermissions" => Some(&[("edit", "allow"), ("bash", "allow")]),
+        _ => None,
+    }
+}
+
 /// Generate markdown content for an OpenCode agent (.opencode/agent/name.md)
 pub(crate) fn generate_subagent_markdown_opencode(subagent: &SubAgent) -> String {
     let mut frontmatter = String::from("---\n");
@@ -164,7 +190,7 @@ pub(crate) fn generate_subagent_markdown_opencode(subagent: &SubAgent) -> String
 
     // OpenCode tools format: object with tool names as keys and boolean values
     if let Some(ref tools) = subagent.tools {
-        if !tools.is_empty() {
+        if !tools.is_empty() && !tools.iter().any(|t| is_all_tools_wildcard(t)) {
             frontmatter.push_str("tools:\n");
             for tool in tools {
                 // Convert tool name to lowercase for OpenCode
@@ -174,20 +200,37 @@ pub(crate) fn generate_subagent_markdown_opencode(subagent: &SubAgent) -> String
         }
     }
 
-    // Note: OpenCode uses "permission" object, not "permissionMode" string
-    // We skip permissionMode for OpenCode as the format is different
+    // OpenCode uses a "permission" object rather than a "permissionMode" string
+    if let Some(permission) = subagent
+        .permission_mode
+        .as_deref()
+        .and_then(opencode_permission)
+    {
+        if !permission.is_empty() {
+            frontmatter.push_str("permission:\n");
+            for (tool, action) in permission {
+                frontmatter.push_str(&format!("  {}: {}\n", tool, action));
+            }
+        }
+    }
 
     frontmatter.push_str("---\n\n");
     format!("{}{}", frontmatter, subagent.content)
 }
 
+/// A sub-agent's OpenCode file: {base_path}/agent/{stem}.md (singular "agent")
+pub fn subagent_file_path_opencode(base_path: &Path, stem: &str) -> PathBuf {
+    base_path.join("agent").join(format!("{}.md", stem))
+}
+
 /// Write a sub-agent to OpenCode's format
-/// OpenCode uses {base_path}/agent/{name}.md (singular "agent")
+/// OpenCode uses {base_path}/agent/{file stem}.md (singular "agent")
 pub fn write_subagent_file_opencode(base_path: &Path, subagent: &SubAgent) -> Result<()> {
-    let agents_dir = base_path.join("agent"); // OpenCode uses singular
-    std::fs::create_dir_all(&agents_dir)?;
+    let file_path = subagent_file_path_opencode(base_path, subagent_file_stem(subagent));
+    if let Some(agents_dir) = file_path.parent() {
+        std::fs::create_dir_all(agents_dir)?;
+    }
 
-    let file_path = agents_dir.join(format!("{}.md", subagent.name));
     crate::utils::backup::backup_file(&file_path)?;
     let content = generate_subagent_markdown_opencode(subagent);
     std::fs::write(file_path, content)?;
@@ -197,7 +240,7 @@ pub fn write_subagent_file_opencode(base_path: &Path, subagent: &SubAgent) -> Re
 
 /// Delete a sub-agent file from OpenCode's format
 pub fn delete_subagent_file_opencode(base_path: &Path, name: &str) -> Result<()> {
-    let file_path = base_path.join("agent").join(format!("{}.md", name));
+    let file_path = subagent_file_path_opencode(base_path, name);
     if file_path.exists() {
         std::fs::remove_file(file_path)?;
     }
@@ -218,13 +261,13 @@ pub fn delete_global_subagent_opencode(name: &str) -> Result<()> {
 
 /// Write a sub-agent to a project's OpenCode config ({project}/.opencode/agent/)
 pub fn write_project_subagent_opencode(project_path: &Path, subagent: &SubAgent) -> Result<()> {
-    let opencode_dir = project_path.join(".opencode");
+    let opencode_dir = project_opencode_dir(project_path);
     write_subagent_file_opencode(&opencode_dir, subagent)
 }
 
 /// Delete a sub-agent from a project's OpenCode config
 pub fn delete_project_subagent_opencode(project_path: &Path, name: &str) -> Result<()> {
-    let opencode_dir = project_path.join(".opencode");
+    let opencode_dir = project_opencode_dir(project_path);
     delete_subagent_file_opencode(&opencode_dir, name)
 }
 
@@ -262,6 +305,7 @@ mod tests {
             initial_prompt: None,
             created_at: "2024-01-01".to_string(),
             updated_at: "2024-01-01".to_string(),
+            file_name: None,
         }
     }
 
@@ -290,6 +334,7 @@ mod tests {
             initial_prompt: None,
             created_at: "2024-01-01".to_string(),
             updated_at: "2024-01-01".to_string(),
+            file_name: None,
         }
     }
 
@@ -486,6 +531,21 @@ mod tests {
         assert!(expected_path.exists());
     }
 
+    #[test]
+    fn test_write_subagent_file_opencode_uses_file_stem() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut subagent = sample_minimal_subagent();
+        subagent.file_name = Some("reviewer-v2".to_string());
+
+        write_subagent_file_opencode(temp_dir.path(), &subagent).unwrap();
+
+        assert!(temp_dir.path().join("agent/reviewer-v2.md").exists());
+        assert!(!temp_dir
+            .path()
+            .join(format!("agent/{}.md", subagent.name))
+            .exists());
+    }
+
     #[test]
     fn test_delete_subagent_file_opencode() {
         let temp_dir = TempDir::new().unwrap();
@@ -544,6 +604,19 @@ mod tests {
         assert!(!md.contains("permissionMode:"));
     }
 
+    #[test]
+    fn test_generate_subagent_markdown_opencode_maps_permission_mode() {
+        let mut subagent = sample_full_subagent();
+        subagent.permission_mode = Some("plan".to_string());
+        let md = generate_subagent_markdown_opencode(&subagent);
+        assert!(md.contains("permission:\n  edit: deny\n  bash: deny\n"));
+
+        subagent.permission_mode = Some("default".to_string());
+        assert!(!generate_subagent_markdown_opencode(&subagent).contains("permission:"));
+        subagent.permission_mode = Some("dontAsk".to_string());
+        assert!(!generate_subagent_markdown_opencode(&subagent).contains("permission:"));
+    }
+
     #[test]
     fn test_generate_subagent_markdown_opencode_quoted_description() {
         let subagent = sample_full_subagent();
@@ -620,6 +693,19 @@ mod tests {
         assert!(expected_path.exists());
     }
 
+    #[test]
+    fn test_project_subagent_layout_per_editor() {
+        let temp_dir = TempDir::new().unwrap();
+        let subagent = sample_full_subagent();
+        write_project_subagent(temp_dir.path(), &subagent).unwrap();
+        write_project_subagent_opencode(temp_dir.path(), &subagent).unwrap();
+
+        let root = temp_dir.path();
+        assert!(root.join(".claude/agents/code-reviewer.md").is_file());
+        assert!(root.join(".opencode/agent/code-reviewer.md").is_file());
+        assert!(!root.join(".opencode/agents").exists());
+    }
+
     #[test]
     fn test_delete_project_subagent_opencode() {
         let temp_dir = TempDir::new().unwrap();
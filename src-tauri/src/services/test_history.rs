@@ -0,0 +1,146 @@
+//! MCP test history
+//!
+//! Records the outcome and response time of every MCP test so the startup
+//! cost of each MCP can be judged from more than the latest run.
+
+use crate::db::Database;
+use crate::services::mcp_client::McpTestResult;
+use anyhow::Result;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+/// Results kept per MCP; older rows are pruned on insert
+const MAX_HISTORY_PER_MCP: i64 = 200;
+
+/// Handshake latency percentiles over an MCP's successful tests
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpLatencyStats {
+    pub mcp_id: i64,
+    /// Successful tests the stats are computed from
+    pub samples: usize,
+    pub p50: Option<u64>,
+    pub p95: Option<u64>,
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+}
+
+/// Record a test result. Skipped (not testable) results are ignored.
+pub fn record_test_result(db: &Database, mcp_id: i64, result: &McpTestResult) -> Result<()> {
+    if result.skipped {
+        return Ok(());
+    }
+    db.conn().execute(
+        "INSERT INTO mcp_test_history (mcp_id, success, response_time_ms, error) VALUES (?, ?, ?, ?)",
+        params![
+            mcp_id,
+            result.success,
+            result.response_time_ms as i64,
+            result.error
+        ],
+    )?;
+    db.conn().execute(
+        "DELETE FROM mcp_test_history WHERE mcp_id = ?1 AND id NOT IN (
+             SELECT id FROM mcp_test_history WHERE mcp_id = ?1 ORDER BY id DESC LIMIT ?2
+         )",
+        params![mcp_id, MAX_HISTORY_PER_MCP],
+    )?;
+    Ok(())
+}
+
+/// Nearest-rank percentile of an ascending slice
+fn percentile(sorted: &[u64], pct: f64) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((pct / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// Latency stats for an MCP. Failed tests are left out because timeouts and
+/// spawn errors say nothing about how long a working handshake takes.
+pub fn get_latency_stats(db: &Database, mcp_id: i64) -> Result<McpLatencyStats> {
+    let mut stmt = db.conn().prepare(
+        "SELECT response_time_ms FROM mcp_test_history
+         WHERE mcp_id = ? AND success = 1 ORDER BY response_time_ms",
+    )?;
+    let times = stmt
+        .query_map([mcp_id], |row| row.get::<_, i64>(0))?
+        .map(|t| t.map(|t| t.max(0) as u64))
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(McpLatencyStats {
+        mcp_id,
+        samples: times.len(),
+        p50: percentile(&times, 50.0),
+        p95: percentile(&times, 95.0),
+        min: times.first().copied(),
+        max: times.last().copied(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::CreateMcpRequest;
+    use crate::services::mcp_client::McpServerInfo;
+
+    fn create_mcp(db: &Database) -> i64 {
+        db.create_mcp(&CreateMcpRequest {
+            name: "slow".to_string(),
+            description: None,
+            mcp_type: "stdio".to_string(),
+            command: Some("npx".to_string()),
+            args: None,
+            url: None,
+            headers: None,
+            env: None,
+            icon: None,
+            tags: None,
+            testable: None,
+            env_file: None,
+            display_name: None,
+            inherit_env: None,
+            shell: None,
+            split_command: None,
+        })
+        .unwrap()
+        .id
+    }
+
+    fn ok(ms: u64) -> McpTestResult {
+        let info = McpServerInfo {
+            name: "slow".to_string(),
+            version: None,
+            protocol_version: None,
+        };
+        McpTestResult::success(info, vec![], false, false, ms)
+    }
+
+    #[test]
+    fn test_latency_stats_from_successful_tests() {
+        let db = Database::in_memory().unwrap();
+        let mcp_id = create_mcp(&db);
+
+        for ms in 1..=20 {
+            record_test_result(&db, mcp_id, &ok(ms * 100)).unwrap();
+        }
+        record_test_result(&db, mcp_id, &McpTestResult::error("timeout".into(), 30_000)).unwrap();
+        record_test_result(&db, mcp_id, &McpTestResult::not_testable()).unwrap();
+
+        let stats = get_latency_stats(&db, mcp_id).unwrap();
+        assert_eq!(stats.samples, 20);
+        assert_eq!(stats.p50, Some(1_000));
+        assert_eq!(stats.p95, Some(1_900));
+        assert_eq!(stats.min, Some(100));
+        assert_eq!(stats.max, Some(2_000));
+    }
+
+    #[test]
+    fn test_latency_stats_without_history() {
+        let db = Database::in_memory().unwrap();
+        let stats = get_latency_stats(&db, create_mcp(&db)).unwrap();
+        assert_eq!(stats.samples, 0);
+        assert_eq!(stats.p95, None);
+    }
+}
@@ -0,0 +1,340 @@
+//! Cached MCP tools
+//!
+//! Stores the tool list each MCP last reported (on a successful test or a
+//! gateway connect) so tools can be browsed and searched without a handshake.
+//! Full schemas are always stored; listings can swap schemas above a size
+//! threshold for a summary, with [`get_full_tool_schema`] for the rest.
+
+use crate::db::Database;
+use crate::services::mcp_client::McpTool;
+use anyhow::{anyhow, Result};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Setting holding the schema size (bytes of JSON) above which listings
+/// summarize a tool's input schema; `0` turns summarizing off
+pub const SCHEMA_SUMMARY_THRESHOLD_SETTING: &str = "tool_schema_summary_threshold";
+
+/// Threshold used when the setting is unset or invalid
+pub const DEFAULT_SCHEMA_SUMMARY_THRESHOLD: usize = 16 * 1024;
+
+/// A cached tool matched by [`search_cached_tools`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedToolMatch {
+    pub mcp_id: i64,
+    pub mcp_name: String,
+    pub tool: McpTool,
+    /// "name" or "description"
+    pub matched_on: String,
+}
+
+/// Replace the cached tools of an MCP with `tools`
+pub fn cache_mcp_tools(db: &Database, mcp_id: i64, tools: &[McpTool]) -> Result<()> {
+    let tx = db.conn().unchecked_transaction()?;
+    tx.execute("DELETE FROM mcp_tools WHERE mcp_id = ?", [mcp_id])?;
+    for tool in tools {
+        let schema = tool
+            .input_schema
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+        tx.execute(
+            "INSERT OR REPLACE INTO mcp_tools (mcp_id, name, description, input_schema)
+             VALUES (?, ?, ?, ?)",
+            params![mcp_id, tool.name, tool.description, schema],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// The cached tools of one MCP, ordered by name
+pub fn get_cached_mcp_tools(db: &Database, mcp_id: i64) -> Result<Vec<McpTool>> {
+    let mut stmt = db.conn().prepare(
+        "SELECT name, description, input_schema FROM mcp_tools WHERE mcp_id = ? ORDER BY name",
+    )?;
+    let tools = stmt
+        .query_map([mcp_id], row_to_tool)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(tools)
+}
+
+fn row_to_tool(row: &rusqlite::Row) -> rusqlite::Result<McpTool> {
+    Ok(McpTool {
+        name: row.get("name")?,
+        description: row.get("description")?,
+        input_schema: row
+            .get::<_, Option<String>>("input_schema")?
+            .and_then(|s| serde_json::from_str(&s).ok()),
+    })
+}
+
+/// The configured summary threshold, `None` when summarizing is off
+pub fn schema_summary_threshold(db: &Database) -> Option<usize> {
+    let threshold = db
+        .get_setting(SCHEMA_SUMMARY_THRESHOLD_SETTING)
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .unwrap_or(DEFAULT_SCHEMA_SUMMARY_THRESHOLD);
+    (threshold > 0).then_some(threshold)
+}
+
+/// Top-level property names and the required list of a schema, marked with
+/// `"summarized": true` and the size of the full schema
+pub fn summarize_schema(schema: &Value, full_size: usize) -> Value {
+    let properties: Vec<&str> = schema
+        .get("properties")
+        .and_then(|p| p.as_object())
+        .map(|p| p.keys().map(String::as_str).collect())
+        .unwrap_or_default();
+    let required = schema
+        .get("required")
+        .filter(|r| r.is_array())
+        .cloned()
+        .unwrap_or_else(|| json!([]));
+    json!({
+        "type": schema.get("type").cloned().unwrap_or_else(|| json!("object")),
+        "summarized": true,
+        "fullSize": full_size,
+        "propertyNames": properties,
+        "required": required,
+    })
+}
+
+/// Replace each tool's schema with its summary when its JSON is larger than
+/// `threshold` bytes
+pub fn summarize_large_schemas(tools: &mut [McpTool], threshold: Option<usize>) {
+    let Some(threshold) = threshold else {
+        return;
+    };
+    for tool in tools {
+        if let Some(schema) = &tool.input_schema {
+            let size = serde_json::to_string(schema).map_or(0, |s| s.len());
+            if size > threshold {
+                tool.input_schema = Some(summarize_schema(schema, size));
+            }
+        }
+    }
+}
+
+/// The complete cached input schema of one tool. `Ok(None)` when the tool is
+/// cached without a schema; an error when the tool isn't cached at all.
+pub fn get_full_tool_schema(db: &Database, mcp_id: i64, tool_name: &str) -> Result<Option<Value>> {
+    let schema: Option<String> = db
+        .conn()
+        .query_row(
+            "SELECT input_schema FROM mcp_tools WHERE mcp_id = ? AND name = ?",
+            params![mcp_id, tool_name],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| anyhow!("No cached tool '{}' for MCP {}", tool_name, mcp_id))?;
+    Ok(schema.map(|s| serde_json::from_str(&s)).transpose()?)
+}
+
+/// Case-insensitive search over cached tool names and descriptions across all
+/// MCPs. Name matches rank first (exact, then prefix, then substring),
+/// followed by description matches.
+pub fn search_cached_tools(db: &Database, query: &str) -> Result<Vec<CachedToolMatch>> {
+    let needle = query.trim().to_lowercase();
+    if needle.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut stmt = db.conn().prepare(
+        "SELECT t.mcp_id, m.name AS mcp_name, t.name, t.description, t.input_schema
+         FROM mcp_tools t
+         JOIN mcps m ON t.mcp_id = m.id
+         WHERE instr(lower(t.name), ?1) > 0 OR instr(lower(coalesce(t.description, '')), ?1) > 0",
+    )?;
+    let rows = stmt
+        .query_map([&needle], |row| {
+            Ok((
+                row.get::<_, i64>("mcp_id")?,
+                row.get::<_, String>("mcp_name")?,
+                row_to_tool(row)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut ranked: Vec<(u8, CachedToolMatch)> = rows
+        .into_iter()
+        .map(|(mcp_id, mcp_name, tool)| {
+            let name = tool.name.to_lowercase();
+            let rank = if name == needle {
+                0
+            } else if name.starts_with(&needle) {
+                1
+            } else if name.contains(&needle) {
+                2
+            } else {
+                3
+            };
+            let matched_on = if rank < 3 { "name" } else { "description" };
+            (
+                rank,
+                CachedToolMatch {
+                    mcp_id,
+                    mcp_name,
+                    tool,
+                    matched_on: matched_on.to_string(),
+                },
+            )
+        })
+        .collect();
+
+    ranked.sort_by(|(ra, a), (rb, b)| {
+        ra.cmp(rb)
+            .then_with(|| a.tool.name.cmp(&b.tool.name))
+            .then_with(|| a.mcp_name.cmp(&b.mcp_name))
+    });
+    Ok(ranked.into_iter().map(|(_, m)| m).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::CreateMcpRequest;
+
+    fn create_mcp(db: &Database, name: &str) -> i64 {
+        db.create_mcp(&CreateMcpRequest {
+            name: name.to_string(),
+            description: None,
+            mcp_type: "stdio".to_string(),
+            command: Some("npx".to_string()),
+            args: None,
+            url: None,
+            headers: None,
+            env: None,
+            icon: None,
+            tags: None,
+            testable: None,
+            env_file: None,
+            display_name: None,
+            inherit_env: None,
+            shell: None,
+            split_command: None,
+        })
+        .unwrap()
+        .id
+    }
+
+    fn tool(name: &str, description: &str) -> McpTool {
+        McpTool {
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            input_schema: Some(serde_json::json!({"type": "object"})),
+        }
+    }
+
+    #[test]
+    fn test_cache_mcp_tools_replaces_previous_set() {
+        let db = Database::in_memory().unwrap();
+        let mcp_id = create_mcp(&db, "fs");
+
+        cache_mcp_tools(
+            &db,
+            mcp_id,
+            &[tool("read_file", "Read"), tool("old", "Gone")],
+        )
+        .unwrap();
+        cache_mcp_tools(&db, mcp_id, &[tool("read_file", "Read a file")]).unwrap();
+
+        let tools = get_cached_mcp_tools(&db, mcp_id).unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].description.as_deref(), Some("Read a file"));
+        assert_eq!(
+            tools[0].input_schema,
+            Some(serde_json::json!({"type": "object"}))
+        );
+    }
+
+    #[test]
+    fn test_search_cached_tools_ranks_name_before_description() {
+        let db = Database::in_memory().unwrap();
+        let fs = create_mcp(&db, "fs");
+        let gh = create_mcp(&db, "github");
+        cache_mcp_tools(
+            &db,
+            fs,
+            &[
+                tool("search_files", "Find files by glob"),
+                tool("read_file", "Read a file, e.g. a search index"),
+            ],
+        )
+        .unwrap();
+        cache_mcp_tools(&db, gh, &[tool("search", "Search code")]).unwrap();
+
+        let matches = search_cached_tools(&db, "SEARCH").unwrap();
+        let names: Vec<_> = matches.iter().map(|m| m.tool.name.as_str()).collect();
+        assert_eq!(names, vec!["search", "search_files", "read_file"]);
+        assert_eq!(matches[0].mcp_name, "github");
+        assert_eq!(matches[2].matched_on, "description");
+
+        assert!(search_cached_tools(&db, "  ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_cached_tools_removed_with_mcp() {
+        let db = Database::in_memory().unwrap();
+        let mcp_id = create_mcp(&db, "fs");
+        cache_mcp_tools(&db, mcp_id, &[tool("read_file", "Read")]).unwrap();
+
+        db.delete_mcp(mcp_id).unwrap();
+        assert!(search_cached_tools(&db, "read").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_summarize_large_schemas() {
+        let db = Database::in_memory().unwrap();
+        let mcp_id = create_mcp(&db, "big");
+        let mut properties = serde_json::Map::new();
+        for i in 0..50 {
+            properties.insert(
+                format!("field_{:02}", i),
+                json!({"type": "string", "description": "x".repeat(40)}),
+            );
+        }
+        let big_schema = json!({
+            "type": "object",
+            "properties": properties,
+            "required": ["field_00"],
+        });
+        let big = McpTool {
+            name: "huge".to_string(),
+            description: None,
+            input_schema: Some(big_schema.clone()),
+        };
+        cache_mcp_tools(&db, mcp_id, &[big, tool("small", "Small")]).unwrap();
+
+        let mut tools = get_cached_mcp_tools(&db, mcp_id).unwrap();
+        summarize_large_schemas(&mut tools, Some(1024));
+        let summary = tools[0].input_schema.as_ref().unwrap();
+        assert_eq!(summary["summarized"], json!(true));
+        assert_eq!(summary["required"], json!(["field_00"]));
+        assert_eq!(summary["propertyNames"].as_array().unwrap().len(), 50);
+        assert_eq!(tools[1].input_schema, Some(json!({"type": "object"})));
+
+        assert_eq!(
+            get_full_tool_schema(&db, mcp_id, "huge").unwrap(),
+            Some(big_schema)
+        );
+        assert!(get_full_tool_schema(&db, mcp_id, "missing").is_err());
+    }
+
+    #[test]
+    fn test_schema_summary_threshold_setting() {
+        let db = Database::in_memory().unwrap();
+        assert_eq!(
+            schema_summary_threshold(&db),
+            Some(DEFAULT_SCHEMA_SUMMARY_THRESHOLD)
+        );
+        db.set_setting(SCHEMA_SUMMARY_THRESHOLD_SETTING, "2048")
+            .unwrap();
+        assert_eq!(schema_summary_threshold(&db), Some(2048));
+        db.set_setting(SCHEMA_SUMMARY_THRESHOLD_SETTING, "0")
+            .unwrap();
+        assert_eq!(schema_summary_threshold(&db), None);
+    }
+}
@@ -0,0 +1,304 @@
+//! Tool reference validation
+//!
+//! Cross-checks the tools listed by a project's skills and sub-agents against
+//! the MCPs the project actually has enabled, so an agent that names a tool no
+//! enabled MCP provides is caught before Claude tries to call it.
+
+use crate::db::Database;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// A tool or skill reference that won't resolve in the project
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolReferenceIssue {
+    /// "skill" or "subagent"
+    pub entity_type: String,
+    pub entity_id: i64,
+    pub entity_name: String,
+    /// The reference as written, e.g. `mcp__github__create_issue`
+    pub reference: String,
+    /// "mcp_not_enabled", "tool_not_found" or "skill_not_available"
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolReferenceReport {
+    pub issues: Vec<ToolReferenceIssue>,
+    /// Enabled MCPs that are referenced but have no cached tools yet, so their
+    /// tool names could not be checked. Testing the MCP fills the cache.
+    pub unverified_mcps: Vec<String>,
+}
+
+/// Server name as it appears in `mcp__<server>__<tool>` tool names
+fn tool_prefix_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Split an MCP tool reference into its server and tool. The tool is `None`
+/// for whole-server references (`mcp__server` or `mcp__server__*`); non-MCP
+/// references such as built-in tools return `None`.
+fn parse_mcp_reference(reference: &str) -> Option<(&str, Option<&str>)> {
+    let rest = reference.trim().strip_prefix("mcp__")?;
+    match rest.split_once("__") {
+        Some((server, "*")) => Some((server, None)),
+        Some((server, tool)) => Some((server, Some(tool))),
+        None => Some((rest, None)),
+    }
+}
+
+fn parse_list(json: Option<String>) -> Vec<String> {
+    json.and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+/// Enabled MCPs of a project (project assignments plus global ones), keyed by
+/// their tool-name prefix
+fn enabled_mcps(db: &Database, project_id: i64) -> Result<HashMap<String, (i64, String)>> {
+    let mut stmt = db.conn().prepare(
+        "SELECT m.id, m.name FROM project_mcps pm JOIN mcps m ON m.id = pm.mcp_id
+         WHERE pm.project_id = ? AND pm.is_enabled = 1
+         UNION
+         SELECT m.id, m.name FROM global_mcps gm JOIN mcps m ON m.id = gm.mcp_id
+         WHERE gm.is_enabled = 1",
+    )?;
+    let rows = stmt
+        .query_map([project_id], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(rows
+        .into_iter()
+        .map(|(id, name)| (tool_prefix_name(&name), (id, name)))
+        .collect())
+}
+
+/// `(id, name, tools JSON, skills JSON)` of a skill or sub-agent
+type EntityRow = (i64, String, Option<String>, Option<String>);
+
+/// The enabled skills or sub-agents of a project, including global ones
+fn enabled_entities(db: &Database, project_id: i64, entity_type: &str) -> Result<Vec<EntityRow>> {
+    let sql = match entity_type {
+        "skill" => {
+            "SELECT s.id, s.name, s.allowed_tools, NULL FROM project_skills ps
+             JOIN skills s ON s.id = ps.skill_id WHERE ps.project_id = ?1 AND ps.is_enabled = 1
+             UNION
+             SELECT s.id, s.name, s.allowed_tools, NULL FROM global_skills gs
+             JOIN skills s ON s.id = gs.skill_id WHERE gs.is_enabled = 1
+             ORDER BY 2"
+        }
+        _ => {
+            "SELECT a.id, a.name, a.tools, a.skills FROM project_subagents pa
+             JOIN subagents a ON a.id = pa.subagent_id WHERE pa.project_id = ?1 AND pa.is_enabled = 1
+             UNION
+             SELECT a.id, a.name, a.tools, a.skills FROM global_subagents ga
+             JOIN subagents a ON a.id = ga.subagent_id WHERE ga.is_enabled = 1
+             ORDER BY 2"
+        }
+    };
+    let mut stmt = db.conn().prepare(sql)?;
+    let rows = stmt
+        .query_map([project_id], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(rows)
+}
+
+fn cached_tool_names(db: &Database, mcp_id: i64) -> Result<HashSet<String>> {
+    let mut stmt = db
+        .conn()
+        .prepare("SELECT name FROM mcp_tools WHERE mcp_id = ?")?;
+    let names = stmt
+        .query_map([mcp_id], |row| row.get(0))?
+        .collect::<rusqlite::Result<HashSet<String>>>()?;
+    Ok(names)
+}
+
+/// Check every enabled skill's `allowed_tools` and sub-agent's `tools` and
+/// `skills` in a project. Built-in tools (anything not prefixed `mcp__`) are
+/// assumed available.
+pub fn validate_tool_references(db: &Database, project_id: i64) -> Result<ToolReferenceReport> {
+    let mcps = enabled_mcps(db, project_id)?;
+    let skills = enabled_entities(db, project_id, "skill")?;
+    let subagents = enabled_entities(db, project_id, "subagent")?;
+    let skill_names: HashSet<&str> = skills.iter().map(|(_, name, _, _)| name.as_str()).collect();
+
+    let mut cached_tools: HashMap<i64, HashSet<String>> = HashMap::new();
+    let mut unverified = Vec::new();
+    let mut report = ToolReferenceReport::default();
+
+    let entities = skills
+        .iter()
+        .map(|e| ("skill", e))
+        .chain(subagents.iter().map(|e| ("subagent", e)));
+    for (entity_type, (id, name, tools, agent_skills)) in entities {
+        let mut issue = |reference: &str, reason: &str| {
+            report.issues.push(ToolReferenceIssue {
+                entity_type: entity_type.to_string(),
+                entity_id: *id,
+                entity_name: name.clone(),
+                reference: reference.to_string(),
+                reason: reason.to_string(),
+            })
+        };
+
+        for reference in parse_list(tools.clone()) {
+            let Some((server, tool)) = parse_mcp_reference(&reference) else {
+                continue;
+            };
+            let Some((mcp_id, mcp_name)) = mcps.get(server) else {
+                issue(&reference, "mcp_not_enabled");
+                continue;
+            };
+            let Some(tool) = tool else {
+                continue;
+            };
+            if !cached_tools.contains_key(mcp_id) {
+                cached_tools.insert(*mcp_id, cached_tool_names(db, *mcp_id)?);
+            }
+            let cached = &cached_tools[mcp_id];
+            if cached.is_empty() {
+                if !unverified.contains(mcp_name) {
+                    unverified.push(mcp_name.clone());
+                }
+            } else if !cached.contains(tool) {
+                issue(&reference, "tool_not_found");
+            }
+        }
+
+        for skill in parse_list(agent_skills.clone()) {
+            if !skill_names.contains(skill.as_str()) {
+                issue(&skill, "skill_not_available");
+            }
+        }
+    }
+
+    unverified.sort();
+    report.unverified_mcps = unverified;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::mcp_client::McpTool;
+    use crate::services::tool_cache::cache_mcp_tools;
+    use rusqlite::params;
+
+    fn insert(db: &Database, sql: &str, values: impl rusqlite::Params) -> i64 {
+        db.conn().execute(sql, values).unwrap();
+        db.conn().last_insert_rowid()
+    }
+
+    fn tool(name: &str) -> McpTool {
+        McpTool {
+            name: name.to_string(),
+            description: None,
+            input_schema: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_mcp_reference() {
+        assert_eq!(
+            parse_mcp_reference("mcp__github__create_issue"),
+            Some(("github", Some("create_issue")))
+        );
+        assert_eq!(parse_mcp_reference("mcp__github"), Some(("github", None)));
+        assert_eq!(
+            parse_mcp_reference("mcp__github__*"),
+            Some(("github", None))
+        );
+        assert_eq!(parse_mcp_reference("Bash(git:*)"), None);
+        assert_eq!(tool_prefix_name("my.server"), "my_server");
+    }
+
+    #[test]
+    fn test_validate_tool_references_reports_unavailable_tools() {
+        let db = Database::in_memory().unwrap();
+        let project = insert(
+            &db,
+            "INSERT INTO projects (name, path) VALUES ('p', '/tmp/p')",
+            [],
+        );
+        let github = insert(
+            &db,
+            "INSERT INTO mcps (name, type, command) VALUES ('github', 'stdio', 'npx')",
+            [],
+        );
+        let docs = insert(
+            &db,
+            "INSERT INTO mcps (name, type, command) VALUES ('docs', 'stdio', 'npx')",
+            [],
+        );
+        insert(
+            &db,
+            "INSERT INTO mcps (name, type, command) VALUES ('slack', 'stdio', 'npx')",
+            [],
+        );
+        for mcp in [github, docs] {
+            insert(
+                &db,
+                "INSERT INTO project_mcps (project_id, mcp_id) VALUES (?, ?)",
+                params![project, mcp],
+            );
+        }
+        cache_mcp_tools(&db, github, &[tool("create_issue")]).unwrap();
+
+        let skill = insert(
+            &db,
+            r#"INSERT INTO skills (name, content, allowed_tools)
+               VALUES ('triage', 'x', '["Read", "mcp__github__create_issue", "mcp__github__delete_repo"]')"#,
+            [],
+        );
+        let agent = insert(
+            &db,
+            r#"INSERT INTO subagents (name, description, content, tools, skills)
+               VALUES ('helper', 'd', 'x', '["mcp__slack__post", "mcp__docs__search", "mcp__github"]', '["triage", "deploy"]')"#,
+            [],
+        );
+        insert(
+            &db,
+            "INSERT INTO project_skills (project_id, skill_id) VALUES (?, ?)",
+            params![project, skill],
+        );
+        insert(
+            &db,
+            "INSERT INTO global_subagents (subagent_id) VALUES (?)",
+            [agent],
+        );
+
+        let report = validate_tool_references(&db, project).unwrap();
+        let found: Vec<(&str, &str, &str)> = report
+            .issues
+            .iter()
+            .map(|i| {
+                (
+                    i.entity_name.as_str(),
+                    i.reference.as_str(),
+                    i.reason.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("triage", "mcp__github__delete_repo", "tool_not_found"),
+                ("helper", "mcp__slack__post", "mcp_not_enabled"),
+                ("helper", "deploy", "skill_not_available"),
+            ]
+        );
+        assert_eq!(report.unverified_mcps, vec!["docs"]);
+    }
+}
@@ -0,0 +1,275 @@
+use crate::services::mcp_json_config::{
+    args_value, render_mcp_servers, string_map_value, write_mcp_servers,
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::Path;
+
+pub use crate::services::mcp_json_config::McpTuple;
+
+/// Parsed MCP from Windsurf format (normalized to internal format)
+#[derive(Debug)]
+pub struct ParsedWindsurfMcp {
+    pub name: String,
+    pub mcp_type: String, // "stdio" or "http"
+    pub command: Option<String>,
+    pub args: Option<Vec<String>>,
+    pub url: Option<String>,
+    pub headers: Option<HashMap<String, String>>,
+    pub env: Option<HashMap<String, String>>,
+}
+
+/// Windsurf mcp_config.json structure (uses mcpServers key like Claude Code)
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct WindsurfMcpConfig {
+    #[serde(default, rename = "mcpServers")]
+    pub mcp_servers: HashMap<String, Value>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// Parse Windsurf mcp_config.json and extract MCP servers
+pub fn parse_windsurf_mcps(path: &Path) -> Result<Vec<ParsedWindsurfMcp>> {
+    let content = std::fs::read_to_string(path)?;
+    let config: WindsurfMcpConfig = serde_json::from_str(&content)?;
+
+    config
+        .mcp_servers
+        .iter()
+        .map(|(name, server_value)| parse_mcp_entry(name, server_value))
+        .collect()
+}
+
+fn string_map(value: Option<&Value>) -> Option<HashMap<String, String>> {
+    value.and_then(|v| v.as_object()).map(|obj| {
+        obj.iter()
+            .filter_map(|(k, v)| v.as_str().map(|val| (k.clone(), val.to_string())))
+            .collect()
+    })
+}
+
+/// Parse a single MCP entry from the config
+fn parse_mcp_entry(name: &str, config: &Value) -> Result<ParsedWindsurfMcp> {
+    let obj = config
+        .as_object()
+        .ok_or_else(|| anyhow::anyhow!("MCP config entry is not an object"))?;
+
+    // Windsurf names the remote URL `serverUrl`; plain `url` is accepted too
+    let url = obj
+        .get("serverUrl")
+        .or_else(|| obj.get("url"))
+        .and_then(|v| v.as_str())
+        .map(String::from);
+
+    if url.is_some() {
+        return Ok(ParsedWindsurfMcp {
+            name: name.to_string(),
+            mcp_type: "http".to_string(),
+            command: None,
+            args: None,
+            url,
+            headers: string_map(obj.get("headers")),
+            env: None,
+        });
+    }
+
+    let args = obj.get("args").and_then(|v| v.as_array()).map(|arr| {
+        arr.iter()
+            .filter_map(|v| v.as_str().map(String::from))
+            .collect()
+    });
+
+    Ok(ParsedWindsurfMcp {
+        name: name.to_string(),
+        mcp_type: "stdio".to_string(),
+        command: obj
+            .get("command")
+            .and_then(|v| v.as_str())
+            .map(String::from),
+        args,
+        url: None,
+        headers: None,
+        env: string_map(obj.get("env")),
+    })
+}
+
+/// Write MCP servers to Windsurf mcp_config.json, preserving existing content
+pub fn write_windsurf_config(path: &Path, mcps: &[McpTuple]) -> Result<()> {
+    write_mcp_servers(
+        path,
+        "windsurf",
+        "Windsurf config",
+        mcps,
+        windsurf_server_entry,
+    )
+}
+
+/// The content [`write_windsurf_config`] would write; `None` leaves the file as is
+pub fn render_windsurf_config(path: &Path, mcps: &[McpTuple]) -> Result<Option<String>> {
+    render_mcp_servers(
+        path,
+        "windsurf",
+        "Windsurf config",
+        mcps,
+        windsurf_server_entry,
+    )
+}
+
+/// Windsurf entry for one MCP (remote servers use `serverUrl`)
+fn windsurf_server_entry(mcp: &McpTuple) -> Option<Value> {
+    let (_, mcp_type, command, args, url, headers, env) = mcp;
+    let mut server_obj = Map::new();
+
+    match mcp_type.as_str() {
+        "stdio" => {
+            if let Some(cmd) = command {
+                server_obj.insert("command".to_string(), Value::String(cmd.clone()));
+            }
+            if let Some(args) = args_value(args.as_deref()) {
+                server_obj.insert("args".to_string(), args);
+            }
+            if let Some(env) = string_map_value(env.as_deref()) {
+                server_obj.insert("env".to_string(), env);
+            }
+        }
+        "http" | "sse" => {
+            if let Some(u) = url {
+                server_obj.insert("serverUrl".to_string(), Value::String(u.clone()));
+            }
+            if let Some(headers) = string_map_value(headers.as_deref()) {
+                server_obj.insert("headers".to_string(), headers);
+            }
+        }
+        _ => return None,
+    }
+
+    Some(Value::Object(server_obj))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_windsurf_mcps_stdio_and_remote() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("mcp_config.json");
+
+        fs::write(
+            &config_path,
+            r#"{
+                "mcpServers": {
+                    "local": {
+                        "command": "npx",
+                        "args": ["-y", "@test/server"],
+                        "env": { "API_KEY": "secret" }
+                    },
+                    "remote": {
+                        "serverUrl": "https://api.example.com/mcp",
+                        "headers": { "Authorization": "Bearer token123" }
+                    },
+                    "legacy": { "url": "https://legacy.example.com/mcp" }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let mut mcps = parse_windsurf_mcps(&config_path).unwrap();
+        mcps.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(mcps.len(), 3);
+
+        assert_eq!(mcps[0].name, "legacy");
+        assert_eq!(mcps[0].mcp_type, "http");
+        assert_eq!(
+            mcps[0].url.as_deref(),
+            Some("https://legacy.example.com/mcp")
+        );
+
+        assert_eq!(mcps[1].name, "local");
+        assert_eq!(mcps[1].mcp_type, "stdio");
+        assert_eq!(mcps[1].command.as_deref(), Some("npx"));
+        assert_eq!(
+            mcps[1].env.as_ref().unwrap().get("API_KEY"),
+            Some(&"secret".to_string())
+        );
+
+        assert_eq!(mcps[2].name, "remote");
+        assert_eq!(mcps[2].url.as_deref(), Some("https://api.example.com/mcp"));
+        assert_eq!(
+            mcps[2].headers.as_ref().unwrap().get("Authorization"),
+            Some(&"Bearer token123".to_string())
+        );
+    }
+
+    #[test]
+    fn test_write_windsurf_config_round_trip_preserves_other_keys() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("mcp_config.json");
+        fs::write(
+            &config_path,
+            r#"{"mcpServers": {"old": {"command": "old"}}, "otherSetting": true}"#,
+        )
+        .unwrap();
+
+        let mcps: Vec<McpTuple> = vec![
+            (
+                "local".to_string(),
+                "stdio".to_string(),
+                Some("node".to_string()),
+                Some(r#"["server.js"]"#.to_string()),
+                None,
+                None,
+                Some(r#"{"DEBUG":"1"}"#.to_string()),
+            ),
+            (
+                "remote".to_string(),
+                "sse".to_string(),
+                None,
+                None,
+                Some("https://example.com/sse".to_string()),
+                Some(r#"{"X-Key":"abc"}"#.to_string()),
+                None,
+            ),
+        ];
+        write_windsurf_config(&config_path, &mcps).unwrap();
+
+        let written: Value =
+            serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+        assert_eq!(written["otherSetting"], true);
+        assert!(written["mcpServers"].get("old").is_none());
+        assert_eq!(written["mcpServers"]["local"]["args"][0], "server.js");
+        assert_eq!(written["mcpServers"]["local"]["env"]["DEBUG"], "1");
+        assert_eq!(
+            written["mcpServers"]["remote"]["serverUrl"],
+            "https://example.com/sse"
+        );
+        assert_eq!(written["mcpServers"]["remote"]["headers"]["X-Key"], "abc");
+        assert!(config_path.with_extension("json.bak").exists());
+
+        let parsed = parse_windsurf_mcps(&config_path).unwrap();
+        assert_eq!(parsed.len(), 2);
+    }
+
+    #[test]
+    fn test_write_windsurf_config_refuses_invalid_json() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("mcp_config.json");
+        fs::write(&config_path, "{ not json").unwrap();
+
+        let mcps: Vec<McpTuple> = vec![(
+            "local".to_string(),
+            "stdio".to_string(),
+            Some("node".to_string()),
+            None,
+            None,
+            None,
+            None,
+        )];
+        assert!(write_windsurf_config(&config_path, &mcps).is_err());
+        assert_eq!(fs::read_to_string(&config_path).unwrap(), "{ not json");
+    }
+}
@@ -0,0 +1,84 @@
+use crate::utils::process::output_with_timeout;
+use directories::BaseDirs;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// How long `claude --version` may take before it's killed
+const VERSION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An installed Claude Code version
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeCodeVersion {
+    /// `major.minor.patch`
+    pub version: String,
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+/// Pull the first `major.minor.patch` out of `claude --version` output, e.g.
+/// `1.0.35 (Claude Code)`
+pub fn parse_claude_version(output: &str) -> Option<ClaudeCodeVersion> {
+    static VERSION_RE: OnceLock<Regex> = OnceLock::new();
+    let re = VERSION_RE.get_or_init(|| Regex::new(r"(\d+)\.(\d+)\.(\d+)").unwrap());
+    let caps = re.captures(output)?;
+    Some(ClaudeCodeVersion {
+        version: caps[0].to_string(),
+        major: caps[1].parse().ok()?,
+        minor: caps[2].parse().ok()?,
+        patch: caps[3].parse().ok()?,
+    })
+}
+
+/// Binaries to try, in order: whatever `claude` is on PATH, then the
+/// locations the installers use, since a GUI app may not inherit the shell's
+/// PATH
+fn claude_binaries() -> Vec<PathBuf> {
+    let mut binaries = vec![PathBuf::from("claude")];
+    #[cfg(windows)]
+    binaries.push(PathBuf::from("claude.cmd"));
+    if let Some(base_dirs) = BaseDirs::new() {
+        let home = base_dirs.home_dir();
+        binaries.push(home.join(".claude").join("local").join("claude"));
+        binaries.push(home.join(".local").join("bin").join("claude"));
+    }
+    binaries
+}
+
+/// Run `claude --version`, or `None` if Claude Code isn't installed or
+/// doesn't answer in time
+pub fn detect_claude_code_version() -> Option<ClaudeCodeVersion> {
+    claude_binaries().into_iter().find_map(|binary| {
+        let output = output_with_timeout(Command::new(&binary).arg("--version"), VERSION_TIMEOUT)?;
+        if !output.status.success() {
+            return None;
+        }
+        parse_claude_version(&String::from_utf8_lossy(&output.stdout))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_claude_version() {
+        let parsed = parse_claude_version("1.0.35 (Claude Code)\n").unwrap();
+        assert_eq!(parsed.version, "1.0.35");
+        assert_eq!((parsed.major, parsed.minor, parsed.patch), (1, 0, 35));
+
+        assert_eq!(
+            parse_claude_version("claude 2.10.0-beta.1")
+                .unwrap()
+                .version,
+            "2.10.0"
+        );
+        assert_eq!(parse_claude_version("command not found"), None);
+        assert_eq!(parse_claude_version(""), None);
+    }
+}
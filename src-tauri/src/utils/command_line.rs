@@ -0,0 +1,152 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// A pasted command line split into the program and its arguments
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedCommandLine {
+    pub command: String,
+    pub args: Vec<String>,
+}
+
+/// Single-quote `value` as one POSIX shell word, whatever it contains
+pub fn shell_escape(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Split a command line into words the way a POSIX shell would, without
+/// expanding anything. Single quotes are literal; inside double quotes a
+/// backslash escapes `"`, `\`, `$` and `` ` ``. Outside quotes a backslash
+/// only escapes whitespace, quotes and itself, so Windows paths like
+/// `C:\tools\server.exe` survive unquoted.
+pub fn split_command_line(input: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    // Whether a word has started, so `''` still yields an empty argument
+    let mut in_word = false;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => bail!("Unterminated single quote"),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.peek() {
+                            Some(&next @ ('"' | '\\' | '$' | '`')) => {
+                                word.push(next);
+                                chars.next();
+                            }
+                            _ => word.push('\\'),
+                        },
+                        Some(c) => word.push(c),
+                        None => bail!("Unterminated double quote"),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                match chars.peek() {
+                    Some(&next) if next.is_whitespace() || matches!(next, '\'' | '"' | '\\') => {
+                        word.push(next);
+                        chars.next();
+                    }
+                    _ => word.push('\\'),
+                }
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+/// Split a full command line into the command and its args
+pub fn parse_command_line(input: &str) -> Result<ParsedCommandLine> {
+    let mut words = split_command_line(input)?.into_iter();
+    let Some(command) = words.next() else {
+        bail!("Command line is empty");
+    };
+    Ok(ParsedCommandLine {
+        command,
+        args: words.collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(input: &str) -> Vec<String> {
+        split_command_line(input).unwrap()
+    }
+
+    #[test]
+    fn test_parse_command_line_splits_command_and_args() {
+        let parsed = parse_command_line("npx -y @scope/server --flag value").unwrap();
+        assert_eq!(parsed.command, "npx");
+        assert_eq!(parsed.args, vec!["-y", "@scope/server", "--flag", "value"]);
+    }
+
+    #[test]
+    fn test_split_command_line_respects_quotes() {
+        assert_eq!(
+            words(r#"node "/Users/me/My Servers/index.js" --name 'a b'"#),
+            vec!["node", "/Users/me/My Servers/index.js", "--name", "a b"]
+        );
+        assert_eq!(
+            words(r#"echo "say \"hi\"" 'it''s'"#),
+            vec!["echo", r#"say "hi""#, "its"]
+        );
+        assert_eq!(words(r#"run --token="" x"#), vec!["run", "--token=", "x"]);
+        assert_eq!(words("run '' x"), vec!["run", "", "x"]);
+    }
+
+    #[test]
+    fn test_split_command_line_backslashes() {
+        assert_eq!(
+            words(r"my\ server --dir a\\b"),
+            vec!["my server", "--dir", r"a\b"]
+        );
+        assert_eq!(
+            words(r"C:\tools\server.exe --port 3000"),
+            vec![r"C:\tools\server.exe", "--port", "3000"]
+        );
+    }
+
+    #[test]
+    fn test_shell_escape_round_trips() {
+        for value in ["plain", "it's", "$HOME `x` \"q\"", ""] {
+            assert_eq!(words(&shell_escape(value)), vec![value]);
+        }
+    }
+
+    #[test]
+    fn test_parse_command_line_errors() {
+        assert!(parse_command_line("   ").is_err());
+        assert!(parse_command_line("npx 'unterminated").is_err());
+        assert!(parse_command_line("npx \"unterminated").is_err());
+    }
+}
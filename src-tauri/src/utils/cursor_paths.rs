@@ -1,6 +1,7 @@
+use crate::utils::install::{install_signals, macos_app_bundle};
 use anyhow::Result;
 use directories::BaseDirs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Cursor IDE configuration paths
 /// Cursor stores its config in ~/.cursor/ on all platforms
@@ -27,32 +28,20 @@ pub fn get_cursor_paths() -> Result<CursorPathsInternal> {
     })
 }
 
-/// Check if Cursor IDE is installed
-/// Checks for: 1) config directory exists, 2) 'cursor' binary in PATH, or 3) Cursor.app exists
-pub fn is_cursor_installed() -> bool {
-    // Check if config directory exists
-    if let Ok(paths) = get_cursor_paths() {
-        if paths.config_dir.exists() {
-            return true;
-        }
-    }
-    // Check if binary is in PATH
-    if std::process::Command::new("which")
-        .arg("cursor")
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false)
-    {
-        return true;
-    }
-    // Check for macOS app bundle
-    #[cfg(target_os = "macos")]
-    {
-        if std::path::Path::new("/Applications/Cursor.app").exists() {
-            return true;
-        }
-    }
-    false
+/// How Cursor IDE was detected: its 'cursor' binary on PATH and/or its config
+/// directory, or its macOS app. Installed if any signal fires.
+pub fn cursor_install_signals() -> Vec<String> {
+    let config_dir = get_cursor_paths().ok().map(|p| p.config_dir);
+    install_signals(
+        "cursor",
+        config_dir.as_deref(),
+        macos_app_bundle("Cursor.app").as_deref(),
+    )
+}
+
+/// Cursor's project MCP config file (`.cursor/mcp.json`)
+pub fn project_cursor_mcp_config(project_path: &Path) -> PathBuf {
+    project_path.join(".cursor").join("mcp.json")
 }
 
 #[cfg(test)]
@@ -76,14 +65,6 @@ mod tests {
         assert!(paths.mcp_config_file.starts_with(&paths.config_dir));
     }
 
-    #[test]
-    fn test_is_cursor_installed_returns_bool() {
-        // This test just verifies the function runs without error
-        // The actual result depends on whether Cursor is installed
-        let _result = is_cursor_installed();
-        // If we get here without panicking, the test passes
-    }
-
     #[test]
     fn test_cursor_paths_home_not_empty() {
         let paths = get_cursor_paths().expect("Should get cursor paths");
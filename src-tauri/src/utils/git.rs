@@ -0,0 +1,125 @@
+use crate::utils::process::output_with_timeout;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+/// How long any single git invocation may take before it's killed
+const GIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Git context for a directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitInfo {
+    /// Top level of the working tree containing the directory
+    pub root: String,
+    /// Current branch; `None` when HEAD is detached or has no commits yet
+    pub branch: Option<String>,
+    /// URL of the `origin` remote, if configured
+    pub remote: Option<String>,
+}
+
+/// Run `git <args>` in `dir` and return its trimmed stdout, or `None` if git
+/// is missing, fails, prints nothing or runs past the timeout.
+fn run_git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = output_with_timeout(Command::new("git").args(args).current_dir(dir), GIT_TIMEOUT)?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!stdout.is_empty()).then_some(stdout)
+}
+
+/// Git root, branch and origin remote for `path`, or `None` when it isn't
+/// inside a git working tree (or git isn't installed)
+pub fn get_git_info(path: &Path) -> Option<GitInfo> {
+    if !path.is_dir() {
+        return None;
+    }
+    let root = run_git(path, &["rev-parse", "--show-toplevel"])?;
+    let branch =
+        run_git(path, &["rev-parse", "--abbrev-ref", "HEAD"]).filter(|branch| branch != "HEAD");
+    let remote = run_git(path, &["config", "--get", "remote.origin.url"]);
+
+    Some(GitInfo {
+        root,
+        branch,
+        remote,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Stdio;
+    use tempfile::TempDir;
+
+    fn git_available() -> bool {
+        Command::new("git")
+            .arg("--version")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn test_get_git_info_outside_repo() {
+        let temp = TempDir::new().unwrap();
+        // Guard against the temp dir itself living inside a checkout
+        if run_git(temp.path(), &["rev-parse", "--show-toplevel"]).is_some() {
+            return;
+        }
+        assert_eq!(get_git_info(temp.path()), None);
+    }
+
+    #[test]
+    fn test_get_git_info_missing_path() {
+        assert_eq!(get_git_info(Path::new("/definitely/not/a/real/dir")), None);
+    }
+
+    #[test]
+    fn test_get_git_info_in_repo() {
+        if !git_available() {
+            return;
+        }
+        let temp = TempDir::new().unwrap();
+        let repo = temp.path();
+        git(repo, &["init", "-q", "-b", "trunk"]);
+        git(repo, &["config", "user.email", "dev@example.com"]);
+        git(repo, &["config", "user.name", "Dev"]);
+        git(
+            repo,
+            &["remote", "add", "origin", "https://github.com/acme/app.git"],
+        );
+
+        let nested = repo.join("packages").join("web");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        // No commits yet: HEAD doesn't resolve to a branch
+        let info = get_git_info(&nested).unwrap();
+        assert_eq!(
+            std::fs::canonicalize(&info.root).unwrap(),
+            std::fs::canonicalize(repo).unwrap()
+        );
+        assert_eq!(info.branch, None);
+        assert_eq!(
+            info.remote.as_deref(),
+            Some("https://github.com/acme/app.git")
+        );
+
+        git(repo, &["commit", "-q", "--allow-empty", "-m", "init"]);
+        let info = get_git_info(&nested).unwrap();
+        assert_eq!(info.branch.as_deref(), Some("trunk"));
+    }
+}
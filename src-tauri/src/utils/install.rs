@@ -0,0 +1,186 @@
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+/// Install signal: the tool's CLI binary is on `PATH`
+pub const INSTALLED_VIA_BINARY: &str = "binary";
+/// Install signal: the tool's config directory exists
+pub const INSTALLED_VIA_CONFIG_DIR: &str = "config_dir";
+/// Install signal: the tool's macOS app bundle exists
+pub const INSTALLED_VIA_APP_BUNDLE: &str = "app_bundle";
+
+/// Locate `binary` on `PATH` the way a shell would, without spawning `which`
+/// (which doesn't exist on Windows)
+pub fn find_on_path(binary: &str) -> Option<PathBuf> {
+    find_in_path_var(&std::env::var_os("PATH")?, binary)
+}
+
+fn find_in_path_var(path_var: &OsStr, binary: &str) -> Option<PathBuf> {
+    std::env::split_paths(path_var).find_map(|dir| executable_in(&dir, binary))
+}
+
+#[cfg(not(windows))]
+fn executable_in(dir: &Path, binary: &str) -> Option<PathBuf> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let candidate = dir.join(binary);
+    let metadata = std::fs::metadata(&candidate).ok()?;
+    (metadata.is_file() && metadata.permissions().mode() & 0o111 != 0).then_some(candidate)
+}
+
+#[cfg(windows)]
+fn executable_in(dir: &Path, binary: &str) -> Option<PathBuf> {
+    let extensions = std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+    extensions
+        .split(';')
+        .filter(|ext| !ext.is_empty())
+        .map(|ext| dir.join(format!("{}{}", binary, ext)))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Which install signals fire for a tool. Either is enough: a config dir can
+/// outlive an uninstall, and a fresh install has none until first run.
+pub fn install_signals(
+    binary: &str,
+    config_dir: Option<&Path>,
+    app_bundle: Option<&Path>,
+) -> Vec<String> {
+    let mut signals = Vec::new();
+    if find_on_path(binary).is_some() {
+        signals.push(INSTALLED_VIA_BINARY.to_string());
+    }
+    if config_dir.is_some_and(Path::exists) {
+        signals.push(INSTALLED_VIA_CONFIG_DIR.to_string());
+    }
+    if app_bundle.is_some_and(Path::exists) {
+        signals.push(INSTALLED_VIA_APP_BUNDLE.to_string());
+    }
+    signals
+}
+
+/// A macOS app bundle under /Applications; `None` on other platforms
+pub fn macos_app_bundle(name: &str) -> Option<PathBuf> {
+    cfg!(target_os = "macos").then(|| Path::new("/Applications").join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use tempfile::TempDir;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_in_path_var_requires_executable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let first = TempDir::new().unwrap();
+        let second = TempDir::new().unwrap();
+        let plain = first.path().join("mytool");
+        std::fs::write(&plain, "").unwrap();
+        let exe = second.path().join("mytool");
+        std::fs::write(&exe, "#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(&exe, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let path_var = std::env::join_paths([first.path(), second.path()]).unwrap();
+        assert_eq!(find_in_path_var(&path_var, "mytool"), Some(exe));
+        assert_eq!(find_in_path_var(&path_var, "missing"), None);
+    }
+
+    #[test]
+    fn test_install_signals_reports_config_dir() {
+        let dir = TempDir::new().unwrap();
+        let binary = "definitely-not-an-installed-tool";
+
+        assert_eq!(
+            install_signals(binary, Some(dir.path()), None),
+            vec![INSTALLED_VIA_CONFIG_DIR.to_string()]
+        );
+        assert!(install_signals(binary, Some(&dir.path().join("gone")), None).is_empty());
+    }
+
+    /// Point HOME and PATH at temp dirs for the duration of `f`
+    #[cfg(unix)]
+    fn with_temp_home_and_path(f: impl FnOnce(&Path, &Path)) {
+        let home = TempDir::new().unwrap();
+        let bin = TempDir::new().unwrap();
+        let saved: Vec<_> = ["HOME", "PATH", "XDG_CONFIG_HOME"]
+            .into_iter()
+            .map(|key| (key, std::env::var_os(key)))
+            .collect();
+        std::env::set_var("HOME", home.path());
+        std::env::set_var("PATH", bin.path());
+        std::env::remove_var("XDG_CONFIG_HOME");
+
+        let result =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f(home.path(), bin.path())));
+
+        for (key, value) in saved {
+            match value {
+                Some(value) => std::env::set_var(key, value),
+                None => std::env::remove_var(key),
+            }
+        }
+        if let Err(panic) = result {
+            std::panic::resume_unwind(panic);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[serial]
+    fn test_editor_install_signals() {
+        use crate::utils::{
+            codex_paths, copilot_paths, cursor_paths, gemini_paths, opencode_paths, paths,
+            windsurf_paths,
+        };
+        use std::os::unix::fs::PermissionsExt;
+
+        type SignalsFn = fn() -> Vec<String>;
+        let editors: [(&str, &str, SignalsFn); 7] = [
+            ("claude", ".claude", paths::claude_install_signals),
+            ("codex", ".codex", codex_paths::codex_install_signals),
+            (
+                "copilot",
+                ".copilot",
+                copilot_paths::copilot_install_signals,
+            ),
+            ("cursor", ".cursor", cursor_paths::cursor_install_signals),
+            ("gemini", ".gemini", gemini_paths::gemini_install_signals),
+            (
+                "opencode",
+                ".config/opencode",
+                opencode_paths::opencode_install_signals,
+            ),
+            (
+                "windsurf",
+                ".codeium/windsurf",
+                windsurf_paths::windsurf_install_signals,
+            ),
+        ];
+
+        for (binary, config_dir, signals) in editors {
+            with_temp_home_and_path(|home, bin| {
+                // App bundles live under /Applications, outside the temp HOME
+                let signals = || {
+                    let mut found = signals();
+                    found.retain(|s| s != INSTALLED_VIA_APP_BUNDLE);
+                    found
+                };
+                assert!(signals().is_empty(), "{}: nothing installed", binary);
+
+                std::fs::create_dir_all(home.join(config_dir)).unwrap();
+                assert_eq!(signals(), vec![INSTALLED_VIA_CONFIG_DIR], "{}", binary);
+
+                let exe = bin.join(binary);
+                std::fs::write(&exe, "#!/bin/sh\n").unwrap();
+                std::fs::set_permissions(&exe, std::fs::Permissions::from_mode(0o755)).unwrap();
+                assert_eq!(
+                    signals(),
+                    vec![INSTALLED_VIA_BINARY, INSTALLED_VIA_CONFIG_DIR],
+                    "{}",
+                    binary
+                );
+            });
+        }
+    }
+}
@@ -1,7 +1,13 @@
 pub mod backup;
+pub mod claude_version;
 pub mod codex_paths;
+pub mod command_line;
 pub mod copilot_paths;
 pub mod cursor_paths;
 pub mod gemini_paths;
+pub mod git;
+pub mod install;
 pub mod opencode_paths;
 pub mod paths;
+pub mod process;
+pub mod windsurf_paths;
@@ -37,9 +37,180 @@ pub fn get_claude_paths() -> Result<ClaudePathsInternal> {
     })
 }
 
-/// Normalize a path to use forward slashes for consistent comparison
+/// How Claude Code was detected: its 'claude' binary on PATH and/or
+/// ~/.claude. Installed if any signal fires.
+pub fn claude_install_signals() -> Vec<String> {
+    let claude_dir = get_claude_paths().ok().map(|p| p.claude_dir);
+    crate::utils::install::install_signals("claude", claude_dir.as_deref(), None)
+}
+
+/// Normalize a path to use forward slashes for consistent comparison.
+/// WSL UNC paths are folded onto the `//wsl$/` host so the legacy and
+/// `wsl.localhost` spellings of the same directory compare equal.
 pub fn normalize_path(path: &str) -> String {
-    path.replace('\\', "/")
+    let normalized = path.replace('\\', "/");
+    match parse_wsl_unc_path(&normalized) {
+        Some((distro, linux_path)) => format!("//wsl$/{}{}", distro, linux_path),
+        None => normalized,
+    }
+}
+
+/// The environment a path has to be resolved in before touching the filesystem
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathHost {
+    /// Native Windows; Linux-style paths map into the default WSL distro
+    Windows { default_wsl_distro: Option<String> },
+    /// Linux running under WSL; Windows drives are mounted at `/mnt/<drive>`
+    Wsl,
+    /// Any other Unix
+    Unix,
+}
+
+/// Detect the host this process is running on (cached)
+pub fn current_path_host() -> &'static PathHost {
+    static HOST: std::sync::OnceLock<PathHost> = std::sync::OnceLock::new();
+    HOST.get_or_init(|| {
+        if cfg!(windows) {
+            PathHost::Windows {
+                default_wsl_distro: default_wsl_distro(),
+            }
+        } else if std::fs::read_to_string("/proc/sys/kernel/osrelease")
+            .map(|r| r.to_lowercase().contains("microsoft"))
+            .unwrap_or(false)
+        {
+            PathHost::Wsl
+        } else {
+            PathHost::Unix
+        }
+    })
+}
+
+/// Ask `wsl.exe` for the default distro. `wsl --list --quiet` prints the
+/// default first, encoded as UTF-16LE.
+fn default_wsl_distro() -> Option<String> {
+    let mut cmd = std::process::Command::new("wsl.exe");
+    cmd.args(["--list", "--quiet"]);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    let output = cmd.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let units: Vec<u16> = output
+        .stdout
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+        .lines()
+        .map(|l| l.trim_matches(|c: char| c.is_whitespace() || c == '\0'))
+        .find(|l| !l.is_empty())
+        .map(str::to_string)
+}
+
+/// Split a WSL UNC path into `(distro, linux_path)`, accepting either slash style
+pub fn parse_wsl_unc_path(path: &str) -> Option<(String, String)> {
+    let p = path.replace('\\', "/");
+    let rest = p.strip_prefix("//")?;
+    let (host, rest) = rest.split_once('/')?;
+    if !host.eq_ignore_ascii_case("wsl$") && !host.eq_ignore_ascii_case("wsl.localhost") {
+        return None;
+    }
+    let (distro, linux_path) = match rest.split_once('/') {
+        Some((d, p)) => (d, format!("/{}", p.trim_end_matches('/'))),
+        None => (rest, "/".to_string()),
+    };
+    if distro.is_empty() {
+        return None;
+    }
+    Some((distro.to_string(), linux_path))
+}
+
+/// Split `C:\foo` / `c:/foo` into `('c', "/foo")`
+fn parse_drive_path(path: &str) -> Option<(char, String)> {
+    let mut chars = path.chars();
+    let drive = chars.next().filter(|c| c.is_ascii_alphabetic())?;
+    if chars.next() != Some(':') {
+        return None;
+    }
+    let rest = chars.as_str().replace('\\', "/");
+    if !rest.is_empty() && !rest.starts_with('/') {
+        return None;
+    }
+    Some((drive.to_ascii_lowercase(), rest))
+}
+
+/// Split `/mnt/c/foo` into `('c', "/foo")`
+fn parse_wsl_mount_path(path: &str) -> Option<(char, String)> {
+    let rest = path.strip_prefix("/mnt/")?;
+    let mut chars = rest.chars();
+    let drive = chars.next().filter(|c| c.is_ascii_alphabetic())?;
+    let rest = chars.as_str();
+    if !rest.is_empty() && !rest.starts_with('/') {
+        return None;
+    }
+    Some((drive, rest.to_string()))
+}
+
+/// Translate a path recorded by any environment (Windows, WSL UNC, or a
+/// WSL-side Linux path) into one that `host` can open.
+pub fn translate_path_for(path: &str, host: &PathHost) -> String {
+    match host {
+        PathHost::Windows { default_wsl_distro } => {
+            if let Some((distro, linux_path)) = parse_wsl_unc_path(path) {
+                return format!("\\\\wsl$\\{}{}", distro, linux_path.replace('/', "\\"));
+            }
+            if let Some((drive, rest)) = parse_wsl_mount_path(path) {
+                return format!("{}:{}", drive.to_ascii_uppercase(), rest.replace('/', "\\"));
+            }
+            match default_wsl_distro {
+                Some(distro) if path.starts_with('/') && !path.starts_with("//") => {
+                    format!("\\\\wsl$\\{}{}", distro, path.replace('/', "\\"))
+                }
+                _ => path.to_string(),
+            }
+        }
+        PathHost::Wsl => {
+            if let Some((_, linux_path)) = parse_wsl_unc_path(path) {
+                return linux_path;
+            }
+            if let Some((drive, rest)) = parse_drive_path(path) {
+                return format!("/mnt/{}{}", drive, rest);
+            }
+            normalize_path(path)
+        }
+        PathHost::Unix => normalize_path(path),
+    }
+}
+
+/// Translate a path into one this process can open
+pub fn to_host_path(path: &str) -> String {
+    translate_path_for(path, current_path_host())
+}
+
+/// Find the entry in `candidates` that refers to the same directory as
+/// `path`, preferring an exact or slash-normalized match.
+pub fn find_equivalent_path<'a, I>(candidates: I, path: &str) -> Option<&'a String>
+where
+    I: IntoIterator<Item = &'a String>,
+{
+    let candidates: Vec<&String> = candidates.into_iter().collect();
+    let normalized = normalize_path(path);
+    let host = normalize_path(&to_host_path(path));
+    candidates
+        .iter()
+        .find(|c| c.as_str() == path)
+        .or_else(|| candidates.iter().find(|c| c.as_str() == normalized))
+        .or_else(|| {
+            candidates
+                .iter()
+                .find(|c| normalize_path(&to_host_path(c)) == host)
+        })
+        .copied()
 }
 
 #[allow(dead_code)]
@@ -109,6 +280,99 @@ mod tests {
         assert_eq!(normalize_path(""), "");
     }
 
+    #[test]
+    fn test_normalize_path_folds_wsl_hosts() {
+        assert_eq!(
+            normalize_path("\\\\wsl.localhost\\Ubuntu\\home\\me\\project"),
+            "//wsl$/Ubuntu/home/me/project"
+        );
+        assert_eq!(
+            normalize_path("\\\\wsl$\\Ubuntu\\home\\me\\project"),
+            "//wsl$/Ubuntu/home/me/project"
+        );
+    }
+
+    #[test]
+    fn test_parse_wsl_unc_path() {
+        assert_eq!(
+            parse_wsl_unc_path("\\\\wsl$\\Ubuntu\\home\\me"),
+            Some(("Ubuntu".to_string(), "/home/me".to_string()))
+        );
+        assert_eq!(
+            parse_wsl_unc_path("//WSL.LOCALHOST/Debian"),
+            Some(("Debian".to_string(), "/".to_string()))
+        );
+        assert_eq!(parse_wsl_unc_path("\\\\server\\share\\dir"), None);
+        assert_eq!(parse_wsl_unc_path("/home/me"), None);
+        assert_eq!(parse_wsl_unc_path("C:\\Users\\me"), None);
+    }
+
+    #[test]
+    fn test_translate_path_for_windows() {
+        let host = PathHost::Windows {
+            default_wsl_distro: Some("Ubuntu".to_string()),
+        };
+        assert_eq!(
+            translate_path_for("/home/me/project", &host),
+            "\\\\wsl$\\Ubuntu\\home\\me\\project"
+        );
+        assert_eq!(
+            translate_path_for("//wsl.localhost/Ubuntu/home/me", &host),
+            "\\\\wsl$\\Ubuntu\\home\\me"
+        );
+        assert_eq!(
+            translate_path_for("/mnt/c/Users/me", &host),
+            "C:\\Users\\me"
+        );
+        assert_eq!(translate_path_for("C:\\Users\\me", &host), "C:\\Users\\me");
+
+        let no_wsl = PathHost::Windows {
+            default_wsl_distro: None,
+        };
+        assert_eq!(
+            translate_path_for("/home/me/project", &no_wsl),
+            "/home/me/project"
+        );
+    }
+
+    #[test]
+    fn test_translate_path_for_wsl() {
+        assert_eq!(
+            translate_path_for("C:\\Users\\me\\project", &PathHost::Wsl),
+            "/mnt/c/Users/me/project"
+        );
+        assert_eq!(
+            translate_path_for("\\\\wsl$\\Ubuntu\\home\\me", &PathHost::Wsl),
+            "/home/me"
+        );
+        assert_eq!(translate_path_for("/home/me", &PathHost::Wsl), "/home/me");
+    }
+
+    #[test]
+    fn test_translate_path_for_unix_only_normalizes() {
+        assert_eq!(
+            translate_path_for("/home/me/project", &PathHost::Unix),
+            "/home/me/project"
+        );
+    }
+
+    #[test]
+    fn test_find_equivalent_path() {
+        let keys = vec![
+            "C:/Users/me/app".to_string(),
+            "//wsl$/Ubuntu/home/me/project".to_string(),
+        ];
+        assert_eq!(
+            find_equivalent_path(&keys, "C:\\Users\\me\\app"),
+            Some(&keys[0])
+        );
+        assert_eq!(
+            find_equivalent_path(&keys, "\\\\wsl.localhost\\Ubuntu\\home\\me\\project"),
+            Some(&keys[1])
+        );
+        assert_eq!(find_equivalent_path(&keys, "/somewhere/else"), None);
+    }
+
     #[test]
     fn test_project_mcp_file() {
         let project_path = PathBuf::from("/home/user/myproject");
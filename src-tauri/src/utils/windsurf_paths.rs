@@ -0,0 +1,67 @@
+use crate::utils::install::{install_signals, macos_app_bundle};
+use anyhow::Result;
+use directories::BaseDirs;
+use std::path::PathBuf;
+
+/// Windsurf (Codeium) configuration paths
+/// Windsurf stores its config in ~/.codeium/windsurf/ on all platforms
+pub struct WindsurfPathsInternal {
+    #[allow(dead_code)]
+    pub home: PathBuf,
+    pub config_dir: PathBuf,           // ~/.codeium/windsurf/
+    pub mcp_config_file: PathBuf,      // ~/.codeium/windsurf/mcp_config.json
+    pub global_rules_file: PathBuf,    // ~/.codeium/windsurf/memories/global_rules.md
+    pub global_workflows_dir: PathBuf, // ~/.codeium/windsurf/global_workflows/
+}
+
+pub fn get_windsurf_paths() -> Result<WindsurfPathsInternal> {
+    let base_dirs =
+        BaseDirs::new().ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
+
+    let home = base_dirs.home_dir().to_path_buf();
+
+    // Windsurf keeps its config under Codeium's directory
+    let config_dir = home.join(".codeium").join("windsurf");
+
+    Ok(WindsurfPathsInternal {
+        mcp_config_file: config_dir.join("mcp_config.json"),
+        global_rules_file: config_dir.join("memories").join("global_rules.md"),
+        global_workflows_dir: config_dir.join("global_workflows"),
+        home,
+        config_dir,
+    })
+}
+
+/// How Windsurf was detected: its 'windsurf' binary on PATH and/or its config
+/// directory, or its macOS app. Installed if any signal fires.
+pub fn windsurf_install_signals() -> Vec<String> {
+    let config_dir = get_windsurf_paths().ok().map(|p| p.config_dir);
+    install_signals(
+        "windsurf",
+        config_dir.as_deref(),
+        macos_app_bundle("Windsurf.app").as_deref(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_windsurf_paths_returns_valid_structure() {
+        let paths = get_windsurf_paths().expect("Should get windsurf paths");
+
+        assert!(paths.config_dir.ends_with(".codeium/windsurf"));
+        assert!(paths.mcp_config_file.ends_with("mcp_config.json"));
+        assert!(paths.config_dir.starts_with(&paths.home));
+    }
+
+    #[test]
+    fn test_windsurf_files_under_config_dir() {
+        let paths = get_windsurf_paths().expect("Should get windsurf paths");
+
+        assert!(paths.mcp_config_file.starts_with(&paths.config_dir));
+        assert!(paths.global_rules_file.starts_with(&paths.config_dir));
+        assert!(paths.global_workflows_dir.starts_with(&paths.config_dir));
+    }
+}
@@ -40,7 +40,7 @@ class ClaudeSettingsLibraryState {
 	async save(settings: ClaudeSettings) {
 		console.log(`[claudeSettingsLibrary] Saving settings for scope=${this.selectedScope}`);
 		try {
-			await invoke<ClaudeSettings>('save_claude_settings', {
+			await invoke('save_claude_settings', {
 				scope: this.selectedScope,
 				projectPath: this.projectPath,
 				settings
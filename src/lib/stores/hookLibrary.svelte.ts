@@ -5,7 +5,8 @@ import type {
 	GlobalHook,
 	ProjectHook,
 	HookEventType,
-	Project
+	Project,
+	WithPendingChanges
 } from '$lib/types';
 
 export type HookViewMode = 'all' | 'byScope';
@@ -174,7 +175,7 @@ class HookLibraryState {
 
 	async update(id: number, request: CreateHookRequest): Promise<Hook> {
 		console.log(`[hookLibrary] Updating hook id=${id}: ${request.name}`);
-		const hook = await invoke<Hook>('update_hook', { id, hook: request });
+		const { item: hook } = await invoke<WithPendingChanges<Hook>>('update_hook', { id, hook: request });
 		this.hooks = this.hooks.map((h) => (h.id === id ? hook : h));
 		console.log(`[hookLibrary] Updated hook id=${id}`);
 		return hook;
@@ -258,7 +259,7 @@ class HookLibraryState {
 		method: 'shell' | 'python'
 	): Promise<Hook[]> {
 		console.log(`[hookLibrary] Creating sound hooks for events: ${events.join(', ')}`);
-		const hooks = await invoke<Hook[]>('create_sound_notification_hooks', {
+		const { item: hooks } = await invoke<WithPendingChanges<Hook[]>>('create_sound_notification_hooks', {
 			events,
 			soundPath,
 			method
@@ -1,5 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { Project, CreateProjectRequest, GlobalMcp } from '$lib/types';
+import type { Project, CreateProjectRequest, GlobalMcp, WithPendingChanges } from '$lib/types';
 
 class ProjectsState {
 	projects = $state<Project[]>([]);
@@ -34,7 +34,7 @@ class ProjectsState {
 
 	async addProject(request: CreateProjectRequest): Promise<Project> {
 		console.log(`[projectsStore] Adding project: ${request.name} at ${request.path}`);
-		const project = await invoke<Project>('add_project', { project: request });
+		const { item: project } = await invoke<WithPendingChanges<Project>>('add_project', { project: request });
 		this.projects = [...this.projects, project];
 		console.log(`[projectsStore] Added project id=${project.id}`);
 		return project;
@@ -1,5 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { Skill, CreateSkillRequest, GlobalSkill, ProjectSkill, SkillFile, CreateSkillFileRequest } from '$lib/types';
+import type { SavedContent, Skill, CreateSkillRequest, GlobalSkill, ProjectSkill, SkillFile, CreateSkillFileRequest } from '$lib/types';
 
 class SkillLibraryState {
 	skills = $state<Skill[]>([]);
@@ -52,13 +52,15 @@ class SkillLibraryState {
 	}
 
 	async create(request: CreateSkillRequest): Promise<Skill> {
-		const skill = await invoke<Skill>('create_skill', { skill: request });
+		const { item: skill, sizeWarning } = await invoke<SavedContent<Skill>>('create_skill', { skill: request });
+		if (sizeWarning) console.warn(sizeWarning);
 		this.skills = [...this.skills, skill];
 		return skill;
 	}
 
 	async update(id: number, request: CreateSkillRequest): Promise<Skill> {
-		const skill = await invoke<Skill>('update_skill', { id, skill: request });
+		const { item: skill, sizeWarning } = await invoke<SavedContent<Skill>>('update_skill', { id, skill: request });
+		if (sizeWarning) console.warn(sizeWarning);
 		this.skills = this.skills.map((s) => (s.id === id ? skill : s));
 		return skill;
 	}
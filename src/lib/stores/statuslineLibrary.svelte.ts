@@ -4,7 +4,8 @@ import type {
 	CreateStatusLineRequest,
 	StatusLineGalleryEntry,
 	StatusLineSegment,
-	StatusLineTheme
+	StatusLineTheme,
+	WithPendingChanges
 } from '$lib/types';
 
 class StatusLineLibraryState {
@@ -75,7 +76,7 @@ class StatusLineLibraryState {
 	}
 
 	async activate(id: number): Promise<void> {
-		const sl = await invoke<StatusLine>('activate_statusline', { id });
+		const { item: sl } = await invoke<WithPendingChanges<StatusLine>>('activate_statusline', { id });
 		this.statuslines = this.statuslines.map((s) => ({
 			...s,
 			isActive: s.id === id
@@ -1,5 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { SubAgent, CreateSubAgentRequest, GlobalSubAgent, ProjectSubAgent } from '$lib/types';
+import type { SavedContent, SubAgent, CreateSubAgentRequest, GlobalSubAgent, ProjectSubAgent } from '$lib/types';
 
 class SubAgentLibraryState {
 	subagents = $state<SubAgent[]>([]);
@@ -52,13 +52,15 @@ class SubAgentLibraryState {
 	}
 
 	async create(request: CreateSubAgentRequest): Promise<SubAgent> {
-		const subagent = await invoke<SubAgent>('create_subagent', { subagent: request });
+		const { item: subagent, sizeWarning } = await invoke<SavedContent<SubAgent>>('create_subagent', { subagent: request });
+		if (sizeWarning) console.warn(sizeWarning);
 		this.subagents = [...this.subagents, subagent];
 		return subagent;
 	}
 
 	async update(id: number, request: CreateSubAgentRequest): Promise<SubAgent> {
-		const subagent = await invoke<SubAgent>('update_subagent', { id, subagent: request });
+		const { item: subagent, sizeWarning } = await invoke<SavedContent<SubAgent>>('update_subagent', { id, subagent: request });
+		if (sizeWarning) console.warn(sizeWarning);
 		this.subagents = this.subagents.map((a) => (a.id === id ? subagent : a));
 		return subagent;
 	}
@@ -0,0 +1,14 @@
+// A config write queued for confirmation while "confirm before writing" is on
+export interface PendingChange {
+	token: string;
+	path: string;
+	before: string | null;
+	after: string;
+	diff: string;
+}
+
+// Returned by commands that both produce a result and write config files
+export interface WithPendingChanges<T> {
+	item: T;
+	pendingChanges: PendingChange[];
+}
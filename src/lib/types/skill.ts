@@ -19,6 +19,13 @@ export interface Skill {
 	updatedAt: string;
 }
 
+// Returned by create/update for skills and sub-agents; sizeWarning is set when the
+// content is over the soft size limit.
+export interface SavedContent<T> {
+	item: T;
+	sizeWarning?: string;
+}
+
 export interface CreateSkillRequest {
 	name: string;
 	description?: string;
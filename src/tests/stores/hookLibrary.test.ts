@@ -281,7 +281,7 @@ describe('Hook Library Store', () => {
 
 			vi.mocked(invoke)
 				.mockResolvedValueOnce(mockHooks)
-				.mockResolvedValueOnce(updatedHook);
+				.mockResolvedValueOnce({ item: updatedHook, pendingChanges: [] });
 
 			const { hookLibrary } = await import('$lib/stores/hookLibrary.svelte');
 			await hookLibrary.load();
@@ -543,7 +543,7 @@ describe('Hook Library Store', () => {
 
 			vi.mocked(invoke)
 				.mockResolvedValueOnce([]) // initial load
-				.mockResolvedValueOnce(mockHooks) // create_sound_notification_hooks
+				.mockResolvedValueOnce({ item: mockHooks, pendingChanges: [] }) // create_sound_notification_hooks
 				.mockResolvedValueOnce([]); // loadGlobalHooks
 
 			const { hookLibrary } = await import('$lib/stores/hookLibrary.svelte');
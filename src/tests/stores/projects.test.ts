@@ -131,7 +131,7 @@ describe('Projects Store', () => {
 				id: 5, name: 'new-project', path: '/code/new-project',
 				assignedMcps: [], isFavorite: false
 			};
-			vi.mocked(invoke).mockResolvedValueOnce(newProject);
+			vi.mocked(invoke).mockResolvedValueOnce({ item: newProject, pendingChanges: [] });
 
 			const { projectsStore } = await import('$lib/stores/projects.svelte');
 			const result = await projectsStore.addProject({
@@ -197,7 +197,7 @@ describe('Skill Library Store', () => {
 
 			vi.mocked(invoke)
 				.mockResolvedValueOnce([]) // initial load
-				.mockResolvedValueOnce(newSkill); // create
+				.mockResolvedValueOnce({ item: newSkill }); // create
 
 			const { skillLibrary } = await import('$lib/stores/skillLibrary.svelte');
 			await skillLibrary.load();
@@ -220,7 +220,7 @@ describe('Skill Library Store', () => {
 
 			vi.mocked(invoke)
 				.mockResolvedValueOnce(mockSkills)
-				.mockResolvedValueOnce(updatedSkill);
+				.mockResolvedValueOnce({ item: updatedSkill });
 
 			const { skillLibrary } = await import('$lib/stores/skillLibrary.svelte');
 			await skillLibrary.load();
@@ -244,7 +244,7 @@ describe('StatusLine Library Store', () => {
 			vi.mocked(invoke)
 				.mockResolvedValueOnce(sls)
 				.mockResolvedValueOnce(null)
-				.mockResolvedValueOnce(activated);
+				.mockResolvedValueOnce({ item: activated, pendingChanges: [] });
 
 			const { statuslineLibrary } = await import('$lib/stores/statuslineLibrary.svelte');
 			await statuslineLibrary.load();
@@ -197,7 +197,7 @@ describe('SubAgent Library Store', () => {
 
 			vi.mocked(invoke)
 				.mockResolvedValueOnce([]) // initial load
-				.mockResolvedValueOnce(newSubAgent); // create
+				.mockResolvedValueOnce({ item: newSubAgent }); // create
 
 			const { subagentLibrary } = await import('$lib/stores/subagentLibrary.svelte');
 			await subagentLibrary.load();
@@ -219,7 +219,7 @@ describe('SubAgent Library Store', () => {
 
 			vi.mocked(invoke)
 				.mockResolvedValueOnce(mockSubAgents)
-				.mockResolvedValueOnce(updatedSubAgent);
+				.mockResolvedValueOnce({ item: updatedSubAgent });
 
 			const { subagentLibrary } = await import('$lib/stores/subagentLibrary.svelte');
 			await subagentLibrary.load();